@@ -1,54 +1,262 @@
-#[cfg(test)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[cfg(all(test, feature = "std"))]
 #[macro_use]
 extern crate approx;
 
+#[cfg(feature = "std")]
+mod nav_annotation;
+#[cfg(feature = "std")]
+mod nav_baker;
+#[cfg(feature = "bevy")]
+mod nav_bevy;
+#[cfg(feature = "std")]
+mod nav_binary;
+#[cfg(feature = "std")]
+mod nav_cover;
+#[cfg(feature = "std")]
+mod nav_crowd;
+#[cfg(feature = "std")]
+mod nav_debug;
+#[cfg(feature = "std")]
+mod nav_detour;
+#[cfg(feature = "std")]
+mod nav_distance_field;
+#[cfg(feature = "std")]
+mod nav_dstar;
+mod nav_funnel;
+#[cfg(feature = "std")]
+mod nav_geom;
+#[cfg(feature = "std")]
+mod nav_gpu;
+mod nav_graph_search;
+#[cfg(feature = "std")]
 mod nav_grid;
+#[cfg(feature = "std")]
+mod nav_grid_any_angle;
+#[cfg(feature = "std")]
+mod nav_grid_convert;
+#[cfg(feature = "std")]
+mod nav_heightmap;
+#[cfg(feature = "std")]
+mod nav_hierarchy;
+#[cfg(feature = "import")]
+mod nav_import;
+#[cfg(feature = "std")]
+mod nav_influence;
+#[cfg(feature = "std")]
 mod nav_islands;
+#[cfg(feature = "std")]
 mod nav_mesh;
+#[cfg(feature = "std")]
+mod nav_mesh_2d;
+#[cfg(feature = "std")]
+mod nav_mesh_batch;
+#[cfg(feature = "std")]
+mod nav_mesh_builder;
+#[cfg(feature = "std")]
+mod nav_mesh_checked;
+#[cfg(feature = "std")]
+mod nav_mesh_csg;
+#[cfg(feature = "std")]
+mod nav_mesh_diff;
+#[cfg(feature = "std")]
+mod nav_mesh_handle;
+#[cfg(feature = "std")]
+mod nav_mesh_islands;
+#[cfg(feature = "std")]
+mod nav_mesh_query;
+#[cfg(feature = "std")]
+mod nav_mesh_search;
+#[cfg(feature = "std")]
+mod nav_mesh_simplify;
+#[cfg(feature = "std")]
+mod nav_mesh_surface;
+#[cfg(feature = "std")]
+mod nav_mesh_validation;
+#[cfg(feature = "std")]
 mod nav_net;
+#[cfg(feature = "import")]
+mod nav_net_import;
+#[cfg(feature = "std")]
+mod nav_obstacle;
+mod nav_open_node;
+#[cfg(feature = "std")]
+mod nav_path_cache;
+#[cfg(feature = "std")]
+mod nav_path_corridor;
+#[cfg(feature = "std")]
+mod nav_path_follower;
+#[cfg(feature = "std")]
+mod nav_path_watch;
+#[cfg(feature = "std")]
+mod nav_patrol;
+#[cfg(feature = "std")]
+mod nav_pick;
+#[cfg(feature = "std")]
+mod nav_poly_mesh;
+#[cfg(feature = "std")]
+mod nav_profile;
+#[cfg(feature = "std")]
+mod nav_quat;
+#[cfg(feature = "std")]
+mod nav_query_pool;
+#[cfg(feature = "std")]
+mod nav_recorder;
+#[cfg(feature = "std")]
+mod nav_runtime_state;
+#[cfg(all(feature = "simd", target_arch = "x86_64", not(feature = "scalar64")))]
+mod nav_simd;
+#[cfg(feature = "std")]
+mod nav_sparse_grid;
+#[cfg(feature = "std")]
+mod nav_stats;
+#[cfg(feature = "std")]
+mod nav_tiled_mesh;
+#[cfg(feature = "std")]
+mod nav_transform;
+#[cfg(feature = "std")]
+mod nav_triangulator;
 mod nav_vec3;
+#[cfg(feature = "std")]
+mod nav_vehicle;
+#[cfg(feature = "std")]
+mod nav_vertex_attribute;
+#[cfg(feature = "std")]
+mod nav_visibility;
+#[cfg(feature = "std")]
+mod nav_world;
+
+#[cfg(feature = "std")]
+pub use crate::{
+    nav_annotation::*, nav_baker::*, nav_cover::*, nav_crowd::*, nav_debug::*, nav_detour::*,
+    nav_distance_field::*, nav_dstar::*, nav_geom::*, nav_gpu::*, nav_grid::*, nav_grid_convert::*,
+    nav_hierarchy::*, nav_influence::*, nav_islands::*, nav_mesh::*, nav_mesh_2d::*,
+    nav_mesh_batch::*, nav_mesh_builder::*, nav_mesh_diff::*, nav_mesh_handle::*,
+    nav_mesh_islands::*, nav_mesh_surface::*, nav_mesh_validation::*, nav_net::*, nav_obstacle::*,
+    nav_path_corridor::*, nav_path_follower::*, nav_path_watch::*, nav_patrol::*, nav_pick::*,
+    nav_poly_mesh::*, nav_profile::*, nav_quat::*, nav_query_pool::*, nav_recorder::*,
+    nav_runtime_state::*, nav_sparse_grid::*, nav_stats::*, nav_tiled_mesh::*, nav_transform::*,
+    nav_triangulator::*, nav_vehicle::*, nav_vertex_attribute::*, nav_visibility::*, nav_world::*,
+};
+pub use crate::{nav_funnel::*, nav_graph_search::*, nav_vec3::*};
 
-pub use crate::{nav_grid::*, nav_islands::*, nav_mesh::*, nav_net::*, nav_vec3::*};
+#[cfg(feature = "bevy")]
+pub use crate::nav_bevy::*;
+#[cfg(feature = "import")]
+pub use crate::{nav_import::*, nav_net_import::*};
 
-use serde::{Deserialize, Serialize};
-use std::{
+use alloc::{string::String, vec::Vec};
+use core::{
     hash::{Hash, Hasher},
     result::Result as StdResult,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "scalar64")]
 pub type Scalar = f64;
 #[cfg(not(feature = "scalar64"))]
 pub type Scalar = f32;
 
-/// Error data.
-#[derive(Debug, Clone)]
+/// Error data, with messages describing what input caused it, so tools can show actionable
+/// diagnostics.
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
     /// Trying to construct triangle with vertice index out of vertices list.
     /// (triangle index, local vertice index, global vertice index)
+    #[error("Triangle {0} vertice {1} uses vertice index {2} out of vertices list")]
     TriangleVerticeIndexOutOfBounds(u32, u8, u32),
     /// Trying to construct connection with vertice index out of vertices list.
     /// (connection index, local vertice index, global vertice index)
+    #[error("Connection {0} vertice {1} uses vertice index {2} out of vertices list")]
     ConnectionVerticeIndexOutOfBounds(u32, u8, u32),
     /// Could not serialize NavMesh. Contains serialization error string.
+    #[error("Could not serialize nav mesh: {0}")]
     CouldNotSerializeNavMesh(String),
     /// Could not deserialize NavMesh. Contains deserialization error string.
+    #[error("Could not deserialize nav mesh: {0}")]
     CouldNotDeserializeNavMesh(String),
     /// Trying to use cells container with size not matching cols and rows count.
     /// (cells count, cols count, rows count)
+    #[error("Cells count {0} does not match {1} cols and {2} rows")]
     CellsCountDoesNotMatchColsRows(usize, usize, usize),
     /// Either cols or rows count is zero.
     /// (cols count, rows count)
+    #[error("Cells container has no cells: {0} cols and {1} rows")]
     EmptyCells(usize, usize),
     /// Trying to use cell coordinate out of bounds.
     /// (col, row, cols count, rows count)
+    #[error("Cell coordinate ({0}, {1}) is out of {2} cols and {3} rows")]
     InvalidCellCoordinate(usize, usize, usize, usize),
+    /// Baking level geometry, combining or repairing nav meshes did not produce any walkable
+    /// surface.
+    #[error("No walkable surface was produced")]
+    NoWalkableSurface,
+    /// Could not import geometry from file. Contains import error string.
+    #[error("Could not import geometry: {0}")]
+    CouldNotImportGeometry(String),
+    /// Nav mesh diff does not match nav mesh it is applied to.
+    /// (expected fingerprint, actual fingerprint)
+    #[error("Nav mesh diff was made for nav mesh {0:016x}, but is applied to {1:016x}")]
+    NavMeshDiffMismatch(u64, u64),
+    /// Trying to construct polygon with vertice index out of vertices list.
+    /// (polygon index, local vertice index, global vertice index)
+    #[error("Polygon {0} vertice {1} uses vertice index {2} out of vertices list")]
+    PolygonVerticeIndexOutOfBounds(u32, u32, u32),
+    /// Triangulation backend could not split polygon into triangles.
+    /// (polygon index, polygon vertices indices)
+    #[error("Could not triangulate polygon {0} with vertices {1:?}")]
+    PolygonTriangulationFailed(u32, Vec<u32>),
+    /// Trying to use triangle index out of triangles list.
+    /// (triangle index, triangles count)
+    #[error("Triangle index {0} is out of {1} triangles")]
+    TriangleIndexOutOfBounds(usize, usize),
+    /// Could not import nav net from file. Contains import error string.
+    #[error("Could not import nav net: {0}")]
+    CouldNotImportNavNet(String),
+    /// Vertex attribute values count does not match nav mesh vertices count.
+    /// (values count, vertices count)
+    #[error("Vertex attribute has {0} values, but nav mesh has {1} vertices")]
+    VertexAttributeCountDoesNotMatchVertices(usize, usize),
+    /// Runtime state was captured from nav mesh with different triangles count.
+    /// (state triangles count, nav mesh triangles count)
+    #[error("Runtime state was captured from nav mesh with {0} triangles, but nav mesh has {1}")]
+    RuntimeStateDoesNotMatchNavMesh(usize, usize),
+    /// Trying to construct nav mesh without vertices or triangles.
+    /// (vertices count, triangles count)
+    #[error("Nav mesh needs vertices and triangles, got {0} vertices and {1} triangles")]
+    EmptyGeometry(usize, usize),
+    /// Path start point does not lie on walkable part of nav mesh.
+    /// (start point)
+    #[error("Path start point {0:?} is not on walkable part of nav mesh")]
+    StartNotWalkable(NavVec3),
+    /// Path goal can not be reached from start point (it is on disconnected island or blocked).
+    /// (nearest reachable point)
+    #[error("Path goal can not be reached, nearest reachable point is {0:?}")]
+    GoalUnreachable(NavVec3),
+    /// Path start and goal lie on different islands (disconnected parts) of nav mesh, so path
+    /// was rejected without search.
+    #[error("Path goal lies on island {to_island}, not connected with start island {from_island}")]
+    Unreachable {
+        from_island: usize,
+        to_island: usize,
+    },
 }
 
+/// Error data of nav mesh construction and queries.
+///
+/// Path queries (`NavMesh::find_path` and its variants) keep returning `Option`, each of them
+/// has `_checked` counterpart (`NavMesh::find_path_checked` for `NavMesh::find_path`) returning
+/// `NavResult` that reports why path could not be found.
+pub type NavError = Error;
+
 /// Result data.
 pub type NavResult<T> = StdResult<T, Error>;
 
-#[derive(Debug, Default, Copy, Clone, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavConnection(pub u32, pub u32);
 
 impl Hash for NavConnection {
@@ -71,8 +279,14 @@ impl PartialEq for NavConnection {
 }
 
 pub(crate) const ZERO_TRESHOLD: Scalar = 1e-6;
+pub(crate) const SCALAR_MAX: Scalar = Scalar::MAX;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
+#[allow(
+    clippy::bool_assert_comparison,
+    clippy::excessive_precision,
+    clippy::useless_vec
+)]
 mod tests {
     use super::*;
 
@@ -89,6 +303,7 @@ mod tests {
         foo::<NavNet>();
         foo::<NavGrid>();
         foo::<NavFreeGrid>();
+        foo::<NavSparseGrid>();
         foo::<NavIslands<(), ()>>();
     }
 
@@ -661,167 +876,6472 @@ mod tests {
     }
 
     #[test]
-    fn test_thicken() {
-        let source = NavMesh::new(
-            vec![
-                [-10.0, -10.0, 0.0].into(),
-                [10.0, -10.0, 0.0].into(),
-                [10.0, 10.0, 0.0].into(),
-                [-10.0, 10.0, 0.0].into(),
-            ],
-            vec![[0, 1, 2].into(), [2, 3, 0].into()],
-        )
-        .unwrap();
-        let thickened = source.thicken(1.0).unwrap();
-        for (a, b) in source.vertices().iter().zip(thickened.vertices().iter()) {
-            assert_relative_eq!(*b, *a + NavVec3::new(0.0, 0.0, 1.0));
+    fn test_obstacles() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+            (0.0, 2.0, 0.0).into(), // 6
+            (1.0, 2.0, 0.0).into(), // 7
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+            (3, 4, 7).into(), // 4
+            (7, 6, 3).into(), // 5
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let crate_box = mesh.add_obstacle(NavObstacle::aabb(
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 1.0, 1.0).into(),
+        ));
+        let mut blocked = mesh.obstacle_triangles(crate_box).unwrap().to_vec();
+        blocked.sort();
+        assert_eq!(blocked, vec![2, 3]);
+        assert!(mesh.find_path_triangles(0, 2).is_none());
+        assert_eq!(mesh.find_path_triangles(0, 5).unwrap().0, vec![0, 1, 4, 5]);
+
+        let pillar = mesh.add_obstacle(NavObstacle::cylinder(
+            (0.25, 1.5, 0.0).into(),
+            (0.0, 0.0, 2.0).into(),
+            0.1,
+        ));
+        assert!(mesh.is_triangle_blocked(5));
+        assert!(!mesh.is_triangle_blocked(4));
+        assert!(mesh.find_path_triangles(0, 5).is_none());
+        // cylinder axis shorter than distance tolerance is degenerate and blocks nothing.
+        let flat = NavObstacle::cylinder((0.5, 0.5, 0.0).into(), (0.0, 0.0, 0.05).into(), 5.0);
+        let (a, b, c) = (
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+        );
+        assert!(flat.intersects_triangle_within(a, b, c, NavTolerance::default()));
+        assert!(!flat.intersects_triangle_within(a, b, c, NavTolerance::scaled(100.0)));
+
+        assert!(mesh.remove_obstacle(crate_box).is_some());
+        assert!(mesh.remove_obstacle(crate_box).is_none());
+        assert_eq!(mesh.find_path_triangles(2, 0).unwrap().0, vec![2, 3, 0]);
+        mesh.remove_obstacle(pillar);
+        assert_eq!(
+            mesh.find_path_triangles(2, 5).unwrap().0,
+            vec![2, 3, 0, 1, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_tiled_mesh() {
+        fn tile(col: i32, row: i32, flipped: bool) -> (Vec<NavVec3>, Vec<NavTriangle>) {
+            let x = col as Scalar;
+            let y = row as Scalar;
+            let vertices = vec![
+                (x, y, 0.0).into(),
+                (x + 1.0, y, 0.0).into(),
+                (x + 1.0, y + 1.0, 0.0).into(),
+                (x, y + 1.0, 0.0).into(),
+            ];
+            let triangles = if flipped {
+                vec![(0, 1, 3).into(), (1, 2, 3).into()]
+            } else {
+                vec![(0, 1, 2).into(), (2, 3, 0).into()]
+            };
+            (vertices, triangles)
         }
 
-        let source = NavMesh::new(
-            vec![
-                [-5.0, -5.0, -5.0].into(), // 0
-                [5.0, -5.0, -5.0].into(),  // 1
-                [5.0, 5.0, -5.0].into(),   // 2
-                [-5.0, 5.0, -5.0].into(),  // 3
-                [-5.0, -5.0, 5.0].into(),  // 4
-                [5.0, -5.0, 5.0].into(),   // 5
-                [5.0, 5.0, 5.0].into(),    // 6
-                [-5.0, 5.0, 5.0].into(),   // 7
-            ],
-            vec![
-                [2, 1, 0].into(),
-                [0, 3, 2].into(),
-                [4, 5, 6].into(),
-                [6, 7, 4].into(),
-                [0, 1, 5].into(),
-                [5, 4, 0].into(),
-                [1, 2, 6].into(),
-                [6, 5, 1].into(),
-                [2, 3, 7].into(),
-                [7, 6, 2].into(),
-                [3, 0, 4].into(),
-                [4, 7, 3].into(),
-            ],
-        )
-        .unwrap();
-        let thickened = source.thicken(1.0).unwrap();
-        let expected = vec![
-            NavVec3 {
-                x: -5.333333333333333,
-                y: -5.666666666666667,
-                z: -5.666666666666667,
-            },
-            NavVec3 {
-                x: 5.816496580927726,
-                y: -5.408248290463863,
-                z: -5.408248290463863,
-            },
-            NavVec3 {
-                x: 5.333333333333333,
-                y: 5.666666666666667,
-                z: -5.666666666666667,
-            },
-            NavVec3 {
-                x: -5.816496580927726,
-                y: 5.408248290463863,
-                z: -5.408248290463863,
-            },
-            NavVec3 {
-                x: -5.666666666666667,
-                y: -5.333333333333333,
-                z: 5.666666666666667,
-            },
-            NavVec3 {
-                x: 5.408248290463863,
-                y: -5.816496580927726,
-                z: 5.408248290463863,
-            },
-            NavVec3 {
-                x: 5.666666666666667,
-                y: 5.333333333333333,
-                z: 5.666666666666667,
-            },
-            NavVec3 {
-                x: -5.408248290463863,
-                y: 5.816496580927726,
-                z: 5.408248290463863,
-            },
+        let mut mesh = NavTiledMesh::new(1.0);
+        for (col, row) in [(0, 0), (1, 0), (1, 1)] {
+            let (vertices, triangles) = tile(col, row, false);
+            mesh.build_tile((col, row), vertices, triangles).unwrap();
+        }
+        assert_eq!(mesh.tile_coord((1.5, 0.5, 0.0).into()), (1, 0));
+        let path = mesh
+            .find_path(
+                (0.5, 0.5, 0.0).into(),
+                (1.5, 1.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert_relative_eq!(
+            NavMesh::path_length(&path),
+            (2.0 as Scalar).sqrt(),
+            epsilon = 1.0e-4
+        );
+        assert_relative_eq!(*path.last().unwrap(), NavVec3::new(1.5, 1.5, 0.0));
+
+        let path = mesh
+            .find_path(
+                (0.5, 0.9, 0.0).into(),
+                (1.1, 1.9, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(path.len(), 3);
+        assert_relative_eq!(path[1], NavVec3::new(1.0, 1.0, 0.0));
+
+        // path inside single tile costs the same as on its nav mesh.
+        let (_, cost) = mesh
+            .find_path_triangles(
+                NavTileTriangle::new((0, 0), 0),
+                NavTileTriangle::new((0, 0), 1),
+            )
+            .unwrap();
+        let (_, expected) = mesh
+            .tile((0, 0))
+            .unwrap()
+            .find_path_triangles(0, 1)
+            .unwrap();
+        assert_relative_eq!(cost, expected, epsilon = 1.0e-4);
+
+        let left_id = mesh.tile((0, 0)).unwrap().id();
+        let (vertices, triangles) = tile(1, 0, true);
+        assert!(mesh
+            .build_tile((1, 0), vertices, triangles)
+            .unwrap()
+            .is_some());
+        assert_eq!(mesh.tile((0, 0)).unwrap().id(), left_id);
+        let start = mesh
+            .find_closest_triangle((0.5, 0.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let end = mesh
+            .find_closest_triangle((1.5, 1.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let path = mesh.find_path_triangles(start, end).unwrap().0;
+        assert!(path.iter().any(|t| t.tile == (1, 0)));
+
+        mesh.remove_tile((1, 0));
+        assert!(mesh.find_path_triangles(start, end).is_none());
+    }
+
+    #[test]
+    fn test_tiled_mesh_y_up() {
+        let mut mesh = NavTiledMesh::new(1.0);
+        mesh.set_up_axis(NavUpAxis::Y);
+        for col in 0..3 {
+            let x = col as Scalar;
+            let vertices = vec![
+                (x, 0.0, 0.0).into(),
+                (x + 1.0, 0.0, 0.0).into(),
+                (x + 1.0, 0.0, 1.0).into(),
+                (x, 0.0, 1.0).into(),
+            ];
+            let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+            let coord = mesh.tile_coord((x + 0.5, 0.0, 0.5).into());
+            mesh.build_tile(coord, vertices, triangles).unwrap();
+        }
+        let from = (0.1, 0.0, 0.1).into();
+        let to = (2.9, 0.0, 0.9).into();
+        let path = mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_relative_eq!(
+            NavMesh::path_length(&path),
+            from.distance(to),
+            epsilon = 1.0e-4
+        );
+        assert_relative_eq!(*path.first().unwrap(), from);
+        assert_relative_eq!(*path.last().unwrap(), to);
+    }
+
+    #[test]
+    fn test_path_watch() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (0.0, 1.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
         ];
-        for (a, b) in expected.iter().zip(thickened.vertices().iter()) {
-            assert_relative_eq!(a, b);
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let path = mesh
+            .find_path_detailed(
+                (0.25, 0.75, 0.0).into(),
+                (1.75, 0.25, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        let a = mesh.watch_path(&path.triangles);
+        let mut corridor = NavPathCorridor::new((0.25, 0.75, 0.0).into(), 1);
+        let b = mesh.watch_path(corridor.triangles());
+        assert_eq!(mesh.watched_paths_count(), 2);
+
+        let obstacle = mesh.add_obstacle(NavObstacle::aabb(
+            (1.6, 0.1, -1.0).into(),
+            (1.9, 0.2, 1.0).into(),
+        ));
+        assert!(mesh.is_watched_path_invalidated(a));
+        assert!(!mesh.is_watched_path_invalidated(b));
+        assert_eq!(mesh.take_invalidated_paths(), vec![a]);
+        assert!(!mesh.is_watched_path_invalidated(a));
+        mesh.remove_obstacle(obstacle);
+        assert_eq!(mesh.take_invalidated_paths(), vec![a]);
+
+        // landmarks do not change triangles.
+        mesh.precompute_landmarks(2);
+        assert!(mesh.take_invalidated_paths().is_empty());
+
+        mesh.set_area_flags(1, 1);
+        mesh.set_area_clearance(2, 1.0);
+        let mut invalidated = mesh.take_invalidated_paths();
+        invalidated.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(invalidated, expected);
+
+        corridor.move_position(&mesh, (0.75, 0.25, 0.0).into());
+        assert!(mesh.update_watched_path(b, corridor.triangles()));
+        mesh.set_area_type(2, 3);
+        assert_eq!(mesh.take_invalidated_paths(), vec![a]);
+        mesh.add_gate("door", vec![0], false).unwrap();
+        assert!(mesh.is_watched_path_invalidated(b));
+        assert!(mesh.unwatch_path(b));
+        assert!(!mesh.unwatch_path(b));
+        assert!(!mesh.update_watched_path(b, &[0]));
+        assert_eq!(mesh.take_invalidated_paths(), vec![a]);
+        assert_eq!(mesh.watched_paths_count(), 1);
+
+        let tile = |col: i32| {
+            let x = col as Scalar;
+            let vertices = vec![
+                (x, 0.0, 0.0).into(),
+                (x + 1.0, 0.0, 0.0).into(),
+                (x + 1.0, 1.0, 0.0).into(),
+                (x, 1.0, 0.0).into(),
+            ];
+            (vertices, vec![(0, 1, 2).into(), (2, 3, 0).into()])
+        };
+        let mut mesh = NavTiledMesh::new(1.0);
+        for col in 0..3 {
+            let (vertices, triangles) = tile(col);
+            mesh.build_tile((col, 0), vertices, triangles).unwrap();
         }
+        let start = NavTileTriangle::new((0, 0), 0);
+        let short = mesh.watch_path(&[start, NavTileTriangle::new((1, 0), 1)]);
+        let long = mesh.watch_path(&[start, NavTileTriangle::new((2, 0), 1)]);
+        let (vertices, triangles) = tile(1);
+        mesh.build_tile((1, 0), vertices, triangles).unwrap();
+        assert_eq!(mesh.take_invalidated_paths(), vec![short]);
+        mesh.remove_tile((2, 0));
+        assert_eq!(mesh.take_invalidated_paths(), vec![long]);
+        mesh.tile_mut((0, 0)).unwrap().set_area_cost(0, 2.0);
+        let mut invalidated = mesh.take_invalidated_paths();
+        invalidated.sort();
+        let mut expected = vec![short, long];
+        expected.sort();
+        assert_eq!(invalidated, expected);
     }
 
     #[test]
-    fn test_grid() {
-        let grid = NavGrid::new(
-            3,
-            3,
-            vec![true, true, true, true, false, true, true, true, true],
-        )
-        .unwrap();
-        let path = grid.find_path((0, 0), (1, 2)).unwrap();
-        assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2)]);
-        assert_eq!(grid.find_path((0, 0), (1, 1)), None);
+    fn test_annotations() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, vec![(0, 1, 2).into(), (2, 3, 0).into()]).unwrap();
+        let mut annotation = NavAnnotation::point("cover", "crate", (2.0, 2.0, 0.0).into());
+        annotation.direction = Some((1.0, 0.0, 0.0).into());
+        let a = mesh.add_annotation(annotation);
+        let b = mesh.add_annotation(NavAnnotation::point("cover", "", (6.0, 2.0, 0.0).into()));
+        let ledge = mesh.add_annotation(NavAnnotation::region(
+            "ledge",
+            "wall",
+            (5.0, 9.0, 1.0).into(),
+            (4.0, 8.0, 0.0).into(),
+        ));
+        let landing =
+            mesh.add_annotation(NavAnnotation::point("landing", "", (4.5, 9.5, 0.0).into()));
+        assert_eq!(mesh.annotations_count(), 4);
+        assert_eq!(mesh.find_annotation("crate"), Some(a));
+        assert_eq!(mesh.find_annotation("wall"), Some(ledge));
+        assert_eq!(mesh.find_annotation("missing"), None);
 
-        let grid = NavGrid::with_connections(
-            2,
-            2,
-            vec![
-                NavGridConnection {
-                    from: (0, 0),
-                    to: (1, 0),
-                },
-                NavGridConnection {
-                    from: (1, 0),
-                    to: (1, 1),
-                },
-                NavGridConnection {
-                    from: (1, 1),
-                    to: (0, 1),
-                },
-                NavGridConnection {
-                    from: (0, 1),
-                    to: (0, 0),
-                },
-            ],
-        )
-        .unwrap();
-        let path = grid.find_path((0, 0), (0, 1)).unwrap();
-        assert_eq!(path, vec![(0, 0), (1, 0), (1, 1), (0, 1)]);
-        let mut islands = grid.find_islands();
-        for island in &mut islands {
-            island.sort();
+        assert_eq!(
+            mesh.nearest_annotation("cover", (5.0, 2.0, 0.0).into(), 10.0),
+            Some(b)
+        );
+        assert_eq!(
+            mesh.nearest_annotation("cover", (3.0, 2.0, 0.0).into(), 10.0),
+            Some(a)
+        );
+        assert_eq!(
+            mesh.nearest_annotation("cover", (9.0, 9.0, 0.0).into(), 1.0),
+            None
+        );
+        assert_eq!(
+            mesh.annotations_in_radius(Some("cover"), (5.0, 2.0, 0.0).into(), 5.0),
+            vec![b, a]
+        );
+        // distance to region is measured to its closest point.
+        assert_eq!(
+            mesh.nearest_annotation("ledge", (4.5, 6.0, 0.5).into(), 2.0),
+            Some(ledge)
+        );
+        assert_eq!(
+            mesh.annotations_in_radius(None, (4.5, 8.5, 0.5).into(), 1.5),
+            vec![ledge, landing]
+        );
+
+        assert!(mesh.link_annotations(ledge, landing, false));
+        assert_eq!(mesh.annotation(ledge).unwrap().link, Some(landing));
+        assert_eq!(mesh.annotation(landing).unwrap().link, None);
+        assert_eq!(mesh.linked_annotations(landing), vec![ledge]);
+        assert!(mesh.link_annotations(a, b, true));
+        assert_eq!(mesh.linked_annotations(a), vec![b]);
+        assert_eq!(mesh.annotation(b).unwrap().link, Some(a));
+
+        mesh.annotation_mut(b).unwrap().kind = "spawn".to_owned();
+        assert_eq!(
+            mesh.nearest_annotation("cover", (5.0, 2.0, 0.0).into(), 10.0),
+            Some(a)
+        );
+        assert!(mesh.remove_annotation(a).is_some());
+        assert!(mesh.remove_annotation(a).is_none());
+        assert_eq!(mesh.annotation(b).unwrap().link, None);
+        assert!(!mesh.link_annotations(a, b, false));
+        let clone = mesh.clone();
+        assert_eq!(clone.annotations_count(), 3);
+    }
+
+    #[test]
+    fn test_cover_points() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (4.0, 0.0, 0.0).into(),
+            (4.0, 4.0, 0.0).into(),
+            (0.0, 4.0, 0.0).into(),
+        ];
+        let mesh = NavMesh::new(vertices, vec![(0, 1, 2).into(), (2, 3, 0).into()]).unwrap();
+        let settings = NavCoverSettings {
+            offset: 0.25,
+            ..Default::default()
+        };
+        let points = mesh.find_cover_points(&settings, None);
+        assert_eq!(points.len(), 16);
+        for point in &points {
+            assert!(point.height.is_none());
+            assert_relative_eq!(point.direction.magnitude(), 1.0);
+            let edge = point.position + point.direction * 0.25;
+            assert!(
+                edge.x.abs() < 1.0e-4
+                    || (edge.x - 4.0).abs() < 1.0e-4
+                    || edge.y.abs() < 1.0e-4
+                    || (edge.y - 4.0).abs() < 1.0e-4
+            );
+            assert_eq!(
+                mesh.find_closest_triangle(point.position, NavQuery::Accuracy),
+                Some(point.triangle)
+            );
         }
-        assert_eq!(islands, vec![vec![(0, 0), (0, 1), (1, 0), (1, 1)]]);
 
-        let grid = NavGrid::new(
-            3,
-            3,
-            vec![true, true, true, false, false, false, true, true, true],
-        )
-        .unwrap();
-        let mut islands = grid.find_islands();
-        for island in &mut islands {
-            island.sort();
+        // tall wall on the left, low wall on the bottom, far wall on the top.
+        let obstruction_vertices = vec![
+            (-0.1, -1.0, 0.0).into(),
+            (-0.1, 5.0, 0.0).into(),
+            (-0.1, 5.0, 3.0).into(),
+            (-0.1, -1.0, 3.0).into(),
+            (-1.0, -0.1, 0.0).into(),
+            (5.0, -0.1, 0.0).into(),
+            (5.0, -0.1, 0.5).into(),
+            (-1.0, -0.1, 0.5).into(),
+            (-1.0, 5.0, 0.0).into(),
+            (5.0, 5.0, 0.0).into(),
+            (5.0, 5.0, 3.0).into(),
+            (-1.0, 5.0, 3.0).into(),
+        ];
+        let obstruction_triangles = vec![
+            (0, 1, 2).into(),
+            (2, 3, 0).into(),
+            (4, 5, 6).into(),
+            (6, 7, 4).into(),
+            (8, 9, 10).into(),
+            (10, 11, 8).into(),
+        ];
+        let points = mesh.find_cover_points(
+            &settings,
+            Some((&obstruction_vertices, &obstruction_triangles)),
+        );
+        assert_eq!(points.len(), 4);
+        for point in &points {
+            assert_relative_eq!(point.position.x, 0.25);
+            assert_relative_eq!(point.direction, NavVec3::new(-1.0, 0.0, 0.0));
+            assert_eq!(point.height, Some(2.0));
         }
+
+        // low cover is accepted once minimal height goes down.
+        let settings = NavCoverSettings {
+            min_height: 0.5,
+            ..Default::default()
+        };
+        let points = mesh.find_cover_points(
+            &settings,
+            Some((&obstruction_vertices, &obstruction_triangles)),
+        );
+        assert_eq!(points.len(), 8);
         assert_eq!(
-            islands,
-            vec![vec![(0, 0), (1, 0), (2, 0)], vec![(0, 2), (1, 2), (2, 2)]]
+            points
+                .iter()
+                .filter(|point| point.height == Some(0.5))
+                .count(),
+            4
         );
+    }
 
-        let grid = NavFreeGrid::new(vec![
-            NavFreeGridConnection {
-                from: (0, 0),
-                to: (0, 2),
-            },
-            NavFreeGridConnection {
-                from: (0, 2),
-                to: (-1, -1),
-            },
-        ]);
-        let path = grid.find_path((0, 0), (-1, -1)).unwrap();
-        assert_eq!(path, vec![(0, 0), (0, 2), (-1, -1)]);
+    #[test]
+    fn test_is_visible() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (0.0, 1.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        assert!(mesh.is_visible((0.5, 0.5, 0.0).into(), (1.5, 0.5, 0.0).into(), 0.0));
+        assert!(!mesh.is_visible((0.5, 0.5, 0.0).into(), (2.5, 0.5, 0.0).into(), 0.0));
+        assert!(mesh.is_visible((1.6, 0.5, 0.0).into(), (1.8, 0.5, 0.0).into(), 0.1));
+
+        mesh.add_gate("door", vec![3], false).unwrap();
+        assert!(!mesh.is_visible((0.5, 0.5, 0.0).into(), (1.5, 0.5, 0.0).into(), 0.0));
+        assert!(mesh.is_visible((1.6, 0.5, 0.0).into(), (1.8, 0.5, 0.0).into(), 0.05));
+        // blocked triangle next to line is too close for wider agent.
+        assert!(!mesh.is_visible((1.6, 0.5, 0.0).into(), (1.8, 0.5, 0.0).into(), 0.1));
+
+        mesh.set_gate("door", true).unwrap();
+        assert!(mesh.is_visible((1.6, 0.5, 0.0).into(), (1.8, 0.5, 0.0).into(), 0.1));
+    }
+
+    #[test]
+    fn test_visibility_graph() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (3.0, 0.0, 0.0).into(),
+            (3.0, 3.0, 0.0).into(),
+            (0.0, 3.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+            (2.0, 2.0, 0.0).into(),
+            (1.0, 2.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 5).into(),
+            (5, 4, 0).into(),
+            (1, 2, 6).into(),
+            (6, 5, 1).into(),
+            (2, 3, 7).into(),
+            (7, 6, 2).into(),
+            (3, 0, 4).into(),
+            (4, 7, 3).into(),
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let graph = mesh.build_visibility_graph();
+        assert_eq!(graph.corners().len(), 4);
+        let mut edges = graph
+            .edges()
+            .map(|(a, b)| {
+                let a = graph.corners()[a];
+                let b = graph.corners()[b];
+                a.distance(b)
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        // hole sides only, diagonals go through hole.
+        assert_eq!(edges, vec![1.0; 4]);
+        for corner in 0..graph.corners().len() {
+            assert_eq!(graph.neighbors(corner).len(), 2);
+        }
+        assert!(graph.neighbors(4).is_empty());
+
+        let visible = graph.visible_corners(&mesh, (0.5, 0.5, 0.0).into());
+        let mut visible = visible
+            .into_iter()
+            .map(|corner| graph.corners()[corner])
+            .collect::<Vec<_>>();
+        visible.sort_by(|a, b| (a.x, a.y).partial_cmp(&(b.x, b.y)).unwrap());
+        assert_eq!(
+            visible,
+            vec![
+                (1.0, 1.0, 0.0).into(),
+                (1.0, 2.0, 0.0).into(),
+                (2.0, 1.0, 0.0).into(),
+            ]
+        );
+
+        let path = graph
+            .find_path(&mesh, (0.5, 0.5, 0.0).into(), (0.5, 2.5, 0.0).into())
+            .unwrap();
+        assert_eq!(path, vec![(0.5, 0.5, 0.0).into(), (0.5, 2.5, 0.0).into()]);
+        let path = graph
+            .find_path(&mesh, (0.5, 1.5, 0.0).into(), (2.5, 1.5, 0.0).into())
+            .unwrap();
+        assert_eq!(path.len(), 4);
+        let length = path
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum::<Scalar>();
+        let expected = (0.5 as Scalar * 0.5 + 0.5 * 0.5).sqrt() * 2.0 + 1.0;
+        assert!((length - expected).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_patrol_route() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (0.0, 1.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let waypoints = [
+            (0.25, 0.5, 0.0).into(),
+            (1.0, 0.8, 0.0).into(),
+            (1.0, 0.2, 0.0).into(),
+            (1.0, 0.2, 0.0).into(),
+        ];
+        assert!(mesh
+            .find_patrol_route(
+                &waypoints[..1],
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+                true
+            )
+            .is_none());
+
+        let route = mesh
+            .find_patrol_route(&waypoints, NavQuery::Accuracy, NavPathMode::Accuracy, false)
+            .unwrap();
+        assert_eq!(route.order, vec![0, 1, 2, 3]);
+        assert_eq!(
+            route
+                .legs
+                .iter()
+                .map(|leg| (leg.from, leg.to))
+                .collect::<Vec<_>>(),
+            vec![(0, 1), (1, 2), (2, 3), (3, 0)]
+        );
+        let path = &route.path;
+        assert_eq!(path.points.first(), path.points.last());
+        assert_eq!(path.segment_area_types.len(), path.points.len() - 1);
+        assert!((path.length - NavMesh::path_length(&path.points)).abs() < 1.0e-4);
+        assert!(
+            (path.length - route.legs.iter().map(|leg| leg.length).sum::<Scalar>()).abs() < 1.0e-4
+        );
+        for leg in &route.legs {
+            assert_eq!(path.points[leg.points.start], waypoints[leg.from]);
+            assert_eq!(path.points[leg.points.end - 1], waypoints[leg.to]);
+        }
+        // duplicated waypoint makes zero length leg.
+        assert_eq!(route.legs[2].length, 0.0);
+        assert_eq!(route.leg_of_segment(0), Some(&route.legs[0]));
+        assert_eq!(
+            route.leg_of_segment(path.points.len() - 2),
+            Some(&route.legs[3])
+        );
+        assert_eq!(route.leg_of_segment(path.points.len() - 1), None);
+        for pair in path.triangles.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+
+        mesh.add_gate("door", vec![1], false).unwrap();
+        assert!(mesh
+            .find_patrol_route(&waypoints, NavQuery::Accuracy, NavPathMode::Accuracy, true)
+            .is_none());
+    }
+
+    #[test]
+    fn test_distance_field() {
+        // 3x3 cells floor and separate 1x1 cell floor above its center.
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for row in 0..4 {
+            for col in 0..4 {
+                vertices.push(NavVec3::new(col as Scalar, row as Scalar, 0.0));
+            }
+        }
+        for row in 0..3 {
+            for col in 0..3 {
+                let index = row * 4 + col;
+                triangles.push((index, index + 1, index + 5).into());
+                triangles.push((index + 5, index + 4, index).into());
+            }
+        }
+        vertices.push((1.0, 1.0, 0.5).into());
+        vertices.push((2.0, 1.0, 0.5).into());
+        vertices.push((2.0, 2.0, 0.5).into());
+        vertices.push((1.0, 2.0, 0.5).into());
+        triangles.push((16, 17, 18).into());
+        triangles.push((18, 19, 16).into());
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+
+        let points: [NavVec3; 5] = [
+            (1.5, 1.5, 0.0).into(),
+            (0.25, 1.75, 0.0).into(),
+            (2.9, 0.5, 0.0).into(),
+            (1.25, 2.5, 0.0).into(),
+            (5.0, 1.5, 0.0).into(),
+        ];
+        for point in points {
+            let point_on_mesh = mesh.closest_point(point, NavQuery::Accuracy).unwrap();
+            let expected = mesh
+                .boundary_edges()
+                .filter(|(_, a, b)| a.z == 0.0 && b.z == 0.0)
+                .map(|(_, a, b)| point_on_mesh.distance_to_segment(a, b))
+                .fold(Scalar::INFINITY, Scalar::min);
+            let distance = mesh.distance_to_boundary(point_on_mesh).unwrap();
+            assert!((distance - expected).abs() < 1.0e-5);
+        }
+        // upper floor walls are closer than ground floor ones.
+        assert!((mesh.distance_to_boundary((1.5, 1.5, 0.5).into()).unwrap() - 0.5).abs() < 1.0e-5);
+        assert!((mesh.distance_to_boundary((1.5, 1.5, 0.0).into()).unwrap() - 1.5).abs() < 1.0e-5);
+        let boundary = mesh.closest_boundary_point((2.9, 0.5, 0.0).into()).unwrap();
+        assert!(boundary.same_as((3.0, 0.5, 0.0).into()));
+
+        let field = mesh.build_distance_field();
+        assert_eq!(field.vertice_distance(0), Some(0.0));
+        assert_eq!(field.vertice_distance(5), Some(1.0));
+        assert_eq!(field.vertice_distance(20), None);
+        let center = field.triangle_distance(8).unwrap();
+        assert!((center - 4.0 / 3.0).abs() < 1.0e-5);
+        assert_eq!(field.max_distance(), center);
+        // interpolated between interior vertices.
+        let distance = field.distance(&mesh, (1.5, 1.0, 0.0).into()).unwrap();
+        assert!((distance - 1.0).abs() < 1.0e-5);
+        let distance = field.distance(&mesh, (0.5, 1.0, 0.0).into()).unwrap();
+        assert!((distance - 0.5).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_path_centerline() {
+        // L shaped corridor turning around inner corner at (1, 1).
+        let mut vertices = vec![];
+        for row in 0..4 {
+            for col in 0..4 {
+                vertices.push(NavVec3::new(col as Scalar, row as Scalar, 0.0));
+            }
+        }
+        let mut triangles = vec![];
+        for (col, row) in [(0, 0), (1, 0), (2, 0), (0, 1), (0, 2)] {
+            let index = row * 4 + col;
+            triangles.push((index, index + 1, index + 5).into());
+            triangles.push((index + 5, index + 4, index).into());
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = (2.5, 0.5, 0.0).into();
+        let to = (0.5, 2.5, 0.0).into();
+        let clearance = |mode| {
+            let path = mesh.find_path(from, to, NavQuery::Accuracy, mode).unwrap();
+            assert_eq!(path.first(), Some(&from));
+            assert_eq!(path.last(), Some(&to));
+            let length = NavMesh::path_length(&path);
+            let clearance = path[1..(path.len() - 1)]
+                .iter()
+                .map(|point| mesh.distance_to_boundary(*point).unwrap())
+                .fold(Scalar::INFINITY, Scalar::min);
+            (length, clearance)
+        };
+        let (_, shortest_clearance) = clearance(NavPathMode::Accuracy);
+        assert!(shortest_clearance < 1.0e-4);
+        let shortest = mesh
+            .find_path_with_radius(from, to, NavQuery::Accuracy, 0.0)
+            .unwrap();
+        let shortest_length = NavMesh::path_length(&shortest);
+        let (length, clearance_none) = clearance(NavPathMode::Centerline(0.0));
+        assert!((length - shortest_length).abs() < 1.0e-4);
+        assert!(clearance_none < 1.0e-4);
+        let (half_length, half_clearance) = clearance(NavPathMode::Centerline(0.5));
+        let (full_length, full_clearance) = clearance(NavPathMode::Centerline(1.0));
+        assert!(shortest_length < half_length && half_length < full_length);
+        assert!(half_clearance > 0.2);
+        assert!(full_clearance > half_clearance);
+        assert!(full_clearance <= 0.5 + 1.0e-4);
+    }
+
+    #[test]
+    fn test_vehicle_path() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+        ];
+        let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let settings = NavVehicleSettings::default();
+        let path = [
+            (1.0, 1.0, 0.0).into(),
+            (8.0, 1.0, 0.0).into(),
+            (8.0, 8.0, 0.0).into(),
+        ];
+        let result = mesh.vehicle_path(&path, None, &settings);
+        assert!(result.feasible);
+        assert_eq!(result.points.len(), result.headings.len());
+        assert_eq!(result.points.len(), result.reverse.len() + 1);
+        assert_eq!(result.points.first(), Some(&path[0]));
+        assert_eq!(result.points.last(), Some(&path[2]));
+        assert!(result.points.iter().any(|point| point.same_as(path[1])));
+        assert!(result.length > 14.0);
+        assert_eq!(result.cusps().count(), 0);
+        // heading never changes faster than turning radius allows.
+        for (pair, points) in result.headings.windows(2).zip(result.points.windows(2)) {
+            let angle = pair[0].dot(pair[1]).clamp(-1.0, 1.0).acos();
+            let distance = points[0].distance(points[1]);
+            assert!(angle <= distance / settings.turning_radius * 1.1 + 1.0e-3);
+        }
+
+        // going forward and then backing up to point behind.
+        let path = [
+            (2.0, 5.0, 0.0).into(),
+            (6.0, 5.0, 0.0).into(),
+            (4.0, 5.0, 0.0).into(),
+        ];
+        let heading = Some((1.0, 0.0, 0.0).into());
+        let settings = NavVehicleSettings {
+            allow_reverse: true,
+            ..Default::default()
+        };
+        let result = mesh.vehicle_path(&path, heading, &settings);
+        assert!(result.feasible);
+        assert!((result.length - 6.0).abs() < 1.0e-4);
+        let cusps = result.cusps().collect::<Vec<_>>();
+        assert_eq!(cusps.len(), 1);
+        assert!(result.points[cusps[0]].same_as(path[1]));
+
+        // L shaped corridor too narrow for big turning radius.
+        let mut vertices = vec![];
+        for row in 0..4 {
+            for col in 0..4 {
+                vertices.push(NavVec3::new(col as Scalar, row as Scalar, 0.0));
+            }
+        }
+        let mut triangles = vec![];
+        for (col, row) in [(0, 0), (1, 0), (2, 0), (0, 1), (0, 2)] {
+            let index = row * 4 + col;
+            triangles.push((index, index + 1, index + 5).into());
+            triangles.push((index + 5, index + 4, index).into());
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let path = [
+            (2.5, 0.5, 0.0).into(),
+            (0.5, 0.5, 0.0).into(),
+            (0.5, 2.5, 0.0).into(),
+        ];
+        let settings = NavVehicleSettings {
+            turning_radius: 0.4,
+            ..Default::default()
+        };
+        assert!(mesh.vehicle_path(&path, None, &settings).feasible);
+        let settings = NavVehicleSettings {
+            turning_radius: 3.0,
+            ..Default::default()
+        };
+        let result = mesh.vehicle_path(&path, None, &settings);
+        assert!(!result.feasible);
+        assert_eq!(result.points.last(), Some(&path[2]));
+    }
+
+    #[test]
+    fn test_mesh_transformed() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.add_gate("door", vec![2, 3], false).unwrap();
+        mesh.set_area_clearance(0, 0.5);
+        let id = mesh.add_annotation(NavAnnotation::point("spawn", "", (0.5, 0.5, 0.0).into()));
+
+        let transform = NavTransform::new(
+            (0.0, 0.0, 5.0).into(),
+            NavQuat::IDENTITY,
+            (-2.0, 2.0, 2.0).into(),
+        );
+        assert!(transform.determinant() < 0.0);
+        let inverse = transform.inverse().unwrap();
+        let point = NavVec3::new(0.3, -1.2, 4.0);
+        assert!(inverse
+            .transform_point(transform.transform_point(point))
+            .same_as(point));
+        assert!((transform * inverse).transform_point(point).same_as(point));
+        assert_eq!(
+            NavTransform::from_cols_array(&transform.to_cols_array()),
+            transform
+        );
+        assert!(NavTransform::from_scale(NavVec3::default())
+            .inverse()
+            .is_none());
+
+        let mirrored = mesh.transformed(&transform).unwrap();
+        assert_eq!(mirrored.triangles().len(), 4);
+        for index in 0..4 {
+            assert_eq!(mirrored.triangle_slope(index), Some(0.0));
+            assert!(mirrored
+                .spatial(index)
+                .normal()
+                .same_as(mesh.spatial(index).normal()));
+        }
+        assert_eq!(mirrored.is_gate_open("door"), Some(false));
+        assert_eq!(mirrored.gate_triangles("door"), Some(&[2, 3][..]));
+        assert_eq!(mirrored.areas()[0].clearance, 1.0);
+        assert!(mirrored
+            .annotation(id)
+            .unwrap()
+            .shape
+            .center()
+            .same_as((-1.0, 1.0, 5.0).into()));
+        let from = NavVec3::new(-0.5, 1.0, 5.0);
+        assert!(mirrored
+            .find_path(
+                from,
+                (-1.5, 1.0, 5.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::MidPoints
+            )
+            .is_some());
+        // closed gate still blocks way to triangles behind it.
+        assert!(mirrored
+            .find_path(
+                from,
+                (-3.5, 1.0, 5.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::MidPoints
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_world() {
+        // corridor piece 2 long and 1 wide.
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (0.0, 1.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mut piece = NavMesh::new(vertices, triangles).unwrap();
+        piece.add_gate("door", vec![2, 3], true).unwrap();
+        let quarter = std::f64::consts::FRAC_PI_2 as Scalar;
+        let up = NavVec3::new(0.0, 0.0, 1.0);
+
+        let mut world = NavWorld::new();
+        let first = world.add_instance(&piece, NavTransform::default()).unwrap();
+        // second piece turned to go up from right end of first one.
+        let second = world
+            .add_instance(
+                &piece,
+                NavTransform::from_translation((2.0, 1.0, 0.0).into())
+                    * NavTransform::from_axis_angle(up, quarter),
+            )
+            .unwrap();
+        assert_eq!(world.instances().count(), 2);
+        assert!(world.portals(NavWorldTriangle::new(first, 2)).is_empty());
+        assert_eq!(world.portals(NavWorldTriangle::new(first, 3)).len(), 1);
+        let from = NavVec3::new(0.5, 0.5, 0.0);
+        let to = NavVec3::new(1.5, 2.5, 0.0);
+        let path = world
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_eq!(path.first(), Some(&from));
+        assert_eq!(path.last(), Some(&to));
+        // shortest path cuts inner corner of turn.
+        assert_eq!(path.len(), 3);
+        assert!(path[1].same_as((1.0, 1.0, 0.0).into()));
+        let (triangles, _) = world
+            .find_path_triangles(
+                world
+                    .find_closest_triangle(from, NavQuery::Accuracy)
+                    .unwrap(),
+                world.find_closest_triangle(to, NavQuery::Accuracy).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(triangles.first().unwrap().instance, first);
+        assert_eq!(triangles.last().unwrap().instance, second);
+        // moves inside single instance cost the same as on its nav mesh alone.
+        let (_, world_cost) = world
+            .find_path_triangles(
+                NavWorldTriangle::new(first, 0),
+                NavWorldTriangle::new(first, 2),
+            )
+            .unwrap();
+        let (_, mesh_cost) = piece.find_path_triangles(0, 2).unwrap();
+        assert!((world_cost - mesh_cost).abs() < 1.0e-4);
+
+        // closing door of first piece cuts second one off.
+        let version = world.version();
+        world
+            .instance_mesh_mut(first)
+            .unwrap()
+            .set_gate("door", false);
+        assert!(world.version() > version);
+        assert!(world
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .is_none());
+        world
+            .instance_mesh_mut(first)
+            .unwrap()
+            .set_gate("door", true);
+
+        // link added before its target instance exists gets attached later.
+        let link = world.add_link(
+            NavWorldLink::new((0.5, 0.5, 0.0).into(), (0.5, 5.5, 0.0).into(), true).with_cost(0.5),
+        );
+        let third = world
+            .add_instance(
+                &piece,
+                NavTransform::from_translation((0.0, 5.0, 0.0).into()),
+            )
+            .unwrap();
+        let far = NavVec3::new(1.5, 5.5, 0.0);
+        let path = world
+            .find_path(to, far, NavQuery::Accuracy, NavPathMode::MidPoints)
+            .unwrap();
+        assert!(path.contains(&NavVec3::new(0.5, 0.5, 0.0)));
+        assert!(path.contains(&NavVec3::new(0.5, 5.5, 0.0)));
+        // bidirectional link is usable backwards.
+        assert!(world
+            .find_path(far, to, NavQuery::Accuracy, NavPathMode::MidPoints)
+            .is_some());
+
+        assert!(world.remove_link(link).is_some());
+        assert!(world
+            .find_path(to, far, NavQuery::Accuracy, NavPathMode::MidPoints)
+            .is_none());
+        assert!(world.remove_instance(second).is_some());
+        assert!(world.portals(NavWorldTriangle::new(first, 3)).is_empty());
+        assert!(world.instance(third).is_some());
+        assert_eq!(world.instances().count(), 2);
+    }
+
+    #[test]
+    fn test_quat() {
+        let up = NavVec3::new(0.0, 0.0, 1.0);
+        let quarter = std::f64::consts::FRAC_PI_2 as Scalar;
+        let rotation = NavQuat::from_axis_angle(up, quarter);
+        assert!(rotation
+            .rotate((1.0, 0.0, 0.0).into())
+            .same_as((0.0, 1.0, 0.0).into()));
+        let (axis, angle) = rotation.to_axis_angle();
+        assert!(axis.same_as(up));
+        assert!((angle - quarter).abs() < 1.0e-5);
+        let half = rotation * rotation;
+        assert!((half.angle_between(NavQuat::IDENTITY) - quarter * 2.0).abs() < 1.0e-4);
+        assert!((rotation.inverse() * rotation).angle_between(NavQuat::IDENTITY) < 1.0e-3);
+        assert!(NavQuat::IDENTITY
+            .slerp(half, 0.5)
+            .forward()
+            .same_as((0.0, 1.0, 0.0).into()));
+        let arc = NavQuat::from_rotation_arc((1.0, 0.0, 0.0).into(), (0.0, 0.0, -3.0).into());
+        assert!(arc.forward().same_as((0.0, 0.0, -1.0).into()));
+        let opposite = NavQuat::from_rotation_arc((1.0, 0.0, 0.0).into(), (-1.0, 0.0, 0.0).into());
+        assert!(opposite.forward().same_as((-1.0, 0.0, 0.0).into()));
+        let facing = NavQuat::facing((-1.0, 1.0, 0.0).into(), NavUpAxis::Z);
+        assert!((facing.to_yaw(NavUpAxis::Z) - quarter * 1.5).abs() < 1.0e-5);
+        let transform = NavTransform::from_rotation(facing);
+        let point = NavVec3::new(0.5, -2.0, 1.0);
+        assert!(transform.transform_point(point).same_as(facing * point));
+    }
+
+    #[test]
+    fn test_raycast_3d() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        // slanted ray hitting slope, and ray going up hitting its back side.
+        for (from, to) in [
+            (NavVec3::new(0.0, 0.5, 3.0), NavVec3::new(3.0, 0.5, -1.5)),
+            (
+                NavVec3::new(1.75, 0.25, -1.0),
+                NavVec3::new(1.75, 0.25, 2.0),
+            ),
+        ] {
+            let hit = mesh.raycast_3d(from, to).unwrap();
+            let triangle = mesh.triangles()[hit.triangle];
+            let vertices = mesh.vertices();
+            let point = vertices[triangle.first as usize] * hit.barycentric.x
+                + vertices[triangle.second as usize] * hit.barycentric.y
+                + vertices[triangle.third as usize] * hit.barycentric.z;
+            assert!(point.same_as(hit.point));
+            assert!((hit.distance - from.distance(hit.point)).abs() < 1.0e-4);
+            assert!(hit.point.distance_to_segment(from, to) < 1.0e-4);
+            assert!(hit.triangle >= 2);
+        }
+        assert!(mesh
+            .raycast_3d((0.5, 0.5, 2.0).into(), (0.5, 0.5, 0.5).into())
+            .is_none());
+    }
+
+    #[test]
+    fn test_pick_target() {
+        // two islands separated by gap, first one split into two areas.
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+            (2.5, 0.0, 0.0).into(), // 6
+            (3.5, 0.0, 0.0).into(), // 7
+            (3.5, 1.0, 0.0).into(), // 8
+            (2.5, 1.0, 0.0).into(), // 9
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+            (6, 7, 8).into(), // 4
+            (8, 9, 6).into(), // 5
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let settings = NavPickSettings {
+            max_distance: 100.0,
+            search_radius: 1.0,
+        };
+        let down = NavVec3::new(0.0, 0.0, -1.0);
+
+        // slanted ray hits far island directly.
+        let origin = NavVec3::new(0.0, 0.5, 3.0);
+        let target = mesh
+            .pick_target(origin, NavVec3::new(3.0, 0.0, -3.0), None, settings)
+            .unwrap();
+        assert!(target.direct);
+        assert!(target.point.same_as((3.0, 0.5, 0.0).into()));
+        assert!((target.distance - origin.distance(target.point)).abs() < 1.0e-4);
+
+        // far island is not reachable from agent, so target snaps to closest reachable point.
+        let agent = Some(NavVec3::new(0.5, 0.5, 0.0));
+        let target = mesh
+            .pick_target((2.7, 0.5, 5.0).into(), down, agent, settings)
+            .unwrap();
+        assert!(!target.direct);
+        assert!(target.point.same_as((2.0, 0.5, 0.0).into()));
+        assert!(matches!(target.triangle, 2 | 3));
+        assert!(mesh
+            .pick_target((3.2, 0.5, 5.0).into(), down, agent, settings)
+            .is_none());
+
+        // blocked triangles are skipped.
+        mesh.add_gate("door", vec![4, 5], false).unwrap();
+        let target = mesh
+            .pick_target((2.7, 0.5, 5.0).into(), down, None, settings)
+            .unwrap();
+        assert!(target.point.same_as((2.0, 0.5, 0.0).into()));
+        mesh.set_gate("door", true);
+
+        // filter rejecting area type makes its triangles invalid targets.
+        mesh.set_area_type(2, 1);
+        mesh.set_area_type(3, 1);
+        let filter = |_: NavVec3, _: NavVec3, area: u8| if area == 1 { None } else { Some(1.0) };
+        let target = mesh
+            .pick_target_with_filter((1.5, 0.5, 5.0).into(), down, agent, settings, &filter)
+            .unwrap();
+        assert!(!target.direct);
+        assert!(target.point.same_as((1.0, 0.5, 0.0).into()));
+        assert!(matches!(target.triangle, 0 | 1));
+    }
+
+    #[test]
+    fn test_vertex_attribute() {
+        // ring of quads around hole in the middle of 3x3 grid.
+        let vertices = (0..16)
+            .map(|index| NavVec3::new((index % 4) as Scalar, (index / 4) as Scalar, 0.0))
+            .collect::<Vec<_>>();
+        let triangles = (0..9)
+            .filter(|index| *index != 4)
+            .flat_map(|index| {
+                let first = (index / 3) * 4 + index % 3;
+                [
+                    (first, first + 1, first + 5).into(),
+                    (first + 5, first + 4, first).into(),
+                ]
+            })
+            .collect::<Vec<NavTriangle>>();
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        assert!(matches!(
+            mesh.set_vertex_attribute("danger", vec![0.0; 3]),
+            Err(Error::VertexAttributeCountDoesNotMatchVertices(3, 16))
+        ));
+        // bottom row of vertices is dangerous.
+        let danger = (0..16)
+            .map(|index| if index < 4 { 1.0 } else { 0.0 })
+            .collect::<Vec<_>>();
+        assert!(mesh
+            .set_vertex_attribute("danger", danger)
+            .unwrap()
+            .is_none());
+        assert_eq!(mesh.vertex_attributes().collect::<Vec<_>>(), vec!["danger"]);
+        let value = mesh
+            .sample_vertex_attribute("danger", (0.5, 0.25, 0.0).into())
+            .unwrap();
+        assert!((value - 0.75).abs() < 1.0e-5);
+        assert_eq!(
+            mesh.sample_vertex_attribute("danger", (0.5, 2.5, 0.0).into()),
+            Some(0.0)
+        );
+        assert!(mesh
+            .sample_vertex_attribute("light", (0.5, 0.5, 0.0).into())
+            .is_none());
+
+        // dangerous bottom side of ring gets avoided.
+        let from = mesh
+            .find_closest_triangle((0.5, 1.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let to = mesh
+            .find_closest_triangle((2.5, 1.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let filter = NavVertexAttributeFilter::new(&mesh, "danger")
+            .unwrap()
+            .with_weight(10.0);
+        let (path, _) = mesh
+            .find_path_triangles_with_filter(from, to, &filter)
+            .unwrap();
+        assert!(path.iter().all(|index| mesh.areas()[*index].center.y > 1.0));
+        assert!(NavVertexAttributeFilter::new(&mesh, "light").is_none());
+
+        // values change dynamically, so top side becomes dangerous instead.
+        for index in 0..16 {
+            let value = if index >= 12 { 1.0 } else { 0.0 };
+            mesh.set_vertex_attribute_value("danger", index, value);
+        }
+        assert!(mesh.set_vertex_attribute_value("danger", 16, 1.0).is_none());
+        let filter = NavVertexAttributeFilter::new(&mesh, "danger")
+            .unwrap()
+            .with_weight(10.0);
+        let (path, _) = mesh
+            .find_path_triangles_with_filter(from, to, &filter)
+            .unwrap();
+        assert!(path.iter().all(|index| mesh.areas()[*index].center.y < 2.0));
+
+        // attributes follow transformed nav mesh.
+        let moved = mesh
+            .transformed(&NavTransform::from_translation((10.0, 0.0, 0.0).into()))
+            .unwrap();
+        assert_eq!(
+            moved.sample_vertex_attribute("danger", (10.5, 2.75, 0.0).into()),
+            mesh.sample_vertex_attribute("danger", (0.5, 2.75, 0.0).into())
+        );
+        assert!(mesh.remove_vertex_attribute("danger").is_some());
+        assert!(mesh.vertex_attribute("danger").is_none());
+    }
+
+    #[test]
+    fn test_influence_map() {
+        // ring of quads around hole in the middle of 3x3 grid.
+        let vertices = (0..16)
+            .map(|index| NavVec3::new((index % 4) as Scalar, (index / 4) as Scalar, 0.0))
+            .collect::<Vec<_>>();
+        let triangles = (0..9)
+            .filter(|index| *index != 4)
+            .flat_map(|index| {
+                let first = (index / 3) * 4 + index % 3;
+                [
+                    (first, first + 1, first + 5).into(),
+                    (first + 5, first + 4, first).into(),
+                ]
+            })
+            .collect::<Vec<NavTriangle>>();
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let mut map = NavInfluenceMap::new(&mesh);
+        assert_eq!(map.mesh(), mesh.id());
+        assert_eq!(map.values().len(), 16);
+        let from = mesh
+            .find_closest_triangle((0.5, 1.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let to = mesh
+            .find_closest_triangle((2.5, 1.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+
+        // sniper sightline covering bottom side of ring.
+        let count = map.add_along(
+            &mesh,
+            (-1.0, 0.25, 0.0).into(),
+            (4.0, 0.25, 0.0).into(),
+            0.5,
+            5.0,
+        );
+        assert_eq!(count, 6);
+        assert!((map.max_value() - 5.0).abs() < 1.0e-5);
+        assert!(map.sample(&mesh, (1.5, 2.5, 0.0).into()).unwrap() < 1.0e-5);
+        let filter = NavInfluenceFilter::new(&mesh, &map).unwrap();
+        let (path, _) = mesh
+            .find_path_triangles_with_filter(from, to, &filter)
+            .unwrap();
+        assert!(path.iter().all(|index| mesh.areas()[*index].center.y > 1.0));
+
+        // grenade blast on top side makes bottom side preferred again.
+        map.add_at(&mesh, (1.5, 2.5, 0.0).into(), 1.0, 20.0);
+        let filter = NavInfluenceFilter::new(&mesh, &map).unwrap();
+        let (path, _) = mesh
+            .find_path_triangles_with_filter(from, to, &filter)
+            .unwrap();
+        assert!(path.iter().all(|index| mesh.areas()[*index].center.y < 2.0));
+        let filter = filter.with_max_value(1.0);
+        assert!(mesh
+            .find_path_triangles_with_filter(from, to, &filter)
+            .is_none());
+
+        // influence spreads and fades.
+        let before = map.values().to_vec();
+        map.blur(0.5);
+        assert!(map.max_value() < before.iter().copied().fold(0.0, Scalar::max));
+        assert!(before
+            .iter()
+            .zip(map.values())
+            .any(|(before, after)| *before == 0.0 && *after > 0.0));
+        map.decay(0.0);
+        assert_eq!(map.max_value(), 0.0);
+        map.set_value(0, 1.0);
+        map.clear();
+        assert_eq!(map.value(0), Some(0.0));
+
+        // map made for other nav mesh is not used.
+        let other = mesh.transformed(&NavTransform::default()).unwrap();
+        assert!(NavInfluenceFilter::new(&other, &map).is_none());
+        assert!(map.sample(&other, (0.5, 0.5, 0.0).into()).is_none());
+        assert_eq!(map.add_at(&other, (0.5, 0.5, 0.0).into(), 1.0, 1.0), 0);
+    }
+
+    #[test]
+    fn test_recorder() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (20.0, 0.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (20.0, 10.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.add_gate("door", vec![2], true).unwrap();
+        let mut recorder = NavRecorder::new(&mesh);
+        let path = recorder.find_path(
+            &mesh,
+            (1.0, 9.0, 0.0).into(),
+            (19.0, 1.0, 0.0).into(),
+            NavQuery::Layered(2.0),
+            NavPathMode::Centerline(0.5),
+        );
+        assert!(path.is_some());
+        recorder.find_path_triangles(&mesh, 1, 2).unwrap();
+        recorder.find_path(
+            &mesh,
+            (1.0, 9.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            NavQuery::Accuracy,
+            NavPathMode::MidPoints,
+        );
+        let records = recorder.records().collect::<Vec<_>>();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|record| record.stats.found));
+        assert_eq!(records[1].stats.from, 1);
+        assert_eq!(records[1].stats.to, 2);
+        assert!(records[1].stats.expansions > 0);
+
+        let bytes = recorder.to_bytes();
+        let loaded = NavRecorder::from_bytes(&bytes).unwrap();
+        assert!(loaded.matches_mesh(&mesh));
+        assert_eq!(loaded.fingerprint(), recorder.fingerprint());
+        for (a, b) in loaded.records().zip(recorder.records()) {
+            assert!(a.result.same_as(&b.result));
+            assert_eq!(a.stats.expansions, b.stats.expansions);
+            assert_eq!(a.stats.time, b.stats.time);
+        }
+        assert!(loaded.replay(&mesh).iter().all(|result| result.matches));
+
+        // closed gate changes results of queries going through it.
+        mesh.set_gate("door", false);
+        let replayed = loaded.replay(&mesh);
+        assert!(!replayed[0].matches);
+        assert!(!replayed[0].result.is_found());
+        assert!(!replayed[1].matches);
+        assert!(replayed[2].matches);
+
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+        assert!(NavRecorder::from_bytes(&corrupted).is_err());
+        assert!(NavRecorder::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(NavRecorder::from_bytes(b"NAVM").is_err());
+
+        // limited recorder keeps only most recent records.
+        let mut recorder = NavRecorder::with_capacity(&mesh, 2);
+        for to in 0..4 {
+            recorder.find_path_triangles(&mesh, 0, to);
+        }
+        let kept = recorder
+            .records()
+            .map(|record| match record.query {
+                NavRecordedQuery::Triangles { to, .. } => to,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kept, vec![2, 3]);
+        let loaded = NavRecorder::from_bytes(&recorder.to_bytes()).unwrap();
+        assert_eq!(loaded.capacity(), Some(2));
+        assert!(!loaded.replay(&mesh)[0].result.is_found());
+    }
+
+    #[test]
+    fn test_locate_from_hint() {
+        // grid of quads with hole in the middle.
+        let size = 10;
+        let mut vertices = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push(NavVec3::new(col as Scalar, row as Scalar, 0.0));
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..size {
+            for col in 0..size {
+                if (3..7).contains(&row) && (3..7).contains(&col) {
+                    continue;
+                }
+                let i = (row * (size + 1) + col) as u32;
+                let j = i + size as u32 + 1;
+                triangles.push((i, i + 1, j + 1).into());
+                triangles.push((j + 1, j, i).into());
+            }
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let count = mesh.triangles().len();
+        let mut seed = 5u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as Scalar / (1 << 24) as Scalar
+        };
+        let distance = |triangle: Option<usize>, point: NavVec3| {
+            let point_on = mesh
+                .closest_point_on_triangle(triangle.unwrap(), point)
+                .unwrap();
+            point_on.distance(point)
+        };
+        for _ in 0..500 {
+            // points on, above and off nav mesh, including ones inside the hole.
+            let point = NavVec3::new(random() * 12.0 - 1.0, random() * 12.0 - 1.0, random() * 0.5);
+            let hint = (random() * count as Scalar) as usize % count;
+            let expected = mesh.find_closest_triangle(point, NavQuery::Accuracy);
+            let located = mesh.locate_from_hint(point, hint);
+            assert!((distance(located, point) - distance(expected, point)).abs() < 1.0e-4);
+        }
+        let point = NavVec3::new(5.0, 2.5, 0.0);
+        assert_eq!(
+            mesh.locate_from_hint(point, count),
+            mesh.find_closest_triangle(point, NavQuery::Accuracy)
+        );
+        // walk around the hole.
+        let from = mesh
+            .find_closest_triangle((5.0, 2.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let point = NavVec3::new(5.2, 7.5, 0.0);
+        let located = mesh.locate_from_hint(point, from).unwrap();
+        assert_eq!(Some(located), mesh.find_triangle_containing(point, 0.1));
+    }
+
+    #[test]
+    fn test_crowd() {
+        let mesh = NavMesh::new(
+            vec![
+                [-10.0, -10.0, 0.0].into(),
+                [10.0, -10.0, 0.0].into(),
+                [10.0, 10.0, 0.0].into(),
+                [-10.0, 10.0, 0.0].into(),
+            ],
+            vec![[0, 1, 2].into(), [2, 3, 0].into()],
+        )
+        .unwrap();
+        let mut crowd = NavCrowd::new();
+        let a = crowd.add_agent(NavCrowdAgent::new((-5.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0));
+        let b = crowd.add_agent(NavCrowdAgent::new((5.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0));
+        assert!(crowd.set_target(a, (5.0, 0.0, 0.0).into()));
+        assert!(crowd.set_target(b, (-5.0, 0.0, 0.0).into()));
+        let mut min_distance = Scalar::MAX;
+        for _ in 0..600 {
+            crowd.update(&mesh, 1.0 / 30.0);
+            let pa = crowd.agent(a).unwrap().position;
+            let pb = crowd.agent(b).unwrap().position;
+            min_distance = min_distance.min((pa - pb).magnitude());
+        }
+        assert!(min_distance > 0.9);
+        assert!(crowd.agent(a).unwrap().has_arrived());
+        assert!(crowd.agent(b).unwrap().has_arrived());
+        let agent = crowd.agent(a).unwrap();
+        assert!(agent.velocity().magnitude() <= agent.max_speed + 1.0e-4);
+        // agents keep facing the way they walked in.
+        assert!(agent.orientation().forward().x > 0.5);
+        assert!(crowd.agent(b).unwrap().orientation().forward().x < -0.5);
+    }
+
+    #[test]
+    fn test_crowd_avoidance_params() {
+        let mesh = NavMesh::new(
+            vec![
+                [-10.0, -10.0, 0.0].into(),
+                [10.0, -10.0, 0.0].into(),
+                [10.0, 10.0, 0.0].into(),
+                [-10.0, 10.0, 0.0].into(),
+            ],
+            vec![[0, 1, 2].into(), [2, 3, 0].into()],
+        )
+        .unwrap();
+        let mut crowd = NavCrowd::new();
+        let mut agent = NavCrowdAgent::new((-2.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0);
+        agent.avoidance.record_samples = true;
+        let a = crowd.add_agent(agent);
+        let b = crowd.add_agent(NavCrowdAgent::new((2.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0));
+        assert!(crowd.set_target(a, (5.0, 0.0, 0.0).into()));
+        assert!(crowd.set_target(b, (-5.0, 0.0, 0.0).into()));
+        crowd.update(&mesh, 1.0 / 30.0);
+        let samples = crowd.agent(a).unwrap().avoidance_samples();
+        assert_eq!(samples.len(), 26);
+        assert_eq!(samples.iter().filter(|sample| sample.selected).count(), 1);
+        let selected = samples.iter().find(|sample| sample.selected).unwrap();
+        assert!(samples
+            .iter()
+            .all(|sample| sample.penalty >= selected.penalty));
+        let direction = crowd.agent(a).unwrap().velocity().normalize();
+        assert!(direction.dot(selected.velocity.normalize()) > 0.999);
+        assert!(samples
+            .iter()
+            .all(|sample| sample.time_to_impact <= 2.5 + 1.0e-4));
+        assert!(crowd.agent(b).unwrap().avoidance_samples().is_empty());
+
+        // agents out of neighbor distance are not avoided.
+        for id in [a, b] {
+            crowd.agent_mut(id).unwrap().avoidance.neighbor_distance = 1.0;
+        }
+        crowd.update(&mesh, 1.0 / 30.0);
+        assert!(crowd.agent(a).unwrap().avoidance_samples().is_empty());
+
+        crowd.agent_mut(a).unwrap().avoidance.neighbor_distance = 20.0;
+        crowd.agent_mut(a).unwrap().avoidance.max_neighbors = 0;
+        crowd.update(&mesh, 1.0 / 30.0);
+        assert!(crowd.agent(a).unwrap().avoidance_samples().is_empty());
+    }
+
+    #[test]
+    fn test_crowd_priority() {
+        let mesh = NavMesh::new(
+            vec![
+                [-10.0, -10.0, 0.0].into(),
+                [10.0, -10.0, 0.0].into(),
+                [10.0, 10.0, 0.0].into(),
+                [-10.0, 10.0, 0.0].into(),
+            ],
+            vec![[0, 1, 2].into(), [2, 3, 0].into()],
+        )
+        .unwrap();
+        let mut crowd = NavCrowd::new();
+        let mut agent = NavCrowdAgent::new((-5.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0);
+        agent.priority = 1;
+        let a = crowd.add_agent(agent);
+        let b = crowd.add_agent(NavCrowdAgent::new((5.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0));
+        assert!(crowd.set_target(a, (5.0, 0.0, 0.0).into()));
+        assert!(crowd.set_target(b, (-5.0, 0.0, 0.0).into()));
+        let mut min_distance = Scalar::MAX;
+        let mut max_offset = (0.0 as Scalar, 0.0 as Scalar);
+        for _ in 0..600 {
+            crowd.update(&mesh, 1.0 / 30.0);
+            let pa = crowd.agent(a).unwrap().position;
+            let pb = crowd.agent(b).unwrap().position;
+            min_distance = min_distance.min((pa - pb).magnitude());
+            max_offset = (max_offset.0.max(pa.y.abs()), max_offset.1.max(pb.y.abs()));
+        }
+        assert!(min_distance > 0.9);
+        assert!(crowd.agent(a).unwrap().has_arrived());
+        assert!(crowd.agent(b).unwrap().has_arrived());
+        // only low priority agent moves out of the way.
+        assert!(max_offset.0 < 1.0e-4);
+        assert!(max_offset.1 > 0.5);
+
+        // idle agent blocking corridor gets pushed through.
+        let mesh = NavMesh::new(
+            vec![
+                [-5.0, -0.6, 0.0].into(),
+                [5.0, -0.6, 0.0].into(),
+                [5.0, 0.6, 0.0].into(),
+                [-5.0, 0.6, 0.0].into(),
+            ],
+            vec![[0, 1, 2].into(), [2, 3, 0].into()],
+        )
+        .unwrap();
+        let mut crowd = NavCrowd::new();
+        crowd.set_push_idle_agents(true);
+        let a = crowd.add_agent(NavCrowdAgent::new((-4.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0));
+        let b = crowd.add_agent(NavCrowdAgent::new((0.0, 0.0, 0.0).into(), 0.5, 2.0, 8.0));
+        assert!(crowd.agent(b).unwrap().is_idle());
+        assert!(crowd.set_target(a, (2.5, 0.0, 0.0).into()));
+        let start = crowd.agent(b).unwrap().position;
+        // moving agent is not slowed down much by idle one.
+        for _ in 0..150 {
+            crowd.update(&mesh, 1.0 / 30.0);
+        }
+        assert!(crowd.agent(a).unwrap().has_arrived());
+        assert!((crowd.agent(b).unwrap().position - start).magnitude() > 1.0);
+    }
+
+    #[test]
+    fn test_crowd_time_sliced() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (20.0, 0.0, 0.0).into(),
+            (20.0, 20.0, 0.0).into(),
+            (0.0, 20.0, 0.0).into(),
+        ];
+        let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let mut crowd = NavCrowd::new();
+        crowd.set_timestep(NavCrowdTimestep {
+            step: 0.1,
+            max_steps: 3,
+            agents_per_step: Some(3),
+        });
+        let ids = (0..8)
+            .map(|index| {
+                let y = 1.0 + index as Scalar * 2.0;
+                let id =
+                    crowd.add_agent(NavCrowdAgent::new((1.0, y, 0.0).into(), 0.25, 1.0, 100.0));
+                crowd.set_target(id, (19.0, y, 0.0).into());
+                id
+            })
+            .collect::<Vec<_>>();
+        let removed = crowd.add_agent(NavCrowdAgent::new((1.0, 19.0, 0.0).into(), 0.25, 1.0, 1.0));
+        assert!(crowd.remove_agent(removed).is_some());
+        let steered = |crowd: &NavCrowd| {
+            ids.iter()
+                .filter(|id| crowd.agent(**id).unwrap().desired_velocity().x > 0.0)
+                .count()
+        };
+
+        // agents take turns in getting steered.
+        assert_eq!(crowd.update_fixed(&mesh, 0.1), 1);
+        assert_eq!(steered(&crowd), 3);
+        assert_eq!(crowd.update_fixed(&mesh, 0.1), 1);
+        assert_eq!(steered(&crowd), 6);
+        assert_eq!(crowd.update_fixed(&mesh, 0.1), 1);
+        assert_eq!(steered(&crowd), 8);
+        // agents that were not steered keep moving.
+        let first = crowd.agent(ids[0]).unwrap();
+        assert!(first.position.x > first.previous_position().x);
+
+        // slow frame is limited to maximal number of steps.
+        assert_eq!(crowd.update_fixed(&mesh, 1.05), 3);
+        assert!(crowd.interpolation_factor() < 1.0);
+        for id in &ids {
+            let agent = crowd.agent(*id).unwrap();
+            assert!(agent.position.x > 1.0);
+            let position = agent.interpolated_position(1.0);
+            assert!(position.same_as(agent.position));
+            let position = agent.interpolated_position(0.0);
+            assert!(position.same_as(agent.previous_position()));
+        }
+        assert_eq!(crowd.update_fixed(&mesh, 0.0), 0);
+
+        // without slicing all agents move same way.
+        crowd.set_timestep(Default::default());
+        for _ in 0..1500 {
+            crowd.update_fixed(&mesh, 1.0 / 60.0);
+        }
+        for id in &ids {
+            assert!(crowd.agent(*id).unwrap().has_arrived());
+        }
+    }
+
+    #[test]
+    fn test_crowd_lod() {
+        let settings = NavAgentLodSettings::default();
+        assert_eq!(settings.lod(0.7), NavAgentLod::Full);
+        assert_eq!(settings.lod(0.3), NavAgentLod::Coarse);
+        assert_eq!(settings.lod(0.0), NavAgentLod::Offscreen);
+        // importance near threshold does not change level.
+        assert_eq!(
+            settings.next_lod(NavAgentLod::Coarse, 0.52),
+            NavAgentLod::Coarse
+        );
+        assert_eq!(
+            settings.next_lod(NavAgentLod::Full, 0.48),
+            NavAgentLod::Full
+        );
+        assert_eq!(
+            settings.next_lod(NavAgentLod::Coarse, 0.6),
+            NavAgentLod::Full
+        );
+        assert_eq!(
+            settings.next_lod(NavAgentLod::Full, 0.0),
+            NavAgentLod::Offscreen
+        );
+
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (20.0, 0.0, 0.0).into(),
+            (20.0, 20.0, 0.0).into(),
+            (0.0, 20.0, 0.0).into(),
+        ];
+        let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let mut crowd = NavCrowd::new();
+        let walker = crowd.add_agent(NavCrowdAgent::new((1.0, 2.0, 0.0).into(), 0.5, 1.0, 0.5));
+        let runner = crowd.add_agent(NavCrowdAgent::new((1.0, 6.0, 0.0).into(), 0.5, 1.0, 0.5));
+        let blocker = crowd.add_agent(NavCrowdAgent::new((10.0, 10.0, 0.0).into(), 0.5, 1.0, 1.0));
+        let pushed = crowd.add_agent(NavCrowdAgent::new((10.5, 10.0, 0.0).into(), 0.5, 1.0, 1.0));
+        crowd.set_target(walker, (19.0, 2.0, 0.0).into());
+        crowd.set_target(runner, (19.0, 6.0, 0.0).into());
+        let importance = [(walker, 1.0), (runner, 0.0), (blocker, 0.3), (pushed, 1.0)]
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>();
+        assert_eq!(crowd.update_lods(&settings, |id, _| importance[&id]), 2);
+        assert_eq!(crowd.agent(runner).unwrap().lod, NavAgentLod::Offscreen);
+        assert_eq!(crowd.agent(blocker).unwrap().lod, NavAgentLod::Coarse);
+        assert_eq!(crowd.update_lods(&settings, |id, _| importance[&id]), 0);
+
+        crowd.update(&mesh, 1.0);
+        // offscreen agent moves exactly along path with full speed right away.
+        let agent = crowd.agent(runner).unwrap();
+        assert!(agent.position.same_as((2.0, 6.0, 0.0).into()));
+        assert!(agent.velocity().same_as((1.0, 0.0, 0.0).into()));
+        assert!(crowd.agent(walker).unwrap().position.x < 2.0);
+        // coarse agent does not get pushed by collision, but pushes other agents.
+        assert!(crowd
+            .agent(blocker)
+            .unwrap()
+            .position
+            .same_as((10.0, 10.0, 0.0).into()));
+        assert!(crowd.agent(pushed).unwrap().position.x > 10.9);
+
+        for _ in 0..20 {
+            crowd.update(&mesh, 1.0);
+        }
+        assert!(crowd.agent(runner).unwrap().has_arrived());
+        assert!(crowd
+            .agent(runner)
+            .unwrap()
+            .position
+            .same_as((19.0, 6.0, 0.0).into()));
+    }
+
+    #[test]
+    fn test_runtime_state() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (20.0, 0.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (20.0, 10.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let source = NavMesh::new(vertices, triangles).unwrap();
+        let mut mesh = source.clone();
+        mesh.add_gate("door", vec![2], true).unwrap();
+        let obstacle = mesh.add_obstacle(NavObstacle::aabb(
+            (14.0, 1.0, -1.0).into(),
+            (16.0, 3.0, 1.0).into(),
+        ));
+        mesh.set_area_cost(1, 4.0);
+        mesh.set_vertex_attribute("danger", vec![1.0; 6]).unwrap();
+        let mut crowd = NavCrowd::new();
+        let ids = (0..6)
+            .map(|index| {
+                let y = 1.0 + index as Scalar * 1.5;
+                let id = crowd.add_agent(NavCrowdAgent::new((1.0, y, 0.0).into(), 0.5, 2.0, 4.0));
+                crowd.set_target(id, (19.0, 9.0 - y, 0.0).into());
+                id
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..30 {
+            crowd.update(&mesh, 1.0 / 30.0);
+        }
+        let state = NavRuntimeState::capture(&mesh, [&crowd]);
+        assert_eq!(state.obstacles.len(), 1);
+        assert_eq!(state.gates, vec![("door".to_owned(), true)]);
+        for _ in 0..60 {
+            crowd.update(&mesh, 1.0 / 30.0);
+        }
+
+        // restored state replays the same way.
+        let mut restored_mesh = source.clone();
+        restored_mesh.add_gate("door", vec![2], false).unwrap();
+        let mut restored = state.restore(&mut restored_mesh).unwrap().remove(0);
+        assert_eq!(restored_mesh.is_gate_open("door"), Some(true));
+        assert_eq!(
+            restored_mesh.obstacle_triangles(obstacle),
+            mesh.obstacle_triangles(obstacle)
+        );
+        assert_eq!(restored_mesh.areas()[1].cost, 4.0);
+        assert_eq!(
+            restored_mesh.vertex_attribute("danger"),
+            Some(&[1.0; 6][..])
+        );
+        assert_eq!(restored.id(), crowd.id());
+        for _ in 0..60 {
+            restored.update(&restored_mesh, 1.0 / 30.0);
+        }
+        for id in &ids {
+            let a = crowd.agent(*id).unwrap();
+            let b = restored.agent(*id).unwrap();
+            assert_eq!(a.position, b.position);
+            assert_eq!(a.velocity(), b.velocity());
+            assert_eq!(a.path(), b.path());
+        }
+
+        // restoring replaces obstacles and rejects nav mesh with other geometry.
+        restored_mesh.add_obstacle(NavObstacle::aabb(
+            (4.0, 4.0, -1.0).into(),
+            (6.0, 6.0, 1.0).into(),
+        ));
+        state.restore(&mut restored_mesh).unwrap();
+        assert_eq!(
+            restored_mesh.obstacles().collect::<Vec<_>>(),
+            vec![obstacle]
+        );
+        let mut other = NavMesh::new(
+            vec![
+                (0.0, 0.0, 0.0).into(),
+                (1.0, 0.0, 0.0).into(),
+                (0.0, 1.0, 0.0).into(),
+            ],
+            vec![(0, 1, 2).into()],
+        )
+        .unwrap();
+        assert!(matches!(
+            state.restore(&mut other),
+            Err(Error::RuntimeStateDoesNotMatchNavMesh(4, 1))
+        ));
+        assert_eq!(other.obstacles().count(), 0);
+    }
+
+    #[test]
+    fn test_crowd_spatial_index() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (20.0, 0.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (20.0, 10.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let mut crowd = NavCrowd::new();
+        let a = crowd.add_agent(NavCrowdAgent::new((8.0, 2.0, 0.0).into(), 0.5, 2.0, 8.0));
+        let b = crowd.add_agent(NavCrowdAgent::new((2.0, 8.0, 0.0).into(), 0.5, 2.0, 8.0));
+        let c = crowd.add_agent(NavCrowdAgent::new((18.0, 2.0, 0.0).into(), 0.5, 2.0, 8.0));
+        let d = crowd.add_agent(NavCrowdAgent::new((12.0, 8.0, 0.0).into(), 0.5, 2.0, 8.0));
+        assert!(crowd.agents_in_triangle(0).is_empty());
+        crowd.update(&mesh, 1.0 / 30.0);
+        assert_eq!(crowd.agent(a).unwrap().triangle(), Some(0));
+        assert_eq!(crowd.agents_in_triangle(0), &[a]);
+        assert_eq!(crowd.agents_in_triangle(1), &[b]);
+        assert_eq!(crowd.agents_in_triangle(2), &[c]);
+        assert_eq!(crowd.agents_in_triangle(3), &[d]);
+
+        let mut found = crowd.agents_in_radius(&mesh, (10.0, 2.0, 0.0).into(), 2.5);
+        assert_eq!(found, vec![a]);
+        found = crowd.agents_in_radius(&mesh, (15.0, 5.0, 0.0).into(), 5.0);
+        found.sort();
+        let mut expected = vec![c, d];
+        expected.sort();
+        assert_eq!(found, expected);
+        assert!(crowd
+            .agents_in_radius(&mesh, (10.0, 5.0, 5.0).into(), 4.0)
+            .is_empty());
+
+        assert_eq!(crowd.nearest_agent(&mesh, (0.0, 10.0, 0.0).into()), Some(b));
+        assert_eq!(crowd.nearest_agent(&mesh, (30.0, 0.0, 0.0).into()), Some(c));
+        assert_eq!(
+            crowd.nearest_agent_custom(&mesh, (18.0, 2.0, 0.0).into(), |id, _| id != c),
+            Some(d)
+        );
+
+        crowd.remove_agent(c);
+        assert!(crowd.agents_in_triangle(2).is_empty());
+        assert_eq!(crowd.nearest_agent(&mesh, (30.0, 0.0, 0.0).into()), Some(d));
+        crowd.remove_agent(a);
+        crowd.remove_agent(b);
+        crowd.remove_agent(d);
+        assert_eq!(crowd.nearest_agent(&mesh, (30.0, 0.0, 0.0).into()), None);
+    }
+
+    #[test]
+    fn test_baker() {
+        let mut vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+        ];
+        let mut triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+        // low box top hanging over the floor: too low to walk under, too high to climb on.
+        let offset = vertices.len() as u32;
+        vertices.extend_from_slice(&[
+            (4.5, 0.0, 1.5).into(),
+            (5.5, 0.0, 1.5).into(),
+            (5.5, 8.0, 1.5).into(),
+            (4.5, 8.0, 1.5).into(),
+        ]);
+        triangles.push((offset, offset + 1, offset + 2).into());
+        triangles.push((offset + 2, offset + 3, offset).into());
+        // tiny platform that should get removed as too small region.
+        let offset = vertices.len() as u32;
+        vertices.extend_from_slice(&[
+            (8.0, 8.0, 5.0).into(),
+            (8.3, 8.0, 5.0).into(),
+            (8.3, 8.3, 5.0).into(),
+        ]);
+        triangles.push((offset, offset + 1, offset + 2).into());
+        let settings = NavMeshBakeSettings::default();
+        let mesh = NavMeshBaker::new(settings)
+            .bake(&vertices, &triangles)
+            .unwrap();
+        for vertice in mesh.vertices() {
+            assert!(vertice.z < 2.0);
+        }
+        let point = mesh
+            .closest_point((2.0, 2.0, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        assert!(point.z.abs() < 1.0e-4);
+        // floor below the box has not enough free space for agent.
+        let point = mesh
+            .closest_point((5.0, 4.0, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        assert!((point.x - 5.0).abs() > 0.4);
+        let point = mesh
+            .closest_point((5.0, 4.0, 1.5).into(), NavQuery::Accuracy)
+            .unwrap();
+        assert!((point.z - 1.5).abs() <= settings.cell_height);
+        let path = mesh
+            .find_path(
+                (2.0, 2.0, 0.0).into(),
+                (8.0, 2.0, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert!(NavMesh::path_length(&path) > 12.0);
+        assert!(matches!(
+            NavMeshBaker::new(settings).bake(&[], &[]),
+            Err(Error::NoWalkableSurface)
+        ));
+    }
+
+    #[test]
+    fn test_path_with_radius() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+            (1.0, 2.0, 0.0).into(), // 6
+            (2.0, 2.0, 0.0).into(), // 7
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+            (4, 5, 7).into(), // 4
+            (7, 6, 4).into(), // 5
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = (0.5, 0.5, 0.0).into();
+        let to = (1.5, 1.5, 0.0).into();
+        let corner = NavVec3::new(1.0, 1.0, 0.0);
+        let path = mesh
+            .find_path_with_radius(from, to, NavQuery::Accuracy, 0.0)
+            .unwrap();
+        assert!(path.iter().any(|p| p.same_as(corner)));
+        let path = mesh
+            .find_path_with_radius(from, to, NavQuery::Accuracy, 0.25)
+            .unwrap();
+        assert!(path.len() > 2);
+        for point in &path[1..path.len() - 1] {
+            assert!((*point - corner).magnitude() >= 0.25 - 1.0e-4);
+        }
+        assert!(path[0].same_as(from));
+        assert!(path[path.len() - 1].same_as(to));
+        assert!(mesh
+            .find_path_with_radius(from, to, NavQuery::Accuracy, 0.6)
+            .is_none());
+    }
+
+    #[test]
+    fn test_area_filter() {
+        let mut vertices = vec![];
+        for row in 0..3 {
+            for col in 0..3 {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..2 {
+            for col in 0..2 {
+                let a = row * 3 + col;
+                triangles.push((a, a + 1, a + 4).into());
+                triangles.push((a + 4, a + 3, a).into());
+            }
+        }
+        const MUD: u8 = 1;
+        const WATER: u16 = 1 << 1;
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_area_type(2, MUD);
+        mesh.set_area_type(3, MUD);
+        let mut filter = NavAreaFilter::default();
+        assert_eq!(filter.set_cost(MUD, 10.0), 1.0);
+        let (path, _) = mesh.find_path_triangles_filtered(0, 7, &filter).unwrap();
+        assert!(!path.contains(&2) && !path.contains(&3));
+        assert_eq!(mesh.set_area_flags(4, WATER), NavArea::DEFAULT_FLAGS);
+        mesh.set_area_flags(5, WATER | NavArea::DEFAULT_FLAGS);
+        filter.exclude_flags = WATER;
+        let (path, _) = mesh.find_path_triangles_filtered(0, 7, &filter).unwrap();
+        assert!(!path.contains(&4) && !path.contains(&5));
+        assert!(path.contains(&2) || path.contains(&3));
+        assert!(mesh.find_path_triangles_filtered(0, 5, &filter).is_none());
+        filter.exclude_flags = 0;
+        filter.include_flags = WATER;
+        assert!(mesh.find_path_triangles_filtered(4, 5, &filter).is_some());
+        assert!(mesh.find_path_triangles_filtered(0, 7, &filter).is_none());
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        let size = 16;
+        let mut vertices = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..size {
+            for col in 0..size {
+                let a = row * (size + 1) + col;
+                triangles.push((a, a + 1, a + size + 2).into());
+                triangles.push((a + size + 2, a + size + 1, a).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let hierarchy = NavMeshHierarchy::new(&mesh, 4.0);
+        assert!(hierarchy.clusters_count() >= 16);
+        let from = 0;
+        let to = mesh.triangles().len() - 1;
+        let (optimal, optimal_cost) = mesh.find_path_triangles(from, to).unwrap();
+        for refine in [false, true] {
+            let (path, cost) = hierarchy
+                .find_path_triangles(&mesh, from, to, refine)
+                .unwrap();
+            assert_eq!(path[0], from);
+            assert_eq!(path[path.len() - 1], to);
+            for pair in path.windows(2) {
+                assert!(mesh.triangle_neighbors(pair[0]).any(|t| t == pair[1]));
+            }
+            assert!(cost >= optimal_cost - 1.0e-4);
+            assert!(cost <= optimal_cost * 1.5);
+        }
+        assert!(!optimal.is_empty());
+        let path = hierarchy
+            .find_path(
+                &mesh,
+                (0.5, 0.5, 0.0).into(),
+                (15.5, 15.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+                true,
+            )
+            .unwrap();
+        assert!((NavMesh::path_length(&path) - 15.0 * (2.0 as Scalar).sqrt()).abs() < 1.0e-2);
+        mesh.add_obstacle(NavObstacle::aabb(
+            (7.1, -1.0, -1.0).into(),
+            (7.9, 15.0, 1.0).into(),
+        ));
+        let (path, _) = hierarchy
+            .find_path_triangles(&mesh, from, to, false)
+            .unwrap();
+        assert!(path.iter().all(|t| !mesh.is_triangle_blocked(*t)));
+        let other = NavMesh::new(mesh.vertices().to_vec(), mesh.triangles().to_vec()).unwrap();
+        assert!(hierarchy
+            .find_path_triangles(&other, from, to, false)
+            .is_none());
+    }
+
+    #[test]
+    fn test_path_corridor() {
+        let size = 8;
+        let mut vertices = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..size {
+            for col in 0..size {
+                let a = row * (size + 1) + col;
+                triangles.push((a, a + 1, a + size + 2).into());
+                triangles.push((a + size + 2, a + size + 1, a).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let mut corridor = NavPathCorridor::plan(
+            &mesh,
+            (0.5, 4.5, 0.0).into(),
+            (7.5, 4.5, 0.0).into(),
+            NavQuery::Accuracy,
+        )
+        .unwrap();
+        let corners = corridor.corners(&mesh);
+        assert!((NavMesh::path_length(&corners) - 7.0).abs() < 1.0e-4);
+        let count = corridor.triangles().len();
+        let position = corridor.move_position(&mesh, (3.5, 4.5, 0.0).into());
+        assert!(position.same_as((3.5, 4.5, 0.0).into()));
+        assert!(corridor.triangles().len() < count);
+        // drifting off corridor start extends it with neighbor triangle.
+        let first = corridor.first_triangle().unwrap();
+        let position = corridor.move_position(&mesh, (3.5, 3.2, 0.0).into());
+        assert!(corridor.triangles().contains(&first));
+        assert!(mesh
+            .closest_point_on_triangle(corridor.first_triangle().unwrap(), position)
+            .unwrap()
+            .same_as(position));
+        let target = corridor.move_target(&mesh, (7.5, 5.5, 0.0).into());
+        assert!(corridor.corners(&mesh).last().unwrap().same_as(target));
+        let next = corridor.corners(&mesh)[1];
+        corridor.optimize_visibility(&mesh, next, 10.0);
+        for pair in corridor.triangles().windows(2) {
+            assert!(mesh.triangle_neighbors(pair[0]).any(|t| t == pair[1]));
+        }
+        assert!(corridor.is_valid(&mesh, usize::MAX));
+        mesh.add_obstacle(NavObstacle::aabb(
+            (5.1, 3.1, -1.0).into(),
+            (5.9, 6.9, 1.0).into(),
+        ));
+        assert!(!corridor.is_valid(&mesh, usize::MAX));
+        assert!(corridor.replan(&mesh));
+        assert!(corridor.is_valid(&mesh, usize::MAX));
+        assert!(mesh
+            .closest_point_on_triangle(corridor.first_triangle().unwrap(), corridor.position())
+            .unwrap()
+            .same_as(corridor.position()));
+        for pair in corridor.triangles().windows(2) {
+            assert!(mesh.triangle_neighbors(pair[0]).any(|t| t == pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_mesh_raycast() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+            (1.0, 2.0, 0.0).into(), // 6
+            (2.0, 2.0, 0.0).into(), // 7
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+            (4, 5, 7).into(), // 4
+            (7, 6, 4).into(), // 5
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let result = mesh
+            .raycast(
+                (0.2, 0.5, 0.0).into(),
+                (1.5, 1.8, 0.0).into(),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        let hit = result.hit.unwrap();
+        assert!(hit.point.same_as((0.7, 1.0, 0.0).into()));
+        assert_eq!(hit.triangle, 1);
+        let (a, b) = hit.edge;
+        assert!((a.y - 1.0).abs() < 1.0e-6 && (b.y - 1.0).abs() < 1.0e-6);
+        let result = mesh
+            .raycast(
+                (0.8, 0.2, 0.0).into(),
+                (1.5, 1.8, 0.0).into(),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        assert!(result.hit.is_none());
+        assert_eq!(result.triangles[0], 0);
+        assert_eq!(*result.triangles.last().unwrap(), 5);
+        mesh.add_obstacle(NavObstacle::aabb(
+            (1.6, 0.1, -1.0).into(),
+            (1.9, 0.3, 1.0).into(),
+        ));
+        let result = mesh
+            .raycast(
+                (0.5, 0.5, 0.0).into(),
+                (1.9, 0.5, 0.0).into(),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        let hit = result.hit.unwrap();
+        assert!(hit.point.same_as((1.5, 0.5, 0.0).into()));
+        assert_eq!(hit.triangle, 3);
+    }
+
+    #[test]
+    fn test_point_queries() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        assert_eq!(
+            mesh.find_triangle_containing((0.75, 0.25, 0.1).into(), 0.2),
+            Some(0)
+        );
+        assert_eq!(
+            mesh.find_triangle_containing((0.75, 0.25, 0.5).into(), 0.2),
+            None
+        );
+        assert_eq!(
+            mesh.find_triangle_containing((1.9, 0.2, 0.9).into(), 0.2),
+            Some(2)
+        );
+        assert_eq!(
+            mesh.find_triangle_containing((-0.5, 0.5, 0.0).into(), 1.0),
+            None
+        );
+        let (index, point) = mesh
+            .sample_position_within_radius((-0.5, 0.5, 0.0).into(), 1.0)
+            .unwrap();
+        assert!(index == 1);
+        assert!(point.same_as((0.0, 0.5, 0.0).into()));
+        assert!(mesh
+            .sample_position_within_radius((-0.5, 0.5, 0.0).into(), 0.25)
+            .is_none());
+        mesh.rebuild_spatial_index();
+        assert_eq!(
+            mesh.find_triangle_containing((0.75, 0.25, 0.0).into(), 0.0),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_path_to_any() {
+        let size = 8;
+        let mut vertices = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..size {
+            for col in 0..size {
+                let a = row * (size + 1) + col;
+                triangles.push((a, a + 1, a + size + 2).into());
+                triangles.push((a + size + 2, a + size + 1, a).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.add_obstacle(NavObstacle::aabb(
+            (1.1, -1.0, -1.0).into(),
+            (1.9, 6.9, 1.0).into(),
+        ));
+        let from = (0.5, 0.5, 0.0).into();
+        let goals = [(2.5, 0.5, 0.0).into(), (0.5, 5.5, 0.0).into()];
+        let (goal, path) = mesh
+            .find_path_to_any(from, &goals, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_eq!(goal, 1);
+        assert!(path.last().unwrap().same_as(goals[1]));
+        // reaching the other goal requires going around the obstacle.
+        assert!(NavMesh::path_length(&path) < 8.0);
+        let (goal, _) = mesh
+            .find_path_to_any(from, &goals[..1], NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_eq!(goal, 0);
+        assert!(mesh
+            .find_path_to_any(from, &[], NavQuery::Accuracy, NavPathMode::Accuracy)
+            .is_none());
+    }
+
+    #[test]
+    fn test_path_smoothing() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+            (1.0, 2.0, 0.0).into(), // 6
+            (2.0, 2.0, 0.0).into(), // 7
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+            (4, 5, 7).into(), // 4
+            (7, 6, 4).into(), // 5
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let path = [
+            (0.3, 0.5, 0.0).into(),
+            (1.5, 0.5, 0.0).into(),
+            (1.5, 1.7, 0.0).into(),
+        ];
+        for smoothing in [NavPathSmoothing::CatmullRom(6), NavPathSmoothing::Bezier(6)] {
+            let smoothed = mesh.smooth_path(&path, smoothing);
+            assert!(smoothed.len() > path.len());
+            assert!(smoothed[0].same_as(path[0]));
+            assert!(smoothed[smoothed.len() - 1].same_as(path[2]));
+            for pair in smoothed.windows(2) {
+                let result = mesh.raycast(pair[0], pair[1], NavQuery::Accuracy).unwrap();
+                assert!(result.hit.is_none());
+            }
+        }
+        // curve around the inner corner would leave nav mesh, so polyline is kept.
+        let path = [
+            (0.5, 0.9, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (1.1, 1.5, 0.0).into(),
+        ];
+        let smoothed = mesh.smooth_path(&path, NavPathSmoothing::CatmullRom(6));
+        assert!(smoothed.iter().any(|p| p.same_as(path[1])));
+        let path = mesh
+            .find_path_smoothed(
+                (0.3, 0.5, 0.0).into(),
+                (1.5, 1.7, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+                NavPathSmoothing::None,
+            )
+            .unwrap();
+        assert_eq!(
+            path,
+            mesh.find_path(
+                (0.3, 0.5, 0.0).into(),
+                (1.5, 1.7, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_binary() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_area_cost(3, 2.0);
+        mesh.set_area_type(2, 4);
+        mesh.set_area_flags(1, 2);
+        let bytes = mesh.to_bytes();
+        let loaded = NavMesh::from_bytes(&bytes).unwrap();
+        assert_ne!(loaded.id(), mesh.id());
+        assert_eq!(loaded.vertices(), mesh.vertices());
+        for (a, b) in loaded.triangles().iter().zip(mesh.triangles().iter()) {
+            assert_eq!((a.first, a.second, a.third), (b.first, b.second, b.third));
+        }
+        for (a, b) in loaded.areas().iter().zip(mesh.areas().iter()) {
+            assert_eq!(a.triangle, b.triangle);
+            assert_eq!(a.cost, b.cost);
+            assert_eq!(a.area_type, b.area_type);
+            assert_eq!(a.flags, b.flags);
+            assert!(a.center.same_as(b.center));
+        }
+        assert_eq!(
+            loaded.find_path_triangles(1, 2),
+            mesh.find_path_triangles(1, 2)
+        );
+        assert_eq!(
+            loaded.find_closest_triangle((1.5, 0.25, 0.5).into(), NavQuery::Accuracy),
+            Some(2)
+        );
+        assert_eq!(loaded.to_bytes().len(), bytes.len());
+
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        assert!(NavMesh::from_bytes(&corrupted).is_err());
+        assert!(NavMesh::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(NavMesh::from_bytes(&bytes[..10]).is_err());
+        let mut size = bytes.clone();
+        size[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(NavMesh::from_bytes(&size).is_err());
+        let mut version = bytes;
+        version[4] = 99;
+        assert!(NavMesh::from_bytes(&version).is_err());
+    }
+
+    #[cfg(feature = "import")]
+    #[test]
+    fn test_net_import() {
+        let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:y="http://www.yworks.com/xml/graphml">
+          <key id="d0" for="node" yfiles.type="nodegraphics"/>
+          <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+          <graph edgedefault="directed">
+            <node id="n0"><data key="d0"><y:ShapeNode><y:Geometry x="-1" y="-1" width="2" height="2"/></y:ShapeNode></data></node>
+            <node id="n1"><data key="d0"><y:ShapeNode><y:Geometry x="9" y="-1" width="2" height="2"/></y:ShapeNode></data></node>
+            <edge source="n0" target="n1"><data key="d1">2.5</data></edge>
+            <edge source="n1" target="n0"/>
+          </graph>
+        </graphml>"#;
+        let settings = NavNetImportSettings {
+            scale: 0.5,
+            ..Default::default()
+        };
+        let (net, names) = NavNetImporter::new(settings)
+            .import_graphml_str(data)
+            .unwrap();
+        assert_eq!(names, vec!["n0", "n1"]);
+        assert_eq!(
+            net.vertices(),
+            &[(0.0, 0.0, 0.0).into(), (5.0, 0.0, 0.0).into()]
+        );
+        assert_eq!(net.connection_cost(0), Some(2.5));
+        assert_eq!(net.connection_cost(1), Some(5.0));
+
+        let settings = NavNetImportSettings {
+            y_up_to_z_up: true,
+            ..Default::default()
+        };
+        let (net, _) = NavNetImporter::new(settings)
+            .import_json_str(r#"{ "nodes": [{ "id": 1, "position": [1, 2, 3] }], "edges": [] }"#)
+            .unwrap();
+        assert_eq!(net.vertices(), &[(1.0, -3.0, 2.0).into()]);
+
+        let importer = NavNetImporter::default();
+        for data in [
+            "",
+            r#"{ "nodes": [] }"#,
+            r#"{ "nodes": [{ "id": "a" }], "edges": [] }"#,
+            r#"{ "nodes": [{ "id": "a", "x": 0, "y": 0 }], "edges": [["a", "b"]] }"#,
+            r#"{ "nodes": [{ "id": "a", "x": 0, "y": 0 }, { "id": "a", "x": 1, "y": 0 }], "edges": [] }"#,
+            r#"{ "nodes": [{ "id": "a", "x": "left", "y": 0 }], "edges": [] }"#,
+        ] {
+            assert!(matches!(
+                importer.import_json_str(data),
+                Err(Error::CouldNotImportNavNet(_))
+            ));
+        }
+        assert!(matches!(
+            importer.import_graphml_str("<graphml><graph><node id=\"a\"/></graph></graphml>"),
+            Err(Error::CouldNotImportNavNet(_))
+        ));
+        assert!(matches!(
+            importer.import_json("missing.json"),
+            Err(Error::CouldNotImportNavNet(_))
+        ));
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn test_mint() {
+        let v = NavVec3::new(0.0, 1.0, -1.0);
+        let _f = mint::Vector3::<Scalar>::from(v);
+        let _t: mint::Vector3<Scalar> = v.into();
+
+        let v = mint::Vector3::<Scalar> {
+            x: 0.0,
+            y: 1.0,
+            z: -1.0,
+        };
+        let _f = NavVec3::from(v);
+        let _t: NavVec3 = v.into();
+
+        let v = NavVec3::new(0.0, 1.0, -1.0);
+        let p: mint::Point3<Scalar> = v.into();
+        assert_eq!(NavVec3::from(p), v);
+        let points = vec![p, mint::Point3::from([2.0, 3.0, 4.0])];
+        let vectors = NavVec3::from_mint_slice(&points);
+        assert_eq!(vectors, &[v, NavVec3::new(2.0, 3.0, 4.0)]);
+        assert_eq!(NavVec3::as_mint_slice(vectors), points.as_slice());
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_glam() {
+        let v = NavVec3::new(0.0, 1.0, -1.0);
+        assert_eq!(NavVec3::from(glam::Vec3::from(v)), v);
+        assert_eq!(NavVec3::from(glam::DVec3::from(v)), v);
+        assert_eq!(glam::Vec3::from(v), glam::Vec3::new(0.0, 1.0, -1.0));
+
+        let points = vec![
+            NavGlamVec3::new(0.0, 1.0, -1.0),
+            NavGlamVec3::new(2.0, 3.0, 4.0),
+        ];
+        let vectors = NavVec3::from_glam_slice(&points);
+        assert_eq!(vectors, &[v, NavVec3::new(2.0, 3.0, 4.0)]);
+        assert_eq!(NavVec3::as_glam_slice(vectors), points.as_slice());
+    }
+
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn test_bevy() {
+        use bevy::{
+            app::App,
+            math::Vec3,
+            time::{TimePlugin, TimeUpdateStrategy},
+            transform::components::Transform,
+        };
+        use std::time::Duration;
+
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, NavMeshPlugin))
+            .insert_resource(NavMeshResource(mesh))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )));
+        let target = Vec3::new(1.5, 0.25, 0.5);
+        let agent = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(0.0, 1.0, 0.0),
+                NavAgent::new(1.0),
+                NavTarget(target),
+            ))
+            .id();
+        app.update();
+        assert!(!app.world().get::<NavAgent>(agent).unwrap().is_idle());
+        for _ in 0..20 {
+            app.update();
+        }
+        let translation = app.world().get::<Transform>(agent).unwrap().translation;
+        assert!(NavVec3::from(translation).same_as(target.into()));
+        assert!(app.world().get::<NavAgent>(agent).unwrap().is_idle());
+
+        let v = NavVec3::new(0.0, 1.0, -1.0);
+        assert_eq!(NavVec3::from(Vec3::from(v)), v);
+    }
+
+    #[test]
+    fn test_net_connection_cost() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (0.0, 1.0, 0.0).into(),
+        ];
+        let connections = vec![
+            NavConnection(0, 1),
+            NavConnection(1, 2),
+            NavConnection(2, 3),
+            NavConnection(3, 0),
+        ];
+        let mut net = NavNet::new(vertices, connections).unwrap();
+        let from = (0.0, 0.1, 0.0).into();
+        let to = (1.0, 0.9, 0.0).into();
+        assert_eq!(net.connection_cost(0), Some(1.0));
+        assert_eq!(net.connection_cost(4), None);
+        assert_eq!(net.set_connection_cost(3, Some(10.0)), Some(1.0));
+        assert_eq!(net.set_connection_cost(2, Some(10.0)), Some(1.0));
+        let path = net.find_path(from, to).unwrap();
+        assert!(path.contains(&(1.0, 0.0, 0.0).into()));
+        net.set_connection_cost(3, None);
+        net.set_connection_cost(2, None);
+        net.set_connection_cost(0, Some(10.0));
+        net.set_connection_cost(1, Some(10.0));
+        let path = net.find_path(from, to).unwrap();
+        assert!(path.contains(&(0.0, 1.0, 0.0).into()));
+        assert!(!path.contains(&(1.0, 0.0, 0.0).into()));
+        let scaled = net.scale((2.0, 2.0, 2.0).into(), None).unwrap();
+        assert_eq!(scaled.connection_cost(0), Some(10.0));
+        assert_eq!(scaled.connection_cost(2), Some(4.0));
+        net.remove_connection(0);
+        assert_eq!(net.connections()[0], NavConnection(3, 0));
+        assert_eq!(net.connection_cost(0), Some(1.0));
+        assert_eq!(net.connection_cost(1), Some(10.0));
+        let index = net.add_connection(NavConnection(0, 1)).unwrap();
+        assert_eq!(net.connection_cost(index), Some(1.0));
+    }
+
+    #[cfg(feature = "import")]
+    #[test]
+    fn test_import() {
+        let gltf = r#"{
+            "asset": { "version": "2.0" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0, 2] }],
+            "nodes": [
+                { "name": "nav", "translation": [0, 2, 0], "children": [1] },
+                { "name": "ground", "mesh": 0 },
+                { "name": "walls", "mesh": 1 }
+            ],
+            "meshes": [
+                { "name": "floor", "primitives": [{ "attributes": { "POSITION": 0 } }] },
+                { "name": "wall", "primitives": [{ "attributes": { "POSITION": 1 } }] }
+            ],
+            "accessors": [
+                {
+                    "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                    "min": [0, 0, -1], "max": [1, 0, 0]
+                },
+                {
+                    "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3",
+                    "min": [0, 0, 0], "max": [1, 1, 0]
+                }
+            ],
+            "bufferViews": [
+                { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+                { "buffer": 0, "byteOffset": 36, "byteLength": 36 }
+            ],
+            "buffers": [{
+                "byteLength": 72,
+                "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAAAAAAIC/AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+            }]
+        }"#;
+        let importer = NavGeometryImporter::default();
+        let (vertices, triangles) = importer
+            .import_gltf_slice(gltf.as_bytes(), |name| name == "nav")
+            .unwrap();
+        assert_eq!(
+            vertices,
+            vec![
+                (0.0, 0.0, 2.0).into(),
+                (1.0, 0.0, 2.0).into(),
+                (0.0, 1.0, 2.0).into(),
+            ]
+        );
+        assert_eq!(triangles.len(), 1);
+        let (vertices, triangles) = importer
+            .import_gltf_slice(gltf.as_bytes(), |name| name == "floor" || name == "wall")
+            .unwrap();
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(triangles.len(), 2);
+        assert!(NavMesh::new(vertices, triangles).is_ok());
+
+        let importer = NavGeometryImporter::new(NavImportSettings {
+            y_up_to_z_up: false,
+            scale: 2.0,
+        });
+        let obj = b"o floor\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+        let (vertices, triangles) = importer.import_obj_slice(obj, |_| true).unwrap();
+        assert_eq!(vertices[2], (2.0, 2.0, 0.0).into());
+        assert_eq!(triangles.len(), 2);
+        assert!(importer
+            .import_obj_slice(obj, |_| false)
+            .unwrap()
+            .0
+            .is_empty());
+        assert!(importer.import_gltf_slice(b"{", |_| true).is_err());
+    }
+
+    #[test]
+    fn test_detour() {
+        use crate::nav_binary::BinaryWriter;
+
+        // single quad polygon tile in Detour Y-up coordinates.
+        fn tile(x: f32, area: u8, flags: u16) -> Vec<u8> {
+            let mut writer = BinaryWriter::default();
+            writer.write_bytes(b"VAND");
+            writer.write_u32(7);
+            writer.write_bytes(&[0; 16]);
+            // polys, verts, links, detail meshes, detail verts, detail tris, bv nodes, off-mesh
+            // connections and off-mesh base.
+            for count in [2, 6, 0, 0, 0, 0, 0, 0, 1] {
+                writer.write_u32(count);
+            }
+            writer.write_bytes(&[0; 40]);
+            for (vx, vz) in [
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, -1.0),
+                (0.0, -1.0),
+                (0.0, 0.0),
+                (0.0, 0.0),
+            ] {
+                for value in [x + vx, 0.0, vz] {
+                    writer.write_bytes(&value.to_le_bytes());
+                }
+            }
+            // walkable quad.
+            writer.write_u32(0);
+            for vert in [0, 1, 2, 3, 0, 0, 0, 0, 0, 0, 0, 0] {
+                writer.write_u16(vert);
+            }
+            writer.write_u16(flags);
+            writer.write_u8(4);
+            writer.write_u8(area);
+            // off-mesh connection polygon is skipped.
+            writer.write_u32(0);
+            for vert in [4, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0] {
+                writer.write_u16(vert);
+            }
+            writer.write_u16(flags);
+            writer.write_u8(2);
+            writer.write_u8(1 << 6);
+            writer.into_inner()
+        }
+
+        let mut writer = BinaryWriter::default();
+        writer.write_bytes(b"TESM");
+        writer.write_u32(1);
+        writer.write_u32(2);
+        writer.write_bytes(&[0; 28]);
+        for (index, data) in [tile(0.0, 3, 1), tile(1.0, 5, 2)].iter().enumerate() {
+            writer.write_u32(index as u32 + 1);
+            writer.write_u32(data.len() as u32);
+            writer.write_bytes(data);
+        }
+        let bytes = writer.into_inner();
+
+        let detour = NavDetourMesh::from_bytes(&bytes).unwrap();
+        assert_eq!(detour.vertices.len(), 6);
+        assert_eq!(detour.triangles.len(), 4);
+        assert_eq!(detour.area_types, vec![3, 3, 5, 5]);
+        assert_eq!(detour.flags, vec![1, 1, 2, 2]);
+        assert!(detour
+            .vertices
+            .iter()
+            .all(|v| v.z == 0.0 && v.y >= 0.0 && v.y <= 1.0));
+        let mesh = detour.into_nav_mesh().unwrap();
+        assert_eq!(mesh.areas()[3].area_type, 5);
+        assert_eq!(mesh.areas()[3].flags, 2);
+        let path = mesh
+            .find_path(
+                (0.1, 0.5, 0.0).into(),
+                (1.9, 0.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert!(path[path.len() - 1].same_as((1.9, 0.5, 0.0).into()));
+
+        let detour = NavDetourMesh::from_bytes(&tile(0.0, 0, 1)).unwrap();
+        assert_eq!(detour.triangles.len(), 2);
+        assert!(NavDetourMesh::from_bytes(&bytes[..bytes.len() - 4]).is_err());
+        let mut huge = tile(0.0, 0, 1);
+        huge[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(NavDetourMesh::from_bytes(&huge).is_err());
+        assert!(NavDetourMesh::from_bytes(b"NAVM").is_err());
+    }
+
+    #[test]
+    fn test_query_pool() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = (0.0, 1.0, 0.0).into();
+        let to = (1.5, 0.25, 0.5).into();
+        let expected = mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+
+        let mut pool = NavQueryPool::new();
+        let ids = (0..10)
+            .map(|_| pool.request(from, to, NavQuery::Accuracy, NavPathMode::Accuracy))
+            .collect::<Vec<_>>();
+        let cancelled = pool.request(from, to, NavQuery::Accuracy, NavPathMode::Accuracy);
+        assert!(pool.cancel(cancelled));
+        assert!(pool.status(cancelled).is_none());
+        assert_eq!(pool.pending_count(), 10);
+        assert_eq!(pool.update(&mesh, NavQueryBudget::Expansions(4)), 4);
+        assert!(pool.status(ids[0]).unwrap().is_pending());
+        assert!(pool.take(ids[0]).is_none());
+        let mut updates = 1;
+        while pool.pending_count() > 0 {
+            assert!(pool.update(&mesh, NavQueryBudget::Expansions(4)) <= 4);
+            updates += 1;
+        }
+        assert!(updates > 5);
+        assert_eq!(pool.completed_count(), 10);
+        for id in ids {
+            match pool.take(id).unwrap() {
+                NavQueryStatus::Found(path) => {
+                    assert_eq!(path.len(), expected.len());
+                    for (a, b) in path.iter().zip(expected.iter()) {
+                        assert!(a.same_as(*b));
+                    }
+                }
+                status => panic!("unexpected status: {:?}", status),
+            }
+        }
+        assert_eq!(pool.completed_count(), 0);
+
+        let id = pool.request(from, from, NavQuery::Accuracy, NavPathMode::Accuracy);
+        pool.update(
+            &mesh,
+            NavQueryBudget::Time(std::time::Duration::from_secs(1)),
+        );
+        assert_eq!(pool.take(id), Some(NavQueryStatus::NotFound));
+    }
+
+    #[test]
+    fn test_path_cache() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        assert_eq!(mesh.path_cache_capacity(), 0);
+        assert!(mesh.find_path_triangles(1, 2).is_some());
+        assert_eq!(mesh.path_cache_len(), 0);
+
+        mesh.set_path_cache_capacity(2);
+        let expected = mesh.find_path_triangles(1, 2).unwrap();
+        assert_eq!(mesh.find_path_triangles(1, 2).unwrap(), expected);
+        assert_eq!(mesh.path_cache_len(), 1);
+        mesh.find_path_triangles(0, 2);
+        mesh.find_path_triangles(1, 2);
+        mesh.find_path_triangles(3, 1);
+        assert_eq!(mesh.path_cache_len(), 2);
+        assert!(mesh
+            .find_path(
+                (0.0, 1.0, 0.0).into(),
+                (1.5, 0.25, 0.5).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .is_some());
+        let clone = mesh.clone();
+        assert_eq!(clone.path_cache_capacity(), 2);
+        assert_eq!(clone.path_cache_len(), 0);
+        mesh.clear_path_cache();
+        assert_eq!(mesh.path_cache_len(), 0);
+
+        assert_eq!(mesh.find_path_triangles(1, 2).unwrap(), expected);
+        let version = mesh.version();
+        let id = mesh.add_obstacle(NavObstacle::aabb(
+            (1.1, -1.0, -1.0).into(),
+            (3.0, 2.0, 1.0).into(),
+        ));
+        assert!(mesh.version() > version);
+        assert!(mesh.find_path_triangles(1, 2).is_none());
+        mesh.remove_obstacle(id);
+        assert_eq!(mesh.find_path_triangles(1, 2).unwrap(), expected);
+        mesh.set_area_cost(0, 10.0);
+        assert!(mesh.find_path_triangles(1, 2).unwrap().1 > expected.1);
+
+        let mut tiled = NavTiledMesh::new(1.0);
+        let version = tiled.version();
+        tiled
+            .build_tile(
+                (0, 0),
+                vec![
+                    (0.0, 0.0, 0.0).into(),
+                    (1.0, 0.0, 0.0).into(),
+                    (1.0, 1.0, 0.0).into(),
+                ],
+                vec![(0, 1, 2).into()],
+            )
+            .unwrap();
+        assert!(tiled.version() > version);
+        let version = tiled.version();
+        tiled.remove_tile((0, 0));
+        assert!(tiled.version() > version);
+    }
+
+    #[test]
+    fn test_batch() {
+        let mut seed = 7u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as Scalar / (1 << 24) as Scalar * 2.0 - 1.0
+        };
+        let mut point = || NavVec3::new(random(), random(), random());
+        let a = (0..11).map(|_| point()).collect::<Vec<_>>();
+        let b = (0..9).map(|_| point()).collect::<Vec<_>>();
+        let result = NavVec3::dot_batch(&a, &b);
+        assert_eq!(result.len(), 9);
+        for (i, dot) in result.iter().enumerate() {
+            assert_relative_eq!(*dot, a[i].dot(b[i]));
+        }
+
+        let triangles = (0..23)
+            .map(|_| {
+                let offset = NavVec3::new(0.0, 0.0, point().z);
+                (point() + offset, point() + offset, point() + offset)
+            })
+            .collect::<Vec<_>>();
+        let from = NavVec3::new(0.1, -0.2, 2.0);
+        let to = NavVec3::new(-0.1, 0.2, -2.0);
+        let result = NavVec3::raycast_triangle_batch(from, to, &triangles);
+        assert_eq!(result.len(), triangles.len());
+        assert!(result.iter().any(|hit| hit.is_some()));
+        for (hit, (a, b, c)) in result.iter().zip(triangles.iter()) {
+            let expected = NavVec3::raycast_triangle(from, to, *a, *b, *c);
+            match (hit, expected) {
+                (Some(hit), Some(expected)) => assert!(hit.same_as(expected)),
+                (None, None) => {}
+                _ => panic!("batch result differs: {:?} vs {:?}", hit, expected),
+            }
+        }
+    }
+
+    #[test]
+    fn test_up_axis() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (4.0, 0.0, 0.0).into(),
+            (4.0, 4.0, 0.0).into(),
+            (0.0, 4.0, 0.0).into(),
+        ];
+        let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+        let rotated = vertices
+            .iter()
+            .map(|v| NavUpAxis::Y.rotate_from_z_up(*v))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            NavUpAxis::Y.rotate_to_z_up(NavVec3::new(1.0, 2.0, 3.0)),
+            NavVec3::new(1.0, -3.0, 2.0)
+        );
+
+        let settings = NavMeshBakeSettings {
+            min_region_area: 0,
+            ..Default::default()
+        };
+        let expected = NavMeshBaker::new(settings)
+            .bake_geometry(&vertices, &triangles)
+            .unwrap();
+        let settings = NavMeshBakeSettings {
+            up_axis: NavUpAxis::Y,
+            ..settings
+        };
+        let mesh = NavMeshBaker::new(settings)
+            .bake(&rotated, &triangles)
+            .unwrap();
+        assert_eq!(mesh.up_axis(), NavUpAxis::Y);
+        assert_eq!(mesh.vertices().len(), expected.0.len());
+        for (vertice, expected) in mesh.vertices().iter().zip(expected.0.iter()) {
+            assert!(NavUpAxis::Y.rotate_to_z_up(*vertice).same_as(*expected));
+        }
+        assert_eq!(mesh.triangles().len(), expected.1.len());
+
+        let mut mesh = NavMesh::new(rotated, triangles).unwrap();
+        mesh.set_up_axis(NavUpAxis::Y);
+        assert_relative_eq!(mesh.triangle_slope(0).unwrap(), 0.0);
+        let (_, point) = mesh
+            .find_surface_point((1.0, -0.5, -1.0).into(), 1.0)
+            .unwrap();
+        assert!(point.same_as((1.0, 0.0, -1.0).into()));
+        assert!(mesh
+            .find_surface_point((1.0, 2.0, -1.0).into(), 1.0)
+            .is_none());
+        let restored = NavMesh::from_bytes(&mesh.to_bytes()).unwrap();
+        assert_eq!(restored.up_axis(), NavUpAxis::Y);
+        let mut crowd = NavCrowd::new();
+        let agent = crowd.add_agent(NavCrowdAgent::new((0.5, 0.0, -0.5).into(), 0.1, 2.0, 8.0));
+        assert!(crowd.set_target(agent, (3.5, 0.0, -3.5).into()));
+        for _ in 0..300 {
+            crowd.update(&mesh, 1.0 / 30.0);
+        }
+        assert!(crowd.agent(agent).unwrap().has_arrived());
+        mesh.set_up_axis(NavUpAxis::Z);
+        assert_relative_eq!(
+            mesh.triangle_slope(0).unwrap(),
+            std::f64::consts::FRAC_PI_2 as Scalar
+        );
+
+        let mut tiled = NavTiledMesh::new(1.0);
+        tiled.set_up_axis(NavUpAxis::Y);
+        assert_eq!(tiled.tile_coord((1.5, 7.0, -2.5).into()), (1, 2));
+        let (min, max) = tiled.tile_bounds((1, 2));
+        assert!(min.same_as((1.0, 0.0, -3.0).into()));
+        assert!(max.same_as((2.0, 0.0, -2.0).into()));
+        tiled.set_tile((0, 0), mesh);
+        assert_eq!(tiled.tile((0, 0)).unwrap().up_axis(), NavUpAxis::Y);
+    }
+
+    #[test]
+    fn test_net_mutation() {
+        let mut net = NavNet::new(
+            vec![
+                (0.0, 0.0, 0.0).into(), // 0
+                (2.0, 0.0, 0.0).into(), // 1
+                (2.0, 2.0, 0.0).into(), // 2
+                (0.0, 2.0, 0.0).into(), // 3
+                (5.0, 5.0, 0.0).into(), // 4
+            ],
+            vec![
+                NavConnection(0, 1),
+                NavConnection(1, 2),
+                NavConnection(2, 3),
+                NavConnection(0, 4),
+            ],
+        )
+        .unwrap();
+        assert!(net.add_connection(NavConnection(3, 7)).is_err());
+        assert_eq!(net.add_connection(NavConnection(3, 0)).unwrap(), 4);
+        let path = net
+            .find_path((0.1, 0.0, 0.0).into(), (0.0, 1.9, 0.0).into())
+            .unwrap();
+        assert_eq!(path, vec![(0.0, 0.0, 0.0).into(), (0.0, 2.0, 0.0).into()]);
+
+        assert_eq!(net.remove_connection(4), Some(NavConnection(3, 0)));
+        assert_eq!(net.remove_connection(4), None);
+        let path = net
+            .find_path((0.0, 0.1, 0.0).into(), (0.0, 1.9, 0.0).into())
+            .unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(path[1], (2.0, 0.0, 0.0).into());
+
+        // vertice 4 takes index of removed vertice 0.
+        assert_eq!(net.remove_vertice(0), Some((0.0, 0.0, 0.0).into()));
+        assert_eq!(net.vertices().len(), 4);
+        assert_eq!(net.vertices()[0], (5.0, 5.0, 0.0).into());
+        assert_eq!(net.connections().len(), 2);
+        assert_relative_eq!(net.origin(), NavVec3::new(2.25, 2.25, 0.0));
+        let index = net.add_vertice((2.0, 5.0, 0.0).into());
+        net.add_connection(NavConnection(0, index as u32)).unwrap();
+        net.add_connection(NavConnection(index as u32, 2)).unwrap();
+        let rebuilt = NavNet::new(net.vertices().to_vec(), net.connections().to_vec()).unwrap();
+        for (from, to) in [
+            (NavVec3::new(5.0, 4.9, 0.0), NavVec3::new(2.1, 0.0, 0.0)),
+            (NavVec3::new(1.9, 1.0, 0.0), NavVec3::new(0.0, 2.1, 0.0)),
+        ] {
+            assert_eq!(net.find_path(from, to), rebuilt.find_path(from, to));
+            assert_eq!(
+                net.find_closest_connection(from),
+                rebuilt.find_closest_connection(from)
+            );
+        }
+        assert_eq!(net.find_islands().len(), 1);
+    }
+
+    #[test]
+    fn test_mesh_islands() {
+        // three separate unit squares along X axis.
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for island in 0..3 {
+            let x = island as Scalar * 3.0;
+            let offset = vertices.len() as u32;
+            vertices.extend_from_slice(&[
+                (x, 0.0, 0.0).into(),
+                (x + 1.0, 0.0, 0.0).into(),
+                (x + 1.0, 1.0, 0.0).into(),
+                (x, 1.0, 0.0).into(),
+            ]);
+            triangles.push((offset, offset + 1, offset + 2).into());
+            triangles.push((offset + 2, offset + 3, offset).into());
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let mut islands = NavMeshIslands::new(&mesh);
+        assert_eq!(islands.islands_count(), 3);
+        assert_eq!(islands.triangle_island(0), islands.triangle_island(1));
+        assert_ne!(islands.triangle_island(0), islands.triangle_island(2));
+        let first = islands
+            .point_island(&mesh, (0.5, 0.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let last = islands
+            .point_island(&mesh, (6.5, 0.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+
+        let a = islands
+            .add_portal(
+                &mesh,
+                NavMeshPortal::new((1.0, 0.5, 0.0).into(), (3.0, 0.5, 0.0).into(), 1.0, false),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        let b = islands
+            .add_portal(
+                &mesh,
+                NavMeshPortal::new((4.0, 0.5, 0.0).into(), (6.0, 0.5, 0.0).into(), 1.0, true),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        // expensive shortcut that should not be used.
+        let c = islands
+            .add_portal(
+                &mesh,
+                NavMeshPortal::new((1.0, 0.5, 0.0).into(), (6.0, 0.5, 0.0).into(), 100.0, false),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(islands.portals().count(), 3);
+        let from = NavVec3::new(0.5, 0.5, 0.0);
+        let to = NavVec3::new(6.5, 0.5, 0.0);
+        let route = islands
+            .find_route(&mesh, from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_eq!(route.len(), 3);
+        assert_eq!(route[0].island, first);
+        assert_eq!(route[0].portal, Some(a));
+        assert_eq!(route[1].portal, Some(b));
+        assert_eq!(route[2].island, last);
+        assert_eq!(route[2].portal, None);
+        let path = islands
+            .find_path(&mesh, from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert!(path.first().unwrap().same_as(from));
+        assert!(path.last().unwrap().same_as(to));
+        assert!(path.iter().any(|p| p.same_as((3.0, 0.5, 0.0).into())));
+        // one way portal can not be used back.
+        assert!(islands
+            .find_route(&mesh, to, from, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .is_none());
+
+        assert!(islands.remove_portal(b).is_some());
+        assert!(islands.portal(b).is_none());
+        let route = islands
+            .find_route(&mesh, from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_eq!(route.len(), 2);
+        assert_eq!(route[0].portal, Some(c));
+    }
+
+    #[test]
+    fn test_path_detailed() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 1.0, 0.0).into());
+        }
+        for col in 0..4 {
+            let i = col * 2;
+            triangles.push((i, i + 2, i + 3).into());
+            triangles.push((i + 3, i + 1, i).into());
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        // first three columns.
+        for index in 0..6 {
+            mesh.set_area_type(index, 7);
+        }
+        let from = NavVec3::new(0.5, 0.25, 0.0);
+        let to = NavVec3::new(3.5, 0.5, 0.0);
+        let path = mesh
+            .find_path_detailed(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        let expected = mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_eq!(path.points, expected);
+        let start = mesh
+            .find_closest_triangle(from, NavQuery::Accuracy)
+            .unwrap();
+        let end = mesh.find_closest_triangle(to, NavQuery::Accuracy).unwrap();
+        let (corridor, cost) = mesh.find_path_triangles(start, end).unwrap();
+        assert_eq!(path.triangles, corridor);
+        assert_relative_eq!(path.cost, cost);
+        assert_relative_eq!(path.length, NavMesh::path_length(&expected));
+        assert_eq!(path.segment_area_types, vec![7]);
+        assert!(!path.partial);
+    }
+
+    #[test]
+    fn test_path_partial() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 1.0, 0.0).into());
+        }
+        for col in 0..4 {
+            let i = col * 2;
+            triangles.push((i, i + 2, i + 3).into());
+            triangles.push((i + 3, i + 1, i).into());
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = NavVec3::new(0.5, 0.5, 0.0);
+        let to = NavVec3::new(3.5, 0.5, 0.0);
+        let path = mesh
+            .find_path_partial(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert!(!path.partial);
+        assert!(path.points.last().unwrap().same_as(to));
+
+        // block third column.
+        mesh.add_obstacle(NavObstacle::aabb(
+            (2.1, -1.0, -1.0).into(),
+            (2.9, 2.0, 1.0).into(),
+        ));
+        assert!(mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .is_none());
+        let path = mesh
+            .find_path_partial(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert!(path.partial);
+        assert!(path.points.last().unwrap().same_as((2.0, 0.5, 0.0).into()));
+        assert!(path.triangles.iter().all(|t| !mesh.is_triangle_blocked(*t)));
+
+        // already at closest reachable point.
+        let path = mesh
+            .find_path_partial(
+                (2.0, 0.5, 0.0).into(),
+                to,
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert!(path.partial);
+        assert_eq!(path.points.len(), 1);
+    }
+
+    #[test]
+    fn test_path_follower() {
+        let mut follower = NavPathFollower::new(2.0, 0.1);
+        let steering = follower.update(NavVec3::default(), (1.0, 0.0, 0.0).into());
+        assert!(steering.arrived);
+        assert_eq!(steering.steering, (-1.0, 0.0, 0.0).into());
+
+        follower.set_path(vec![
+            (0.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (2.0, 2.0, 0.0).into(),
+            (4.0, 2.0, 0.0).into(),
+        ]);
+        assert_relative_eq!(follower.length(), 6.0);
+        let steering = follower.update((1.0, 0.05, 0.0).into(), NavVec3::default());
+        assert_eq!(steering.corner, 1);
+        assert!(!steering.reanchored);
+        assert_relative_eq!(steering.traveled, 1.0);
+        assert_relative_eq!(steering.remaining, 5.0);
+        assert_relative_eq!(steering.desired_velocity.magnitude(), 2.0);
+
+        // pushed away from path next to its third segment.
+        let steering = follower.update((3.0, 1.2, 0.0).into(), NavVec3::default());
+        assert!(steering.reanchored);
+        assert_eq!(follower.segment(), 2);
+        assert!(steering.target.same_as((3.0, 2.0, 0.0).into()));
+        assert_relative_eq!(steering.progress, 5.0 / 6.0);
+
+        // corners are not skipped backwards once passed.
+        let steering = follower.update((2.0, 1.95, 0.0).into(), NavVec3::default());
+        assert_eq!(follower.segment(), 2);
+        assert_eq!(steering.corner, 3);
+
+        follower.slowing_distance = 1.0;
+        let steering = follower.update((3.5, 2.0, 0.0).into(), (2.0, 0.0, 0.0).into());
+        assert!(!steering.arrived);
+        assert_relative_eq!(steering.desired_velocity.x, 1.0);
+        assert_relative_eq!(steering.steering.x, -1.0);
+        let steering = follower.update((3.95, 2.0, 0.0).into(), (1.0, 0.0, 0.0).into());
+        assert!(steering.arrived);
+        assert_eq!(steering.desired_velocity, NavVec3::default());
+        assert_eq!(steering.target, (4.0, 2.0, 0.0).into());
+    }
+
+    #[test]
+    fn test_random_point() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (2.0, 0.0, 0.0).into(), // 1
+            (2.0, 1.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (3.0, 0.0, 0.0).into(), // 4
+            (4.0, 0.0, 0.0).into(), // 5
+            (4.0, 1.0, 0.0).into(), // 6
+            (3.0, 1.0, 0.0).into(), // 7
+        ];
+        let triangles = vec![
+            (0, 1, 2).into(),
+            (2, 3, 0).into(),
+            (4, 5, 6).into(),
+            (6, 7, 4).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        assert_eq!(mesh.find_reachable_triangles(0), vec![0, 1]);
+        let mut seed = 3u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as Scalar / (1 << 24) as Scalar
+        };
+        let query = NavQuery::Accuracy;
+        let mut left = 0;
+        for _ in 0..300 {
+            let point = mesh.random_point(None, query, &mut random).unwrap();
+            assert!(mesh.closest_point(point, query).unwrap().same_as(point));
+            if point.x <= 2.0 {
+                left += 1;
+            }
+        }
+        // left island has two thirds of total area.
+        assert!((170..230).contains(&left));
+        for _ in 0..100 {
+            let point = mesh
+                .random_point(Some((3.5, 0.5, 0.0).into()), query, &mut random)
+                .unwrap();
+            assert!(point.x >= 3.0);
+            let center = NavVec3::new(1.0, 0.5, 0.0);
+            let point = mesh
+                .random_point_around(center, 0.5, None, query, &mut random)
+                .unwrap();
+            assert!((point - center).magnitude() <= 0.5 + ZERO_TRESHOLD);
+            let point = mesh
+                .random_point_around(center, 2.5, Some(center), query, &mut random)
+                .unwrap();
+            assert!(point.x <= 2.0);
+        }
+        assert!(mesh
+            .random_point_around((10.0, 0.0, 0.0).into(), 1.0, None, query, &mut random)
+            .is_none());
+
+        mesh.add_obstacle(NavObstacle::aabb(
+            (-1.0, -1.0, -1.0).into(),
+            (2.5, 2.0, 1.0).into(),
+        ));
+        assert!(mesh.find_reachable_triangles(0).is_empty());
+        for _ in 0..100 {
+            let point = mesh.random_point(None, query, &mut random).unwrap();
+            assert!(point.x >= 3.0);
+        }
+    }
+
+    #[test]
+    fn test_reachable_area() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 1.0, 0.0).into());
+        }
+        for col in 0..4 {
+            let i = col * 2;
+            triangles.push((i, i + 2, i + 3).into());
+            triangles.push((i + 3, i + 1, i).into());
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = NavVec3::new(0.25, 0.75, 0.0);
+        let query = NavQuery::Accuracy;
+        let all = mesh.reachable_area(from, SCALAR_MAX, query).unwrap();
+        assert_eq!(all.triangles.len(), 8);
+        assert!(all.costs.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(all.cost(1), Some(0.0));
+        // whole strip boundary: two long sides and two ends.
+        assert_eq!(all.boundary(&mesh).len(), 10);
+        let budget = all.cost(4).unwrap();
+        let area = mesh.reachable_area(from, budget, query).unwrap();
+        assert!(area.contains(4));
+        assert!(!area.contains(6));
+        assert!(area.costs.iter().all(|cost| *cost <= budget));
+
+        // expensive middle column limits range.
+        mesh.set_area_cost(2, 10.0);
+        mesh.set_area_cost(3, 10.0);
+        let area = mesh.reachable_area(from, budget, query).unwrap();
+        assert!(!area.contains(4));
+        mesh.add_obstacle(NavObstacle::aabb(
+            (1.1, -1.0, -1.0).into(),
+            (1.9, 2.0, 1.0).into(),
+        ));
+        let area = mesh.reachable_area(from, SCALAR_MAX, query).unwrap();
+        assert_eq!(area.triangles.len(), 2);
+        assert!(mesh
+            .reachable_area((3.5, 0.5, 0.0).into(), 0.0, query)
+            .is_some_and(|area| area.triangles.len() == 1));
+    }
+
+    #[test]
+    fn test_mesh_csg() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 2.0, 0.0).into());
+        }
+        for col in 0..4 {
+            let i = col * 2;
+            triangles.push((i, i + 2, i + 3).into());
+            triangles.push((i + 3, i + 1, i).into());
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        for index in 0..4 {
+            mesh.set_area_type(index, 3);
+        }
+        let total_area = |mesh: &NavMesh| mesh.areas().iter().map(|a| a.size).sum::<Scalar>();
+
+        // split mesh into two chunks along cut going through triangles and join them back.
+        let left = mesh
+            .clip(&NavObstacle::aabb(
+                (-1.0, -1.0, -1.0).into(),
+                (1.5, 3.0, 1.0).into(),
+            ))
+            .unwrap();
+        let right = mesh
+            .clip(&NavObstacle::aabb(
+                (1.5, -1.0, -1.0).into(),
+                (5.0, 3.0, 1.0).into(),
+            ))
+            .unwrap();
+        assert!((total_area(&left) - 3.0).abs() < 1.0e-4);
+        assert!((total_area(&right) - 5.0).abs() < 1.0e-4);
+        assert!(left
+            .areas()
+            .iter()
+            .all(|area| area.area_type == 3 || area.center.x > 1.0));
+        let joined = left.union(&right).unwrap();
+        assert!((total_area(&joined) - 8.0).abs() < 1.0e-4);
+        assert_eq!(joined.find_triangle_islands().len(), 1);
+        let from = NavVec3::new(0.25, 1.0, 0.0);
+        let to = NavVec3::new(3.75, 1.0, 0.0);
+        let path = joined
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_relative_eq!(NavMesh::path_length(&path), 3.5, epsilon = 1.0e-4);
+
+        // cut hole in the middle, agents go around it.
+        let holed = mesh
+            .subtract(&NavObstacle::cylinder(
+                (2.0, 1.0, -1.0).into(),
+                (0.0, 0.0, 2.0).into(),
+                0.5,
+            ))
+            .unwrap();
+        assert_eq!(holed.find_triangle_islands().len(), 1);
+        assert!(holed
+            .find_triangle_containing((2.0, 1.0, 0.0).into(), 0.1)
+            .is_none());
+        let path = holed
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert!(NavMesh::path_length(&path) > 3.5);
+
+        // cutting mesh in half with wall splits it into islands.
+        let cut = mesh
+            .subtract(&NavObstacle::aabb(
+                (1.9, -1.0, -1.0).into(),
+                (2.1, 3.0, 1.0).into(),
+            ))
+            .unwrap();
+        assert_eq!(cut.find_triangle_islands().len(), 2);
+        assert!(matches!(
+            mesh.clip(&NavObstacle::aabb(
+                (10.0, 10.0, 10.0).into(),
+                (11.0, 11.0, 11.0).into(),
+            )),
+            Err(Error::NoWalkableSurface)
+        ));
+    }
+
+    #[test]
+    fn test_layered_query() {
+        // ground floor and first floor overlapping it, connected with ramp standing on ground.
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (2.0, 0.0, 0.0).into(), // 1
+            (2.0, 2.0, 0.0).into(), // 2
+            (0.0, 2.0, 0.0).into(), // 3
+            (0.0, 0.0, 3.0).into(), // 4
+            (2.0, 0.0, 3.0).into(), // 5
+            (2.0, 2.0, 3.0).into(), // 6
+            (0.0, 2.0, 3.0).into(), // 7
+            (6.0, 0.0, 0.0).into(), // 8
+            (6.0, 2.0, 0.0).into(), // 9
+        ];
+        let triangles = vec![
+            (0, 1, 2).into(), // 0
+            (2, 3, 0).into(), // 1
+            (4, 5, 6).into(), // 2
+            (6, 7, 4).into(), // 3
+            (5, 8, 9).into(), // 4
+            (9, 6, 5).into(), // 5
+            (1, 8, 9).into(), // 6
+            (9, 2, 1).into(), // 7
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let ground = |t: Option<usize>| t.is_some_and(|t| !(2..=5).contains(&t));
+        let upper = |t: Option<usize>| t.is_some_and(|t| t == 2 || t == 3);
+        let point = NavVec3::new(1.0, 0.5, 1.6);
+        assert!(upper(mesh.find_closest_triangle(point, NavQuery::Accuracy)));
+        assert!(ground(
+            mesh.find_closest_triangle(point, NavQuery::Layered(0.5))
+        ));
+        assert!(upper(mesh.find_closest_triangle(
+            NavVec3::new(1.0, 0.5, 2.8),
+            NavQuery::Layered(0.5)
+        )));
+        assert!(upper(mesh.find_closest_triangle(
+            NavVec3::new(1.0, 0.5, 5.0),
+            NavQuery::Layered(0.5)
+        )));
+        let p = mesh
+            .closest_point(NavVec3::new(1.0, 0.5, 1.6), NavQuery::Layered(0.5))
+            .unwrap();
+        assert!(p.same_as((1.0, 0.5, 0.0).into()));
+        // below all layers falls back to closest triangle.
+        assert!(ground(mesh.find_closest_triangle(
+            NavVec3::new(1.0, 0.5, -1.0),
+            NavQuery::Layered(0.5)
+        )));
+        assert_eq!(mesh.find_layer_triangle((1.0, 0.5, -1.0).into(), 0.5), None);
+        // path from ground floor under first floor goes up the ramp.
+        let path = mesh
+            .find_path(
+                (1.0, 1.0, 1.0).into(),
+                (1.0, 1.0, 3.5).into(),
+                NavQuery::Layered(0.5),
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert!(path.first().unwrap().same_as((1.0, 1.0, 0.0).into()));
+        assert!(path.last().unwrap().same_as((1.0, 1.0, 3.0).into()));
+
+        // the same with Y up axis.
+        let rotated = mesh
+            .vertices()
+            .iter()
+            .map(|v| NavUpAxis::Y.rotate_from_z_up(*v))
+            .collect::<Vec<_>>();
+        mesh = NavMesh::new(rotated, mesh.triangles().to_vec()).unwrap();
+        mesh.set_up_axis(NavUpAxis::Y);
+        let point = NavUpAxis::Y.rotate_from_z_up(point);
+        assert!(upper(mesh.find_closest_triangle(point, NavQuery::Accuracy)));
+        assert!(ground(
+            mesh.find_closest_triangle(point, NavQuery::Layered(0.5))
+        ));
+    }
+
+    #[test]
+    fn test_validate_repair() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),  // 0
+            (1.0, 0.0, 0.0).into(),  // 1
+            (1.0, 1.0, 0.0).into(),  // 2
+            (0.0, 1.0, 0.0).into(),  // 3
+            (1.0, 0.0, 0.0).into(),  // 4
+            (2.0, 0.0, 0.0).into(),  // 5
+            (2.0, 1.0, 0.0).into(),  // 6
+            (1.0, 1.0, 0.0).into(),  // 7
+            (1.5, 0.5, 1.0).into(),  // 8
+            (10.0, 0.0, 0.0).into(), // 9
+            (10.1, 0.0, 0.0).into(), // 10
+            (10.0, 0.1, 0.0).into(), // 11
+            (1.5, 0.5, -1.0).into(), // 12
+        ];
+        let triangles = vec![
+            (0, 1, 2).into(),   // 0
+            (2, 3, 0).into(),   // 1
+            (4, 6, 5).into(),   // 2 inverted
+            (6, 4, 7).into(),   // 3 inverted
+            (1, 1, 2).into(),   // 4 degenerate
+            (7, 4, 8).into(),   // 5
+            (7, 4, 12).into(),  // 6
+            (9, 10, 11).into(), // 7 sliver
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_area_type(2, 5);
+        let report = mesh.validate();
+        assert!(!report.is_valid());
+        assert_eq!(report.duplicate_vertices, vec![(1, 4), (2, 7)]);
+        assert_eq!(report.degenerate_triangles, vec![4]);
+        assert_eq!(report.inverted_triangles, vec![2, 3]);
+        assert_eq!(report.non_manifold_edges, vec![NavConnection(4, 7)]);
+        assert_eq!(report.slivers, vec![vec![7]]);
+
+        let repaired = mesh.repair().unwrap();
+        assert_eq!(repaired.vertices().len(), 11);
+        assert_eq!(repaired.triangles().len(), 7);
+        assert_eq!(repaired.areas()[2].area_type, 5);
+        let report = repaired.validate();
+        assert!(report.duplicate_vertices.is_empty());
+        assert!(report.degenerate_triangles.is_empty());
+        assert!(report.inverted_triangles.is_empty());
+        assert_eq!(report.non_manifold_edges.len(), 1);
+        assert_eq!(report.slivers.len(), 1);
+        assert_eq!(repaired.find_triangle_islands().len(), 2);
+        let path = repaired
+            .find_path(
+                (0.25, 0.5, 0.0).into(),
+                (1.75, 0.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert!(path.last().unwrap().same_as((1.75, 0.5, 0.0).into()));
+    }
+
+    #[test]
+    fn test_new_welded() {
+        // two quads exported as separate objects, with seam vertices slightly apart.
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),    // 0
+            (1.0, 0.0, 0.0).into(),    // 1
+            (1.0, 1.0, 0.0).into(),    // 2
+            (0.0, 1.0, 0.0).into(),    // 3
+            (1.0, 0.0001, 0.0).into(), // 4
+            (2.0, 0.0, 0.0).into(),    // 5
+            (2.0, 1.0, 0.0).into(),    // 6
+            (1.0001, 1.0, 0.0).into(), // 7
+            (5.0, 5.0, 0.0).into(),    // 8
+        ];
+        let triangles = vec![
+            (0, 1, 2).into(), // 0
+            (2, 3, 0).into(), // 1
+            (4, 5, 6).into(), // 2
+            (6, 7, 4).into(), // 3
+            (2, 1, 0).into(), // 4 duplicate with flipped winding
+            (1, 4, 2).into(), // 5 degenerate after welding
+            (1, 2, 7).into(), // 6 degenerate after welding
+        ];
+        let separate = NavMesh::new(vertices.clone(), triangles.clone()).unwrap();
+        assert_eq!(separate.find_triangle_islands().len(), 2);
+
+        let tolerance = NavTolerance {
+            weld_distance: 0.001,
+            ..Default::default()
+        };
+        let (mesh, report) = NavMesh::new_welded(vertices, triangles, tolerance).unwrap();
+        assert!(!report.is_unchanged());
+        assert_eq!(report.welded_vertices, vec![(1, 4), (2, 7)]);
+        assert_eq!(report.vertices_map, vec![0, 1, 2, 3, 1, 4, 5, 2, 6]);
+        assert_eq!(report.duplicate_triangles, vec![(0, 4)]);
+        assert_eq!(report.degenerate_triangles, vec![5, 6]);
+        assert_eq!(
+            report.triangles_map,
+            vec![Some(0), Some(1), Some(2), Some(3), None, None, None]
+        );
+        assert_eq!(mesh.vertices().len(), 7);
+        assert_eq!(mesh.triangles().len(), 4);
+        assert_eq!(mesh.tolerance(), tolerance);
+        assert_eq!(mesh.find_triangle_islands().len(), 1);
+        assert!(mesh.validate().duplicate_vertices.is_empty());
+        assert!(mesh
+            .find_path(
+                (0.25, 0.5, 0.0).into(),
+                (1.75, 0.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .is_some());
+
+        let (_, report) = NavMesh::new_welded(
+            separate.vertices()[..4].to_vec(),
+            separate.triangles()[..2].to_vec(),
+            NavTolerance::default(),
+        )
+        .unwrap();
+        assert!(report.is_unchanged());
+        assert!(matches!(
+            NavMesh::new_welded(
+                separate.vertices().to_vec(),
+                vec![(0, 1, 9).into()],
+                NavTolerance::default()
+            ),
+            Err(Error::TriangleVerticeIndexOutOfBounds(0, 2, 9))
+        ));
+        assert!(matches!(
+            NavMesh::new_welded(
+                separate.vertices().to_vec(),
+                vec![(0, 4, 1).into()],
+                tolerance
+            ),
+            Err(Error::NoWalkableSurface)
+        ));
+    }
+
+    #[test]
+    fn test_mesh_builder() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),    // 0
+            (1.0, 0.0, 0.0).into(),    // 1
+            (1.0, 1.0, 0.0).into(),    // 2
+            (0.0, 1.0, 0.0).into(),    // 3
+            (1.0, 0.0005, 0.0).into(), // 4
+            (2.0, 0.0, 0.0).into(),    // 5
+            (2.0, 1.0, 0.0).into(),    // 6
+        ];
+        let triangles = vec![
+            (0, 1, 2).into(),
+            (2, 3, 0).into(),
+            (4, 5, 6).into(),
+            (6, 2, 4).into(),
+        ];
+
+        // defaults match plain constructor.
+        let plain = NavMesh::new(vertices.clone(), triangles.clone()).unwrap();
+        let (built, report) = NavMeshBuilder::new(vertices.clone(), triangles.clone())
+            .build_with_report()
+            .unwrap();
+        assert!(report.is_unchanged());
+        assert_eq!(report.vertices_map, (0..7).collect::<Vec<_>>());
+        assert_eq!(built.vertices(), plain.vertices());
+        assert_eq!(built.fingerprint(), plain.fingerprint());
+        assert_eq!(built.up_axis(), plain.up_axis());
+        assert_eq!(built.tolerance(), plain.tolerance());
+        assert_eq!(built.landmarks().count(), 0);
+        assert_eq!(built.path_cache_capacity(), 0);
+        assert_eq!(built.find_triangle_islands().len(), 2);
+
+        let builder = NavMesh::builder(vertices, triangles)
+            .with_weld_distance(0.001)
+            .with_area_cost(3.0)
+            .with_area_type(2)
+            .with_area_flags(0b10)
+            .with_landmarks(2)
+            .with_path_cache_capacity(8);
+        assert!(builder.welding());
+        assert_eq!(builder.tolerance().weld_distance, 0.001);
+        let mesh = builder.build().unwrap();
+        assert_eq!(mesh.vertices().len(), 6);
+        assert_eq!(mesh.find_triangle_islands().len(), 1);
+        assert!(mesh
+            .areas()
+            .iter()
+            .all(|area| area.cost == 3.0 && area.area_type == 2 && area.flags == 0b10));
+        assert_eq!(mesh.landmarks().count(), 2);
+        assert_eq!(mesh.path_cache_capacity(), 8);
+        let (_, cost) = mesh.find_path_triangles(1, 2).unwrap();
+        assert!(mesh.estimate_path_cost(1, 2) <= cost);
+
+        assert!(matches!(
+            NavMeshBuilder::new(vec![(0.0, 0.0, 0.0).into()], vec![(0, 0, 0).into()])
+                .with_welding(true)
+                .build(),
+            Err(Error::NoWalkableSurface)
+        ));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            NavMesh::new(vec![], vec![]),
+            Err(Error::EmptyGeometry(0, 0))
+        ));
+        let error = NavMesh::new(vec![(0.0, 0.0, 0.0).into()], vec![(0, 0, 1).into()])
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Triangle 0 vertice 2 uses vertice index 1 out of vertices list"
+        );
+        let boxed: Box<dyn std::error::Error> = Box::new(Error::NoWalkableSurface);
+        assert_eq!(boxed.to_string(), "No walkable surface was produced");
+
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (20.0, 0.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (20.0, 10.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = NavVec3::new(1.0, 9.0, 0.0);
+        let to = NavVec3::new(19.0, 1.0, 0.0);
+        let path = mesh
+            .find_path_checked(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert!(path.points.last().unwrap().same_as(to));
+
+        mesh.add_gate("door", vec![2], false).unwrap();
+        match mesh.find_path_checked(from, to, NavQuery::Accuracy, NavPathMode::Accuracy) {
+            Err(Error::GoalUnreachable(nearest)) => {
+                assert!(mesh.find_triangle_containing(nearest, 0.1).is_some());
+                assert!(nearest.distance(to) < from.distance(to));
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(matches!(
+            mesh.find_path_checked(to, from, NavQuery::Accuracy, NavPathMode::Accuracy),
+            Err(Error::StartNotWalkable(_))
+        ));
+        // checked counterparts of other path queries report the same errors.
+        match mesh.find_path_filtered_checked(
+            from,
+            to,
+            NavQuery::Accuracy,
+            NavPathMode::Accuracy,
+            &NavAreaFilter::default(),
+        ) {
+            Err(Error::GoalUnreachable(nearest)) => {
+                assert!(mesh.find_triangle_containing(nearest, 0.1).is_some());
+                assert!(nearest.distance(to) < from.distance(to));
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(matches!(
+            mesh.find_path_triangles_checked(2, 0),
+            Err(Error::StartNotWalkable(_))
+        ));
+        assert!(matches!(
+            mesh.find_path_triangles_checked(0, 4),
+            Err(Error::TriangleIndexOutOfBounds(4, 4))
+        ));
+        assert!(matches!(
+            mesh.find_path_triangles_with_stats_checked(0, 2).0,
+            Err(Error::GoalUnreachable(_))
+        ));
+        // goal is walkable, but query itself rejects every connection.
+        match mesh.find_path_triangles_with_filter_checked(0, 1, &|_, _, _| None) {
+            Err(Error::GoalUnreachable(nearest)) => {
+                assert!(nearest.same_as(mesh.areas()[0].center))
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(
+            mesh.find_path_triangles_bidirectional_checked(0, 1)
+                .unwrap(),
+            mesh.find_path_triangles(0, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_islands_rejection() {
+        // row of separate quads.
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for index in 0..8 {
+            let x = index as Scalar * 2.0;
+            let first = vertices.len() as u32;
+            vertices.push((x, 0.0, 0.0).into());
+            vertices.push((x + 1.0, 0.0, 0.0).into());
+            vertices.push((x + 1.0, 1.0, 0.0).into());
+            vertices.push((x, 1.0, 0.0).into());
+            triangles.push((first, first + 1, first + 2).into());
+            triangles.push((first + 2, first + 3, first).into());
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        assert_eq!(mesh.islands_count(), 8);
+        assert_eq!(mesh.islands_count(), mesh.find_triangle_islands().len());
+        for index in 0..16 {
+            assert_eq!(mesh.triangle_island(index), Some(index / 2));
+        }
+        assert_eq!(mesh.triangle_island(16), None);
+        assert!(!mesh.are_triangles_connected(0, 16));
+
+        let (result, stats) = mesh.find_path_triangles_with_stats(0, 15);
+        assert!(result.is_none());
+        assert_eq!(stats.expansions, 0);
+        let (result, stats) = mesh.find_path_triangles_with_stats(0, 1);
+        assert!(result.is_some());
+        assert!(stats.expansions > 0);
+        assert!(mesh
+            .find_path_triangles_custom(0, 15, |_, _, _| true)
+            .is_none());
+
+        let from = NavVec3::new(0.5, 0.5, 0.0);
+        let to = NavVec3::new(14.5, 0.5, 0.0);
+        assert!(mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .is_none());
+        assert!(matches!(
+            mesh.find_path_checked(from, to, NavQuery::Accuracy, NavPathMode::Accuracy),
+            Err(Error::Unreachable {
+                from_island: 0,
+                to_island: 7
+            })
+        ));
+        let partial = mesh
+            .find_path_partial(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert!(partial.partial);
+
+        let loaded = NavMesh::from_bytes(&mesh.to_bytes()).unwrap();
+        assert_eq!(loaded.islands_count(), 8);
+        assert_eq!(loaded.triangle_island(15), Some(7));
+    }
+
+    #[test]
+    fn test_debug_draw() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 1.0, 0.0).into());
+        }
+        for col in 0..4 {
+            let i = col * 2;
+            triangles.push((i, i + 2, i + 3).into());
+            triangles.push((i + 3, i + 1, i).into());
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_area_type(0, 1);
+        let drawn = debug_mesh_triangles(&mesh);
+        assert_eq!(drawn.len(), 8);
+        assert_ne!(drawn[0].3, drawn[1].3);
+        assert_eq!(drawn[1].3, drawn[2].3);
+        let connections = debug_mesh_connections(&mesh, NavDebugColor::GREEN);
+        assert_eq!(connections.len(), 7);
+        assert!(connections.iter().all(|c| c.2 == NavDebugColor::GREEN));
+        let bounds = debug_mesh_bounds(&mesh);
+        assert!(!bounds.is_empty());
+        assert_eq!(bounds.len() % 12, 0);
+
+        let path = mesh
+            .find_path_detailed(
+                (0.25, 0.5, 0.0).into(),
+                (3.75, 0.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        let lines = debug_path(&mesh, &path);
+        let count = |color| lines.iter().filter(|l| l.2 == color).count();
+        assert_eq!(count(NavDebugColor::WHITE), path.triangles.len() * 3);
+        assert_eq!(count(NavDebugColor::CYAN), path.triangles.len() - 1);
+        assert_eq!(count(NavDebugColor::YELLOW), path.points.len() - 1);
+
+        mesh.add_obstacle(NavObstacle::aabb(
+            (1.1, -1.0, -1.0).into(),
+            (1.9, 2.0, 1.0).into(),
+        ));
+        assert_eq!(debug_mesh_triangles(&mesh).len(), 6);
+        assert_eq!(
+            debug_mesh_connections(&mesh, NavDebugColor::GREEN)
+                .iter()
+                .filter(|c| c.2 == NavDebugColor::RED)
+                .count(),
+            3
+        );
+
+        let mut crowd = NavCrowd::new();
+        let agent = crowd.add_agent(NavCrowdAgent::new((0.5, 0.5, 0.0).into(), 0.25, 1.0, 10.0));
+        crowd.set_target(agent, (1.0, 0.5, 0.0).into());
+        crowd.update(&mesh, 0.1);
+        let lines = debug_crowd(&crowd, &mesh);
+        assert_eq!(lines.len(), 18);
+        let position = crowd.agent(agent).unwrap().position;
+        assert!(lines
+            .iter()
+            .filter(|l| l.2 == NavDebugColor::WHITE)
+            .all(|l| ((l.0 - position).magnitude() - 0.25).abs() < 1.0e-4));
+    }
+
+    #[test]
+    fn test_string_pull() {
+        // L shaped corridor of portals.
+        let portals = vec![
+            (NavVec3::new(1.0, 0.0, 0.0), NavVec3::new(1.0, 1.0, 0.0)),
+            (NavVec3::new(2.0, 1.0, 0.0), NavVec3::new(3.0, 1.0, 0.0)),
+        ];
+        let start = NavVec3::new(0.5, 0.5, 0.0);
+        let end = NavVec3::new(2.5, 2.5, 0.0);
+        let path = string_pull_portals(&portals, start, end, NavVec3::new(0.0, 0.0, 1.0));
+        assert_eq!(path, vec![start, (2.0, 1.0, 0.0).into(), end]);
+        // reversed order gives the same corner.
+        let reversed = portals.iter().rev().copied().collect::<Vec<_>>();
+        let path = string_pull_portals(&reversed, end, start, NavVec3::new(0.0, 0.0, 1.0));
+        assert_eq!(path, vec![end, (2.0, 1.0, 0.0).into(), start]);
+        assert_eq!(
+            string_pull_portals(&[], start, end, NavVec3::new(0.0, 0.0, 1.0)),
+            vec![start, end]
+        );
+
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 1.0, 0.0).into());
+        }
+        for col in 0..4 {
+            let i = col * 2;
+            triangles.push((i, i + 2, i + 3).into());
+            triangles.push((i + 3, i + 1, i).into());
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = NavVec3::new(0.25, 0.25, 0.0);
+        let to = NavVec3::new(3.75, 0.75, 0.0);
+        let (corridor, _) = mesh.find_path_triangles(1, 6).unwrap();
+        let path = string_pull(&mesh, &corridor, from, to, 0.0).unwrap();
+        assert_eq!(path, vec![from, to]);
+        assert!(string_pull(&mesh, &corridor, from, to, 0.6).is_none());
+        assert!(string_pull(&mesh, &[], from, to, 0.0).is_none());
+        assert!(string_pull(&mesh, &[1, 100], from, to, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_query_filter() {
+        struct AvoidDanger {
+            danger: NavVec3,
+            radius: Scalar,
+        }
+
+        impl NavQueryFilter for AvoidDanger {
+            fn cost(&self, from: NavVec3, to: NavVec3, _: u8) -> Option<Scalar> {
+                if (to - self.danger).magnitude() < self.radius {
+                    None
+                } else {
+                    Some((to - from).magnitude())
+                }
+            }
+        }
+
+        let mut vertices = vec![];
+        for row in 0..=2 {
+            for col in 0..=4 {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..2 {
+            for col in 0..4 {
+                let i = row * 5 + col;
+                triangles.push((i, i + 1, i + 6).into());
+                triangles.push((i + 6, i + 5, i).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let filter = AvoidDanger {
+            danger: (2.0, 2.0, 0.0).into(),
+            radius: 1.0,
+        };
+        let dangerous = |path: &[usize]| {
+            path.iter()
+                .any(|index| (mesh.areas()[*index].center - filter.danger).magnitude() < 1.0)
+        };
+        let (path, _) = mesh.find_path_triangles(9, 14).unwrap();
+        assert!(dangerous(&path));
+        let (filtered, _) = mesh
+            .find_path_triangles_with_filter(9, 14, &filter)
+            .unwrap();
+        assert!(!dangerous(&filtered));
+        assert!(filtered.len() > path.len());
+        let points = mesh
+            .find_path_with_filter(
+                (0.1, 1.9, 0.0).into(),
+                (3.9, 1.9, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+                &filter,
+            )
+            .unwrap();
+        assert!(points
+            .iter()
+            .all(|point| point.y <= 1.0 + 1.0e-4 || (*point - filter.danger).magnitude() >= 1.0));
+
+        // closures work as filters and area costs still apply.
+        let distance = |from: NavVec3, to: NavVec3, _: u8| Some((to - from).magnitude());
+        let (_, base) = mesh
+            .find_path_triangles_with_filter(0, 7, &distance)
+            .unwrap();
+        for index in 0..mesh.triangles().len() {
+            mesh.set_area_cost(index, 2.0);
+        }
+        let (_, doubled) = mesh
+            .find_path_triangles_with_filter(0, 7, &distance)
+            .unwrap();
+        assert!((doubled - base * 2.0).abs() < 1.0e-4);
+        let blocked = |_: NavVec3, _: NavVec3, area: u8| if area == 0 { None } else { Some(1.0) };
+        assert!(mesh
+            .find_path_triangles_with_filter(0, 7, &blocked)
+            .is_none());
+    }
+
+    #[test]
+    fn test_mesh_diff() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 1.0, 0.0).into());
+            if col < 4 {
+                let i = col * 2;
+                triangles.push((i, i + 2, i + 3).into());
+                triangles.push((i + 3, i + 1, i).into());
+            }
+        }
+        let old = NavMesh::new(vertices, triangles).unwrap();
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(old.diff(&old).triangles.len(), 0);
+
+        // carve middle of the strip out.
+        let new = old
+            .subtract(&NavObstacle::aabb(
+                (1.5, -1.0, -1.0).into(),
+                (2.5, 2.0, 1.0).into(),
+            ))
+            .unwrap();
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        let bytes = diff.to_bytes();
+        assert!(bytes.len() < new.to_bytes().len());
+        let diff = NavMeshDiff::from_bytes(&bytes).unwrap();
+        let patched = old.apply_diff(&diff).unwrap();
+        assert_eq!(patched.fingerprint(), new.fingerprint());
+        assert_eq!(patched.find_triangle_islands().len(), 2);
+
+        // shrinking back works too.
+        let back = new.diff(&old);
+        assert_eq!(
+            patched.apply_diff(&back).unwrap().fingerprint(),
+            old.fingerprint()
+        );
+
+        assert!(matches!(
+            new.apply_diff(&diff),
+            Err(Error::NavMeshDiffMismatch(_, _))
+        ));
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        assert!(NavMeshDiff::from_bytes(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_area_clearance() {
+        let mut vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (10.0, 0.0, 0.0).into(),
+            (10.0, 10.0, 0.0).into(),
+            (0.0, 10.0, 0.0).into(),
+        ];
+        let mut triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+        // low ceiling over part of the floor, only crawling agents fit under it.
+        let offset = vertices.len() as u32;
+        vertices.extend_from_slice(&[
+            (4.0, 0.0, 1.5).into(),
+            (6.0, 0.0, 1.5).into(),
+            (6.0, 6.0, 1.5).into(),
+            (4.0, 6.0, 1.5).into(),
+        ]);
+        triangles.push((offset, offset + 1, offset + 2).into());
+        triangles.push((offset + 2, offset + 3, offset).into());
+        let settings = NavMeshBakeSettings {
+            agent_height: 1.0,
+            ..Default::default()
+        };
+        let mesh = NavMeshBaker::new(settings)
+            .bake(&vertices, &triangles)
+            .unwrap();
+        let low = mesh
+            .find_closest_triangle((5.0, 3.0, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let clearance = mesh.areas()[low].clearance;
+        assert!(clearance > 1.0 && clearance < 2.0);
+        let open = mesh
+            .find_closest_triangle((5.0, 8.0, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        assert_eq!(mesh.areas()[open].clearance, SCALAR_MAX);
+
+        let from = (2.0, 3.0, 0.0).into();
+        let to = (8.0, 3.0, 0.0).into();
+        let query = NavQuery::Accuracy;
+        let mode = NavPathMode::Accuracy;
+        let crawler = NavAreaFilter {
+            agent_height: 1.0,
+            ..Default::default()
+        };
+        let path = mesh
+            .find_path_filtered(from, to, query, mode, &crawler)
+            .unwrap();
+        assert!(path.iter().all(|point| point.y < 6.0));
+        let tall = NavAreaFilter {
+            agent_height: 2.0,
+            ..Default::default()
+        };
+        let path = mesh
+            .find_path_filtered(from, to, query, mode, &tall)
+            .unwrap();
+        assert!(path.iter().any(|point| point.y >= 6.0));
+
+        let loaded = NavMesh::from_bytes(&mesh.to_bytes()).unwrap();
+        assert_eq!(loaded.areas()[low].clearance, clearance);
+        assert_eq!(loaded.areas()[open].clearance, SCALAR_MAX);
+    }
+
+    #[test]
+    fn test_slope_filter() {
+        let mut vertices = vec![];
+        for row in 0..=3 {
+            for col in 0..=4 {
+                // hill in the middle of two bottom rows.
+                let height = if col == 2 && row <= 1 { 1.0 } else { 0.0 };
+                vertices.push((col as Scalar, row as Scalar, height).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..3 {
+            for col in 0..4 {
+                let i = row * 5 + col;
+                triangles.push((i, i + 1, i + 6).into());
+                triangles.push((i + 6, i + 5, i).into());
+            }
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = (0.5, 0.5, 0.0).into();
+        let to = (3.5, 0.5, 0.0).into();
+        let query = NavQuery::Accuracy;
+        let mode = NavPathMode::Accuracy;
+        let flat = NavSlopeFilter {
+            uphill_cost: 0.0,
+            max_climb_angle: std::f64::consts::FRAC_PI_2 as Scalar,
+            ..Default::default()
+        };
+        let path = mesh
+            .find_path_with_filter(from, to, query, mode, &flat)
+            .unwrap();
+        assert!(path.iter().all(|point| point.y < 2.0 - 1.0e-4));
+        assert!(path.iter().any(|point| point.z > 0.0));
+
+        let steep = NavSlopeFilter {
+            max_climb_angle: 10.0_f64.to_radians() as Scalar,
+            ..Default::default()
+        };
+        let path = mesh
+            .find_path_with_filter(from, to, query, mode, &steep)
+            .unwrap();
+        assert!(path.iter().any(|point| point.y >= 2.0 - 1.0e-4));
+        assert!(path.iter().all(|point| point.z.abs() < 1.0e-4));
+
+        let costly = NavSlopeFilter {
+            uphill_cost: 10.0,
+            downhill_cost: 10.0,
+            max_climb_angle: std::f64::consts::FRAC_PI_2 as Scalar,
+            ..Default::default()
+        };
+        let path = mesh
+            .find_path_with_filter(from, to, query, mode, &costly)
+            .unwrap();
+        assert!(path.iter().any(|point| point.y >= 2.0 - 1.0e-4));
+        assert!(steep.cost(from, to, 0).is_some());
+        assert!(steep.cost(from, (0.5, 0.5, 1.0).into(), 0).is_none());
+        assert!(steep.cost((0.5, 0.5, 1.0).into(), from, 0).is_some());
+    }
+
+    #[test]
+    fn test_path_search() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=8 {
+            vertices.push((col as Scalar, 0.0, 0.0).into());
+            vertices.push((col as Scalar, 1.0, 0.0).into());
+            if col < 8 {
+                let i = col * 2;
+                triangles.push((i, i + 2, i + 3).into());
+                triangles.push((i + 3, i + 1, i).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let from = (0.25, 0.5, 0.0).into();
+        let to = (7.75, 0.5, 0.0).into();
+        let query = NavQuery::Accuracy;
+        let mode = NavPathMode::Accuracy;
+        let expected = mesh.find_path(from, to, query, mode).unwrap();
+
+        let mut search = mesh.start_search(from, to, query, mode);
+        let mut steps = 0;
+        while !search.is_finished() {
+            assert!(search.status().is_pending());
+            assert!(search.step(&mesh, 2) <= 2);
+            steps += 1;
+        }
+        assert!(steps > 4);
+        assert_eq!(search.step(&mesh, 10), 0);
+        match search.take_result() {
+            NavQueryStatus::Found(path) => {
+                assert_eq!(path.len(), expected.len());
+                for (a, b) in path.iter().zip(expected.iter()) {
+                    assert!(a.same_as(*b));
+                }
+            }
+            status => panic!("unexpected status: {:?}", status),
+        }
+
+        // search restarts on other nav mesh.
+        let mut search = mesh.start_search(from, to, query, mode);
+        search.step(&mesh, 3);
+        let other = NavMesh::new(mesh.vertices().to_vec(), mesh.triangles().to_vec()).unwrap();
+        search.step(&other, 1000);
+        assert!(matches!(search.take_result(), NavQueryStatus::Found(_)));
+
+        mesh.add_obstacle(NavObstacle::aabb(
+            (3.5, -1.0, -1.0).into(),
+            (4.5, 2.0, 1.0).into(),
+        ));
+        let mut search = mesh.start_search(from, to, query, mode);
+        search.step(&mesh, 1000);
+        assert_eq!(search.take_result(), NavQueryStatus::NotFound);
+        let search = mesh.start_search(from, to, query, mode);
+        assert_eq!(search.take_result(), NavQueryStatus::Pending);
+    }
+
+    #[test]
+    fn test_weighted_search() {
+        let size = 12;
+        let mut vertices = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..size {
+            for col in 0..size {
+                let i = row * (size + 1) + col;
+                triangles.push((i, i + 1, i + size + 2).into());
+                triangles.push((i + size + 2, i + size + 1, i).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        // wall with gap near its top end.
+        mesh.add_obstacle(NavObstacle::aabb(
+            (5.2, -1.0, -1.0).into(),
+            (6.8, 10.0, 1.0).into(),
+        ));
+        let from = mesh
+            .find_closest_triangle((1.5, 1.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        let to = mesh
+            .find_closest_triangle((10.5, 1.5, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        mesh.precompute_landmarks(4);
+        let (_, optimal) = mesh.find_path_triangles_weighted(from, to, 1.0).unwrap();
+        assert!((optimal - mesh.find_path_triangles(from, to).unwrap().1).abs() < 1.0e-4);
+        let length = |from: NavVec3, to: NavVec3, _: u8| Some(from.distance(to));
+        let (_, filtered) = mesh
+            .find_path_triangles_with_filter(from, to, &length)
+            .unwrap();
+        for weight in [1.5, 2.0, 5.0] {
+            let (path, cost) = mesh.find_path_triangles_weighted(from, to, weight).unwrap();
+            assert_eq!(path.first(), Some(&from));
+            assert_eq!(path.last(), Some(&to));
+            assert!(cost >= optimal - 1.0e-4);
+            assert!(cost <= optimal * weight + 1.0e-4);
+            let (path, cost) = mesh
+                .find_path_triangles_weighted_with_filter(from, to, weight, &length)
+                .unwrap();
+            assert_eq!(path.last(), Some(&to));
+            assert!(cost >= filtered - 1.0e-4);
+            assert!(cost <= filtered * weight + 1.0e-4);
+        }
+        let (_, cost) = mesh
+            .find_path_triangles_anytime_with_filter(
+                from,
+                to,
+                5.0,
+                NavQueryBudget::Expansions(usize::MAX),
+                &length,
+            )
+            .unwrap();
+        assert!((cost - filtered).abs() < 1.0e-4);
+        let (_, quick) = mesh.find_path_triangles_weighted(from, to, 5.0).unwrap();
+        let (_, cost) = mesh
+            .find_path_triangles_anytime(from, to, 5.0, NavQueryBudget::Expansions(0))
+            .unwrap();
+        assert!((cost - quick).abs() < 1.0e-4);
+        let (_, cost) = mesh
+            .find_path_triangles_anytime(from, to, 5.0, NavQueryBudget::Expansions(usize::MAX))
+            .unwrap();
+        assert!((cost - optimal).abs() < 1.0e-4);
+        let path = mesh
+            .find_path_anytime(
+                (1.5, 1.5, 0.0).into(),
+                (10.5, 1.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+                3.0,
+                NavQueryBudget::Time(std::time::Duration::from_secs(1)),
+            )
+            .unwrap();
+        assert!(path.iter().any(|point| point.y >= 10.0 - 1.0e-4));
+        assert!(mesh.find_path_triangles_weighted(from, 0, 2.0).is_some());
+        let blocked = mesh
+            .find_closest_triangle((6.0, 5.0, 0.0).into(), NavQuery::Accuracy)
+            .unwrap();
+        assert!(mesh
+            .find_path_triangles_weighted(from, blocked, 2.0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_bidirectional_search() {
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=40 {
+            // corridor winding up and down.
+            let y = ((col as Scalar) * 0.5).sin() * 2.0;
+            vertices.push((col as Scalar, y, 0.0).into());
+            vertices.push((col as Scalar, y + 1.0, 0.0).into());
+            if col < 40 {
+                let i = col * 2;
+                triangles.push((i, i + 2, i + 3).into());
+                triangles.push((i + 3, i + 1, i).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_area_cost(30, 3.0);
+        let last = mesh.triangles().len() - 1;
+        let length = |from: NavVec3, to: NavVec3, _: u8| Some(from.distance(to));
+        for landmarks in [0, 2] {
+            mesh.precompute_landmarks(landmarks);
+            for (from, to) in [(0, last), (last, 0), (5, 6), (20, 21), (3, 60)] {
+                let (path, cost) = mesh.find_path_triangles_bidirectional(from, to).unwrap();
+                let (expected, expected_cost) = mesh.find_path_triangles(from, to).unwrap();
+                assert_eq!(path.first(), Some(&from));
+                assert_eq!(path.last(), Some(&to));
+                assert!(path
+                    .windows(2)
+                    .all(|pair| mesh.triangles_portal(pair[0], pair[1]).is_some()));
+                assert!((cost - expected_cost).abs() < 1.0e-3);
+                assert_eq!(path, expected);
+                let (path, cost) = mesh
+                    .find_path_triangles_bidirectional_with_filter(from, to, &length)
+                    .unwrap();
+                let (expected, expected_cost) = mesh
+                    .find_path_triangles_with_filter(from, to, &length)
+                    .unwrap();
+                assert!((cost - expected_cost).abs() < 1.0e-3);
+                assert_eq!(path, expected);
+            }
+        }
+        assert_eq!(
+            mesh.find_path_triangles_bidirectional(7, 7),
+            Some((vec![7], 0.0))
+        );
+        let path = mesh
+            .find_path_bidirectional(
+                (0.25, 0.5, 0.0).into(),
+                (
+                    39.75,
+                    40.0_f64.mul_add(0.0, (20.0_f64).sin() * 2.0) as Scalar + 0.5,
+                    0.0,
+                )
+                    .into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert!(path.len() > 2);
+
+        mesh.add_obstacle(NavObstacle::aabb(
+            (19.5, -5.0, -1.0).into(),
+            (20.5, 5.0, 1.0).into(),
+        ));
+        assert!(mesh.find_path_triangles_bidirectional(0, last).is_none());
+        assert!(mesh.find_path_triangles_bidirectional(0, 10).is_some());
+        assert!(mesh
+            .find_path_triangles_bidirectional_with_filter(0, last, &length)
+            .is_none());
+    }
+
+    #[test]
+    fn test_landmarks() {
+        let size = 8;
+        let mut vertices = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+            }
+        }
+        let mut triangles = vec![];
+        for row in 0..size {
+            for col in 0..size {
+                // leave walls with gaps at opposite ends, making a maze.
+                if (col == 2 && row > 0) || (col == 5 && row < size - 1) {
+                    continue;
+                }
+                let i = row * (size + 1) + col;
+                triangles.push((i, i + 1, i + size + 2).into());
+                triangles.push((i + size + 2, i + size + 1, i).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let count = mesh.triangles().len();
+        let pairs = (0..count)
+            .step_by(7)
+            .flat_map(|a| (0..count).step_by(11).map(move |b| (a, b)))
+            .collect::<Vec<_>>();
+        let expected = pairs
+            .iter()
+            .map(|(a, b)| mesh.find_path_triangles(*a, *b).unwrap().1)
+            .collect::<Vec<_>>();
+        assert_eq!(mesh.estimate_path_cost(0, count - 1), 0.0);
+
+        mesh.precompute_landmarks(4);
+        let landmarks = mesh.landmarks().collect::<Vec<_>>();
+        assert_eq!(landmarks.len(), 4);
+        let mut tight = 0;
+        for ((a, b), cost) in pairs.iter().zip(&expected) {
+            let (path, found) = mesh.find_path_triangles(*a, *b).unwrap();
+            assert_eq!(path.first(), Some(a));
+            assert_eq!(path.last(), Some(b));
+            assert!((found - cost).abs() < 1.0e-3);
+            let estimate = mesh.estimate_path_cost(*a, *b);
+            assert!(estimate <= cost + 1.0e-3);
+            if estimate >= cost * 0.5 {
+                tight += 1;
+            }
+        }
+        assert!(tight * 2 > pairs.len());
+
+        let loaded = NavMesh::from_bytes(&mesh.to_bytes()).unwrap();
+        assert_eq!(loaded.landmarks().collect::<Vec<_>>(), landmarks);
+        assert_eq!(
+            loaded.estimate_path_cost(0, count - 1),
+            mesh.estimate_path_cost(0, count - 1)
+        );
+
+        mesh.set_area_cost(3, 2.0);
+        assert_eq!(mesh.landmarks().count(), 4);
+        mesh.set_area_cost(3, 0.5);
+        assert_eq!(mesh.landmarks().count(), 0);
+        mesh.precompute_landmarks(2);
+        mesh.clear_landmarks();
+        assert_eq!(mesh.landmarks().count(), 0);
+    }
+
+    #[test]
+    fn test_vec3_math() {
+        let x = NavVec3::new(1.0, 0.0, 0.0);
+        let y = NavVec3::new(0.0, 2.0, 0.0);
+        let half_pi = std::f64::consts::FRAC_PI_2 as Scalar;
+        assert!((x.angle_between(y) - half_pi).abs() < 1.0e-4);
+        assert!((x.angle_between(-x) - half_pi * 2.0).abs() < 1.0e-4);
+        assert_eq!(x.angle_between(NavVec3::default()), 0.0);
+        let middle = x.slerp(y, 0.5);
+        assert!((middle.magnitude() - 1.5).abs() < 1.0e-4);
+        assert!((middle.angle_between(x) - half_pi * 0.5).abs() < 1.0e-4);
+        assert!(x.slerp(y, 0.0).same_as(x));
+        assert!(x.slerp(y, 1.0).same_as(y));
+        assert!(x.slerp(x * 3.0, 0.5).same_as(x * 2.0));
+
+        let reflected = NavVec3::new(1.0, -1.0, 0.0).reflect((0.0, 2.0, 0.0).into());
+        assert!(reflected.same_as((1.0, 1.0, 0.0).into()));
+        assert!(y.clamp_length(0.0, 1.0).same_as((0.0, 1.0, 0.0).into()));
+        assert!(x.clamp_length(3.0, 4.0).same_as((3.0, 0.0, 0.0).into()));
+        assert!(y.clamp_length(1.0, 3.0).same_as(y));
+        let moved = NavVec3::default().move_towards(y, 0.5);
+        assert!(moved.same_as((0.0, 0.5, 0.0).into()));
+        assert!(NavVec3::default().move_towards(y, 5.0).same_as(y));
+        assert_eq!(
+            NavVec3::new(-2.0, 3.0, -0.5).signum(),
+            NavVec3::new(-1.0, 1.0, -1.0)
+        );
+        assert_eq!(x.distance(y), (5.0 as Scalar).sqrt());
+        assert_eq!(x.distance_squared(y), 5.0);
+
+        let from = NavVec3::new(0.0, 0.0, 0.0);
+        let to = NavVec3::new(4.0, 0.0, 0.0);
+        let point = NavVec3::new(2.0, 3.0, 0.0);
+        assert!(point
+            .closest_point_on_segment(from, to)
+            .same_as((2.0, 0.0, 0.0).into()));
+        assert!((point.distance_to_segment(from, to) - 3.0).abs() < 1.0e-4);
+        assert!((NavVec3::new(-3.0, 4.0, 0.0).distance_to_segment(from, to) - 5.0).abs() < 1.0e-4);
+        assert!(
+            (NavVec3::new(7.0, 4.0, 0.0).distance_squared_to_segment(from, to) - 25.0).abs()
+                < 1.0e-3
+        );
+        assert!(point.closest_point_on_segment(from, from).same_as(from));
+    }
+
+    #[cfg(feature = "robust")]
+    #[test]
+    fn test_robust_predicates() {
+        let up = NavVec3::new(0.0, 0.0, 1.0);
+        let from = NavVec3::new(12.0, 12.0, 0.0);
+        let to = NavVec3::new(24.0, 24.0, 0.0);
+        let sign = |v: Scalar| {
+            if v > 0.0 {
+                1
+            } else if v < 0.0 {
+                -1
+            } else {
+                0
+            }
+        };
+        // points around line, closer to it than floating point determinant can tell.
+        for i in 0..16 {
+            for j in 0..16 {
+                let offset = Scalar::EPSILON * 0.5;
+                let point =
+                    NavVec3::new(0.5 + i as Scalar * offset, 0.5 + j as Scalar * offset, 0.0);
+                let expected = sign(orient_on_plane(point, from, to, up));
+                assert_eq!(sign(orient_on_plane(from, to, point, up)), expected);
+                assert_eq!(sign(orient_on_plane(to, point, from, up)), expected);
+                assert_eq!(sign(orient_on_plane(from, point, to, up)), -expected);
+                assert_eq!(sign(orient_on_plane(point, from, to, -up)), -expected);
+                let above = point + up;
+                assert_eq!(sign(orient3d(point, from, to, above)), expected);
+                assert_eq!(sign(orient3d(to, point, from, above)), expected);
+                assert_eq!(sign(orient3d(from, point, to, above)), -expected);
+            }
+        }
+
+        // segment going through shared edge of near-degenerate triangles hits at least one of
+        // them, no matter vertices order.
+        let a = NavVec3::new(0.0, 0.0, 0.0);
+        let b = NavVec3::new(3.0, 1.0, 0.0);
+        let c = NavVec3::new(1.5, 0.5 + 1.0e-4, 0.0);
+        let d = NavVec3::new(1.5, 0.5 - 1.0e-4, 0.0);
+        for i in 1..30 {
+            let x = i as Scalar * 0.1;
+            let top = NavVec3::new(x, x / 3.0, 1.0);
+            let bottom = NavVec3::new(x, x / 3.0, -1.0);
+            let hits = [(a, b, c), (b, a, d)]
+                .iter()
+                .map(|(a, b, c)| {
+                    let hit = segment_crosses_triangle(top, bottom, *a, *b, *c).is_some();
+                    assert_eq!(
+                        segment_crosses_triangle(bottom, top, *b, *c, *a).is_some(),
+                        hit
+                    );
+                    assert_eq!(
+                        segment_crosses_triangle(top, bottom, *c, *a, *b).is_some(),
+                        hit
+                    );
+                    assert_eq!(
+                        NavVec3::raycast_triangle(top, bottom, *a, *c, *b).is_some(),
+                        hit
+                    );
+                    hit
+                })
+                .filter(|hit| *hit)
+                .count();
+            if x < 3.0 {
+                assert!(hits > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_geom() {
+        let (a, b) = closest_points_on_segments(
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+            (3.0, 1.0, 0.0).into(),
+        );
+        assert!(a.same_as((1.0, 0.0, 0.0).into()));
+        assert!(b.same_as((2.0, 1.0, 0.0).into()));
+        let (a, b) = closest_points_on_segments(
+            (0.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+        );
+        assert!(a.same_as((1.0, 0.0, 0.0).into()));
+        assert!(b.same_as((1.0, 1.0, 0.0).into()));
+
+        let ta = NavVec3::new(0.0, 0.0, 0.0);
+        let tb = NavVec3::new(2.0, 0.0, 0.0);
+        let tc = NavVec3::new(0.0, 2.0, 0.0);
+        assert!(
+            closest_point_on_triangle((0.5, 0.5, 1.0).into(), ta, tb, tc)
+                .same_as((0.5, 0.5, 0.0).into())
+        );
+        assert!(closest_point_on_triangle((-1.0, -1.0, 0.0).into(), ta, tb, tc).same_as(ta));
+        assert!(
+            closest_point_on_triangle((2.0, 2.0, 0.0).into(), ta, tb, tc)
+                .same_as((1.0, 1.0, 0.0).into())
+        );
+
+        let (a, b) = closest_points_segment_triangle(
+            (0.5, 0.5, -1.0).into(),
+            (0.5, 0.5, 1.0).into(),
+            ta,
+            tb,
+            tc,
+        );
+        assert!(a.same_as(b));
+        assert!(a.same_as((0.5, 0.5, 0.0).into()));
+
+        assert!(sphere_intersects_triangle(
+            (1.0, 1.0, 0.5).into(),
+            0.6,
+            ta,
+            tb,
+            tc
+        ));
+        assert!(!sphere_intersects_triangle(
+            (2.0, 2.0, 0.0).into(),
+            1.0,
+            ta,
+            tb,
+            tc
+        ));
+        assert!(capsule_intersects_triangle(
+            (3.0, -1.0, 0.5).into(),
+            (-1.0, 3.0, 0.5).into(),
+            0.6,
+            ta,
+            tb,
+            tc
+        ));
+        assert!(!capsule_intersects_triangle(
+            (3.0, -1.0, 0.5).into(),
+            (-1.0, 3.0, 0.5).into(),
+            0.4,
+            ta,
+            tb,
+            tc
+        ));
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let scale = 1.0e-2;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for col in 0..=4 {
+            vertices.push(NavVec3::new(col as Scalar * scale, 0.0, 0.0));
+            vertices.push(NavVec3::new(col as Scalar * scale, scale, 0.0));
+            if col < 4 {
+                let i = col * 2;
+                triangles.push((i, i + 2, i + 3).into());
+                triangles.push((i + 3, i + 1, i).into());
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        assert_eq!(mesh.tolerance(), NavTolerance::default());
+        // points closer than default treshold are treated as the same.
+        let from = NavVec3::new(0.5 * scale, 0.5 * scale, 0.0);
+        let to = NavVec3::new(0.52 * scale, 0.5 * scale, 0.0);
+        assert!(mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .is_none());
+        let tolerance = NavTolerance::scaled(scale);
+        let path = mesh
+            .find_path_with_tolerance(
+                from,
+                to,
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+                tolerance,
+            )
+            .unwrap();
+        assert_eq!(path, vec![from, to]);
+
+        let to = NavVec3::new(3.5 * scale, 0.5 * scale, 0.0);
+        mesh.set_tolerance(tolerance);
+        assert!(mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .is_some());
+        let loaded = NavMesh::from_bytes(&mesh.to_bytes()).unwrap();
+        assert_eq!(loaded.tolerance(), tolerance);
+        assert_eq!(mesh.repair().unwrap().tolerance(), tolerance);
+
+        // coarse weld distance merges whole mesh into degenerate triangles.
+        mesh.set_tolerance(NavTolerance {
+            weld_distance: scale * 10.0,
+            ..tolerance
+        });
+        assert!(!mesh.validate().duplicate_vertices.is_empty());
+        assert!(mesh.repair().is_err());
+
+        // millimeters scale distances but not angles, so gentle slope still changes level.
+        let scale = 1000.0;
+        let vertices = vec![
+            NavVec3::new(0.0, 0.0, 0.0) * scale, // 0
+            NavVec3::new(1.0, 0.0, 0.0) * scale, // 1
+            NavVec3::new(2.0, 0.0, 0.1) * scale, // 2
+            NavVec3::new(0.0, 1.0, 0.0) * scale, // 3
+            NavVec3::new(1.0, 1.0, 0.0) * scale, // 4
+            NavVec3::new(2.0, 1.0, 0.1) * scale, // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let tolerance = NavTolerance::scaled(scale);
+        assert_eq!(tolerance.angular, NavTolerance::default().angular);
+        assert_eq!(
+            tolerance.distance_sqr,
+            NavTolerance::default().distance_sqr * scale * scale
+        );
+        mesh.set_tolerance(tolerance);
+        let path = mesh
+            .find_path(
+                NavVec3::new(0.5, 0.1, 0.0) * scale,
+                NavVec3::new(1.5, 0.9, 0.05) * scale,
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(path.len(), 3);
+    }
+
+    #[test]
+    fn test_triangulator() {
+        // U shaped concave polygon, fan around first point covers the gap between its arms.
+        let polygon: Vec<NavVec3> = vec![
+            (0.0, 0.0, 0.0).into(),
+            (3.0, 0.0, 0.0).into(),
+            (3.0, 2.0, 0.0).into(),
+            (2.0, 2.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (1.0, 2.0, 0.0).into(),
+            (0.0, 2.0, 0.0).into(),
+        ];
+        let area = |polygon: &[NavVec3], triangles: &[(usize, usize, usize)]| {
+            triangles
+                .iter()
+                .map(|(a, b, c)| NavArea::calculate_area(polygon[*a], polygon[*b], polygon[*c]))
+                .sum::<Scalar>()
+        };
+        let triangles = NavEarClipTriangulator.triangulate(&polygon).unwrap();
+        assert_eq!(triangles.len(), 6);
+        assert!((area(&polygon, &triangles) - 5.0).abs() < 1.0e-4);
+        let triangles = NavFanTriangulator.triangulate(&polygon).unwrap();
+        assert!(area(&polygon, &triangles) > 5.0 + 1.0e-4);
+
+        // co-linear points are skipped instead of failing.
+        let polygon: Vec<NavVec3> = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+            (0.0, 1.0, 0.0).into(),
+        ];
+        for triangles in [
+            NavEarClipTriangulator.triangulate(&polygon).unwrap(),
+            NavFanTriangulator.triangulate(&polygon).unwrap(),
+        ] {
+            assert!((area(&polygon, &triangles) - 2.0).abs() < 1.0e-4);
+        }
+        let line: Vec<NavVec3> = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+        ];
+        assert!(NavEarClipTriangulator.triangulate(&line).is_none());
+        assert!(NavFanTriangulator.triangulate(&line).is_none());
+
+        assert!(matches!(
+            NavMesh::from_polygons_with(line, vec![vec![0, 1, 2]], &NavEarClipTriangulator),
+            Err(Error::PolygonTriangulationFailed(0, indices)) if indices == vec![0, 1, 2]
+        ));
+        assert!(matches!(
+            NavMesh::from_polygons_with(polygon, vec![vec![0, 1, 9]], &NavEarClipTriangulator),
+            Err(Error::PolygonVerticeIndexOutOfBounds(0, 2, 9))
+        ));
+    }
+
+    #[test]
+    fn test_polygons_with_holes() {
+        let outer = vec![
+            NavVec3::new(0.0, 0.0, 0.0),
+            NavVec3::new(6.0, 0.0, 0.0),
+            NavVec3::new(6.0, 3.0, 0.0),
+            NavVec3::new(0.0, 3.0, 0.0),
+        ];
+        let square = |x: Scalar| {
+            vec![
+                NavVec3::new(x, 1.0, 0.0),
+                NavVec3::new(x + 1.0, 1.0, 0.0),
+                NavVec3::new(x + 1.0, 2.0, 0.0),
+                NavVec3::new(x, 2.0, 0.0),
+            ]
+        };
+        // second hole wound the other way.
+        let mut reversed = square(4.0);
+        reversed.reverse();
+        let mesh = NavMesh::from_polygons(&outer, &[square(1.0), reversed]).unwrap();
+        assert_eq!(mesh.triangles().len(), 14);
+        let size = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+        assert!((size - 16.0).abs() < 1.0e-4);
+        for x in [1.5, 4.5] {
+            assert!(mesh
+                .find_triangle_containing(NavVec3::new(x, 1.5, 0.0), 0.1)
+                .is_none());
+        }
+        assert!(mesh.validate().is_valid());
+
+        let from = NavVec3::new(0.5, 1.5, 0.0);
+        let to = NavVec3::new(2.5, 1.5, 0.0);
+        let path = mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert_eq!(path.len(), 4);
+        assert!(NavMesh::path_length(&path) > 2.0 + 1.0e-4);
+
+        assert!(matches!(
+            NavMesh::from_polygons(&outer, &[square(1.0), outer[..2].to_vec()]),
+            Err(Error::PolygonTriangulationFailed(2, indices)) if indices == vec![8, 9]
+        ));
+    }
+
+    #[test]
+    fn test_heightmap() {
+        // 6x6 cells: flat ground with sloped ramp on the last two columns and steep wall cell.
+        let size = 7;
+        let mut samples = vec![0.0; size * size];
+        for y in 0..size {
+            for x in 5..size {
+                samples[y * size + x] = (x - 4) as Scalar * 0.5;
+            }
+        }
+        samples[6 * size] = 10.0;
+        let mesh = NavMesh::from_heightmap(size, size, &samples, 1.0, 0.5).unwrap();
+        assert!(mesh.validate().is_valid());
+        // steep corner cell loses single triangle touching the wall sample.
+        assert!(mesh.triangles().len() < 6 * 6 * 2);
+        let path = mesh
+            .find_path(
+                (0.5, 0.2, 0.0).into(),
+                (5.8, 5.5, 0.9).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert!(path.last().unwrap().z > 0.5);
+
+        let steep = NavMesh::from_heightmap(size, size, &samples, 1.0, 0.1).unwrap();
+        assert!(steep
+            .find_closest_triangle((5.5, 2.5, 0.75).into(), NavQuery::Accuracy)
+            .map(|index| steep.areas()[index].center.x < 5.0)
+            .unwrap());
+
+        assert!(matches!(
+            NavMesh::from_heightmap(size, size, &samples[1..], 1.0, 0.5),
+            Err(Error::CellsCountDoesNotMatchColsRows(_, _, _))
+        ));
+        assert!(matches!(
+            NavMesh::from_heightmap(2, 2, &[0.0, 0.0, 0.0, 10.0], 1.0, 0.1),
+            Err(Error::NoWalkableSurface)
+        ));
+    }
+
+    #[test]
+    fn test_simplify() {
+        let size = 6;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                // single raised vertice that must not get flattened.
+                let z = if row == 4 && col == 4 { 0.5 } else { 0.0 };
+                vertices.push(NavVec3::new(col as Scalar, row as Scalar, z));
+                if row < size && col < size {
+                    let i = row * (size + 1) + col;
+                    triangles.push((i, i + 1, i + size + 2).into());
+                    triangles.push((i + size + 2, i + size + 1, i).into());
+                }
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        for index in 0..mesh.triangles().len() {
+            if mesh.areas()[index].center.x < 2.0 {
+                mesh.set_area_type(index, 1);
+            }
+        }
+        let sizes = |mesh: &NavMesh| {
+            mesh.areas().iter().fold([0.0; 2], |mut result, area| {
+                result[area.area_type as usize] += area.size;
+                result
+            })
+        };
+        let simplified = mesh.simplify(0.01).unwrap();
+        assert!(simplified.triangles().len() < mesh.triangles().len());
+        assert!(simplified.validate().is_valid());
+        assert_eq!(simplified.find_triangle_islands().len(), 1);
+        let (before, after) = (sizes(&mesh), sizes(&simplified));
+        for (before, after) in before.iter().zip(after.iter()) {
+            assert!((before - after).abs() < 1.0e-4);
+        }
+        // raised vertice is kept.
+        assert!(simplified.vertices().iter().any(|v| v.z > 0.25));
+        // area type border is kept.
+        for index in 0..simplified.triangles().len() {
+            let area = &simplified.areas()[index];
+            assert_eq!(area.area_type == 1, area.center.x < 2.0);
+        }
+        let from = NavVec3::new(0.5, 0.5, 0.0);
+        let to = NavVec3::new(5.5, 0.5, 0.0);
+        let path = simplified
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .unwrap();
+        assert!(path[0].same_as(from));
+        assert!(path.last().unwrap().same_as(to));
+        assert!(NavMesh::path_length(&path) < 5.5);
+
+        // big error tolerance flattens the bump too.
+        assert!(mesh.simplify(1.0).unwrap().triangles().len() < simplified.triangles().len());
+    }
+
+    #[test]
+    fn test_poly_mesh() {
+        let size = 6;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push(NavVec3::new(col as Scalar, row as Scalar, 0.0));
+                // leave hole in the middle, so paths have to go around it.
+                if row < size && col < size && !(2..4).contains(&row) || (row < size && col < 2) {
+                    let i = row * (size + 1) + col;
+                    triangles.push((i, i + 1, i + size + 2).into());
+                    triangles.push((i + size + 2, i + size + 1, i).into());
+                }
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        for index in 0..mesh.triangles().len() {
+            if mesh.areas()[index].center.y > 4.0 {
+                mesh.set_area_type(index, 1);
+                mesh.set_area_cost(index, 2.0);
+            }
+        }
+        let poly = NavPolyMesh::from_nav_mesh(&mesh, 0.01).unwrap();
+        assert!(poly.polygons().len() * 4 < mesh.triangles().len());
+        assert!(poly.polygons().iter().any(|p| p.vertices.len() > 3));
+        assert!(poly
+            .polygons()
+            .iter()
+            .filter(|p| p.area_type == 1)
+            .all(|p| p.center.y > 4.0 && p.cost == 2.0));
+
+        let from = NavVec3::new(5.5, 0.5, 0.0);
+        let to = NavVec3::new(5.5, 5.0, 0.0);
+        let path = poly.find_path(from, to, NavPathMode::Accuracy).unwrap();
+        assert_eq!(path.first(), Some(&from));
+        assert_eq!(path.last(), Some(&to));
+        // goes around the hole through its corners.
+        assert!(path.contains(&NavVec3::new(2.0, 2.0, 0.0)));
+        assert!(path.contains(&NavVec3::new(2.0, 4.0, 0.0)));
+        let length = NavMesh::path_length(&path);
+        let expected = mesh
+            .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            .map(|path| NavMesh::path_length(&path))
+            .unwrap();
+        assert!(length <= expected + 1.0e-4);
+        // moves between polygons cost like nav mesh connections.
+        let next = poly.polygon_neighbors(0).next().unwrap();
+        let (_, cost) = poly.find_path_polygons(0, next).unwrap();
+        let (a, b) = (&poly.polygons()[0], &poly.polygons()[next]);
+        assert!((cost - (b.center - a.center).sqr_magnitude() * a.cost * b.cost).abs() < 1.0e-4);
+
+        let back = poly.to_nav_mesh().unwrap();
+        assert!(back.validate().is_valid());
+        let size = |mesh: &NavMesh| mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+        assert!((size(&back) - size(&mesh)).abs() < 1.0e-4);
+        assert!(matches!(
+            NavPolyMesh::new(vec![NavVec3::default(); 3], vec![vec![0, 1, 3]]),
+            Err(Error::PolygonVerticeIndexOutOfBounds(0, 2, 3))
+        ));
+    }
+
+    #[test]
+    fn test_stitch() {
+        let mut left = NavMesh::new(
+            vec![
+                (0.0, 0.0, 0.0).into(),
+                (1.0, 0.0, 0.0).into(),
+                (1.0, 2.0, 0.0).into(),
+                (0.0, 2.0, 0.0).into(),
+            ],
+            vec![(0, 1, 2).into(), (2, 3, 0).into()],
+        )
+        .unwrap();
+        left.set_area_cost(0, 2.0);
+        // right chunk edge is split in the middle and lies a bit off the left chunk edge.
+        let mut right = NavMesh::new(
+            vec![
+                (1.002, 0.0, 0.0).into(),
+                (2.0, 0.0, 0.0).into(),
+                (2.0, 2.0, 0.0).into(),
+                (1.002, 2.0, 0.0).into(),
+                (1.002, 1.0, 0.0).into(),
+            ],
+            vec![(0, 1, 4).into(), (1, 2, 4).into(), (2, 3, 4).into()],
+        )
+        .unwrap();
+        right.set_area_cost(1, 3.0);
+        let far = NavMesh::new(
+            vec![
+                (5.0, 0.0, 0.0).into(),
+                (6.0, 0.0, 0.0).into(),
+                (6.0, 1.0, 0.0).into(),
+            ],
+            vec![(0, 1, 2).into()],
+        )
+        .unwrap();
+
+        left.stitch(right, 0.01).unwrap();
+        assert_eq!(left.vertices().len(), 7);
+        assert_eq!(left.find_triangle_islands().len(), 1);
+        assert!(left.areas().iter().any(|area| area.cost == 2.0));
+        assert!(left.areas().iter().any(|area| area.cost == 3.0));
+        let path = left
+            .find_path(
+                (0.5, 1.5, 0.0).into(),
+                (1.5, 0.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(path.first().unwrap().clone(), NavVec3::new(0.5, 1.5, 0.0));
+        assert_eq!(path.last().unwrap().clone(), NavVec3::new(1.5, 0.5, 0.0));
+
+        // too far chunks get merged without connection.
+        left.stitch(far, 0.01).unwrap();
+        assert_eq!(left.vertices().len(), 10);
+        assert_eq!(left.find_triangle_islands().len(), 2);
+    }
+
+    #[test]
+    fn test_spatial_queries() {
+        let size = 4;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+                if row < size && col < size {
+                    let i = row * (size + 1) + col;
+                    triangles.push((i, i + 1, i + size + 2).into());
+                    triangles.push((i + size + 2, i + size + 1, i).into());
+                }
+            }
+        }
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+
+        // box lying inside of single cell lower triangle.
+        assert_eq!(
+            mesh.triangles_in_aabb((1.6, 1.1, -1.0).into(), (1.9, 1.4, 1.0).into()),
+            vec![10]
+        );
+        // box above nav mesh surface.
+        assert!(mesh
+            .triangles_in_aabb((0.0, 0.0, 1.0).into(), (4.0, 4.0, 2.0).into())
+            .is_empty());
+        assert_eq!(
+            mesh.triangles_in_aabb((-1.0, -1.0, -1.0).into(), (5.0, 5.0, 1.0).into())
+                .len(),
+            32
+        );
+
+        // sphere touching cells corners around (2, 2).
+        assert_eq!(
+            mesh.triangles_in_sphere((2.0, 2.0, 0.0).into(), 0.1),
+            vec![10, 11, 13, 18, 20, 21]
+        );
+        assert!(mesh
+            .triangles_in_sphere((2.0, 2.0, 1.0).into(), 0.5)
+            .is_empty());
+        // sphere bounding box overlaps triangles that sphere itself does not.
+        assert_eq!(
+            mesh.triangles_in_sphere((0.2, 0.8, 0.0).into(), 0.15),
+            vec![1]
+        );
+
+        // unbounded half space falls back to checking every triangle, touching ones included.
+        let planes = vec![((0.0, 1.0, 0.0).into(), (0.0, 1.0, 0.0).into())];
+        assert_eq!(
+            mesh.triangles_in_convex_hull(&planes),
+            (0..16).collect::<Vec<_>>()
+        );
+        let volume = NavObstacle::aabb((0.1, 0.1, -1.0).into(), (0.9, 0.9, 1.0).into());
+        assert_eq!(mesh.triangles_in_convex_hull(&volume.planes()), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_gates() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_path_cache_capacity(16);
+        assert!(matches!(
+            mesh.add_gate("door", vec![3, 7], true),
+            Err(Error::TriangleIndexOutOfBounds(7, 4))
+        ));
+        assert!(mesh.gates().next().is_none());
+
+        mesh.add_gate("door", vec![3, 3], true).unwrap();
+        assert_eq!(mesh.gate_triangles("door"), Some([3].as_slice()));
+        assert!(mesh.find_path_triangles(1, 0).is_some());
+        assert!(mesh.find_path_triangles(1, 2).is_some());
+        assert_eq!(mesh.path_cache_len(), 2);
+
+        // closing gate drops only cached paths going through it.
+        let version = mesh.version();
+        assert_eq!(mesh.set_gate("door", false), Some(true));
+        assert_ne!(mesh.version(), version);
+        assert!(mesh.is_triangle_blocked(3));
+        assert_eq!(mesh.path_cache_len(), 1);
+        assert!(mesh.find_path_triangles(1, 2).is_none());
+        assert_eq!(mesh.set_gate("door", false), Some(false));
+        assert!(mesh.set_gate("window", false).is_none());
+
+        // obstacle and gate blocking the same triangle.
+        let id = mesh.add_obstacle(NavObstacle::aabb(
+            (1.2, 0.7, -1.0).into(),
+            (1.3, 0.8, 1.0).into(),
+        ));
+        assert_eq!(mesh.obstacle_triangles(id), Some([3].as_slice()));
+        mesh.set_gate("door", true);
+        assert!(mesh.is_triangle_blocked(3));
+        assert_eq!(mesh.path_cache_len(), 0);
+        mesh.remove_obstacle(id);
+        assert!(mesh.find_path_triangles(1, 2).is_some());
+
+        // replaced and removed closed gates unblock their triangles.
+        mesh.add_gate("door", vec![0], false).unwrap();
+        assert!(!mesh.is_triangle_blocked(3));
+        assert!(mesh.is_triangle_blocked(0));
+        assert_eq!(mesh.is_gate_open("door"), Some(false));
+        assert_eq!(mesh.remove_gate("door"), Some(vec![0]));
+        assert!(!mesh.is_triangle_blocked(0));
+        assert!(mesh.is_gate_open("door").is_none());
+    }
+
+    #[test]
+    fn test_profiles() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let human = mesh.add_profile(NavProfile::default());
+        let tank = mesh.add_profile(NavProfile {
+            height: 2.0,
+            max_slope: 0.5,
+            ..Default::default()
+        });
+        assert_eq!(mesh.profile_ids().count(), 2);
+        assert_eq!(mesh.is_triangle_passable_for_profile(2, human), Some(true));
+        assert_eq!(mesh.is_triangle_passable_for_profile(2, tank), Some(false));
+        assert_eq!(mesh.is_triangle_passable_for_profile(4, tank), None);
+        assert!(mesh.find_path_triangles_for_profile(1, 2, human).is_some());
+        assert!(mesh.find_path_triangles_for_profile(1, 2, tank).is_none());
+        assert!(mesh.find_path_triangles_for_profile(1, 0, tank).is_some());
+
+        // precomputed data follows areas changes.
+        mesh.set_area_clearance(0, 1.0);
+        assert_eq!(mesh.is_triangle_passable_for_profile(0, human), Some(true));
+        assert!(mesh.find_path_triangles_for_profile(1, 0, tank).is_none());
+        mesh.set_area_clearance(0, 3.0);
+        assert!(mesh.find_path_triangles_for_profile(1, 0, tank).is_some());
+        mesh.set_up_axis(NavUpAxis::Y);
+        assert_eq!(mesh.is_triangle_passable_for_profile(0, tank), Some(false));
+        mesh.set_up_axis(NavUpAxis::Z);
+        assert_eq!(mesh.is_triangle_passable_for_profile(0, tank), Some(true));
+
+        // area types costs.
+        const MUD: u8 = 1;
+        mesh.set_area_type(0, MUD);
+        let mut dog = NavProfile::default();
+        dog.set_cost(MUD, 4.0);
+        let dog = mesh.add_profile(dog);
+        let (_, cost) = mesh.find_path_triangles_for_profile(1, 3, human).unwrap();
+        let (_, dog_cost) = mesh.find_path_triangles_for_profile(1, 3, dog).unwrap();
+        // both path connections touch mud triangle.
+        assert!((dog_cost - cost * 4.0).abs() < 1.0e-4);
+        assert_eq!(mesh.remove_profile(dog).unwrap().cost(MUD), 4.0);
+        assert!(mesh.find_path_triangles_for_profile(1, 3, dog).is_none());
+
+        // agent radius.
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let small = mesh.add_profile(NavProfile {
+            radius: 0.25,
+            ..Default::default()
+        });
+        let big = mesh.add_profile(NavProfile {
+            radius: 0.75,
+            ..Default::default()
+        });
+        let from = (0.5, 0.5, 0.0).into();
+        let to = (1.5, 0.5, 0.0).into();
+        assert_eq!(
+            mesh.find_path_for_profile(from, to, NavQuery::Accuracy, small),
+            mesh.find_path_with_radius(from, to, NavQuery::Accuracy, 0.25)
+        );
+        assert!(mesh
+            .find_path_for_profile(from, to, NavQuery::Accuracy, big)
+            .is_none());
+    }
+
+    #[test]
+    fn test_gpu_buffers() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.5).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_area_type(2, 3);
+        mesh.set_area_flags(2, 5);
+        mesh.set_area_cost(1, 2.0);
+        mesh.add_obstacle(NavObstacle::aabb(
+            (1.2, 0.7, -1.0).into(),
+            (1.3, 0.8, 1.0).into(),
+        ));
+
+        let buffers = mesh.to_gpu_buffers();
+        assert_eq!(buffers.triangles_count(), 4);
+        assert_eq!(&buffers.positions[15..], &[2.0, 1.0, 0.5]);
+        assert_eq!(
+            buffers.adjacency,
+            vec![
+                NAV_GPU_NONE,
+                3,
+                1,
+                NAV_GPU_NONE,
+                NAV_GPU_NONE,
+                0,
+                NAV_GPU_NONE,
+                NAV_GPU_NONE,
+                3,
+                NAV_GPU_NONE,
+                0,
+                2,
+            ]
+        );
+        assert_eq!(
+            buffers.areas,
+            vec![1 << 8, 1 << 8, 3 | 5 << 8, 1 << 8 | 1 << 24]
+        );
+        assert_eq!(buffers.costs, vec![1.0, 2.0, 1.0, 1.0]);
+
+        // blocked triangle cuts triangle 2 off the rest.
+        let field = mesh.to_gpu_flow_field(0).unwrap();
+        assert_eq!(
+            field.next,
+            vec![NAV_GPU_NONE, 0, NAV_GPU_NONE, NAV_GPU_NONE]
+        );
+        assert_eq!(field.costs[2], f32::MAX);
+        assert!(field.costs[1] > 0.0 && field.costs[1] < f32::MAX);
+        assert!(mesh.to_gpu_flow_field(3).is_none());
+        assert!(mesh.to_gpu_flow_field(4).is_none());
+    }
+
+    #[test]
+    fn test_paths_batch() {
+        let size = 8;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+                if row < size && col < size {
+                    let i = row * (size + 1) + col;
+                    triangles.push((i, i + 1, i + size + 2).into());
+                    triangles.push((i + size + 2, i + size + 1, i).into());
+                }
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_path_cache_capacity(8);
+        let queries = (0..64)
+            .map(|i| {
+                let from = NavVec3::new(0.5 + (i % 8) as Scalar, 0.5, 0.0);
+                let to = NavVec3::new(7.5 - (i / 8) as Scalar, 7.5, 0.0);
+                NavQueryDesc::new(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+            })
+            .chain(std::iter::once(NavQueryDesc::new(
+                (20.0, 20.0, 0.0).into(),
+                (20.0, 20.0, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )))
+            .collect::<Vec<_>>();
+        let paths = mesh.find_paths_batch(&queries);
+        assert_eq!(paths.len(), queries.len());
+        for (desc, path) in queries.iter().zip(&paths).take(64) {
+            let expected = mesh.find_path_detailed(desc.from, desc.to, desc.query, desc.mode);
+            assert_eq!(
+                path.as_ref().map(|p| &p.points),
+                expected.as_ref().map(|p| &p.points)
+            );
+        }
+        assert!(paths.last().unwrap().is_none());
+        assert!(mesh.find_paths_batch(&[]).is_empty());
+        // batch searches reuse their state between queries, without cache hits hiding it.
+        mesh.set_path_cache_capacity(0);
+        mesh.precompute_landmarks(2);
+        for (desc, path) in queries.iter().zip(mesh.find_paths_batch(&queries)).take(64) {
+            let expected = mesh
+                .find_path_detailed(desc.from, desc.to, desc.query, desc.mode)
+                .unwrap();
+            assert!((path.unwrap().cost - expected.cost).abs() < 1.0e-4);
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        use std::sync::{Arc, Mutex};
+
+        let size = 6;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar, 0.0).into());
+                if row < size && col < size {
+                    let i = row * (size + 1) + col;
+                    triangles.push((i, i + 1, i + size + 2).into());
+                    triangles.push((i + size + 2, i + size + 1, i).into());
+                }
+            }
+        }
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_path_cache_capacity(4);
+        let last = mesh.triangles().len() - 1;
+
+        let (path, stats) = mesh.find_path_triangles_with_stats(0, last);
+        assert!(stats.found && !stats.cache_hit);
+        assert_eq!((stats.from, stats.to), (0, last));
+        assert!(stats.expansions >= path.unwrap().0.len());
+        assert!(stats.expansions <= mesh.triangles().len());
+        assert!(stats.edges_visited >= stats.expansions);
+        assert!(stats.open_peak > 0 && stats.open_peak <= stats.edges_visited + 1);
+        let (_, cached) = mesh.find_path_triangles_with_stats(0, last);
+        assert!(cached.cache_hit && cached.found);
+        assert_eq!(cached.edges_visited, 0);
+        assert_eq!(cached.open_peak, 0);
+
+        mesh.add_obstacle(NavObstacle::aabb(
+            (0.0, 0.0, -1.0).into(),
+            (6.0, 6.0, 1.0).into(),
+        ));
+        let (path, stats) = mesh.find_path_triangles_with_stats(0, last);
+        assert!(path.is_none() && !stats.found && !stats.cache_hit);
+
+        // other tests may run queries while hook is installed, so only this mesh is checked.
+        let traced = Arc::new(Mutex::new(vec![]));
+        let sink = traced.clone();
+        set_nav_trace_hook(move |stats: &NavStats| {
+            if stats.to == 71 {
+                sink.lock().unwrap().push(stats.clone());
+            }
+        });
+        let mesh = NavMesh::new(mesh.vertices().to_vec(), mesh.triangles().to_vec()).unwrap();
+        assert!(mesh
+            .find_path(
+                (0.2, 0.1, 0.0).into(),
+                (5.8, 5.9, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .is_some());
+        clear_nav_trace_hook();
+        let count = traced.lock().unwrap().len();
+        assert!(mesh.find_path_triangles(0, last).is_some());
+        let traced = traced.lock().unwrap();
+        assert_eq!(traced.len(), count);
+        assert!(traced
+            .iter()
+            .any(|stats| stats.from == 0 && stats.found && stats.expansions > 0));
+    }
+
+    #[test]
+    fn test_mesh_handle() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+        ];
+        let handle =
+            NavMeshHandle::from(NavMesh::new(vertices.clone(), triangles.clone()).unwrap());
+        let from = (0.25, 0.75, 0.0).into();
+        let to = (1.75, 0.25, 0.0).into();
+        let find = |mesh: &NavMesh| {
+            mesh.find_path_detailed(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+                .unwrap()
+        };
+
+        // query in flight keeps its snapshot while mesh gets swapped from other thread.
+        let snapshot = handle.get();
+        let path = find(&snapshot);
+        assert!(handle.is_path_valid(&path));
+        let editor = handle.clone();
+        let old = std::thread::spawn(move || {
+            let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+            mesh.set_area_cost(0, 10.0);
+            editor.swap(mesh)
+        })
+        .join()
+        .unwrap();
+        assert_eq!(old.id(), snapshot.id());
+        assert_eq!(handle.generation(), 1);
+        assert!(path.is_valid_for(&snapshot));
+        assert!(!handle.is_path_valid(&path));
+
+        // replanning on swapped mesh.
+        let path = find(&handle.get());
+        assert!(handle.is_path_valid(&path));
+        assert_eq!(path.mesh, handle.get().id());
+        assert!(path.cost > find(&snapshot).cost);
+    }
+
+    #[test]
+    fn test_nav_mesh_2d() {
+        let size = 5;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for row in 0..=size {
+            for col in 0..=size {
+                vertices.push((col as Scalar, row as Scalar));
+                if row < size && col < size {
+                    let i = row * (size + 1) + col;
+                    triangles.push((i, i + 1, i + size + 2).into());
+                    triangles.push((i + size + 2, i + size + 1, i).into());
+                }
+            }
+        }
+        let mut mesh3d = NavMesh::new(
+            vertices
+                .iter()
+                .map(|(x, y)| NavVec3::new(*x, 2.0, *y))
+                .collect(),
+            triangles.clone(),
+        )
+        .unwrap();
+        mesh3d.set_up_axis(NavUpAxis::Y);
+        // wall cell in the middle.
+        mesh3d.set_area_cost(24, 100.0);
+        mesh3d.set_area_cost(25, 100.0);
+        let mesh = NavMesh2D::new(vertices, triangles).unwrap();
+        let flat = NavMesh2D::from_nav_mesh(&mesh3d).unwrap();
+        assert_eq!(flat.nav_mesh().areas()[24].cost, 100.0);
+
+        for (index, area) in mesh.nav_mesh().areas().iter().enumerate() {
+            let center = (area.center.x, area.center.y);
+            assert_eq!(mesh.find_triangle(center), Some(index));
+            assert_eq!(flat.find_triangle(center), Some(index));
+        }
+        assert!(mesh.find_triangle((-0.5, 2.0)).is_none());
+        assert!(mesh.find_triangle((2.5, 5.5)).is_none());
+        // points on shared edges and corners belong to one of triangles touching them.
+        let found = mesh.find_triangle((2.0, 2.0)).unwrap();
+        assert!(
+            mesh.nav_mesh().triangles()[found].first == 14
+                || mesh.nav_mesh().triangles()[found].second == 14
+                || mesh.nav_mesh().triangles()[found].third == 14
+        );
+        assert_eq!(mesh.closest_point((-1.0, 2.5)).unwrap().1, (0.0, 2.5));
+        assert_eq!(mesh.closest_point((1.5, 1.2)).unwrap().1, (1.5, 1.2));
+
+        let path = mesh.find_path((0.5, 4.5), (4.5, 0.5)).unwrap();
+        let path3d = mesh
+            .nav_mesh()
+            .find_path(
+                NavMesh2D::to_3d((0.5, 4.5)),
+                NavMesh2D::to_3d((4.5, 0.5)),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(
+            path,
+            path3d
+                .into_iter()
+                .map(|v| NavMesh2D::flatten(v, NavUpAxis::Z))
+                .collect::<Vec<_>>()
+        );
+        let path = flat.find_path((0.5, 4.5), (4.5, 0.5)).unwrap();
+        assert!(path.len() > 2);
+        assert_eq!(path.first(), Some(&(0.5, 4.5)));
+        assert_eq!(path.last(), Some(&(4.5, 0.5)));
+        let path = mesh.find_path((-2.0, 0.5), (4.5, 0.5)).unwrap();
+        assert_eq!(path.first(), Some(&(0.0, 0.5)));
+        assert!(mesh.find_path((1.0, 1.0), (1.0, 1.0)).is_none());
+        assert_eq!(
+            NavMesh2D::flatten(NavMesh2D::to_3d((1.0, 2.0)), NavUpAxis::Z),
+            (1.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_path_corners() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 0.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 0.0).into(), // 5
+            (0.0, 2.0, 0.0).into(), // 6
+            (1.0, 2.0, 0.0).into(), // 7
+            (2.0, 2.0, 0.0).into(), // 8
+            (0.0, 3.0, 0.0).into(), // 9
+            (1.0, 3.0, 0.0).into(), // 10
+            (2.0, 3.0, 0.0).into(), // 11
+        ];
+        // corridor without (0, 2) cell.
+        let triangles = vec![
+            (0, 1, 4).into(),   // 0
+            (4, 3, 0).into(),   // 1
+            (1, 2, 5).into(),   // 2
+            (5, 4, 1).into(),   // 3
+            (3, 4, 7).into(),   // 4
+            (7, 6, 3).into(),   // 5
+            (4, 5, 8).into(),   // 6
+            (8, 7, 4).into(),   // 7
+            (7, 8, 11).into(),  // 8
+            (11, 10, 7).into(), // 9
+        ];
+        let mesh = NavMesh::new(vertices, triangles).unwrap();
+        let (path, corners) = mesh
+            .find_path_with_corners(
+                (0.25, 1.75, 0.0).into(),
+                (1.75, 2.75, 0.0).into(),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(
+            path.points,
+            vec![
+                (0.25, 1.75, 0.0).into(),
+                (1.0, 2.0, 0.0).into(),
+                (1.75, 2.75, 0.0).into(),
+            ]
+        );
+        assert_eq!(corners.len(), 1);
+        let corner = corners[0];
+        assert_eq!(corner.point, 1);
+        assert_eq!(corner.turn, NavPathTurn::Left);
+        assert!(
+            corner.portal.0 == path.points[1] || corner.portal.1 == path.points[1],
+            "{:?}",
+            corner
+        );
+        let position = path
+            .triangles
+            .iter()
+            .position(|index| *index == corner.triangles.0)
+            .unwrap();
+        assert_eq!(path.triangles[position + 1], corner.triangles.1);
+
+        let (path, corners) = mesh
+            .find_path_with_corners(
+                (1.75, 2.75, 0.0).into(),
+                (0.25, 1.75, 0.0).into(),
+                NavQuery::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(path.points[1], (1.0, 2.0, 0.0).into());
+        assert_eq!(
+            corners
+                .iter()
+                .map(|c| (c.point, c.turn))
+                .collect::<Vec<_>>(),
+            vec![(1, NavPathTurn::Right)]
+        );
+        let plain = mesh
+            .find_path(
+                (1.75, 2.75, 0.0).into(),
+                (0.25, 1.75, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .unwrap();
+        assert_eq!(plain, path.points);
+        assert!(mesh
+            .find_path_with_corners(
+                (0.25, 0.25, 0.0).into(),
+                (1.75, 0.75, 0.0).into(),
+                NavQuery::Accuracy,
+            )
+            .unwrap()
+            .1
+            .is_empty());
+    }
+
+    #[test]
+    fn test_surface_movement() {
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(), // 0
+            (1.0, 0.0, 0.0).into(), // 1
+            (2.0, 0.0, 1.0).into(), // 2
+            (0.0, 1.0, 0.0).into(), // 3
+            (1.0, 1.0, 0.0).into(), // 4
+            (2.0, 1.0, 1.0).into(), // 5
+            (0.0, 2.0, 0.0).into(), // 6
+            (1.0, 2.0, 0.0).into(), // 7
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(), // 0
+            (4, 3, 0).into(), // 1
+            (1, 2, 5).into(), // 2
+            (5, 4, 1).into(), // 3
+            (3, 4, 7).into(), // 4
+            (7, 6, 3).into(), // 5
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+
+        assert_eq!(
+            mesh.clamp_point((0.25, 0.75, 0.5).into()),
+            Some((0.25, 0.75, 0.0).into())
+        );
+        assert_eq!(
+            mesh.clamp_point((1.5, 1.5, 0.0).into()),
+            Some((1.0, 1.5, 0.0).into())
+        );
+        assert_eq!(
+            mesh.clamp_point((3.0, 0.5, 1.0).into()),
+            Some((2.0, 0.5, 1.0).into())
+        );
+
+        let points = mesh.project_path_onto_mesh(&[
+            (0.5, 1.5, 1.0).into(),
+            (0.5, 0.5, 1.0).into(),
+            (1.5, 0.5, 0.5).into(),
+        ]);
+        assert_eq!(
+            points,
+            vec![
+                (0.5, 1.5, 0.0).into(),
+                (0.5, 0.5, 0.0).into(),
+                (1.0, 0.5, 0.0).into(),
+                (1.5, 0.5, 0.5).into(),
+            ]
+        );
+        assert!(mesh.project_path_onto_mesh(&[]).is_empty());
+
+        // slide along top wall of slope.
+        let result = mesh
+            .move_along_surface((1.25, 0.5, 0.25).into(), (1.75, 1.5, 0.75).into())
+            .unwrap();
+        assert!(
+            result.position.same_as((1.75, 1.0, 0.75).into()),
+            "{:?}",
+            result
+        );
+        assert_eq!(result.triangles, vec![3]);
+        assert_eq!(result.triangle(), 3);
+        // free movement.
+        let result = mesh
+            .move_along_surface((0.75, 0.25, 0.0).into(), (0.75, 1.75, 0.0).into())
+            .unwrap();
+        assert!(
+            result.position.same_as((0.75, 1.75, 0.0).into()),
+            "{:?}",
+            result
+        );
+        assert_eq!(result.triangles, vec![0, 1, 4]);
+        // slide along left wall.
+        let result = mesh
+            .move_along_surface((0.5, 0.5, 0.0).into(), (-0.5, 1.5, 0.0).into())
+            .unwrap();
+        assert!(
+            result.position.same_as((0.0, 1.5, 0.0).into()),
+            "{:?}",
+            result
+        );
+        let result = mesh
+            .move_along_surface((0.5, 0.5, 0.0).into(), (0.5, 0.5, 0.0).into())
+            .unwrap();
+        assert_eq!(result.position, (0.5, 0.5, 0.0).into());
+        assert_eq!(result.triangles.len(), 1);
+
+        // blocked triangles stop movement like boundary does.
+        mesh.add_gate("slope", vec![2, 3], false).unwrap();
+        let result = mesh
+            .move_along_surface((0.5, 0.5, 0.0).into(), (1.5, 0.5, 0.0).into())
+            .unwrap();
+        assert!(
+            result.position.same_as((1.0, 0.5, 0.0).into()),
+            "{:?}",
+            result
+        );
+        assert!(!result.triangles.contains(&2) && !result.triangles.contains(&3));
+        assert_eq!(
+            mesh.clamp_point((1.5, 0.5, 0.5).into()),
+            Some((1.0, 0.5, 0.0).into())
+        );
+    }
+
+    #[test]
+    fn test_thicken() {
+        let source = NavMesh::new(
+            vec![
+                [-10.0, -10.0, 0.0].into(),
+                [10.0, -10.0, 0.0].into(),
+                [10.0, 10.0, 0.0].into(),
+                [-10.0, 10.0, 0.0].into(),
+            ],
+            vec![[0, 1, 2].into(), [2, 3, 0].into()],
+        )
+        .unwrap();
+        let thickened = source.thicken(1.0).unwrap();
+        for (a, b) in source.vertices().iter().zip(thickened.vertices().iter()) {
+            assert_relative_eq!(*b, *a + NavVec3::new(0.0, 0.0, 1.0));
+        }
+
+        let source = NavMesh::new(
+            vec![
+                [-5.0, -5.0, -5.0].into(), // 0
+                [5.0, -5.0, -5.0].into(),  // 1
+                [5.0, 5.0, -5.0].into(),   // 2
+                [-5.0, 5.0, -5.0].into(),  // 3
+                [-5.0, -5.0, 5.0].into(),  // 4
+                [5.0, -5.0, 5.0].into(),   // 5
+                [5.0, 5.0, 5.0].into(),    // 6
+                [-5.0, 5.0, 5.0].into(),   // 7
+            ],
+            vec![
+                [2, 1, 0].into(),
+                [0, 3, 2].into(),
+                [4, 5, 6].into(),
+                [6, 7, 4].into(),
+                [0, 1, 5].into(),
+                [5, 4, 0].into(),
+                [1, 2, 6].into(),
+                [6, 5, 1].into(),
+                [2, 3, 7].into(),
+                [7, 6, 2].into(),
+                [3, 0, 4].into(),
+                [4, 7, 3].into(),
+            ],
+        )
+        .unwrap();
+        let thickened = source.thicken(1.0).unwrap();
+        let expected = vec![
+            NavVec3 {
+                x: -5.333333333333333,
+                y: -5.666666666666667,
+                z: -5.666666666666667,
+            },
+            NavVec3 {
+                x: 5.816496580927726,
+                y: -5.408248290463863,
+                z: -5.408248290463863,
+            },
+            NavVec3 {
+                x: 5.333333333333333,
+                y: 5.666666666666667,
+                z: -5.666666666666667,
+            },
+            NavVec3 {
+                x: -5.816496580927726,
+                y: 5.408248290463863,
+                z: -5.408248290463863,
+            },
+            NavVec3 {
+                x: -5.666666666666667,
+                y: -5.333333333333333,
+                z: 5.666666666666667,
+            },
+            NavVec3 {
+                x: 5.408248290463863,
+                y: -5.816496580927726,
+                z: 5.408248290463863,
+            },
+            NavVec3 {
+                x: 5.666666666666667,
+                y: 5.333333333333333,
+                z: 5.666666666666667,
+            },
+            NavVec3 {
+                x: -5.408248290463863,
+                y: 5.816496580927726,
+                z: 5.408248290463863,
+            },
+        ];
+        for (a, b) in expected.iter().zip(thickened.vertices().iter()) {
+            assert_relative_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = NavGrid::new(
+            3,
+            3,
+            vec![true, true, true, true, false, true, true, true, true],
+        )
+        .unwrap();
+        let path = grid.find_path((0, 0), (1, 2)).unwrap();
+        assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2)]);
+        assert_eq!(grid.find_path((0, 0), (1, 1)), None);
+
+        let grid = NavGrid::with_connections(
+            2,
+            2,
+            vec![
+                NavGridConnection {
+                    from: (0, 0),
+                    to: (1, 0),
+                },
+                NavGridConnection {
+                    from: (1, 0),
+                    to: (1, 1),
+                },
+                NavGridConnection {
+                    from: (1, 1),
+                    to: (0, 1),
+                },
+                NavGridConnection {
+                    from: (0, 1),
+                    to: (0, 0),
+                },
+            ],
+        )
+        .unwrap();
+        let path = grid.find_path((0, 0), (0, 1)).unwrap();
+        assert_eq!(path, vec![(0, 0), (1, 0), (1, 1), (0, 1)]);
+        let mut islands = grid.find_islands();
+        for island in &mut islands {
+            island.sort();
+        }
+        assert_eq!(islands, vec![vec![(0, 0), (0, 1), (1, 0), (1, 1)]]);
+
+        let grid = NavGrid::new(
+            3,
+            3,
+            vec![true, true, true, false, false, false, true, true, true],
+        )
+        .unwrap();
+        let mut islands = grid.find_islands();
+        for island in &mut islands {
+            island.sort();
+        }
+        assert_eq!(
+            islands,
+            vec![vec![(0, 0), (1, 0), (2, 0)], vec![(0, 2), (1, 2), (2, 2)]]
+        );
+
+        let grid = NavFreeGrid::new(vec![
+            NavFreeGridConnection {
+                from: (0, 0),
+                to: (0, 2),
+            },
+            NavFreeGridConnection {
+                from: (0, 2),
+                to: (-1, -1),
+            },
+        ]);
+        let path = grid.find_path((0, 0), (-1, -1)).unwrap();
+        assert_eq!(path, vec![(0, 0), (0, 2), (-1, -1)]);
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = NavSparseGrid::new(4);
+        assert_eq!(grid.paint_walkable((-5, -1), (5, -1), true), 11);
+        assert_eq!(grid.paint_walkable((-5, -1), (-3, -1), true), 0);
+        assert_eq!(grid.chunks_count(), 4);
+        assert_eq!(grid.cells_count(), 11);
+        assert_eq!(grid.chunk_coord(-5, -1), (-2, -1));
+        assert_eq!(grid.chunk_coord(4, 3), (1, 0));
+        let mut cells = grid.cells().collect::<Vec<_>>();
+        cells.sort();
+        assert_eq!(cells, (-5..=5).map(|col| (col, -1)).collect::<Vec<_>>());
+        assert!(grid.is_walkable(-5, -1));
+        assert!(!grid.is_walkable(-5, 0));
+        assert_eq!(grid.cell_cost(0, 0), None);
+        assert_eq!(grid.set_cell_cost(0, 0, 2.0), None);
+
+        let path = grid.find_path((-5, -1), (5, -1)).unwrap();
+        assert_eq!(path.len(), 11);
+        assert_eq!(grid.find_path((-5, -1), (6, -1)), None);
+
+        // detour over second row around expensive cell.
+        grid.paint_walkable((-1, -2), (1, -2), true);
+        assert_eq!(grid.set_cell_cost(0, -1, 10.0), Some(1.0));
+        let path = grid.find_path((-2, -1), (2, -1)).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                (-2, -1),
+                (-1, -1),
+                (-1, -2),
+                (0, -2),
+                (1, -2),
+                (1, -1),
+                (2, -1)
+            ]
+        );
+        assert!(grid
+            .find_path_custom((-2, -1), (2, -1), |_, to| to.1 == -1)
+            .unwrap()
+            .contains(&(0, -1)));
+
+        // chunks get released with their last walkable cell.
+        assert!(grid.set_walkable(5, -1, false));
+        assert!(!grid.set_walkable(5, -1, false));
+        assert_eq!(grid.chunks_count(), 4);
+        assert!(grid.set_walkable(4, -1, false));
+        assert_eq!(grid.chunks_count(), 3);
+        assert!(grid.clear_chunk((-2, -1)));
+        assert!(!grid.clear_chunk((-2, -1)));
+        assert!(!grid.is_walkable(-5, -1));
+        assert!(grid.is_walkable(-4, -1));
+
+        let mut grid = NavSparseGrid::with_neighborhood(
+            8,
+            NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid),
+        );
+        grid.paint_walkable((1_000_000, 1_000_000), (1_000_002, 1_000_002), true);
+        grid.set_walkable(1_000_001, 1_000_000, false);
+        let mut neighbors = grid.neighbors(1_000_000, 1_000_000).collect::<Vec<_>>();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![(1_000_000, 1_000_001)]);
+        let path = grid
+            .find_path((1_000_000, 1_000_000), (1_000_002, 1_000_000))
+            .unwrap();
+        assert_eq!(
+            path,
+            vec![
+                (1_000_000, 1_000_000),
+                (1_000_000, 1_000_001),
+                (1_000_001, 1_000_001),
+                (1_000_002, 1_000_001),
+                (1_000_002, 1_000_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grid_navmesh_conversion() {
+        #[rustfmt::skip]
+        let cells = vec![
+            true,  true,  true,  true,  false,
+            true,  false, false, true,  false,
+            true,  false, false, true,  false,
+            true,  true,  true,  true,  false,
+            false, false, false, false, true,
+        ];
+        let grid = NavGrid::new(5, 5, cells.clone()).unwrap();
+        let mesh = grid.to_navmesh(1.0).unwrap();
+        let size = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+        assert!((size - 13.0).abs() < 1.0e-4);
+        // cell touching ring only by corner is separate island.
+        assert_eq!(mesh.find_triangle_islands().len(), 2);
+        assert!(mesh
+            .find_path(
+                (0.5, 0.5, 0.0).into(),
+                (3.5, 3.5, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            )
+            .is_some());
+        assert!(mesh
+            .find_path(
+                (0.5, 0.5, 0.0).into(),
+                (4.5, 4.5, 0.0).into(),
+                NavQuery::Closest,
+                NavPathMode::Accuracy,
+            )
+            .is_none());
+        assert!(mesh
+            .find_triangle_containing((2.0, 2.0, 0.0).into(), 0.1)
+            .is_none());
+
+        let raster = mesh.rasterize_to_grid(1.0).unwrap();
+        assert_eq!(raster.grid.cells(), cells.as_slice());
+        assert_eq!(raster.cell((3.5, 0.5, 0.0).into()), Some((3, 0)));
+        assert_eq!(raster.cell((-0.5, 0.5, 0.0).into()), None);
+        let raster = mesh.rasterize_to_grid(0.5).unwrap();
+        assert_eq!(raster.grid.cells().iter().filter(|cell| **cell).count(), 52);
+
+        // Y-up mesh with blocked triangle and expensive area.
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (2.0, 0.0, -1.0).into(),
+            (0.0, 0.0, -1.0).into(),
+            (4.0, 0.0, 0.0).into(),
+            (4.0, 0.0, -1.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 2).into(),
+            (2, 3, 0).into(),
+            (1, 4, 5).into(),
+            (5, 2, 1).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        mesh.set_up_axis(NavUpAxis::Y);
+        mesh.set_area_cost(0, 3.0);
+        mesh.set_area_cost(1, 3.0);
+        mesh.add_gate("door", vec![2, 3], false).unwrap();
+        let raster = mesh.rasterize_to_grid(1.0).unwrap();
+        assert_eq!(raster.grid.cells(), &[true, true, false, false]);
+        assert_eq!(raster.grid.cell_cost(0, 0), Some(3.0));
+        assert_eq!(raster.cell((0.5, 0.0, -0.5).into()), Some((0, 0)));
+        assert_eq!(raster.cell_center(1, 0), (1.5, 0.0, -0.5).into());
+
+        assert!(matches!(
+            NavGrid::new(2, 1, vec![false, false])
+                .unwrap()
+                .to_navmesh(1.0),
+            Err(Error::NoWalkableSurface)
+        ));
+    }
+
+    #[test]
+    fn test_grid_any_angle() {
+        let length = |path: &[(usize, usize)]| {
+            path.windows(2)
+                .map(|pair| {
+                    let dx = pair[0].0 as Scalar - pair[1].0 as Scalar;
+                    let dy = pair[0].1 as Scalar - pair[1].1 as Scalar;
+                    (dx * dx + dy * dy).sqrt()
+                })
+                .sum::<Scalar>()
+        };
+        let grid = NavGrid::with_neighborhood(
+            10,
+            10,
+            vec![true; 100],
+            NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid),
+        )
+        .unwrap();
+        assert_eq!(
+            grid.find_path_any_angle((0, 0), (9, 4)).unwrap(),
+            vec![(0, 0), (9, 4)]
+        );
+
+        // wall with gap at the top.
+        let mut cells = vec![true; 100];
+        for row in 0..8 {
+            cells[row * 10 + 5] = false;
+        }
+        let grid = NavGrid::new(10, 10, cells.clone()).unwrap();
+        let path = grid.find_path_any_angle((0, 0), (9, 0)).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(9, 0)));
+        assert!(path.len() <= 4, "{:?}", path);
+        for pair in path.windows(2) {
+            assert!(grid.has_line_of_sight(pair[0], pair[1]));
+        }
+        let plain = grid.find_path((0, 0), (9, 0)).unwrap();
+        assert!(length(&path) < length(&plain) - 1.0);
+        assert!(!grid.has_line_of_sight((0, 0), (9, 0)));
+        assert!(grid.has_line_of_sight((9, 0), (9, 0)));
+        assert!(grid.find_path_any_angle((0, 0), (5, 0)).is_none());
+        assert!(grid.find_path_any_angle((0, 0), (10, 0)).is_none());
+
+        // expensive swamp is not crossed by straight lines.
+        let mut grid = NavGrid::new(10, 10, vec![true; 100]).unwrap();
+        grid.paint_cost(NavGridRect::new((3, 0), (6, 6)), 10.0);
+        let path = grid.find_path_any_angle((0, 0), (9, 0)).unwrap();
+        for pair in path.windows(2) {
+            assert!(grid.has_line_of_sight(pair[0], pair[1]));
+        }
+        assert!(path.iter().any(|(_, row)| *row >= 7), "{:?}", path);
+
+        // hex grid gets plain path.
+        let grid =
+            NavGrid::with_neighborhood(4, 4, vec![true; 16], NavGridNeighborhood::Hex).unwrap();
+        assert_eq!(
+            grid.find_path_any_angle((0, 0), (3, 0)),
+            grid.find_path((0, 0), (3, 0))
+        );
+    }
+
+    #[test]
+    fn test_dstar_lite() {
+        let size = 20;
+        let mut grid = NavGrid::with_neighborhood(
+            size,
+            size,
+            vec![true; size * size],
+            NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid),
+        )
+        .unwrap();
+        let path_cost = |grid: &NavGrid, path: &[usize]| {
+            path.windows(2)
+                .map(|pair| {
+                    grid.successors(pair[0])
+                        .into_iter()
+                        .find(|(next, _)| *next == pair[1])
+                        .unwrap()
+                        .1
+                })
+                .sum::<Scalar>()
+        };
+        let reference = |grid: &NavGrid, from: usize, to: usize| {
+            NavDStarLite::new(grid, from, to).find_path(grid).unwrap()
+        };
+        let mut start = grid.index(0, 10).unwrap();
+        let goal = grid.index(19, 10).unwrap();
+        let mut query = NavDStarLite::new(&grid, start, goal);
+        let path = query.find_path(&grid).unwrap();
+        assert_eq!(path.len(), 20);
+        let initial = query.last_expansions();
+        assert!(initial > 0);
+        assert!(query.find_path(&grid).is_some());
+        assert_eq!(query.last_expansions(), 0);
+
+        // wall moves in front of agent following path, step by step.
+        for (step, node) in path.iter().enumerate().take(10).skip(1) {
+            start = *node;
+            query.set_start(&grid, start);
+            if step == 5 {
+                let mut changed = vec![];
+                for row in 5..16 {
+                    grid.set_cell_cost(12, row, 1000.0);
+                    changed.push(grid.index(12, row).unwrap());
+                }
+                query.notify_changed(&grid, &changed);
+            }
+            let repaired = query.find_path(&grid).unwrap();
+            assert_eq!(repaired.first(), Some(&start));
+            assert_eq!(repaired.last(), Some(&goal));
+            let expected = reference(&grid, start, goal);
+            assert!(
+                (path_cost(&grid, &repaired) - path_cost(&grid, &expected)).abs() < 1.0e-3,
+                "{:?} {:?}",
+                repaired,
+                expected
+            );
+            if step == 5 {
+                assert!(repaired
+                    .iter()
+                    .all(|index| grid.coord(*index).unwrap().0 != 12
+                        || !(5..16).contains(&grid.coord(*index).unwrap().1)));
+            }
+        }
+
+        // wall goes away.
+        let changed = (5..16)
+            .map(|row| {
+                grid.set_cell_cost(12, row, 1.0);
+                grid.index(12, row).unwrap()
+            })
+            .collect::<Vec<_>>();
+        query.notify_changed(&grid, &changed);
+        let repaired = query.find_path(&grid).unwrap();
+        let expected = reference(&grid, start, goal);
+        assert!((path_cost(&grid, &repaired) - path_cost(&grid, &expected)).abs() < 1.0e-3);
+
+        let mut query = NavDStarLite::new(&grid, goal, goal);
+        assert_eq!(query.find_path(&grid), Some(vec![goal]));
+
+        // triangles blocked by gate.
+        let vertices = vec![
+            (0.0, 0.0, 0.0).into(),
+            (1.0, 0.0, 0.0).into(),
+            (2.0, 0.0, 0.0).into(),
+            (0.0, 1.0, 0.0).into(),
+            (1.0, 1.0, 0.0).into(),
+            (2.0, 1.0, 0.0).into(),
+        ];
+        let triangles = vec![
+            (0, 1, 4).into(),
+            (4, 3, 0).into(),
+            (1, 2, 5).into(),
+            (5, 4, 1).into(),
+        ];
+        let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+        let mut query = NavDStarLite::new(&mesh, 1, 2);
+        assert_eq!(query.find_path(&mesh), Some(vec![1, 0, 3, 2]));
+        mesh.add_gate("door", vec![3], false).unwrap();
+        query.notify_changed(&mesh, &[3]);
+        assert_eq!(query.find_path(&mesh), None);
+        mesh.set_gate("door", true);
+        query.notify_changed(&mesh, &[3]);
+        assert_eq!(query.find_path(&mesh), Some(vec![1, 0, 3, 2]));
+    }
+
+    #[test]
+    fn test_grid_costs() {
+        let mut grid = NavGrid::new(5, 3, vec![true; 15]).unwrap();
+        // swamp in the middle row and cheap road along bottom row.
+        assert_eq!(grid.paint_cost(NavGridRect::new((4, 1), (0, 1)), 4.0), 5);
+        assert_eq!(grid.paint_cost(NavGridRect::new((0, 2), (10, 2)), 0.25), 5);
+        assert_eq!(grid.cell_cost(2, 1), Some(4.0));
+        assert_eq!(grid.cell_cost(2, 2), Some(0.25));
+        assert_eq!(grid.cell_cost(5, 2), None);
+        assert_eq!(grid.paint_cost(NavGridRect::new((7, 7), (9, 9)), 2.0), 0);
+        let path = grid.find_path((0, 0), (4, 0)).unwrap();
+        assert_eq!(path, vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)]);
+        let path = grid.find_path((0, 1), (4, 1)).unwrap();
+        assert_eq!(
+            path,
+            vec![(0, 1), (0, 2), (1, 2), (2, 2), (3, 2), (4, 2), (4, 1)]
+        );
+    }
+
+    #[test]
+    fn test_grid_neighborhood() {
+        let grid = NavGrid::with_neighborhood(
+            3,
+            3,
+            vec![true; 9],
+            NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid),
+        )
+        .unwrap();
+        let path = grid.find_path((0, 0), (2, 2)).unwrap();
+        assert_eq!(path, vec![(0, 0), (1, 1), (2, 2)]);
+
+        let cells = vec![true, false, false, true];
+        for (cutting, expected) in [
+            (NavGridCornerCutting::Allow, Some(vec![(0, 0), (1, 1)])),
+            (NavGridCornerCutting::IfOneFree, None),
+            (NavGridCornerCutting::Forbid, None),
+        ] {
+            let grid = NavGrid::with_neighborhood(
+                2,
+                2,
+                cells.clone(),
+                NavGridNeighborhood::Diagonal(cutting),
+            )
+            .unwrap();
+            assert_eq!(grid.find_path((0, 0), (1, 1)), expected);
+        }
+
+        let grid =
+            NavGrid::with_neighborhood(3, 3, vec![true; 9], NavGridNeighborhood::Hex).unwrap();
+        let mut neighbors = grid.neighbors(1, 1).unwrap().collect::<Vec<_>>();
+        neighbors.sort();
+        assert_eq!(
+            neighbors,
+            vec![(0, 1), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)]
+        );
+        let mut neighbors = grid.neighbors(1, 0).unwrap().collect::<Vec<_>>();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![(0, 0), (0, 1), (1, 1), (2, 0)]);
+
+        let knight = NavGridNeighborhood::Custom(vec![
+            (1, 2),
+            (2, 1),
+            (-1, 2),
+            (-2, 1),
+            (1, -2),
+            (2, -1),
+            (-1, -2),
+            (-2, -1),
+        ]);
+        let grid = NavGrid::with_neighborhood(3, 3, vec![true; 9], knight.clone()).unwrap();
+        assert_eq!(grid.neighborhood(), Some(&knight));
+        assert_eq!(grid.find_path((0, 0), (2, 1)), Some(vec![(0, 0), (2, 1)]));
+        assert_eq!(grid.find_path((0, 0), (1, 1)), None);
+    }
+
+    #[test]
+    fn test_grid_jump_points() {
+        let length = |path: &[(usize, usize)]| {
+            path.windows(2)
+                .map(|pair| {
+                    let dx = pair[0].0 as Scalar - pair[1].0 as Scalar;
+                    let dy = pair[0].1 as Scalar - pair[1].1 as Scalar;
+                    (dx * dx + dy * dy).sqrt()
+                })
+                .sum::<Scalar>()
+        };
+        let mut seed = 13u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 16) % 100
+        };
+        let (cols, rows) = (24, 16);
+        for neighborhood in [
+            NavGridNeighborhood::Orthogonal,
+            NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid),
+        ] {
+            for _ in 0..20 {
+                let cells = (0..(cols * rows)).map(|_| random() >= 30).collect();
+                let grid =
+                    NavGrid::with_neighborhood(cols, rows, cells, neighborhood.clone()).unwrap();
+                assert!(grid.supports_jump_point_search());
+                for _ in 0..10 {
+                    let from = (random() as usize % cols, random() as usize % rows);
+                    let to = (random() as usize % cols, random() as usize % rows);
+                    let expected = grid.find_path_with_query(from, to, NavGridQuery::AStar);
+                    let path = grid.find_path_with_query(from, to, NavGridQuery::JumpPointSearch);
+                    match (path, expected) {
+                        (Some(path), Some(expected)) => {
+                            assert_eq!(path.first(), Some(&from));
+                            assert_eq!(path.last(), Some(&to));
+                            for pair in path.windows(2) {
+                                assert!(grid
+                                    .neighbors(pair[0].0, pair[0].1)
+                                    .unwrap()
+                                    .any(|cell| cell == pair[1]));
+                            }
+                            assert_relative_eq!(length(&path), length(&expected), epsilon = 1.0e-3);
+                        }
+                        (None, None) => {}
+                        (path, expected) => panic!("{:?} vs {:?}", path, expected),
+                    }
+                }
+            }
+        }
+
+        let mut grid = NavGrid::new(3, 3, vec![true; 9]).unwrap();
+        assert!(grid.supports_jump_point_search());
+        grid.set_cell_cost(1, 1, 2.0);
+        assert!(!grid.has_uniform_costs());
+        assert!(!grid.supports_jump_point_search());
+        let grid = NavGrid::with_neighborhood(
+            3,
+            3,
+            vec![true; 9],
+            NavGridNeighborhood::Diagonal(NavGridCornerCutting::Allow),
+        )
+        .unwrap();
+        assert!(!grid.supports_jump_point_search());
     }
 
     #[test]
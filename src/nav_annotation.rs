@@ -0,0 +1,279 @@
+use crate::{NavMesh, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use typid::ID;
+
+/// Nav annotation identifier.
+pub type NavAnnotationID = ID<NavAnnotation>;
+
+/// Place described by annotation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavAnnotationShape {
+    Point(NavVec3),
+    /// Axis aligned box region.
+    Region {
+        min: NavVec3,
+        max: NavVec3,
+    },
+}
+
+impl NavAnnotationShape {
+    /// Point of shape closest to given point.
+    pub fn closest_point(&self, point: NavVec3) -> NavVec3 {
+        match self {
+            Self::Point(position) => *position,
+            Self::Region { min, max } => point.max(*min).min(*max),
+        }
+    }
+
+    /// Shape center.
+    pub fn center(&self) -> NavVec3 {
+        match self {
+            Self::Point(position) => *position,
+            Self::Region { min, max } => (*min + *max) * 0.5,
+        }
+    }
+}
+
+/// Named location attached to nav mesh, like spawn point, cover spot or vaultable ledge, that
+/// gameplay code can query by kind and position.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavAnnotation {
+    /// Annotation kind, for example `"cover"` or `"spawn"`.
+    pub kind: String,
+    /// Annotation name, can be empty.
+    pub name: String,
+    pub shape: NavAnnotationShape,
+    /// Direction annotation faces, for example where cover protects from.
+    pub direction: Option<NavVec3>,
+    /// Annotation on the other end of smart object link (for example ledge landing spot of
+    /// vaultable ledge start), see `NavMesh::link_annotations`.
+    pub link: Option<NavAnnotationID>,
+}
+
+impl NavAnnotation {
+    /// Create point annotation.
+    ///
+    /// # Arguments
+    /// * `kind` - annotation kind.
+    /// * `name` - annotation name.
+    /// * `position` - annotation position.
+    pub fn point(kind: impl Into<String>, name: impl Into<String>, position: NavVec3) -> Self {
+        Self {
+            kind: kind.into(),
+            name: name.into(),
+            shape: NavAnnotationShape::Point(position),
+            direction: None,
+            link: None,
+        }
+    }
+
+    /// Create axis aligned box region annotation.
+    ///
+    /// # Arguments
+    /// * `kind` - annotation kind.
+    /// * `name` - annotation name.
+    /// * `min` - box minimum corner.
+    /// * `max` - box maximum corner.
+    pub fn region(
+        kind: impl Into<String>,
+        name: impl Into<String>,
+        min: NavVec3,
+        max: NavVec3,
+    ) -> Self {
+        Self {
+            kind: kind.into(),
+            name: name.into(),
+            shape: NavAnnotationShape::Region {
+                min: min.min(max),
+                max: min.max(max),
+            },
+            direction: None,
+            link: None,
+        }
+    }
+
+    /// Distance between annotation shape and given point.
+    #[inline]
+    pub fn distance(&self, point: NavVec3) -> Scalar {
+        self.shape.closest_point(point).distance(point)
+    }
+}
+
+impl NavMesh {
+    /// Attach annotation to nav mesh. Annotations are stored (and serialized) together with nav
+    /// mesh.
+    ///
+    /// # Arguments
+    /// * `annotation` - annotation.
+    ///
+    /// # Returns
+    /// Annotation identifier.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let cover = mesh.add_annotation(NavAnnotation::point("cover", "", (1.5, 0.5, 0.0).into()));
+    /// mesh.add_annotation(NavAnnotation::point("spawn", "start", (0.5, 0.5, 0.0).into()));
+    /// assert_eq!(
+    ///     mesh.nearest_annotation("cover", (0.5, 0.5, 0.0).into(), 2.0),
+    ///     Some(cover),
+    /// );
+    /// assert_eq!(
+    ///     mesh.nearest_annotation("cover", (0.5, 0.5, 0.0).into(), 0.5),
+    ///     None,
+    /// );
+    /// ```
+    pub fn add_annotation(&mut self, annotation: NavAnnotation) -> NavAnnotationID {
+        let id = NavAnnotationID::new();
+        self.annotations_map_mut().insert(id, annotation);
+        id
+    }
+
+    /// Remove annotation, clearing links of annotations linked to it.
+    ///
+    /// # Returns
+    /// `Some` with removed annotation or `None` if there was no annotation with given identifier.
+    pub fn remove_annotation(&mut self, id: NavAnnotationID) -> Option<NavAnnotation> {
+        let annotations = self.annotations_map_mut();
+        let result = annotations.remove(&id)?;
+        for annotation in annotations.values_mut() {
+            if annotation.link == Some(id) {
+                annotation.link = None;
+            }
+        }
+        Some(result)
+    }
+
+    #[inline]
+    pub fn annotation(&self, id: NavAnnotationID) -> Option<&NavAnnotation> {
+        self.annotations_map().get(&id)
+    }
+
+    #[inline]
+    pub fn annotation_mut(&mut self, id: NavAnnotationID) -> Option<&mut NavAnnotation> {
+        self.annotations_map_mut().get_mut(&id)
+    }
+
+    /// Iterator over all annotations, in no particular order.
+    #[inline]
+    pub fn annotations(&self) -> impl Iterator<Item = (NavAnnotationID, &NavAnnotation)> {
+        self.annotations_map().iter().map(|(id, item)| (*id, item))
+    }
+
+    #[inline]
+    pub fn annotations_count(&self) -> usize {
+        self.annotations_map().len()
+    }
+
+    /// Find annotation by its name.
+    pub fn find_annotation(&self, name: &str) -> Option<NavAnnotationID> {
+        self.annotations()
+            .find(|(_, annotation)| annotation.name == name)
+            .map(|(id, _)| id)
+    }
+
+    /// Find annotations of given kind within radius from point, ordered by distance.
+    ///
+    /// # Arguments
+    /// * `kind` - annotations kind, `None` accepts all kinds.
+    /// * `position` - query point.
+    /// * `radius` - maximal distance of annotation shape from query point.
+    pub fn annotations_in_radius(
+        &self,
+        kind: Option<&str>,
+        position: NavVec3,
+        radius: Scalar,
+    ) -> Vec<NavAnnotationID> {
+        let mut result = self
+            .annotations()
+            .filter(|(_, annotation)| kind.map(|kind| annotation.kind == kind).unwrap_or(true))
+            .map(|(id, annotation)| (id, annotation.distance(position)))
+            .filter(|(_, distance)| *distance <= radius)
+            .collect::<Vec<_>>();
+        result.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        result.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Find annotation of given kind closest to point.
+    ///
+    /// # Arguments
+    /// * `kind` - annotation kind.
+    /// * `position` - query point.
+    /// * `radius` - maximal distance of annotation shape from query point.
+    pub fn nearest_annotation(
+        &self,
+        kind: &str,
+        position: NavVec3,
+        radius: Scalar,
+    ) -> Option<NavAnnotationID> {
+        self.annotations()
+            .filter(|(_, annotation)| annotation.kind == kind)
+            .map(|(id, annotation)| (id, annotation.distance(position)))
+            .filter(|(_, distance)| *distance <= radius)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(id, _)| id)
+    }
+
+    /// Link annotation to other one, describing smart object connection (like ledge vault or
+    /// ladder) that agents traverse between two annotated places off the nav mesh surface.
+    ///
+    /// # Arguments
+    /// * `from` - annotation at connection start.
+    /// * `to` - annotation at connection end.
+    /// * `bidirectional` - also link `to` annotation back to `from` one.
+    ///
+    /// # Returns
+    /// `true` if both annotations exist.
+    pub fn link_annotations(
+        &mut self,
+        from: NavAnnotationID,
+        to: NavAnnotationID,
+        bidirectional: bool,
+    ) -> bool {
+        let annotations = self.annotations_map_mut();
+        if !annotations.contains_key(&from) || !annotations.contains_key(&to) {
+            return false;
+        }
+        annotations.get_mut(&from).unwrap().link = Some(to);
+        if bidirectional {
+            annotations.get_mut(&to).unwrap().link = Some(from);
+        }
+        true
+    }
+
+    /// Find annotations linked to given one, that is annotation it links to and annotations
+    /// linking to it.
+    pub fn linked_annotations(&self, id: NavAnnotationID) -> Vec<NavAnnotationID> {
+        let mut result = self
+            .annotation(id)
+            .and_then(|annotation| annotation.link)
+            .into_iter()
+            .collect::<Vec<_>>();
+        for (other, annotation) in self.annotations() {
+            if annotation.link == Some(id) && !result.contains(&other) {
+                result.push(other);
+            }
+        }
+        result
+    }
+}
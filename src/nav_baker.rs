@@ -0,0 +1,373 @@
+use crate::{
+    Error, NavMesh, NavResult, NavTriangle, NavUpAxis, NavVec3, Scalar, SCALAR_MAX, ZERO_TRESHOLD,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Nav mesh baking settings.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshBakeSettings {
+    /// Size of voxel cell on ground plane.
+    pub cell_size: Scalar,
+    /// Size of voxel cell along up axis.
+    pub cell_height: Scalar,
+    /// Minimal free space above walkable surface required by agent.
+    pub agent_height: Scalar,
+    /// Maximal slope (in radians) of walkable surface.
+    pub agent_max_slope: Scalar,
+    /// Maximal height difference between neighbor cells that agent can step over.
+    pub agent_max_climb: Scalar,
+    /// Walkable regions made of less cells than this value are removed.
+    pub min_region_area: usize,
+    /// Up axis of level geometry, also used by baked nav mesh.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub up_axis: NavUpAxis,
+}
+
+impl Default for NavMeshBakeSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: 0.3,
+            cell_height: 0.2,
+            agent_height: 2.0,
+            agent_max_slope: 45.0_f64.to_radians() as Scalar,
+            agent_max_climb: 0.9,
+            min_region_area: 8,
+            up_axis: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Span {
+    min: i32,
+    max: i32,
+    walkable: bool,
+}
+
+/// Produces walkable nav mesh from arbitrary level geometry, by voxelizing it into height field,
+/// filtering walkable surface and building regions out of it.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshBaker {
+    settings: NavMeshBakeSettings,
+}
+
+impl NavMeshBaker {
+    pub fn new(settings: NavMeshBakeSettings) -> Self {
+        Self { settings }
+    }
+
+    #[inline]
+    pub fn settings(&self) -> &NavMeshBakeSettings {
+        &self.settings
+    }
+
+    /// Bake nav mesh from level geometry.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of level geometry vertices points.
+    /// * `triangles` - list of level geometry triangles.
+    ///
+    /// # Returns
+    /// `Ok` with walkable nav mesh or `Err` if there is no walkable surface or input data is
+    /// invalid.
+    ///
+    /// Areas get clearance measured to ceiling geometry above them.
+    pub fn bake(&self, vertices: &[NavVec3], triangles: &[NavTriangle]) -> NavResult<NavMesh> {
+        let (vertices, triangles, clearances) = self.bake_surface(vertices, triangles)?;
+        let mut result = NavMesh::new(vertices, triangles)?;
+        result.set_up_axis(self.settings.up_axis);
+        for (index, clearance) in clearances.into_iter().enumerate() {
+            result.set_area_clearance(index, clearance);
+        }
+        Ok(result)
+    }
+
+    /// Bake walkable surface geometry from level geometry.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of level geometry vertices points.
+    /// * `triangles` - list of level geometry triangles.
+    ///
+    /// # Returns
+    /// `Ok` with walkable surface vertices and triangles or `Err` if there is no walkable surface
+    /// or input data is invalid.
+    pub fn bake_geometry(
+        &self,
+        vertices: &[NavVec3],
+        triangles: &[NavTriangle],
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>)> {
+        let (vertices, triangles, _) = self.bake_surface(vertices, triangles)?;
+        Ok((vertices, triangles))
+    }
+
+    // (vertices, triangles, triangles clearance)
+    fn bake_surface(
+        &self,
+        vertices: &[NavVec3],
+        triangles: &[NavTriangle],
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>, Vec<Scalar>)> {
+        for (i, triangle) in triangles.iter().enumerate() {
+            for (local, index) in [triangle.first, triangle.second, triangle.third]
+                .iter()
+                .enumerate()
+            {
+                if *index as usize >= vertices.len() {
+                    return Err(Error::TriangleVerticeIndexOutOfBounds(
+                        i as u32,
+                        local as u8,
+                        *index,
+                    ));
+                }
+            }
+        }
+        if vertices.is_empty() || triangles.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        // height field is built in Z-up space.
+        let up_axis = self.settings.up_axis;
+        let vertices = vertices
+            .iter()
+            .map(|v| up_axis.rotate_to_z_up(*v))
+            .collect::<Vec<_>>();
+        let cs = self.settings.cell_size.max(ZERO_TRESHOLD);
+        let ch = self.settings.cell_height.max(ZERO_TRESHOLD);
+        let (min, max) = vertices
+            .iter()
+            .fold((vertices[0], vertices[0]), |(min, max), v| {
+                (min.min(*v), max.max(*v))
+            });
+        let cols = (((max.x - min.x) / cs).ceil() as usize).max(1);
+        let rows = (((max.y - min.y) / cs).ceil() as usize).max(1);
+        let mut columns = vec![Vec::<Span>::new(); cols * rows];
+        let walkable_normal = self.settings.agent_max_slope.cos();
+        let merge_treshold = (self.settings.agent_max_climb / ch).floor() as i32;
+
+        // rasterize triangles into height field.
+        for triangle in triangles {
+            let a = vertices[triangle.first as usize];
+            let b = vertices[triangle.second as usize];
+            let c = vertices[triangle.third as usize];
+            let normal = (b - a).cross(c - a).normalize();
+            let walkable = normal.z >= walkable_normal;
+            let tmin = a.min(b).min(c);
+            let tmax = a.max(b).max(c);
+            let col_a = (((tmin.x - min.x) / cs).floor() as isize).max(0) as usize;
+            let col_b = ((((tmax.x - min.x) / cs).floor() as isize).max(0) as usize).min(cols - 1);
+            let row_a = (((tmin.y - min.y) / cs).floor() as isize).max(0) as usize;
+            let row_b = ((((tmax.y - min.y) / cs).floor() as isize).max(0) as usize).min(rows - 1);
+            for row in row_a..=row_b {
+                for col in col_a..=col_b {
+                    let x0 = min.x + col as Scalar * cs;
+                    let y0 = min.y + row as Scalar * cs;
+                    let polygon = clip_axis(vec![a, b, c], 0, x0, false);
+                    let polygon = clip_axis(polygon, 0, x0 + cs, true);
+                    let polygon = clip_axis(polygon, 1, y0, false);
+                    let polygon = clip_axis(polygon, 1, y0 + cs, true);
+                    if polygon.len() < 3 {
+                        continue;
+                    }
+                    let (zmin, zmax) = polygon
+                        .iter()
+                        .fold((Scalar::INFINITY, -Scalar::INFINITY), |(zmin, zmax), p| {
+                            (zmin.min(p.z), zmax.max(p.z))
+                        });
+                    let smin = ((zmin - min.z) / ch).floor() as i32;
+                    let smax = (((zmax - min.z) / ch).ceil() as i32).max(smin);
+                    let span = Span {
+                        min: smin,
+                        max: smax,
+                        walkable,
+                    };
+                    add_span(&mut columns[row * cols + col], span, merge_treshold);
+                }
+            }
+        }
+
+        // find walkable spans with enough free space above them.
+        let agent_height = (self.settings.agent_height / ch).ceil() as i32;
+        let climb = (self.settings.agent_max_climb / ch).floor() as i32;
+        // (column index, floor, ceiling)
+        let mut nodes = vec![];
+        let mut column_nodes = vec![Vec::<usize>::new(); cols * rows];
+        for (index, column) in columns.iter().enumerate() {
+            for (i, span) in column.iter().enumerate() {
+                if !span.walkable {
+                    continue;
+                }
+                let ceiling = column.get(i + 1).map(|s| s.min).unwrap_or(i32::MAX);
+                if ceiling.saturating_sub(span.max) >= agent_height {
+                    column_nodes[index].push(nodes.len());
+                    nodes.push((index, span.max, ceiling));
+                }
+            }
+        }
+
+        // build regions of connected walkable spans.
+        let connected = |a: (usize, i32, i32), b: (usize, i32, i32)| {
+            (a.1 - b.1).abs() <= climb && a.2.min(b.2).saturating_sub(a.1.max(b.1)) >= agent_height
+        };
+        let neighbors = |index: usize| {
+            let col = index % cols;
+            let row = index / cols;
+            let mut result = Vec::with_capacity(4);
+            if col > 0 {
+                result.push(index - 1);
+            }
+            if col + 1 < cols {
+                result.push(index + 1);
+            }
+            if row > 0 {
+                result.push(index - cols);
+            }
+            if row + 1 < rows {
+                result.push(index + cols);
+            }
+            result
+        };
+        let mut regions = vec![usize::MAX; nodes.len()];
+        let mut region_sizes = vec![];
+        for start in 0..nodes.len() {
+            if regions[start] != usize::MAX {
+                continue;
+            }
+            let region = region_sizes.len();
+            let mut size = 0;
+            let mut queue = VecDeque::new();
+            regions[start] = region;
+            queue.push_back(start);
+            while let Some(node) = queue.pop_front() {
+                size += 1;
+                for column in neighbors(nodes[node].0) {
+                    for other in &column_nodes[column] {
+                        if regions[*other] == usize::MAX && connected(nodes[node], nodes[*other]) {
+                            regions[*other] = region;
+                            queue.push_back(*other);
+                        }
+                    }
+                }
+            }
+            region_sizes.push(size);
+        }
+
+        // build triangles out of cells top surfaces.
+        // {corner index: [(height, vertice index)]}
+        let mut corners = vec![Vec::<(i32, u32)>::new(); (cols + 1) * (rows + 1)];
+        let mut result_vertices = Vec::<NavVec3>::new();
+        let mut heights = Vec::<(Scalar, usize)>::new();
+        let mut result_triangles = vec![];
+        let mut result_clearances = vec![];
+        for (node, (index, floor, ceiling)) in nodes.iter().enumerate() {
+            if region_sizes[regions[node]] < self.settings.min_region_area {
+                continue;
+            }
+            let col = index % cols;
+            let row = index / cols;
+            let mut quad = [0; 4];
+            for (i, (c, r)) in [
+                (col, row),
+                (col + 1, row),
+                (col + 1, row + 1),
+                (col, row + 1),
+            ]
+            .iter()
+            .enumerate()
+            {
+                let corner = &mut corners[r * (cols + 1) + c];
+                let found = corner
+                    .iter()
+                    .find(|(height, _)| (height - floor).abs() <= climb)
+                    .map(|(_, vertice)| *vertice);
+                quad[i] = match found {
+                    Some(vertice) => vertice,
+                    None => {
+                        let vertice = result_vertices.len() as u32;
+                        result_vertices.push(NavVec3::new(
+                            min.x + *c as Scalar * cs,
+                            min.y + *r as Scalar * cs,
+                            0.0,
+                        ));
+                        heights.push((0.0, 0));
+                        corner.push((*floor, vertice));
+                        vertice
+                    }
+                };
+                let height = &mut heights[quad[i] as usize];
+                height.0 += min.z + *floor as Scalar * ch;
+                height.1 += 1;
+            }
+            result_triangles.push(NavTriangle::from((quad[0], quad[1], quad[2])));
+            result_triangles.push(NavTriangle::from((quad[2], quad[3], quad[0])));
+            let clearance = if *ceiling == i32::MAX {
+                SCALAR_MAX
+            } else {
+                (ceiling - floor) as Scalar * ch
+            };
+            result_clearances.extend([clearance, clearance]);
+        }
+        if result_triangles.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        for (vertice, (height, count)) in result_vertices.iter_mut().zip(heights) {
+            vertice.z = height / count.max(1) as Scalar;
+            *vertice = up_axis.rotate_from_z_up(*vertice);
+        }
+        Ok((result_vertices, result_triangles, result_clearances))
+    }
+}
+
+fn add_span(column: &mut Vec<Span>, mut span: Span, merge_treshold: i32) {
+    let mut i = 0;
+    while i < column.len() {
+        let other = column[i];
+        if other.min > span.max {
+            break;
+        }
+        if other.max < span.min {
+            i += 1;
+            continue;
+        }
+        if (other.max - span.max).abs() <= merge_treshold {
+            span.walkable = span.walkable || other.walkable;
+        } else if other.max > span.max {
+            span.walkable = other.walkable;
+        }
+        span.min = span.min.min(other.min);
+        span.max = span.max.max(other.max);
+        column.remove(i);
+    }
+    column.insert(i, span);
+}
+
+/// Clip polygon by axis aligned plane, keeping part below (or above when `upper` is false) the
+/// plane value.
+fn clip_axis(polygon: Vec<NavVec3>, axis: usize, value: Scalar, upper: bool) -> Vec<NavVec3> {
+    if polygon.is_empty() {
+        return polygon;
+    }
+    let component = |v: NavVec3| {
+        let d = if axis == 0 { v.x - value } else { v.y - value };
+        if upper {
+            d
+        } else {
+            -d
+        }
+    };
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let current = polygon[i];
+        let next = polygon[(i + 1) % polygon.len()];
+        let dc = component(current);
+        let dn = component(next);
+        if dc <= 0.0 {
+            result.push(current);
+        }
+        if (dc < 0.0 && dn > 0.0) || (dc > 0.0 && dn < 0.0) {
+            result.push(NavVec3::unproject(current, next, dc / (dc - dn)));
+        }
+    }
+    result
+}
@@ -0,0 +1,140 @@
+use crate::{NavMesh, NavPathMode, NavQuery, NavVec3, Scalar, ZERO_TRESHOLD};
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::{
+        component::Component,
+        query::Changed,
+        resource::Resource,
+        schedule::{IntoScheduleConfigs, SystemSet},
+        system::{Query, Res},
+    },
+    math::Vec3,
+    time::Time,
+    transform::components::Transform,
+};
+
+/// Nav mesh used by agents to find their paths.
+#[derive(Resource, Debug, Clone)]
+pub struct NavMeshResource(pub NavMesh);
+
+/// Agent moving along nav mesh path towards its `NavTarget`.
+#[derive(Component, Debug, Clone)]
+pub struct NavAgent {
+    /// Movement speed in units per second.
+    pub speed: Scalar,
+    pub query: NavQuery,
+    pub mode: NavPathMode,
+    path: Vec<NavVec3>,
+}
+
+impl Default for NavAgent {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl NavAgent {
+    pub fn new(speed: Scalar) -> Self {
+        Self {
+            speed,
+            query: NavQuery::Accuracy,
+            mode: NavPathMode::Accuracy,
+            path: vec![],
+        }
+    }
+
+    /// Remaining path points agent has to visit.
+    #[inline]
+    pub fn path(&self) -> &[NavVec3] {
+        &self.path
+    }
+
+    /// Tells if agent has reached end of its path.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        self.path.is_empty()
+    }
+}
+
+/// Point agent should move to. Changing it makes agent find new path.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct NavTarget(pub Vec3);
+
+/// System sets of nav mesh plugin, run in order in `Update` schedule.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NavMeshSystems {
+    FindPaths,
+    FollowPaths,
+}
+
+/// Bevy plugin that finds paths for agents whose target has changed and moves agents along their
+/// paths each frame, using `NavMeshResource` nav mesh.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NavMeshPlugin;
+
+impl Plugin for NavMeshPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            Update,
+            (NavMeshSystems::FindPaths, NavMeshSystems::FollowPaths).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                find_paths.in_set(NavMeshSystems::FindPaths),
+                follow_paths.in_set(NavMeshSystems::FollowPaths),
+            ),
+        );
+    }
+}
+
+/// Find paths for agents whose target has been added or changed.
+pub fn find_paths(
+    mesh: Option<Res<NavMeshResource>>,
+    mut agents: Query<(&Transform, &NavTarget, &mut NavAgent), Changed<NavTarget>>,
+) {
+    let mesh = match mesh {
+        Some(mesh) => mesh,
+        None => return,
+    };
+    for (transform, target, mut agent) in agents.iter_mut() {
+        let mut path = mesh
+            .0
+            .find_path(
+                transform.translation.into(),
+                target.0.into(),
+                agent.query,
+                agent.mode,
+            )
+            .unwrap_or_default();
+        if !path.is_empty() {
+            path.remove(0);
+        }
+        agent.path = path;
+    }
+}
+
+/// Move agents along their paths with their speed.
+pub fn follow_paths(time: Res<Time>, mut agents: Query<(&mut Transform, &mut NavAgent)>) {
+    let delta = time.delta_secs() as Scalar;
+    for (mut transform, mut agent) in agents.iter_mut() {
+        if agent.path.is_empty() {
+            continue;
+        }
+        let mut position = NavVec3::from(transform.translation);
+        let mut distance = agent.speed * delta;
+        while let Some(point) = agent.path.first().copied() {
+            let diff = point - position;
+            let length = diff.magnitude();
+            if length <= distance || length < ZERO_TRESHOLD {
+                position = point;
+                distance -= length;
+                agent.path.remove(0);
+            } else {
+                position = position + diff * (distance / length);
+                break;
+            }
+        }
+        transform.translation = position.into();
+    }
+}
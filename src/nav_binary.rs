@@ -0,0 +1,169 @@
+use crate::{Error, NavResult, NavVec3, Scalar};
+use std::mem::size_of;
+
+/// Little endian binary data writer used by compact serialization formats.
+#[derive(Debug, Default)]
+pub(crate) struct BinaryWriter {
+    bytes: Vec<u8>,
+}
+
+impl BinaryWriter {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bytes: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        self.bytes.extend_from_slice(value);
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_scalar(&mut self, value: Scalar) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_vec3(&mut self, value: NavVec3) {
+        self.write_scalar(value.x);
+        self.write_scalar(value.y);
+        self.write_scalar(value.z);
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Little endian binary data reader used by compact serialization formats.
+///
+/// Scalars are read with size they were written with, so data baked with `scalar64` feature can
+/// be read without it and the other way around.
+#[derive(Debug)]
+pub(crate) struct BinaryReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    scalar_size: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            scalar_size: size_of::<Scalar>(),
+        }
+    }
+
+    pub fn set_scalar_size(&mut self, size: usize) -> NavResult<()> {
+        if size == 4 || size == 8 {
+            self.scalar_size = size;
+            Ok(())
+        } else {
+            Err(Error::CouldNotDeserializeNavMesh(format!(
+                "Unsupported scalar size: {}",
+                size
+            )))
+        }
+    }
+
+    pub fn read_bytes(&mut self, count: usize) -> NavResult<&'a [u8]> {
+        let end = match self.position.checked_add(count) {
+            Some(end) if end <= self.bytes.len() => end,
+            _ => {
+                return Err(Error::CouldNotDeserializeNavMesh(
+                    "Unexpected end of data".to_owned(),
+                ))
+            }
+        };
+        let result = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(result)
+    }
+
+    pub fn read_u8(&mut self) -> NavResult<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> NavResult<u16> {
+        let mut buffer = [0; 2];
+        buffer.copy_from_slice(self.read_bytes(2)?);
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    pub fn read_u32(&mut self) -> NavResult<u32> {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(buffer))
+    }
+
+    pub fn read_u64(&mut self) -> NavResult<u64> {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_le_bytes(buffer))
+    }
+
+    pub fn read_scalar(&mut self) -> NavResult<Scalar> {
+        if self.scalar_size == 8 {
+            let mut buffer = [0; 8];
+            buffer.copy_from_slice(self.read_bytes(8)?);
+            Ok(f64::from_le_bytes(buffer) as Scalar)
+        } else {
+            let mut buffer = [0; 4];
+            buffer.copy_from_slice(self.read_bytes(4)?);
+            Ok(f32::from_le_bytes(buffer) as Scalar)
+        }
+    }
+
+    pub fn read_vec3(&mut self) -> NavResult<NavVec3> {
+        Ok(NavVec3::new(
+            self.read_scalar()?,
+            self.read_scalar()?,
+            self.read_scalar()?,
+        ))
+    }
+
+    /// Read items count, making sure there is enough data left for given item size.
+    pub fn read_count(&mut self, item_size: usize) -> NavResult<usize> {
+        let count = self.read_u32()? as usize;
+        if count.saturating_mul(item_size) > self.bytes.len() - self.position {
+            return Err(Error::CouldNotDeserializeNavMesh(
+                "Unexpected end of data".to_owned(),
+            ));
+        }
+        Ok(count)
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.position.min(self.bytes.len())..]
+    }
+
+    pub fn scalar_size(&self) -> usize {
+        self.scalar_size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+}
+
+/// FNV-1a hash of data, used as checksum.
+pub(crate) fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,216 @@
+use crate::{NavMesh, NavQuery, NavTriangle, NavVec3, Scalar, ZERO_TRESHOLD};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Settings of cover points generation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavCoverSettings {
+    /// Distance between cover points along boundary edges.
+    pub spacing: Scalar,
+    /// Distance cover points are moved from boundary into walkable area, usually agent radius.
+    pub offset: Scalar,
+    /// Boundary edges shorter than this do not get cover points.
+    pub min_edge_length: Scalar,
+    /// Lowest obstruction height that gives cover, for example crouching agent height.
+    pub min_height: Scalar,
+    /// Highest probed obstruction height, for example standing agent height.
+    pub max_height: Scalar,
+    /// Distance between probed heights.
+    pub height_step: Scalar,
+    /// Maximal distance of obstruction behind boundary edge.
+    pub max_distance: Scalar,
+}
+
+impl Default for NavCoverSettings {
+    fn default() -> Self {
+        Self {
+            spacing: 1.0,
+            offset: 0.0,
+            min_edge_length: 0.5,
+            min_height: 1.0,
+            max_height: 2.0,
+            height_step: 0.25,
+            max_distance: 0.5,
+        }
+    }
+}
+
+/// Candidate cover point found along nav mesh boundary.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavCoverPoint {
+    /// Point on nav mesh surface.
+    pub position: NavVec3,
+    /// Unit direction on ground plane pointing from position towards obstruction, so agents
+    /// taking cover are protected from threats in that direction.
+    pub direction: NavVec3,
+    /// Index of triangle containing position.
+    pub triangle: usize,
+    /// Obstruction height above position, measured with `height_step` precision, or `None` if no
+    /// obstruction geometry was given.
+    pub height: Option<Scalar>,
+}
+
+impl NavMesh {
+    /// Find candidate cover points along boundary edges of nav mesh that face non-walkable space.
+    ///
+    /// Without obstruction geometry every boundary edge is assumed to be wall. With obstruction
+    /// geometry (for example level collision triangles), edges are probed with horizontal rays
+    /// going outwards at growing heights and only points with obstruction at least
+    /// `min_height` tall within `max_distance` become cover points.
+    ///
+    /// # Arguments
+    /// * `settings` - generation settings.
+    /// * `obstruction` - optional obstruction geometry vertices and triangles.
+    ///
+    /// # Returns
+    /// List of cover points.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(),
+    ///     (4.0, 0.0, 0.0).into(),
+    ///     (4.0, 4.0, 0.0).into(),
+    ///     (0.0, 4.0, 0.0).into(),
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    ///
+    /// // wall 1.5 tall along right side of nav mesh.
+    /// let wall_vertices = vec![
+    ///     (4.2, -1.0, 0.0).into(),
+    ///     (4.2, 5.0, 0.0).into(),
+    ///     (4.2, 5.0, 1.5).into(),
+    ///     (4.2, -1.0, 1.5).into(),
+    /// ];
+    /// let wall_triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    /// let points = mesh.find_cover_points(
+    ///     &NavCoverSettings::default(),
+    ///     Some((&wall_vertices, &wall_triangles)),
+    /// );
+    /// assert_eq!(points.len(), 4);
+    /// for point in points {
+    ///     assert_eq!(point.position.x, 4.0);
+    ///     assert_eq!(point.direction, (1.0, 0.0, 0.0).into());
+    ///     assert_eq!(point.height, Some(1.5));
+    /// }
+    /// ```
+    pub fn find_cover_points(
+        &self,
+        settings: &NavCoverSettings,
+        obstruction: Option<(&[NavVec3], &[NavTriangle])>,
+    ) -> Vec<NavCoverPoint> {
+        let up_axis = self.up_axis();
+        let up = up_axis.up();
+        let spacing = settings.spacing.max(ZERO_TRESHOLD);
+        let height_step = settings.height_step.max(ZERO_TRESHOLD);
+        let obstruction = obstruction.map(|(vertices, triangles)| {
+            triangles
+                .iter()
+                .filter_map(|triangle| {
+                    let a = *vertices.get(triangle.first as usize)?;
+                    let b = *vertices.get(triangle.second as usize)?;
+                    let c = *vertices.get(triangle.third as usize)?;
+                    Some((a, b, c, a.min(b).min(c), a.max(b).max(c)))
+                })
+                .collect::<Vec<_>>()
+        });
+        let mut edges = self.boundary_edges().collect::<Vec<_>>();
+        // boundary edges are stored in hash map, so order them to make results deterministic.
+        edges.sort_by(|a, b| {
+            a.0.cmp(&b.0).then_with(|| {
+                (a.1.x, a.1.y, a.1.z)
+                    .partial_cmp(&(b.1.x, b.1.y, b.1.z))
+                    .unwrap()
+            })
+        });
+        let mut result = Vec::<NavCoverPoint>::new();
+        for (triangle, a, b) in edges {
+            let length = up_axis.flatten(b - a).magnitude();
+            if length < settings.min_edge_length.max(ZERO_TRESHOLD) {
+                continue;
+            }
+            let spatial = self.spatial(triangle);
+            let center = (spatial.a + spatial.b + spatial.c) / 3.0;
+            let mut direction = up_axis.flatten((b - a).cross(up)).normalize();
+            if direction.dot(center - a) > 0.0 {
+                direction = -direction;
+            }
+            let count = ((length / spacing) as usize).max(1);
+            for index in 0..count {
+                let edge_point =
+                    NavVec3::unproject(a, b, (index as Scalar + 0.5) / count as Scalar);
+                let height = match &obstruction {
+                    Some(obstruction) => {
+                        // probe middle of every height step, going up until probe misses.
+                        let mut height = 0.0;
+                        while height + height_step <= settings.max_height + ZERO_TRESHOLD
+                            && probe_obstruction(
+                                obstruction,
+                                edge_point + up * (height + height_step * 0.5),
+                                direction,
+                                settings.max_distance,
+                            )
+                        {
+                            height += height_step;
+                        }
+                        if height < settings.min_height - ZERO_TRESHOLD {
+                            continue;
+                        }
+                        Some(height)
+                    }
+                    None => None,
+                };
+                let position = edge_point - direction * settings.offset.max(0.0);
+                let (triangle, position) =
+                    match self.find_closest_triangle(position, NavQuery::Accuracy) {
+                        Some(index) => (index, self.spatial(index).closest_point(position)),
+                        None => (triangle, edge_point),
+                    };
+                let duplicate = result.iter().any(|other| {
+                    other.direction.dot(direction) > 0.9
+                        && other.position.distance_squared(position) < spacing * spacing * 0.25
+                });
+                if !duplicate {
+                    result.push(NavCoverPoint {
+                        position,
+                        direction,
+                        triangle,
+                        height,
+                    });
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Tells if horizontal probe ray going from point in given direction hits obstruction.
+fn probe_obstruction(
+    obstruction: &[(NavVec3, NavVec3, NavVec3, NavVec3, NavVec3)],
+    from: NavVec3,
+    direction: NavVec3,
+    distance: Scalar,
+) -> bool {
+    let to = from + direction * distance;
+    let (min, max) = (from.min(to), from.max(to));
+    let triangles = obstruction
+        .iter()
+        .filter(|(_, _, _, tmin, tmax)| {
+            tmin.x <= max.x
+                && tmin.y <= max.y
+                && tmin.z <= max.z
+                && tmax.x >= min.x
+                && tmax.y >= min.y
+                && tmax.z >= min.z
+        })
+        .map(|(a, b, c, _, _)| (*a, *b, *c))
+        .collect::<Vec<_>>();
+    NavVec3::raycast_triangle_batch(from, to, &triangles)
+        .into_iter()
+        .any(|hit| hit.is_some())
+}
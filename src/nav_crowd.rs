@@ -0,0 +1,1075 @@
+use crate::{
+    NavMesh, NavPathMode, NavQuat, NavQuery, NavTolerance, NavUpAxis, NavVec3, Scalar,
+    ZERO_TRESHOLD,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use spade::SpatialObject;
+use std::collections::{HashMap, HashSet};
+use typid::ID;
+
+/// Nav crowd identifier.
+pub type NavCrowdID = ID<NavCrowd>;
+
+/// Nav crowd agent identifier.
+pub type NavAgentID = ID<NavCrowdAgent>;
+
+const AVOIDANCE_DIRECTIONS: usize = 8;
+const AVOIDANCE_RINGS: usize = 3;
+const COLLISION_ITERATIONS: usize = 4;
+
+/// Simulation level of detail of crowd agent, cheaper levels are meant for agents far away from
+/// player or not seen at all.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavAgentLod {
+    /// Path following with local avoidance and collisions.
+    #[default]
+    Full,
+    /// Path following without local avoidance, agent does not get pushed by collisions but still
+    /// blocks agents with full simulation.
+    Coarse,
+    /// Agent jumps exactly along its path with its maximal speed, ignoring other agents.
+    Offscreen,
+}
+
+/// Importance thresholds of automatic agents level of detail changes, see
+/// `NavCrowd::update_lods`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavAgentLodSettings {
+    /// Minimal importance of agent with full simulation.
+    pub full_importance: Scalar,
+    /// Minimal importance of agent with coarse simulation, less important agents are offscreen.
+    pub coarse_importance: Scalar,
+    /// Margin that importance has to cross threshold by to change level, so agents near
+    /// thresholds do not flicker between levels.
+    pub hysteresis: Scalar,
+}
+
+impl Default for NavAgentLodSettings {
+    fn default() -> Self {
+        Self {
+            full_importance: 0.5,
+            coarse_importance: 0.1,
+            hysteresis: 0.05,
+        }
+    }
+}
+
+impl NavAgentLodSettings {
+    /// Level of detail agent with given importance gets, without hysteresis.
+    pub fn lod(&self, importance: Scalar) -> NavAgentLod {
+        if importance >= self.full_importance {
+            NavAgentLod::Full
+        } else if importance >= self.coarse_importance {
+            NavAgentLod::Coarse
+        } else {
+            NavAgentLod::Offscreen
+        }
+    }
+
+    /// Level of detail agent with given importance and current level gets.
+    pub fn next_lod(&self, current: NavAgentLod, importance: Scalar) -> NavAgentLod {
+        let detail = |lod| match lod {
+            NavAgentLod::Full => 2,
+            NavAgentLod::Coarse => 1,
+            NavAgentLod::Offscreen => 0,
+        };
+        let promoted = self.lod(importance - self.hysteresis);
+        if detail(promoted) > detail(current) {
+            return promoted;
+        }
+        let demoted = self.lod(importance + self.hysteresis);
+        if detail(demoted) < detail(current) {
+            return demoted;
+        }
+        current
+    }
+}
+
+/// Fixed timestep settings of nav crowd, see `NavCrowd::update_fixed`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavCrowdTimestep {
+    /// Time in seconds simulated by single step.
+    pub step: Scalar,
+    /// Maximal number of steps performed by single update, time above that gets dropped so slow
+    /// frames do not make next updates even slower.
+    pub max_steps: usize,
+    /// `Some` with number of agents steered (finding paths and performing local avoidance) per
+    /// step, taking turns in round-robin order, or `None` to steer all agents every step. Agents
+    /// not steered in given step keep moving with their last velocity.
+    pub agents_per_step: Option<usize>,
+}
+
+impl Default for NavCrowdTimestep {
+    fn default() -> Self {
+        Self {
+            step: 1.0 / 30.0,
+            max_steps: 4,
+            agents_per_step: None,
+        }
+    }
+}
+
+/// Local avoidance tunables of single agent.
+///
+/// Every candidate velocity gets penalty summed from its distance to desired velocity, distance to
+/// current velocity and how soon it would hit neighbor agent, and one with lowest penalty wins.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavAvoidanceParams {
+    /// Time in seconds within which collisions with neighbors are avoided.
+    pub time_horizon: Scalar,
+    /// Distance between agents edges within which other agents are considered neighbors.
+    pub neighbor_distance: Scalar,
+    /// Maximal number of closest neighbors taken into account.
+    pub max_neighbors: usize,
+    /// Penalty weight of candidate velocity deviating from desired velocity.
+    pub weight_desired_velocity: Scalar,
+    /// Penalty weight of candidate velocity deviating from current velocity.
+    pub weight_current_velocity: Scalar,
+    /// Penalty weight of candidate velocity leading to collision with neighbor.
+    pub weight_time_to_impact: Scalar,
+    /// Keep candidate velocities sampled during last update (see
+    /// `NavCrowdAgent::avoidance_samples`).
+    pub record_samples: bool,
+}
+
+impl Default for NavAvoidanceParams {
+    fn default() -> Self {
+        Self {
+            time_horizon: 2.5,
+            neighbor_distance: 5.0,
+            max_neighbors: 16,
+            weight_desired_velocity: 2.0,
+            weight_current_velocity: 0.75,
+            weight_time_to_impact: 2.5,
+            record_samples: false,
+        }
+    }
+}
+
+/// Candidate velocity sampled by local avoidance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavAvoidanceSample {
+    pub velocity: NavVec3,
+    /// Total penalty, lower is better.
+    pub penalty: Scalar,
+    /// Time after which agent would hit closest neighbor, limited by time horizon.
+    pub time_to_impact: Scalar,
+    /// Tells if this candidate was chosen.
+    pub selected: bool,
+}
+
+/// Single agent managed by nav crowd.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavCrowdAgent {
+    /// Agent position on nav mesh.
+    pub position: NavVec3,
+    /// Agent radius used by local avoidance.
+    pub radius: Scalar,
+    /// Maximal agent speed.
+    pub max_speed: Scalar,
+    /// Maximal change of agent velocity per second.
+    pub max_acceleration: Scalar,
+    /// Distance from target at which agent is considered arrived.
+    pub arrival_distance: Scalar,
+    /// Local avoidance tunables.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub avoidance: NavAvoidanceParams,
+    /// Agents with lower priority yield to ones with higher priority by taking whole avoidance
+    /// effort and getting pushed away on collision, while agents with equal priority share it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub priority: i32,
+    /// Simulation level of detail.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lod: NavAgentLod,
+    velocity: NavVec3,
+    desired_velocity: NavVec3,
+    target: Option<NavVec3>,
+    path: Vec<NavVec3>,
+    corner: usize,
+    replan: bool,
+    // up axis of nav mesh agent moves on, taken during last update.
+    #[cfg_attr(feature = "serde", serde(default))]
+    up_axis: NavUpAxis,
+    #[cfg_attr(feature = "serde", serde(default))]
+    samples: Vec<NavAvoidanceSample>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    triangle: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    orientation: NavQuat,
+    // position before last update, used for interpolation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    previous_position: Option<NavVec3>,
+    // time passed since agent was steered last time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    steer_time: Scalar,
+}
+
+impl NavCrowdAgent {
+    /// Create new agent.
+    ///
+    /// # Arguments
+    /// * `position` - initial position.
+    /// * `radius` - agent radius.
+    /// * `max_speed` - maximal agent speed.
+    /// * `max_acceleration` - maximal change of agent velocity per second.
+    pub fn new(
+        position: NavVec3,
+        radius: Scalar,
+        max_speed: Scalar,
+        max_acceleration: Scalar,
+    ) -> Self {
+        let radius = radius.max(0.0);
+        Self {
+            position,
+            radius,
+            max_speed: max_speed.max(0.0),
+            max_acceleration: max_acceleration.max(0.0),
+            arrival_distance: radius.max(ZERO_TRESHOLD),
+            avoidance: Default::default(),
+            priority: 0,
+            lod: NavAgentLod::Full,
+            velocity: NavVec3::default(),
+            desired_velocity: NavVec3::default(),
+            target: None,
+            path: vec![],
+            corner: 0,
+            replan: false,
+            up_axis: Default::default(),
+            samples: vec![],
+            triangle: None,
+            orientation: NavQuat::IDENTITY,
+            previous_position: None,
+            steer_time: 0.0,
+        }
+    }
+
+    /// Velocity agent was moved with during last update (after local avoidance).
+    #[inline]
+    pub fn velocity(&self) -> NavVec3 {
+        self.velocity
+    }
+
+    /// Velocity agent wanted to move with during last update (before local avoidance).
+    #[inline]
+    pub fn desired_velocity(&self) -> NavVec3 {
+        self.desired_velocity
+    }
+
+    /// Candidate velocities sampled by local avoidance during last update, useful for debugging
+    /// jittery agents. Empty unless `avoidance.record_samples` is set and agent had neighbors.
+    #[inline]
+    pub fn avoidance_samples(&self) -> &[NavAvoidanceSample] {
+        &self.samples
+    }
+
+    /// Agent orientation, turning local forward axis towards direction agent was moving in
+    /// during last update. Standing agents keep orientation they had.
+    #[inline]
+    pub fn orientation(&self) -> NavQuat {
+        self.orientation
+    }
+
+    /// Set agent orientation, for example initial facing of spawned agent.
+    #[inline]
+    pub fn set_orientation(&mut self, orientation: NavQuat) {
+        self.orientation = orientation.normalize();
+    }
+
+    /// Position agent had before last simulation step.
+    #[inline]
+    pub fn previous_position(&self) -> NavVec3 {
+        self.previous_position.unwrap_or(self.position)
+    }
+
+    /// Position between one before and after last simulation step, used to render agents
+    /// smoothly between fixed steps, see `NavCrowd::interpolation_factor`.
+    #[inline]
+    pub fn interpolated_position(&self, factor: Scalar) -> NavVec3 {
+        self.previous_position().lerp(self.position, factor)
+    }
+
+    /// Index of nav mesh triangle agent stood on during last update.
+    #[inline]
+    pub fn triangle(&self) -> Option<usize> {
+        self.triangle
+    }
+
+    #[inline]
+    pub fn target(&self) -> Option<NavVec3> {
+        self.target
+    }
+
+    /// Path currently followed by agent.
+    #[inline]
+    pub fn path(&self) -> &[NavVec3] {
+        &self.path
+    }
+
+    /// Tells if agent stays in place, having no target or having reached it.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        self.target.is_none() || self.has_arrived()
+    }
+
+    /// Part of avoidance effort between this and other agent that this agent takes.
+    fn avoidance_share(&self, other: (i32, bool), push_idle: bool) -> Scalar {
+        let (priority, idle) = other;
+        if self.priority != priority {
+            return if self.priority < priority { 1.0 } else { 0.0 };
+        }
+        match (push_idle, self.is_idle(), idle) {
+            (true, true, false) => 1.0,
+            (true, false, true) => 0.0,
+            _ => 0.5,
+        }
+    }
+
+    /// Tells if agent has target and reached it.
+    #[inline]
+    pub fn has_arrived(&self) -> bool {
+        match self.target {
+            Some(target) => {
+                let d = self.up_axis.flatten(target - self.position);
+                d.sqr_magnitude() <= self.arrival_distance * self.arrival_distance
+            }
+            None => false,
+        }
+    }
+}
+
+/// Manages many agents moving on shared nav mesh, steering them along their paths and
+/// performing local avoidance between them.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavCrowd {
+    id: NavCrowdID,
+    agents: HashMap<NavAgentID, NavCrowdAgent>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    push_idle_agents: bool,
+    // {triangle: agents standing on it}
+    #[cfg_attr(feature = "serde", serde(default))]
+    index: HashMap<usize, Vec<NavAgentID>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    timestep: NavCrowdTimestep,
+    // time not simulated yet by fixed steps.
+    #[cfg_attr(feature = "serde", serde(default))]
+    accumulator: Scalar,
+    // agents in round-robin steering order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    order: Vec<NavAgentID>,
+    // position in steering order of agent that gets steered next.
+    #[cfg_attr(feature = "serde", serde(default))]
+    cursor: usize,
+}
+
+impl NavCrowd {
+    pub fn new() -> Self {
+        Self {
+            id: NavCrowdID::new(),
+            agents: Default::default(),
+            push_idle_agents: false,
+            index: Default::default(),
+            timestep: Default::default(),
+            accumulator: 0.0,
+            order: vec![],
+            cursor: 0,
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NavCrowdID {
+        self.id
+    }
+
+    #[inline]
+    pub fn push_idle_agents(&self) -> bool {
+        self.push_idle_agents
+    }
+
+    /// Enable "push through" mode, where idle agents (see `NavCrowdAgent::is_idle`) yield to
+    /// moving agents of the same priority and get displaced by them, instead of both sides
+    /// sharing avoidance and deadlocking narrow corridors.
+    #[inline]
+    pub fn set_push_idle_agents(&mut self, value: bool) {
+        self.push_idle_agents = value;
+    }
+
+    #[inline]
+    pub fn timestep(&self) -> &NavCrowdTimestep {
+        &self.timestep
+    }
+
+    #[inline]
+    pub fn set_timestep(&mut self, timestep: NavCrowdTimestep) {
+        self.timestep = timestep;
+    }
+
+    /// Part of fixed step that is not simulated yet, to interpolate agents positions with (see
+    /// `NavCrowdAgent::interpolated_position`).
+    #[inline]
+    pub fn interpolation_factor(&self) -> Scalar {
+        if self.timestep.step > 0.0 {
+            (self.accumulator / self.timestep.step).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn add_agent(&mut self, agent: NavCrowdAgent) -> NavAgentID {
+        let id = NavAgentID::new();
+        self.agents.insert(id, agent);
+        self.order.push(id);
+        id
+    }
+
+    pub fn remove_agent(&mut self, id: NavAgentID) -> Option<NavCrowdAgent> {
+        let agent = self.agents.remove(&id)?;
+        if let Some(index) = self.order.iter().position(|other| *other == id) {
+            self.order.remove(index);
+            if index < self.cursor {
+                self.cursor -= 1;
+            }
+        }
+        if let Some(triangle) = agent.triangle {
+            if let Some(agents) = self.index.get_mut(&triangle) {
+                agents.retain(|other| *other != id);
+                if agents.is_empty() {
+                    self.index.remove(&triangle);
+                }
+            }
+        }
+        Some(agent)
+    }
+
+    #[inline]
+    pub fn agent(&self, id: NavAgentID) -> Option<&NavCrowdAgent> {
+        self.agents.get(&id)
+    }
+
+    #[inline]
+    pub fn agent_mut(&mut self, id: NavAgentID) -> Option<&mut NavCrowdAgent> {
+        self.agents.get_mut(&id)
+    }
+
+    #[inline]
+    pub fn agents(&self) -> impl Iterator<Item = (NavAgentID, &NavCrowdAgent)> {
+        self.agents.iter().map(|(id, agent)| (*id, agent))
+    }
+
+    #[inline]
+    pub fn agents_count(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// Agents standing on given nav mesh triangle.
+    ///
+    /// Agents get assigned to triangles during update, so agents added or moved since last update
+    /// are not reported at their new position yet.
+    pub fn agents_in_triangle(&self, triangle: usize) -> &[NavAgentID] {
+        self.index
+            .get(&triangle)
+            .map(|agents| agents.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Find agents within radius from given point, in no particular order.
+    ///
+    /// Agents get assigned to triangles during update, so agents added or moved since last update
+    /// are not reported at their new position yet.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh that agents move on.
+    /// * `position` - query point.
+    /// * `radius` - maximal distance of agent position from query point.
+    pub fn agents_in_radius(
+        &self,
+        mesh: &NavMesh,
+        position: NavVec3,
+        radius: Scalar,
+    ) -> Vec<NavAgentID> {
+        let radius2 = radius * radius;
+        indexed_agents(&self.index, mesh, position, radius)
+            .into_iter()
+            .filter(|id| {
+                self.agents
+                    .get(id)
+                    .map(|agent| (agent.position - position).sqr_magnitude() <= radius2)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Find agent closest to given point.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh that agents move on.
+    /// * `position` - query point.
+    pub fn nearest_agent(&self, mesh: &NavMesh, position: NavVec3) -> Option<NavAgentID> {
+        self.nearest_agent_custom(mesh, position, |_, _| true)
+    }
+
+    // filter params: agent id, agent.
+    pub fn nearest_agent_custom<F>(
+        &self,
+        mesh: &NavMesh,
+        position: NavVec3,
+        mut filter: F,
+    ) -> Option<NavAgentID>
+    where
+        F: FnMut(NavAgentID, &NavCrowdAgent) -> bool,
+    {
+        let mut best = None;
+        let mut best_distance = Scalar::INFINITY;
+        // agents lie on their triangles, so triangle distance is lower bound of agents distance.
+        for spatial in mesh.spatial_index().nearest_neighbor_iterator(&position) {
+            if spatial.distance2(&position) > best_distance {
+                break;
+            }
+            for id in self.agents_in_triangle(spatial.index) {
+                let agent = &self.agents[id];
+                let distance = (agent.position - position).sqr_magnitude();
+                if distance < best_distance && filter(*id, agent) {
+                    best = Some(*id);
+                    best_distance = distance;
+                }
+            }
+        }
+        best
+    }
+
+    /// Set agent movement target. Path gets found during next update.
+    ///
+    /// # Returns
+    /// `true` if agent exists.
+    pub fn set_target(&mut self, id: NavAgentID, target: NavVec3) -> bool {
+        if let Some(agent) = self.agents.get_mut(&id) {
+            agent.target = Some(target);
+            agent.replan = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove agent movement target and make it stop.
+    ///
+    /// # Returns
+    /// `true` if agent exists.
+    pub fn clear_target(&mut self, id: NavAgentID) -> bool {
+        if let Some(agent) = self.agents.get_mut(&id) {
+            agent.target = None;
+            agent.path.clear();
+            agent.corner = 0;
+            agent.replan = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Perform single simulation step.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh that agents move on.
+    /// * `delta_time` - time in seconds since last update.
+    pub fn update(&mut self, mesh: &NavMesh, delta_time: Scalar) {
+        self.step(mesh, delta_time, None);
+    }
+
+    /// Advance simulation by fixed steps (see `NavCrowdTimestep`), accumulating time left over
+    /// to next updates. Agents positions can be interpolated between last two steps with
+    /// `interpolation_factor` for smooth rendering.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh that agents move on.
+    /// * `delta_time` - time in seconds since last update.
+    ///
+    /// # Returns
+    /// Number of performed steps.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (10.0, 0.0, 0.0).into(), // 1
+    ///     (10.0, 10.0, 0.0).into(), // 2
+    ///     (0.0, 10.0, 0.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let mut crowd = NavCrowd::new();
+    /// crowd.set_timestep(NavCrowdTimestep {
+    ///     step: 0.125,
+    ///     ..Default::default()
+    /// });
+    /// let id = crowd.add_agent(NavCrowdAgent::new((1.0, 5.0, 0.0).into(), 0.5, 1.0, 100.0));
+    /// crowd.set_target(id, (9.0, 5.0, 0.0).into());
+    /// assert_eq!(crowd.update_fixed(&mesh, 0.1), 0);
+    /// assert_eq!(crowd.update_fixed(&mesh, 0.2), 2);
+    /// assert!((crowd.interpolation_factor() - 0.4).abs() < 1.0e-4);
+    /// let agent = crowd.agent(id).unwrap();
+    /// let position = agent.interpolated_position(crowd.interpolation_factor());
+    /// assert!(agent.previous_position().x < position.x);
+    /// assert!(position.x < agent.position.x);
+    /// ```
+    pub fn update_fixed(&mut self, mesh: &NavMesh, delta_time: Scalar) -> usize {
+        let step = self.timestep.step;
+        if step <= 0.0 {
+            return 0;
+        }
+        self.accumulator += delta_time.max(0.0);
+        let mut steps = 0;
+        while self.accumulator >= step && steps < self.timestep.max_steps {
+            let selected = self.next_steered_agents();
+            self.step(mesh, step, selected.as_ref());
+            self.accumulator -= step;
+            steps += 1;
+        }
+        if self.accumulator >= step {
+            self.accumulator %= step;
+        }
+        steps
+    }
+
+    /// Promote or demote agents levels of detail by their importance, for example based on their
+    /// distance from camera and whether they are on screen.
+    ///
+    /// # Arguments
+    /// * `settings` - importance thresholds.
+    /// * `importance` - callback telling importance of agent.
+    ///
+    /// # Returns
+    /// Number of agents that changed level of detail.
+    pub fn update_lods<F>(&mut self, settings: &NavAgentLodSettings, mut importance: F) -> usize
+    where
+        F: FnMut(NavAgentID, &NavCrowdAgent) -> Scalar,
+    {
+        let mut count = 0;
+        for (id, agent) in self.agents.iter_mut() {
+            let lod = settings.next_lod(agent.lod, importance(*id, agent));
+            if lod != agent.lod {
+                agent.lod = lod;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Take agents steered in next step in round-robin order, `None` means all of them.
+    fn next_steered_agents(&mut self) -> Option<HashSet<NavAgentID>> {
+        let count = self.timestep.agents_per_step?;
+        if self.order.len() != self.agents.len() {
+            // order is not stored by older versions, so it is restored from agents.
+            self.order = self.agents.keys().copied().collect();
+            self.order.sort_unstable();
+            self.cursor = 0;
+        }
+        if count >= self.order.len() {
+            return None;
+        }
+        let result = (0..count)
+            .map(|index| self.order[(self.cursor + index) % self.order.len()])
+            .collect();
+        self.cursor = (self.cursor + count) % self.order.len();
+        Some(result)
+    }
+
+    fn step(&mut self, mesh: &NavMesh, delta_time: Scalar, selected: Option<&HashSet<NavAgentID>>) {
+        if delta_time <= 0.0 {
+            return;
+        }
+        let steered = |id: &NavAgentID| selected.map(|list| list.contains(id)).unwrap_or(true);
+        for (id, agent) in self.agents.iter_mut() {
+            agent.previous_position = Some(agent.position);
+            agent.steer_time += delta_time;
+            agent.up_axis = mesh.up_axis();
+            agent.triangle = match agent.triangle {
+                Some(hint) => mesh.locate_from_hint(agent.position, hint),
+                None => mesh.find_closest_triangle(agent.position, NavQuery::Accuracy),
+            };
+            if steered(id) {
+                Self::update_desired_velocity(agent, mesh);
+            }
+        }
+        self.rebuild_index();
+        // offscreen agents are not avoided.
+        let snapshot = self
+            .agents
+            .iter()
+            .filter(|(_, agent)| agent.lod != NavAgentLod::Offscreen)
+            .map(|(id, agent)| {
+                (
+                    *id,
+                    (
+                        agent.position,
+                        agent.velocity,
+                        agent.radius,
+                        (agent.priority, agent.is_idle()),
+                    ),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let max_radius = snapshot
+            .values()
+            .map(|(_, _, radius, _)| *radius)
+            .fold(0.0, Scalar::max);
+        let push_idle = self.push_idle_agents;
+        for (id, agent) in self.agents.iter_mut() {
+            if !steered(id) {
+                continue;
+            }
+            match agent.lod {
+                NavAgentLod::Full => {}
+                NavAgentLod::Coarse => {
+                    agent.samples.clear();
+                    let velocity = agent.desired_velocity;
+                    Self::accelerate(agent, velocity);
+                    continue;
+                }
+                NavAgentLod::Offscreen => {
+                    agent.samples.clear();
+                    let position = agent.position;
+                    Self::advance_along_path(agent, agent.max_speed * agent.steer_time);
+                    agent.velocity = (agent.position - position) / agent.steer_time;
+                    agent.steer_time = 0.0;
+                    continue;
+                }
+            }
+            let range = agent.avoidance.neighbor_distance + agent.radius + max_radius;
+            let mut neighbors = indexed_agents(&self.index, mesh, agent.position, range)
+                .into_iter()
+                .filter(|other_id| other_id != id)
+                .filter_map(|other_id| {
+                    let (position, velocity, radius, other) = snapshot.get(&other_id)?;
+                    let share = agent.avoidance_share(*other, push_idle);
+                    if share <= 0.0 {
+                        return None;
+                    }
+                    let limit = agent.avoidance.neighbor_distance + agent.radius + radius;
+                    let distance = agent
+                        .up_axis
+                        .flatten(*position - agent.position)
+                        .sqr_magnitude();
+                    if distance < limit * limit {
+                        Some((distance, (*position, *velocity, *radius, share)))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            if neighbors.len() > agent.avoidance.max_neighbors {
+                neighbors.sort_by(|a, b| a.0.total_cmp(&b.0));
+                neighbors.truncate(agent.avoidance.max_neighbors);
+            }
+            let neighbors = neighbors
+                .into_iter()
+                .map(|(_, neighbor)| neighbor)
+                .collect::<Vec<_>>();
+            let velocity = Self::sample_velocity(agent, &neighbors);
+            Self::accelerate(agent, velocity);
+        }
+        for agent in self.agents.values_mut() {
+            // offscreen agents got already moved along their paths.
+            if agent.lod != NavAgentLod::Offscreen {
+                agent.position = agent.position + agent.velocity * delta_time;
+            }
+            if agent.up_axis.flatten(agent.velocity).sqr_magnitude() > mesh.tolerance().distance_sqr
+            {
+                agent.orientation = NavQuat::facing(agent.velocity, agent.up_axis);
+            }
+        }
+        self.resolve_collisions(mesh.tolerance());
+        for agent in self.agents.values_mut() {
+            agent.triangle = match agent.triangle {
+                Some(hint) => mesh.locate_from_hint(agent.position, hint),
+                None => mesh.find_closest_triangle(agent.position, NavQuery::Accuracy),
+            };
+            if let Some(triangle) = agent.triangle {
+                if let Some(position) = mesh.closest_point_on_triangle(triangle, agent.position) {
+                    agent.position = position;
+                }
+            }
+        }
+        self.rebuild_index();
+    }
+
+    /// Change agent velocity towards given one, limited by agent acceleration.
+    fn accelerate(agent: &mut NavCrowdAgent, velocity: NavVec3) {
+        let change = velocity - agent.velocity;
+        // agents skipped by previous steps catch up with time that passed since.
+        let max_change = agent.max_acceleration * agent.steer_time;
+        agent.steer_time = 0.0;
+        agent.velocity = if change.sqr_magnitude() > max_change * max_change {
+            agent.velocity + change.normalize() * max_change
+        } else {
+            velocity
+        };
+    }
+
+    /// Move agent exactly along its path by given distance.
+    fn advance_along_path(agent: &mut NavCrowdAgent, mut distance: Scalar) {
+        while let Some(corner) = agent.path.get(agent.corner).copied() {
+            let diff = corner - agent.position;
+            let length = diff.magnitude();
+            if length > distance {
+                agent.position = agent.position + diff / length * distance;
+                return;
+            }
+            agent.position = corner;
+            distance -= length;
+            if agent.corner + 1 >= agent.path.len() {
+                return;
+            }
+            agent.corner += 1;
+        }
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        // agents are indexed in stable order, so restored crowds replay the same way.
+        let mut ids = self.agents.keys().copied().collect::<Vec<_>>();
+        ids.sort_unstable();
+        for id in ids {
+            if let Some(triangle) = self.agents[&id].triangle {
+                self.index.entry(triangle).or_default().push(id);
+            }
+        }
+    }
+
+    fn update_desired_velocity(agent: &mut NavCrowdAgent, mesh: &NavMesh) {
+        agent.desired_velocity = NavVec3::default();
+        let target = match agent.target {
+            Some(target) => target,
+            None => return,
+        };
+        if agent.has_arrived() {
+            agent.path.clear();
+            agent.corner = 0;
+            return;
+        }
+        if agent.replan {
+            agent.replan = false;
+            agent.corner = 1;
+            agent.path = mesh
+                .find_path(
+                    agent.position,
+                    target,
+                    NavQuery::Accuracy,
+                    NavPathMode::Accuracy,
+                )
+                .unwrap_or_default();
+        }
+        while agent.corner + 1 < agent.path.len() {
+            let d = agent
+                .up_axis
+                .flatten(agent.path[agent.corner] - agent.position);
+            if d.sqr_magnitude() > agent.radius * agent.radius * 0.25 {
+                break;
+            }
+            agent.corner += 1;
+        }
+        let corner = match agent.path.get(agent.corner) {
+            Some(corner) => *corner,
+            None => return,
+        };
+        let diff = agent.up_axis.flatten(corner - agent.position);
+        let distance = diff.magnitude();
+        if distance < mesh.tolerance().distance {
+            return;
+        }
+        let speed = if agent.corner + 1 == agent.path.len() && agent.max_acceleration > 0.0 {
+            // slow down when approaching final target.
+            agent
+                .max_speed
+                .min((2.0 * agent.max_acceleration * distance).sqrt())
+        } else {
+            agent.max_speed
+        };
+        agent.desired_velocity = diff / distance * speed;
+    }
+
+    fn sample_velocity(
+        agent: &mut NavCrowdAgent,
+        neighbors: &[(NavVec3, NavVec3, Scalar, Scalar)],
+    ) -> NavVec3 {
+        agent.samples.clear();
+        let desired = agent.desired_velocity;
+        if neighbors.is_empty() || agent.max_speed < ZERO_TRESHOLD {
+            return desired;
+        }
+        // candidates are sampled on ground plane.
+        let up_axis = agent.up_axis;
+        let flat_desired = up_axis.rotate_to_z_up(desired);
+        let base = flat_desired.y.atan2(flat_desired.x);
+        let mut candidates = Vec::with_capacity(AVOIDANCE_DIRECTIONS * AVOIDANCE_RINGS + 2);
+        candidates.push(desired);
+        candidates.push(NavVec3::default());
+        for ring in 1..=AVOIDANCE_RINGS {
+            let speed = agent.max_speed * ring as Scalar / AVOIDANCE_RINGS as Scalar;
+            for direction in 0..AVOIDANCE_DIRECTIONS {
+                let angle = base
+                    + std::f64::consts::TAU as Scalar * direction as Scalar
+                        / AVOIDANCE_DIRECTIONS as Scalar;
+                candidates.push(up_axis.rotate_from_z_up(NavVec3::new(
+                    angle.cos() * speed,
+                    angle.sin() * speed,
+                    0.0,
+                )));
+            }
+        }
+        let inv_speed = 1.0 / agent.max_speed;
+        let params = &agent.avoidance;
+        let time_horizon = params.time_horizon.max(ZERO_TRESHOLD);
+        let samples = candidates
+            .into_iter()
+            .map(|candidate| {
+                let toi = neighbors
+                    .iter()
+                    .map(|(position, velocity, radius, share)| {
+                        // reciprocal velocity obstacle: agents split avoidance effort, so agent
+                        // taking given share of it reaches candidate velocity by changing its
+                        // velocity only by that share of relative velocity change.
+                        let relative_velocity =
+                            (candidate - agent.velocity) / *share + agent.velocity - *velocity;
+                        time_to_impact(
+                            up_axis.flatten(*position - agent.position),
+                            up_axis.flatten(relative_velocity),
+                            agent.radius + radius,
+                        )
+                    })
+                    .fold(time_horizon, Scalar::min);
+                let penalty =
+                    params.weight_desired_velocity * (candidate - desired).magnitude() * inv_speed
+                        + params.weight_current_velocity
+                            * (candidate - agent.velocity).magnitude()
+                            * inv_speed
+                        + params.weight_time_to_impact / (0.1 + toi / time_horizon);
+                NavAvoidanceSample {
+                    velocity: candidate,
+                    penalty,
+                    time_to_impact: toi,
+                    selected: false,
+                }
+            })
+            .collect::<Vec<_>>();
+        let best = samples
+            .iter()
+            .enumerate()
+            .fold(
+                None,
+                |best: Option<(usize, Scalar)>, (index, item)| match best {
+                    Some(best) if best.1 <= item.penalty => Some(best),
+                    _ => Some((index, item.penalty)),
+                },
+            )
+            .map(|(index, _)| index);
+        let result = best.map(|index| samples[index].velocity).unwrap_or(desired);
+        if agent.avoidance.record_samples {
+            agent.samples = samples;
+            if let Some(index) = best {
+                agent.samples[index].selected = true;
+            }
+        }
+        result
+    }
+
+    fn resolve_collisions(&mut self, tolerance: NavTolerance) {
+        for _ in 0..COLLISION_ITERATIONS {
+            let mut snapshot = self
+                .agents
+                .iter()
+                .filter(|(_, agent)| agent.lod != NavAgentLod::Offscreen)
+                .map(|(id, agent)| {
+                    (
+                        *id,
+                        agent.position,
+                        agent.radius,
+                        (agent.priority, agent.is_idle()),
+                    )
+                })
+                .collect::<Vec<_>>();
+            // pushes get summed in stable order, so restored crowds replay the same way.
+            snapshot.sort_unstable_by_key(|(id, _, _, _)| *id);
+            let push_idle = self.push_idle_agents;
+            let mut resolved = true;
+            // only agents with full simulation get pushed, coarse ones just block them.
+            for (id, agent) in self.agents.iter_mut() {
+                if agent.lod != NavAgentLod::Full {
+                    continue;
+                }
+                let mut push = NavVec3::default();
+                let mut count = 0;
+                for (other_id, position, radius, other) in &snapshot {
+                    if other_id == id {
+                        continue;
+                    }
+                    let diff = agent.up_axis.flatten(agent.position - *position);
+                    let limit = agent.radius + radius;
+                    let distance = diff.magnitude();
+                    let share = agent.avoidance_share(*other, push_idle);
+                    if distance < limit - tolerance.distance && share > 0.0 {
+                        let dir = if distance > tolerance.distance {
+                            diff / distance
+                        } else {
+                            NavVec3::new(1.0, 0.0, 0.0)
+                        };
+                        push = push + dir * ((limit - distance) * share);
+                        count += 1;
+                    }
+                }
+                if count > 0 {
+                    agent.position = agent.position + push / count as Scalar;
+                    resolved = false;
+                }
+            }
+            if resolved {
+                break;
+            }
+        }
+    }
+}
+
+/// Agents standing on triangles within radius from given point.
+fn indexed_agents(
+    index: &HashMap<usize, Vec<NavAgentID>>,
+    mesh: &NavMesh,
+    position: NavVec3,
+    radius: Scalar,
+) -> Vec<NavAgentID> {
+    mesh.spatial_index()
+        .lookup_in_circle(&position, &(radius * radius))
+        .into_iter()
+        .filter_map(|spatial| index.get(&spatial.index))
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Time after which circle moving with `velocity` from origin hits circle at `position`.
+fn time_to_impact(position: NavVec3, velocity: NavVec3, radius: Scalar) -> Scalar {
+    let c = position.sqr_magnitude() - radius * radius;
+    let b = position.dot(velocity);
+    if c < 0.0 {
+        // already overlapping - moving apart is fine, moving closer is not.
+        return if b > 0.0 { 0.0 } else { Scalar::INFINITY };
+    }
+    let a = velocity.sqr_magnitude();
+    if a < ZERO_TRESHOLD || b <= 0.0 {
+        return Scalar::INFINITY;
+    }
+    let d = b * b - a * c;
+    if d < 0.0 {
+        return Scalar::INFINITY;
+    }
+    (b - d.sqrt()) / a
+}
@@ -0,0 +1,214 @@
+//! Extraction of debug geometry as plain line and triangle lists, so any renderer can draw nav
+//! mesh internals.
+
+use crate::{NavCrowd, NavMesh, NavPath, NavSpatialObject, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use spade::rtree::{DirectoryNodeData, RTreeNode};
+
+/// Number of segments used to draw circles.
+const CIRCLE_SEGMENTS: usize = 16;
+
+/// Color of debug geometry.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavDebugColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl NavDebugColor {
+    pub const WHITE: Self = Self::new(1.0, 1.0, 1.0, 1.0);
+    pub const RED: Self = Self::new(1.0, 0.0, 0.0, 1.0);
+    pub const GREEN: Self = Self::new(0.0, 1.0, 0.0, 1.0);
+    pub const BLUE: Self = Self::new(0.0, 0.0, 1.0, 1.0);
+    pub const YELLOW: Self = Self::new(1.0, 1.0, 0.0, 1.0);
+    pub const CYAN: Self = Self::new(0.0, 1.0, 1.0, 1.0);
+    pub const MAGENTA: Self = Self::new(1.0, 0.0, 1.0, 1.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Distinct color for given index (e.g. area type or tree depth), with hues spread by golden
+    /// angle so neighboring indices are easy to tell apart.
+    pub fn from_index(index: usize) -> Self {
+        let hue = (index as f32 * 0.618_034).fract() * 6.0;
+        let x = 1.0 - (hue % 2.0 - 1.0).abs();
+        let (r, g, b) = match hue as usize {
+            0 => (1.0, x, 0.0),
+            1 => (x, 1.0, 0.0),
+            2 => (0.0, 1.0, x),
+            3 => (0.0, x, 1.0),
+            4 => (x, 0.0, 1.0),
+            _ => (1.0, 0.0, x),
+        };
+        Self::new(r, g, b, 1.0)
+    }
+}
+
+/// Debug line: (from, to, color).
+pub type NavDebugLine = (NavVec3, NavVec3, NavDebugColor);
+
+/// Debug triangle: (first, second, third, color).
+pub type NavDebugTriangle = (NavVec3, NavVec3, NavVec3, NavDebugColor);
+
+/// Walkable (not blocked) nav mesh triangles colored by their area type.
+///
+/// # Arguments
+/// * `mesh` - nav mesh.
+pub fn debug_mesh_triangles(mesh: &NavMesh) -> Vec<NavDebugTriangle> {
+    let vertices = mesh.vertices();
+    mesh.triangles()
+        .iter()
+        .zip(mesh.areas())
+        .enumerate()
+        .filter(|(index, _)| !mesh.is_triangle_blocked(*index))
+        .map(|(_, (t, area))| {
+            (
+                vertices[t.first as usize],
+                vertices[t.second as usize],
+                vertices[t.third as usize],
+                NavDebugColor::from_index(area.area_type as usize),
+            )
+        })
+        .collect()
+}
+
+/// Connections of triangles graph, drawn between triangles centers. Connections to blocked
+/// triangles are drawn red.
+///
+/// # Arguments
+/// * `mesh` - nav mesh.
+/// * `color` - color of walkable connections.
+pub fn debug_mesh_connections(mesh: &NavMesh, color: NavDebugColor) -> Vec<NavDebugLine> {
+    let areas = mesh.areas();
+    (0..mesh.triangles().len())
+        .flat_map(|from| {
+            mesh.triangle_neighbors(from)
+                .filter(move |to| from < *to)
+                .map(move |to| {
+                    let color = if mesh.is_triangle_blocked(from) || mesh.is_triangle_blocked(to) {
+                        NavDebugColor::RED
+                    } else {
+                        color
+                    };
+                    (areas[from].center, areas[to].center, color)
+                })
+        })
+        .collect()
+}
+
+/// Bounding boxes of nav mesh spatial index (AABB tree) nodes, colored by node depth.
+///
+/// # Arguments
+/// * `mesh` - nav mesh.
+pub fn debug_mesh_bounds(mesh: &NavMesh) -> Vec<NavDebugLine> {
+    fn visit(node: &DirectoryNodeData<NavSpatialObject>, result: &mut Vec<NavDebugLine>) {
+        if node.children().is_empty() {
+            return;
+        }
+        let mbr = node.mbr();
+        let color = NavDebugColor::from_index(node.depth());
+        result.extend(
+            box_lines(mbr.lower(), mbr.upper())
+                .into_iter()
+                .map(|(from, to)| (from, to, color)),
+        );
+        for child in node.children() {
+            if let RTreeNode::DirectoryNode(child) = child {
+                visit(child, result);
+            }
+        }
+    }
+
+    let mut result = vec![];
+    visit(mesh.spatial_index().root(), &mut result);
+    result
+}
+
+/// Path corridor triangles outline (white), portals between corridor triangles (cyan) and path
+/// points found by funnel (yellow).
+///
+/// # Arguments
+/// * `mesh` - nav mesh path was found on.
+/// * `path` - path.
+pub fn debug_path(mesh: &NavMesh, path: &NavPath) -> Vec<NavDebugLine> {
+    let vertices = mesh.vertices();
+    let mut result = vec![];
+    for triangle in &path.triangles {
+        if let Some(t) = mesh.triangles().get(*triangle) {
+            let a = vertices[t.first as usize];
+            let b = vertices[t.second as usize];
+            let c = vertices[t.third as usize];
+            for (from, to) in [(a, b), (b, c), (c, a)] {
+                result.push((from, to, NavDebugColor::WHITE));
+            }
+        }
+    }
+    for pair in path.triangles.windows(2) {
+        if let Some((from, to)) = mesh.triangles_portal(pair[0], pair[1]) {
+            result.push((from, to, NavDebugColor::CYAN));
+        }
+    }
+    for pair in path.points.windows(2) {
+        result.push((pair[0], pair[1], NavDebugColor::YELLOW));
+    }
+    result
+}
+
+/// Crowd agents radius circles (white), desired velocities (green) and velocities chosen by
+/// local avoidance (magenta).
+///
+/// # Arguments
+/// * `crowd` - nav crowd.
+/// * `mesh` - nav mesh that agents move on, its up axis tells circles plane.
+pub fn debug_crowd(crowd: &NavCrowd, mesh: &NavMesh) -> Vec<NavDebugLine> {
+    let up_axis = mesh.up_axis();
+    let mut result = vec![];
+    for (_, agent) in crowd.agents() {
+        let position = agent.position;
+        let points = (0..=CIRCLE_SEGMENTS)
+            .map(|i| {
+                let angle =
+                    std::f64::consts::TAU as Scalar * i as Scalar / CIRCLE_SEGMENTS as Scalar;
+                let offset = NavVec3::new(angle.cos(), angle.sin(), 0.0) * agent.radius;
+                position + up_axis.rotate_from_z_up(offset)
+            })
+            .collect::<Vec<_>>();
+        for pair in points.windows(2) {
+            result.push((pair[0], pair[1], NavDebugColor::WHITE));
+        }
+        result.push((
+            position,
+            position + agent.desired_velocity(),
+            NavDebugColor::GREEN,
+        ));
+        result.push((
+            position,
+            position + agent.velocity(),
+            NavDebugColor::MAGENTA,
+        ));
+    }
+    result
+}
+
+fn box_lines(min: NavVec3, max: NavVec3) -> Vec<(NavVec3, NavVec3)> {
+    let corner = |i: usize| {
+        NavVec3::new(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        )
+    };
+    (0..8)
+        .flat_map(|i| {
+            [1, 2, 4]
+                .into_iter()
+                .filter(move |bit| i & bit == 0)
+                .map(move |bit| (corner(i), corner(i | bit)))
+        })
+        .collect()
+}
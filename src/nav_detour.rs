@@ -0,0 +1,281 @@
+use crate::{
+    nav_binary::BinaryReader, Error, NavFanTriangulator, NavMesh, NavResult, NavTriangle,
+    NavTriangulator, NavVec3, Scalar,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 'MSET' tiles set magic (as little endian int) used by RecastNavigation samples.
+const SET_MAGIC: &[u8; 4] = b"TESM";
+const SET_VERSION: u32 = 1;
+/// 'DNAV' tile data magic (as little endian int).
+const TILE_MAGIC: &[u8; 4] = b"VAND";
+const TILE_VERSION: u32 = 7;
+const TILE_HEADER_SIZE: usize = 100;
+const VERTS_PER_POLYGON: usize = 6;
+const POLY_SIZE: usize = 32;
+const DETAIL_MESH_SIZE: usize = 12;
+const BV_NODE_SIZE: usize = 16;
+const OFF_MESH_CONNECTION_SIZE: usize = 36;
+const POLYTYPE_OFFMESH_CONNECTION: u8 = 1;
+/// Vertices closer than inverse of this value are welded together, so tiles get connected.
+const WELD_PRECISION: Scalar = 1e4;
+
+/// Walkable geometry read from navigation mesh baked by RecastNavigation (Detour `dtNavMesh`).
+///
+/// Supports tiles set files (`MSET`) saved by RecastNavigation samples, as well as single tile
+/// data (`DNAV`), in little endian byte order with either 32 or 64 bit polygon references.
+/// Detail meshes are used for triangles when present, otherwise polygons are triangulated.
+/// Off-mesh connections are skipped. Detour Y-up coordinates are converted into Z-up ones.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavDetourMesh {
+    pub vertices: Vec<NavVec3>,
+    pub triangles: Vec<NavTriangle>,
+    /// Detour area id of every triangle.
+    pub area_types: Vec<u8>,
+    /// Detour polygon flags of every triangle.
+    pub flags: Vec<u16>,
+}
+
+impl NavDetourMesh {
+    /// Read Detour navigation mesh data.
+    ///
+    /// # Arguments
+    /// * `bytes` - either tiles set or single tile data.
+    ///
+    /// # Returns
+    /// `Ok` with walkable geometry or `Err` with `Error::CouldNotDeserializeNavMesh`.
+    pub fn from_bytes(bytes: &[u8]) -> NavResult<Self> {
+        let mut result = Self::default();
+        let mut welded = HashMap::new();
+        if bytes.starts_with(SET_MAGIC) {
+            let mut reader = BinaryReader::new(bytes);
+            reader.read_bytes(4)?;
+            let version = reader.read_u32()?;
+            if version != SET_VERSION {
+                return Err(Error::CouldNotDeserializeNavMesh(format!(
+                    "Unsupported Detour tiles set version: {}",
+                    version
+                )));
+            }
+            let count = reader.read_u32()?;
+            // nav mesh params: origin, tile width, tile height, max tiles, max polys.
+            reader.read_bytes(28)?;
+            for _ in 0..count {
+                let rest = reader.remaining();
+                let narrow = rest.len() >= 12 && &rest[8..12] == TILE_MAGIC;
+                let wide = rest.len() >= 20 && &rest[16..20] == TILE_MAGIC;
+                let (tile_ref, size) = if wide && !narrow {
+                    let tile_ref = reader.read_u64()?;
+                    let size = reader.read_u32()?;
+                    reader.read_u32()?;
+                    (tile_ref, size)
+                } else {
+                    (reader.read_u32()? as u64, reader.read_u32()?)
+                };
+                if tile_ref == 0 || size == 0 {
+                    break;
+                }
+                result.read_tile(reader.read_bytes(size as usize)?, &mut welded)?;
+            }
+        } else if bytes.starts_with(TILE_MAGIC) {
+            result.read_tile(bytes, &mut welded)?;
+        } else if bytes.starts_with(b"MSET") || bytes.starts_with(b"DNAV") {
+            return Err(Error::CouldNotDeserializeNavMesh(
+                "Big endian Detour data is not supported".to_owned(),
+            ));
+        } else {
+            return Err(Error::CouldNotDeserializeNavMesh(
+                "Data is not a Detour nav mesh".to_owned(),
+            ));
+        }
+        Ok(result)
+    }
+
+    /// Build nav mesh out of Detour geometry, with area types and flags applied to its areas.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh or `Err` if geometry is invalid.
+    pub fn into_nav_mesh(self) -> NavResult<NavMesh> {
+        let mut mesh = NavMesh::new(self.vertices, self.triangles)?;
+        for (index, (area_type, flags)) in self.area_types.into_iter().zip(self.flags).enumerate() {
+            mesh.set_area_type(index, area_type);
+            mesh.set_area_flags(index, flags);
+        }
+        Ok(mesh)
+    }
+
+    fn read_tile(&mut self, data: &[u8], welded: &mut HashMap<[i64; 3], u32>) -> NavResult<()> {
+        let invalid = |message: &str| Error::CouldNotDeserializeNavMesh(message.to_owned());
+        let mut reader = BinaryReader::new(data);
+        reader.set_scalar_size(4)?;
+        if reader.read_bytes(4)? != TILE_MAGIC {
+            return Err(invalid("Data is not a Detour tile"));
+        }
+        let version = reader.read_u32()?;
+        if version != TILE_VERSION {
+            return Err(Error::CouldNotDeserializeNavMesh(format!(
+                "Unsupported Detour tile version: {}",
+                version
+            )));
+        }
+        // tile x, y, layer and user id.
+        reader.read_bytes(16)?;
+        let poly_count = reader.read_u32()? as usize;
+        let vert_count = reader.read_u32()? as usize;
+        let max_link_count = reader.read_u32()? as usize;
+        let detail_mesh_count = reader.read_u32()? as usize;
+        let detail_vert_count = reader.read_u32()? as usize;
+        let detail_tri_count = reader.read_u32()? as usize;
+        let bv_node_count = reader.read_u32()? as usize;
+        let off_mesh_con_count = reader.read_u32()? as usize;
+        // off-mesh base.
+        reader.read_u32()?;
+        // agent params, bounds and bv quantization factor.
+        reader.read_bytes(TILE_HEADER_SIZE - 60)?;
+
+        // counts come from untrusted data, so make sure they fit in it before allocating.
+        let size = [
+            (vert_count, 12),
+            (poly_count, POLY_SIZE),
+            (max_link_count, 12),
+            (detail_mesh_count, DETAIL_MESH_SIZE),
+            (detail_vert_count, 12),
+            (detail_tri_count, 4),
+            (bv_node_count, BV_NODE_SIZE),
+            (off_mesh_con_count, OFF_MESH_CONNECTION_SIZE),
+        ]
+        .iter()
+        .fold(TILE_HEADER_SIZE, |size, (count, item_size)| {
+            size.saturating_add(count.saturating_mul(*item_size))
+        });
+        if size > data.len() {
+            return Err(invalid("Unexpected end of Detour tile data"));
+        }
+        let link_size = if data.len() == size.saturating_add(max_link_count * 4) {
+            16
+        } else {
+            12
+        };
+
+        let vertices = (0..vert_count)
+            .map(|_| reader.read_vec3().map(convert))
+            .collect::<NavResult<Vec<_>>>()?;
+        // (vertices, flags, area, type)
+        let mut polys = Vec::with_capacity(poly_count);
+        for _ in 0..poly_count {
+            reader.read_u32()?;
+            let mut verts = [0; VERTS_PER_POLYGON];
+            for vert in &mut verts {
+                *vert = reader.read_u16()? as usize;
+            }
+            reader.read_bytes(VERTS_PER_POLYGON * 2)?;
+            let flags = reader.read_u16()?;
+            let count = (reader.read_u8()? as usize).min(VERTS_PER_POLYGON);
+            let area_and_type = reader.read_u8()?;
+            if verts[..count].iter().any(|v| *v >= vertices.len()) {
+                return Err(invalid("Detour polygon vertice index out of bounds"));
+            }
+            polys.push((
+                verts[..count].to_vec(),
+                flags,
+                area_and_type & 0x3f,
+                area_and_type >> 6,
+            ));
+        }
+        reader.read_bytes(max_link_count * link_size)?;
+        // (vertice base, triangle base, triangles count)
+        let mut details = Vec::with_capacity(detail_mesh_count);
+        for _ in 0..detail_mesh_count {
+            let vert_base = reader.read_u32()? as usize;
+            let tri_base = reader.read_u32()? as usize;
+            reader.read_u8()?;
+            let tri_count = reader.read_u8()? as usize;
+            reader.read_u16()?;
+            details.push((vert_base, tri_base, tri_count));
+        }
+        let detail_vertices = (0..detail_vert_count)
+            .map(|_| reader.read_vec3().map(convert))
+            .collect::<NavResult<Vec<_>>>()?;
+        let detail_triangles = reader.read_bytes(detail_tri_count * 4)?;
+
+        for (index, (verts, flags, area, kind)) in polys.iter().enumerate() {
+            if *kind == POLYTYPE_OFFMESH_CONNECTION || verts.len() < 3 {
+                continue;
+            }
+            match details.get(index) {
+                Some((vert_base, tri_base, tri_count)) => {
+                    for triangle in 0..*tri_count {
+                        let offset = (tri_base + triangle) * 4;
+                        let local = detail_triangles
+                            .get(offset..(offset + 3))
+                            .ok_or_else(|| invalid("Detour detail triangle out of bounds"))?;
+                        let mut points = [NavVec3::default(); 3];
+                        for (point, local) in points.iter_mut().zip(local) {
+                            let local = *local as usize;
+                            *point = if local < verts.len() {
+                                vertices[verts[local]]
+                            } else {
+                                *detail_vertices
+                                    .get(vert_base + local - verts.len())
+                                    .ok_or_else(|| invalid("Detour detail vertice out of bounds"))?
+                            };
+                        }
+                        self.push_triangle(points, *flags, *area, welded);
+                    }
+                }
+                None => {
+                    let points = verts.iter().map(|v| vertices[*v]).collect::<Vec<_>>();
+                    for (a, b, c) in NavFanTriangulator.triangulate(&points).unwrap_or_default() {
+                        self.push_triangle(
+                            [points[a], points[b], points[c]],
+                            *flags,
+                            *area,
+                            welded,
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_triangle(
+        &mut self,
+        mut points: [NavVec3; 3],
+        flags: u16,
+        area: u8,
+        welded: &mut HashMap<[i64; 3], u32>,
+    ) {
+        // walkable surface faces up.
+        if (points[1] - points[0]).cross(points[2] - points[0]).z < 0.0 {
+            points.swap(1, 2);
+        }
+        let mut indices = [0; 3];
+        for (index, point) in indices.iter_mut().zip(points.iter()) {
+            let key = [
+                (point.x * WELD_PRECISION).round() as i64,
+                (point.y * WELD_PRECISION).round() as i64,
+                (point.z * WELD_PRECISION).round() as i64,
+            ];
+            let count = self.vertices.len() as u32;
+            *index = *welded.entry(key).or_insert(count);
+            if *index == count {
+                self.vertices.push(*point);
+            }
+        }
+        if indices[0] == indices[1] || indices[1] == indices[2] || indices[2] == indices[0] {
+            return;
+        }
+        self.triangles
+            .push(NavTriangle::from((indices[0], indices[1], indices[2])));
+        self.area_types.push(area);
+        self.flags.push(flags);
+    }
+}
+
+fn convert(point: NavVec3) -> NavVec3 {
+    NavVec3::new(point.x, -point.z, point.y)
+}
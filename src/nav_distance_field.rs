@@ -0,0 +1,168 @@
+use crate::{nav_open_node::OpenNode, NavMesh, NavQuery, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use spade::SpatialObject;
+use std::collections::{BinaryHeap, HashSet};
+
+/// Precomputed distances to nav mesh boundary, sampled at triangles vertices and centers, for
+/// cheap clearance lookups in steering or spawning that run for many points every frame.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavDistanceField {
+    vertices: Vec<Scalar>,
+    triangles: Vec<Scalar>,
+}
+
+impl NavDistanceField {
+    /// Distance of nav mesh vertice to boundary.
+    #[inline]
+    pub fn vertice_distance(&self, index: usize) -> Option<Scalar> {
+        self.vertices.get(index).copied()
+    }
+
+    /// Distance of nav mesh triangle center to boundary.
+    #[inline]
+    pub fn triangle_distance(&self, index: usize) -> Option<Scalar> {
+        self.triangles.get(index).copied()
+    }
+
+    /// Highest distance to boundary found in field.
+    pub fn max_distance(&self) -> Scalar {
+        self.vertices
+            .iter()
+            .chain(self.triangles.iter())
+            .copied()
+            .fold(0.0, Scalar::max)
+    }
+
+    /// Approximate distance of point to boundary, interpolated from distances of vertices of
+    /// triangle closest to point.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh this field was built from.
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with distance or `None` if point is not near any triangle.
+    pub fn distance(&self, mesh: &NavMesh, point: NavVec3) -> Option<Scalar> {
+        let index = mesh.find_closest_triangle(point, NavQuery::Accuracy)?;
+        let triangle = mesh.triangles().get(index)?;
+        let spatial = mesh.spatial(index);
+        let weights = spatial.barycentric(spatial.closest_point(point));
+        Some(
+            self.vertice_distance(triangle.first as usize)? * weights.x
+                + self.vertice_distance(triangle.second as usize)? * weights.y
+                + self.vertice_distance(triangle.third as usize)? * weights.z,
+        )
+    }
+}
+
+impl NavMesh {
+    /// Find point on nav mesh boundary closest to given point, searching only surface connected
+    /// to triangle closest to given point (so walls of other floors are not taken into account).
+    ///
+    /// Boundary is made of triangles edges not shared with other triangles, triangles blocked by
+    /// obstacles or closed gates are not treated as boundary.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with boundary point or `None` if nav mesh has no boundary.
+    pub fn closest_boundary_point(&self, point: NavVec3) -> Option<NavVec3> {
+        let start = self.find_closest_triangle(point, NavQuery::Accuracy)?;
+        let mut best = None::<(Scalar, NavVec3)>;
+        let mut open = BinaryHeap::new();
+        let mut visited = HashSet::new();
+        open.push(OpenNode(self.spatial(start).distance2(&point), start));
+        visited.insert(start);
+        while let Some(OpenNode(distance, index)) = open.pop() {
+            if best.map(|(best, _)| distance >= best).unwrap_or_default() {
+                break;
+            }
+            for (a, b) in self.triangle_boundary_edges(index) {
+                let candidate = point.closest_point_on_segment(*a, *b);
+                let candidate_distance = candidate.distance_squared(point);
+                if best
+                    .map(|(best, _)| candidate_distance < best)
+                    .unwrap_or(true)
+                {
+                    best = Some((candidate_distance, candidate));
+                }
+            }
+            for neighbor in self.triangle_neighbors(index) {
+                if visited.insert(neighbor) {
+                    open.push(OpenNode(self.spatial(neighbor).distance2(&point), neighbor));
+                }
+            }
+        }
+        best.map(|(_, point)| point)
+    }
+
+    /// Find distance of point to nav mesh boundary, see `closest_boundary_point`.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with distance or `None` if nav mesh has no boundary.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// assert_eq!(mesh.distance_to_boundary((1.0, 0.5, 0.0).into()), Some(0.5));
+    /// assert_eq!(mesh.distance_to_boundary((1.75, 0.5, 0.0).into()), Some(0.25));
+    /// assert_eq!(
+    ///     mesh.closest_boundary_point((1.75, 0.5, 0.0).into()),
+    ///     Some((2.0, 0.5, 0.0).into()),
+    /// );
+    ///
+    /// let field = mesh.build_distance_field();
+    /// assert_eq!(field.vertice_distance(4), Some(0.0));
+    /// // triangles centers are furthest from boundary.
+    /// assert!((field.max_distance() - 1.0 / 3.0).abs() < 1.0e-5);
+    /// ```
+    pub fn distance_to_boundary(&self, point: NavVec3) -> Option<Scalar> {
+        self.closest_boundary_point(point)
+            .map(|boundary| boundary.distance(point))
+    }
+
+    /// Build distance field telling distances of triangles vertices and centers to nav mesh
+    /// boundary, see `distance_to_boundary`.
+    ///
+    /// Field does not change together with nav mesh, so it should be built again after nav mesh
+    /// geometry gets replaced.
+    pub fn build_distance_field(&self) -> NavDistanceField {
+        let distance = |point| self.distance_to_boundary(point).unwrap_or(0.0);
+        NavDistanceField {
+            vertices: self
+                .vertices()
+                .iter()
+                .map(|point| distance(*point))
+                .collect(),
+            triangles: (0..self.triangles().len())
+                .map(|index| {
+                    let spatial = self.spatial(index);
+                    distance((spatial.a + spatial.b + spatial.c) / 3.0)
+                })
+                .collect(),
+        }
+    }
+}
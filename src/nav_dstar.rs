@@ -0,0 +1,379 @@
+//! D* Lite incremental path finding, repairing previously found path when costs change instead of
+//! searching from scratch.
+
+use crate::{NavGrid, NavGridNeighborhood, NavMesh, Scalar};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// Graph that D* Lite can search, with nodes identified by indices.
+///
+/// Moves costs are expected to be symmetric (moving from `a` to `b` costs the same as moving from
+/// `b` to `a`).
+pub trait NavDStarGraph {
+    /// List of nodes connected to given node together with cost of moving there, infinite for
+    /// currently impassable connections that can become passable later.
+    fn successors(&self, node: usize) -> Vec<(usize, Scalar)>;
+
+    /// Lower bound of path cost between two nodes, assuming lowest moves costs.
+    fn heuristic(&self, from: usize, to: usize) -> Scalar;
+}
+
+/// Grid nodes are cells indices (see `NavGrid::index`).
+impl NavDStarGraph for NavGrid {
+    fn successors(&self, node: usize) -> Vec<(usize, Scalar)> {
+        let (col, row) = match self.coord(node) {
+            Some(coord) => coord,
+            None => return vec![],
+        };
+        let neighbors = match self.neighbors(col, row) {
+            Some(neighbors) => neighbors,
+            None => return vec![],
+        };
+        let cost = self.cells_costs()[node];
+        neighbors
+            .filter_map(|(c, r)| {
+                let index = self.index(c, r)?;
+                let offset = (c as isize - col as isize, r as isize - row as isize);
+                let distance = self
+                    .neighborhood()
+                    .map(|neighborhood| neighborhood.distance(offset))
+                    .unwrap_or(1.0);
+                Some((index, cost * self.cells_costs()[index] * distance))
+            })
+            .collect()
+    }
+
+    fn heuristic(&self, from: usize, to: usize) -> Scalar {
+        match (self.neighborhood(), self.coord(from), self.coord(to)) {
+            (Some(NavGridNeighborhood::Hex), _, _) | (None, _, _) => 0.0,
+            (_, Some(a), Some(b)) => {
+                let dx = a.0 as Scalar - b.0 as Scalar;
+                let dy = a.1 as Scalar - b.1 as Scalar;
+                (dx * dx + dy * dy).sqrt()
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// Nav mesh nodes are triangles indices. Blocked triangles are impassable.
+impl NavDStarGraph for NavMesh {
+    fn successors(&self, node: usize) -> Vec<(usize, Scalar)> {
+        self.triangle_neighbors(node)
+            .map(|next| {
+                (
+                    next,
+                    self.connection_cost(node, next).unwrap_or(Scalar::INFINITY),
+                )
+            })
+            .collect()
+    }
+
+    fn heuristic(&self, _: usize, _: usize) -> Scalar {
+        // connections weights are squared distances, so distance is not their lower bound.
+        0.0
+    }
+}
+
+/// D* Lite query, keeping search state between path requests so when few nodes change cost (for
+/// example cells or triangles blocked by moving obstacles) or agent moves along path, path gets
+/// repaired by visiting only affected part of graph instead of being searched again.
+///
+/// Search goes backwards from goal, so goal stays fixed and start can move freely.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let mut grid = NavGrid::new(5, 3, vec![true; 15]).unwrap();
+/// let start = grid.index(0, 1).unwrap();
+/// let goal = grid.index(4, 1).unwrap();
+/// let mut query = NavDStarLite::new(&grid, start, goal);
+/// let path = query.find_path(&grid).unwrap();
+/// assert_eq!(path.len(), 5);
+///
+/// // obstacle moves onto middle of path.
+/// grid.set_cell_cost(2, 1, 100.0);
+/// query.notify_changed(&grid, &[grid.index(2, 1).unwrap()]);
+/// let path = query.find_path(&grid).unwrap();
+/// assert_eq!(path.len(), 7);
+/// assert!(!path.contains(&grid.index(2, 1).unwrap()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NavDStarLite {
+    start: usize,
+    goal: usize,
+    last: usize,
+    km: Scalar,
+    heuristic_factor: Scalar,
+    g: HashMap<usize, Scalar>,
+    rhs: HashMap<usize, Scalar>,
+    open: BinaryHeap<DStarNode>,
+    // current keys of nodes in open set, older heap entries are skipped.
+    queued: HashMap<usize, (Scalar, Scalar)>,
+    expansions: usize,
+}
+
+impl NavDStarLite {
+    /// Create D* Lite query. Search happens lazily in `find_path`.
+    ///
+    /// # Arguments
+    /// * `graph` - searched graph.
+    /// * `start` - start node.
+    /// * `goal` - goal node.
+    pub fn new<G>(graph: &G, start: usize, goal: usize) -> Self
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        Self::with_heuristic_factor(graph, start, goal, 1.0)
+    }
+
+    /// Create D* Lite query with heuristic scaled by given factor, which should not exceed lowest
+    /// move cost factor (for grids squared lowest cell cost) to keep paths shortest.
+    ///
+    /// # Arguments
+    /// * `graph` - searched graph.
+    /// * `start` - start node.
+    /// * `goal` - goal node.
+    /// * `heuristic_factor` - heuristic scale.
+    pub fn with_heuristic_factor<G>(
+        graph: &G,
+        start: usize,
+        goal: usize,
+        heuristic_factor: Scalar,
+    ) -> Self
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        let mut result = Self {
+            start,
+            goal,
+            last: start,
+            km: 0.0,
+            heuristic_factor: heuristic_factor.max(0.0),
+            g: Default::default(),
+            rhs: Default::default(),
+            open: Default::default(),
+            queued: Default::default(),
+            expansions: 0,
+        };
+        result.rhs.insert(goal, 0.0);
+        let key = (result.h(graph, start, goal), 0.0);
+        result.push(goal, key);
+        result
+    }
+
+    #[inline]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    #[inline]
+    pub fn goal(&self) -> usize {
+        self.goal
+    }
+
+    /// Number of nodes expanded by last `find_path` call, telling how much work path repair took.
+    #[inline]
+    pub fn last_expansions(&self) -> usize {
+        self.expansions
+    }
+
+    /// Move start node, for example when agent advanced along path.
+    ///
+    /// # Arguments
+    /// * `graph` - searched graph.
+    /// * `start` - new start node.
+    pub fn set_start<G>(&mut self, graph: &G, start: usize)
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        self.km += self.h(graph, self.last, start);
+        self.last = start;
+        self.start = start;
+    }
+
+    /// Tell that costs of moves from and to given nodes have changed.
+    ///
+    /// # Arguments
+    /// * `graph` - searched graph, already containing changed costs.
+    /// * `nodes` - nodes which costs have changed.
+    pub fn notify_changed<G>(&mut self, graph: &G, nodes: &[usize])
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        for node in nodes {
+            for (neighbor, _) in graph.successors(*node) {
+                self.update_rhs(graph, neighbor);
+            }
+            self.update_rhs(graph, *node);
+        }
+    }
+
+    /// Find path from start node to goal node, repairing previously found one if costs changed.
+    ///
+    /// # Arguments
+    /// * `graph` - searched graph.
+    ///
+    /// # Returns
+    /// `Some` with path nodes (starting with start node and ending with goal node) or `None` if
+    /// goal cannot be reached.
+    pub fn find_path<G>(&mut self, graph: &G) -> Option<Vec<usize>>
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        self.compute_shortest_path(graph);
+        if self.rhs(self.start) == Scalar::INFINITY {
+            return None;
+        }
+        let mut path = vec![self.start];
+        let mut current = self.start;
+        while current != self.goal {
+            let next = graph
+                .successors(current)
+                .into_iter()
+                .map(|(next, cost)| (next, cost + self.g(next)))
+                .filter(|(next, cost)| *cost < Scalar::INFINITY && !path.contains(next))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))?;
+            current = next.0;
+            path.push(current);
+        }
+        Some(path)
+    }
+
+    fn compute_shortest_path<G>(&mut self, graph: &G)
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        self.expansions = 0;
+        while let Some((key, node)) = self.top() {
+            let start_key = self.key(graph, self.start);
+            if !less(key, start_key) && self.rhs(self.start) <= self.g(self.start) {
+                break;
+            }
+            self.expansions += 1;
+            let new_key = self.key(graph, node);
+            if less(key, new_key) {
+                self.push(node, new_key);
+            } else if self.g(node) > self.rhs(node) {
+                self.queued.remove(&node);
+                self.g.insert(node, self.rhs(node));
+                let g = self.g(node);
+                for (previous, cost) in graph.successors(node) {
+                    if previous != self.goal && cost + g < self.rhs(previous) {
+                        self.rhs.insert(previous, cost + g);
+                    }
+                    self.update_vertex(graph, previous);
+                }
+            } else {
+                self.g.insert(node, Scalar::INFINITY);
+                for (previous, _) in graph.successors(node) {
+                    self.update_rhs(graph, previous);
+                }
+                self.update_rhs(graph, node);
+            }
+        }
+    }
+
+    fn update_rhs<G>(&mut self, graph: &G, node: usize)
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        if node != self.goal {
+            let rhs = graph
+                .successors(node)
+                .into_iter()
+                .map(|(next, cost)| cost + self.g(next))
+                .fold(Scalar::INFINITY, Scalar::min);
+            self.rhs.insert(node, rhs);
+        }
+        self.update_vertex(graph, node);
+    }
+
+    fn update_vertex<G>(&mut self, graph: &G, node: usize)
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        if self.g(node) != self.rhs(node) {
+            let key = self.key(graph, node);
+            self.push(node, key);
+        } else {
+            self.queued.remove(&node);
+        }
+    }
+
+    fn top(&mut self) -> Option<((Scalar, Scalar), usize)> {
+        while let Some(top) = self.open.peek() {
+            if self.queued.get(&top.node) == Some(&top.key) {
+                return Some((top.key, top.node));
+            }
+            self.open.pop();
+        }
+        None
+    }
+
+    fn push(&mut self, node: usize, key: (Scalar, Scalar)) {
+        self.queued.insert(node, key);
+        self.open.push(DStarNode { key, node });
+    }
+
+    fn key<G>(&self, graph: &G, node: usize) -> (Scalar, Scalar)
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        let value = self.g(node).min(self.rhs(node));
+        (value + self.h(graph, self.start, node) + self.km, value)
+    }
+
+    #[inline]
+    fn h<G>(&self, graph: &G, from: usize, to: usize) -> Scalar
+    where
+        G: NavDStarGraph + ?Sized,
+    {
+        graph.heuristic(from, to) * self.heuristic_factor
+    }
+
+    #[inline]
+    fn g(&self, node: usize) -> Scalar {
+        self.g.get(&node).copied().unwrap_or(Scalar::INFINITY)
+    }
+
+    #[inline]
+    fn rhs(&self, node: usize) -> Scalar {
+        self.rhs.get(&node).copied().unwrap_or(Scalar::INFINITY)
+    }
+}
+
+#[inline]
+fn less(a: (Scalar, Scalar), b: (Scalar, Scalar)) -> bool {
+    a.0 < b.0 || (a.0 == b.0 && a.1 < b.1)
+}
+
+/// Open set entry ordered so that `BinaryHeap` pops lowest key first.
+#[derive(Debug, Copy, Clone)]
+struct DStarNode {
+    key: (Scalar, Scalar),
+    node: usize,
+}
+
+impl PartialEq for DStarNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for DStarNode {}
+
+impl PartialOrd for DStarNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DStarNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.partial_cmp(&self.key).unwrap_or(Ordering::Equal)
+    }
+}
@@ -0,0 +1,260 @@
+#[cfg(feature = "std")]
+use crate::NavMesh;
+use crate::{NavVec3, Scalar, ZERO_TRESHOLD};
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Direction path turns to at its corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavPathTurn {
+    /// Path goes around corner on its left side.
+    Left,
+    /// Path goes around corner on its right side.
+    Right,
+}
+
+/// Path corner together with portal edge that generated it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPathCorner {
+    /// Index of corner point in path points.
+    pub point: usize,
+    /// Portal edge as (left, right) pair when looking along path, corner lies on one of its ends.
+    pub portal: (NavVec3, NavVec3),
+    /// Indices of triangles connected by portal, in path order.
+    pub triangles: (usize, usize),
+    /// Direction path turns to at corner.
+    pub turn: NavPathTurn,
+}
+
+/// Find shortest path points through corridor of connected nav mesh triangles, keeping corners
+/// away from nav mesh boundary by given radius.
+///
+/// Meant for corridors found by custom searches (e.g. hierarchical ones), so they are turned into
+/// paths the same way as by `NavMesh::find_path`.
+///
+/// # Arguments
+/// * `mesh` - nav mesh corridor lies on.
+/// * `corridor` - list of connected triangles indices.
+/// * `start` - path start point, lying on first corridor triangle.
+/// * `end` - path end point, lying on last corridor triangle.
+/// * `radius` - distance of path corners from nav mesh boundary.
+///
+/// # Returns
+/// `Some` with path points or `None` if corridor is empty, its triangles are not connected or
+/// some portal is too narrow for given radius.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (2.0, 0.0, 1.0).into(), // 2
+///     (0.0, 1.0, 0.0).into(), // 3
+///     (1.0, 1.0, 0.0).into(), // 4
+///     (2.0, 1.0, 1.0).into(), // 5
+/// ];
+/// let triangles = vec![
+///     (0, 1, 4).into(), // 0
+///     (4, 3, 0).into(), // 1
+///     (1, 2, 5).into(), // 2
+///     (5, 4, 1).into(), // 3
+/// ];
+///
+/// let mesh = NavMesh::new(vertices, triangles).unwrap();
+/// let from = (0.0, 1.0, 0.0).into();
+/// let to = (1.5, 0.25, 0.5).into();
+/// let path = string_pull(&mesh, &[1, 0, 3, 2], from, to, 0.0).unwrap();
+/// assert_eq!(path.first(), Some(&from));
+/// assert_eq!(path.last(), Some(&to));
+/// assert!(string_pull(&mesh, &[1, 2], from, to, 0.0).is_none());
+/// ```
+#[cfg(feature = "std")]
+pub fn string_pull(
+    mesh: &NavMesh,
+    corridor: &[usize],
+    start: NavVec3,
+    end: NavVec3,
+    radius: Scalar,
+) -> Option<Vec<NavVec3>> {
+    if corridor
+        .iter()
+        .any(|triangle| *triangle >= mesh.triangles().len())
+    {
+        return None;
+    }
+    mesh.string_pull(start, end, corridor, radius)
+}
+
+/// Find shortest path points through list of portals (edges that path has to cross), for
+/// corridors that are not made of nav mesh triangles.
+///
+/// # Arguments
+/// * `portals` - list of portals edges, in order from start to end.
+/// * `start` - path start point.
+/// * `end` - path end point.
+/// * `up` - up direction of surface portals lie on.
+///
+/// # Returns
+/// List of path points, starting with `start` and ending with `end`.
+pub fn string_pull_portals(
+    portals: &[(NavVec3, NavVec3)],
+    start: NavVec3,
+    end: NavVec3,
+    up: NavVec3,
+) -> Vec<NavVec3> {
+    let up = up.normalize();
+    let mut oriented = Vec::with_capacity(portals.len() + 2);
+    oriented.push((start, start));
+    let mut previous = start;
+    for (index, portal) in portals.iter().enumerate() {
+        let next = portals
+            .get(index + 1)
+            .map(|(a, b)| (*a + *b) * 0.5)
+            .unwrap_or(end);
+        oriented.push(orient_portal(previous, next, *portal, up));
+        previous = (portal.0 + portal.1) * 0.5;
+    }
+    oriented.push((end, end));
+    funnel(&oriented, up)
+}
+
+/// Orient portal edge so it gets returned as (left, right) pair when looking from `from` point
+/// towards `to` point.
+pub(crate) fn orient_portal(
+    from: NavVec3,
+    to: NavVec3,
+    portal: (NavVec3, NavVec3),
+    up: NavVec3,
+) -> (NavVec3, NavVec3) {
+    let (a, b) = portal;
+    let dir = to - from;
+    let mid = (a + b) * 0.5;
+    if dir.cross(a - mid).dot(up) >= 0.0 {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Move portal ends lying on walls towards portal middle by given weight, so paths going
+/// through portal keep away from walls.
+#[cfg(feature = "std")]
+pub(crate) fn center_portal(
+    portal: (NavVec3, NavVec3),
+    walls: (bool, bool),
+    weight: Scalar,
+) -> (NavVec3, NavVec3) {
+    let (a, b) = portal;
+    let mid = (a + b) * 0.5;
+    let weight = weight.clamp(0.0, 1.0);
+    (
+        if walls.0 { a.lerp(mid, weight) } else { a },
+        if walls.1 { b.lerp(mid, weight) } else { b },
+    )
+}
+
+/// Simple stupid funnel algorithm over list of (left, right) portals.
+///
+/// First and last portals are expected to be degenerated (start, start) and (end, end) pairs.
+pub(crate) fn funnel(portals: &[(NavVec3, NavVec3)], up: NavVec3) -> Vec<NavVec3> {
+    funnel_corners(portals, up)
+        .into_iter()
+        .map(|(point, _)| point)
+        .collect()
+}
+
+/// Simple stupid funnel algorithm over list of (left, right) portals, telling for every path
+/// point index of portal that generated it and direction path turns to there. Start and end
+/// points have no portal.
+///
+/// First and last portals are expected to be degenerated (start, start) and (end, end) pairs.
+pub(crate) fn funnel_corners(
+    portals: &[(NavVec3, NavVec3)],
+    up: NavVec3,
+) -> Vec<(NavVec3, Option<(usize, NavPathTurn)>)> {
+    if portals.is_empty() {
+        return vec![];
+    }
+    let mut points = Vec::with_capacity(portals.len());
+    let mut apex = portals[0].0;
+    let mut left = portals[0].0;
+    let mut right = portals[0].1;
+    let mut left_index = 0;
+    let mut right_index = 0;
+    points.push((apex, None));
+    let mut i = 1;
+    while i < portals.len() {
+        let (l, r) = portals[i];
+        if area(apex, right, r, up) >= 0.0 {
+            if apex.same_as(right) || area(apex, left, r, up) < 0.0 {
+                right = r;
+                right_index = i;
+            } else {
+                apex = left;
+                let apex_index = left_index;
+                points.push((apex, Some((apex_index, NavPathTurn::Left))));
+                left = apex;
+                right = apex;
+                left_index = apex_index;
+                right_index = apex_index;
+                i = apex_index + 1;
+                continue;
+            }
+        }
+        if area(apex, left, l, up) <= 0.0 {
+            if apex.same_as(left) || area(apex, right, l, up) > 0.0 {
+                left = l;
+                left_index = i;
+            } else {
+                apex = right;
+                let apex_index = right_index;
+                points.push((apex, Some((apex_index, NavPathTurn::Right))));
+                left = apex;
+                right = apex;
+                left_index = apex_index;
+                right_index = apex_index;
+                i = apex_index + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    let end = portals[portals.len() - 1].0;
+    points.push((end, None));
+    points.dedup_by(|a, b| a.0.same_as(b.0));
+    points
+}
+
+/// Twice the signed area of triangle projected on plane described by `up` normal.
+/// Positive value tells that `c` lies on the left side of `a` -> `b` line.
+#[inline]
+fn area(a: NavVec3, b: NavVec3, c: NavVec3, up: NavVec3) -> Scalar {
+    let v = (b - a).cross(c - a).dot(up);
+    if v.abs() < ZERO_TRESHOLD {
+        0.0
+    } else {
+        v
+    }
+}
+
+/// Tells if segment `from` -> `to` crosses portal edge when both are projected on plane described
+/// by `up` normal.
+#[cfg(feature = "std")]
+pub(crate) fn segment_crosses_portal(
+    from: NavVec3,
+    to: NavVec3,
+    portal: (NavVec3, NavVec3),
+    up: NavVec3,
+) -> bool {
+    let (a, b) = portal;
+    let sa = area(from, to, a, up);
+    let sb = area(from, to, b, up);
+    let sf = area(a, b, from, up);
+    let st = area(a, b, to, up);
+    sa * sb <= 0.0 && sf * st <= 0.0
+}
@@ -0,0 +1,437 @@
+//! Geometry tests shared by agent radius handling and obstacle carving. Degenerate inputs
+//! (zero length segments, zero area triangles) are handled with `ZERO_TRESHOLD` tolerance.
+//!
+//! Orientation predicates (`orient3d`, `orient_on_plane`) and tests built on them are exact with
+//! `robust` feature (adaptive precision arithmetic), so near-degenerate triangles give consistent
+//! results no matter the order vertices are passed in.
+
+use crate::{NavVec3, Scalar, ZERO_TRESHOLD};
+
+/// Find closest points of two segments.
+///
+/// # Arguments
+/// * `a_from` - first segment start point.
+/// * `a_to` - first segment end point.
+/// * `b_from` - second segment start point.
+/// * `b_to` - second segment end point.
+///
+/// # Returns
+/// (point on first segment, point on second segment).
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let (a, b) = closest_points_on_segments(
+///     (0.0, 0.0, 0.0).into(),
+///     (2.0, 0.0, 0.0).into(),
+///     (1.0, -1.0, 1.0).into(),
+///     (1.0, 1.0, 1.0).into(),
+/// );
+/// assert!(a.same_as((1.0, 0.0, 0.0).into()));
+/// assert!(b.same_as((1.0, 0.0, 1.0).into()));
+/// ```
+pub fn closest_points_on_segments(
+    a_from: NavVec3,
+    a_to: NavVec3,
+    b_from: NavVec3,
+    b_to: NavVec3,
+) -> (NavVec3, NavVec3) {
+    let da = a_to - a_from;
+    let db = b_to - b_from;
+    let r = a_from - b_from;
+    let la = da.sqr_magnitude();
+    let lb = db.sqr_magnitude();
+    let f = db.dot(r);
+    let epsilon = ZERO_TRESHOLD * ZERO_TRESHOLD;
+    let (s, t) = if la <= epsilon && lb <= epsilon {
+        (0.0, 0.0)
+    } else if la <= epsilon {
+        (0.0, (f / lb).clamp(0.0, 1.0))
+    } else {
+        let c = da.dot(r);
+        if lb <= epsilon {
+            ((-c / la).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = da.dot(db);
+            let denom = la * lb - b * b;
+            // parallel segments get any pair of closest points, starting with first segment start.
+            let mut s = if denom > epsilon {
+                ((b * f - c * lb) / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let mut t = (b * s + f) / lb;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / la).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / la).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+    (a_from + da * s, b_from + db * t)
+}
+
+/// Orientation of point relative to plane going through triangle.
+///
+/// Sign of result is exact with `robust` feature, otherwise it is plain floating point
+/// determinant that might get wrong sign for nearly co-planar points.
+///
+/// # Arguments
+/// * `a` - first triangle vertice point.
+/// * `b` - second triangle vertice point.
+/// * `c` - third triangle vertice point.
+/// * `point` - query point.
+///
+/// # Returns
+/// Positive value if point lies above triangle plane (on side counter-clockwise triangle normal
+/// points to), negative if it lies below and zero if it lies on plane. Value equals six times
+/// signed volume of tetrahedron made of triangle and point.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let a = (0.0, 0.0, 0.0).into();
+/// let b = (1.0, 0.0, 0.0).into();
+/// let c = (0.0, 1.0, 0.0).into();
+/// assert!(orient3d(a, b, c, (0.2, 0.2, 1.0).into()) > 0.0);
+/// assert!(orient3d(a, b, c, (0.2, 0.2, -1.0).into()) < 0.0);
+/// assert_eq!(orient3d(a, b, c, (5.0, 3.0, 0.0).into()), 0.0);
+/// ```
+pub fn orient3d(a: NavVec3, b: NavVec3, c: NavVec3, point: NavVec3) -> Scalar {
+    #[cfg(feature = "robust")]
+    {
+        let coord = |v: NavVec3| robust::Coord3D {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        };
+        // `robust` treats points above counter-clockwise triangle as negative.
+        exact_scalar(-robust::orient3d(
+            coord(a),
+            coord(b),
+            coord(c),
+            coord(point),
+        ))
+    }
+    #[cfg(not(feature = "robust"))]
+    {
+        (b - a).cross(c - a).dot(point - a)
+    }
+}
+
+/// Orientation of triangle seen from direction opposite to normal (looking down at plane normal
+/// points out of), for points lying on the same plane, like polygon points.
+///
+/// With `robust` feature points are projected onto coordinate plane closest to perpendicular to
+/// normal and sign of result is exact, otherwise it is plain floating point determinant.
+///
+/// # Arguments
+/// * `a` - first triangle vertice point.
+/// * `b` - second triangle vertice point.
+/// * `c` - third triangle vertice point.
+/// * `normal` - plane normal.
+///
+/// # Returns
+/// Positive value if triangle winds counter-clockwise around normal, negative if clockwise and
+/// zero if its points are co-linear.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let up = (0.0, 0.0, 1.0).into();
+/// let a = (0.0, 0.0, 0.0).into();
+/// let b = (1.0, 0.0, 0.0).into();
+/// let c = (0.0, 1.0, 0.0).into();
+/// assert!(orient_on_plane(a, b, c, up) > 0.0);
+/// assert!(orient_on_plane(a, c, b, up) < 0.0);
+/// assert_eq!(orient_on_plane(a, b, (2.0, 0.0, 0.0).into(), up), 0.0);
+/// ```
+pub fn orient_on_plane(a: NavVec3, b: NavVec3, c: NavVec3, normal: NavVec3) -> Scalar {
+    #[cfg(feature = "robust")]
+    {
+        let n = normal.abs();
+        let (axis, sign) = if n.x >= n.y && n.x >= n.z {
+            (0, normal.x)
+        } else if n.y >= n.z {
+            (1, normal.y)
+        } else {
+            (2, normal.z)
+        };
+        // projection keeps right-handed coordinates when looking along dropped axis.
+        let coord = |v: NavVec3| match axis {
+            0 => robust::Coord { x: v.y, y: v.z },
+            1 => robust::Coord { x: v.z, y: v.x },
+            _ => robust::Coord { x: v.x, y: v.y },
+        };
+        let result = robust::orient2d(coord(a), coord(b), coord(c));
+        exact_scalar(if sign < 0.0 { -result } else { result })
+    }
+    #[cfg(not(feature = "robust"))]
+    {
+        (b - a).cross(c - a).dot(normal)
+    }
+}
+
+/// Tells if segment crosses triangle (touching its edges and vertices counts), using orientation
+/// predicates, so with `robust` feature result does not depend on order of triangle vertices or
+/// segment direction. Segments lying on triangle plane are not considered crossing.
+///
+/// # Arguments
+/// * `from` - segment start point.
+/// * `to` - segment end point.
+/// * `a` - first triangle vertice point.
+/// * `b` - second triangle vertice point.
+/// * `c` - third triangle vertice point.
+///
+/// # Returns
+/// `Some` with factor of segment length at which it crosses triangle or `None` if it does not.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let a = (0.0, 0.0, 0.0).into();
+/// let b = (1.0, 0.0, 0.0).into();
+/// let c = (0.0, 1.0, 0.0).into();
+/// let t = segment_crosses_triangle((0.25, 0.25, 1.0).into(), (0.25, 0.25, -3.0).into(), a, b, c)
+///     .unwrap();
+/// assert!((t - 0.25).abs() < 1.0e-6);
+/// assert!(
+///     segment_crosses_triangle((1.0, 1.0, 1.0).into(), (1.0, 1.0, -1.0).into(), a, b, c)
+///         .is_none()
+/// );
+/// ```
+pub fn segment_crosses_triangle(
+    from: NavVec3,
+    to: NavVec3,
+    a: NavVec3,
+    b: NavVec3,
+    c: NavVec3,
+) -> Option<Scalar> {
+    let sf = orient3d(a, b, c, from);
+    let st = orient3d(a, b, c, to);
+    if (sf > 0.0 && st > 0.0) || (sf < 0.0 && st < 0.0) || (sf == 0.0 && st == 0.0) {
+        return None;
+    }
+    let sab = orient3d(from, to, a, b);
+    let sbc = orient3d(from, to, b, c);
+    let sca = orient3d(from, to, c, a);
+    let positive = sab >= 0.0 && sbc >= 0.0 && sca >= 0.0;
+    let negative = sab <= 0.0 && sbc <= 0.0 && sca <= 0.0;
+    if positive || negative {
+        Some((sf / (sf - st)).clamp(0.0, 1.0))
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "robust")]
+#[allow(clippy::unnecessary_cast)]
+fn exact_scalar(value: f64) -> Scalar {
+    // keep sign of values too small to be represented by `Scalar`.
+    let result = value as Scalar;
+    if result == 0.0 && value != 0.0 {
+        Scalar::MIN_POSITIVE.copysign(value as Scalar)
+    } else {
+        result
+    }
+}
+
+/// Find point of triangle closest to given point.
+///
+/// # Arguments
+/// * `point` - query point.
+/// * `a` - first triangle vertice point.
+/// * `b` - second triangle vertice point.
+/// * `c` - third triangle vertice point.
+pub fn closest_point_on_triangle(point: NavVec3, a: NavVec3, b: NavVec3, c: NavVec3) -> NavVec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = point - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = point - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let sum = va + vb + vc;
+    if sum.abs() <= ZERO_TRESHOLD * ZERO_TRESHOLD {
+        // degenerate triangle: closest point lies on one of its edges.
+        return [(a, b), (b, c), (c, a)]
+            .iter()
+            .map(|(from, to)| point.closest_point_on_segment(*from, *to))
+            .min_by(|x, y| {
+                point
+                    .distance_squared(*x)
+                    .partial_cmp(&point.distance_squared(*y))
+                    .unwrap()
+            })
+            .unwrap();
+    }
+    a + ab * (vb / sum) + ac * (vc / sum)
+}
+
+/// Find closest points of segment and triangle.
+///
+/// # Arguments
+/// * `from` - segment start point.
+/// * `to` - segment end point.
+/// * `a` - first triangle vertice point.
+/// * `b` - second triangle vertice point.
+/// * `c` - third triangle vertice point.
+///
+/// # Returns
+/// (point on segment, point on triangle), both are the same if segment crosses triangle.
+pub fn closest_points_segment_triangle(
+    from: NavVec3,
+    to: NavVec3,
+    a: NavVec3,
+    b: NavVec3,
+    c: NavVec3,
+) -> (NavVec3, NavVec3) {
+    if let Some(t) = segment_crosses_triangle(from, to, a, b, c) {
+        let hit = NavVec3::unproject(from, to, t);
+        return (hit, hit);
+    }
+    let mut candidates = vec![
+        (from, closest_point_on_triangle(from, a, b, c)),
+        (to, closest_point_on_triangle(to, a, b, c)),
+    ];
+    for (p, q) in [(a, b), (b, c), (c, a)] {
+        candidates.push(closest_points_on_segments(from, to, p, q));
+    }
+    candidates
+        .into_iter()
+        .min_by(|x, y| {
+            x.0.distance_squared(x.1)
+                .partial_cmp(&y.0.distance_squared(y.1))
+                .unwrap()
+        })
+        .unwrap()
+}
+
+/// Tells if sphere overlaps triangle.
+///
+/// # Arguments
+/// * `center` - sphere center.
+/// * `radius` - sphere radius.
+/// * `a` - first triangle vertice point.
+/// * `b` - second triangle vertice point.
+/// * `c` - third triangle vertice point.
+pub fn sphere_intersects_triangle(
+    center: NavVec3,
+    radius: Scalar,
+    a: NavVec3,
+    b: NavVec3,
+    c: NavVec3,
+) -> bool {
+    let radius = radius.max(0.0);
+    center.distance_squared(closest_point_on_triangle(center, a, b, c))
+        <= radius * radius + ZERO_TRESHOLD
+}
+
+/// Tells if capsule (for example agent body) overlaps triangle.
+///
+/// # Arguments
+/// * `from` - capsule segment start point.
+/// * `to` - capsule segment end point.
+/// * `radius` - capsule radius.
+/// * `a` - first triangle vertice point.
+/// * `b` - second triangle vertice point.
+/// * `c` - third triangle vertice point.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let a = (0.0, 0.0, 0.0).into();
+/// let b = (2.0, 0.0, 0.0).into();
+/// let c = (0.0, 2.0, 0.0).into();
+/// let from = (0.5, 0.5, 0.2).into();
+/// let to = (0.5, 0.5, 2.0).into();
+/// assert!(capsule_intersects_triangle(from, to, 0.3, a, b, c));
+/// assert!(!capsule_intersects_triangle(from, to, 0.1, a, b, c));
+/// ```
+pub fn capsule_intersects_triangle(
+    from: NavVec3,
+    to: NavVec3,
+    radius: Scalar,
+    a: NavVec3,
+    b: NavVec3,
+    c: NavVec3,
+) -> bool {
+    let radius = radius.max(0.0);
+    let (p, q) = closest_points_segment_triangle(from, to, a, b, c);
+    p.distance_squared(q) <= radius * radius + ZERO_TRESHOLD
+}
+
+/// Keeps part of polygon that lies behind the plane (opposite to its normal).
+pub(crate) fn clip_polygon(
+    polygon: Vec<NavVec3>,
+    origin: NavVec3,
+    normal: NavVec3,
+) -> Vec<NavVec3> {
+    if polygon.is_empty() {
+        return polygon;
+    }
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let current = polygon[i];
+        let next = polygon[(i + 1) % polygon.len()];
+        let dc = current.distance_to_plane(origin, normal);
+        let dn = next.distance_to_plane(origin, normal);
+        if dc <= ZERO_TRESHOLD {
+            result.push(current);
+        }
+        if (dc < -ZERO_TRESHOLD && dn > ZERO_TRESHOLD)
+            || (dc > ZERO_TRESHOLD && dn < -ZERO_TRESHOLD)
+        {
+            result.push(NavVec3::unproject(current, next, dc / (dc - dn)));
+        }
+    }
+    result
+}
+
+pub(crate) fn polygon_area(polygon: &[NavVec3]) -> Scalar {
+    if polygon.len() < 3 {
+        return 0.0;
+    }
+    let first = polygon[0];
+    polygon
+        .windows(2)
+        .skip(1)
+        .fold(NavVec3::default(), |a, pair| {
+            a + (pair[0] - first).cross(pair[1] - first)
+        })
+        .magnitude()
+        * 0.5
+}
@@ -0,0 +1,187 @@
+//! Export of nav mesh into flat arrays that can be uploaded to GPU storage buffers as they are
+//! (for example WGSL `array<f32>` and `array<u32>`), without reflection over internal structs.
+
+use crate::{nav_open_node::OpenNode, NavMesh, SCALAR_MAX};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BinaryHeap;
+
+/// Value of indices that do not point to any triangle.
+pub const NAV_GPU_NONE: u32 = u32::MAX;
+
+/// Nav mesh flattened into tightly packed arrays.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavGpuBuffers {
+    /// Vertices positions as `x, y, z` triplets.
+    pub positions: Vec<f32>,
+    /// Triangles vertices indices as `first, second, third` triplets.
+    pub indices: Vec<u32>,
+    /// Neighbor triangle index across triangle edges (first-second, second-third, third-first)
+    /// as triplets, `NAV_GPU_NONE` for edges without neighbor.
+    pub adjacency: Vec<u32>,
+    /// Triangles areas packed as `area_type | flags << 8 | blocked << 24`.
+    pub areas: Vec<u32>,
+    /// Triangles areas traverse cost factors.
+    pub costs: Vec<f32>,
+}
+
+impl NavGpuBuffers {
+    /// Number of triangles stored in buffers.
+    #[inline]
+    pub fn triangles_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+/// Flow field towards target triangle, one entry per nav mesh triangle.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavGpuFlowField {
+    /// Target triangle index.
+    pub target: u32,
+    /// Path cost from triangle to target, `f32::MAX` for triangles that cannot reach it.
+    pub costs: Vec<f32>,
+    /// Next triangle index on the way to target, `NAV_GPU_NONE` for target and triangles that
+    /// cannot reach it.
+    pub next: Vec<u32>,
+}
+
+impl NavMesh {
+    /// Flatten nav mesh into GPU friendly buffers.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let buffers = NavMesh::new(vertices, triangles).unwrap().to_gpu_buffers();
+    /// assert_eq!(buffers.positions.len(), 12);
+    /// assert_eq!(buffers.indices, vec![0, 1, 2, 2, 3, 0]);
+    /// assert_eq!(buffers.adjacency, vec![NAV_GPU_NONE, NAV_GPU_NONE, 1, NAV_GPU_NONE, NAV_GPU_NONE, 0]);
+    /// ```
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_gpu_buffers(&self) -> NavGpuBuffers {
+        let positions = self
+            .vertices()
+            .iter()
+            .flat_map(|v| [v.x as f32, v.y as f32, v.z as f32])
+            .collect();
+        let indices = self
+            .triangles()
+            .iter()
+            .flat_map(|t| [t.first, t.second, t.third])
+            .collect();
+        let mut adjacency = vec![NAV_GPU_NONE; self.triangles().len() * 3];
+        for (index, t) in self.triangles().iter().enumerate() {
+            let edges = [(t.first, t.second), (t.second, t.third), (t.third, t.first)];
+            for neighbor in self.triangle_neighbors(index) {
+                let n = self.triangles()[neighbor];
+                let has = |v| n.first == v || n.second == v || n.third == v;
+                if let Some(edge) = edges.iter().position(|(a, b)| has(*a) && has(*b)) {
+                    adjacency[index * 3 + edge] = neighbor as u32;
+                }
+            }
+        }
+        let areas = self
+            .areas()
+            .iter()
+            .enumerate()
+            .map(|(index, area)| {
+                area.area_type as u32
+                    | (area.flags as u32) << 8
+                    | (self.is_triangle_blocked(index) as u32) << 24
+            })
+            .collect();
+        let costs = self.areas().iter().map(|area| area.cost as f32).collect();
+        NavGpuBuffers {
+            positions,
+            indices,
+            adjacency,
+            areas,
+            costs,
+        }
+    }
+
+    /// Compute flow field towards target triangle, so many agents going to the same place can
+    /// follow it on GPU instead of finding paths one by one. Blocked triangles do not reach
+    /// target.
+    ///
+    /// # Arguments
+    /// * `target` - target triangle index.
+    ///
+    /// # Returns
+    /// `Some` with flow field or `None` if target triangle does not exist or is blocked.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let field = mesh.to_gpu_flow_field(2).unwrap();
+    /// assert_eq!(field.next, vec![3, 0, NAV_GPU_NONE, 2]);
+    /// assert_eq!(field.costs[2], 0.0);
+    /// ```
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_gpu_flow_field(&self, target: usize) -> Option<NavGpuFlowField> {
+        if target >= self.triangles().len() || self.is_triangle_blocked(target) {
+            return None;
+        }
+        let mut costs = vec![SCALAR_MAX; self.triangles().len()];
+        let mut next = vec![NAV_GPU_NONE; self.triangles().len()];
+        let mut open = BinaryHeap::new();
+        costs[target] = 0.0;
+        open.push(OpenNode(0.0, target));
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if cost > costs[current] {
+                continue;
+            }
+            for neighbor in self.triangle_neighbors(current) {
+                if let Some(weight) = self.connection_cost(neighbor, current) {
+                    let neighbor_cost = cost + weight;
+                    if neighbor_cost < costs[neighbor] {
+                        costs[neighbor] = neighbor_cost;
+                        next[neighbor] = current as u32;
+                        open.push(OpenNode(neighbor_cost, neighbor));
+                    }
+                }
+            }
+        }
+        Some(NavGpuFlowField {
+            target: target as u32,
+            costs: costs
+                .into_iter()
+                .map(|cost| {
+                    if cost == SCALAR_MAX {
+                        f32::MAX
+                    } else {
+                        cost as f32
+                    }
+                })
+                .collect(),
+            next,
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{nav_open_node::OpenNode, Scalar, SCALAR_MAX};
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+
+/// A* search over graph of nodes identified by indices, which keeps its state between searches
+/// so repeated ones do not allocate it every time.
+///
+/// Search knows nothing about graph storage (neighbors, moves costs and heuristic are provided
+/// by caller), so it is available without `std` too. Nav mesh triangles path queries run on it.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// // line of four nodes connected with unit cost moves.
+/// let neighbors = |node: usize| [node.wrapping_sub(1), node + 1].into_iter().filter(|n| *n < 4);
+/// let cost = |_, _| Some(1.0);
+/// let mut search = NavGraphSearch::default();
+/// let (path, total) = search
+///     .find_path(4, 0, 3, neighbors, cost, |node| (3 - node) as Scalar)
+///     .unwrap();
+/// assert_eq!(path, vec![0, 1, 2, 3]);
+/// assert_eq!(total, 3.0);
+/// // node 2 can not be entered.
+/// let cost = |_, to| if to == 2 { None } else { Some(1.0) };
+/// assert!(search.find_path(4, 0, 3, neighbors, cost, |_| 0.0).is_none());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct NavGraphSearch {
+    open: BinaryHeap<OpenNode<usize>>,
+    // [cost] per node, reset after every search for nodes listed in `visited`.
+    costs: Vec<Scalar>,
+    parents: Vec<usize>,
+    visited: Vec<usize>,
+    // statistics of last search.
+    pub(crate) expansions: usize,
+    pub(crate) edges_visited: usize,
+    pub(crate) open_peak: usize,
+}
+
+impl NavGraphSearch {
+    /// Find cheapest path between two graph nodes.
+    ///
+    /// # Arguments
+    /// * `count` - number of graph nodes, indexed from 0.
+    /// * `from` - start node index.
+    /// * `to` - goal node index.
+    /// * `neighbors` - closure that gets node index and returns indices of its neighbors.
+    /// * `cost` - closure that gets move start and end node index and returns `Some` with move
+    ///   cost or `None` if move is not allowed.
+    /// * `heuristic` - closure that gets node index and returns estimated cost of path to goal,
+    ///   which must never be bigger than the real one (zero turns search into Dijkstra one).
+    ///
+    /// # Returns
+    /// `Some` with path nodes and path cost if found or `None` otherwise.
+    pub fn find_path<N, I, C, H>(
+        &mut self,
+        count: usize,
+        from: usize,
+        to: usize,
+        mut neighbors: N,
+        mut cost: C,
+        mut heuristic: H,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        N: FnMut(usize) -> I,
+        I: IntoIterator<Item = usize>,
+        C: FnMut(usize, usize) -> Option<Scalar>,
+        H: FnMut(usize) -> Scalar,
+    {
+        self.expansions = 0;
+        self.edges_visited = 0;
+        self.open_peak = 0;
+        if from >= count || to >= count {
+            return None;
+        }
+        if self.costs.len() < count {
+            self.costs.resize(count, SCALAR_MAX);
+            self.parents.resize(count, usize::MAX);
+        }
+        let result = self.search(from, to, &mut neighbors, &mut cost, &mut heuristic);
+        for index in self.visited.drain(..) {
+            self.costs[index] = SCALAR_MAX;
+            self.parents[index] = usize::MAX;
+        }
+        self.open.clear();
+        result
+    }
+
+    fn search<N, I, C, H>(
+        &mut self,
+        from: usize,
+        to: usize,
+        neighbors: &mut N,
+        cost: &mut C,
+        heuristic: &mut H,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        N: FnMut(usize) -> I,
+        I: IntoIterator<Item = usize>,
+        C: FnMut(usize, usize) -> Option<Scalar>,
+        H: FnMut(usize) -> Scalar,
+    {
+        self.costs[from] = 0.0;
+        self.visited.push(from);
+        self.open.push(OpenNode(heuristic(from), from));
+        self.open_peak = 1;
+        while let Some(OpenNode(score, current)) = self.open.pop() {
+            let current_cost = self.costs[current];
+            // skip entries left behind by cheaper ones.
+            if score > current_cost + heuristic(current) {
+                continue;
+            }
+            self.expansions += 1;
+            if current == to {
+                let mut path = vec![current];
+                let mut node = current;
+                while self.parents[node] != usize::MAX {
+                    node = self.parents[node];
+                    path.push(node);
+                }
+                path.reverse();
+                return Some((path, current_cost));
+            }
+            for next in neighbors(current) {
+                self.edges_visited += 1;
+                if let Some(weight) = cost(current, next) {
+                    let next_cost = current_cost + weight;
+                    if next_cost < self.costs[next] {
+                        if self.costs[next] == SCALAR_MAX {
+                            self.visited.push(next);
+                        }
+                        self.costs[next] = next_cost;
+                        self.parents[next] = current;
+                        self.open.push(OpenNode(next_cost + heuristic(next), next));
+                        self.open_peak = self.open_peak.max(self.open.len());
+                    }
+                }
+            }
+        }
+        None
+    }
+}
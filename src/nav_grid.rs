@@ -1,16 +1,15 @@
-use crate::{Error, NavResult, Scalar};
+use crate::{nav_open_node::OpenNode, Error, NavResult, Scalar, SCALAR_MAX};
 use petgraph::{
     algo::{astar, tarjan_scc},
     graph::NodeIndex,
     visit::EdgeRef,
     Directed, Graph, Undirected,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-#[cfg(not(feature = "scalar64"))]
-use std::f32::MAX as SCALAR_MAX;
-#[cfg(feature = "scalar64")]
-use std::f64::MAX as SCALAR_MAX;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use typid::ID;
 
 #[cfg(feature = "parallel")]
@@ -26,29 +25,164 @@ macro_rules! iter {
     };
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavGridConnection {
     pub from: (usize, usize),
     pub to: (usize, usize),
 }
 
+/// Rectangle of grid cells spanned between two corner cells (both inclusive).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavGridRect {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+}
+
+impl NavGridRect {
+    #[inline]
+    pub fn new(from: (usize, usize), to: (usize, usize)) -> Self {
+        Self { from, to }
+    }
+}
+
+/// Path finding algorithm used by nav grid.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavGridQuery {
+    /// Jump point search when grid supports it, A* otherwise.
+    #[default]
+    Auto,
+    /// A* over graph of cells.
+    AStar,
+    /// Jump point search, skipping over straight runs of open cells instead of expanding every
+    /// one of them. Requires grid with uniform costs and either orthogonal or diagonal moves that
+    /// never cut corners, otherwise A* is used.
+    JumpPointSearch,
+}
+
 /// Nav grid identifier.
 pub type NavGridID = ID<NavGrid>;
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// Rule of diagonal moves passing by corners of blocked cells.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavGridCornerCutting {
+    /// Diagonal move is allowed whenever target cell is walkable.
+    Allow,
+    /// Diagonal move requires at least one of two orthogonal cells on the way to be walkable, so
+    /// agents cannot squeeze between diagonally touching walls.
+    IfOneFree,
+    /// Diagonal move requires both orthogonal cells on the way to be walkable.
+    #[default]
+    Forbid,
+}
+
+/// Set of moves allowed between nav grid cells.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavGridNeighborhood {
+    /// Moves along columns and rows.
+    #[default]
+    Orthogonal,
+    /// Moves along columns, rows and diagonals (with longer distance).
+    Diagonal(NavGridCornerCutting),
+    /// Hexagonal grid with odd rows shifted by half of cell ("odd-r" layout).
+    Hex,
+    /// List of (column, row) offsets of cells reachable from any cell, e.g. knight moves.
+    Custom(Vec<(isize, isize)>),
+}
+
+impl NavGridNeighborhood {
+    /// List of (column, row) offsets of cells reachable from cell in given row.
+    // `usize::is_multiple_of` needs Rust 1.87, modulo keeps older toolchains supported.
+    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+    pub fn offsets(&self, row: usize) -> Vec<(isize, isize)> {
+        match self {
+            Self::Orthogonal => vec![(1, 0), (-1, 0), (0, 1), (0, -1)],
+            Self::Diagonal(_) => vec![
+                (1, 0),
+                (-1, 0),
+                (0, 1),
+                (0, -1),
+                (1, 1),
+                (-1, 1),
+                (1, -1),
+                (-1, -1),
+            ],
+            Self::Hex => {
+                if row % 2 == 0 {
+                    vec![(1, 0), (-1, 0), (0, 1), (-1, 1), (0, -1), (-1, -1)]
+                } else {
+                    vec![(1, 0), (-1, 0), (1, 1), (0, 1), (1, -1), (0, -1)]
+                }
+            }
+            Self::Custom(offsets) => offsets.clone(),
+        }
+    }
+
+    /// Distance of move by given offset.
+    pub fn distance(&self, offset: (isize, isize)) -> Scalar {
+        match self {
+            Self::Hex => 1.0,
+            _ => ((offset.0 * offset.0 + offset.1 * offset.1) as Scalar).sqrt(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavGrid {
     id: NavGridID,
     cols: usize,
     rows: usize,
     cells: Vec<bool>,
     costs: Vec<Scalar>,
-    graph: Graph<(), (), Directed>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    neighborhood: Option<NavGridNeighborhood>,
+    // edges weights are moves distances.
+    graph: Graph<(), Scalar, Directed>,
     nodes: Vec<Option<NodeIndex>>,
     nodes_map: HashMap<NodeIndex, usize>,
 }
 
 impl NavGrid {
     pub fn new(cols: usize, rows: usize, cells: Vec<bool>) -> NavResult<Self> {
+        Self::with_neighborhood(cols, rows, cells, NavGridNeighborhood::Orthogonal)
+    }
+
+    /// Create nav grid with walkable cells connected according to set of allowed moves.
+    ///
+    /// # Arguments
+    /// * `cols` - number of columns.
+    /// * `rows` - number of rows.
+    /// * `cells` - list of cells (row by row), true for walkable ones.
+    /// * `neighborhood` - set of allowed moves.
+    ///
+    /// # Returns
+    /// `Ok` with nav grid or `Err` if input data is invalid.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let grid = NavGrid::with_neighborhood(
+    ///     3,
+    ///     3,
+    ///     vec![true, true, true, false, true, false, true, true, true],
+    ///     NavGridNeighborhood::Diagonal(NavGridCornerCutting::IfOneFree),
+    /// )
+    /// .unwrap();
+    /// let path = grid.find_path((0, 0), (0, 2)).unwrap();
+    /// assert_eq!(path, vec![(0, 0), (1, 1), (0, 2)]);
+    /// ```
+    pub fn with_neighborhood(
+        cols: usize,
+        rows: usize,
+        cells: Vec<bool>,
+        neighborhood: NavGridNeighborhood,
+    ) -> NavResult<Self> {
         if cols == 0 || rows == 0 {
             return Err(Error::EmptyCells(cols, rows));
         }
@@ -60,9 +194,16 @@ impl NavGrid {
             ));
         }
         let costs = vec![1.0; cells.len()];
-        let mut graph = Graph::<(), (), Directed>::with_capacity(
+        let walkable = |col: isize, row: isize| {
+            col >= 0
+                && row >= 0
+                && (col as usize) < cols
+                && (row as usize) < rows
+                && cells[row as usize * cols + col as usize]
+        };
+        let mut graph = Graph::<(), Scalar, Directed>::with_capacity(
             cells.len(),
-            (cols - 1) * rows + (rows - 1) * cols,
+            cells.len() * neighborhood.offsets(0).len(),
         );
         let nodes = (0..cells.len())
             .zip(cells.iter())
@@ -74,25 +215,36 @@ impl NavGrid {
                 }
             })
             .collect::<Vec<_>>();
-        for ca in 0..(cols - 1) {
-            for r in 0..rows {
-                let cb = ca + 1;
-                let ia = r * cols + ca;
-                let ib = r * cols + cb;
-                if let (Some(na), Some(nb)) = (nodes[ia], nodes[ib]) {
-                    graph.add_edge(na, nb, ());
-                    graph.add_edge(nb, na, ());
-                }
-            }
-        }
-        for c in 0..cols {
-            for ra in 0..(rows - 1) {
-                let rb = ra + 1;
-                let ia = ra * cols + c;
-                let ib = rb * cols + c;
-                if let (Some(na), Some(nb)) = (nodes[ia], nodes[ib]) {
-                    graph.add_edge(na, nb, ());
-                    graph.add_edge(nb, na, ());
+        for row in 0..rows {
+            let offsets = neighborhood.offsets(row);
+            for col in 0..cols {
+                let na = match nodes[row * cols + col] {
+                    Some(node) => node,
+                    None => continue,
+                };
+                for offset in &offsets {
+                    let c = col as isize + offset.0;
+                    let r = row as isize + offset.1;
+                    if (c, r) == (col as isize, row as isize) || !walkable(c, r) {
+                        continue;
+                    }
+                    if let NavGridNeighborhood::Diagonal(cutting) = neighborhood {
+                        if offset.0 != 0 && offset.1 != 0 {
+                            let a = walkable(c, row as isize);
+                            let b = walkable(col as isize, r);
+                            let allowed = match cutting {
+                                NavGridCornerCutting::Allow => true,
+                                NavGridCornerCutting::IfOneFree => a || b,
+                                NavGridCornerCutting::Forbid => a && b,
+                            };
+                            if !allowed {
+                                continue;
+                            }
+                        }
+                    }
+                    if let Some(nb) = nodes[r as usize * cols + c as usize] {
+                        graph.add_edge(na, nb, neighborhood.distance(*offset));
+                    }
                 }
             }
         }
@@ -106,6 +258,7 @@ impl NavGrid {
             rows,
             cells,
             costs,
+            neighborhood: Some(neighborhood),
             graph,
             nodes,
             nodes_map,
@@ -140,8 +293,7 @@ impl NavGrid {
             }
         }
         let costs = vec![1.0; count];
-        let mut graph =
-            Graph::<(), (), Directed>::with_capacity(count, (cols - 1) * rows + (rows - 1) * cols);
+        let mut graph = Graph::<(), Scalar, Directed>::with_capacity(count, connections.len());
         let nodes = (0..count)
             .map(|index| {
                 let coord = (index % cols, index / cols);
@@ -156,7 +308,7 @@ impl NavGrid {
             let ia = connection.from.1 * cols + connection.from.0;
             let ib = connection.to.1 * cols + connection.to.0;
             if let (Some(na), Some(nb)) = (nodes[ia], nodes[ib]) {
-                graph.add_edge(na, nb, ());
+                graph.add_edge(na, nb, 1.0);
             }
         }
         let nodes_map = iter!(nodes)
@@ -169,6 +321,7 @@ impl NavGrid {
             rows,
             cells: nodes.iter().map(Option::is_some).collect(),
             costs,
+            neighborhood: None,
             graph,
             nodes,
             nodes_map,
@@ -180,6 +333,12 @@ impl NavGrid {
         self.id
     }
 
+    /// Set of moves grid was created with or `None` if it was created from connections.
+    #[inline]
+    pub fn neighborhood(&self) -> Option<&NavGridNeighborhood> {
+        self.neighborhood.as_ref()
+    }
+
     #[inline]
     pub fn cells(&self) -> &[bool] {
         &self.cells
@@ -190,6 +349,11 @@ impl NavGrid {
         &self.costs
     }
 
+    #[inline]
+    pub fn cell_cost(&self, col: usize, row: usize) -> Option<Scalar> {
+        self.costs.get(self.index(col, row)?).copied()
+    }
+
     #[inline]
     pub fn set_cell_cost(&mut self, col: usize, row: usize, cost: Scalar) -> Option<Scalar> {
         let index = self.index(col, row)?;
@@ -199,6 +363,41 @@ impl NavGrid {
         Some(old)
     }
 
+    /// Set traversal cost of all cells in rectangle, so terrain like roads (cheap) or swamps
+    /// (expensive) can be painted over grid.
+    ///
+    /// # Arguments
+    /// * `rect` - cells rectangle, clipped to grid size.
+    /// * `cost` - new cost of cells.
+    ///
+    /// # Returns
+    /// Number of painted cells.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mut grid = NavGrid::new(3, 3, vec![true; 9]).unwrap();
+    /// assert_eq!(grid.paint_cost(NavGridRect::new((1, 0), (1, 1)), 10.0), 2);
+    /// let path = grid.find_path((0, 0), (2, 0)).unwrap();
+    /// assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0)]);
+    /// ```
+    pub fn paint_cost(&mut self, rect: NavGridRect, cost: Scalar) -> usize {
+        let cost = cost.max(0.0);
+        let min_col = rect.from.0.min(rect.to.0);
+        let max_col = rect.from.0.max(rect.to.0).min(self.cols - 1);
+        let min_row = rect.from.1.min(rect.to.1);
+        let max_row = rect.from.1.max(rect.to.1).min(self.rows - 1);
+        let mut count = 0;
+        for row in min_row..=max_row {
+            for col in min_col..=max_col {
+                self.costs[row * self.cols + col] = cost;
+                count += 1;
+            }
+        }
+        count
+    }
+
     pub fn neighbors(
         &self,
         col: usize,
@@ -213,12 +412,71 @@ impl NavGrid {
         }))
     }
 
+    /// Tells if all cells have the same cost.
+    pub fn has_uniform_costs(&self) -> bool {
+        self.costs.windows(2).all(|pair| pair[0] == pair[1])
+    }
+
+    /// Tells if jump point search can be used to find paths on this grid.
+    pub fn supports_jump_point_search(&self) -> bool {
+        matches!(
+            self.neighborhood,
+            Some(NavGridNeighborhood::Orthogonal)
+                | Some(NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid))
+        ) && self.has_uniform_costs()
+    }
+
+    /// Find cheapest path between cells, where moving between neighbor cells costs product of
+    /// their costs and move distance.
     pub fn find_path(
         &self,
         from: (usize, usize),
         to: (usize, usize),
     ) -> Option<Vec<(usize, usize)>> {
-        self.find_path_custom(from, to, |_, _| true)
+        self.find_path_with_query(from, to, NavGridQuery::Auto)
+    }
+
+    /// Find cheapest path between cells using given algorithm.
+    ///
+    /// # Arguments
+    /// * `from` - start cell.
+    /// * `to` - end cell.
+    /// * `query` - path finding algorithm.
+    ///
+    /// # Returns
+    /// `Some` with list of visited neighbor cells or `None` if there is no path.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let grid = NavGrid::with_neighborhood(
+    ///     4,
+    ///     3,
+    ///     vec![true; 12],
+    ///     NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid),
+    /// )
+    /// .unwrap();
+    /// assert!(grid.supports_jump_point_search());
+    /// let path = grid
+    ///     .find_path_with_query((0, 0), (3, 0), NavGridQuery::JumpPointSearch)
+    ///     .unwrap();
+    /// assert_eq!(path, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+    /// ```
+    pub fn find_path_with_query(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        query: NavGridQuery,
+    ) -> Option<Vec<(usize, usize)>> {
+        match query {
+            NavGridQuery::Auto | NavGridQuery::JumpPointSearch
+                if self.supports_jump_point_search() =>
+            {
+                self.find_path_jump_points(from, to)
+            }
+            _ => self.find_path_custom(from, to, |_, _| true),
+        }
     }
 
     // filter params: first col-row, second col-row.
@@ -245,7 +503,7 @@ impl NavGrid {
                 if filter(self.coord(a).unwrap(), self.coord(b).unwrap()) {
                     let a = self.costs[a];
                     let b = self.costs[b];
-                    a * b
+                    a * b * *e.weight()
                 } else {
                     SCALAR_MAX
                 }
@@ -261,6 +519,213 @@ impl NavGrid {
         )
     }
 
+    fn find_path_jump_points(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        let start = self.index(from.0, from.1)?;
+        let end = self.index(to.0, to.1)?;
+        if !self.cells[start] || !self.cells[end] {
+            return None;
+        }
+        let diagonal = matches!(self.neighborhood, Some(NavGridNeighborhood::Diagonal(_)));
+        let cost = self.costs[0] * self.costs[0];
+        let target = (to.0 as isize, to.1 as isize);
+        let distance = |a: (isize, isize), b: (isize, isize)| {
+            let dx = (a.0 - b.0).abs() as Scalar;
+            let dy = (a.1 - b.1).abs() as Scalar;
+            if diagonal {
+                dx.max(dy) + (std::f64::consts::SQRT_2 as Scalar - 1.0) * dx.min(dy)
+            } else {
+                dx + dy
+            }
+        };
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut parents = HashMap::<usize, usize>::new();
+        let mut closed = HashSet::new();
+        open.push(OpenNode(0.0, start));
+        costs.insert(start, 0.0);
+        while let Some(OpenNode(_, current)) = open.pop() {
+            if current == end {
+                let mut points = vec![current];
+                let mut node = current;
+                while let Some(parent) = parents.get(&node) {
+                    points.push(*parent);
+                    node = *parent;
+                }
+                points.reverse();
+                let mut result = vec![from];
+                for pair in points.windows(2) {
+                    let (mut col, mut row) = self.coord(pair[0])?;
+                    let (end_col, end_row) = self.coord(pair[1])?;
+                    while (col, row) != (end_col, end_row) {
+                        col = (col as isize + (end_col as isize - col as isize).signum()) as usize;
+                        row = (row as isize + (end_row as isize - row as isize).signum()) as usize;
+                        result.push((col, row));
+                    }
+                }
+                return Some(result);
+            }
+            if !closed.insert(current) {
+                continue;
+            }
+            let (col, row) = self.coord(current)?;
+            let position = (col as isize, row as isize);
+            let parent = parents.get(&current).and_then(|parent| self.coord(*parent));
+            let current_cost = costs[&current];
+            for (next_col, next_row) in self.pruned_neighbors(position, parent, diagonal) {
+                let jump = self.jump(
+                    next_col,
+                    next_row,
+                    next_col - position.0,
+                    next_row - position.1,
+                    target,
+                    diagonal,
+                );
+                if let Some(jump) = jump {
+                    let index = jump.1 as usize * self.cols + jump.0 as usize;
+                    if closed.contains(&index) {
+                        continue;
+                    }
+                    let next_cost = current_cost + distance(position, jump) * cost;
+                    if next_cost < costs.get(&index).copied().unwrap_or(Scalar::INFINITY) {
+                        costs.insert(index, next_cost);
+                        parents.insert(index, current);
+                        open.push(OpenNode(next_cost + distance(jump, target) * cost, index));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn is_walkable(&self, col: isize, row: isize) -> bool {
+        col >= 0
+            && row >= 0
+            && (col as usize) < self.cols
+            && (row as usize) < self.rows
+            && self.cells[row as usize * self.cols + col as usize]
+    }
+
+    /// Neighbors of jump point search node that cannot be reached by better path omitting it.
+    fn pruned_neighbors(
+        &self,
+        (col, row): (isize, isize),
+        parent: Option<(usize, usize)>,
+        diagonal: bool,
+    ) -> Vec<(isize, isize)> {
+        let (dx, dy) = match parent {
+            Some((parent_col, parent_row)) => (
+                (col - parent_col as isize).signum(),
+                (row - parent_row as isize).signum(),
+            ),
+            None => {
+                return self
+                    .neighbors(col as usize, row as usize)
+                    .map(|neighbors| {
+                        neighbors
+                            .map(|(col, row)| (col as isize, row as isize))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+            }
+        };
+        let walk = |c: isize, r: isize| self.is_walkable(c, r);
+        let mut result = Vec::with_capacity(5);
+        if dx != 0 && dy != 0 {
+            let vertical = walk(col, row + dy);
+            let horizontal = walk(col + dx, row);
+            if vertical {
+                result.push((col, row + dy));
+            }
+            if horizontal {
+                result.push((col + dx, row));
+            }
+            if vertical && horizontal && walk(col + dx, row + dy) {
+                result.push((col + dx, row + dy));
+            }
+        } else {
+            // side axis perpendicular to move direction.
+            let (sx, sy) = (dy.abs(), dx.abs());
+            let next = walk(col + dx, row + dy);
+            let side_a = walk(col + sx, row + sy);
+            let side_b = walk(col - sx, row - sy);
+            if next {
+                result.push((col + dx, row + dy));
+                if diagonal {
+                    if side_a && walk(col + dx + sx, row + dy + sy) {
+                        result.push((col + dx + sx, row + dy + sy));
+                    }
+                    if side_b && walk(col + dx - sx, row + dy - sy) {
+                        result.push((col + dx - sx, row + dy - sy));
+                    }
+                }
+            }
+            if side_a {
+                result.push((col + sx, row + sy));
+            }
+            if side_b {
+                result.push((col - sx, row - sy));
+            }
+        }
+        result
+    }
+
+    /// Move from cell in direction until reaching jump point (target, cell with forced neighbors
+    /// or cell from which jump point can be reached by straight move).
+    fn jump(
+        &self,
+        mut col: isize,
+        mut row: isize,
+        dx: isize,
+        dy: isize,
+        target: (isize, isize),
+        diagonal: bool,
+    ) -> Option<(isize, isize)> {
+        let walk = |c: isize, r: isize| self.is_walkable(c, r);
+        loop {
+            if !walk(col, row) {
+                return None;
+            }
+            if (col, row) == target {
+                return Some((col, row));
+            }
+            if dx != 0 && dy != 0 {
+                if self.jump(col + dx, row, dx, 0, target, diagonal).is_some()
+                    || self.jump(col, row + dy, 0, dy, target, diagonal).is_some()
+                {
+                    return Some((col, row));
+                }
+                if !walk(col + dx, row) || !walk(col, row + dy) {
+                    return None;
+                }
+            } else if dx != 0 {
+                if (walk(col, row - 1) && !walk(col - dx, row - 1))
+                    || (walk(col, row + 1) && !walk(col - dx, row + 1))
+                {
+                    return Some((col, row));
+                }
+            } else {
+                if (walk(col - 1, row) && !walk(col - 1, row - dy))
+                    || (walk(col + 1, row) && !walk(col + 1, row - dy))
+                {
+                    return Some((col, row));
+                }
+                // without diagonal moves turns are found by horizontal jumps.
+                if !diagonal
+                    && (self.jump(col + 1, row, 1, 0, target, diagonal).is_some()
+                        || self.jump(col - 1, row, -1, 0, target, diagonal).is_some())
+                {
+                    return Some((col, row));
+                }
+            }
+            col += dx;
+            row += dy;
+        }
+    }
+
     pub fn find_islands(&self) -> Vec<Vec<(usize, usize)>> {
         tarjan_scc(&self.graph)
             .into_iter()
@@ -292,7 +757,8 @@ impl NavGrid {
     }
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavFreeGridConnection {
     pub from: (isize, isize),
     pub to: (isize, isize),
@@ -301,7 +767,8 @@ pub struct NavFreeGridConnection {
 /// Nav free grid identifier.
 pub type NavFreeGridID = ID<NavFreeGrid>;
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavFreeGrid {
     id: NavFreeGridID,
     cells: Vec<(isize, isize)>,
@@ -0,0 +1,199 @@
+use crate::{nav_open_node::OpenNode, NavGrid, NavGridNeighborhood, Scalar, SCALAR_MAX};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+impl NavGrid {
+    /// Tells if straight line between centers of two cells goes only through walkable cells of
+    /// the same cost. Line going exactly through cells corner requires both cells by its side to
+    /// be walkable.
+    ///
+    /// # Arguments
+    /// * `from` - start cell.
+    /// * `to` - end cell.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let grid = NavGrid::new(
+    ///     3,
+    ///     3,
+    ///     vec![true, true, true, true, false, true, true, true, true],
+    /// )
+    /// .unwrap();
+    /// assert!(grid.has_line_of_sight((0, 0), (2, 0)));
+    /// assert!(!grid.has_line_of_sight((0, 0), (2, 2)));
+    /// assert!(!grid.has_line_of_sight((0, 1), (2, 1)));
+    /// ```
+    pub fn has_line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        let cost = match self.walkable_cost(from.0 as isize, from.1 as isize) {
+            Some(cost) => cost,
+            None => return false,
+        };
+        let passes = |col: isize, row: isize| self.walkable_cost(col, row) == Some(cost);
+        let (mut col, mut row) = (from.0 as isize, from.1 as isize);
+        let (to_col, to_row) = (to.0 as isize, to.1 as isize);
+        let (dx, dy) = ((to_col - col).abs(), (to_row - row).abs());
+        let (sx, sy) = ((to_col - col).signum(), (to_row - row).signum());
+        let mut error = dx - dy;
+        let mut steps = dx + dy;
+        while steps > 0 {
+            if error > 0 {
+                col += sx;
+                error -= 2 * dy;
+            } else if error < 0 {
+                row += sy;
+                error += 2 * dx;
+            } else {
+                // line goes through cells corner.
+                if !passes(col + sx, row) || !passes(col, row + sy) {
+                    return false;
+                }
+                col += sx;
+                row += sy;
+                error += 2 * (dx - dy);
+                steps -= 1;
+            }
+            if !passes(col, row) {
+                return false;
+            }
+            steps -= 1;
+        }
+        true
+    }
+
+    /// Find any-angle path between cells with Theta* search, that is not constrained to moves
+    /// between neighbor cells, so it does not need smoothing afterwards.
+    ///
+    /// Path goes straight between cells centers whenever they see each other (see
+    /// `has_line_of_sight`), costing distance multiplied by squared cost of cells on the way,
+    /// otherwise it moves between neighbor cells like `find_path`. Grids with other than
+    /// orthogonal or diagonal moves get plain path.
+    ///
+    /// # Arguments
+    /// * `from` - start cell.
+    /// * `to` - end cell.
+    ///
+    /// # Returns
+    /// `Some` with list of path corner cells (starting and ending with given cells) or `None` if
+    /// there is no path.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// #[rustfmt::skip]
+    /// let grid = NavGrid::new(
+    ///     5,
+    ///     3,
+    ///     vec![
+    ///         true, true, true,  true, true,
+    ///         true, true, false, true, true,
+    ///         true, true, true,  true, true,
+    ///     ],
+    /// )
+    /// .unwrap();
+    /// let path = grid.find_path_any_angle((0, 0), (4, 1)).unwrap();
+    /// assert_eq!(path, vec![(0, 0), (3, 0), (4, 1)]);
+    /// let path = grid.find_path_any_angle((0, 0), (4, 2)).unwrap();
+    /// assert_eq!(path.len(), 3);
+    /// ```
+    pub fn find_path_any_angle(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        if !matches!(
+            self.neighborhood(),
+            Some(NavGridNeighborhood::Orthogonal) | Some(NavGridNeighborhood::Diagonal(_))
+        ) {
+            return self.find_path_custom(from, to, |_, _| true);
+        }
+        let start = self.index(from.0, from.1)?;
+        let end = self.index(to.0, to.1)?;
+        if !self.cells()[start] || !self.cells()[end] {
+            return None;
+        }
+        let min_cost = self
+            .cells_costs()
+            .iter()
+            .zip(self.cells())
+            .filter(|(_, walkable)| **walkable)
+            .map(|(cost, _)| *cost)
+            .fold(SCALAR_MAX, Scalar::min);
+        let factor = min_cost * min_cost;
+        let distance = |a: (usize, usize), b: (usize, usize)| {
+            let dx = a.0 as Scalar - b.0 as Scalar;
+            let dy = a.1 as Scalar - b.1 as Scalar;
+            (dx * dx + dy * dy).sqrt()
+        };
+        let cost = |index: usize| self.cells_costs()[index];
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut parents = HashMap::new();
+        let mut closed = HashSet::new();
+        open.push(OpenNode(0.0, start));
+        costs.insert(start, 0.0);
+        parents.insert(start, start);
+        while let Some(OpenNode(_, current)) = open.pop() {
+            if current == end {
+                let mut path = vec![self.coord(current)?];
+                let mut node = current;
+                while parents[&node] != node {
+                    node = parents[&node];
+                    path.push(self.coord(node)?);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if !closed.insert(current) {
+                continue;
+            }
+            let position = self.coord(current)?;
+            let parent = parents[&current];
+            let parent_position = self.coord(parent)?;
+            for next_position in self.neighbors(position.0, position.1)? {
+                let next = self.index(next_position.0, next_position.1)?;
+                if closed.contains(&next) {
+                    continue;
+                }
+                let (next_cost, next_parent) = if parent != current
+                    && cost(parent) == cost(next)
+                    && self.has_line_of_sight(parent_position, next_position)
+                {
+                    let c = cost(parent);
+                    (
+                        costs[&parent] + distance(parent_position, next_position) * c * c,
+                        parent,
+                    )
+                } else {
+                    (
+                        costs[&current]
+                            + distance(position, next_position) * cost(current) * cost(next),
+                        current,
+                    )
+                };
+                if next_cost < costs.get(&next).copied().unwrap_or(SCALAR_MAX) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, next_parent);
+                    open.push(OpenNode(
+                        next_cost + distance(next_position, to) * factor,
+                        next,
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    fn walkable_cost(&self, col: isize, row: isize) -> Option<Scalar> {
+        if col < 0 || row < 0 {
+            return None;
+        }
+        let index = self.index(col as usize, row as usize)?;
+        if self.cells()[index] {
+            Some(self.cells_costs()[index])
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Conversions between nav grids and nav meshes, so grid and mesh based algorithms can be mixed.
+
+use crate::{Error, NavGrid, NavMesh, NavResult, NavTriangle, NavUpAxis, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Nav grid rasterized from nav mesh, with mapping between grid cells and nav mesh space.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavGridRaster {
+    /// Grid with cells walkable where their centers lie on walkable nav mesh triangles.
+    pub grid: NavGrid,
+    /// Corner of first cell on ground plane, in Z-up space.
+    pub origin: NavVec3,
+    /// Cell width and height.
+    pub cell_size: Scalar,
+    /// Up axis of nav mesh grid was rasterized from.
+    pub up_axis: NavUpAxis,
+}
+
+impl NavGridRaster {
+    /// Find grid cell containing point projected on ground plane.
+    ///
+    /// # Returns
+    /// `Some` with (column, row) of cell or `None` if point lies outside of grid.
+    pub fn cell(&self, point: NavVec3) -> Option<(usize, usize)> {
+        let point = self.up_axis.rotate_to_z_up(point) - self.origin;
+        let col = (point.x / self.cell_size).floor();
+        let row = (point.y / self.cell_size).floor();
+        if col < 0.0 || row < 0.0 {
+            return None;
+        }
+        let (col, row) = (col as usize, row as usize);
+        self.grid.index(col, row).map(|_| (col, row))
+    }
+
+    /// Center of grid cell on ground plane (at zero height) in nav mesh space.
+    pub fn cell_center(&self, col: usize, row: usize) -> NavVec3 {
+        let offset = NavVec3::new(
+            (col as Scalar + 0.5) * self.cell_size,
+            (row as Scalar + 0.5) * self.cell_size,
+            0.0,
+        );
+        self.up_axis.rotate_from_z_up(self.origin + offset)
+    }
+}
+
+impl NavGrid {
+    /// Build nav mesh covering walkable cells, by extracting outlines of walkable regions with
+    /// marching squares and triangulating them together with their holes.
+    ///
+    /// Cell at (column, row) covers square from `(column, row) * cell_size` to
+    /// `(column + 1, row + 1) * cell_size` on XY plane (nav mesh is Z-up). Straight runs of
+    /// outline edges are merged, so large open areas produce few triangles. Cells touching only
+    /// by corners end up in separate, disconnected parts of nav mesh, and cells costs are not
+    /// kept.
+    ///
+    /// # Arguments
+    /// * `cell_size` - cell width and height.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh or `Err` with `Error::NoWalkableSurface` if no cell is walkable.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// // ring of walkable cells around blocked center.
+    /// let grid = NavGrid::new(
+    ///     3,
+    ///     3,
+    ///     vec![true, true, true, true, false, true, true, true, true],
+    /// )
+    /// .unwrap();
+    /// let mesh = grid.to_navmesh(2.0).unwrap();
+    /// let size = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+    /// assert!((size - 32.0).abs() < 1.0e-4);
+    /// assert_eq!(mesh.vertices().len(), 8);
+    /// ```
+    pub fn to_navmesh(&self, cell_size: Scalar) -> NavResult<NavMesh> {
+        let loops = self.walkable_outlines();
+        let area = |ring: &[(isize, isize)]| {
+            (0..ring.len())
+                .map(|i| {
+                    let a = ring[i];
+                    let b = ring[(i + 1) % ring.len()];
+                    (a.0 * b.1 - b.0 * a.1) as Scalar
+                })
+                .sum::<Scalar>()
+                * 0.5
+        };
+        let (outers, holes): (Vec<_>, Vec<_>) = loops
+            .into_iter()
+            .map(|ring| (area(&ring), ring))
+            .partition(|(area, _)| *area > 0.0);
+        if outers.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        // holes belong to the smallest outline containing them.
+        let mut owned = vec![vec![]; outers.len()];
+        for (_, hole) in holes {
+            let (a, b) = (hole[0], hole[1]);
+            // point on the right side (inside) of hole edge.
+            let probe = (
+                (a.0 + b.0) as Scalar * 0.5 + (b.1 - a.1) as Scalar * 0.25,
+                (a.1 + b.1) as Scalar * 0.5 - (b.0 - a.0) as Scalar * 0.25,
+            );
+            let owner = outers
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, outer))| is_inside(probe, outer))
+                .min_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap())
+                .map(|(index, _)| index);
+            if let Some(owner) = owner {
+                owned[owner].push(hole);
+            }
+        }
+        let point = |(col, row): (isize, isize)| {
+            NavVec3::new(col as Scalar * cell_size, row as Scalar * cell_size, 0.0)
+        };
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for ((_, outer), holes) in outers.iter().zip(owned) {
+            let outer = outer.iter().copied().map(point).collect::<Vec<_>>();
+            let holes = holes
+                .into_iter()
+                .map(|hole| hole.into_iter().map(point).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let mesh = NavMesh::from_polygons(&outer, &holes)?;
+            let offset = vertices.len() as u32;
+            vertices.extend_from_slice(mesh.vertices());
+            triangles.extend(mesh.triangles().iter().map(|t| {
+                NavTriangle::from((t.first + offset, t.second + offset, t.third + offset))
+            }));
+        }
+        NavMesh::new(vertices, triangles)
+    }
+
+    /// Trace closed outlines of walkable cells along cells edges, with walkable cells on their
+    /// left side, so outer outlines go counter-clockwise and holes clockwise. Straight runs of
+    /// edges are merged.
+    fn walkable_outlines(&self) -> Vec<Vec<(isize, isize)>> {
+        let (cols, rows) = match self.coord(self.cells().len().saturating_sub(1)) {
+            Some((col, row)) => (col as isize + 1, row as isize + 1),
+            None => return vec![],
+        };
+        let walkable = |col: isize, row: isize| {
+            col >= 0
+                && row >= 0
+                && col < cols
+                && row < rows
+                && self.cells()[(row * cols + col) as usize]
+        };
+        // {edge start: [edge end]}
+        let mut edges = HashMap::<(isize, isize), Vec<(isize, isize)>>::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                if !walkable(col, row) {
+                    continue;
+                }
+                if !walkable(col, row - 1) {
+                    edges.entry((col, row)).or_default().push((col + 1, row));
+                }
+                if !walkable(col + 1, row) {
+                    edges
+                        .entry((col + 1, row))
+                        .or_default()
+                        .push((col + 1, row + 1));
+                }
+                if !walkable(col, row + 1) {
+                    edges
+                        .entry((col + 1, row + 1))
+                        .or_default()
+                        .push((col, row + 1));
+                }
+                if !walkable(col - 1, row) {
+                    edges.entry((col, row + 1)).or_default().push((col, row));
+                }
+            }
+        }
+        let mut starts = edges.keys().copied().collect::<Vec<_>>();
+        starts.sort();
+        let mut result = vec![];
+        for start in starts {
+            while let Some(next) = edges.get_mut(&start).and_then(|ends| ends.pop()) {
+                let mut ring = vec![start];
+                let mut previous = start;
+                let mut current = next;
+                while current != start {
+                    let dir = (current.0 - previous.0, current.1 - previous.1);
+                    let ends = match edges.get_mut(&current) {
+                        Some(ends) if !ends.is_empty() => ends,
+                        _ => break,
+                    };
+                    // on saddles prefer turning left, so corner touching cells stay apart.
+                    let turns = [(-dir.1, dir.0), dir, (dir.1, -dir.0)];
+                    let index = turns
+                        .iter()
+                        .find_map(|turn| {
+                            ends.iter()
+                                .position(|end| (end.0 - current.0, end.1 - current.1) == *turn)
+                        })
+                        .unwrap_or(0);
+                    let end = ends.swap_remove(index);
+                    ring.push(current);
+                    previous = current;
+                    current = end;
+                }
+                let ring = merge_straight_runs(ring);
+                if ring.len() >= 3 {
+                    result.push(ring);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl NavMesh {
+    /// Rasterize nav mesh into grid, with cells walkable where their centers lie on triangles
+    /// that are not blocked, and cells costs taken from areas costs of those triangles. Nav mesh
+    /// gets flattened along its up axis, so overlapping floors merge into single grid layer.
+    ///
+    /// # Arguments
+    /// * `cell_size` - cell width and height.
+    ///
+    /// # Returns
+    /// `Ok` with rasterized grid or `Err` with `Error::EmptyCells` if nav mesh is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (2.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let raster = mesh.rasterize_to_grid(0.5).unwrap();
+    /// assert_eq!(raster.grid.cells().len(), 8);
+    /// assert!(raster.grid.cells().iter().all(|cell| *cell));
+    /// assert_eq!(raster.cell((1.8, 0.1, 0.0).into()), Some((3, 0)));
+    /// assert_eq!(raster.cell_center(3, 0), (1.75, 0.25, 0.0).into());
+    /// ```
+    pub fn rasterize_to_grid(&self, cell_size: Scalar) -> NavResult<NavGridRaster> {
+        let up_axis = self.up_axis();
+        let points = self
+            .vertices()
+            .iter()
+            .map(|v| up_axis.rotate_to_z_up(*v))
+            .collect::<Vec<_>>();
+        let (min, max) = match points.first() {
+            Some(first) => points
+                .iter()
+                .fold((*first, *first), |(min, max), v| (min.min(*v), max.max(*v))),
+            None => return Err(Error::EmptyCells(0, 0)),
+        };
+        let cell_size = cell_size.max(self.tolerance().distance);
+        let cols = (((max.x - min.x) / cell_size).ceil() as usize).max(1);
+        let rows = (((max.y - min.y) / cell_size).ceil() as usize).max(1);
+        let mut cells = vec![false; cols * rows];
+        let mut costs = vec![1.0; cols * rows];
+        for (index, t) in self.triangles().iter().enumerate() {
+            if self.is_triangle_blocked(index) {
+                continue;
+            }
+            let a = points[t.first as usize] - min;
+            let b = points[t.second as usize] - min;
+            let c = points[t.third as usize] - min;
+            if ((b - a).cross(c - a).z).abs() < self.tolerance().distance_sqr {
+                continue;
+            }
+            let col_from = (a.x.min(b.x).min(c.x) / cell_size - 0.5).ceil().max(0.0) as usize;
+            let col_to =
+                ((a.x.max(b.x).max(c.x) / cell_size - 0.5).floor().max(0.0) as usize).min(cols - 1);
+            let row_from = (a.y.min(b.y).min(c.y) / cell_size - 0.5).ceil().max(0.0) as usize;
+            let row_to =
+                ((a.y.max(b.y).max(c.y) / cell_size - 0.5).floor().max(0.0) as usize).min(rows - 1);
+            for row in row_from..=row_to {
+                for col in col_from..=col_to {
+                    let p = NavVec3::new(
+                        (col as Scalar + 0.5) * cell_size,
+                        (row as Scalar + 0.5) * cell_size,
+                        0.0,
+                    );
+                    if is_inside_triangle_2d(p, a, b, c) {
+                        let cell = row * cols + col;
+                        if !cells[cell] {
+                            cells[cell] = true;
+                            costs[cell] = self.areas()[index].cost;
+                        }
+                    }
+                }
+            }
+        }
+        let mut grid = NavGrid::new(cols, rows, cells)?;
+        for (index, cost) in costs.into_iter().enumerate() {
+            grid.set_cell_cost(index % cols, index / cols, cost);
+        }
+        Ok(NavGridRaster {
+            grid,
+            origin: NavVec3::new(min.x, min.y, 0.0),
+            cell_size,
+            up_axis,
+        })
+    }
+}
+
+/// Remove outline points lying in the middle of straight runs of edges.
+fn merge_straight_runs(ring: Vec<(isize, isize)>) -> Vec<(isize, isize)> {
+    let count = ring.len();
+    (0..count)
+        .filter(|i| {
+            let prev = ring[(i + count - 1) % count];
+            let current = ring[*i];
+            let next = ring[(i + 1) % count];
+            (current.0 - prev.0) * (next.1 - current.1)
+                != (current.1 - prev.1) * (next.0 - current.0)
+        })
+        .map(|i| ring[i])
+        .collect()
+}
+
+/// Even-odd test of point against polygon.
+fn is_inside(point: (Scalar, Scalar), polygon: &[(isize, isize)]) -> bool {
+    let mut result = false;
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        let (ax, ay) = (a.0 as Scalar, a.1 as Scalar);
+        let (bx, by) = (b.0 as Scalar, b.1 as Scalar);
+        if (ay > point.1) != (by > point.1) && point.0 < ax + (point.1 - ay) / (by - ay) * (bx - ax)
+        {
+            result = !result;
+        }
+    }
+    result
+}
+
+/// Tells if point lies inside of triangle (or on its edges) on XY plane, for any winding.
+fn is_inside_triangle_2d(p: NavVec3, a: NavVec3, b: NavVec3, c: NavVec3) -> bool {
+    let side = |u: NavVec3, v: NavVec3| (v.x - u.x) * (p.y - u.y) - (v.y - u.y) * (p.x - u.x);
+    let (ab, bc, ca) = (side(a, b), side(b, c), side(c, a));
+    (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+}
@@ -0,0 +1,200 @@
+use crate::{Error, NavMesh, NavResult, NavTriangle, NavVec3, Scalar, ZERO_TRESHOLD};
+use std::collections::HashMap;
+
+impl NavMesh {
+    /// Create nav mesh from terrain heightmap.
+    ///
+    /// Heightmap cells are split into triangles lying on ground plane (Z up), triangles steeper
+    /// than maximal slope are removed. Flat regions covered by many cells get merged into big
+    /// rectangles, triangulated around their center without splitting neighbor edges.
+    ///
+    /// # Arguments
+    /// * `width` - number of samples along X axis.
+    /// * `height` - number of samples along Y axis.
+    /// * `samples` - terrain heights, row by row (`y * width + x`).
+    /// * `cell_size` - distance between neighbor samples.
+    /// * `max_slope` - maximal slope (in radians) of walkable triangles.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh or `Err` if samples count does not match heightmap size or there is
+    /// no walkable surface.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// // flat plateau with steep cliff along its right side.
+    /// let samples = vec![
+    ///     0.0, 0.0, 0.0, 0.0, 5.0,
+    ///     0.0, 0.0, 0.0, 0.0, 5.0,
+    ///     0.0, 0.0, 0.0, 0.0, 5.0,
+    ///     0.0, 0.0, 0.0, 0.0, 5.0,
+    /// ];
+    /// let mesh = NavMesh::from_heightmap(5, 4, &samples, 1.0, 0.5).unwrap();
+    /// let size = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+    /// assert!((size - 9.0).abs() < 1.0e-4);
+    /// assert!(mesh.triangles().len() < 18);
+    /// ```
+    pub fn from_heightmap(
+        width: usize,
+        height: usize,
+        samples: &[Scalar],
+        cell_size: Scalar,
+        max_slope: Scalar,
+    ) -> NavResult<Self> {
+        if samples.len() != width * height {
+            return Err(Error::CellsCountDoesNotMatchColsRows(
+                samples.len(),
+                width,
+                height,
+            ));
+        }
+        if width < 2 || height < 2 {
+            return Err(Error::EmptyCells(
+                width.saturating_sub(1),
+                height.saturating_sub(1),
+            ));
+        }
+        let cell_size = cell_size.max(ZERO_TRESHOLD);
+        let cols = width - 1;
+        let rows = height - 1;
+        let point = |x: usize, y: usize| {
+            NavVec3::new(
+                x as Scalar * cell_size,
+                y as Scalar * cell_size,
+                samples[y * width + x],
+            )
+        };
+        let min_up = max_slope
+            .clamp(0.0, std::f64::consts::FRAC_PI_2 as Scalar)
+            .cos();
+        let is_walkable = |a: NavVec3, b: NavVec3, c: NavVec3| {
+            let normal = (b - a).cross(c - a);
+            let length = normal.magnitude();
+            length > ZERO_TRESHOLD && normal.z / length >= min_up - ZERO_TRESHOLD
+        };
+        // [(first triangle walkable, second triangle walkable)]
+        let walkable = (0..rows)
+            .flat_map(|y| (0..cols).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (a, b, c, d) = (
+                    point(x, y),
+                    point(x + 1, y),
+                    point(x + 1, y + 1),
+                    point(x, y + 1),
+                );
+                (is_walkable(a, b, c), is_walkable(c, d, a))
+            })
+            .collect::<Vec<_>>();
+        // cell plane as (origin, normal) if both cell triangles are walkable and coplanar.
+        let plane = |x: usize, y: usize| {
+            if walkable[y * cols + x] != (true, true) {
+                return None;
+            }
+            let (a, b, c, d) = (
+                point(x, y),
+                point(x + 1, y),
+                point(x + 1, y + 1),
+                point(x, y + 1),
+            );
+            let normal = (b - a).cross(c - a).normalize();
+            if d.distance_to_plane(a, normal).abs() <= ZERO_TRESHOLD {
+                Some((a, normal))
+            } else {
+                None
+            }
+        };
+        let on_plane = |x: usize, y: usize, (origin, normal): (NavVec3, NavVec3)| {
+            plane(x, y).is_some()
+                && [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)]
+                    .iter()
+                    .all(|(x, y)| {
+                        point(*x, *y).distance_to_plane(origin, normal).abs() <= ZERO_TRESHOLD
+                    })
+        };
+
+        // greedy merge of coplanar cells into rectangles: (x, y, cols, rows).
+        let mut merged = vec![false; cols * rows];
+        let mut rectangles = vec![];
+        for y in 0..rows {
+            for x in 0..cols {
+                if merged[y * cols + x] {
+                    continue;
+                }
+                let base = match plane(x, y) {
+                    Some(base) => base,
+                    None => continue,
+                };
+                let fits = |cx: usize, cy: usize| !merged[cy * cols + cx] && on_plane(cx, cy, base);
+                let mut w = 1;
+                while x + w < cols && fits(x + w, y) {
+                    w += 1;
+                }
+                let mut h = 1;
+                while y + h < rows && (x..(x + w)).all(|cx| fits(cx, y + h)) {
+                    h += 1;
+                }
+                if w > 1 && h > 1 {
+                    for cy in y..(y + h) {
+                        for cx in x..(x + w) {
+                            merged[cy * cols + cx] = true;
+                        }
+                    }
+                    rectangles.push((x, y, w, h));
+                }
+            }
+        }
+
+        let mut vertices = vec![];
+        // {sample index: vertice index}
+        let mut used = HashMap::new();
+        let mut vertice = |x: usize, y: usize, vertices: &mut Vec<NavVec3>| {
+            *used.entry(y * width + x).or_insert_with(|| {
+                vertices.push(point(x, y));
+                vertices.len() as u32 - 1
+            })
+        };
+        let mut triangles = vec![];
+        for y in 0..rows {
+            for x in 0..cols {
+                if merged[y * cols + x] {
+                    continue;
+                }
+                let (first, second) = walkable[y * cols + x];
+                if first || second {
+                    let a = vertice(x, y, &mut vertices);
+                    let c = vertice(x + 1, y + 1, &mut vertices);
+                    if first {
+                        let b = vertice(x + 1, y, &mut vertices);
+                        triangles.push(NavTriangle::from((a, b, c)));
+                    }
+                    if second {
+                        let d = vertice(x, y + 1, &mut vertices);
+                        triangles.push(NavTriangle::from((c, d, a)));
+                    }
+                }
+            }
+        }
+        for (x, y, w, h) in rectangles {
+            // rectangle outline going through every sample, so neighbor cells edges are shared.
+            let outline = (x..(x + w))
+                .map(|cx| (cx, y))
+                .chain((y..(y + h)).map(|cy| (x + w, cy)))
+                .chain(((x + 1)..=(x + w)).rev().map(|cx| (cx, y + h)))
+                .chain(((y + 1)..=(y + h)).rev().map(|cy| (x, cy)))
+                .map(|(x, y)| vertice(x, y, &mut vertices))
+                .collect::<Vec<_>>();
+            let center = (point(x, y) + point(x + w, y + h)) * 0.5;
+            vertices.push(center);
+            let center = vertices.len() as u32 - 1;
+            for i in 0..outline.len() {
+                let next = outline[(i + 1) % outline.len()];
+                triangles.push(NavTriangle::from((center, outline[i], next)));
+            }
+        }
+        if triangles.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        Self::new(vertices, triangles)
+    }
+}
@@ -0,0 +1,370 @@
+use crate::{nav_open_node::OpenNode, NavMesh, NavMeshID, NavPathMode, NavQuery, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Hierarchical path finding layer over nav mesh (HPA*).
+///
+/// Triangles are grouped into connected clusters, triangles that lie on clusters borders become
+/// entrances and costs of traveling between entrances of the same cluster are precomputed, so
+/// long distance queries can search small abstract graph instead of whole nav mesh.
+///
+/// Precomputed costs do not follow later changes of nav mesh areas costs, so hierarchy should be
+/// rebuilt after such changes. Obstacles are respected by searches though.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshHierarchy {
+    mesh_id: NavMeshID,
+    cluster_size: Scalar,
+    // [cluster index by triangle index]
+    clusters: Vec<usize>,
+    clusters_count: usize,
+    // [triangle index by entrance index]
+    entrances: Vec<usize>,
+    // {triangle index: entrance index}
+    entrances_map: HashMap<usize, usize>,
+    // [[(entrance index, cost)] by entrance index]
+    edges: Vec<Vec<(usize, Scalar)>>,
+}
+
+impl NavMeshHierarchy {
+    /// Build hierarchy for given nav mesh.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `cluster_size` - size of the cube that bounds triangles center points in single cluster.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let hierarchy = NavMeshHierarchy::new(&mesh, 1.0);
+    /// let path = hierarchy.find_path_triangles(&mesh, 1, 2, true).unwrap().0;
+    /// assert_eq!(path, vec![1, 0, 3, 2]);
+    /// ```
+    pub fn new(mesh: &NavMesh, cluster_size: Scalar) -> Self {
+        let cluster_size = cluster_size.max(crate::ZERO_TRESHOLD);
+        let areas = mesh.areas();
+        let cell = |index: usize| {
+            let c = areas[index].center;
+            (
+                (c.x / cluster_size).floor() as i64,
+                (c.y / cluster_size).floor() as i64,
+                (c.z / cluster_size).floor() as i64,
+            )
+        };
+
+        // split grid cells into connected clusters.
+        let mut clusters = vec![usize::MAX; areas.len()];
+        let mut clusters_count = 0;
+        for start in 0..areas.len() {
+            if clusters[start] != usize::MAX {
+                continue;
+            }
+            let key = cell(start);
+            clusters[start] = clusters_count;
+            let mut stack = vec![start];
+            while let Some(index) = stack.pop() {
+                for next in mesh.triangle_neighbors(index) {
+                    if clusters[next] == usize::MAX && cell(next) == key {
+                        clusters[next] = clusters_count;
+                        stack.push(next);
+                    }
+                }
+            }
+            clusters_count += 1;
+        }
+
+        let entrances = (0..areas.len())
+            .filter(|index| {
+                mesh.triangle_neighbors(*index)
+                    .any(|next| clusters[next] != clusters[*index])
+            })
+            .collect::<Vec<_>>();
+        let entrances_map = entrances
+            .iter()
+            .enumerate()
+            .map(|(i, index)| (*index, i))
+            .collect::<HashMap<_, _>>();
+
+        let mut edges = vec![vec![]; entrances.len()];
+        for (i, index) in entrances.iter().enumerate() {
+            let cluster = clusters[*index];
+            let (costs, _) = search(mesh, *index, None, false, |t| clusters[t] == cluster);
+            for (triangle, cost) in costs {
+                if triangle != *index {
+                    if let Some(other) = entrances_map.get(&triangle) {
+                        edges[i].push((*other, cost));
+                    }
+                }
+            }
+            for next in mesh.triangle_neighbors(*index) {
+                if clusters[next] != cluster {
+                    edges[i].push((entrances_map[&next], edge_cost(mesh, *index, next)));
+                }
+            }
+        }
+
+        Self {
+            mesh_id: mesh.id(),
+            cluster_size,
+            clusters,
+            clusters_count,
+            entrances,
+            entrances_map,
+            edges,
+        }
+    }
+
+    #[inline]
+    pub fn mesh_id(&self) -> NavMeshID {
+        self.mesh_id
+    }
+
+    #[inline]
+    pub fn cluster_size(&self) -> Scalar {
+        self.cluster_size
+    }
+
+    #[inline]
+    pub fn clusters_count(&self) -> usize {
+        self.clusters_count
+    }
+
+    /// Get cluster index of triangle.
+    #[inline]
+    pub fn triangle_cluster(&self, index: usize) -> Option<usize> {
+        self.clusters.get(index).copied()
+    }
+
+    /// Reference to list of entrance triangles indices.
+    #[inline]
+    pub fn entrances(&self) -> &[usize] {
+        &self.entrances
+    }
+
+    /// Find path between two triangles using abstract graph of clusters entrances.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh this hierarchy was built for.
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `refine` - if true, path gets refined by searching triangles of clusters visited by
+    ///   abstract path, which makes it closer to optimal one.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path cost if found or `None` otherwise.
+    pub fn find_path_triangles(
+        &self,
+        mesh: &NavMesh,
+        from: usize,
+        to: usize,
+        refine: bool,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        if mesh.id() != self.mesh_id
+            || from >= self.clusters.len()
+            || to >= self.clusters.len()
+            || mesh.is_triangle_blocked(from)
+            || mesh.is_triangle_blocked(to)
+        {
+            return None;
+        }
+        let start_cluster = self.clusters[from];
+        let end_cluster = self.clusters[to];
+        if start_cluster == end_cluster {
+            if let Some(result) = mesh.find_path_triangles(from, to) {
+                return Some(result);
+            }
+        }
+
+        let (start_costs, start_parents) = search(mesh, from, None, true, |t| {
+            self.clusters[t] == start_cluster
+        });
+        let (end_costs, end_parents) =
+            search(mesh, to, None, true, |t| self.clusters[t] == end_cluster);
+
+        // abstract graph search, where `self.entrances.len()` is the goal node.
+        let goal = self.entrances.len();
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::<usize, Scalar>::new();
+        let mut parents = HashMap::<usize, usize>::new();
+        for (triangle, cost) in &start_costs {
+            if let Some(entrance) = self.entrances_map.get(triangle) {
+                costs.insert(*entrance, *cost);
+                open.push(OpenNode(*cost, *entrance));
+            }
+        }
+        let mut found = None;
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if current == goal {
+                found = Some(cost);
+                break;
+            }
+            if cost > costs.get(&current).copied().unwrap_or(Scalar::INFINITY) {
+                continue;
+            }
+            let triangle = self.entrances[current];
+            let mut next_nodes = self.edges[current].clone();
+            if let Some(goal_cost) = end_costs.get(&triangle) {
+                next_nodes.push((goal, *goal_cost));
+            }
+            for (next, edge) in next_nodes {
+                if next != goal && mesh.is_triangle_blocked(self.entrances[next]) {
+                    continue;
+                }
+                let next_cost = cost + edge;
+                if next_cost < costs.get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, current);
+                    open.push(OpenNode(next_cost, next));
+                }
+            }
+        }
+        found?;
+        let mut abstract_path = vec![];
+        let mut node = parents[&goal];
+        abstract_path.push(self.entrances[node]);
+        while let Some(parent) = parents.get(&node) {
+            node = *parent;
+            abstract_path.push(self.entrances[node]);
+        }
+        abstract_path.reverse();
+
+        // expand abstract path into triangles corridor.
+        let mut path = unwind(&start_parents, abstract_path[0]);
+        for pair in abstract_path.windows(2) {
+            let cluster = self.clusters[pair[0]];
+            if cluster == self.clusters[pair[1]] {
+                let (_, local) = search(mesh, pair[0], Some(pair[1]), true, |t| {
+                    self.clusters[t] == cluster
+                });
+                path.extend(unwind(&local, pair[1]).into_iter().skip(1));
+            } else {
+                path.push(pair[1]);
+            }
+        }
+        let mut tail = unwind(&end_parents, abstract_path[abstract_path.len() - 1]);
+        tail.reverse();
+        path.extend(tail.into_iter().skip(1));
+        path.dedup();
+
+        if refine {
+            let corridor = path
+                .iter()
+                .map(|index| self.clusters[*index])
+                .collect::<HashSet<_>>();
+            let (costs, parents) = search(mesh, from, Some(to), true, |t| {
+                corridor.contains(&self.clusters[t])
+            });
+            if let Some(cost) = costs.get(&to) {
+                return Some((unwind(&parents, to), *cost));
+            }
+        }
+        let cost = path
+            .windows(2)
+            .map(|pair| edge_cost(mesh, pair[0], pair[1]))
+            .sum();
+        Some((path, cost))
+    }
+
+    /// Find path between two points using abstract graph of clusters entrances.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh this hierarchy was built for.
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `refine` - if true, path gets refined by searching triangles of clusters visited by
+    ///   abstract path.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    pub fn find_path(
+        &self,
+        mesh: &NavMesh,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        refine: bool,
+    ) -> Option<Vec<NavVec3>> {
+        mesh.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles(mesh, start, end, refine)
+        })
+    }
+}
+
+fn edge_cost(mesh: &NavMesh, from: usize, to: usize) -> Scalar {
+    let a = &mesh.areas()[from];
+    let b = &mesh.areas()[to];
+    (b.center - a.center).sqr_magnitude() * a.cost * b.cost
+}
+
+/// Dijkstra search over triangles accepted by `allowed`, stopping early when `to` gets reached.
+///
+/// # Returns
+/// Pair of ({triangle index: cost}, {triangle index: parent triangle index}).
+fn search<F>(
+    mesh: &NavMesh,
+    from: usize,
+    to: Option<usize>,
+    skip_blocked: bool,
+    allowed: F,
+) -> (HashMap<usize, Scalar>, HashMap<usize, usize>)
+where
+    F: Fn(usize) -> bool,
+{
+    let mut open = BinaryHeap::new();
+    let mut costs = HashMap::<usize, Scalar>::new();
+    let mut parents = HashMap::<usize, usize>::new();
+    costs.insert(from, 0.0);
+    open.push(OpenNode(0.0, from));
+    while let Some(OpenNode(cost, current)) = open.pop() {
+        if Some(current) == to {
+            break;
+        }
+        if cost > costs.get(&current).copied().unwrap_or(Scalar::INFINITY) {
+            continue;
+        }
+        for next in mesh.triangle_neighbors(current) {
+            if !allowed(next) || (skip_blocked && mesh.is_triangle_blocked(next)) {
+                continue;
+            }
+            let next_cost = cost + edge_cost(mesh, current, next);
+            if next_cost < costs.get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                costs.insert(next, next_cost);
+                parents.insert(next, current);
+                open.push(OpenNode(next_cost, next));
+            }
+        }
+    }
+    (costs, parents)
+}
+
+fn unwind(parents: &HashMap<usize, usize>, to: usize) -> Vec<usize> {
+    let mut path = vec![to];
+    let mut node = to;
+    while let Some(parent) = parents.get(&node) {
+        path.push(*parent);
+        node = *parent;
+    }
+    path.reverse();
+    path
+}
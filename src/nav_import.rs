@@ -0,0 +1,331 @@
+use crate::{Error, NavResult, NavTriangle, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+type Matrix = [[f32; 4]; 4];
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// Geometry import settings.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavImportSettings {
+    /// Convert Y-up coordinates (used by glTF and most OBJ exporters) into Z-up coordinates used
+    /// by nav mesh and baker.
+    pub y_up_to_z_up: bool,
+    /// Uniform scale applied to imported vertices.
+    pub scale: Scalar,
+}
+
+impl Default for NavImportSettings {
+    fn default() -> Self {
+        Self {
+            y_up_to_z_up: true,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Loads triangle geometry from glTF or OBJ files, to use it as nav mesh or as level geometry for
+/// `NavMeshBaker`.
+///
+/// Every import function takes name filter, which receives node or mesh names (object or group
+/// name for OBJ) and tells if geometry should be imported.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavGeometryImporter {
+    settings: NavImportSettings,
+}
+
+impl NavGeometryImporter {
+    pub fn new(settings: NavImportSettings) -> Self {
+        Self { settings }
+    }
+
+    #[inline]
+    pub fn settings(&self) -> &NavImportSettings {
+        &self.settings
+    }
+
+    /// Import triangles from glTF file (either `.gltf` with external buffers or `.glb`).
+    ///
+    /// Node is imported when filter accepts either its name or its mesh name, and then all of its
+    /// children are imported too. Node transforms are applied to vertices.
+    ///
+    /// # Arguments
+    /// * `path` - path to glTF file.
+    /// * `filter` - name filter.
+    ///
+    /// # Returns
+    /// `Ok` with vertices and triangles or `Err` with `Error::CouldNotImportGeometry`.
+    pub fn import_gltf<P, F>(
+        &self,
+        path: P,
+        filter: F,
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>)>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&str) -> bool,
+    {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|error| {
+            Error::CouldNotImportGeometry(format!("{}: {}", path.display(), error))
+        })?;
+        self.load_gltf(&bytes, path.parent(), filter)
+    }
+
+    /// Import triangles from glTF data that does not reference external files (`.glb` or `.gltf`
+    /// with embedded buffers).
+    ///
+    /// # Arguments
+    /// * `bytes` - glTF data.
+    /// * `filter` - name filter.
+    ///
+    /// # Returns
+    /// `Ok` with vertices and triangles or `Err` with `Error::CouldNotImportGeometry`.
+    pub fn import_gltf_slice<F>(
+        &self,
+        bytes: &[u8],
+        filter: F,
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>)>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        self.load_gltf(bytes, None, filter)
+    }
+
+    /// Import triangles from OBJ file. Materials are ignored.
+    ///
+    /// # Arguments
+    /// * `path` - path to OBJ file.
+    /// * `filter` - name filter.
+    ///
+    /// # Returns
+    /// `Ok` with vertices and triangles or `Err` with `Error::CouldNotImportGeometry`.
+    pub fn import_obj<P, F>(
+        &self,
+        path: P,
+        filter: F,
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>)>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&str) -> bool,
+    {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|error| {
+            Error::CouldNotImportGeometry(format!("{}: {}", path.display(), error))
+        })?;
+        self.load_obj(&mut BufReader::new(file), filter)
+    }
+
+    /// Import triangles from OBJ data. Materials are ignored.
+    ///
+    /// # Arguments
+    /// * `bytes` - OBJ data.
+    /// * `filter` - name filter.
+    ///
+    /// # Returns
+    /// `Ok` with vertices and triangles or `Err` with `Error::CouldNotImportGeometry`.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let data = b"
+    /// o floor
+    /// v 0 0 0
+    /// v 1 0 0
+    /// v 1 0 -1
+    /// v 0 0 -1
+    /// f 1 2 3 4
+    /// o wall
+    /// v 0 0 0
+    /// v 0 1 0
+    /// v 1 1 0
+    /// f 5 6 7
+    /// ";
+    /// let importer = NavGeometryImporter::default();
+    /// let (vertices, triangles) = importer.import_obj_slice(data, |name| name == "floor").unwrap();
+    /// assert_eq!(vertices.len(), 4);
+    /// assert_eq!(triangles.len(), 2);
+    /// assert_eq!(vertices[2], (1.0, 1.0, 0.0).into());
+    /// ```
+    pub fn import_obj_slice<F>(
+        &self,
+        mut bytes: &[u8],
+        filter: F,
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>)>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        self.load_obj(&mut bytes, filter)
+    }
+
+    fn load_gltf<F>(
+        &self,
+        bytes: &[u8],
+        base: Option<&Path>,
+        mut filter: F,
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>)>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let gltf = gltf::Gltf::from_slice(bytes)
+            .map_err(|error| Error::CouldNotImportGeometry(error.to_string()))?;
+        let gltf::Gltf { document, blob } = gltf;
+        let buffers = gltf::import_buffers(&document, base, blob)
+            .map_err(|error| Error::CouldNotImportGeometry(error.to_string()))?;
+        let scene = match document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+        {
+            Some(scene) => scene,
+            None => return Ok((vec![], vec![])),
+        };
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        // (node, parent transform, parent accepted)
+        let mut stack = scene
+            .nodes()
+            .map(|node| (node, IDENTITY, false))
+            .collect::<Vec<_>>();
+        while let Some((node, parent, accepted)) = stack.pop() {
+            let transform = multiply(&parent, &node.transform().matrix());
+            let accepted = accepted
+                || filter(node.name().unwrap_or_default())
+                || node
+                    .mesh()
+                    .map(|mesh| filter(mesh.name().unwrap_or_default()))
+                    .unwrap_or_default();
+            if accepted {
+                if let Some(mesh) = node.mesh() {
+                    for primitive in mesh.primitives() {
+                        if primitive.mode() != gltf::mesh::Mode::Triangles {
+                            continue;
+                        }
+                        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+                        let positions = match reader.read_positions() {
+                            Some(positions) => positions,
+                            None => continue,
+                        };
+                        let offset = vertices.len() as u32;
+                        vertices.extend(
+                            positions.map(|p| self.convert(transform_point(&transform, p))),
+                        );
+                        let count = vertices.len() as u32 - offset;
+                        let indices = match reader.read_indices() {
+                            Some(indices) => indices.into_u32().collect::<Vec<_>>(),
+                            None => (0..count).collect(),
+                        };
+                        for chunk in indices.chunks_exact(3) {
+                            if chunk.iter().any(|index| *index >= count) {
+                                return Err(Error::CouldNotImportGeometry(format!(
+                                    "Mesh {} has vertice index out of bounds",
+                                    mesh.name().unwrap_or_default()
+                                )));
+                            }
+                            triangles.push(NavTriangle::from((
+                                chunk[0] + offset,
+                                chunk[1] + offset,
+                                chunk[2] + offset,
+                            )));
+                        }
+                    }
+                }
+            }
+            stack.extend(node.children().map(|child| (child, transform, accepted)));
+        }
+        Ok((vertices, triangles))
+    }
+
+    fn load_obj<B, F>(
+        &self,
+        reader: &mut B,
+        mut filter: F,
+    ) -> NavResult<(Vec<NavVec3>, Vec<NavTriangle>)>
+    where
+        B: BufRead,
+        F: FnMut(&str) -> bool,
+    {
+        let options = tobj::LoadOptions {
+            triangulate: true,
+            single_index: true,
+            ..Default::default()
+        };
+        let (models, _) =
+            tobj::load_obj_buf(reader, &options, |_| Err(tobj::LoadError::OpenFileFailed))
+                .map_err(|error| Error::CouldNotImportGeometry(error.to_string()))?;
+        let mut vertices = vec![];
+        let mut triangles = vec![];
+        for model in models {
+            if !filter(&model.name) {
+                continue;
+            }
+            let offset = vertices.len() as u32;
+            vertices.extend(
+                model
+                    .mesh
+                    .positions
+                    .chunks_exact(3)
+                    .map(|p| self.convert([p[0], p[1], p[2]])),
+            );
+            let count = vertices.len() as u32 - offset;
+            for chunk in model.mesh.indices.chunks_exact(3) {
+                if chunk.iter().any(|index| *index >= count) {
+                    return Err(Error::CouldNotImportGeometry(format!(
+                        "Object {} has vertice index out of bounds",
+                        model.name
+                    )));
+                }
+                triangles.push(NavTriangle::from((
+                    chunk[0] + offset,
+                    chunk[1] + offset,
+                    chunk[2] + offset,
+                )));
+            }
+        }
+        Ok((vertices, triangles))
+    }
+
+    fn convert(&self, point: [f32; 3]) -> NavVec3 {
+        let [x, y, z] = point;
+        let result = if self.settings.y_up_to_z_up {
+            NavVec3::new(x as Scalar, -z as Scalar, y as Scalar)
+        } else {
+            NavVec3::new(x as Scalar, y as Scalar, z as Scalar)
+        };
+        result * self.settings.scale
+    }
+}
+
+/// Multiply column major matrices.
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 4];
+    for (col, column) in result.iter_mut().enumerate() {
+        for (row, value) in column.iter_mut().enumerate() {
+            *value = (0..4).map(|i| a[i][row] * b[col][i]).sum();
+        }
+    }
+    result
+}
+
+fn transform_point(matrix: &Matrix, point: [f32; 3]) -> [f32; 3] {
+    let mut result = [0.0; 3];
+    for (row, value) in result.iter_mut().enumerate() {
+        *value = matrix[0][row] * point[0]
+            + matrix[1][row] * point[1]
+            + matrix[2][row] * point[2]
+            + matrix[3][row];
+    }
+    result
+}
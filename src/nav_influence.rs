@@ -0,0 +1,320 @@
+use crate::{
+    closest_points_segment_triangle, NavMesh, NavMeshID, NavQuery, NavQueryFilter, NavVec3, Scalar,
+    SCALAR_MAX, ZERO_TRESHOLD,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use spade::BoundingRect;
+
+/// Influence map storing dynamic value per nav mesh triangle (danger of recent grenade blasts,
+/// sniper sightlines), that fades and spreads with update steps and feeds into path costs with
+/// `NavInfluenceFilter`, so nav mesh does not need to be changed or baked again.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (2.0, 0.0, 0.0).into(), // 2
+///     (0.0, 1.0, 0.0).into(), // 3
+///     (1.0, 1.0, 0.0).into(), // 4
+///     (2.0, 1.0, 0.0).into(), // 5
+/// ];
+/// let triangles = vec![
+///     (0, 1, 4).into(), // 0
+///     (4, 3, 0).into(), // 1
+///     (1, 2, 5).into(), // 2
+///     (5, 4, 1).into(), // 3
+/// ];
+///
+/// let mesh = NavMesh::new(vertices, triangles).unwrap();
+/// let mut map = NavInfluenceMap::new(&mesh);
+/// // grenade blast.
+/// map.add_at(&mesh, (1.8, 0.2, 0.0).into(), 0.0, 1.0);
+/// assert_eq!(map.value(2), Some(1.0));
+/// assert_eq!(map.value(1), Some(0.0));
+/// map.decay(0.5);
+/// assert_eq!(map.value(2), Some(0.5));
+/// map.blur(1.0);
+/// assert_eq!(map.value(3), Some(0.25));
+/// ```
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavInfluenceMap {
+    mesh: NavMeshID,
+    values: Vec<Scalar>,
+    // neighbor triangles of every triangle.
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl NavInfluenceMap {
+    /// Create influence map with zero values for all nav mesh triangles.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    pub fn new(mesh: &NavMesh) -> Self {
+        Self {
+            mesh: mesh.id(),
+            values: vec![0.0; mesh.triangles().len()],
+            neighbors: (0..mesh.triangles().len())
+                .map(|index| mesh.triangle_neighbors(index).collect())
+                .collect(),
+        }
+    }
+
+    /// Identifier of nav mesh influence map was made for.
+    #[inline]
+    pub fn mesh(&self) -> NavMeshID {
+        self.mesh
+    }
+
+    /// Values of all triangles.
+    #[inline]
+    pub fn values(&self) -> &[Scalar] {
+        &self.values
+    }
+
+    /// Get value of triangle.
+    #[inline]
+    pub fn value(&self, index: usize) -> Option<Scalar> {
+        self.values.get(index).copied()
+    }
+
+    /// Set value of triangle.
+    ///
+    /// # Returns
+    /// `Some` with previous value or `None` if triangle does not exist.
+    pub fn set_value(&mut self, index: usize, value: Scalar) -> Option<Scalar> {
+        self.values
+            .get_mut(index)
+            .map(|item| std::mem::replace(item, value))
+    }
+
+    /// Add to value of triangle.
+    ///
+    /// # Returns
+    /// `Some` with new value or `None` if triangle does not exist.
+    pub fn add(&mut self, index: usize, amount: Scalar) -> Option<Scalar> {
+        let item = self.values.get_mut(index)?;
+        *item += amount;
+        Some(*item)
+    }
+
+    /// Largest value of all triangles, zero if there are no triangles.
+    pub fn max_value(&self) -> Scalar {
+        self.values.iter().copied().fold(0.0, Scalar::max)
+    }
+
+    /// Set all values to zero.
+    pub fn clear(&mut self) {
+        for value in &mut self.values {
+            *value = 0.0;
+        }
+    }
+
+    /// Get value of nav mesh triangle closest to point.
+    pub fn sample(&self, mesh: &NavMesh, point: NavVec3) -> Option<Scalar> {
+        if mesh.id() != self.mesh {
+            return None;
+        }
+        self.value(mesh.find_closest_triangle(point, NavQuery::Accuracy)?)
+    }
+
+    /// Add influence around point, for example grenade blast.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `point` - influence center.
+    /// * `radius` - influence radius, amount falls off linearly to zero at this distance.
+    /// * `amount` - influence amount at its center.
+    ///
+    /// # Returns
+    /// Number of affected triangles.
+    pub fn add_at(
+        &mut self,
+        mesh: &NavMesh,
+        point: NavVec3,
+        radius: Scalar,
+        amount: Scalar,
+    ) -> usize {
+        self.add_along(mesh, point, point, radius, amount)
+    }
+
+    /// Add influence around segment, for example sniper sightline.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `from` - segment start point.
+    /// * `to` - segment end point.
+    /// * `radius` - influence radius, amount falls off linearly to zero at this distance.
+    /// * `amount` - influence amount at segment.
+    ///
+    /// # Returns
+    /// Number of affected triangles.
+    pub fn add_along(
+        &mut self,
+        mesh: &NavMesh,
+        from: NavVec3,
+        to: NavVec3,
+        radius: Scalar,
+        amount: Scalar,
+    ) -> usize {
+        if mesh.id() != self.mesh {
+            return 0;
+        }
+        let radius = radius.max(0.0);
+        let extent = NavVec3::new(radius, radius, radius);
+        let mut count = 0;
+        for spatial in mesh
+            .spatial_index()
+            .lookup_in_rectangle(&BoundingRect::from_corners(
+                &(from.min(to) - extent),
+                &(from.max(to) + extent),
+            ))
+        {
+            let (a, b) = closest_points_segment_triangle(from, to, spatial.a, spatial.b, spatial.c);
+            let distance = a.distance(b);
+            if distance > radius + ZERO_TRESHOLD {
+                continue;
+            }
+            let factor = if radius > ZERO_TRESHOLD {
+                (1.0 - distance / radius).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            if self.add(spatial.index, amount * factor).is_some() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Fade values, for example every frame with factor depending on time passed.
+    ///
+    /// # Arguments
+    /// * `factor` - factor (in range from 0 to 1) that values get multiplied by.
+    pub fn decay(&mut self, factor: Scalar) {
+        let factor = factor.clamp(0.0, 1.0);
+        for value in &mut self.values {
+            *value *= factor;
+        }
+    }
+
+    /// Spread values over neighbor triangles.
+    ///
+    /// # Arguments
+    /// * `factor` - factor (in range from 0 to 1) of blending values with average of their
+    ///   neighbor triangles values.
+    pub fn blur(&mut self, factor: Scalar) {
+        let factor = factor.clamp(0.0, 1.0);
+        self.values = self
+            .values
+            .iter()
+            .zip(self.neighbors.iter())
+            .map(|(value, neighbors)| {
+                if neighbors.is_empty() {
+                    return *value;
+                }
+                let average = neighbors
+                    .iter()
+                    .filter_map(|index| self.values.get(*index))
+                    .sum::<Scalar>()
+                    / neighbors.len() as Scalar;
+                *value + (average - *value) * factor
+            })
+            .collect();
+    }
+}
+
+/// Query filter turning influence map into dynamic path costs, so paths avoid triangles with high
+/// influence and skip ones above limit.
+///
+/// Cost of move is its length multiplied by `1 + weight * value`, where value is influence of
+/// triangle move goes through.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (0.0, 1.0, 0.0).into(), // 2
+///     (1.0, 1.0, 0.0).into(), // 3
+/// ];
+/// let triangles = vec![(0, 1, 3).into(), (3, 2, 0).into()];
+///
+/// let mesh = NavMesh::new(vertices, triangles).unwrap();
+/// let mut map = NavInfluenceMap::new(&mesh);
+/// map.set_value(1, 2.0);
+/// let filter = NavInfluenceFilter::new(&mesh, &map).unwrap();
+/// let (_, safe) = mesh.find_path_triangles(0, 1).unwrap();
+/// let (_, dangerous) = mesh.find_path_triangles_with_filter(0, 1, &filter).unwrap();
+/// assert!(dangerous > safe);
+/// let filter = filter.with_max_value(1.0);
+/// assert!(mesh.find_path_triangles_with_filter(0, 1, &filter).is_none());
+/// ```
+#[derive(Clone, Copy)]
+pub struct NavInfluenceFilter<'a> {
+    mesh: &'a NavMesh,
+    map: &'a NavInfluenceMap,
+    weight: Scalar,
+    max_value: Scalar,
+}
+
+impl<'a> NavInfluenceFilter<'a> {
+    /// Create filter with weight of 1 and no values limit.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `map` - influence map.
+    ///
+    /// # Returns
+    /// `Some` with filter or `None` if influence map was not made for this nav mesh.
+    pub fn new(mesh: &'a NavMesh, map: &'a NavInfluenceMap) -> Option<Self> {
+        if mesh.id() != map.mesh() {
+            return None;
+        }
+        Some(Self {
+            mesh,
+            map,
+            weight: 1.0,
+            max_value: SCALAR_MAX,
+        })
+    }
+
+    /// Set how much influence adds to moves cost.
+    pub fn with_weight(mut self, weight: Scalar) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Set influence above which moves are not allowed.
+    pub fn with_max_value(mut self, max_value: Scalar) -> Self {
+        self.max_value = max_value;
+        self
+    }
+
+    #[inline]
+    pub fn weight(&self) -> Scalar {
+        self.weight
+    }
+
+    #[inline]
+    pub fn max_value(&self) -> Scalar {
+        self.max_value
+    }
+}
+
+impl NavQueryFilter for NavInfluenceFilter<'_> {
+    fn cost(&self, from: NavVec3, to: NavVec3, _: u8) -> Option<Scalar> {
+        // moves go from triangle center to its edge, so their middle lies on that triangle.
+        let value = self.map.sample(self.mesh, (from + to) * 0.5).unwrap_or(0.0);
+        if value > self.max_value {
+            return None;
+        }
+        Some(from.distance(to) * (1.0 + self.weight * value).max(0.0))
+    }
+}
@@ -1,15 +1,14 @@
-use crate::Scalar;
+use crate::{Scalar, SCALAR_MAX};
 use petgraph::{
     algo::{astar, tarjan_scc},
     graph::NodeIndex,
     visit::EdgeRef,
     Directed, Graph,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-#[cfg(not(feature = "scalar64"))]
-use std::f32::MAX as SCALAR_MAX;
-#[cfg(feature = "scalar64")]
-use std::f64::MAX as SCALAR_MAX;
 use std::{
     collections::{HashMap, HashSet},
     hash::Hash,
@@ -32,36 +31,51 @@ macro_rules! iter {
 /// Nav islands identifier.
 pub type NavIslandsID = ID<NavIslands<(), ()>>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavIslandPortal<Island, Portal>
 where
     Island: std::fmt::Debug + Clone + Eq + Hash + Send + Sync,
     Portal: std::fmt::Debug + Clone + Eq + Hash + Send + Sync,
 {
-    #[serde(bound(deserialize = "Island: Serialize + DeserializeOwned"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(deserialize = "Island: Serialize + DeserializeOwned"))
+    )]
     pub island: Island,
-    #[serde(bound(deserialize = "Portal: Serialize + DeserializeOwned"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(deserialize = "Portal: Serialize + DeserializeOwned"))
+    )]
     pub portal: Option<Portal>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavIslandsConnection<Island, Portal>
 where
     Island: std::fmt::Debug + Clone + Eq + Hash + Send + Sync,
     Portal: std::fmt::Debug + Clone + Eq + Hash + Send + Sync,
 {
-    #[serde(bound(
-        deserialize = "Island: Serialize + DeserializeOwned, Portal: Serialize + DeserializeOwned"
-    ))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(
+            deserialize = "Island: Serialize + DeserializeOwned, Portal: Serialize + DeserializeOwned"
+        ))
+    )]
     pub from: NavIslandPortal<Island, Portal>,
-    #[serde(bound(
-        deserialize = "Island: Serialize + DeserializeOwned, Portal: Serialize + DeserializeOwned"
-    ))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(
+            deserialize = "Island: Serialize + DeserializeOwned, Portal: Serialize + DeserializeOwned"
+        ))
+    )]
     pub to: NavIslandPortal<Island, Portal>,
     pub distance: Scalar,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavIslands<Island, Portal>
 where
     Island: std::fmt::Debug + Clone + Eq + Hash + Send + Sync,
@@ -69,9 +83,12 @@ where
 {
     id: NavIslandsID,
     costs: Vec<Scalar>,
-    #[serde(bound(
-        deserialize = "Island: Serialize + DeserializeOwned, Portal: Serialize + DeserializeOwned"
-    ))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(
+            deserialize = "Island: Serialize + DeserializeOwned, Portal: Serialize + DeserializeOwned"
+        ))
+    )]
     portals: Vec<NavIslandPortal<Island, Portal>>,
     graph: Graph<(), Scalar, Directed>,
     nodes: Vec<NodeIndex>,
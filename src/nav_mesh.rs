@@ -1,19 +1,27 @@
-use crate::{Error, NavConnection, NavResult, NavVec3, Scalar, ZERO_TRESHOLD};
-use petgraph::{
-    algo::{astar, tarjan_scc},
-    graph::NodeIndex,
-    visit::EdgeRef,
-    Graph, Undirected,
+use crate::{
+    nav_binary::{checksum, BinaryReader, BinaryWriter},
+    nav_funnel::{center_portal, funnel, funnel_corners, orient_portal, segment_crosses_portal},
+    nav_geom::{closest_points_on_segments, closest_points_segment_triangle},
+    nav_mesh_csg::WELD_TRESHOLD,
+    nav_open_node::OpenNode,
+    nav_path_cache::{next_version, NavPathCache},
+    nav_path_watch::NavPathWatcher,
+    nav_profile::{NavProfileData, NavProfileID},
+    nav_stats::{is_tracing, trace, NavTimer},
+    Error, NavAnnotation, NavAnnotationID, NavConnection, NavGraphSearch, NavObstacle,
+    NavObstacleID, NavPathCorner, NavResult, NavStats, NavUpAxis, NavVec3, Scalar, SCALAR_MAX,
+    ZERO_TRESHOLD,
 };
+use petgraph::{algo::astar, graph::NodeIndex, visit::EdgeRef, Graph, Undirected};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use spade::{rtree::RTree, BoundingRect, SpatialObject};
-use std::collections::HashMap;
-#[cfg(not(feature = "scalar64"))]
-use std::f32::MAX as SCALAR_MAX;
-#[cfg(feature = "scalar64")]
-use std::f64::MAX as SCALAR_MAX;
+use std::{
+    borrow::Cow,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
 use typid::ID;
 
 #[cfg(feature = "parallel")]
@@ -46,7 +54,8 @@ pub type NavMeshID = ID<NavMesh>;
 
 /// Nav mesh triangle description - lists used vertices indices.
 #[repr(C)]
-#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavTriangle {
     pub first: u32,
     pub second: u32,
@@ -75,7 +84,8 @@ impl From<[u32; 3]> for NavTriangle {
 
 /// Nav mesh area descriptor. Nav mesh area holds information about specific nav mesh triangle.
 #[repr(C)]
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavArea {
     /// Triangle index.
     pub triangle: u32,
@@ -90,9 +100,32 @@ pub struct NavArea {
     pub radius: Scalar,
     /// Squared version of `radius`.
     pub radius_sqr: Scalar,
+    /// Area type (e.g. ground, water, road) used to look up traverse cost in `NavAreaFilter`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub area_type: u8,
+    /// Area flags used to include or exclude area by `NavAreaFilter`.
+    #[cfg_attr(feature = "serde", serde(default = "NavArea::default_flags"))]
+    pub flags: u16,
+    /// Vertical free space above triangle (distance to ceiling), used to keep agents taller than
+    /// that out of it.
+    #[cfg_attr(feature = "serde", serde(default = "NavArea::default_clearance"))]
+    pub clearance: Scalar,
 }
 
 impl NavArea {
+    /// Flags given to every area of newly created nav mesh.
+    pub const DEFAULT_FLAGS: u16 = 1;
+
+    #[cfg(feature = "serde")]
+    fn default_flags() -> u16 {
+        Self::DEFAULT_FLAGS
+    }
+
+    #[cfg(feature = "serde")]
+    fn default_clearance() -> Scalar {
+        SCALAR_MAX
+    }
+
     /// Calculate triangle area value.
     ///
     /// # Arguments
@@ -119,7 +152,140 @@ impl NavArea {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Nav mesh areas filter that customizes path finding with per area type traverse costs and
+/// area flags masks.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavAreaFilter {
+    /// Traverse cost factor per area type. Area types without entry have cost factor of 1.
+    pub costs: HashMap<u8, Scalar>,
+    /// Area has to have at least one of these flags to be traversable.
+    pub include_flags: u16,
+    /// Area has to have none of these flags to be traversable.
+    pub exclude_flags: u16,
+    /// Height of agent, areas with smaller clearance are not traversable.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub agent_height: Scalar,
+}
+
+impl Default for NavAreaFilter {
+    fn default() -> Self {
+        Self {
+            costs: Default::default(),
+            include_flags: u16::MAX,
+            exclude_flags: 0,
+            agent_height: 0.0,
+        }
+    }
+}
+
+impl NavAreaFilter {
+    /// Set traverse cost factor of area type.
+    ///
+    /// # Arguments
+    /// * `area_type` - area type.
+    /// * `cost` - cost factor.
+    ///
+    /// # Returns
+    /// Old area type cost value.
+    pub fn set_cost(&mut self, area_type: u8, cost: Scalar) -> Scalar {
+        self.costs.insert(area_type, cost.max(0.0)).unwrap_or(1.0)
+    }
+
+    /// Traverse cost factor of area type.
+    #[inline]
+    pub fn cost(&self, area_type: u8) -> Scalar {
+        self.costs.get(&area_type).copied().unwrap_or(1.0)
+    }
+
+    /// Tells if area can be traversed.
+    #[inline]
+    pub fn passes(&self, area: &NavArea) -> bool {
+        area.flags & self.include_flags != 0
+            && area.flags & self.exclude_flags == 0
+            && area.clearance >= self.agent_height
+    }
+}
+
+/// Custom path finding cost callback, for per agent cost shaping (for example avoiding fire or
+/// preferring cover) and dynamic exclusion of nav mesh parts without rebuilding it.
+///
+/// Closures with the same signature as `cost` method implement it too.
+pub trait NavQueryFilter {
+    /// Cost of moving between two points lying on area of given type.
+    ///
+    /// # Arguments
+    /// * `from` - move start point.
+    /// * `to` - move end point.
+    /// * `area` - area type of triangle move goes through.
+    ///
+    /// # Returns
+    /// `Some` with move cost or `None` if move is not allowed.
+    fn cost(&self, from: NavVec3, to: NavVec3, area: u8) -> Option<Scalar>;
+}
+
+impl<F> NavQueryFilter for F
+where
+    F: Fn(NavVec3, NavVec3, u8) -> Option<Scalar>,
+{
+    fn cost(&self, from: NavVec3, to: NavVec3, area: u8) -> Option<Scalar> {
+        self(from, to, area)
+    }
+}
+
+/// Query filter that scales moves cost with their climb angle, so agents prefer flatter routes
+/// on terrain nav meshes.
+///
+/// Cost of a move is its length multiplied by `1 + angle * factor`, where angle (in radians) is
+/// measured between move direction and ground plane and factor is either `uphill_cost` or
+/// `downhill_cost`. Uphill moves steeper than `max_climb_angle` are not allowed.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavSlopeFilter {
+    /// Axis pointing up, should match nav mesh up axis.
+    pub up_axis: NavUpAxis,
+    /// Cost factor of climbing uphill.
+    pub uphill_cost: Scalar,
+    /// Cost factor of going downhill.
+    pub downhill_cost: Scalar,
+    /// Maximal angle (in radians) agent can climb.
+    pub max_climb_angle: Scalar,
+}
+
+impl Default for NavSlopeFilter {
+    fn default() -> Self {
+        Self {
+            up_axis: Default::default(),
+            uphill_cost: 1.0,
+            downhill_cost: 0.0,
+            max_climb_angle: 45.0_f64.to_radians() as Scalar,
+        }
+    }
+}
+
+impl NavQueryFilter for NavSlopeFilter {
+    fn cost(&self, from: NavVec3, to: NavVec3, _: u8) -> Option<Scalar> {
+        let diff = to - from;
+        let length = diff.magnitude();
+        if length <= ZERO_TRESHOLD {
+            return Some(0.0);
+        }
+        let rise = diff.dot(self.up_axis.up());
+        let angle = (rise.abs() / length).clamp(0.0, 1.0).asin();
+        if rise > 0.0 {
+            if angle > self.max_climb_angle {
+                return None;
+            }
+            Some(length * (1.0 + angle * self.uphill_cost.max(0.0)))
+        } else {
+            Some(length * (1.0 + angle * self.downhill_cost.max(0.0)))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
 pub struct NavSpatialObject {
     pub index: usize,
     pub a: NavVec3,
@@ -182,6 +348,19 @@ impl NavSpatialObject {
         }
         point.project_on_plane(self.a, self.normal)
     }
+
+    /// Barycentric coordinates (weights of `a`, `b` and `c` vertices) of point projected on
+    /// triangle plane. All of them are in 0 to 1 range only for points inside triangle.
+    pub fn barycentric(&self, point: NavVec3) -> NavVec3 {
+        let normal = self.ab.cross(-self.ca);
+        let area = normal.sqr_magnitude();
+        if area < ZERO_TRESHOLD * ZERO_TRESHOLD {
+            return NavVec3::new(1.0, 0.0, 0.0);
+        }
+        let u = self.bc.cross(point - self.b).dot(normal) / area;
+        let v = self.ca.cross(point - self.c).dot(normal) / area;
+        NavVec3::new(u, v, 1.0 - u - v)
+    }
 }
 
 impl SpatialObject for NavSpatialObject {
@@ -207,7 +386,8 @@ impl SpatialObject for NavSpatialObject {
 }
 
 /// Quality of querying a point on nav mesh.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NavQuery {
     /// Best quality, totally accurate.
     Accuracy,
@@ -215,19 +395,274 @@ pub enum NavQuery {
     Closest,
     /// Low quality, finds first triangle in range of query.
     ClosestFirst,
+    /// Finds triangle of walkable layer lying directly below query point along up axis, so
+    /// overlapping floors (multi story buildings, bridges) are told apart by query point height.
+    /// Contains step height telling how far above query point layer can still be picked. Falls
+    /// back to `Accuracy` when there is no layer below query point.
+    Layered(Scalar),
 }
 
 /// Quality of finding path.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NavPathMode {
     /// Best quality, finds shortest path.
     Accuracy,
     /// Medium quality, finds shortest path througs triangles midpoints.
     MidPoints,
+    /// Finds path that keeps away from corridor walls, for large units and vehicles that look
+    /// wrong hugging corners. Contains clearance weight from 0 (same path as `Accuracy`) to 1
+    /// (path goes through middles of corridor portals).
+    Centerline(Scalar),
+}
+
+/// Tolerances of nav mesh geometry tests, that have to match units nav mesh is authored in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavTolerance {
+    /// Distances under this treshold are treated as zero in plane and edge tests.
+    pub distance: Scalar,
+    /// Squared distances under this treshold are treated as zero in points equality tests, as
+    /// well as areas in tests of points sides of lines.
+    pub distance_sqr: Scalar,
+    /// Unitless values under this treshold are treated as zero, such as differences of unit
+    /// normals dot products from one (when telling if surfaces are coplanar) and line parameters.
+    pub angular: Scalar,
+    /// Vertices closer than this distance are welded together.
+    pub weld_distance: Scalar,
+}
+
+impl Default for NavTolerance {
+    fn default() -> Self {
+        Self {
+            distance: ZERO_TRESHOLD,
+            distance_sqr: ZERO_TRESHOLD,
+            angular: ZERO_TRESHOLD,
+            weld_distance: WELD_TRESHOLD,
+        }
+    }
+}
+
+impl NavTolerance {
+    /// Default tolerances scaled for meshes authored in different units than meters, where
+    /// squared distance tolerance scales with square of units and angular one does not scale.
+    ///
+    /// # Arguments
+    /// * `units_per_meter` - number of mesh units per meter, e.g. `100` for centimeters.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let tolerance = NavTolerance::scaled(100.0);
+    /// let default = NavTolerance::default();
+    /// assert!((tolerance.distance - default.distance * 100.0).abs() < 1.0e-9);
+    /// assert!((tolerance.distance_sqr - default.distance_sqr * 10000.0).abs() < 1.0e-9);
+    /// assert_eq!(tolerance.angular, default.angular);
+    /// ```
+    pub fn scaled(units_per_meter: Scalar) -> Self {
+        let scale = units_per_meter.abs();
+        let result = Self::default();
+        Self {
+            distance: result.distance * scale,
+            distance_sqr: result.distance_sqr * scale * scale,
+            angular: result.angular,
+            weld_distance: result.weld_distance * scale,
+        }
+    }
+}
+
+/// Path smoothing applied to path points polyline.
+#[derive(Debug, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavPathSmoothing {
+    /// Keep polyline as it is.
+    #[default]
+    None,
+    /// Catmull-Rom spline going through all path points, with given number of samples per
+    /// segment.
+    CatmullRom(usize),
+    /// Quadratic Bezier curves rounding each path corner, with given number of samples per
+    /// corner.
+    Bezier(usize),
+}
+
+/// Place where nav mesh raycast got blocked.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavRaycastHit {
+    /// Hit point on nav mesh surface.
+    pub point: NavVec3,
+    /// Index of last triangle ray has traveled through.
+    pub triangle: usize,
+    /// Edge of that triangle that blocked the ray.
+    pub edge: (NavVec3, NavVec3),
+}
+
+/// First nav mesh triangle hit by free 3D segment, see `NavMesh::raycast_3d`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavSurfaceHit {
+    /// Hit point on triangle.
+    pub point: NavVec3,
+    /// Index of hit triangle.
+    pub triangle: usize,
+    /// Barycentric coordinates of hit point (weights of triangle first, second and third
+    /// vertices).
+    pub barycentric: NavVec3,
+    /// Distance from segment start to hit point.
+    pub distance: Scalar,
+}
+
+/// Nav mesh raycast result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavRaycast {
+    /// Indices of triangles ray has traveled through, starting with one containing start point.
+    pub triangles: Vec<usize>,
+    /// `Some` with hit information if ray got blocked before reaching target point.
+    pub hit: Option<NavRaycastHit>,
+}
+
+/// Path found on nav mesh, together with information about how it goes through nav mesh.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPath {
+    /// Path points.
+    pub points: Vec<NavVec3>,
+    /// Total cost of triangles path, including areas costs.
+    pub cost: Scalar,
+    /// Geometric length of path polyline.
+    pub length: Scalar,
+    /// Indices of triangles (corridor) path goes through.
+    pub triangles: Vec<usize>,
+    /// Area type of every path segment.
+    pub segment_area_types: Vec<u8>,
+    /// Tells if path ends at point closest to target instead of target itself.
+    pub partial: bool,
+    /// Identifier of nav mesh path was found on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mesh: NavMeshID,
+    /// Version of nav mesh path was found on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mesh_version: u64,
+}
+
+impl NavPath {
+    /// Tells if path was found on given nav mesh and that nav mesh did not change since then, so
+    /// agents following path found on older or replaced (for example hot-reloaded) nav mesh know
+    /// they should find new one.
+    ///
+    /// Any nav mesh version change (areas, obstacles, gates) makes path stale, even if it does
+    /// not go through changed part.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let path = mesh
+    ///     .find_path_detailed(
+    ///         (0.25, 0.75, 0.0).into(),
+    ///         (1.75, 0.25, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .unwrap();
+    /// assert!(path.is_valid_for(&mesh));
+    /// mesh.set_area_cost(0, 2.0);
+    /// assert!(!path.is_valid_for(&mesh));
+    /// ```
+    pub fn is_valid_for(&self, mesh: &NavMesh) -> bool {
+        self.mesh == mesh.id() && self.mesh_version == mesh.version()
+    }
+}
+
+/// Triangles of nav mesh reachable from given point within movement budget.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavReachableArea {
+    /// Indices of reached triangles, ordered by cost of reaching them.
+    pub triangles: Vec<usize>,
+    /// Cost of reaching every triangle, measured the same way as triangles path cost.
+    pub costs: Vec<Scalar>,
+}
+
+impl NavReachableArea {
+    #[inline]
+    pub fn contains(&self, triangle: usize) -> bool {
+        self.triangles.contains(&triangle)
+    }
+
+    /// Get cost of reaching triangle or `None` if it is not reachable.
+    pub fn cost(&self, triangle: usize) -> Option<Scalar> {
+        let index = self.triangles.iter().position(|t| *t == triangle)?;
+        Some(self.costs[index])
+    }
+
+    /// Find boundary of reachable area, made of triangles edges that are not shared with other
+    /// reached triangles.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh this area was found on.
+    ///
+    /// # Returns
+    /// List of boundary edges points.
+    pub fn boundary(&self, mesh: &NavMesh) -> Vec<(NavVec3, NavVec3)> {
+        let reached = self.triangles.iter().copied().collect::<HashSet<_>>();
+        let mut result = vec![];
+        for triangle in &self.triangles {
+            let t = match mesh.triangles().get(*triangle) {
+                Some(t) => *t,
+                None => continue,
+            };
+            let shared = mesh
+                .triangle_neighbors(*triangle)
+                .filter(|neighbor| reached.contains(neighbor))
+                .filter_map(|neighbor| mesh.triangles_portal(*triangle, neighbor))
+                .collect::<Vec<_>>();
+            let vertices = mesh.vertices();
+            for (a, b) in [(t.first, t.second), (t.second, t.third), (t.third, t.first)] {
+                let a = vertices[a as usize];
+                let b = vertices[b as usize];
+                let is_shared = shared.iter().any(|(pa, pb)| {
+                    (pa.same_as(a) && pb.same_as(b)) || (pa.same_as(b) && pb.same_as(a))
+                });
+                if !is_shared {
+                    result.push((a, b));
+                }
+            }
+        }
+        result
+    }
 }
 
+const BINARY_MAGIC: &[u8; 4] = b"NAVM";
+const BINARY_VERSION: u16 = 6;
+const RANDOM_POINT_ATTEMPTS: usize = 16;
+const LOCATE_MAX_STEPS: usize = 64;
+
 /// Nav mesh object used to find shortest path between two points.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavMesh {
     id: NavMeshID,
     vertices: Vec<NavVec3>,
@@ -243,6 +678,39 @@ pub struct NavMesh {
     // {triangle index: [(from, to)]}
     hard_edges: HashMap<usize, Vec<(NavVec3, NavVec3)>>,
     origin: NavVec3,
+    #[cfg_attr(feature = "serde", serde(default))]
+    up_axis: NavUpAxis,
+    // {obstacle id: (obstacle, [blocked triangle index])}
+    #[cfg_attr(feature = "serde", serde(default))]
+    obstacles: HashMap<NavObstacleID, (NavObstacle, Vec<usize>)>,
+    // {gate name: ([triangle index], is open)}
+    #[cfg_attr(feature = "serde", serde(default))]
+    gates: HashMap<String, (Vec<usize>, bool)>,
+    // number of obstacles and closed gates blocking given triangle.
+    #[cfg_attr(feature = "serde", serde(default))]
+    blocked: Vec<usize>,
+    // {profile id: profile with its precomputed data}
+    #[cfg_attr(feature = "serde", serde(default))]
+    profiles: HashMap<NavProfileID, NavProfileData>,
+    // [(landmark triangle index, path cost to every triangle)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    landmarks: Vec<(usize, Vec<Scalar>)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tolerance: NavTolerance,
+    #[cfg_attr(feature = "serde", serde(default))]
+    annotations: HashMap<NavAnnotationID, NavAnnotation>,
+    // {attribute name: [value per vertex]}
+    #[cfg_attr(feature = "serde", serde(default))]
+    vertex_attributes: HashMap<String, Vec<Scalar>>,
+    // island of every triangle, empty if not known (nav mesh serialized by older version).
+    #[cfg_attr(feature = "serde", serde(default))]
+    islands: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "next_version"))]
+    version: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    path_cache: NavPathCache,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    path_watcher: NavPathWatcher<usize>,
 }
 
 impl NavMesh {
@@ -254,7 +722,7 @@ impl NavMesh {
     ///
     /// # Returns
     /// `Ok` with nav mesh object or `Err` with `Error::TriangleVerticeIndexOutOfBounds` if input
-    /// data is invalid.
+    /// data is invalid or `Error::EmptyGeometry` if there are no vertices or triangles.
     ///
     /// # Example
     /// ```
@@ -278,6 +746,9 @@ impl NavMesh {
     /// let mesh = NavMesh::new(vertices, triangles).unwrap();
     /// ```
     pub fn new(vertices: Vec<NavVec3>, triangles: Vec<NavTriangle>) -> NavResult<Self> {
+        if vertices.is_empty() || triangles.is_empty() {
+            return Err(Error::EmptyGeometry(vertices.len(), triangles.len()));
+        }
         let origin = vertices
             .iter()
             .cloned()
@@ -323,6 +794,9 @@ impl NavMesh {
                     center,
                     radius,
                     radius_sqr: radius * radius,
+                    area_type: 0,
+                    flags: NavArea::DEFAULT_FLAGS,
+                    clearance: SCALAR_MAX,
                 })
             })
             .collect::<NavResult<Vec<_>>>()?;
@@ -432,6 +906,8 @@ impl NavMesh {
             })
             .collect::<HashMap<_, _>>();
 
+        let islands = find_islands(&graph, &nodes, &nodes_map);
+
         Ok(Self {
             id: ID::new(),
             vertices,
@@ -441,10 +917,23 @@ impl NavMesh {
             graph,
             nodes,
             nodes_map,
+            islands,
             rtree,
             spatials,
             hard_edges,
             origin,
+            up_axis: Default::default(),
+            obstacles: Default::default(),
+            gates: Default::default(),
+            blocked: Default::default(),
+            profiles: Default::default(),
+            landmarks: Default::default(),
+            tolerance: Default::default(),
+            version: next_version(),
+            path_cache: Default::default(),
+            path_watcher: Default::default(),
+            annotations: Default::default(),
+            vertex_attributes: Default::default(),
         })
     }
 
@@ -470,7 +959,9 @@ impl NavMesh {
                 *v + n.normalize() * value
             })
             .collect::<Vec<_>>();
-        Self::new(shifted, self.triangles.clone())
+        let mut result = Self::new(shifted, self.triangles.clone())?;
+        result.up_axis = self.up_axis;
+        Ok(result)
     }
 
     pub fn scale(&self, value: NavVec3, origin: Option<NavVec3>) -> NavResult<Self> {
@@ -478,7 +969,354 @@ impl NavMesh {
         let vertices = iter!(self.vertices)
             .map(|v| (*v - origin) * value + origin)
             .collect::<Vec<_>>();
-        Self::new(vertices, self.triangles.clone())
+        let mut result = Self::new(vertices, self.triangles.clone())?;
+        result.up_axis = self.up_axis;
+        Ok(result)
+    }
+
+    /// Serialize nav mesh into compact versioned binary format, that stores precomputed
+    /// triangles connections and spatial index so loading does not have to build them again.
+    ///
+    /// Spatial index is stored only with `serde` feature enabled, otherwise it gets rebuilt on load.
+    ///
+    /// Obstacles are not stored, since they are meant to be added at runtime.
+    ///
+    /// # Returns
+    /// Bytes of serialized nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let bytes = mesh.to_bytes();
+    /// let loaded = NavMesh::from_bytes(&bytes).unwrap();
+    /// assert_eq!(loaded.find_path_triangles(1, 2).unwrap().0, vec![1, 0, 3, 2]);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let scalar_size = std::mem::size_of::<Scalar>();
+        let mut payload = BinaryWriter::with_capacity(
+            self.vertices.len() * scalar_size * 3
+                + self.triangles.len() * (12 + scalar_size * 2 + 3)
+                + self.connections.len() * (16 + scalar_size),
+        );
+        payload.write_vec3(self.origin);
+        payload.write_u32(self.vertices.len() as u32);
+        for vertice in &self.vertices {
+            payload.write_vec3(*vertice);
+        }
+        payload.write_u32(self.triangles.len() as u32);
+        for (triangle, area) in self.triangles.iter().zip(self.areas.iter()) {
+            payload.write_u32(triangle.first);
+            payload.write_u32(triangle.second);
+            payload.write_u32(triangle.third);
+            payload.write_scalar(area.cost);
+            payload.write_u8(area.area_type);
+            payload.write_u16(area.flags);
+            payload.write_scalar(area.clearance);
+        }
+        payload.write_u32(self.connections.len() as u32);
+        for (triangles, (weight, vertices)) in &self.connections {
+            payload.write_u32(triangles.0);
+            payload.write_u32(triangles.1);
+            payload.write_u32(vertices.0);
+            payload.write_u32(vertices.1);
+            payload.write_scalar(*weight);
+        }
+        let mut hard_edges = self.hard_edges.iter().collect::<Vec<_>>();
+        hard_edges.sort_by_key(|(index, _)| **index);
+        payload.write_u32(hard_edges.len() as u32);
+        for (index, edges) in hard_edges {
+            payload.write_u32(*index as u32);
+            payload.write_u32(edges.len() as u32);
+            for (a, b) in edges {
+                payload.write_vec3(*a);
+                payload.write_vec3(*b);
+            }
+        }
+        payload.write_u32(self.landmarks.len() as u32);
+        for (index, costs) in &self.landmarks {
+            payload.write_u32(*index as u32);
+            for cost in costs {
+                payload.write_scalar(*cost);
+            }
+        }
+        payload.write_scalar(self.tolerance.distance);
+        payload.write_scalar(self.tolerance.distance_sqr);
+        payload.write_scalar(self.tolerance.angular);
+        payload.write_scalar(self.tolerance.weld_distance);
+        let spatial_index = encode_spatial_index(&self.rtree);
+        payload.write_u64(spatial_index.len() as u64);
+        payload.write_bytes(&spatial_index);
+        let payload = payload.into_inner();
+
+        let mut result = BinaryWriter::with_capacity(payload.len() + 24);
+        result.write_bytes(BINARY_MAGIC);
+        result.write_u16(BINARY_VERSION);
+        result.write_u8(scalar_size as u8);
+        result.write_u8(match self.up_axis {
+            NavUpAxis::Z => 0,
+            NavUpAxis::Y => 1,
+        });
+        result.write_u64(payload.len() as u64);
+        result.write_u64(checksum(&payload));
+        result.write_bytes(&payload);
+        result.into_inner()
+    }
+
+    /// Deserialize nav mesh from binary format produced by `NavMesh::to_bytes`.
+    ///
+    /// # Arguments
+    /// * `bytes` - serialized nav mesh bytes.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh or `Err` with `Error::CouldNotDeserializeNavMesh` if data is invalid,
+    /// corrupted or has unsupported version.
+    pub fn from_bytes(bytes: &[u8]) -> NavResult<Self> {
+        let invalid = |message: &str| Error::CouldNotDeserializeNavMesh(message.to_owned());
+        let mut header = BinaryReader::new(bytes);
+        if header.read_bytes(4)? != BINARY_MAGIC {
+            return Err(invalid("Data is not a binary nav mesh"));
+        }
+        let version = header.read_u16()?;
+        // version 1 did not store areas clearance, version 2 did not store landmarks, version 3
+        // did not store tolerance, version 4 did not store spatial index, version 5 stored single
+        // tolerance for distances and angles.
+        if version == 0 || version > BINARY_VERSION {
+            return Err(Error::CouldNotDeserializeNavMesh(format!(
+                "Unsupported binary nav mesh version: {}",
+                version
+            )));
+        }
+        let scalar_size = header.read_u8()? as usize;
+        let up_axis = match header.read_u8()? {
+            0 => NavUpAxis::Z,
+            1 => NavUpAxis::Y,
+            axis => {
+                return Err(Error::CouldNotDeserializeNavMesh(format!(
+                    "Unsupported up axis: {}",
+                    axis
+                )))
+            }
+        };
+        let size = header.read_u64()? as usize;
+        let sum = header.read_u64()?;
+        let payload = header.read_bytes(size)?;
+        if !header.is_empty() {
+            return Err(invalid("Unexpected data after nav mesh"));
+        }
+        if checksum(payload) != sum {
+            return Err(invalid("Checksum mismatch"));
+        }
+
+        let mut reader = BinaryReader::new(payload);
+        reader.set_scalar_size(scalar_size)?;
+        let scalar_size = reader.scalar_size();
+        let origin = reader.read_vec3()?;
+        let count = reader.read_count(scalar_size * 3)?;
+        let vertices = (0..count)
+            .map(|_| reader.read_vec3())
+            .collect::<NavResult<Vec<_>>>()?;
+        let area_size = if version == 1 {
+            12 + scalar_size + 3
+        } else {
+            12 + scalar_size * 2 + 3
+        };
+        let count = reader.read_count(area_size)?;
+        let mut triangles = Vec::with_capacity(count);
+        let mut areas = Vec::with_capacity(count);
+        for i in 0..count {
+            let triangle = NavTriangle {
+                first: reader.read_u32()?,
+                second: reader.read_u32()?,
+                third: reader.read_u32()?,
+            };
+            for (local, index) in [triangle.first, triangle.second, triangle.third]
+                .iter()
+                .enumerate()
+            {
+                if *index as usize >= vertices.len() {
+                    return Err(Error::TriangleVerticeIndexOutOfBounds(
+                        i as u32,
+                        local as u8,
+                        *index,
+                    ));
+                }
+            }
+            let first = vertices[triangle.first as usize];
+            let second = vertices[triangle.second as usize];
+            let third = vertices[triangle.third as usize];
+            let center = NavArea::calculate_center(first, second, third);
+            let radius = (first - center)
+                .magnitude()
+                .max((second - center).magnitude())
+                .max((third - center).magnitude());
+            areas.push(NavArea {
+                triangle: i as u32,
+                size: NavArea::calculate_area(first, second, third),
+                cost: reader.read_scalar()?,
+                center,
+                radius,
+                radius_sqr: radius * radius,
+                area_type: reader.read_u8()?,
+                flags: reader.read_u16()?,
+                clearance: if version == 1 {
+                    SCALAR_MAX
+                } else {
+                    reader.read_scalar()?
+                },
+            });
+            triangles.push(triangle);
+        }
+        let count = reader.read_count(16 + scalar_size)?;
+        let mut connections = HashMap::with_capacity(count);
+        for i in 0..count {
+            let a = reader.read_u32()?;
+            let b = reader.read_u32()?;
+            let va = reader.read_u32()?;
+            let vb = reader.read_u32()?;
+            let weight = reader.read_scalar()?;
+            if a as usize >= triangles.len() || b as usize >= triangles.len() {
+                return Err(invalid("Connection triangle index out of bounds"));
+            }
+            if va as usize >= vertices.len() {
+                return Err(Error::ConnectionVerticeIndexOutOfBounds(i as u32, 0, va));
+            }
+            if vb as usize >= vertices.len() {
+                return Err(Error::ConnectionVerticeIndexOutOfBounds(i as u32, 1, vb));
+            }
+            connections.insert(NavConnection(a, b), (weight, NavConnection(va, vb)));
+        }
+        let count = reader.read_count(8)?;
+        let mut hard_edges = HashMap::with_capacity(count);
+        for _ in 0..count {
+            let index = reader.read_u32()? as usize;
+            if index >= triangles.len() {
+                return Err(invalid("Hard edge triangle index out of bounds"));
+            }
+            let edges_count = reader.read_count(scalar_size * 6)?;
+            let edges = (0..edges_count)
+                .map(|_| Ok((reader.read_vec3()?, reader.read_vec3()?)))
+                .collect::<NavResult<Vec<_>>>()?;
+            hard_edges.insert(index, edges);
+        }
+        let count = if version < 3 {
+            0
+        } else {
+            reader.read_count(4 + scalar_size * triangles.len())?
+        };
+        let mut landmarks = Vec::with_capacity(count);
+        for _ in 0..count {
+            let index = reader.read_u32()? as usize;
+            if index >= triangles.len() {
+                return Err(invalid("Landmark triangle index out of bounds"));
+            }
+            let costs = (0..triangles.len())
+                .map(|_| reader.read_scalar())
+                .collect::<NavResult<Vec<_>>>()?;
+            landmarks.push((index, costs));
+        }
+        let tolerance = if version < 4 {
+            NavTolerance::default()
+        } else if version < 6 {
+            let epsilon = reader.read_scalar()?;
+            NavTolerance {
+                distance: epsilon,
+                distance_sqr: epsilon,
+                angular: epsilon,
+                weld_distance: reader.read_scalar()?,
+            }
+        } else {
+            NavTolerance {
+                distance: reader.read_scalar()?,
+                distance_sqr: reader.read_scalar()?,
+                angular: reader.read_scalar()?,
+                weld_distance: reader.read_scalar()?,
+            }
+        };
+        let spatial_index = if version < 5 {
+            &[]
+        } else {
+            let size = reader.read_u64()? as usize;
+            reader.read_bytes(size)?
+        };
+        if !reader.is_empty() {
+            return Err(invalid("Unexpected data after nav mesh"));
+        }
+
+        let mut graph = Graph::<(), Scalar, Undirected>::new_undirected();
+        let nodes = (0..triangles.len())
+            .map(|_| graph.add_node(()))
+            .collect::<Vec<_>>();
+        graph.extend_with_edges(
+            connections
+                .iter()
+                .map(|(conn, (w, _))| (nodes[conn.0 as usize], nodes[conn.1 as usize], *w)),
+        );
+        let nodes_map = nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+        let spatials = triangles
+            .iter()
+            .enumerate()
+            .map(|(index, triangle)| {
+                NavSpatialObject::new(
+                    index,
+                    vertices[triangle.first as usize],
+                    vertices[triangle.second as usize],
+                    vertices[triangle.third as usize],
+                )
+            })
+            .collect::<Vec<_>>();
+        let rtree = decode_spatial_index(spatial_index, &spatials).unwrap_or_else(|| {
+            let mut rtree = RTree::new();
+            for spatial in &spatials {
+                rtree.insert(spatial.clone());
+            }
+            rtree
+        });
+        let islands = find_islands(&graph, &nodes, &nodes_map);
+
+        Ok(Self {
+            id: ID::new(),
+            vertices,
+            triangles,
+            areas,
+            connections,
+            graph,
+            nodes,
+            nodes_map,
+            islands,
+            rtree,
+            spatials,
+            hard_edges,
+            origin,
+            up_axis,
+            obstacles: Default::default(),
+            gates: Default::default(),
+            blocked: Default::default(),
+            profiles: Default::default(),
+            landmarks,
+            tolerance,
+            version: next_version(),
+            path_cache: Default::default(),
+            path_watcher: Default::default(),
+            annotations: Default::default(),
+            vertex_attributes: Default::default(),
+        })
     }
 
     /// Nav mesh identifier.
@@ -525,20 +1363,423 @@ impl NavMesh {
         let old = area.cost;
         let cost = cost.max(0.0);
         area.cost = cost;
+        if cost < old {
+            // cheaper area makes landmarks overestimate path costs.
+            self.landmarks.clear();
+        }
+        self.version = next_version();
+        self.path_watcher.notify(&[index]);
         old
     }
 
-    /// Find closest point on nav mesh.
+    /// Set area type by triangle index.
     ///
     /// # Arguments
-    /// * `point` - query point.
-    /// * `query` - query quality.
+    /// * `index` - triangle index.
+    /// * `area_type` - area type.
     ///
     /// # Returns
-    /// `Some` with point on nav mesh if found or `None` otherwise.
-    pub fn closest_point(&self, point: NavVec3, query: NavQuery) -> Option<NavVec3> {
-        self.find_closest_triangle(point, query)
-            .map(|triangle| self.spatials[triangle].closest_point(point))
+    /// Old area type value.
+    #[inline]
+    pub fn set_area_type(&mut self, index: usize, area_type: u8) -> u8 {
+        self.version = next_version();
+        self.path_watcher.notify(&[index]);
+        std::mem::replace(&mut self.areas[index].area_type, area_type)
+    }
+
+    /// Set area flags by triangle index.
+    ///
+    /// # Arguments
+    /// * `index` - triangle index.
+    /// * `flags` - area flags.
+    ///
+    /// # Returns
+    /// Old area flags value.
+    #[inline]
+    pub fn set_area_flags(&mut self, index: usize, flags: u16) -> u16 {
+        self.version = next_version();
+        self.path_watcher.notify(&[index]);
+        let old = std::mem::replace(&mut self.areas[index].flags, flags);
+        self.refresh_profiles_triangle(index);
+        old
+    }
+
+    /// Set area clearance by triangle index.
+    ///
+    /// # Arguments
+    /// * `index` - triangle index.
+    /// * `clearance` - vertical free space above triangle.
+    ///
+    /// # Returns
+    /// Old area clearance value.
+    #[inline]
+    pub fn set_area_clearance(&mut self, index: usize, clearance: Scalar) -> Scalar {
+        self.version = next_version();
+        self.path_watcher.notify(&[index]);
+        let old = std::mem::replace(&mut self.areas[index].clearance, clearance.max(0.0));
+        self.refresh_profiles_triangle(index);
+        old
+    }
+
+    /// Add obstacle that blocks all triangles it overlaps until it gets removed.
+    ///
+    /// # Arguments
+    /// * `obstacle` - obstacle volume.
+    ///
+    /// # Returns
+    /// Obstacle identifier used to remove it later.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let id = mesh.add_obstacle(NavObstacle::aabb(
+    ///     (1.1, -1.0, -1.0).into(),
+    ///     (3.0, 2.0, 1.0).into(),
+    /// ));
+    /// assert!(mesh.find_path_triangles(1, 2).is_none());
+    /// mesh.remove_obstacle(id);
+    /// assert!(mesh.find_path_triangles(1, 2).is_some());
+    /// ```
+    pub fn add_obstacle(&mut self, obstacle: NavObstacle) -> NavObstacleID {
+        let id = NavObstacleID::new();
+        self.insert_obstacle(id, obstacle);
+        id
+    }
+
+    /// Add obstacle with given identifier, replacing obstacle that had it.
+    pub(crate) fn insert_obstacle(&mut self, id: NavObstacleID, obstacle: NavObstacle) {
+        self.remove_obstacle(id);
+        let triangles = match obstacle.bounds() {
+            Some((min, max)) => self
+                .rtree
+                .lookup_in_rectangle(&BoundingRect::from_corners(&min, &max))
+                .into_iter()
+                .map(|s| s.index)
+                .collect::<Vec<_>>(),
+            None => (0..self.spatials.len()).collect::<Vec<_>>(),
+        };
+        let mut triangles = triangles
+            .into_iter()
+            .filter(|index| {
+                let s = &self.spatials[*index];
+                obstacle.intersects_triangle_within(s.a, s.b, s.c, self.tolerance)
+            })
+            .collect::<Vec<_>>();
+        triangles.sort_unstable();
+        if self.blocked.len() != self.triangles.len() {
+            self.blocked = vec![0; self.triangles.len()];
+        }
+        for index in &triangles {
+            self.blocked[*index] += 1;
+        }
+        self.path_watcher.notify(&triangles);
+        self.obstacles.insert(id, (obstacle, triangles));
+        self.version = next_version();
+    }
+
+    /// Remove obstacle and restore connectivity of triangles it was blocking.
+    ///
+    /// # Arguments
+    /// * `id` - obstacle identifier.
+    ///
+    /// # Returns
+    /// `Some` with removed obstacle or `None` if there was no obstacle with given identifier.
+    pub fn remove_obstacle(&mut self, id: NavObstacleID) -> Option<NavObstacle> {
+        let (obstacle, triangles) = self.obstacles.remove(&id)?;
+        for index in &triangles {
+            if let Some(count) = self.blocked.get_mut(*index) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        self.path_watcher.notify(&triangles);
+        self.version = next_version();
+        Some(obstacle)
+    }
+
+    /// Add named gate (for example door) made of triangles that can be opened and closed at
+    /// runtime. Closed gate blocks its triangles the same way obstacles do, but toggling it does
+    /// not need to search for overlapped triangles again.
+    ///
+    /// Adding gate with already used name replaces that gate.
+    ///
+    /// # Arguments
+    /// * `name` - gate name.
+    /// * `triangles` - list of triangles indices gate is made of.
+    /// * `open` - initial gate state.
+    ///
+    /// # Returns
+    /// `Ok` or `Err` with `Error::TriangleIndexOutOfBounds` if any triangle does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.add_gate("door_07", vec![3], true).unwrap();
+    /// assert!(mesh.find_path_triangles(1, 2).is_some());
+    /// mesh.set_gate("door_07", false);
+    /// assert!(mesh.find_path_triangles(1, 2).is_none());
+    /// mesh.set_gate("door_07", true);
+    /// assert!(mesh.find_path_triangles(1, 2).is_some());
+    /// ```
+    pub fn add_gate(
+        &mut self,
+        name: impl Into<String>,
+        mut triangles: Vec<usize>,
+        open: bool,
+    ) -> NavResult<()> {
+        if let Some(index) = triangles
+            .iter()
+            .copied()
+            .find(|index| *index >= self.triangles.len())
+        {
+            return Err(Error::TriangleIndexOutOfBounds(index, self.triangles.len()));
+        }
+        triangles.sort_unstable();
+        triangles.dedup();
+        let name = name.into();
+        self.remove_gate(&name);
+        self.gates.insert(name.clone(), (triangles, true));
+        if !open {
+            self.set_gate(&name, false);
+        }
+        Ok(())
+    }
+
+    /// Remove gate, restoring connectivity of its triangles if it was closed.
+    ///
+    /// # Arguments
+    /// * `name` - gate name.
+    ///
+    /// # Returns
+    /// `Some` with gate triangles or `None` if there was no gate with given name.
+    pub fn remove_gate(&mut self, name: &str) -> Option<Vec<usize>> {
+        self.set_gate(name, true)?;
+        self.gates.remove(name).map(|(triangles, _)| triangles)
+    }
+
+    /// Open or close gate.
+    ///
+    /// Closing gate keeps cached paths that do not go through it, opening gate drops all cached
+    /// paths since they might get shorter. Landmarks ignore gates the same way they ignore
+    /// obstacles, so they stay valid.
+    ///
+    /// # Arguments
+    /// * `name` - gate name.
+    /// * `open` - new gate state.
+    ///
+    /// # Returns
+    /// `Some` with old gate state or `None` if there is no gate with given name.
+    pub fn set_gate(&mut self, name: &str, open: bool) -> Option<bool> {
+        let (triangles, state) = self.gates.get_mut(name)?;
+        let old = std::mem::replace(state, open);
+        if old == open {
+            return Some(old);
+        }
+        if self.blocked.len() != self.triangles.len() {
+            self.blocked = vec![0; self.triangles.len()];
+        }
+        for index in triangles.iter() {
+            if open {
+                self.blocked[*index] = self.blocked[*index].saturating_sub(1);
+            } else {
+                self.blocked[*index] += 1;
+            }
+        }
+        self.path_watcher.notify(triangles.iter());
+        let version = self.version;
+        self.version = next_version();
+        self.path_cache.retain(version, self.version, |value| {
+            !open
+                && !matches!(value, Some((path, _)) if path
+                    .iter()
+                    .any(|index| triangles.binary_search(index).is_ok()))
+        });
+        Some(old)
+    }
+
+    /// Tells if gate is open.
+    ///
+    /// # Returns
+    /// `Some` with gate state or `None` if there is no gate with given name.
+    #[inline]
+    pub fn is_gate_open(&self, name: &str) -> Option<bool> {
+        self.gates.get(name).map(|(_, open)| *open)
+    }
+
+    /// List of triangles indices given gate is made of.
+    #[inline]
+    pub fn gate_triangles(&self, name: &str) -> Option<&[usize]> {
+        self.gates
+            .get(name)
+            .map(|(triangles, _)| triangles.as_slice())
+    }
+
+    /// Iterator over all gates names.
+    #[inline]
+    pub fn gates(&self) -> impl Iterator<Item = &str> + '_ {
+        self.gates.keys().map(|name| name.as_str())
+    }
+
+    /// Axis pointing up, used by queries that need to know where ground plane is.
+    #[inline]
+    pub fn up_axis(&self) -> NavUpAxis {
+        self.up_axis
+    }
+
+    #[inline]
+    pub fn set_up_axis(&mut self, up_axis: NavUpAxis) {
+        self.up_axis = up_axis;
+        self.refresh_profiles();
+    }
+
+    /// Tolerances used by geometry tests of this nav mesh.
+    #[inline]
+    pub fn tolerance(&self) -> NavTolerance {
+        self.tolerance
+    }
+
+    #[inline]
+    pub fn set_tolerance(&mut self, tolerance: NavTolerance) {
+        self.tolerance = tolerance;
+    }
+
+    /// Nav mesh version, changed every time its areas, obstacles or gates change.
+    ///
+    /// Versions are unique across all nav meshes, so stored version tells if nav mesh has changed
+    /// since then.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Set how many triangles paths found by `find_path_triangles` (and `find_path`) are kept in
+    /// least recently used cache. Zero capacity disables cache (default).
+    ///
+    /// Cache is invalidated whenever nav mesh version changes.
+    ///
+    /// # Arguments
+    /// * `capacity` - maximal number of cached paths.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_path_cache_capacity(64);
+    /// assert!(mesh.find_path_triangles(1, 2).is_some());
+    /// assert_eq!(mesh.path_cache_len(), 1);
+    /// mesh.add_obstacle(NavObstacle::aabb(
+    ///     (1.1, -1.0, -1.0).into(),
+    ///     (3.0, 2.0, 1.0).into(),
+    /// ));
+    /// assert!(mesh.find_path_triangles(1, 2).is_none());
+    /// ```
+    pub fn set_path_cache_capacity(&mut self, capacity: usize) {
+        self.path_cache = NavPathCache::new(capacity);
+    }
+
+    #[inline]
+    pub fn path_cache_capacity(&self) -> usize {
+        self.path_cache.capacity()
+    }
+
+    /// Number of currently cached paths.
+    pub fn path_cache_len(&self) -> usize {
+        self.path_cache.len()
+    }
+
+    pub fn clear_path_cache(&self) {
+        self.path_cache.clear();
+    }
+
+    /// Get obstacle by its identifier.
+    #[inline]
+    pub fn obstacle(&self, id: NavObstacleID) -> Option<&NavObstacle> {
+        self.obstacles.get(&id).map(|(obstacle, _)| obstacle)
+    }
+
+    /// Iterator over all obstacles identifiers.
+    #[inline]
+    pub fn obstacles(&self) -> impl Iterator<Item = NavObstacleID> + '_ {
+        self.obstacles.keys().copied()
+    }
+
+    /// List of triangles indices blocked by given obstacle.
+    #[inline]
+    pub fn obstacle_triangles(&self, id: NavObstacleID) -> Option<&[usize]> {
+        self.obstacles
+            .get(&id)
+            .map(|(_, triangles)| triangles.as_slice())
+    }
+
+    /// Tells if triangle is blocked by any obstacle or closed gate.
+    ///
+    /// # Arguments
+    /// * `index` - triangle index.
+    #[inline]
+    pub fn is_triangle_blocked(&self, index: usize) -> bool {
+        self.blocked.get(index).is_some_and(|count| *count > 0)
+    }
+
+    /// Find closest point on nav mesh.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with point on nav mesh if found or `None` otherwise.
+    pub fn closest_point(&self, point: NavVec3, query: NavQuery) -> Option<NavVec3> {
+        self.find_closest_triangle(point, query)
+            .map(|triangle| self.spatials[triangle].closest_point(point))
     }
 
     /// Find shortest path on nav mesh between two points.
@@ -598,7 +1839,34 @@ impl NavMesh {
         query: NavQuery,
         mode: NavPathMode,
     ) -> Option<Vec<NavVec3>> {
-        self.find_path_custom(from, to, query, mode, |_, _, _| true)
+        self.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles(start, end)
+        })
+    }
+
+    /// Find shortest path on nav mesh between two points, overriding nav mesh tolerances.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `tolerance` - tolerances used instead of nav mesh ones.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    pub fn find_path_with_tolerance(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        tolerance: NavTolerance,
+    ) -> Option<Vec<NavVec3>> {
+        self.find_nav_path_within(from, to, query, mode, tolerance, |start, end| {
+            self.find_path_triangles(start, end)
+        })
+        .map(|path| path.points)
     }
 
     /// Find shortest path on nav mesh between two points, providing custom filtering function.
@@ -665,206 +1933,2381 @@ impl NavMesh {
     where
         F: FnMut(Scalar, usize, usize) -> bool,
     {
-        if from.same_as(to) {
-            return None;
-        }
-        let start = self.find_closest_triangle(from, query)?;
-        let end = self.find_closest_triangle(to, query)?;
-        let from = self.spatials[start].closest_point(from);
-        let to = self.spatials[end].closest_point(to);
-        let (triangles, _) = self.find_path_triangles_custom(start, end, filter)?;
-        if triangles.is_empty() {
-            return None;
-        } else if triangles.len() == 1 {
-            return Some(vec![from, to]);
-        }
-        match mode {
-            NavPathMode::Accuracy => Some(self.find_path_accuracy(from, to, &triangles)),
-            NavPathMode::MidPoints => Some(self.find_path_midpoints(from, to, &triangles)),
-        }
+        self.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles_custom(start, end, filter)
+        })
     }
 
-    fn find_path_accuracy(&self, from: NavVec3, to: NavVec3, triangles: &[usize]) -> Vec<NavVec3> {
-        #[derive(Debug)]
-        enum Node {
-            Point(NavVec3),
-            // (a, b, normal)
-            LevelChange(NavVec3, NavVec3, NavVec3),
-        }
-
-        // TODO: reduce allocations.
-        if triangles.len() == 2 {
-            let NavConnection(a, b) =
-                self.connections[&NavConnection(triangles[0] as u32, triangles[1] as u32)].1;
-            let a = self.vertices[a as usize];
-            let b = self.vertices[b as usize];
-            let n = self.spatials[triangles[0]].normal();
-            let m = self.spatials[triangles[1]].normal();
-            if !NavVec3::is_line_between_points(from, to, a, b, n) {
-                let da = (from - a).sqr_magnitude();
-                let db = (from - b).sqr_magnitude();
-                let point = if da < db { a } else { b };
-                return vec![from, point, to];
-            } else if n.dot(m) < 1.0 - ZERO_TRESHOLD {
-                let n = (b - a).normalize().cross(n);
-                if let Some(point) = NavVec3::raycast_line(from, to, a, b, n) {
-                    return vec![from, point, to];
-                }
-            }
-            return vec![from, to];
-        }
+    /// Find shortest path on nav mesh between two points, using areas filter.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `filter` - areas filter with area types costs and flags masks.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// const WATER: u16 = 1 << 1;
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_area_flags(3, WATER);
+    /// let filter = NavAreaFilter {
+    ///     exclude_flags: WATER,
+    ///     ..Default::default()
+    /// };
+    /// let path = mesh.find_path_filtered(
+    ///     (0.0, 1.0, 0.0).into(),
+    ///     (1.5, 0.25, 0.5).into(),
+    ///     NavQuery::Accuracy,
+    ///     NavPathMode::MidPoints,
+    ///     &filter,
+    /// );
+    /// assert!(path.is_none());
+    /// ```
+    pub fn find_path_filtered(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        filter: &NavAreaFilter,
+    ) -> Option<Vec<NavVec3>> {
+        self.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles_filtered(start, end, filter)
+        })
+    }
+
+    /// Find shortest path on nav mesh between two points, using custom cost callback.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `filter` - cost callback.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// const FIRE: u8 = 1;
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_area_type(3, FIRE);
+    /// let avoid_fire = |from: NavVec3, to: NavVec3, area: u8| {
+    ///     if area == FIRE {
+    ///         None
+    ///     } else {
+    ///         Some((to - from).magnitude())
+    ///     }
+    /// };
+    /// let from = (0.0, 1.0, 0.0).into();
+    /// let to = (1.5, 0.25, 0.0).into();
+    /// assert!(mesh
+    ///     .find_path(from, to, NavQuery::Accuracy, NavPathMode::MidPoints)
+    ///     .is_some());
+    /// assert!(mesh
+    ///     .find_path_with_filter(from, to, NavQuery::Accuracy, NavPathMode::MidPoints, &avoid_fire)
+    ///     .is_none());
+    /// ```
+    pub fn find_path_with_filter<Q>(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        filter: &Q,
+    ) -> Option<Vec<NavVec3>>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles_with_filter(start, end, filter)
+        })
+    }
+
+    /// Find shortest path on nav mesh between two points and smooth it.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `smoothing` - path smoothing.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    pub fn find_path_smoothed(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        smoothing: NavPathSmoothing,
+    ) -> Option<Vec<NavVec3>> {
+        let path = self.find_path(from, to, query, mode)?;
+        Some(self.smooth_path(&path, smoothing))
+    }
+
+    /// Smooth path points polyline, keeping smoothed path on nav mesh. Parts of curve that would
+    /// leave nav mesh fall back to original polyline.
+    ///
+    /// # Arguments
+    /// * `path` - path points.
+    /// * `smoothing` - path smoothing.
+    ///
+    /// # Returns
+    /// Smoothed path points.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (2.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 2.0, 0.0).into(), // 2
+    ///     (0.0, 2.0, 0.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let path = [
+    ///     (0.5, 0.5, 0.0).into(),
+    ///     (1.5, 0.5, 0.0).into(),
+    ///     (1.5, 1.5, 0.0).into(),
+    /// ];
+    /// let smoothed = mesh.smooth_path(&path, NavPathSmoothing::Bezier(4));
+    /// assert!(smoothed.len() > path.len());
+    /// assert!(smoothed[0].same_as(path[0]));
+    /// assert!(smoothed[smoothed.len() - 1].same_as(path[2]));
+    /// ```
+    pub fn smooth_path(&self, path: &[NavVec3], smoothing: NavPathSmoothing) -> Vec<NavVec3> {
+        if path.len() < 3 {
+            return path.to_vec();
+        }
+        let mut result = Vec::with_capacity(path.len() * 4);
+        result.push(path[0]);
+        match smoothing {
+            NavPathSmoothing::None => return path.to_vec(),
+            NavPathSmoothing::CatmullRom(samples) => {
+                let samples = samples.max(1);
+                for i in 0..(path.len() - 1) {
+                    let p0 = path[i.saturating_sub(1)];
+                    let p1 = path[i];
+                    let p2 = path[i + 1];
+                    let p3 = path[(i + 2).min(path.len() - 1)];
+                    let curve = (1..samples)
+                        .map(|s| {
+                            let t = s as Scalar / samples as Scalar;
+                            let t2 = t * t;
+                            let t3 = t2 * t;
+                            (p1 * 2.0
+                                + (p2 - p0) * t
+                                + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+                                + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+                                * 0.5
+                        })
+                        .chain(std::iter::once(p2))
+                        .collect::<Vec<_>>();
+                    self.extend_smoothed(&mut result, curve, &[p2]);
+                }
+            }
+            NavPathSmoothing::Bezier(samples) => {
+                let samples = samples.max(1);
+                for i in 1..(path.len() - 1) {
+                    let a = (path[i - 1] + path[i]) * 0.5;
+                    let b = path[i];
+                    let c = (path[i] + path[i + 1]) * 0.5;
+                    self.extend_smoothed(&mut result, vec![a], &[a]);
+                    let curve = (1..=samples)
+                        .map(|s| {
+                            let t = s as Scalar / samples as Scalar;
+                            let u = 1.0 - t;
+                            a * (u * u) + b * (2.0 * u * t) + c * (t * t)
+                        })
+                        .collect::<Vec<_>>();
+                    self.extend_smoothed(&mut result, curve, &[b, c]);
+                }
+                let last = path[path.len() - 1];
+                self.extend_smoothed(&mut result, vec![last], &[last]);
+            }
+        }
+        result.dedup_by(|a, b| a.same_as(*b));
+        result
+    }
+
+    /// Append curve points projected on nav mesh to smoothed path, or fallback points if curve
+    /// leaves nav mesh.
+    fn extend_smoothed(
+        &self,
+        result: &mut Vec<NavVec3>,
+        curve: Vec<NavVec3>,
+        fallback: &[NavVec3],
+    ) {
+        let mut last = match result.last() {
+            Some(last) => *last,
+            None => {
+                result.extend(curve);
+                return;
+            }
+        };
+        let mut points = Vec::with_capacity(curve.len());
+        for point in curve {
+            let point = match self.closest_point(point, NavQuery::Accuracy) {
+                Some(point) => point,
+                None => {
+                    points.clear();
+                    break;
+                }
+            };
+            let visible = last.same_as(point)
+                || self
+                    .raycast(last, point, NavQuery::Accuracy)
+                    .map(|result| result.hit.is_none())
+                    .unwrap_or(false);
+            if !visible {
+                points.clear();
+                break;
+            }
+            points.push(point);
+            last = point;
+        }
+        if points.is_empty() {
+            result.extend_from_slice(fallback);
+        } else {
+            result.extend(points);
+        }
+    }
+
+    /// Find shortest path on nav mesh from point to whichever of goal points is the cheapest one
+    /// to reach, using single search.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `goals` - list of goal points.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with pair of (reached goal index, path points on nav mesh) if found or `None`
+    /// otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let (goal, _) = mesh
+    ///     .find_path_to_any(
+    ///         (0.0, 1.0, 0.0).into(),
+    ///         &[(1.5, 0.25, 0.5).into(), (0.75, 0.25, 0.0).into()],
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(goal, 1);
+    /// ```
+    pub fn find_path_to_any(
+        &self,
+        from: NavVec3,
+        goals: &[NavVec3],
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<(usize, Vec<NavVec3>)> {
+        let start = self.find_closest_triangle(from, query)?;
+        // [(goal index, triangle index)]
+        let targets = goals
+            .iter()
+            .enumerate()
+            .filter_map(|(i, goal)| {
+                let triangle = self.find_closest_triangle(*goal, query)?;
+                if self.is_triangle_blocked(triangle) {
+                    None
+                } else {
+                    Some((i, triangle))
+                }
+            })
+            .collect::<Vec<_>>();
+        let (triangles, _) = self.find_path_triangles_to_any_with(
+            start,
+            |t| targets.iter().any(|(_, triangle)| *triangle == t),
+            |w, a, b| w * self.areas[a].cost * self.areas[b].cost,
+            |_| 0.0,
+        )?;
+        let end = *triangles.last()?;
+        targets
+            .iter()
+            .filter(|(_, triangle)| *triangle == end)
+            .filter_map(|(i, _)| {
+                let path = self.find_path_with(from, goals[*i], query, mode, |_, _| {
+                    Some((triangles.clone(), 0.0))
+                })?;
+                Some((*i, path))
+            })
+            .min_by(|a, b| {
+                Self::path_length(&a.1)
+                    .partial_cmp(&Self::path_length(&b.1))
+                    .unwrap()
+            })
+    }
+
+    /// Find shortest path on nav mesh between two points, with its cost, length and corridor.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with path if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_area_type(1, 2);
+    /// let path = mesh
+    ///     .find_path_detailed(
+    ///         (0.25, 0.75, 0.0).into(),
+    ///         (1.75, 0.25, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(path.triangles, vec![1, 0, 3, 2]);
+    /// assert_eq!(path.segment_area_types.len(), path.points.len() - 1);
+    /// assert_eq!(path.length, NavMesh::path_length(&path.points));
+    /// assert!(!path.partial);
+    /// ```
+    pub fn find_path_detailed(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<NavPath> {
+        self.find_nav_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles(start, end)
+        })
+    }
+
+    /// Find shortest path on nav mesh between two points, together with description of every
+    /// path corner: portal edge that generated it and direction path turns to there, so
+    /// animation can lean into turns and smoothing can round only actual corners.
+    ///
+    /// Path points are always found with funnel algorithm, as in `NavPathMode::Accuracy`.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with path and its corners if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    ///     (0.0, 2.0, 0.0).into(), // 6
+    ///     (1.0, 2.0, 0.0).into(), // 7
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    ///     (3, 4, 7).into(), // 4
+    ///     (7, 6, 3).into(), // 5
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let (path, corners) = mesh
+    ///     .find_path_with_corners(
+    ///         (1.5, 0.5, 0.0).into(),
+    ///         (0.75, 1.75, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(path.points.len(), 3);
+    /// assert_eq!(corners.len(), 1);
+    /// assert_eq!(corners[0].point, 1);
+    /// assert_eq!(path.points[1], (1.0, 1.0, 0.0).into());
+    /// assert_eq!(corners[0].turn, NavPathTurn::Right);
+    /// ```
+    pub fn find_path_with_corners(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+    ) -> Option<(NavPath, Vec<NavPathCorner>)> {
+        if from.same_as_within(to, self.tolerance.distance_sqr) {
+            return None;
+        }
+        let start = self.find_closest_triangle(from, query)?;
+        let end = self.find_closest_triangle(to, query)?;
+        let from = self.spatials[start].closest_point(from);
+        let to = self.spatials[end].closest_point(to);
+        let (triangles, cost) = self.find_path_triangles(start, end)?;
+        let (points, corners) = self.string_pull_corners(from, to, &triangles, 0.0)?;
+        Some((self.nav_path(points, triangles, cost), corners))
+    }
+
+    /// Find path on nav mesh between two points, or when target can not be reached (it is on
+    /// disconnected island or blocked), partial path towards point closest to target that can be
+    /// reached.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with path, marked as partial if it does not reach target, or `None` if start point
+    /// is not on walkable part of nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (3.0, 0.0, 0.0).into(), // 4
+    ///     (4.0, 0.0, 0.0).into(), // 5
+    ///     (4.0, 1.0, 0.0).into(), // 6
+    ///     (3.0, 1.0, 0.0).into(), // 7
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 2).into(),
+    ///     (2, 3, 0).into(),
+    ///     (4, 5, 6).into(),
+    ///     (6, 7, 4).into(),
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let from = (0.25, 0.5, 0.0).into();
+    /// let to = (3.5, 0.5, 0.0).into();
+    /// assert!(mesh
+    ///     .find_path(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+    ///     .is_none());
+    /// let path = mesh
+    ///     .find_path_partial(from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+    ///     .unwrap();
+    /// assert!(path.partial);
+    /// assert!(path.points.last().unwrap().same_as((1.0, 0.5, 0.0).into()));
+    /// ```
+    pub fn find_path_partial(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<NavPath> {
+        let start = self.find_closest_triangle(from, query)?;
+        let end = self.find_closest_triangle(to, query);
+        self.find_path_partial_between(from, to, start, end, mode)
+    }
+
+    /// Find path like `find_path_partial` does, with start and end triangles already resolved.
+    fn find_path_partial_between(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        start: usize,
+        end: Option<usize>,
+        mode: NavPathMode,
+    ) -> Option<NavPath> {
+        if let Some(end) = end {
+            if !from.same_as_within(to, self.tolerance.distance_sqr) {
+                let path = self.find_nav_path_between(
+                    from,
+                    to,
+                    start,
+                    end,
+                    mode,
+                    self.tolerance,
+                    |start, end| self.find_path_triangles(start, end),
+                );
+                if path.is_some() {
+                    return path;
+                }
+            }
+        }
+        if self.is_triangle_blocked(start) {
+            return None;
+        }
+        let end = self
+            .find_reachable_triangles(start)
+            .into_iter()
+            .map(|index| (index, self.spatials[index].distance2(&to)))
+            .fold(
+                (start, Scalar::INFINITY),
+                |a, b| if b.1 < a.1 { b } else { a },
+            )
+            .0;
+        let from = self.spatials[start].closest_point(from);
+        let target = self.spatials[end].closest_point(to);
+        let mut path = if from.same_as(target) {
+            self.nav_path(vec![from], vec![start], 0.0)
+        } else {
+            self.find_nav_path_between(
+                from,
+                target,
+                start,
+                end,
+                mode,
+                self.tolerance,
+                |start, end| self.find_path_triangles(start, end),
+            )?
+        };
+        path.partial = !target.same_as(to);
+        Some(path)
+    }
+
+    /// Find path on nav mesh between two points like `find_path_detailed` does, telling why path
+    /// could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Ok` with path, or `Err` with `Error::StartNotWalkable` if start point is not on walkable
+    /// part of nav mesh, `Error::Unreachable` if target lies on other island than start point or
+    /// `Error::GoalUnreachable` with point closest to target that can be reached.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (3.0, 0.0, 0.0).into(), // 4
+    ///     (4.0, 0.0, 0.0).into(), // 5
+    ///     (4.0, 1.0, 0.0).into(), // 6
+    ///     (3.0, 1.0, 0.0).into(), // 7
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 2).into(),
+    ///     (2, 3, 0).into(),
+    ///     (4, 5, 6).into(),
+    ///     (6, 7, 4).into(),
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let from = (0.25, 0.5, 0.0).into();
+    /// let to = (3.5, 0.5, 0.0).into();
+    /// assert!(matches!(
+    ///     mesh.find_path_checked(from, to, NavQuery::Accuracy, NavPathMode::Accuracy),
+    ///     Err(Error::Unreachable {
+    ///         from_island: 0,
+    ///         to_island: 1
+    ///     })
+    /// ));
+    ///
+    /// let mut mesh = mesh;
+    /// mesh.add_gate("door", vec![0], false).unwrap();
+    /// let to = (0.75, 0.25, 0.0).into();
+    /// match mesh.find_path_checked(from, to, NavQuery::Accuracy, NavPathMode::Accuracy) {
+    ///     Err(Error::GoalUnreachable(nearest)) => assert!(mesh
+    ///         .find_triangle_containing(nearest, 0.1)
+    ///         .is_some()),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn find_path_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> NavResult<NavPath> {
+        let start = self
+            .find_closest_triangle(from, query)
+            .ok_or(Error::StartNotWalkable(from))?;
+        let end = self.find_closest_triangle(to, query);
+        if let Some(end) = end {
+            if let (Some(from_island), Some(to_island)) =
+                (self.triangle_island(start), self.triangle_island(end))
+            {
+                if from_island != to_island {
+                    return Err(Error::Unreachable {
+                        from_island,
+                        to_island,
+                    });
+                }
+            }
+        }
+        let path = self
+            .find_path_partial_between(from, to, start, end, mode)
+            .ok_or(Error::StartNotWalkable(from))?;
+        if path.partial {
+            let nearest = path.points.last().copied().unwrap_or(from);
+            return Err(Error::GoalUnreachable(nearest));
+        }
+        Ok(path)
+    }
+
+    /// Find triangles that can be reached by walking from given triangle, avoiding blocked ones.
+    ///
+    /// # Arguments
+    /// * `from` - triangle index.
+    ///
+    /// # Returns
+    /// List of reached triangles indices, starting with `from`, or empty list if `from` triangle
+    /// does not exist or is blocked.
+    pub fn find_reachable_triangles(&self, from: usize) -> Vec<usize> {
+        if from >= self.triangles.len() || self.is_triangle_blocked(from) {
+            return vec![];
+        }
+        let mut visited = vec![false; self.triangles.len()];
+        visited[from] = true;
+        let mut result = vec![from];
+        let mut index = 0;
+        while let Some(current) = result.get(index).copied() {
+            index += 1;
+            for next in self.triangle_neighbors(current) {
+                if !visited[next] && self.connection_cost(current, next).is_some() {
+                    visited[next] = true;
+                    result.push(next);
+                }
+            }
+        }
+        result
+    }
+
+    /// Find triangles that can be reached from point within movement budget, for example to
+    /// show movement range in turn based games.
+    ///
+    /// Costs are measured between triangles centers, the same way as triangles path costs.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `max_cost` - movement budget.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with reached triangles or `None` if point is not on walkable part of nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let area = mesh
+    ///     .reachable_area((0.25, 0.75, 0.0).into(), 0.5, NavQuery::Accuracy)
+    ///     .unwrap();
+    /// assert_eq!(area.triangles, vec![1, 0]);
+    /// assert_eq!(area.boundary(&mesh).len(), 4);
+    /// ```
+    pub fn reachable_area(
+        &self,
+        from: NavVec3,
+        max_cost: Scalar,
+        query: NavQuery,
+    ) -> Option<NavReachableArea> {
+        let start = self.find_closest_triangle(from, query)?;
+        if self.is_triangle_blocked(start) {
+            return None;
+        }
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut result = NavReachableArea::default();
+        open.push(OpenNode(0.0, start));
+        costs.insert(start, 0.0);
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if cost > costs.get(&current).copied().unwrap_or(Scalar::INFINITY) {
+                continue;
+            }
+            result.triangles.push(current);
+            result.costs.push(cost);
+            for next in self.triangle_neighbors(current) {
+                if let Some(weight) = self.connection_cost(current, next) {
+                    let next_cost = cost + weight;
+                    if next_cost <= max_cost
+                        && next_cost < costs.get(&next).copied().unwrap_or(Scalar::INFINITY)
+                    {
+                        costs.insert(next, next_cost);
+                        open.push(OpenNode(next_cost, next));
+                    }
+                }
+            }
+        }
+        Some(result)
+    }
+
+    /// Sample random point on walkable part of nav mesh, uniformly distributed by triangles area.
+    ///
+    /// # Arguments
+    /// * `origin` - when set, only points reachable from this point are sampled.
+    /// * `query` - query quality used to find origin triangle.
+    /// * `random` - generator of random numbers in range from 0 to 1.
+    ///
+    /// # Returns
+    /// `Some` with point or `None` if there is no walkable triangle to sample.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (3.0, 0.0, 0.0).into(), // 4
+    ///     (4.0, 0.0, 0.0).into(), // 5
+    ///     (4.0, 1.0, 0.0).into(), // 6
+    ///     (3.0, 1.0, 0.0).into(), // 7
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 2).into(),
+    ///     (2, 3, 0).into(),
+    ///     (4, 5, 6).into(),
+    ///     (6, 7, 4).into(),
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let mut seed = 42u32;
+    /// let mut random = move || {
+    ///     seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+    ///     (seed >> 8) as Scalar / (1 << 24) as Scalar
+    /// };
+    /// let point = mesh
+    ///     .random_point(Some((0.5, 0.5, 0.0).into()), NavQuery::Accuracy, &mut random)
+    ///     .unwrap();
+    /// assert!(point.x <= 1.0);
+    /// ```
+    pub fn random_point<R>(
+        &self,
+        origin: Option<NavVec3>,
+        query: NavQuery,
+        random: R,
+    ) -> Option<NavVec3>
+    where
+        R: FnMut() -> Scalar,
+    {
+        let triangles = self.sampled_triangles(origin, query)?;
+        self.sample_triangles(&triangles, random)
+    }
+
+    /// Sample random point on walkable part of nav mesh within given distance from center,
+    /// uniformly distributed by triangles area.
+    ///
+    /// # Arguments
+    /// * `center` - center of sampled area.
+    /// * `radius` - maximal distance of sampled point from center.
+    /// * `origin` - when set, only points reachable from this point are sampled.
+    /// * `query` - query quality used to find origin triangle.
+    /// * `random` - generator of random numbers in range from 0 to 1.
+    ///
+    /// # Returns
+    /// `Some` with point or `None` if there is no walkable triangle within radius.
+    pub fn random_point_around<R>(
+        &self,
+        center: NavVec3,
+        radius: Scalar,
+        origin: Option<NavVec3>,
+        query: NavQuery,
+        mut random: R,
+    ) -> Option<NavVec3>
+    where
+        R: FnMut() -> Scalar,
+    {
+        let radius_sqr = radius.max(0.0) * radius.max(0.0);
+        let triangles = self
+            .sampled_triangles(origin, query)?
+            .into_iter()
+            .filter(|index| {
+                (self.spatials[*index].closest_point(center) - center).sqr_magnitude() <= radius_sqr
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..RANDOM_POINT_ATTEMPTS {
+            let point = self.sample_triangles(&triangles, &mut random)?;
+            if (point - center).sqr_magnitude() <= radius_sqr {
+                return Some(point);
+            }
+        }
+        // fall back to point that is guaranteed to be within radius.
+        let index = triangles[self.pick_triangle(&triangles, &mut random)?];
+        Some(self.spatials[index].closest_point(center))
+    }
+
+    fn sampled_triangles(&self, origin: Option<NavVec3>, query: NavQuery) -> Option<Vec<usize>> {
+        match origin {
+            Some(origin) => {
+                Some(self.find_reachable_triangles(self.find_closest_triangle(origin, query)?))
+            }
+            None => Some(
+                (0..self.triangles.len())
+                    .filter(|index| !self.is_triangle_blocked(*index))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn pick_triangle<R>(&self, triangles: &[usize], mut random: R) -> Option<usize>
+    where
+        R: FnMut() -> Scalar,
+    {
+        if triangles.is_empty() {
+            return None;
+        }
+        let total = triangles
+            .iter()
+            .map(|index| self.areas[*index].size)
+            .sum::<Scalar>();
+        let mut value = random().clamp(0.0, 1.0) * total;
+        for (i, index) in triangles.iter().enumerate() {
+            value -= self.areas[*index].size;
+            if value <= 0.0 {
+                return Some(i);
+            }
+        }
+        Some(triangles.len() - 1)
+    }
+
+    fn sample_triangles<R>(&self, triangles: &[usize], mut random: R) -> Option<NavVec3>
+    where
+        R: FnMut() -> Scalar,
+    {
+        let index = triangles[self.pick_triangle(triangles, &mut random)?];
+        let spatial = &self.spatials[index];
+        let mut u = random().clamp(0.0, 1.0);
+        let mut v = random().clamp(0.0, 1.0);
+        if u + v > 1.0 {
+            u = 1.0 - u;
+            v = 1.0 - v;
+        }
+        Some(spatial.a + (spatial.b - spatial.a) * u + (spatial.c - spatial.a) * v)
+    }
+
+    pub(crate) fn find_path_with<F>(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        find_triangles: F,
+    ) -> Option<Vec<NavVec3>>
+    where
+        F: FnOnce(usize, usize) -> Option<(Vec<usize>, Scalar)>,
+    {
+        self.find_nav_path_with(from, to, query, mode, find_triangles)
+            .map(|path| path.points)
+    }
+
+    pub(crate) fn find_nav_path_with<F>(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        find_triangles: F,
+    ) -> Option<NavPath>
+    where
+        F: FnOnce(usize, usize) -> Option<(Vec<usize>, Scalar)>,
+    {
+        self.find_nav_path_within(from, to, query, mode, self.tolerance, find_triangles)
+    }
+
+    fn find_nav_path_within<F>(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        tolerance: NavTolerance,
+        find_triangles: F,
+    ) -> Option<NavPath>
+    where
+        F: FnOnce(usize, usize) -> Option<(Vec<usize>, Scalar)>,
+    {
+        if from.same_as_within(to, tolerance.distance_sqr) {
+            return None;
+        }
+        let start = self.find_closest_triangle(from, query)?;
+        let end = self.find_closest_triangle(to, query)?;
+        self.find_nav_path_between(from, to, start, end, mode, tolerance, find_triangles)
+    }
+
+    /// Find path between two points lying on already resolved start and end triangles.
+    #[allow(clippy::too_many_arguments)]
+    fn find_nav_path_between<F>(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        start: usize,
+        end: usize,
+        mode: NavPathMode,
+        tolerance: NavTolerance,
+        find_triangles: F,
+    ) -> Option<NavPath>
+    where
+        F: FnOnce(usize, usize) -> Option<(Vec<usize>, Scalar)>,
+    {
+        let from = self.spatials[start].closest_point(from);
+        let to = self.spatials[end].closest_point(to);
+        let (triangles, cost) = find_triangles(start, end)?;
+        let points = if triangles.is_empty() {
+            return None;
+        } else if triangles.len() == 1 {
+            vec![from, to]
+        } else {
+            match mode {
+                NavPathMode::Accuracy => self.find_path_accuracy(from, to, &triangles, tolerance),
+                NavPathMode::MidPoints => self.find_path_midpoints(from, to, &triangles, tolerance),
+                NavPathMode::Centerline(weight) => self
+                    .find_path_centerline(from, to, &triangles, weight)
+                    .unwrap_or_else(|| self.find_path_accuracy(from, to, &triangles, tolerance)),
+            }
+        };
+        Some(self.nav_path(points, triangles, cost))
+    }
+
+    /// Build path description out of path points and triangles corridor they go through.
+    pub(crate) fn nav_path(
+        &self,
+        points: Vec<NavVec3>,
+        triangles: Vec<usize>,
+        cost: Scalar,
+    ) -> NavPath {
+        let segment_area_types = points
+            .windows(2)
+            .map(|pair| {
+                let mid = (pair[0] + pair[1]) * 0.5;
+                triangles
+                    .iter()
+                    .map(|index| (*index, self.spatials[*index].distance2(&mid)))
+                    .fold(None, |best: Option<(usize, Scalar)>, item| match best {
+                        Some(best) if best.1 <= item.1 => Some(best),
+                        _ => Some(item),
+                    })
+                    .map(|(index, _)| self.areas[index].area_type)
+                    .unwrap_or_default()
+            })
+            .collect();
+        NavPath {
+            length: Self::path_length(&points),
+            points,
+            cost,
+            triangles,
+            segment_area_types,
+            partial: false,
+            mesh: self.id,
+            mesh_version: self.version,
+        }
+    }
+
+    /// Find shortest path on nav mesh between two points for agent of given radius.
+    ///
+    /// Path corners are kept away from nav mesh boundary by agent radius and corridors narrower
+    /// than agent diameter are rejected.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `radius` - agent radius.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let from = (0.5, 0.5, 0.0).into();
+    /// let to = (1.5, 0.5, 0.0).into();
+    /// assert!(mesh.find_path_with_radius(from, to, NavQuery::Accuracy, 0.25).is_some());
+    /// assert!(mesh.find_path_with_radius(from, to, NavQuery::Accuracy, 0.75).is_none());
+    /// ```
+    pub fn find_path_with_radius(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        radius: Scalar,
+    ) -> Option<Vec<NavVec3>> {
+        if from.same_as_within(to, self.tolerance.distance_sqr) {
+            return None;
+        }
+        let radius = radius.max(0.0);
+        let start = self.find_closest_triangle(from, query)?;
+        let end = self.find_closest_triangle(to, query)?;
+        let from = self.spatials[start].closest_point(from);
+        let to = self.spatials[end].closest_point(to);
+        let (triangles, _) = self.find_path_triangles_custom(start, end, |_, a, b| {
+            self.shrunk_portal(a, b, radius).is_some()
+        })?;
+        self.string_pull(from, to, &triangles, radius)
+    }
+
+    /// Find shortest path points through triangles corridor, keeping corners away from nav mesh
+    /// boundary by given radius.
+    pub(crate) fn string_pull(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        triangles: &[usize],
+        radius: Scalar,
+    ) -> Option<Vec<NavVec3>> {
+        self.string_pull_corners(from, to, triangles, radius)
+            .map(|(points, _)| points)
+    }
+
+    /// Find shortest path points through triangles corridor like `string_pull`, together with
+    /// portal edges and turn directions of path corners.
+    pub(crate) fn string_pull_corners(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        triangles: &[usize],
+        radius: Scalar,
+    ) -> Option<(Vec<NavVec3>, Vec<NavPathCorner>)> {
+        if triangles.is_empty() {
+            return None;
+        } else if triangles.len() == 1 {
+            return Some((vec![from, to], vec![]));
+        }
+        let up = triangles
+            .iter()
+            .fold(NavVec3::default(), |a, index| {
+                a + self.spatials[*index].normal()
+            })
+            .normalize();
+        let mut portals = Vec::with_capacity(triangles.len() + 1);
+        portals.push((from, from));
+        for pair in triangles.windows(2) {
+            let portal = self.shrunk_portal(pair[0], pair[1], radius)?;
+            portals.push(orient_portal(
+                self.areas[pair[0]].center,
+                self.areas[pair[1]].center,
+                portal,
+                up,
+            ));
+        }
+        portals.push((to, to));
+        let result = funnel_corners(&portals, up);
+        let corners = result
+            .iter()
+            .enumerate()
+            .filter_map(|(point, (_, corner))| {
+                let (portal, turn) = (*corner)?;
+                if portal == 0 || portal >= triangles.len() {
+                    return None;
+                }
+                Some(NavPathCorner {
+                    point,
+                    portal: portals[portal],
+                    triangles: (triangles[portal - 1], triangles[portal]),
+                    turn,
+                })
+            })
+            .collect();
+        let points = result.into_iter().map(|(point, _)| point).collect();
+        Some((points, corners))
+    }
+
+    /// Walk triangles along straight line from point on given triangle towards target point.
+    ///
+    /// # Returns
+    /// Pair of visited triangles list and flag telling if target point was reached (otherwise
+    /// walk got stopped by nav mesh boundary or blocked triangle).
+    pub(crate) fn walk_line(
+        &self,
+        triangle: usize,
+        from: NavVec3,
+        to: NavVec3,
+        tolerance: NavTolerance,
+    ) -> (Vec<usize>, bool) {
+        let mut visited = vec![triangle];
+        let mut current = triangle;
+        let mut progress: Scalar = 0.0;
+        loop {
+            let up = self.spatials[current].normal();
+            let closest = self.spatials[current].closest_point(to);
+            let diff = to - closest;
+            if (diff - up * diff.dot(up)).sqr_magnitude() < tolerance.distance_sqr {
+                return (visited, true);
+            }
+            // pick portal where line leaves current triangle, that is the furthest crossed one.
+            let next = self
+                .triangle_neighbors(current)
+                .filter(|next| !visited.contains(next))
+                .filter_map(|next| {
+                    let (a, b) = self.triangles_portal(current, next)?;
+                    if !segment_crosses_portal(from, to, (a, b), up) {
+                        return None;
+                    }
+                    let df = (b - a).cross(from - a).dot(up);
+                    let dt = (b - a).cross(to - a).dot(up);
+                    let t = if (df - dt).abs() < tolerance.distance_sqr {
+                        0.0
+                    } else {
+                        df / (df - dt)
+                    };
+                    if t < progress - tolerance.angular {
+                        return None;
+                    }
+                    Some((next, t))
+                })
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            match next {
+                Some((next, t)) if !self.is_triangle_blocked(next) => {
+                    progress = t;
+                    current = next;
+                    visited.push(next);
+                }
+                _ => return (visited, false),
+            }
+        }
+    }
+
+    /// Portal between two triangles with its ends lying on nav mesh boundary pushed inside by
+    /// given radius, or `None` if portal is too narrow.
+    pub(crate) fn shrunk_portal(
+        &self,
+        from: usize,
+        to: usize,
+        radius: Scalar,
+    ) -> Option<(NavVec3, NavVec3)> {
+        let (a, b) = self.triangles_portal(from, to)?;
+        if radius <= 0.0 {
+            return Some((a, b));
+        }
+        let left = self.is_boundary_point(a);
+        let right = self.is_boundary_point(b);
+        let required = radius * (left as u8 + right as u8) as Scalar;
+        let diff = b - a;
+        let length = diff.magnitude();
+        if length < required {
+            return None;
+        }
+        let dir = diff / length.max(self.tolerance.distance);
+        let a = if left { a + dir * radius } else { a };
+        let b = if right { b - dir * radius } else { b };
+        Some((a, b))
+    }
+
+    fn find_path_centerline(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        triangles: &[usize],
+        weight: Scalar,
+    ) -> Option<Vec<NavVec3>> {
+        let up = triangles
+            .iter()
+            .fold(NavVec3::default(), |a, index| {
+                a + self.spatials[*index].normal()
+            })
+            .normalize();
+        let mut portals = Vec::with_capacity(triangles.len() + 1);
+        portals.push((from, from));
+        for pair in triangles.windows(2) {
+            let (a, b) = self.triangles_portal(pair[0], pair[1])?;
+            let walls = (self.is_boundary_point(a), self.is_boundary_point(b));
+            portals.push(orient_portal(
+                self.areas[pair[0]].center,
+                self.areas[pair[1]].center,
+                center_portal((a, b), walls, weight),
+                up,
+            ));
+        }
+        portals.push((to, to));
+        Some(funnel(&portals, up))
+    }
+
+    fn is_boundary_point(&self, point: NavVec3) -> bool {
+        self.rtree
+            .lookup_in_circle(&point, &self.tolerance.distance_sqr)
+            .into_iter()
+            .filter_map(|s| self.hard_edges.get(&s.index))
+            .flatten()
+            .any(|(a, b)| {
+                a.same_as_within(point, self.tolerance.distance_sqr)
+                    || b.same_as_within(point, self.tolerance.distance_sqr)
+            })
+    }
+
+    fn find_path_accuracy(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        triangles: &[usize],
+        tolerance: NavTolerance,
+    ) -> Vec<NavVec3> {
+        #[derive(Debug)]
+        enum Node {
+            Point(NavVec3),
+            // (a, b, normal)
+            LevelChange(NavVec3, NavVec3, NavVec3),
+        }
+
+        // TODO: reduce allocations.
+        if triangles.len() == 2 {
+            let NavConnection(a, b) =
+                self.connections[&NavConnection(triangles[0] as u32, triangles[1] as u32)].1;
+            let a = self.vertices[a as usize];
+            let b = self.vertices[b as usize];
+            let n = self.spatials[triangles[0]].normal();
+            let m = self.spatials[triangles[1]].normal();
+            if !NavVec3::is_line_between_points_within(from, to, a, b, n, tolerance.distance_sqr) {
+                let da = (from - a).sqr_magnitude();
+                let db = (from - b).sqr_magnitude();
+                let point = if da < db { a } else { b };
+                return vec![from, point, to];
+            } else if n.dot(m) < 1.0 - tolerance.angular {
+                let n = (b - a).normalize().cross(n);
+                if let Some(point) = NavVec3::raycast_line(from, to, a, b, n) {
+                    return vec![from, point, to];
+                }
+            }
+            return vec![from, to];
+        }
+        let mut start = from;
+        let mut last_normal = self.spatials[triangles[0]].normal();
+        let mut nodes = Vec::with_capacity(triangles.len() - 1);
+        for triplets in triangles.windows(3) {
+            let NavConnection(a, b) =
+                self.connections[&NavConnection(triplets[0] as u32, triplets[1] as u32)].1;
+            let a = self.vertices[a as usize];
+            let b = self.vertices[b as usize];
+            let NavConnection(c, d) =
+                self.connections[&NavConnection(triplets[1] as u32, triplets[2] as u32)].1;
+            let c = self.vertices[c as usize];
+            let d = self.vertices[d as usize];
+            let normal = self.spatials[triplets[1]].normal();
+            let old_last_normal = last_normal;
+            last_normal = normal;
+            if !NavVec3::is_line_between_points_within(
+                start,
+                c,
+                a,
+                b,
+                normal,
+                tolerance.distance_sqr,
+            ) || !NavVec3::is_line_between_points_within(
+                start,
+                d,
+                a,
+                b,
+                normal,
+                tolerance.distance_sqr,
+            ) {
+                let da = (start - a).sqr_magnitude();
+                let db = (start - b).sqr_magnitude();
+                start = if da < db { a } else { b };
+                nodes.push(Node::Point(start));
+            } else if old_last_normal.dot(normal) < 1.0 - tolerance.angular {
+                let normal = self.spatials[triplets[0]].normal();
+                let normal = (b - a).normalize().cross(normal);
+                nodes.push(Node::LevelChange(a, b, normal));
+            }
+        }
+        {
+            let NavConnection(a, b) = self.connections[&NavConnection(
+                triangles[triangles.len() - 2] as u32,
+                triangles[triangles.len() - 1] as u32,
+            )]
+                .1;
+            let a = self.vertices[a as usize];
+            let b = self.vertices[b as usize];
+            let n = self.spatials[triangles[triangles.len() - 2]].normal();
+            let m = self.spatials[triangles[triangles.len() - 1]].normal();
+            if !NavVec3::is_line_between_points_within(start, to, a, b, n, tolerance.distance_sqr) {
+                let da = (start - a).sqr_magnitude();
+                let db = (start - b).sqr_magnitude();
+                let point = if da < db { a } else { b };
+                nodes.push(Node::Point(point));
+            } else if n.dot(m) < 1.0 - tolerance.angular {
+                let n = (b - a).normalize().cross(n);
+                nodes.push(Node::LevelChange(a, b, n));
+            }
+        }
+
+        let mut points = Vec::with_capacity(nodes.len() + 2);
+        points.push(from);
+        let mut point = from;
+        for i in 0..nodes.len() {
+            match nodes[i] {
+                Node::Point(p) => {
+                    point = p;
+                    points.push(p);
+                }
+                Node::LevelChange(a, b, n) => {
+                    let next = nodes
+                        .iter()
+                        .skip(i + 1)
+                        .find_map(|n| match n {
+                            Node::Point(p) => Some(*p),
+                            _ => None,
+                        })
+                        .unwrap_or(to);
+                    if let Some(p) = NavVec3::raycast_line(point, next, a, b, n) {
+                        points.push(p);
+                    }
+                }
+            }
+        }
+        points.push(to);
+        points.dedup();
+        points
+    }
+
+    fn find_path_midpoints(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        triangles: &[usize],
+        tolerance: NavTolerance,
+    ) -> Vec<NavVec3> {
+        if triangles.len() == 2 {
+            let NavConnection(a, b) =
+                self.connections[&NavConnection(triangles[0] as u32, triangles[1] as u32)].1;
+            let a = self.vertices[a as usize];
+            let b = self.vertices[b as usize];
+            let n = self.spatials[triangles[0]].normal();
+            let m = self.spatials[triangles[1]].normal();
+            if n.dot(m) < 1.0 - tolerance.angular
+                || !NavVec3::is_line_between_points_within(
+                    from,
+                    to,
+                    a,
+                    b,
+                    n,
+                    tolerance.distance_sqr,
+                )
+            {
+                return vec![from, (a + b) * 0.5, to];
+            } else {
+                return vec![from, to];
+            }
+        }
         let mut start = from;
         let mut last_normal = self.spatials[triangles[0]].normal();
-        let mut nodes = Vec::with_capacity(triangles.len() - 1);
+        let mut points = Vec::with_capacity(triangles.len() + 1);
+        points.push(from);
         for triplets in triangles.windows(3) {
             let NavConnection(a, b) =
                 self.connections[&NavConnection(triplets[0] as u32, triplets[1] as u32)].1;
             let a = self.vertices[a as usize];
             let b = self.vertices[b as usize];
-            let NavConnection(c, d) =
-                self.connections[&NavConnection(triplets[1] as u32, triplets[2] as u32)].1;
-            let c = self.vertices[c as usize];
-            let d = self.vertices[d as usize];
+            let point = (a + b) * 0.5;
             let normal = self.spatials[triplets[1]].normal();
             let old_last_normal = last_normal;
             last_normal = normal;
-            if !NavVec3::is_line_between_points(start, c, a, b, normal)
-                || !NavVec3::is_line_between_points(start, d, a, b, normal)
+            if old_last_normal.dot(normal) < 1.0 - tolerance.angular {
+                start = point;
+                points.push(start);
+            } else {
+                let NavConnection(c, d) =
+                    self.connections[&NavConnection(triplets[1] as u32, triplets[2] as u32)].1;
+                let c = self.vertices[c as usize];
+                let d = self.vertices[d as usize];
+                let end = (c + d) * 0.5;
+                if !NavVec3::is_line_between_points_within(
+                    start,
+                    end,
+                    a,
+                    b,
+                    normal,
+                    tolerance.distance_sqr,
+                ) {
+                    start = point;
+                    points.push(start);
+                }
+            }
+        }
+        {
+            let NavConnection(a, b) = self.connections[&NavConnection(
+                triangles[triangles.len() - 2] as u32,
+                triangles[triangles.len() - 1] as u32,
+            )]
+                .1;
+            let a = self.vertices[a as usize];
+            let b = self.vertices[b as usize];
+            let n = self.spatials[triangles[triangles.len() - 2]].normal();
+            let m = self.spatials[triangles[triangles.len() - 1]].normal();
+            if n.dot(m) < 1.0 - tolerance.angular
+                || !NavVec3::is_line_between_points_within(
+                    start,
+                    to,
+                    a,
+                    b,
+                    n,
+                    tolerance.distance_sqr,
+                )
             {
-                let da = (start - a).sqr_magnitude();
-                let db = (start - b).sqr_magnitude();
-                start = if da < db { a } else { b };
-                nodes.push(Node::Point(start));
-            } else if old_last_normal.dot(normal) < 1.0 - ZERO_TRESHOLD {
-                let normal = self.spatials[triplets[0]].normal();
-                let normal = (b - a).normalize().cross(normal);
-                nodes.push(Node::LevelChange(a, b, normal));
+                points.push((a + b) * 0.5);
+            }
+        }
+        points.push(to);
+        points.dedup();
+        points
+    }
+
+    /// Find shortest path on nav mesh between two points.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh and path length if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let path = mesh.find_path_triangles(1, 2).unwrap().0;
+    /// assert_eq!(path, vec![1, 0, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn find_path_triangles(&self, from: usize, to: usize) -> Option<(Vec<usize>, Scalar)> {
+        self.find_path_triangles_cached(from, to, || {
+            self.find_path_triangles_custom(from, to, |_, _, _| true)
+        })
+    }
+
+    /// Find path triangles like `find_path_triangles` does, going through path cache and trace
+    /// hook, with given search performing default path finding on cache miss.
+    pub(crate) fn find_path_triangles_cached<F>(
+        &self,
+        from: usize,
+        to: usize,
+        search: F,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        F: FnOnce() -> Option<(Vec<usize>, Scalar)>,
+    {
+        if is_tracing() {
+            return self.find_path_triangles_with_stats(from, to).0;
+        }
+        if let Some(result) = self.path_cache.get(self.version, from, to) {
+            return result;
+        }
+        let result = search();
+        self.path_cache
+            .insert(self.version, from, to, result.clone());
+        result
+    }
+
+    /// Find shortest path on nav mesh between two triangles like `find_path_triangles` does,
+    /// collecting search statistics. Statistics are passed to global trace hook too.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    ///
+    /// # Returns
+    /// Pair of (`Some` with path triangles and path length if found or `None` otherwise, search
+    /// statistics).
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_path_cache_capacity(4);
+    /// let (path, stats) = mesh.find_path_triangles_with_stats(1, 2);
+    /// assert_eq!(path.unwrap().0, vec![1, 0, 3, 2]);
+    /// assert_eq!(stats.expansions, 4);
+    /// assert!(stats.found && !stats.cache_hit);
+    /// let (_, stats) = mesh.find_path_triangles_with_stats(1, 2);
+    /// assert!(stats.found && stats.cache_hit);
+    /// assert_eq!(stats.expansions, 0);
+    /// ```
+    pub fn find_path_triangles_with_stats(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> (Option<(Vec<usize>, Scalar)>, NavStats) {
+        let timer = NavTimer::start();
+        let mut stats = NavStats {
+            from,
+            to,
+            ..Default::default()
+        };
+        let result = match self.path_cache.get(self.version, from, to) {
+            Some(result) => {
+                stats.cache_hit = true;
+                result
+            }
+            None => {
+                let mut scratch = NavGraphSearch::default();
+                let result = scratch.find_path_triangles(self, from, to);
+                stats.expansions = scratch.expansions;
+                stats.edges_visited = scratch.edges_visited;
+                stats.open_peak = scratch.open_peak;
+                self.path_cache
+                    .insert(self.version, from, to, result.clone());
+                result
+            }
+        };
+        stats.found = result.is_some();
+        stats.time = timer.elapsed();
+        trace(&stats);
+        (result, stats)
+    }
+
+    /// Find shortest path on nav mesh between two points, providing custom filtering function.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `filter` - closure that gives you a connection distance squared, first triangle index
+    ///   and second triangle index.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh and path length if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let path = mesh.find_path_triangles_custom(
+    ///     1,
+    ///     2,
+    ///     |_dist_sqr, _first_idx, _second_idx| true
+    /// ).unwrap().0;
+    /// assert_eq!(path, vec![1, 0, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn find_path_triangles_custom<F>(
+        &self,
+        from: usize,
+        to: usize,
+        mut filter: F,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        F: FnMut(Scalar, usize, usize) -> bool,
+    {
+        if self.is_triangle_blocked(to) || !self.are_triangles_connected(from, to) {
+            return None;
+        }
+        self.find_path_triangles_to_any_with(
+            from,
+            |t| t == to,
+            |w, a, b| {
+                if filter(w, a, b) {
+                    let a = self.areas[a].cost;
+                    let b = self.areas[b].cost;
+                    w * a * b
+                } else {
+                    SCALAR_MAX
+                }
+            },
+            |t| self.estimate_path_cost(t, to),
+        )
+    }
+
+    /// Find shortest path on nav mesh between two triangles, using areas filter.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `filter` - areas filter with area types costs and flags masks.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path length if found or `None` otherwise.
+    pub fn find_path_triangles_filtered(
+        &self,
+        from: usize,
+        to: usize,
+        filter: &NavAreaFilter,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        if !filter.passes(&self.areas[from]) || !filter.passes(&self.areas[to]) {
+            return None;
+        }
+        let (path, cost) = self.find_path_triangles_with(from, to, |w, a, b| {
+            let a = &self.areas[a];
+            let b = &self.areas[b];
+            if filter.passes(a) && filter.passes(b) {
+                w * a.cost * filter.cost(a.area_type) * b.cost * filter.cost(b.area_type)
+            } else {
+                SCALAR_MAX
             }
+        })?;
+        if path.iter().any(|index| !filter.passes(&self.areas[*index])) {
+            return None;
+        }
+        Some((path, cost))
+    }
+
+    /// Find shortest path on nav mesh between two triangles, using custom cost callback.
+    ///
+    /// Moves between connected triangles are split in two parts: from first triangle center to
+    /// their shared edge middle point and from there to second triangle center, each part asked
+    /// for its cost with its triangle area type and multiplied by that area cost.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `filter` - cost callback.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path cost if found or `None` otherwise.
+    pub fn find_path_triangles_with_filter<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        filter: &Q,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        let (path, cost) = self.find_path_triangles_with(from, to, |_, a, b| {
+            self.filtered_connection_cost(a, b, filter)
+                .unwrap_or(SCALAR_MAX)
+        })?;
+        if path.windows(2).any(|pair| {
+            self.filtered_connection_cost(pair[0], pair[1], filter)
+                .is_none()
+        }) {
+            return None;
+        }
+        Some((path, cost))
+    }
+
+    pub(crate) fn filtered_connection_cost<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        filter: &Q,
+    ) -> Option<Scalar>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        let (a, b) = self.triangles_portal(from, to)?;
+        let middle = (a + b) * 0.5;
+        let from = &self.areas[from];
+        let to = &self.areas[to];
+        let first = filter.cost(from.center, middle, from.area_type)?;
+        let second = filter.cost(middle, to.center, to.area_type)?;
+        Some(first.max(0.0) * from.cost + second.max(0.0) * to.cost)
+    }
+
+    pub(crate) fn find_path_triangles_with<F>(
+        &self,
+        from: usize,
+        to: usize,
+        cost: F,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        F: FnMut(Scalar, usize, usize) -> Scalar,
+    {
+        if self.is_triangle_blocked(to) || !self.are_triangles_connected(from, to) {
+            return None;
+        }
+        self.find_path_triangles_to_any_with(from, |t| t == to, cost, |_| 0.0)
+    }
+
+    fn find_path_triangles_to_any_with<G, F, H>(
+        &self,
+        from: usize,
+        mut is_goal: G,
+        mut cost: F,
+        mut heuristic: H,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        G: FnMut(usize) -> bool,
+        F: FnMut(Scalar, usize, usize) -> Scalar,
+        H: FnMut(usize) -> Scalar,
+    {
+        if self.is_triangle_blocked(from) {
+            return None;
         }
-        {
-            let NavConnection(a, b) = self.connections[&NavConnection(
-                triangles[triangles.len() - 2] as u32,
-                triangles[triangles.len() - 1] as u32,
-            )]
-                .1;
-            let a = self.vertices[a as usize];
-            let b = self.vertices[b as usize];
-            let n = self.spatials[triangles[triangles.len() - 2]].normal();
-            let m = self.spatials[triangles[triangles.len() - 1]].normal();
-            if !NavVec3::is_line_between_points(start, to, a, b, n) {
-                let da = (start - a).sqr_magnitude();
-                let db = (start - b).sqr_magnitude();
-                let point = if da < db { a } else { b };
-                nodes.push(Node::Point(point));
-            } else if n.dot(m) < 1.0 - ZERO_TRESHOLD {
-                let n = (b - a).normalize().cross(n);
-                nodes.push(Node::LevelChange(a, b, n));
+        let (cost, path) = astar(
+            &self.graph,
+            self.nodes[from],
+            |n| is_goal(self.nodes_map[&n]),
+            |e| {
+                let a = self.nodes_map[&e.source()];
+                let b = self.nodes_map[&e.target()];
+                let w = *e.weight();
+                if self.is_triangle_blocked(a) || self.is_triangle_blocked(b) {
+                    SCALAR_MAX
+                } else {
+                    cost(w, a, b)
+                }
+            },
+            |n| heuristic(self.nodes_map[&n]),
+        )?;
+        let path = iter!(path).map(|v| self.nodes_map[v]).collect::<Vec<_>>();
+        if path.iter().any(|index| self.is_triangle_blocked(*index)) {
+            return None;
+        }
+        Some((path, cost))
+    }
+
+    /// Cost of moving between connected triangles used by default path finding, or `None` if
+    /// triangles are not connected or either of them is blocked.
+    pub(crate) fn connection_cost(&self, from: usize, to: usize) -> Option<Scalar> {
+        if self.is_triangle_blocked(from) || self.is_triangle_blocked(to) {
+            return None;
+        }
+        self.unblocked_connection_cost(from, to)
+    }
+
+    fn unblocked_connection_cost(&self, from: usize, to: usize) -> Option<Scalar> {
+        let (w, _) = self
+            .connections
+            .get(&NavConnection(from as u32, to as u32))?;
+        Some(w * self.areas[from].cost * self.areas[to].cost)
+    }
+
+    /// Precompute path costs from landmark triangles to every triangle, used as A* heuristic that
+    /// is much tighter than none at all on mazes and interiors. Landmarks are picked so they lie
+    /// far from each other, usually at nav mesh extremities.
+    ///
+    /// Landmarks are stored with nav mesh (also in binary format) and cleared when any area gets
+    /// cheaper. Obstacles do not invalidate them.
+    ///
+    /// # Arguments
+    /// * `count` - number of landmarks, every one of them costs one scalar per triangle.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.precompute_landmarks(2);
+    /// assert_eq!(mesh.landmarks().count(), 2);
+    /// let (path, cost) = mesh.find_path_triangles(1, 2).unwrap();
+    /// assert_eq!(path, vec![1, 0, 3, 2]);
+    /// assert!(mesh.estimate_path_cost(1, 2) <= cost);
+    /// ```
+    pub fn precompute_landmarks(&mut self, count: usize) {
+        self.landmarks.clear();
+        let count = count.min(self.triangles.len());
+        if count == 0 {
+            return;
+        }
+        // farthest triangle from first one starts, then every next landmark is the one farthest
+        // from already picked ones.
+        let mut next = self
+            .landmark_costs(0)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, cost)| *cost < SCALAR_MAX)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index);
+        let mut closest = vec![SCALAR_MAX; self.triangles.len()];
+        while self.landmarks.len() < count {
+            let index = match next {
+                Some(index) => index,
+                None => break,
+            };
+            let costs = self.landmark_costs(index);
+            for (closest, cost) in closest.iter_mut().zip(&costs) {
+                *closest = closest.min(*cost);
             }
+            self.landmarks.push((index, costs));
+            next = (0..self.triangles.len())
+                .filter(|index| !self.landmarks.iter().any(|(other, _)| other == index))
+                .filter(|index| closest[*index] < SCALAR_MAX)
+                .max_by(|a, b| closest[*a].partial_cmp(&closest[*b]).unwrap())
+                .or_else(|| {
+                    // pick landmarks on islands not reached yet.
+                    (0..self.triangles.len()).find(|index| closest[*index] == SCALAR_MAX)
+                });
         }
+        self.version = next_version();
+    }
 
-        let mut points = Vec::with_capacity(nodes.len() + 2);
-        points.push(from);
-        let mut point = from;
-        for i in 0..nodes.len() {
-            match nodes[i] {
-                Node::Point(p) => {
-                    point = p;
-                    points.push(p);
+    pub fn clear_landmarks(&mut self) {
+        self.landmarks.clear();
+    }
+
+    /// Iterator over indices of landmark triangles.
+    pub fn landmarks(&self) -> impl Iterator<Item = usize> + '_ {
+        self.landmarks.iter().map(|(index, _)| *index)
+    }
+
+    /// Lower bound of path cost between two triangles (as found by `NavMesh::find_path`),
+    /// estimated with precomputed landmarks.
+    ///
+    /// # Returns
+    /// Estimated cost or 0 if there are no landmarks.
+    pub fn estimate_path_cost(&self, from: usize, to: usize) -> Scalar {
+        self.landmarks
+            .iter()
+            .filter_map(|(_, costs)| {
+                let a = *costs.get(from)?;
+                let b = *costs.get(to)?;
+                if a < SCALAR_MAX && b < SCALAR_MAX {
+                    Some((a - b).abs())
+                } else {
+                    None
                 }
-                Node::LevelChange(a, b, n) => {
-                    let next = nodes
-                        .iter()
-                        .skip(i + 1)
-                        .find_map(|n| match n {
-                            Node::Point(p) => Some(*p),
-                            _ => None,
-                        })
-                        .unwrap_or(to);
-                    if let Some(p) = NavVec3::raycast_line(point, next, a, b, n) {
-                        points.push(p);
+            })
+            .fold(0.0, Scalar::max)
+    }
+
+    /// Path costs from triangle to every other one, ignoring obstacles.
+    fn landmark_costs(&self, from: usize) -> Vec<Scalar> {
+        let mut costs = vec![SCALAR_MAX; self.triangles.len()];
+        let mut open = BinaryHeap::new();
+        costs[from] = 0.0;
+        open.push(OpenNode(0.0, from));
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if cost > costs[current] {
+                continue;
+            }
+            for next in self.triangle_neighbors(current) {
+                if let Some(weight) = self.unblocked_connection_cost(current, next) {
+                    let next_cost = cost + weight;
+                    if next_cost < costs[next] {
+                        costs[next] = next_cost;
+                        open.push(OpenNode(next_cost, next));
                     }
                 }
             }
         }
-        points.push(to);
-        points.dedup();
-        points
+        costs
+    }
+
+    /// Iterator over indices of triangles connected with given triangle.
+    ///
+    /// # Arguments
+    /// * `index` - triangle index.
+    pub fn triangle_neighbors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.nodes
+            .get(index)
+            .into_iter()
+            .flat_map(move |node| self.graph.neighbors(*node))
+            .filter_map(move |node| self.nodes_map.get(&node).copied())
+    }
+
+    /// Get edge shared by two connected triangles.
+    ///
+    /// # Arguments
+    /// * `from` - first triangle index.
+    /// * `to` - second triangle index.
+    ///
+    /// # Returns
+    /// `Some` with edge vertices points or `None` if triangles are not connected.
+    pub fn triangles_portal(&self, from: usize, to: usize) -> Option<(NavVec3, NavVec3)> {
+        let (_, NavConnection(a, b)) = self
+            .connections
+            .get(&NavConnection(from as u32, to as u32))?;
+        Some((self.vertices[*a as usize], self.vertices[*b as usize]))
+    }
+
+    /// Iterator over edges that are not shared with any other triangle.
+    ///
+    /// # Returns
+    /// Iterator of (triangle index, edge start, edge end).
+    pub fn boundary_edges(&self) -> impl Iterator<Item = (usize, NavVec3, NavVec3)> + '_ {
+        self.hard_edges
+            .iter()
+            .flat_map(|(index, edges)| edges.iter().map(move |(a, b)| (*index, *a, *b)))
+    }
+
+    /// Boundary edges of given triangle.
+    pub(crate) fn triangle_boundary_edges(&self, index: usize) -> &[(NavVec3, NavVec3)] {
+        self.hard_edges
+            .get(&index)
+            .map(|edges| edges.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Find closest point on given triangle.
+    ///
+    /// # Arguments
+    /// * `index` - triangle index.
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with point on triangle or `None` if triangle does not exists.
+    #[inline]
+    pub fn closest_point_on_triangle(&self, index: usize, point: NavVec3) -> Option<NavVec3> {
+        self.spatials.get(index).map(|s| s.closest_point(point))
+    }
+
+    /// Find first triangle crossed by straight segment, for example to pick nav mesh point under
+    /// cursor ray.
+    ///
+    /// # Arguments
+    /// * `from` - segment start.
+    /// * `to` - segment end.
+    ///
+    /// # Returns
+    /// `Some` with index of triangle closest to segment start and contact point or `None` if
+    /// segment does not cross nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let (triangle, point) = mesh
+    ///     .find_segment_intersection((1.5, 0.25, 2.0).into(), (1.5, 0.25, -2.0).into())
+    ///     .unwrap();
+    /// assert_eq!(triangle, 2);
+    /// assert_eq!(point, (1.5, 0.25, 0.5).into());
+    /// ```
+    pub fn find_segment_intersection(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+    ) -> Option<(usize, NavVec3)> {
+        let candidates = self
+            .rtree
+            .lookup_in_rectangle(&BoundingRect::from_corners(&from.min(to), &from.max(to)));
+        let triangles = candidates
+            .iter()
+            .map(|s| (s.a, s.b, s.c))
+            .collect::<Vec<_>>();
+        NavVec3::raycast_triangle_batch(from, to, &triangles)
+            .into_iter()
+            .zip(candidates)
+            .filter_map(|(point, s)| Some((s.index, point?)))
+            .min_by(|a, b| {
+                (a.1 - from)
+                    .sqr_magnitude()
+                    .partial_cmp(&(b.1 - from).sqr_magnitude())
+                    .unwrap()
+            })
+    }
+
+    /// Find first triangle hit by free 3D segment (not constrained to nav mesh surface), for
+    /// example mouse picking ray. Blocked triangles are hit too.
+    ///
+    /// # Arguments
+    /// * `from` - segment start point.
+    /// * `to` - segment end point.
+    ///
+    /// # Returns
+    /// `Some` with hit closest to segment start or `None` if segment misses nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (2.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 2.0, 0.0).into(), // 2
+    ///     (0.0, 2.0, 0.0).into(), // 3
+    ///     (0.0, 0.0, 1.0).into(), // 4
+    ///     (2.0, 0.0, 1.0).into(), // 5
+    ///     (2.0, 2.0, 1.0).into(), // 6
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into(), (4, 5, 6).into()];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// // upper floor gets hit first.
+    /// let hit = mesh
+    ///     .raycast_3d((1.5, 0.5, 3.0).into(), (1.5, 0.5, -1.0).into())
+    ///     .unwrap();
+    /// assert_eq!(hit.triangle, 2);
+    /// assert_eq!(hit.point, (1.5, 0.5, 1.0).into());
+    /// assert_eq!(hit.distance, 2.0);
+    /// assert!(hit.barycentric.same_as((0.25, 0.5, 0.25).into()));
+    /// assert!(mesh
+    ///     .raycast_3d((3.0, 0.5, 3.0).into(), (3.0, 0.5, -1.0).into())
+    ///     .is_none());
+    /// ```
+    pub fn raycast_3d(&self, from: NavVec3, to: NavVec3) -> Option<NavSurfaceHit> {
+        let (triangle, point) = self.find_segment_intersection(from, to)?;
+        Some(NavSurfaceHit {
+            point,
+            triangle,
+            barycentric: self.spatials[triangle].barycentric(point),
+            distance: point.distance(from),
+        })
+    }
+
+    /// Find nav mesh point directly above or below given point along up axis.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `height` - maximal vertical distance of point from nav mesh surface.
+    ///
+    /// # Returns
+    /// `Some` with index of triangle and point on its surface closest to query point or `None` if
+    /// there is no nav mesh surface within height range.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 0.0, -1.0).into(), // 2
+    ///     (0.0, 0.0, -1.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_up_axis(NavUpAxis::Y);
+    /// let (_, point) = mesh.find_surface_point((0.5, 3.0, -0.25).into(), 5.0).unwrap();
+    /// assert!(point.same_as((0.5, 0.0, -0.25).into()));
+    /// assert!(mesh.find_surface_point((2.0, 3.0, -0.25).into(), 5.0).is_none());
+    /// ```
+    pub fn find_surface_point(&self, point: NavVec3, height: Scalar) -> Option<(usize, NavVec3)> {
+        let offset = self.up_axis.up() * height.max(0.0);
+        let above = self.find_segment_intersection(point, point + offset);
+        let below = self.find_segment_intersection(point, point - offset);
+        match (above, below) {
+            (Some(above), Some(below)) => {
+                if (above.1 - point).sqr_magnitude() < (below.1 - point).sqr_magnitude() {
+                    Some(above)
+                } else {
+                    Some(below)
+                }
+            }
+            (above, below) => above.or(below),
+        }
+    }
+
+    /// Slope of triangle, meaning angle (in radians) between its normal and up axis.
+    ///
+    /// # Arguments
+    /// * `index` - triangle index.
+    ///
+    /// # Returns
+    /// `Some` with slope angle or `None` if triangle does not exists.
+    pub fn triangle_slope(&self, index: usize) -> Option<Scalar> {
+        let normal = self.spatials.get(index)?.normal();
+        Some(normal.dot(self.up_axis.up()).abs().min(1.0).acos())
+    }
+
+    /// Find triangle that contains point, meaning point projected along triangle normal lies
+    /// inside of it.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `height` - maximal distance of point from triangle surface.
+    ///
+    /// # Returns
+    /// `Some` with index of triangle closest to the point along its normal or `None` if there is
+    /// no such triangle.
+    pub fn find_triangle_containing(&self, point: NavVec3, height: Scalar) -> Option<usize> {
+        let height = height.max(0.0);
+        let extents = NavVec3::new(height, height, height);
+        self.rtree
+            .lookup_in_rectangle(&BoundingRect::from_corners(
+                &(point - extents),
+                &(point + extents),
+            ))
+            .into_iter()
+            .filter_map(|s| {
+                let diff = point - s.closest_point(point);
+                let distance = diff.dot(s.normal());
+                let tangent = diff - s.normal() * distance;
+                if tangent.sqr_magnitude() < self.tolerance.distance_sqr && distance.abs() <= height
+                {
+                    Some((s.index, distance.abs()))
+                } else {
+                    None
+                }
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index)
+    }
+
+    /// Find closest point on nav mesh within given radius from query point.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `radius` - maximal distance of found point from the query point.
+    ///
+    /// # Returns
+    /// `Some` with pair of (triangle index, point on that triangle) or `None` if nav mesh does not
+    /// lie within radius.
+    pub fn sample_position_within_radius(
+        &self,
+        point: NavVec3,
+        radius: Scalar,
+    ) -> Option<(usize, NavVec3)> {
+        let radius = radius.max(0.0);
+        self.rtree
+            .lookup_in_circle(&point, &(radius * radius))
+            .into_iter()
+            .filter(|s| !self.is_triangle_blocked(s.index))
+            .map(|s| {
+                let p = s.closest_point(point);
+                (s.index, p, (p - point).sqr_magnitude())
+            })
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
+            .map(|(index, p, _)| (index, p))
+    }
+
+    #[inline]
+    pub(crate) fn spatial_index(&self) -> &RTree<NavSpatialObject> {
+        &self.rtree
+    }
+
+    #[inline]
+    pub(crate) fn annotations_map(&self) -> &HashMap<NavAnnotationID, NavAnnotation> {
+        &self.annotations
+    }
+
+    #[inline]
+    pub(crate) fn annotations_map_mut(&mut self) -> &mut HashMap<NavAnnotationID, NavAnnotation> {
+        &mut self.annotations
+    }
+
+    #[inline]
+    pub(crate) fn vertex_attributes_map(&self) -> &HashMap<String, Vec<Scalar>> {
+        &self.vertex_attributes
+    }
+
+    #[inline]
+    pub(crate) fn vertex_attributes_map_mut(&mut self) -> &mut HashMap<String, Vec<Scalar>> {
+        &mut self.vertex_attributes
     }
 
-    fn find_path_midpoints(&self, from: NavVec3, to: NavVec3, triangles: &[usize]) -> Vec<NavVec3> {
-        if triangles.len() == 2 {
-            let NavConnection(a, b) =
-                self.connections[&NavConnection(triangles[0] as u32, triangles[1] as u32)].1;
-            let a = self.vertices[a as usize];
-            let b = self.vertices[b as usize];
-            let n = self.spatials[triangles[0]].normal();
-            let m = self.spatials[triangles[1]].normal();
-            if n.dot(m) < 1.0 - ZERO_TRESHOLD || !NavVec3::is_line_between_points(from, to, a, b, n)
-            {
-                return vec![from, (a + b) * 0.5, to];
-            } else {
-                return vec![from, to];
-            }
-        }
-        let mut start = from;
-        let mut last_normal = self.spatials[triangles[0]].normal();
-        let mut points = Vec::with_capacity(triangles.len() + 1);
-        points.push(from);
-        for triplets in triangles.windows(3) {
-            let NavConnection(a, b) =
-                self.connections[&NavConnection(triplets[0] as u32, triplets[1] as u32)].1;
-            let a = self.vertices[a as usize];
-            let b = self.vertices[b as usize];
-            let point = (a + b) * 0.5;
-            let normal = self.spatials[triplets[1]].normal();
-            let old_last_normal = last_normal;
-            last_normal = normal;
-            if old_last_normal.dot(normal) < 1.0 - ZERO_TRESHOLD {
-                start = point;
-                points.push(start);
-            } else {
-                let NavConnection(c, d) =
-                    self.connections[&NavConnection(triplets[1] as u32, triplets[2] as u32)].1;
-                let c = self.vertices[c as usize];
-                let d = self.vertices[d as usize];
-                let end = (c + d) * 0.5;
-                if !NavVec3::is_line_between_points(start, end, a, b, normal) {
-                    start = point;
-                    points.push(start);
-                }
-            }
-        }
-        {
-            let NavConnection(a, b) = self.connections[&NavConnection(
-                triangles[triangles.len() - 2] as u32,
-                triangles[triangles.len() - 1] as u32,
-            )]
-                .1;
-            let a = self.vertices[a as usize];
-            let b = self.vertices[b as usize];
-            let n = self.spatials[triangles[triangles.len() - 2]].normal();
-            let m = self.spatials[triangles[triangles.len() - 1]].normal();
-            if n.dot(m) < 1.0 - ZERO_TRESHOLD
-                || !NavVec3::is_line_between_points(start, to, a, b, n)
-            {
-                points.push((a + b) * 0.5);
-            }
+    #[inline]
+    pub(crate) fn path_watcher(&self) -> &NavPathWatcher<usize> {
+        &self.path_watcher
+    }
+
+    #[inline]
+    pub(crate) fn path_watcher_mut(&mut self) -> &mut NavPathWatcher<usize> {
+        &mut self.path_watcher
+    }
+
+    #[inline]
+    pub(crate) fn spatial(&self, index: usize) -> &NavSpatialObject {
+        &self.spatials[index]
+    }
+
+    #[inline]
+    pub(crate) fn profiles(&self) -> &HashMap<NavProfileID, NavProfileData> {
+        &self.profiles
+    }
+
+    #[inline]
+    pub(crate) fn profiles_mut(&mut self) -> &mut HashMap<NavProfileID, NavProfileData> {
+        &mut self.profiles
+    }
+
+    /// Rebuild spatial index (AABB tree) used to accelerate point queries on triangles.
+    ///
+    /// Nav mesh keeps its spatial index up to date by itself, this is meant for cases where index
+    /// got lost or corrupted, e.g. after deserializing data edited by external tools.
+    pub fn rebuild_spatial_index(&mut self) {
+        let mut rtree = RTree::new();
+        for spatial in &self.spatials {
+            rtree.insert(spatial.clone());
         }
-        points.push(to);
-        points.dedup();
-        points
+        self.rtree = rtree;
     }
 
-    /// Find shortest path on nav mesh between two points.
+    /// Cast ray along nav mesh surface, walking triangles on straight line between two points.
     ///
     /// # Arguments
-    /// * `from` - query point from.
-    /// * `to` - query point to.
-    /// * `query` - query quality.
-    /// * `mode` - path finding quality.
+    /// * `from` - ray start point.
+    /// * `to` - ray target point.
+    /// * `query` - query quality used to find start triangle.
     ///
     /// # Returns
-    /// `Some` with path points on nav mesh and path length if found or `None` otherwise.
+    /// `Some` with raycast result or `None` if start triangle could not be found.
     ///
     /// # Example
     /// ```
@@ -873,10 +4316,10 @@ impl NavMesh {
     /// let vertices = vec![
     ///     (0.0, 0.0, 0.0).into(), // 0
     ///     (1.0, 0.0, 0.0).into(), // 1
-    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (2.0, 0.0, 0.0).into(), // 2
     ///     (0.0, 1.0, 0.0).into(), // 3
     ///     (1.0, 1.0, 0.0).into(), // 4
-    ///     (2.0, 1.0, 1.0).into(), // 5
+    ///     (2.0, 1.0, 0.0).into(), // 5
     /// ];
     /// let triangles = vec![
     ///     (0, 1, 4).into(), // 0
@@ -886,26 +4329,96 @@ impl NavMesh {
     /// ];
     ///
     /// let mesh = NavMesh::new(vertices, triangles).unwrap();
-    /// let path = mesh.find_path_triangles(1, 2).unwrap().0;
-    /// assert_eq!(path, vec![1, 0, 3, 2]);
+    /// let result = mesh
+    ///     .raycast((0.5, 0.5, 0.0).into(), (1.5, 0.5, 0.0).into(), NavQuery::Accuracy)
+    ///     .unwrap();
+    /// assert!(result.hit.is_none());
+    /// let result = mesh
+    ///     .raycast((0.5, 0.5, 0.0).into(), (0.5, 2.0, 0.0).into(), NavQuery::Accuracy)
+    ///     .unwrap();
+    /// let hit = result.hit.unwrap();
+    /// assert!(hit.point.same_as((0.5, 1.0, 0.0).into()));
     /// ```
-    #[inline]
-    pub fn find_path_triangles(&self, from: usize, to: usize) -> Option<(Vec<usize>, Scalar)> {
-        self.find_path_triangles_custom(from, to, |_, _, _| true)
+    pub fn raycast(&self, from: NavVec3, to: NavVec3, query: NavQuery) -> Option<NavRaycast> {
+        self.raycast_with_tolerance(from, to, query, self.tolerance)
     }
 
-    /// Find shortest path on nav mesh between two points, providing custom filtering function.
+    /// Cast ray along nav mesh surface like `raycast`, overriding nav mesh tolerances.
     ///
     /// # Arguments
-    /// * `from` - query point from.
-    /// * `to` - query point to.
-    /// * `query` - query quality.
-    /// * `mode` - path finding quality.
-    /// * `filter` - closure that gives you a connection distance squared, first triangle index
-    ///   and second triangle index.
+    /// * `from` - ray start point.
+    /// * `to` - ray target point.
+    /// * `query` - query quality used to find start triangle.
+    /// * `tolerance` - tolerances used instead of nav mesh ones.
     ///
     /// # Returns
-    /// `Some` with path points on nav mesh and path length if found or `None` otherwise.
+    /// `Some` with raycast result or `None` if start triangle could not be found.
+    pub fn raycast_with_tolerance(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        tolerance: NavTolerance,
+    ) -> Option<NavRaycast> {
+        let start = self.find_closest_triangle(from, query)?;
+        let from = self.spatials[start].closest_point(from);
+        let (triangles, reached) = self.walk_line(start, from, to, tolerance);
+        if reached {
+            return Some(NavRaycast {
+                triangles,
+                hit: None,
+            });
+        }
+        let index = *triangles.last()?;
+        let spatial = &self.spatials[index];
+        let up = spatial.normal();
+        let mut hit = NavRaycastHit {
+            point: from,
+            triangle: index,
+            edge: (spatial.a, spatial.b),
+        };
+        let mut furthest = -Scalar::INFINITY;
+        for (a, b) in [
+            (spatial.a, spatial.b),
+            (spatial.b, spatial.c),
+            (spatial.c, spatial.a),
+        ] {
+            let df = (b - a).cross(from - a).dot(up);
+            let dt = (b - a).cross(to - a).dot(up);
+            if (df - dt).abs() < tolerance.distance_sqr {
+                continue;
+            }
+            let t = df / (df - dt);
+            let epsilon = tolerance.angular;
+            if t <= furthest || !(-epsilon..=1.0 + epsilon).contains(&t) {
+                continue;
+            }
+            let point = NavVec3::unproject(from, to, t.clamp(0.0, 1.0));
+            if (-epsilon..=1.0 + epsilon).contains(&point.project(a, b)) {
+                furthest = t;
+                hit.point = spatial.closest_point(point);
+                hit.edge = (a, b);
+            }
+        }
+        Some(NavRaycast {
+            triangles,
+            hit: Some(hit),
+        })
+    }
+
+    /// Tells if agent can walk straight between two points, cheap check to do before falling
+    /// back to full path query.
+    ///
+    /// Straight line has to stay on walkable triangles and, for agents with radius, keep at least
+    /// `agent_radius` away from nav mesh boundary and blocked triangles.
+    ///
+    /// # Arguments
+    /// * `from` - start point.
+    /// * `to` - target point.
+    /// * `agent_radius` - agent radius, zero for plain surface raycast.
+    ///
+    /// # Returns
+    /// `true` if straight line between points is walkable.
     ///
     /// # Example
     /// ```
@@ -914,10 +4427,10 @@ impl NavMesh {
     /// let vertices = vec![
     ///     (0.0, 0.0, 0.0).into(), // 0
     ///     (1.0, 0.0, 0.0).into(), // 1
-    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (2.0, 0.0, 0.0).into(), // 2
     ///     (0.0, 1.0, 0.0).into(), // 3
     ///     (1.0, 1.0, 0.0).into(), // 4
-    ///     (2.0, 1.0, 1.0).into(), // 5
+    ///     (2.0, 1.0, 0.0).into(), // 5
     /// ];
     /// let triangles = vec![
     ///     (0, 1, 4).into(), // 0
@@ -927,55 +4440,124 @@ impl NavMesh {
     /// ];
     ///
     /// let mesh = NavMesh::new(vertices, triangles).unwrap();
-    /// let path = mesh.find_path_triangles_custom(
-    ///     1,
-    ///     2,
-    ///     |_dist_sqr, _first_idx, _second_idx| true
-    /// ).unwrap().0;
-    /// assert_eq!(path, vec![1, 0, 3, 2]);
+    /// let from = (0.5, 0.5, 0.0).into();
+    /// assert!(mesh.is_visible(from, (1.5, 0.5, 0.0).into(), 0.0));
+    /// assert!(mesh.is_visible(from, (1.5, 0.5, 0.0).into(), 0.25));
+    /// assert!(!mesh.is_visible(from, (1.5, 0.5, 0.0).into(), 0.75));
+    /// assert!(!mesh.is_visible(from, (0.5, 2.0, 0.0).into(), 0.0));
     /// ```
-    #[inline]
-    pub fn find_path_triangles_custom<F>(
-        &self,
-        from: usize,
-        to: usize,
-        mut filter: F,
-    ) -> Option<(Vec<usize>, Scalar)>
-    where
-        F: FnMut(Scalar, usize, usize) -> bool,
-    {
-        let to = self.nodes[to];
-        astar(
-            &self.graph,
-            self.nodes[from],
-            |n| n == to,
-            |e| {
-                let a = self.nodes_map[&e.source()];
-                let b = self.nodes_map[&e.target()];
-                let w = *e.weight();
-                if filter(w, a, b) {
-                    let a = self.areas[a].cost;
-                    let b = self.areas[b].cost;
-                    w * a * b
-                } else {
-                    SCALAR_MAX
+    pub fn is_visible(&self, from: NavVec3, to: NavVec3, agent_radius: Scalar) -> bool {
+        let start = match self.find_closest_triangle(from, NavQuery::Accuracy) {
+            Some(start) => start,
+            None => return false,
+        };
+        let from = self.spatials[start].closest_point(from);
+        let (triangles, reached) = self.walk_line(start, from, to, self.tolerance);
+        if !reached {
+            return false;
+        }
+        let radius = agent_radius.max(0.0);
+        if radius <= 0.0 {
+            return true;
+        }
+        let radius_sqr = radius * radius;
+        let extents = NavVec3::new(radius, radius, radius);
+        let to = self.spatials[*triangles.last().unwrap_or(&start)].closest_point(to);
+        self.rtree
+            .lookup_in_rectangle(&BoundingRect::from_corners(
+                &(from.min(to) - extents),
+                &(from.max(to) + extents),
+            ))
+            .into_iter()
+            .all(|s| {
+                if self.is_triangle_blocked(s.index) {
+                    let (a, b) = closest_points_segment_triangle(from, to, s.a, s.b, s.c);
+                    return (b - a).sqr_magnitude() >= radius_sqr;
                 }
-            },
-            |_| 0.0,
-        )
-        .map(|(c, v)| (iter!(v).map(|v| self.nodes_map[v]).collect(), c))
+                self.hard_edges
+                    .get(&s.index)
+                    .map(|edges| {
+                        edges.iter().all(|(ea, eb)| {
+                            let (a, b) = closest_points_on_segments(from, to, *ea, *eb);
+                            (b - a).sqr_magnitude() >= radius_sqr
+                        })
+                    })
+                    .unwrap_or(true)
+            })
+    }
+
+    /// Get island (connected part of nav mesh) of triangle. Islands are found when nav mesh gets
+    /// created and numbered in order of their first triangles.
+    ///
+    /// # Returns
+    /// `Some` with island index or `None` if triangle does not exist.
+    #[inline]
+    pub fn triangle_island(&self, index: usize) -> Option<usize> {
+        self.islands.get(index).copied()
+    }
+
+    /// Number of islands (connected parts) of nav mesh.
+    pub fn islands_count(&self) -> usize {
+        self.islands
+            .iter()
+            .max()
+            .map(|island| island + 1)
+            .unwrap_or(0)
+    }
+
+    /// Tells if triangles lie on the same island, so path between them might exist. Triangles
+    /// on different islands are never connected, that lets path queries fail without search.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (3.0, 0.0, 0.0).into(), // 4
+    ///     (4.0, 0.0, 0.0).into(), // 5
+    ///     (4.0, 1.0, 0.0).into(), // 6
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into(), (4, 5, 6).into()];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// assert_eq!(mesh.islands_count(), 2);
+    /// assert_eq!(mesh.triangle_island(2), Some(1));
+    /// assert!(mesh.are_triangles_connected(0, 1));
+    /// assert!(!mesh.are_triangles_connected(0, 2));
+    /// ```
+    pub fn are_triangles_connected(&self, from: usize, to: usize) -> bool {
+        if from >= self.triangles.len() || to >= self.triangles.len() {
+            return false;
+        }
+        match (self.triangle_island(from), self.triangle_island(to)) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
     }
 
+    /// Find triangles of every island of nav mesh, using islands found when nav mesh got
+    /// created.
     pub fn find_triangle_islands(&self) -> Vec<Vec<usize>> {
-        tarjan_scc(&self.graph)
-            .into_iter()
-            .map(|v| {
-                v.into_iter()
-                    .filter_map(|n| self.nodes_map.get(&n).copied())
-                    .collect::<Vec<_>>()
-            })
-            .filter(|v| !v.is_empty())
-            .collect()
+        let islands = self.triangles_islands();
+        let count = islands.iter().max().map(|island| island + 1).unwrap_or(0);
+        let mut result = vec![vec![]; count];
+        for (index, island) in islands.iter().enumerate() {
+            result[*island].push(index);
+        }
+        result
+    }
+
+    /// Island of every triangle, found again only if nav mesh was serialized by older version.
+    pub(crate) fn triangles_islands(&self) -> Cow<'_, [usize]> {
+        if self.islands.len() == self.triangles.len() {
+            Cow::Borrowed(&self.islands)
+        } else {
+            Cow::Owned(find_islands(&self.graph, &self.nodes, &self.nodes_map))
+        }
     }
 
     /// Find closest triangle on nav mesh closest to given point.
@@ -987,9 +4569,14 @@ impl NavMesh {
     /// # Returns
     /// `Some` with nav mesh triangle index if found or `None` otherwise.
     pub fn find_closest_triangle(&self, point: NavVec3, query: NavQuery) -> Option<usize> {
+        // distances to triangles are measured inside spatial tree queries, which take one
+        // triangle at a time, so there is nothing to hand over to `NavVec3` batch operations.
         match query {
             NavQuery::Accuracy => self.rtree.nearest_neighbor(&point).map(|t| t.index),
             NavQuery::ClosestFirst => self.rtree.close_neighbor(&point).map(|t| t.index),
+            NavQuery::Layered(step) => self
+                .find_layer_triangle(point, step)
+                .or_else(|| self.find_closest_triangle(point, NavQuery::Accuracy)),
             NavQuery::Closest => self
                 .rtree
                 .nearest_neighbors(&point)
@@ -1010,6 +4597,118 @@ impl NavMesh {
         }
     }
 
+    /// Find triangle of the highest walkable layer lying below query point along up axis.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `step` - maximal distance of layer above query point.
+    ///
+    /// # Returns
+    /// `Some` with nav mesh triangle index or `None` if there is no layer below query point.
+    pub fn find_layer_triangle(&self, point: NavVec3, step: Scalar) -> Option<usize> {
+        let up = self.up_axis.up();
+        let top = point + up * step.max(0.0);
+        let bottom = point - up * SCALAR_MAX.sqrt();
+        let candidates = self.rtree.lookup_in_rectangle(&BoundingRect::from_corners(
+            &top.min(bottom),
+            &top.max(bottom),
+        ));
+        let normals = candidates.iter().map(|s| s.normal()).collect::<Vec<_>>();
+        let offsets = candidates.iter().map(|s| s.a - point).collect::<Vec<_>>();
+        let verticals = NavVec3::dot_batch(&normals, &vec![up; normals.len()]);
+        let heights = NavVec3::dot_batch(&offsets, &normals);
+        candidates
+            .into_iter()
+            .zip(verticals.into_iter().zip(heights))
+            .filter_map(|(s, (vertical, height))| {
+                if vertical.abs() < self.tolerance.angular {
+                    return None;
+                }
+                let height = height / vertical;
+                let hit = point + up * height;
+                if height <= step.max(0.0)
+                    && s.closest_point(hit)
+                        .same_as_within(hit, self.tolerance.distance_sqr)
+                {
+                    Some((s.index, height))
+                } else {
+                    None
+                }
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index)
+    }
+
+    /// Find nav mesh triangle closest to point by walking triangles adjacency from hint
+    /// triangle, for example the one agent stood on in previous frame, which is much cheaper than
+    /// global nearest search when point moved only a few triangles away.
+    ///
+    /// Walk stops at triangle point lies on (above or below it) and falls back to
+    /// `find_closest_triangle` with `NavQuery::Accuracy` when it gets stuck (point is off nav mesh
+    /// or behind hole) or hint triangle does not exist.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `hint` - triangle index to start walk from.
+    ///
+    /// # Returns
+    /// `Some` with triangle index or `None` if nav mesh is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mut vertices = vec![];
+    /// let mut triangles = vec![];
+    /// for col in 0..=8 {
+    ///     vertices.push((col as Scalar, 0.0, 0.0).into());
+    ///     vertices.push((col as Scalar, 1.0, 0.0).into());
+    /// }
+    /// for col in 0..8 {
+    ///     let i = col * 2;
+    ///     triangles.push((i, i + 2, i + 3).into());
+    ///     triangles.push((i + 3, i + 1, i).into());
+    /// }
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let mut triangle = 0;
+    /// for step in 0..16 {
+    ///     let point = (0.3 + step as Scalar * 0.5, 0.25, 0.1).into();
+    ///     triangle = mesh.locate_from_hint(point, triangle).unwrap();
+    ///     assert_eq!(
+    ///         Some(triangle),
+    ///         mesh.find_closest_triangle(point, NavQuery::Accuracy)
+    ///     );
+    /// }
+    /// ```
+    pub fn locate_from_hint(&self, point: NavVec3, hint: usize) -> Option<usize> {
+        if hint >= self.spatials.len() {
+            return self.find_closest_triangle(point, NavQuery::Accuracy);
+        }
+        let mut current = hint;
+        let mut distance = self.spatials[current].distance2(&point);
+        for _ in 0..LOCATE_MAX_STEPS {
+            let spatial = &self.spatials[current];
+            let diff = point - spatial.closest_point(point);
+            let tangent = diff - spatial.normal() * diff.dot(spatial.normal());
+            if tangent.sqr_magnitude() < self.tolerance.distance_sqr {
+                return Some(current);
+            }
+            let next = self
+                .triangle_neighbors(current)
+                .map(|index| (index, self.spatials[index].distance2(&point)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            match next {
+                Some((index, d)) if d < distance => {
+                    current = index;
+                    distance = d;
+                }
+                _ => break,
+            }
+        }
+        self.find_closest_triangle(point, NavQuery::Accuracy)
+    }
+
     /// Find target point on nav mesh path.
     ///
     /// # Arguments
@@ -1057,7 +4756,7 @@ impl NavMesh {
                     .0
             }
         };
-        (p + offset).max(0.0).min(Self::path_length(path))
+        (p + offset).clamp(0.0, Self::path_length(path))
     }
 
     /// Find point on nav mesh path at given distance.
@@ -1124,3 +4823,121 @@ impl NavMesh {
         }
     }
 }
+
+#[cfg(feature = "serde")]
+fn spatial_index_options() -> impl bincode::Options {
+    use bincode::Options;
+    bincode::options().with_fixint_encoding()
+}
+
+#[cfg(feature = "serde")]
+fn encode_spatial_index(rtree: &RTree<NavSpatialObject>) -> Vec<u8> {
+    use bincode::Options;
+    spatial_index_options().serialize(rtree).unwrap_or_default()
+}
+
+#[cfg(not(feature = "serde"))]
+fn encode_spatial_index(_: &RTree<NavSpatialObject>) -> Vec<u8> {
+    vec![]
+}
+
+/// Decode stored spatial index, making sure it holds exactly given triangles, so data stored by
+/// other version of spatial index falls back to rebuilding it.
+#[cfg(feature = "serde")]
+fn decode_spatial_index(
+    bytes: &[u8],
+    spatials: &[NavSpatialObject],
+) -> Option<RTree<NavSpatialObject>> {
+    use bincode::Options;
+    if bytes.is_empty() {
+        return None;
+    }
+    let rtree = spatial_index_options()
+        .with_limit(bytes.len() as u64)
+        .deserialize::<RTree<NavSpatialObject>>(bytes)
+        .ok()?;
+    let mut found = vec![false; spatials.len()];
+    for spatial in rtree.iter() {
+        let expected = spatials.get(spatial.index)?;
+        if found[spatial.index]
+            || spatial.a != expected.a
+            || spatial.b != expected.b
+            || spatial.c != expected.c
+        {
+            return None;
+        }
+        found[spatial.index] = true;
+    }
+    if found.into_iter().all(|found| found) {
+        Some(rtree)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn decode_spatial_index(_: &[u8], _: &[NavSpatialObject]) -> Option<RTree<NavSpatialObject>> {
+    None
+}
+
+impl NavGraphSearch {
+    /// Find path between triangles with the same connections costs and landmarks heuristic as
+    /// `NavMesh::find_path`, counting search work for `NavStats`.
+    pub(crate) fn find_path_triangles(
+        &mut self,
+        mesh: &NavMesh,
+        from: usize,
+        to: usize,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        self.expansions = 0;
+        self.edges_visited = 0;
+        self.open_peak = 0;
+        let count = mesh.triangles().len();
+        if from >= count
+            || to >= count
+            || mesh.is_triangle_blocked(from)
+            || mesh.is_triangle_blocked(to)
+            || !mesh.are_triangles_connected(from, to)
+        {
+            return None;
+        }
+        self.find_path(
+            count,
+            from,
+            to,
+            |current| mesh.triangle_neighbors(current),
+            |current, next| mesh.connection_cost(current, next),
+            |triangle| mesh.estimate_path_cost(triangle, to),
+        )
+    }
+}
+
+/// Number islands of triangles graph in order of their first triangles.
+fn find_islands(
+    graph: &Graph<(), Scalar, Undirected>,
+    nodes: &[NodeIndex],
+    nodes_map: &HashMap<NodeIndex, usize>,
+) -> Vec<usize> {
+    let mut result = vec![usize::MAX; nodes.len()];
+    let mut count = 0;
+    let mut stack = vec![];
+    for first in 0..nodes.len() {
+        if result[first] != usize::MAX {
+            continue;
+        }
+        result[first] = count;
+        stack.push(first);
+        while let Some(current) = stack.pop() {
+            for node in graph.neighbors(nodes[current]) {
+                if let Some(next) = nodes_map.get(&node).copied() {
+                    if result[next] == usize::MAX {
+                        result[next] = count;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        count += 1;
+    }
+    result
+}
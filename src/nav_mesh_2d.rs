@@ -0,0 +1,256 @@
+use crate::{NavMesh, NavQuery, NavResult, NavTriangle, NavUpAxis, NavVec3, Scalar};
+
+/// 2D point (x, y).
+pub type NavPoint2D = (Scalar, Scalar);
+
+/// Nav mesh for top-down 2D games, taking and returning points on X/Y plane.
+///
+/// Points are located with uniform grid and 2D triangle tests instead of 3D spatial index and
+/// planes projections. Path finding runs on underlying 3D nav mesh lying at zero height with Z up
+/// axis, which is available for all other queries.
+#[derive(Debug, Clone)]
+pub struct NavMesh2D {
+    mesh: NavMesh,
+    min: NavPoint2D,
+    cell_size: Scalar,
+    cols: usize,
+    rows: usize,
+    // [cell: [triangle index]]
+    cells: Vec<Vec<u32>>,
+}
+
+impl NavMesh2D {
+    /// Create new 2D nav mesh from vertices and triangles.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of vertices points.
+    /// * `triangles` - list of vertices indices that produces triangles.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh object or `Err` with `Error::TriangleVerticeIndexOutOfBounds` if input
+    /// data is invalid.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0), // 0
+    ///     (1.0, 0.0), // 1
+    ///     (2.0, 0.0), // 2
+    ///     (0.0, 1.0), // 3
+    ///     (1.0, 1.0), // 4
+    ///     (2.0, 1.0), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh2D::new(vertices, triangles).unwrap();
+    /// assert_eq!(mesh.find_triangle((1.75, 0.25)), Some(2));
+    /// let path = mesh.find_path((0.25, 0.75), (1.75, 0.25)).unwrap();
+    /// assert_eq!(path, vec![(0.25, 0.75), (1.75, 0.25)]);
+    /// ```
+    pub fn new(vertices: Vec<NavPoint2D>, triangles: Vec<NavTriangle>) -> NavResult<Self> {
+        let vertices = vertices
+            .into_iter()
+            .map(|(x, y)| NavVec3::new(x, y, 0.0))
+            .collect();
+        Ok(Self::from_flat_nav_mesh(NavMesh::new(vertices, triangles)?))
+    }
+
+    /// Create 2D nav mesh by flattening 3D one along its up axis. Areas properties are kept,
+    /// obstacles are not.
+    ///
+    /// # Arguments
+    /// * `mesh` - 3D nav mesh, its ground plane coordinates become 2D points.
+    pub fn from_nav_mesh(mesh: &NavMesh) -> NavResult<Self> {
+        let vertices = mesh
+            .vertices()
+            .iter()
+            .map(|v| {
+                let (x, y) = Self::flatten(*v, mesh.up_axis());
+                NavVec3::new(x, y, 0.0)
+            })
+            .collect();
+        let mut result = NavMesh::new(vertices, mesh.triangles().to_vec())?;
+        for (index, area) in mesh.areas().iter().enumerate() {
+            result.set_area_cost(index, area.cost);
+            result.set_area_type(index, area.area_type);
+            result.set_area_flags(index, area.flags);
+            result.set_area_clearance(index, area.clearance);
+        }
+        result.set_tolerance(mesh.tolerance());
+        Ok(Self::from_flat_nav_mesh(result))
+    }
+
+    fn from_flat_nav_mesh(mesh: NavMesh) -> Self {
+        let vertices = mesh.vertices();
+        let mut min = (Scalar::INFINITY, Scalar::INFINITY);
+        let mut max = (Scalar::NEG_INFINITY, Scalar::NEG_INFINITY);
+        for v in vertices {
+            min = (min.0.min(v.x), min.1.min(v.y));
+            max = (max.0.max(v.x), max.1.max(v.y));
+        }
+        let count = mesh.triangles().len();
+        if count == 0 {
+            return Self {
+                mesh,
+                min: (0.0, 0.0),
+                cell_size: 1.0,
+                cols: 0,
+                rows: 0,
+                cells: vec![],
+            };
+        }
+        // cells roughly as big as average triangle.
+        let area = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+        let cell_size = (area / count as Scalar)
+            .sqrt()
+            .max((max.0 - min.0).max(max.1 - min.1) / 1024.0)
+            .max(mesh.tolerance().distance);
+        let cols = ((max.0 - min.0) / cell_size) as usize + 1;
+        let rows = ((max.1 - min.1) / cell_size) as usize + 1;
+        let mut result = Self {
+            mesh,
+            min,
+            cell_size,
+            cols,
+            rows,
+            cells: vec![vec![]; cols * rows],
+        };
+        for (index, t) in result.mesh.triangles().iter().enumerate() {
+            let vertices = result.mesh.vertices();
+            let (a, b, c) = (
+                vertices[t.first as usize],
+                vertices[t.second as usize],
+                vertices[t.third as usize],
+            );
+            let (col_min, row_min) = result.cell(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y));
+            let (col_max, row_max) = result.cell(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y));
+            for row in row_min..=row_max {
+                for col in col_min..=col_max {
+                    result.cells[row * cols + col].push(index as u32);
+                }
+            }
+        }
+        result
+    }
+
+    /// Underlying 3D nav mesh, lying at zero height with Z up axis.
+    #[inline]
+    pub fn nav_mesh(&self) -> &NavMesh {
+        &self.mesh
+    }
+
+    #[inline]
+    pub fn into_nav_mesh(self) -> NavMesh {
+        self.mesh
+    }
+
+    /// Find triangle that contains point.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with triangle index or `None` if point lies outside of nav mesh.
+    pub fn find_triangle(&self, point: NavPoint2D) -> Option<usize> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let (col, row) = self.cell(point.0, point.1);
+        let epsilon = self.mesh.tolerance().distance_sqr;
+        let vertices = self.mesh.vertices();
+        self.cells[row * self.cols + col]
+            .iter()
+            .map(|index| *index as usize)
+            .find(|index| {
+                let t = self.mesh.triangles()[*index];
+                let a = vertices[t.first as usize];
+                let b = vertices[t.second as usize];
+                let c = vertices[t.third as usize];
+                let side = |p: NavVec3, q: NavVec3| {
+                    (q.x - p.x) * (point.1 - p.y) - (q.y - p.y) * (point.0 - p.x)
+                };
+                let (ab, bc, ca) = (side(a, b), side(b, c), side(c, a));
+                let negative = ab < -epsilon || bc < -epsilon || ca < -epsilon;
+                let positive = ab > epsilon || bc > epsilon || ca > epsilon;
+                !(negative && positive)
+            })
+    }
+
+    /// Find closest point on nav mesh.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with closest point and triangle it lies on or `None` if nav mesh is empty.
+    pub fn closest_point(&self, point: NavPoint2D) -> Option<(usize, NavPoint2D)> {
+        if let Some(index) = self.find_triangle(point) {
+            return Some((index, point));
+        }
+        let query = NavVec3::new(point.0, point.1, 0.0);
+        let index = self.mesh.find_closest_triangle(query, NavQuery::Accuracy)?;
+        let closest = self.mesh.spatial(index).closest_point(query);
+        Some((index, (closest.x, closest.y)))
+    }
+
+    /// Find shortest path on nav mesh between two points. Points lying outside of nav mesh are
+    /// moved to closest points on it.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    ///
+    /// # Returns
+    /// `Some` with path points if found or `None` otherwise.
+    pub fn find_path(&self, from: NavPoint2D, to: NavPoint2D) -> Option<Vec<NavPoint2D>> {
+        let (start, from) = self.closest_point(from)?;
+        let (end, to) = self.closest_point(to)?;
+        let epsilon = self.mesh.tolerance().distance;
+        if (from.0 - to.0).abs() <= epsilon && (from.1 - to.1).abs() <= epsilon {
+            return None;
+        }
+        let (triangles, _) = self.mesh.find_path_triangles(start, end)?;
+        let points = self.mesh.string_pull(
+            NavVec3::new(from.0, from.1, 0.0),
+            NavVec3::new(to.0, to.1, 0.0),
+            &triangles,
+            0.0,
+        )?;
+        Some(points.into_iter().map(|v| (v.x, v.y)).collect())
+    }
+
+    /// Convert 2D point into 3D point of underlying nav mesh.
+    #[inline]
+    pub fn to_3d(point: NavPoint2D) -> NavVec3 {
+        NavVec3::new(point.0, point.1, 0.0)
+    }
+
+    /// Convert 3D point into 2D point, dropping its up axis coordinate.
+    ///
+    /// # Arguments
+    /// * `point` - 3D point.
+    /// * `up_axis` - axis pointing up in 3D space.
+    #[inline]
+    pub fn flatten(point: NavVec3, up_axis: NavUpAxis) -> NavPoint2D {
+        match up_axis {
+            NavUpAxis::Z => (point.x, point.y),
+            NavUpAxis::Y => (point.x, point.z),
+        }
+    }
+
+    fn cell(&self, x: Scalar, y: Scalar) -> (usize, usize) {
+        let col = ((x - self.min.0) / self.cell_size).max(0.0) as usize;
+        let row = ((y - self.min.1) / self.cell_size).max(0.0) as usize;
+        (
+            col.min(self.cols.saturating_sub(1)),
+            row.min(self.rows.saturating_sub(1)),
+        )
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{NavGraphSearch, NavMesh, NavPath, NavPathMode, NavQuery, NavVec3};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Description of single path query performed by `NavMesh::find_paths_batch`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavQueryDesc {
+    /// Query point from.
+    pub from: NavVec3,
+    /// Query point to.
+    pub to: NavVec3,
+    /// Query quality.
+    pub query: NavQuery,
+    /// Path finding quality.
+    pub mode: NavPathMode,
+}
+
+impl NavQueryDesc {
+    pub fn new(from: NavVec3, to: NavVec3, query: NavQuery, mode: NavPathMode) -> Self {
+        Self {
+            from,
+            to,
+            query,
+            mode,
+        }
+    }
+}
+
+impl NavMesh {
+    /// Find paths for many queries at once, for example for all units ordered to move in RTS.
+    ///
+    /// With `parallel` feature queries are spread across threads, all of them reading the same
+    /// nav mesh data. Every thread allocates search state once and reuses it for all queries it
+    /// performs. Path cache (when enabled) is shared by all of them.
+    ///
+    /// # Arguments
+    /// * `queries` - list of queries.
+    ///
+    /// # Returns
+    /// List of results in the same order as queries, `Some` with path if found or `None`
+    /// otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let paths = mesh.find_paths_batch(&[
+    ///     NavQueryDesc::new(
+    ///         (0.25, 0.75, 0.0).into(),
+    ///         (1.75, 0.25, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     ),
+    ///     NavQueryDesc::new(
+    ///         (0.25, 0.75, 0.0).into(),
+    ///         (0.25, 0.75, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     ),
+    /// ]);
+    /// assert_eq!(paths.len(), 2);
+    /// assert_eq!(paths[0].as_ref().unwrap().triangles, vec![1, 0, 3, 2]);
+    /// assert!(paths[1].is_none());
+    /// ```
+    pub fn find_paths_batch(&self, queries: &[NavQueryDesc]) -> Vec<Option<NavPath>> {
+        #[cfg(feature = "parallel")]
+        {
+            queries
+                .par_iter()
+                .map_init(NavGraphSearch::default, |scratch, desc| {
+                    self.find_batch_path(scratch, desc)
+                })
+                .collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let mut scratch = NavGraphSearch::default();
+            queries
+                .iter()
+                .map(|desc| self.find_batch_path(&mut scratch, desc))
+                .collect()
+        }
+    }
+
+    fn find_batch_path(
+        &self,
+        scratch: &mut NavGraphSearch,
+        desc: &NavQueryDesc,
+    ) -> Option<NavPath> {
+        self.find_nav_path_with(desc.from, desc.to, desc.query, desc.mode, |start, end| {
+            self.find_path_triangles_cached(start, end, || {
+                scratch.find_path_triangles(self, start, end)
+            })
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    NavMesh, NavResult, NavTolerance, NavTriangle, NavUpAxis, NavVec3, NavWeldReport, Scalar,
+};
+
+/// Builder of nav mesh objects, collecting construction options so new ones can be added without
+/// changing `NavMesh::new` signature.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (1.0, 0.0, 1.0).into(), // 2
+///     (1.0, 0.0, 1.0).into(), // 3
+///     (0.0, 0.0, 1.0).into(), // 4
+/// ];
+/// let triangles = vec![(0, 2, 1).into(), (3, 0, 4).into()];
+///
+/// let (mesh, report) = NavMeshBuilder::new(vertices, triangles)
+///     .with_welding(true)
+///     .with_up_axis(NavUpAxis::Y)
+///     .with_area_cost(2.0)
+///     .with_landmarks(1)
+///     .build_with_report()
+///     .unwrap();
+/// assert_eq!(report.welded_vertices, vec![(2, 3)]);
+/// assert_eq!(mesh.up_axis(), NavUpAxis::Y);
+/// assert_eq!(mesh.areas()[1].cost, 2.0);
+/// assert_eq!(mesh.landmarks().count(), 1);
+/// assert_eq!(mesh.find_triangle_islands().len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NavMeshBuilder {
+    vertices: Vec<NavVec3>,
+    triangles: Vec<NavTriangle>,
+    welding: bool,
+    tolerance: NavTolerance,
+    up_axis: NavUpAxis,
+    area_cost: Option<Scalar>,
+    area_type: Option<u8>,
+    area_flags: Option<u16>,
+    landmarks: usize,
+    path_cache_capacity: usize,
+}
+
+impl NavMeshBuilder {
+    /// Create builder with options matching `NavMesh::new`.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of vertices points.
+    /// * `triangles` - list of vertices indices that produces triangles.
+    pub fn new(vertices: Vec<NavVec3>, triangles: Vec<NavTriangle>) -> Self {
+        Self {
+            vertices,
+            triangles,
+            welding: false,
+            tolerance: Default::default(),
+            up_axis: Default::default(),
+            area_cost: None,
+            area_type: None,
+            area_flags: None,
+            landmarks: 0,
+            path_cache_capacity: 0,
+        }
+    }
+
+    /// Set if vertices within weld distance get welded and degenerate and duplicate triangles
+    /// dropped, see `NavMesh::new_welded`.
+    pub fn with_welding(mut self, welding: bool) -> Self {
+        self.welding = welding;
+        self
+    }
+
+    /// Enable welding of vertices closer than given distance.
+    pub fn with_weld_distance(mut self, distance: Scalar) -> Self {
+        self.welding = true;
+        self.tolerance.weld_distance = distance;
+        self
+    }
+
+    /// Set tolerances of nav mesh geometry tests.
+    pub fn with_tolerance(mut self, tolerance: NavTolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set axis pointing up.
+    pub fn with_up_axis(mut self, up_axis: NavUpAxis) -> Self {
+        self.up_axis = up_axis;
+        self
+    }
+
+    /// Set cost of all areas.
+    pub fn with_area_cost(mut self, cost: Scalar) -> Self {
+        self.area_cost = Some(cost);
+        self
+    }
+
+    /// Set type of all areas.
+    pub fn with_area_type(mut self, area_type: u8) -> Self {
+        self.area_type = Some(area_type);
+        self
+    }
+
+    /// Set flags of all areas.
+    pub fn with_area_flags(mut self, flags: u16) -> Self {
+        self.area_flags = Some(flags);
+        self
+    }
+
+    /// Set number of landmarks to precompute, see `NavMesh::precompute_landmarks`.
+    pub fn with_landmarks(mut self, count: usize) -> Self {
+        self.landmarks = count;
+        self
+    }
+
+    /// Set capacity of paths cache, see `NavMesh::set_path_cache_capacity`.
+    pub fn with_path_cache_capacity(mut self, capacity: usize) -> Self {
+        self.path_cache_capacity = capacity;
+        self
+    }
+
+    #[inline]
+    pub fn welding(&self) -> bool {
+        self.welding
+    }
+
+    #[inline]
+    pub fn tolerance(&self) -> NavTolerance {
+        self.tolerance
+    }
+
+    #[inline]
+    pub fn up_axis(&self) -> NavUpAxis {
+        self.up_axis
+    }
+
+    #[inline]
+    pub fn landmarks(&self) -> usize {
+        self.landmarks
+    }
+
+    #[inline]
+    pub fn path_cache_capacity(&self) -> usize {
+        self.path_cache_capacity
+    }
+
+    /// Build nav mesh.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh object or `Err` with `Error::TriangleVerticeIndexOutOfBounds` if input
+    /// data is invalid or `Error::NoWalkableSurface` if welding left no triangle.
+    pub fn build(self) -> NavResult<NavMesh> {
+        self.build_with_report().map(|(mesh, _)| mesh)
+    }
+
+    /// Build nav mesh, reporting changes made to input data.
+    ///
+    /// # Returns
+    /// Same as `build` together with report of changes, that is unchanged if welding is disabled.
+    pub fn build_with_report(self) -> NavResult<(NavMesh, NavWeldReport)> {
+        let (mut mesh, report) = if self.welding {
+            NavMesh::new_welded(self.vertices, self.triangles, self.tolerance)?
+        } else {
+            let report = NavWeldReport {
+                vertices_map: (0..self.vertices.len() as u32).collect(),
+                triangles_map: (0..self.triangles.len()).map(Some).collect(),
+                ..Default::default()
+            };
+            let mut mesh = NavMesh::new(self.vertices, self.triangles)?;
+            mesh.set_tolerance(self.tolerance);
+            (mesh, report)
+        };
+        mesh.set_up_axis(self.up_axis);
+        for index in 0..mesh.triangles().len() {
+            if let Some(cost) = self.area_cost {
+                mesh.set_area_cost(index, cost);
+            }
+            if let Some(area_type) = self.area_type {
+                mesh.set_area_type(index, area_type);
+            }
+            if let Some(flags) = self.area_flags {
+                mesh.set_area_flags(index, flags);
+            }
+        }
+        mesh.set_path_cache_capacity(self.path_cache_capacity);
+        // landmarks come last, since they depend on areas costs.
+        if self.landmarks > 0 {
+            mesh.precompute_landmarks(self.landmarks);
+        }
+        Ok((mesh, report))
+    }
+}
+
+impl NavMesh {
+    /// Start building nav mesh with construction options, see `NavMeshBuilder`.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of vertices points.
+    /// * `triangles` - list of vertices indices that produces triangles.
+    #[inline]
+    pub fn builder(vertices: Vec<NavVec3>, triangles: Vec<NavTriangle>) -> NavMeshBuilder {
+        NavMeshBuilder::new(vertices, triangles)
+    }
+}
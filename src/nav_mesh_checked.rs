@@ -0,0 +1,721 @@
+use crate::{
+    Error, NavAreaFilter, NavMesh, NavPath, NavPathCorner, NavPathMode, NavPathSmoothing,
+    NavProfileID, NavQuery, NavQueryBudget, NavQueryFilter, NavResult, NavStats, NavTolerance,
+    NavVec3, Scalar,
+};
+
+impl NavMesh {
+    /// Same as `NavMesh::find_path_with_tolerance`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `tolerance` - tolerances used instead of nav mesh ones.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (3.0, 0.0, 0.0).into(), // 4
+    ///     (4.0, 0.0, 0.0).into(), // 5
+    ///     (4.0, 1.0, 0.0).into(), // 6
+    ///     (3.0, 1.0, 0.0).into(), // 7
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 2).into(),
+    ///     (2, 3, 0).into(),
+    ///     (4, 5, 6).into(),
+    ///     (6, 7, 4).into(),
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// assert!(matches!(
+    ///     mesh.find_path_with_tolerance_checked(
+    ///         (0.25, 0.5, 0.0).into(),
+    ///         (3.5, 0.5, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///         NavTolerance::default(),
+    ///     ),
+    ///     Err(Error::Unreachable { .. })
+    /// ));
+    /// assert!(matches!(
+    ///     mesh.find_path_triangles_checked(0, 4),
+    ///     Err(Error::TriangleIndexOutOfBounds(4, 4))
+    /// ));
+    /// ```
+    pub fn find_path_with_tolerance_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        tolerance: NavTolerance,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_with_tolerance(from, to, query, mode, tolerance)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_custom`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `filter` - closure that gets connection distance squared, start and end triangle index.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_custom_checked<F>(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        filter: F,
+    ) -> NavResult<Vec<NavVec3>>
+    where
+        F: FnMut(Scalar, usize, usize) -> bool,
+    {
+        self.find_path_custom(from, to, query, mode, filter)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_filtered`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `filter` - area filter of agent.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_filtered_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        filter: &NavAreaFilter,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_filtered(from, to, query, mode, filter)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_with_filter`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `filter` - query filter of agent.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_with_filter_checked<Q>(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        filter: &Q,
+    ) -> NavResult<Vec<NavVec3>>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.find_path_with_filter(from, to, query, mode, filter)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_smoothed`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `smoothing` - smoothing applied to found path.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_smoothed_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        smoothing: NavPathSmoothing,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_smoothed(from, to, query, mode, smoothing)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_to_any`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `goals` - list of goal points.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Ok` with index of reached goal and path points on nav mesh or `Err` with the same errors
+    /// as `NavMesh::find_path_checked`, reported for goal closest to `from` point.
+    pub fn find_path_to_any_checked(
+        &self,
+        from: NavVec3,
+        goals: &[NavVec3],
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> NavResult<(usize, Vec<NavVec3>)> {
+        self.find_path_to_any(from, goals, query, mode)
+            .ok_or_else(|| {
+                let to = goals
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| {
+                        (*a - from)
+                            .sqr_magnitude()
+                            .total_cmp(&(*b - from).sqr_magnitude())
+                    })
+                    .unwrap_or(from);
+                self.path_error(from, to, query)
+            })
+    }
+
+    /// Same as `NavMesh::find_path_detailed`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Ok` with detailed path or `Err` with the same errors as `NavMesh::find_path_checked`.
+    pub fn find_path_detailed_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> NavResult<NavPath> {
+        self.find_path_detailed(from, to, query, mode)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_with_corners`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Ok` with detailed path and its corners or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_with_corners_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+    ) -> NavResult<(NavPath, Vec<NavPathCorner>)> {
+        self.find_path_with_corners(from, to, query)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_partial`, but reports why path could not be found.
+    ///
+    /// Partial path is still a found path, so only start point can make this query fail.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Ok` with path or `Err` with `Error::StartNotWalkable`.
+    pub fn find_path_partial_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> NavResult<NavPath> {
+        self.find_path_partial(from, to, query, mode)
+            .ok_or(Error::StartNotWalkable(from))
+    }
+
+    /// Same as `NavMesh::find_path_with_radius`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `radius` - agent radius.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_with_radius_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        radius: Scalar,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_with_radius(from, to, query, radius)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_weighted`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `weight` - heuristic weight, 1 gives optimal path, bigger values give faster searches.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_weighted_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        weight: Scalar,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_weighted(from, to, query, mode, weight)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_anytime`, but reports why path could not be found.
+    ///
+    /// Running out of budget before any path was found is reported as `Error::GoalUnreachable`
+    /// with start point as the nearest reachable one.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `weight` - initial heuristic weight.
+    /// * `budget` - search budget.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_anytime_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        weight: Scalar,
+        budget: NavQueryBudget,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_anytime(from, to, query, mode, weight, budget)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_bidirectional`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_bidirectional_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_bidirectional(from, to, query, mode)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_for_profile`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `id` - agent profile identifier.
+    ///
+    /// # Returns
+    /// `Ok` with path points on nav mesh or `Err` with the same errors as
+    /// `NavMesh::find_path_checked`.
+    pub fn find_path_for_profile_checked(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        id: NavProfileID,
+    ) -> NavResult<Vec<NavVec3>> {
+        self.find_path_for_profile(from, to, query, id)
+            .ok_or_else(|| self.path_error(from, to, query))
+    }
+
+    /// Same as `NavMesh::find_path_triangles`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost, or `Err` with `Error::TriangleIndexOutOfBounds`
+    /// if either triangle does not exist, otherwise the same errors as
+    /// `NavMesh::find_path_checked` (with triangles centers as query points).
+    pub fn find_path_triangles_checked(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> NavResult<(Vec<usize>, Scalar)> {
+        self.find_path_triangles(from, to)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_with_stats`, but reports why path could not be
+    /// found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    ///
+    /// # Returns
+    /// Pair of search result with the same errors as `NavMesh::find_path_triangles_checked`
+    /// and search statistics.
+    pub fn find_path_triangles_with_stats_checked(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> (NavResult<(Vec<usize>, Scalar)>, NavStats) {
+        let (path, stats) = self.find_path_triangles_with_stats(from, to);
+        (
+            path.ok_or_else(|| self.path_triangles_error(from, to)),
+            stats,
+        )
+    }
+
+    /// Same as `NavMesh::find_path_triangles_custom`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `filter` - closure that gets connection distance squared, start and end triangle index.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_custom_checked<F>(
+        &self,
+        from: usize,
+        to: usize,
+        filter: F,
+    ) -> NavResult<(Vec<usize>, Scalar)>
+    where
+        F: FnMut(Scalar, usize, usize) -> bool,
+    {
+        self.find_path_triangles_custom(from, to, filter)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_filtered`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `filter` - area filter of agent.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_filtered_checked(
+        &self,
+        from: usize,
+        to: usize,
+        filter: &NavAreaFilter,
+    ) -> NavResult<(Vec<usize>, Scalar)> {
+        self.find_path_triangles_filtered(from, to, filter)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_with_filter`, but reports why path could not be
+    /// found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `filter` - query filter of agent.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_with_filter_checked<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        filter: &Q,
+    ) -> NavResult<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.find_path_triangles_with_filter(from, to, filter)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_weighted`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `weight` - heuristic weight, 1 gives optimal path, bigger values give faster searches.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_weighted_checked(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+    ) -> NavResult<(Vec<usize>, Scalar)> {
+        self.find_path_triangles_weighted(from, to, weight)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_weighted_with_filter`, but reports why path could
+    /// not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `weight` - heuristic weight, 1 gives optimal path, bigger values give faster searches.
+    /// * `filter` - query filter of agent.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_weighted_with_filter_checked<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        filter: &Q,
+    ) -> NavResult<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.find_path_triangles_weighted_with_filter(from, to, weight, filter)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_anytime`, but reports why path could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `weight` - initial heuristic weight.
+    /// * `budget` - search budget.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_anytime_checked(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        budget: NavQueryBudget,
+    ) -> NavResult<(Vec<usize>, Scalar)> {
+        self.find_path_triangles_anytime(from, to, weight, budget)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_anytime_with_filter`, but reports why path could
+    /// not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `weight` - initial heuristic weight.
+    /// * `budget` - search budget.
+    /// * `filter` - query filter of agent.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_anytime_with_filter_checked<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        budget: NavQueryBudget,
+        filter: &Q,
+    ) -> NavResult<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.find_path_triangles_anytime_with_filter(from, to, weight, budget, filter)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_bidirectional`, but reports why path could not be
+    /// found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_bidirectional_checked(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> NavResult<(Vec<usize>, Scalar)> {
+        self.find_path_triangles_bidirectional(from, to)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_bidirectional_with_filter`, but reports why path
+    /// could not be found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `filter` - query filter of agent.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_bidirectional_with_filter_checked<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        filter: &Q,
+    ) -> NavResult<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.find_path_triangles_bidirectional_with_filter(from, to, filter)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Same as `NavMesh::find_path_triangles_for_profile`, but reports why path could not be
+    /// found.
+    ///
+    /// # Arguments
+    /// * `from` - start triangle index.
+    /// * `to` - end triangle index.
+    /// * `id` - agent profile identifier.
+    ///
+    /// # Returns
+    /// `Ok` with path triangles and path cost or `Err` with the same errors as
+    /// `NavMesh::find_path_triangles_checked`.
+    pub fn find_path_triangles_for_profile_checked(
+        &self,
+        from: usize,
+        to: usize,
+        id: NavProfileID,
+    ) -> NavResult<(Vec<usize>, Scalar)> {
+        self.find_path_triangles_for_profile(from, to, id)
+            .ok_or_else(|| self.path_triangles_error(from, to))
+    }
+
+    /// Tells why path between two points was not found.
+    fn path_error(&self, from: NavVec3, to: NavVec3, query: NavQuery) -> Error {
+        match self.find_closest_triangle(from, query) {
+            Some(start) if !self.is_triangle_blocked(start) => {
+                let end = self.find_closest_triangle(to, query);
+                self.reachability_error(start, end, from, to)
+            }
+            _ => Error::StartNotWalkable(from),
+        }
+    }
+
+    /// Tells why path between two triangles was not found.
+    fn path_triangles_error(&self, from: usize, to: usize) -> Error {
+        let count = self.triangles().len();
+        if from >= count {
+            return Error::TriangleIndexOutOfBounds(from, count);
+        }
+        if to >= count {
+            return Error::TriangleIndexOutOfBounds(to, count);
+        }
+        let (start, end) = (self.areas()[from].center, self.areas()[to].center);
+        if self.is_triangle_blocked(from) {
+            return Error::StartNotWalkable(start);
+        }
+        self.reachability_error(from, Some(to), start, end)
+    }
+
+    /// Tells why goal is not reachable from walkable start triangle: either it lies on other
+    /// island, or the nearest point reachable by walking nav mesh is reported. When walking
+    /// reaches goal triangle, it is query own constraints that rejected path, so start point is
+    /// the only one known to be reachable.
+    fn reachability_error(
+        &self,
+        start: usize,
+        end: Option<usize>,
+        from: NavVec3,
+        to: NavVec3,
+    ) -> Error {
+        if let Some(end) = end {
+            if let (Some(from_island), Some(to_island)) =
+                (self.triangle_island(start), self.triangle_island(end))
+            {
+                if from_island != to_island {
+                    return Error::Unreachable {
+                        from_island,
+                        to_island,
+                    };
+                }
+            }
+        }
+        let reachable = self.find_reachable_triangles(start);
+        if end.is_some_and(|end| reachable.contains(&end)) {
+            return Error::GoalUnreachable(from);
+        }
+        let nearest = reachable
+            .into_iter()
+            .filter_map(|index| self.closest_point_on_triangle(index, to))
+            .min_by(|a, b| {
+                (*a - to)
+                    .sqr_magnitude()
+                    .total_cmp(&(*b - to).sqr_magnitude())
+            })
+            .unwrap_or(from);
+        Error::GoalUnreachable(nearest)
+    }
+}
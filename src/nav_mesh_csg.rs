@@ -0,0 +1,492 @@
+use crate::{
+    nav_geom::{clip_polygon, polygon_area},
+    Error, NavConnection, NavFanTriangulator, NavMesh, NavObstacle, NavResult, NavTolerance,
+    NavTriangle, NavTriangulator, NavVec3, Scalar, ZERO_TRESHOLD,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Distance under which vertices of resulting nav mesh are welded together.
+pub(crate) const WELD_TRESHOLD: Scalar = 1.0e-4;
+
+// (source nav mesh index, source triangle index)
+type NavSource = (usize, usize);
+
+impl NavMesh {
+    /// Combine two nav meshes into one, welding their coincident vertices and splitting edges
+    /// that other mesh vertices lie on, so triangles get connected along shared boundary.
+    ///
+    /// Overlapping triangles are kept as they are, so it is meant to join neighboring parts, like
+    /// separately baked chunks. Areas properties are kept, obstacles are not.
+    ///
+    /// # Arguments
+    /// * `other` - nav mesh to combine with.
+    ///
+    /// # Returns
+    /// Combined nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let a = NavMesh::new(
+    ///     vec![
+    ///         (0.0, 0.0, 0.0).into(),
+    ///         (1.0, 0.0, 0.0).into(),
+    ///         (1.0, 1.0, 0.0).into(),
+    ///         (0.0, 1.0, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 2).into(), (2, 3, 0).into()],
+    /// )
+    /// .unwrap();
+    /// // right chunk has its left edge split in the middle.
+    /// let b = NavMesh::new(
+    ///     vec![
+    ///         (1.0, 0.0, 0.0).into(),
+    ///         (2.0, 0.0, 0.0).into(),
+    ///         (2.0, 1.0, 0.0).into(),
+    ///         (1.0, 1.0, 0.0).into(),
+    ///         (1.0, 0.5, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 4).into(), (1, 2, 4).into(), (2, 3, 4).into()],
+    /// )
+    /// .unwrap();
+    ///
+    /// let mesh = a.union(&b).unwrap();
+    /// assert_eq!(mesh.find_triangle_islands().len(), 1);
+    /// assert!(mesh
+    ///     .find_path(
+    ///         (0.25, 0.5, 0.0).into(),
+    ///         (1.75, 0.5, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .is_some());
+    /// ```
+    pub fn union(&self, other: &Self) -> NavResult<Self> {
+        let meshes = [self, other];
+        let pieces = meshes
+            .iter()
+            .enumerate()
+            .flat_map(|(source, mesh)| {
+                (0..mesh.triangles().len())
+                    .map(move |index| (mesh.triangle_polygon(index), (source, index)))
+            })
+            .collect();
+        Self::from_pieces(&meshes, pieces)
+    }
+
+    /// Merge other nav mesh into this one, connecting their boundary edges whose vertices lie
+    /// within weld distance, for composing streamed world chunks at runtime.
+    ///
+    /// Unlike `union` only boundary vertices get welded, so inner geometry of both meshes stays
+    /// untouched. Boundary edges that other mesh vertices lie on get split to connect with them.
+    /// Areas properties are kept, obstacles are not.
+    ///
+    /// # Arguments
+    /// * `other` - nav mesh to merge into this one.
+    /// * `weld_epsilon` - maximal distance between boundary vertices that get welded.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mut a = NavMesh::new(
+    ///     vec![
+    ///         (0.0, 0.0, 0.0).into(),
+    ///         (1.0, 0.0, 0.0).into(),
+    ///         (1.0, 1.0, 0.0).into(),
+    ///         (0.0, 1.0, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 2).into(), (2, 3, 0).into()],
+    /// )
+    /// .unwrap();
+    /// // right chunk does not line up perfectly with the left one.
+    /// let b = NavMesh::new(
+    ///     vec![
+    ///         (1.001, 0.0, 0.0).into(),
+    ///         (2.0, 0.0, 0.0).into(),
+    ///         (2.0, 1.0, 0.0).into(),
+    ///         (1.001, 1.0, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 2).into(), (2, 3, 0).into()],
+    /// )
+    /// .unwrap();
+    ///
+    /// a.stitch(b, 0.01).unwrap();
+    /// assert_eq!(a.vertices().len(), 6);
+    /// assert_eq!(a.find_triangle_islands().len(), 1);
+    /// ```
+    pub fn stitch(&mut self, other: NavMesh, weld_epsilon: Scalar) -> NavResult<()> {
+        let weld_epsilon = weld_epsilon.max(0.0);
+        let boundary = |mesh: &NavMesh| {
+            let mut counts = HashMap::<NavConnection, usize>::new();
+            for t in mesh.triangles() {
+                for edge in [(t.first, t.second), (t.second, t.third), (t.third, t.first)] {
+                    *counts.entry(NavConnection(edge.0, edge.1)).or_default() += 1;
+                }
+            }
+            counts
+                .into_iter()
+                .filter(|(_, count)| *count == 1)
+                .flat_map(|(edge, _)| [edge.0, edge.1])
+                .collect::<HashSet<_>>()
+        };
+        let mut welder = NavVerticesWelder::new(weld_epsilon);
+        let mut vertices = self.vertices().to_vec();
+        // {welder vertice: result vertice}
+        let mut welded = HashMap::new();
+        for index in boundary(self) {
+            welded
+                .entry(welder.weld(vertices[index as usize]))
+                .or_insert(index);
+        }
+        let other_boundary = boundary(&other);
+        let indices = other
+            .vertices()
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                if other_boundary.contains(&(index as u32)) {
+                    *welded.entry(welder.weld(*point)).or_insert_with(|| {
+                        vertices.push(*point);
+                        vertices.len() as u32 - 1
+                    })
+                } else {
+                    vertices.push(*point);
+                    vertices.len() as u32 - 1
+                }
+            })
+            .collect::<Vec<_>>();
+        let triangles = self
+            .triangles()
+            .iter()
+            .enumerate()
+            .map(|(index, t)| (*t, (0, index)))
+            .chain(
+                other
+                    .triangles()
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, t)| {
+                        let t = NavTriangle::from((
+                            indices[t.first as usize],
+                            indices[t.second as usize],
+                            indices[t.third as usize],
+                        ));
+                        if t.first == t.second || t.second == t.third || t.third == t.first {
+                            None
+                        } else {
+                            Some((t, (1, index)))
+                        }
+                    }),
+            )
+            .collect();
+        let tolerance = self.tolerance();
+        let triangles = split_t_junctions(
+            &vertices,
+            triangles,
+            NavTolerance {
+                weld_distance: weld_epsilon,
+                ..tolerance
+            },
+        );
+        let mut result = Self::new(vertices, triangles.iter().map(|(t, _)| *t).collect())?;
+        let meshes = [&*self, &other];
+        for (index, (_, (mesh, triangle))) in triangles.into_iter().enumerate() {
+            let area = &meshes[mesh].areas()[triangle];
+            result.set_area_cost(index, area.cost);
+            result.set_area_type(index, area.area_type);
+            result.set_area_flags(index, area.flags);
+            result.set_area_clearance(index, area.clearance);
+        }
+        result.set_up_axis(self.up_axis());
+        result.set_tolerance(tolerance);
+        *self = result;
+        Ok(())
+    }
+
+    /// Remove part of nav mesh that lies inside of convex volume (for example building
+    /// footprint), cutting triangles along volume boundary.
+    ///
+    /// Cylinder volumes are approximated with prism. Areas properties are kept, obstacles are
+    /// not.
+    ///
+    /// # Arguments
+    /// * `volume` - removed volume.
+    ///
+    /// # Returns
+    /// Nav mesh without removed part.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mesh = NavMesh::new(
+    ///     vec![
+    ///         (0.0, 0.0, 0.0).into(),
+    ///         (4.0, 0.0, 0.0).into(),
+    ///         (4.0, 4.0, 0.0).into(),
+    ///         (0.0, 4.0, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 2).into(), (2, 3, 0).into()],
+    /// )
+    /// .unwrap();
+    /// let mesh = mesh
+    ///     .subtract(&NavObstacle::aabb(
+    ///         (1.0, 1.0, -1.0).into(),
+    ///         (3.0, 3.0, 1.0).into(),
+    ///     ))
+    ///     .unwrap();
+    /// let area = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+    /// assert!((area - 12.0).abs() < 1.0e-4);
+    /// assert!(mesh
+    ///     .find_triangle_containing((2.0, 2.0, 0.0).into(), 0.1)
+    ///     .is_none());
+    /// assert!(mesh
+    ///     .find_path(
+    ///         (0.5, 2.0, 0.0).into(),
+    ///         (3.5, 2.0, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .is_some());
+    /// ```
+    pub fn subtract(&self, volume: &NavObstacle) -> NavResult<Self> {
+        let planes = volume.planes();
+        let mut pieces = vec![];
+        for index in 0..self.triangles().len() {
+            let mut remaining = self.triangle_polygon(index);
+            for (origin, normal) in &planes {
+                let outside = clip_polygon(remaining.clone(), *origin, -*normal);
+                if polygon_area(&outside) > self.tolerance().distance_sqr {
+                    pieces.push((outside, (0, index)));
+                }
+                remaining = clip_polygon(remaining, *origin, *normal);
+                if polygon_area(&remaining) <= self.tolerance().distance_sqr {
+                    break;
+                }
+            }
+        }
+        Self::from_pieces(&[self], pieces)
+    }
+
+    /// Keep only part of nav mesh that lies inside of convex volume (for example streaming cell
+    /// boundary), cutting triangles along volume boundary.
+    ///
+    /// Cylinder volumes are approximated with prism. Areas properties are kept, obstacles are
+    /// not.
+    ///
+    /// # Arguments
+    /// * `volume` - kept volume.
+    ///
+    /// # Returns
+    /// Nav mesh clipped to volume or error if nothing is left.
+    pub fn clip(&self, volume: &NavObstacle) -> NavResult<Self> {
+        let planes = volume.planes();
+        let pieces = (0..self.triangles().len())
+            .map(|index| {
+                let polygon = planes
+                    .iter()
+                    .fold(self.triangle_polygon(index), |polygon, (origin, normal)| {
+                        clip_polygon(polygon, *origin, *normal)
+                    });
+                (polygon, (0, index))
+            })
+            .filter(|(polygon, _)| polygon_area(polygon) > self.tolerance().distance_sqr)
+            .collect();
+        Self::from_pieces(&[self], pieces)
+    }
+
+    fn triangle_polygon(&self, index: usize) -> Vec<NavVec3> {
+        let triangle = self.triangles()[index];
+        let vertices = self.vertices();
+        vec![
+            vertices[triangle.first as usize],
+            vertices[triangle.second as usize],
+            vertices[triangle.third as usize],
+        ]
+    }
+
+    /// Build nav mesh from convex polygons taken from source nav meshes triangles.
+    fn from_pieces(meshes: &[&Self], pieces: Vec<(Vec<NavVec3>, NavSource)>) -> NavResult<Self> {
+        let tolerance = meshes[0].tolerance();
+        let mut welder = NavVerticesWelder::new(tolerance.weld_distance);
+        let mut triangles = vec![];
+        for (polygon, source) in pieces {
+            let mut indices = polygon
+                .into_iter()
+                .map(|point| welder.weld(point))
+                .collect::<Vec<_>>();
+            indices.dedup();
+            while indices.len() > 1 && indices.first() == indices.last() {
+                indices.pop();
+            }
+            let points = indices
+                .iter()
+                .map(|index| welder.vertice(*index))
+                .collect::<Vec<_>>();
+            for (a, b, c) in NavFanTriangulator.triangulate(&points).unwrap_or_default() {
+                let triangle = NavTriangle::from((indices[a], indices[b], indices[c]));
+                triangles.push((triangle, source));
+            }
+        }
+        let vertices = welder.into_vertices();
+        let triangles = split_t_junctions(&vertices, triangles, tolerance)
+            .into_iter()
+            .filter(|(t, _)| {
+                let a = vertices[t.first as usize];
+                let b = vertices[t.second as usize];
+                let c = vertices[t.third as usize];
+                (b - a).cross(c - a).magnitude() > tolerance.distance_sqr
+            })
+            .collect::<Vec<_>>();
+        if triangles.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        let mut result = Self::new(vertices, triangles.iter().map(|(t, _)| *t).collect())?;
+        for (index, (_, (mesh, triangle))) in triangles.into_iter().enumerate() {
+            let area = &meshes[mesh].areas()[triangle];
+            result.set_area_cost(index, area.cost);
+            result.set_area_type(index, area.area_type);
+            result.set_area_flags(index, area.flags);
+            result.set_area_clearance(index, area.clearance);
+        }
+        result.set_up_axis(meshes[0].up_axis());
+        result.set_tolerance(tolerance);
+        Ok(result)
+    }
+}
+
+/// Collects vertices, merging ones closer than weld treshold.
+#[derive(Debug)]
+pub(crate) struct NavVerticesWelder {
+    distance: Scalar,
+    vertices: Vec<NavVec3>,
+    // {weld cell: [vertice index]}
+    cells: HashMap<(i64, i64, i64), Vec<u32>>,
+}
+
+impl Default for NavVerticesWelder {
+    fn default() -> Self {
+        Self::new(WELD_TRESHOLD)
+    }
+}
+
+impl NavVerticesWelder {
+    pub fn new(distance: Scalar) -> Self {
+        Self {
+            distance: distance.max(ZERO_TRESHOLD),
+            vertices: Default::default(),
+            cells: Default::default(),
+        }
+    }
+
+    /// Add vertice or find already added one lying within weld treshold.
+    ///
+    /// # Returns
+    /// Vertice index.
+    pub fn weld(&mut self, point: NavVec3) -> u32 {
+        let cell = (
+            (point.x / self.distance).floor() as i64,
+            (point.y / self.distance).floor() as i64,
+            (point.z / self.distance).floor() as i64,
+        );
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    let found = self
+                        .cells
+                        .get(&(cell.0 + x, cell.1 + y, cell.2 + z))
+                        .and_then(|list| {
+                            list.iter().copied().find(|index| {
+                                (self.vertices[*index as usize] - point).sqr_magnitude()
+                                    <= self.distance * self.distance
+                            })
+                        });
+                    if let Some(index) = found {
+                        return index;
+                    }
+                }
+            }
+        }
+        let index = self.vertices.len() as u32;
+        self.vertices.push(point);
+        self.cells.entry(cell).or_default().push(index);
+        index
+    }
+
+    pub fn vertice(&self, index: u32) -> NavVec3 {
+        self.vertices[index as usize]
+    }
+
+    pub fn into_vertices(self) -> Vec<NavVec3> {
+        self.vertices
+    }
+}
+
+/// Split triangles which have vertice of other triangle lying on their open edge, so both get
+/// connected by shared edge.
+fn split_t_junctions(
+    vertices: &[NavVec3],
+    triangles: Vec<(NavTriangle, NavSource)>,
+    tolerance: NavTolerance,
+) -> Vec<(NavTriangle, NavSource)> {
+    let mut counts = HashMap::<NavConnection, usize>::new();
+    for (t, _) in &triangles {
+        for edge in [(t.first, t.second), (t.second, t.third), (t.third, t.first)] {
+            *counts.entry(NavConnection(edge.0, edge.1)).or_default() += 1;
+        }
+    }
+    let mut open_edges = counts
+        .into_iter()
+        .filter(|(_, count)| *count == 1)
+        .map(|(edge, _)| edge)
+        .collect::<HashSet<_>>();
+    let mut open_vertices = open_edges
+        .iter()
+        .flat_map(|edge| [edge.0, edge.1])
+        .collect::<Vec<_>>();
+    open_vertices.sort_unstable();
+    open_vertices.dedup();
+    let lies_on = |point: NavVec3, from: NavVec3, to: NavVec3| {
+        let t = point.project(from, to);
+        t > tolerance.angular
+            && t < 1.0 - tolerance.angular
+            && (NavVec3::unproject(from, to, t) - point).sqr_magnitude()
+                <= tolerance.weld_distance * tolerance.weld_distance
+    };
+    let mut result = Vec::with_capacity(triangles.len());
+    for (triangle, source) in triangles {
+        let mut stack = vec![triangle];
+        while let Some(t) = stack.pop() {
+            let corners = [t.first, t.second, t.third];
+            let split = (0..3).find_map(|i| {
+                let a = corners[i];
+                let b = corners[(i + 1) % 3];
+                if !open_edges.contains(&NavConnection(a, b)) {
+                    return None;
+                }
+                let pa = vertices[a as usize];
+                let pb = vertices[b as usize];
+                open_vertices
+                    .iter()
+                    .copied()
+                    .find(|v| *v != a && *v != b && lies_on(vertices[*v as usize], pa, pb))
+                    .map(|v| (i, v))
+            });
+            match split {
+                Some((i, v)) => {
+                    let a = corners[i];
+                    let b = corners[(i + 1) % 3];
+                    let c = corners[(i + 2) % 3];
+                    open_edges.insert(NavConnection(a, v));
+                    open_edges.insert(NavConnection(v, b));
+                    stack.push((v, b, c).into());
+                    stack.push((a, v, c).into());
+                }
+                None => result.push((t, source)),
+            }
+        }
+    }
+    result
+}
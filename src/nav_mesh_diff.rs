@@ -0,0 +1,342 @@
+use crate::{
+    nav_binary::{checksum, BinaryReader, BinaryWriter},
+    Error, NavArea, NavMesh, NavResult, NavTriangle, NavUpAxis, NavVec3, Scalar, SCALAR_MAX,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const DIFF_MAGIC: &[u8; 4] = b"NAVD";
+const DIFF_VERSION: u16 = 1;
+
+/// Triangle changed or added by `NavMeshDiff`, together with its area properties.
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshDiffTriangle {
+    pub index: u32,
+    pub triangle: NavTriangle,
+    pub cost: Scalar,
+    pub area_type: u8,
+    pub flags: u16,
+    pub clearance: Scalar,
+}
+
+/// Compact difference between two versions of nav mesh (for example after obstacle carving or
+/// tile rebake), that servers can send to clients instead of whole serialized nav mesh.
+///
+/// Only vertices and triangles that changed are stored. Obstacles are not part of diff, since
+/// they are meant to be added at runtime.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshDiff {
+    /// Fingerprint of nav mesh this diff applies to.
+    pub base: u64,
+    /// Fingerprint of nav mesh produced by applying this diff.
+    pub target: u64,
+    /// Vertices count of produced nav mesh.
+    pub vertices_count: u32,
+    /// Changed or added vertices: (index, point).
+    pub vertices: Vec<(u32, NavVec3)>,
+    /// Triangles count of produced nav mesh.
+    pub triangles_count: u32,
+    /// Changed or added triangles.
+    pub triangles: Vec<NavMeshDiffTriangle>,
+    pub up_axis: NavUpAxis,
+}
+
+impl NavMeshDiff {
+    /// Tells if diff does not change anything.
+    pub fn is_empty(&self) -> bool {
+        self.base == self.target
+    }
+
+    /// Serialize diff into compact versioned binary format.
+    ///
+    /// # Returns
+    /// Bytes of serialized diff.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let scalar_size = std::mem::size_of::<Scalar>();
+        let mut payload = BinaryWriter::with_capacity(
+            16 + self.vertices.len() * (4 + scalar_size * 3)
+                + self.triangles.len() * (19 + scalar_size * 2),
+        );
+        payload.write_u32(self.vertices_count);
+        payload.write_u32(self.vertices.len() as u32);
+        for (index, vertice) in &self.vertices {
+            payload.write_u32(*index);
+            payload.write_vec3(*vertice);
+        }
+        payload.write_u32(self.triangles_count);
+        payload.write_u32(self.triangles.len() as u32);
+        for item in &self.triangles {
+            payload.write_u32(item.index);
+            payload.write_u32(item.triangle.first);
+            payload.write_u32(item.triangle.second);
+            payload.write_u32(item.triangle.third);
+            payload.write_scalar(item.cost);
+            payload.write_u8(item.area_type);
+            payload.write_u16(item.flags);
+            payload.write_scalar(item.clearance);
+        }
+        let payload = payload.into_inner();
+
+        let mut result = BinaryWriter::with_capacity(payload.len() + 40);
+        result.write_bytes(DIFF_MAGIC);
+        result.write_u16(DIFF_VERSION);
+        result.write_u8(scalar_size as u8);
+        result.write_u8(match self.up_axis {
+            NavUpAxis::Z => 0,
+            NavUpAxis::Y => 1,
+        });
+        result.write_u64(self.base);
+        result.write_u64(self.target);
+        result.write_u64(payload.len() as u64);
+        result.write_u64(checksum(&payload));
+        result.write_bytes(&payload);
+        result.into_inner()
+    }
+
+    /// Deserialize diff from binary format produced by `NavMeshDiff::to_bytes`.
+    ///
+    /// # Arguments
+    /// * `bytes` - serialized diff bytes.
+    ///
+    /// # Returns
+    /// `Ok` with diff or `Err` with `Error::CouldNotDeserializeNavMesh` if data is invalid,
+    /// corrupted or has unsupported version.
+    pub fn from_bytes(bytes: &[u8]) -> NavResult<Self> {
+        let invalid = |message: &str| Error::CouldNotDeserializeNavMesh(message.to_owned());
+        let mut header = BinaryReader::new(bytes);
+        if header.read_bytes(4)? != DIFF_MAGIC {
+            return Err(invalid("Data is not a binary nav mesh diff"));
+        }
+        let version = header.read_u16()?;
+        if version != DIFF_VERSION {
+            return Err(Error::CouldNotDeserializeNavMesh(format!(
+                "Unsupported binary nav mesh diff version: {}",
+                version
+            )));
+        }
+        let scalar_size = header.read_u8()? as usize;
+        let up_axis = match header.read_u8()? {
+            0 => NavUpAxis::Z,
+            1 => NavUpAxis::Y,
+            axis => {
+                return Err(Error::CouldNotDeserializeNavMesh(format!(
+                    "Unsupported up axis: {}",
+                    axis
+                )))
+            }
+        };
+        let base = header.read_u64()?;
+        let target = header.read_u64()?;
+        let size = header.read_u64()? as usize;
+        let sum = header.read_u64()?;
+        let payload = header.read_bytes(size)?;
+        if !header.is_empty() {
+            return Err(invalid("Unexpected data after nav mesh diff"));
+        }
+        if checksum(payload) != sum {
+            return Err(invalid("Checksum mismatch"));
+        }
+
+        let mut reader = BinaryReader::new(payload);
+        reader.set_scalar_size(scalar_size)?;
+        let scalar_size = reader.scalar_size();
+        let vertices_count = reader.read_u32()?;
+        let count = reader.read_count(4 + scalar_size * 3)?;
+        let vertices = (0..count)
+            .map(|_| Ok((reader.read_u32()?, reader.read_vec3()?)))
+            .collect::<NavResult<Vec<_>>>()?;
+        let triangles_count = reader.read_u32()?;
+        let count = reader.read_count(19 + scalar_size * 2)?;
+        let triangles = (0..count)
+            .map(|_| {
+                Ok(NavMeshDiffTriangle {
+                    index: reader.read_u32()?,
+                    triangle: NavTriangle {
+                        first: reader.read_u32()?,
+                        second: reader.read_u32()?,
+                        third: reader.read_u32()?,
+                    },
+                    cost: reader.read_scalar()?,
+                    area_type: reader.read_u8()?,
+                    flags: reader.read_u16()?,
+                    clearance: reader.read_scalar()?,
+                })
+            })
+            .collect::<NavResult<Vec<_>>>()?;
+        if !reader.is_empty() {
+            return Err(invalid("Unexpected data after nav mesh diff"));
+        }
+        Ok(Self {
+            base,
+            target,
+            vertices_count,
+            vertices,
+            triangles_count,
+            triangles,
+            up_axis,
+        })
+    }
+}
+
+impl NavMesh {
+    /// Hash of nav mesh geometry and areas properties, used to tell if diff can be applied to it.
+    ///
+    /// Obstacles are not included. Meshes using different `Scalar` size have different
+    /// fingerprints.
+    pub fn fingerprint(&self) -> u64 {
+        let mut writer = BinaryWriter::default();
+        writer.write_u8(match self.up_axis() {
+            NavUpAxis::Z => 0,
+            NavUpAxis::Y => 1,
+        });
+        writer.write_u32(self.vertices().len() as u32);
+        for vertice in self.vertices() {
+            writer.write_vec3(*vertice);
+        }
+        writer.write_u32(self.triangles().len() as u32);
+        for (triangle, area) in self.triangles().iter().zip(self.areas()) {
+            writer.write_u32(triangle.first);
+            writer.write_u32(triangle.second);
+            writer.write_u32(triangle.third);
+            writer.write_scalar(area.cost);
+            writer.write_u8(area.area_type);
+            writer.write_u16(area.flags);
+            writer.write_scalar(area.clearance);
+        }
+        checksum(&writer.into_inner())
+    }
+
+    /// Compute diff that turns this nav mesh into other one.
+    ///
+    /// # Arguments
+    /// * `other` - newer version of nav mesh.
+    ///
+    /// # Returns
+    /// Diff with changed vertices and triangles.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    /// let old = NavMesh::new(vertices.clone(), triangles.clone()).unwrap();
+    /// let mut new = NavMesh::new(vertices, triangles).unwrap();
+    /// new.set_area_type(1, 2);
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.vertices.len(), 0);
+    /// assert_eq!(diff.triangles.len(), 1);
+    /// let diff = NavMeshDiff::from_bytes(&diff.to_bytes()).unwrap();
+    /// let patched = old.apply_diff(&diff).unwrap();
+    /// assert_eq!(patched.fingerprint(), new.fingerprint());
+    /// assert_eq!(patched.areas()[1].area_type, 2);
+    /// ```
+    pub fn diff(&self, other: &Self) -> NavMeshDiff {
+        let vertices = other
+            .vertices()
+            .iter()
+            .enumerate()
+            .filter(|(index, vertice)| self.vertices().get(*index) != Some(*vertice))
+            .map(|(index, vertice)| (index as u32, *vertice))
+            .collect();
+        let triangles = other
+            .triangles()
+            .iter()
+            .zip(other.areas())
+            .enumerate()
+            .filter(|(index, (triangle, area))| {
+                match (self.triangles().get(*index), self.areas().get(*index)) {
+                    (Some(t), Some(a)) => {
+                        t.first != triangle.first
+                            || t.second != triangle.second
+                            || t.third != triangle.third
+                            || a.cost != area.cost
+                            || a.area_type != area.area_type
+                            || a.flags != area.flags
+                            || a.clearance != area.clearance
+                    }
+                    _ => true,
+                }
+            })
+            .map(|(index, (triangle, area))| NavMeshDiffTriangle {
+                index: index as u32,
+                triangle: *triangle,
+                cost: area.cost,
+                area_type: area.area_type,
+                flags: area.flags,
+                clearance: area.clearance,
+            })
+            .collect();
+        NavMeshDiff {
+            base: self.fingerprint(),
+            target: other.fingerprint(),
+            vertices_count: other.vertices().len() as u32,
+            vertices,
+            triangles_count: other.triangles().len() as u32,
+            triangles,
+            up_axis: other.up_axis(),
+        }
+    }
+
+    /// Apply diff produced by `NavMesh::diff` to this nav mesh.
+    ///
+    /// # Arguments
+    /// * `diff` - diff computed against this nav mesh version.
+    ///
+    /// # Returns
+    /// `Ok` with patched nav mesh or `Err` with `Error::NavMeshDiffMismatch` if diff was computed
+    /// against other nav mesh or does not produce expected nav mesh.
+    pub fn apply_diff(&self, diff: &NavMeshDiff) -> NavResult<Self> {
+        let base = self.fingerprint();
+        if base != diff.base {
+            return Err(Error::NavMeshDiffMismatch(diff.base, base));
+        }
+        let mut vertices = self.vertices().to_vec();
+        vertices.resize(diff.vertices_count as usize, NavVec3::default());
+        for (index, vertice) in &diff.vertices {
+            match vertices.get_mut(*index as usize) {
+                Some(v) => *v = *vertice,
+                None => return Err(Error::NavMeshDiffMismatch(diff.target, base)),
+            }
+        }
+        let count = diff.triangles_count as usize;
+        let mut triangles = self.triangles().to_vec();
+        triangles.resize(count, NavTriangle::default());
+        let mut areas = self
+            .areas()
+            .iter()
+            .map(|area| (area.cost, area.area_type, area.flags, area.clearance))
+            .collect::<Vec<_>>();
+        areas.resize(count, (1.0, 0, NavArea::DEFAULT_FLAGS, SCALAR_MAX));
+        for item in &diff.triangles {
+            let index = item.index as usize;
+            if index >= count {
+                return Err(Error::NavMeshDiffMismatch(diff.target, base));
+            }
+            triangles[index] = item.triangle;
+            areas[index] = (item.cost, item.area_type, item.flags, item.clearance);
+        }
+        let mut result = Self::new(vertices, triangles)?;
+        for (index, (cost, area_type, flags, clearance)) in areas.into_iter().enumerate() {
+            result.set_area_cost(index, cost);
+            result.set_area_type(index, area_type);
+            result.set_area_flags(index, flags);
+            result.set_area_clearance(index, clearance);
+        }
+        result.set_up_axis(diff.up_axis);
+        result.set_tolerance(self.tolerance());
+        let target = result.fingerprint();
+        if target != diff.target {
+            return Err(Error::NavMeshDiffMismatch(diff.target, target));
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{NavMesh, NavPath};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock,
+};
+
+#[derive(Debug)]
+struct NavMeshHandleInner {
+    mesh: RwLock<Arc<NavMesh>>,
+    generation: AtomicU64,
+}
+
+/// Shared handle to nav mesh that can be atomically swapped at runtime (for example when nav
+/// mesh gets hot-reloaded from editor).
+///
+/// Clones of handle point to the same nav mesh. Readers take snapshot of current nav mesh, so
+/// queries in flight keep using the mesh they started with while new ones see the swapped one.
+#[derive(Debug, Clone)]
+pub struct NavMeshHandle {
+    inner: Arc<NavMeshHandleInner>,
+}
+
+impl From<NavMesh> for NavMeshHandle {
+    fn from(mesh: NavMesh) -> Self {
+        Self::new(mesh)
+    }
+}
+
+impl NavMeshHandle {
+    pub fn new(mesh: NavMesh) -> Self {
+        Self {
+            inner: Arc::new(NavMeshHandleInner {
+                mesh: RwLock::new(Arc::new(mesh)),
+                generation: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Snapshot of current nav mesh.
+    pub fn get(&self) -> Arc<NavMesh> {
+        self.inner
+            .mesh
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Replace nav mesh, so all handle clones see the new one.
+    ///
+    /// # Arguments
+    /// * `mesh` - new nav mesh.
+    ///
+    /// # Returns
+    /// Previous nav mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(),
+    ///     (1.0, 0.0, 0.0).into(),
+    ///     (1.0, 1.0, 0.0).into(),
+    ///     (0.0, 1.0, 0.0).into(),
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let handle = NavMeshHandle::new(NavMesh::new(vertices.clone(), triangles.clone()).unwrap());
+    /// let agent = handle.clone();
+    /// let path = agent
+    ///     .get()
+    ///     .find_path_detailed(
+    ///         (0.1, 0.9, 0.0).into(),
+    ///         (0.9, 0.1, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .unwrap();
+    /// assert!(agent.is_path_valid(&path));
+    ///
+    /// handle.swap(NavMesh::new(vertices, triangles).unwrap());
+    /// assert_eq!(agent.generation(), 1);
+    /// assert!(!agent.is_path_valid(&path));
+    /// ```
+    pub fn swap(&self, mesh: NavMesh) -> Arc<NavMesh> {
+        let mut guard = self
+            .inner
+            .mesh
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let old = std::mem::replace(&mut *guard, Arc::new(mesh));
+        self.inner.generation.fetch_add(1, Ordering::AcqRel);
+        old
+    }
+
+    /// Number of times nav mesh got swapped.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.inner.generation.load(Ordering::Acquire)
+    }
+
+    /// Tells if path was found on current nav mesh and it did not change since then.
+    #[inline]
+    pub fn is_path_valid(&self, path: &NavPath) -> bool {
+        path.is_valid_for(&self.get())
+    }
+}
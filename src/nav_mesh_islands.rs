@@ -0,0 +1,347 @@
+use crate::{nav_open_node::OpenNode, NavMesh, NavMeshID, NavPathMode, NavQuery, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+
+/// Connection between two points of nav mesh (usually on different islands) that agents can
+/// travel with, like elevator, boat or teleport.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshPortal {
+    /// Point where agent enters portal.
+    pub from: NavVec3,
+    /// Point where agent leaves portal.
+    pub to: NavVec3,
+    /// Cost of travelling with portal, comparable with path lengths.
+    pub cost: Scalar,
+    /// Tells if portal can be travelled from `to` to `from` as well.
+    pub both_ways: bool,
+}
+
+impl NavMeshPortal {
+    pub fn new(from: NavVec3, to: NavVec3, cost: Scalar, both_ways: bool) -> Self {
+        Self {
+            from,
+            to,
+            cost,
+            both_ways,
+        }
+    }
+}
+
+/// Part of route lying on single island.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshRouteLeg {
+    /// Island this part of route lies on.
+    pub island: usize,
+    /// Path points on island.
+    pub points: Vec<NavVec3>,
+    /// Index of portal taken at the end of this part or `None` if it is the last one.
+    pub portal: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct NavMeshPortalEntry {
+    portal: NavMeshPortal,
+    from_island: usize,
+    to_island: usize,
+}
+
+/// Disconnected parts (islands) of nav mesh, connected with user registered portals, so paths
+/// can lead across islands.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshIslands {
+    mesh: NavMeshID,
+    // island of every triangle.
+    triangles: Vec<usize>,
+    count: usize,
+    portals: Vec<Option<NavMeshPortalEntry>>,
+}
+
+impl NavMeshIslands {
+    /// Find islands of nav mesh.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    pub fn new(mesh: &NavMesh) -> Self {
+        let triangles = mesh.triangles_islands().into_owned();
+        let count = triangles.iter().max().map(|island| island + 1).unwrap_or(0);
+        Self {
+            mesh: mesh.id(),
+            triangles,
+            count,
+            portals: vec![],
+        }
+    }
+
+    /// Identifier of nav mesh islands were found for.
+    #[inline]
+    pub fn mesh(&self) -> NavMeshID {
+        self.mesh
+    }
+
+    #[inline]
+    pub fn islands_count(&self) -> usize {
+        self.count
+    }
+
+    /// Get island of triangle.
+    #[inline]
+    pub fn triangle_island(&self, index: usize) -> Option<usize> {
+        self.triangles.get(index).copied()
+    }
+
+    /// Get island of nav mesh triangle closest to point.
+    pub fn point_island(&self, mesh: &NavMesh, point: NavVec3, query: NavQuery) -> Option<usize> {
+        if mesh.id() != self.mesh {
+            return None;
+        }
+        self.triangle_island(mesh.find_closest_triangle(point, query)?)
+    }
+
+    /// Register portal between two nav mesh points.
+    ///
+    /// # Returns
+    /// `Some` with portal index or `None` if portal points are not on nav mesh.
+    pub fn add_portal(
+        &mut self,
+        mesh: &NavMesh,
+        portal: NavMeshPortal,
+        query: NavQuery,
+    ) -> Option<usize> {
+        let from_island = self.point_island(mesh, portal.from, query)?;
+        let to_island = self.point_island(mesh, portal.to, query)?;
+        let entry = NavMeshPortalEntry {
+            portal,
+            from_island,
+            to_island,
+        };
+        match self.portals.iter().position(Option::is_none) {
+            Some(index) => {
+                self.portals[index] = Some(entry);
+                Some(index)
+            }
+            None => {
+                self.portals.push(Some(entry));
+                Some(self.portals.len() - 1)
+            }
+        }
+    }
+
+    /// Unregister portal. Indices of other portals stay the same.
+    ///
+    /// # Returns
+    /// `Some` with removed portal or `None` if portal does not exists.
+    pub fn remove_portal(&mut self, index: usize) -> Option<NavMeshPortal> {
+        self.portals
+            .get_mut(index)?
+            .take()
+            .map(|entry| entry.portal)
+    }
+
+    #[inline]
+    pub fn portal(&self, index: usize) -> Option<&NavMeshPortal> {
+        self.portals.get(index)?.as_ref().map(|entry| &entry.portal)
+    }
+
+    /// Iterate over registered portals with their indices.
+    pub fn portals(&self) -> impl Iterator<Item = (usize, &NavMeshPortal)> {
+        self.portals
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| entry.as_ref().map(|entry| (index, &entry.portal)))
+    }
+
+    /// Find route between two points of (possibly different) islands, travelling with portals.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh islands were found for.
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with route parts for every visited island or `None` if there is no route.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (3.0, 0.0, 0.0).into(), // 4
+    ///     (4.0, 0.0, 0.0).into(), // 5
+    ///     (4.0, 1.0, 0.0).into(), // 6
+    ///     (3.0, 1.0, 0.0).into(), // 7
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 2).into(),
+    ///     (2, 3, 0).into(),
+    ///     (4, 5, 6).into(),
+    ///     (6, 7, 4).into(),
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let mut islands = NavMeshIslands::new(&mesh);
+    /// assert_eq!(islands.islands_count(), 2);
+    /// let from = (0.25, 0.5, 0.0).into();
+    /// let to = (3.75, 0.5, 0.0).into();
+    /// assert!(islands
+    ///     .find_route(&mesh, from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+    ///     .is_none());
+    ///
+    /// let portal = NavMeshPortal::new((1.0, 0.5, 0.0).into(), (3.0, 0.5, 0.0).into(), 1.0, true);
+    /// let index = islands.add_portal(&mesh, portal, NavQuery::Accuracy).unwrap();
+    /// let route = islands
+    ///     .find_route(&mesh, from, to, NavQuery::Accuracy, NavPathMode::Accuracy)
+    ///     .unwrap();
+    /// assert_eq!(route.len(), 2);
+    /// assert_eq!(route[0].portal, Some(index));
+    /// assert_ne!(route[0].island, route[1].island);
+    /// ```
+    pub fn find_route(
+        &self,
+        mesh: &NavMesh,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<Vec<NavMeshRouteLeg>> {
+        let start_island = self.point_island(mesh, from, query)?;
+        let end_island = self.point_island(mesh, to, query)?;
+        // nodes: start, end, then entry and exit point of every portal direction.
+        // [(point, island)]
+        let mut nodes = vec![(from, start_island), (to, end_island)];
+        // {entry node: (exit node, portal index, cost)}
+        let mut jumps = HashMap::new();
+        for (index, entry) in self.portals.iter().enumerate() {
+            let entry = match entry {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let mut directions = vec![(
+                (entry.portal.from, entry.from_island),
+                (entry.portal.to, entry.to_island),
+            )];
+            if entry.portal.both_ways {
+                directions.push((
+                    (entry.portal.to, entry.to_island),
+                    (entry.portal.from, entry.from_island),
+                ));
+            }
+            for (enter, exit) in directions {
+                jumps.insert(nodes.len(), (nodes.len() + 1, index, entry.portal.cost));
+                nodes.push(enter);
+                nodes.push(exit);
+            }
+        }
+        // {(from node, to node): leg path}
+        let mut legs = HashMap::<(usize, usize), Option<Vec<NavVec3>>>::new();
+        let mut leg = |a: usize, b: usize| {
+            legs.entry((a, b))
+                .or_insert_with(|| {
+                    let (from, _) = nodes[a];
+                    let (to, _) = nodes[b];
+                    if from.same_as(to) {
+                        Some(vec![from])
+                    } else {
+                        mesh.find_path(from, to, query, mode)
+                    }
+                })
+                .clone()
+        };
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        // {node: (previous node, reached by portal)}
+        let mut parents = HashMap::<usize, (usize, bool)>::new();
+        open.push(OpenNode(0.0, 0));
+        costs.insert(0, 0.0);
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if current == 1 {
+                break;
+            }
+            if cost > costs.get(&current).copied().unwrap_or(Scalar::INFINITY) {
+                continue;
+            }
+            let mut edges = vec![];
+            let island = nodes[current].1;
+            // walk to end or portal entries on the same island.
+            for next in std::iter::once(1).chain(jumps.keys().copied()) {
+                if next != current && nodes[next].1 == island {
+                    if let Some(path) = leg(current, next) {
+                        edges.push((next, NavMesh::path_length(&path), false));
+                    }
+                }
+            }
+            if let Some((exit, _, portal_cost)) = jumps.get(&current) {
+                edges.push((*exit, portal_cost.max(0.0), true));
+            }
+            for (next, weight, portal) in edges {
+                let next_cost = cost + weight;
+                if next_cost < costs.get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, (current, portal));
+                    open.push(OpenNode(next_cost, next));
+                }
+            }
+        }
+        let mut steps = vec![];
+        let mut node = 1;
+        while node != 0 {
+            let (parent, portal) = *parents.get(&node)?;
+            steps.push((parent, node, portal));
+            node = parent;
+        }
+        steps.reverse();
+        let mut result = vec![];
+        let mut points = vec![];
+        for (a, b, portal) in steps {
+            if portal {
+                let (_, index, _) = jumps[&a];
+                result.push(NavMeshRouteLeg {
+                    island: nodes[a].1,
+                    points: std::mem::take(&mut points),
+                    portal: Some(index),
+                });
+            } else {
+                let path = leg(a, b)?;
+                let skip = if points.is_empty() { 0 } else { 1 };
+                points.extend(path.into_iter().skip(skip));
+            }
+        }
+        result.push(NavMeshRouteLeg {
+            island: end_island,
+            points,
+            portal: None,
+        });
+        Some(result)
+    }
+
+    /// Find path between two points of (possibly different) islands, travelling with portals.
+    ///
+    /// # Returns
+    /// `Some` with path points, including portals entry and exit points, or `None` if there is no
+    /// route.
+    pub fn find_path(
+        &self,
+        mesh: &NavMesh,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<Vec<NavVec3>> {
+        Some(
+            self.find_route(mesh, from, to, query, mode)?
+                .into_iter()
+                .flat_map(|leg| leg.points)
+                .collect(),
+        )
+    }
+}
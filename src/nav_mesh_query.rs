@@ -0,0 +1,151 @@
+use crate::{
+    nav_geom::{clip_polygon, sphere_intersects_triangle},
+    NavMesh, NavObstacle, NavVec3, Scalar,
+};
+use spade::BoundingRect;
+
+impl NavMesh {
+    /// Find triangles overlapping axis aligned box.
+    ///
+    /// # Arguments
+    /// * `min` - box minimum corner.
+    /// * `max` - box maximum corner.
+    ///
+    /// # Returns
+    /// Sorted list of overlapping triangles indices.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mesh = NavMesh::new(
+    ///     vec![
+    ///         (0.0, 0.0, 0.0).into(),
+    ///         (2.0, 0.0, 0.0).into(),
+    ///         (2.0, 2.0, 0.0).into(),
+    ///         (0.0, 2.0, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 2).into(), (2, 3, 0).into()],
+    /// )
+    /// .unwrap();
+    /// let found = mesh.triangles_in_aabb((1.5, 0.1, -1.0).into(), (1.9, 0.5, 1.0).into());
+    /// assert_eq!(found, vec![0]);
+    /// ```
+    pub fn triangles_in_aabb(&self, min: NavVec3, max: NavVec3) -> Vec<usize> {
+        let (min, max) = (min.min(max), min.max(max));
+        self.triangles_in_planes(&NavObstacle::aabb(min, max).planes(), Some((min, max)))
+    }
+
+    /// Find triangles overlapping sphere, for example area of effect range.
+    ///
+    /// # Arguments
+    /// * `center` - sphere center.
+    /// * `radius` - sphere radius.
+    ///
+    /// # Returns
+    /// Sorted list of overlapping triangles indices.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mesh = NavMesh::new(
+    ///     vec![
+    ///         (0.0, 0.0, 0.0).into(),
+    ///         (2.0, 0.0, 0.0).into(),
+    ///         (2.0, 2.0, 0.0).into(),
+    ///         (0.0, 2.0, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 2).into(), (2, 3, 0).into()],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(mesh.triangles_in_sphere((0.2, 1.8, 0.5).into(), 0.6), vec![1]);
+    /// assert_eq!(mesh.triangles_in_sphere((1.0, 1.0, 0.0).into(), 0.1), vec![0, 1]);
+    /// ```
+    pub fn triangles_in_sphere(&self, center: NavVec3, radius: Scalar) -> Vec<usize> {
+        let radius = radius.max(0.0);
+        let extents = NavVec3::new(radius, radius, radius);
+        let mut result = self
+            .spatial_index()
+            .lookup_in_rectangle(&BoundingRect::from_corners(
+                &(center - extents),
+                &(center + extents),
+            ))
+            .into_iter()
+            .filter(|s| sphere_intersects_triangle(center, radius, s.a, s.b, s.c))
+            .map(|s| s.index)
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+        result
+    }
+
+    /// Find triangles overlapping convex volume.
+    ///
+    /// # Arguments
+    /// * `planes` - convex volume bounding planes: list of (origin, outward normal).
+    ///
+    /// # Returns
+    /// Sorted list of overlapping triangles indices.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mesh = NavMesh::new(
+    ///     vec![
+    ///         (0.0, 0.0, 0.0).into(),
+    ///         (2.0, 0.0, 0.0).into(),
+    ///         (2.0, 2.0, 0.0).into(),
+    ///         (0.0, 2.0, 0.0).into(),
+    ///     ],
+    ///     vec![(0, 1, 2).into(), (2, 3, 0).into()],
+    /// )
+    /// .unwrap();
+    /// // half space below the diagonal going from top left to bottom right corner.
+    /// let planes = vec![((1.0, 1.0, 0.0).into(), (1.0, 1.0, 0.0).into())];
+    /// assert_eq!(mesh.triangles_in_convex_hull(&planes), vec![0, 1]);
+    /// // half space above the diagonal going from bottom left to top right corner.
+    /// let planes = vec![((0.0, 0.5, 0.0).into(), (1.0, -1.0, 0.0).into())];
+    /// assert_eq!(mesh.triangles_in_convex_hull(&planes), vec![1]);
+    /// ```
+    pub fn triangles_in_convex_hull(&self, planes: &[(NavVec3, NavVec3)]) -> Vec<usize> {
+        let volume = NavObstacle::convex(planes.to_vec());
+        self.triangles_in_planes(&volume.planes(), volume.bounds())
+    }
+
+    fn triangles_in_planes(
+        &self,
+        planes: &[(NavVec3, NavVec3)],
+        bounds: Option<(NavVec3, NavVec3)>,
+    ) -> Vec<usize> {
+        let overlaps = |a: NavVec3, b: NavVec3, c: NavVec3| {
+            !planes
+                .iter()
+                .fold(vec![a, b, c], |polygon, (origin, normal)| {
+                    clip_polygon(polygon, *origin, *normal)
+                })
+                .is_empty()
+        };
+        let mut result = match bounds {
+            Some((min, max)) => self
+                .spatial_index()
+                .lookup_in_rectangle(&BoundingRect::from_corners(&min, &max))
+                .into_iter()
+                .filter(|s| overlaps(s.a, s.b, s.c))
+                .map(|s| s.index)
+                .collect::<Vec<_>>(),
+            None => self
+                .triangles()
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| {
+                    let point = |index: u32| self.vertices()[index as usize];
+                    overlaps(point(t.first), point(t.second), point(t.third))
+                })
+                .map(|(index, _)| index)
+                .collect(),
+        };
+        result.sort_unstable();
+        result
+    }
+}
@@ -0,0 +1,570 @@
+use crate::{
+    nav_open_node::OpenNode, NavMesh, NavPathMode, NavQuery, NavQueryBudget, NavQueryFilter,
+    NavVec3, Scalar,
+};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Weights below this difference from 1 are treated as optimal search by anytime search.
+const ANYTIME_WEIGHT_TRESHOLD: Scalar = 0.01;
+
+impl NavMesh {
+    /// Find path on nav mesh between two points with weighted A* search, that trades path
+    /// optimality for less expanded triangles.
+    ///
+    /// Search uses the same connections costs as `NavMesh::find_path` and estimates remaining
+    /// cost with precomputed landmarks (see `NavMesh::precompute_landmarks`), found path cost is
+    /// at most `weight` times bigger than the optimal one. Without landmarks there is nothing to
+    /// weight, so search expands the same triangles as optimal one.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `weight` - heuristic weight, 1 gives optimal path, bigger values give faster searches.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let path = mesh
+    ///     .find_path_weighted(
+    ///         (0.0, 1.0, 0.0).into(),
+    ///         (1.5, 0.25, 0.5).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::MidPoints,
+    ///         2.0,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(path.first(), Some(&(0.0, 1.0, 0.0).into()));
+    /// assert_eq!(mesh.find_path_triangles_weighted(1, 2, 2.0).unwrap().0, vec![1, 0, 3, 2]);
+    /// ```
+    pub fn find_path_weighted(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        weight: Scalar,
+    ) -> Option<Vec<NavVec3>> {
+        self.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles_weighted(start, end, weight)
+        })
+    }
+
+    /// Find path on nav mesh between two triangles with weighted A* search.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `weight` - heuristic weight, 1 gives optimal path, bigger values give faster searches.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path cost if found or `None` otherwise.
+    pub fn find_path_triangles_weighted(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        self.weighted_search(
+            from,
+            to,
+            weight,
+            |a, b| self.connection_cost(a, b),
+            |index| self.estimate_path_cost(index, to),
+            || false,
+        )
+    }
+
+    /// Find path on nav mesh between two triangles with weighted A* search, using custom cost
+    /// callback like `NavMesh::find_path_triangles_with_filter` does.
+    ///
+    /// Remaining cost is estimated with distance between triangles centers scaled by the smallest
+    /// area cost, so path cost stays within `weight` times the optimal one as long as filter
+    /// costs are not smaller than moves lengths.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `weight` - heuristic weight, 1 gives optimal path, bigger values give faster searches.
+    /// * `filter` - cost callback.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path cost if found or `None` otherwise.
+    pub fn find_path_triangles_weighted_with_filter<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        filter: &Q,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        let estimate = self.centers_distance_estimate();
+        self.weighted_search(
+            from,
+            to,
+            weight,
+            |a, b| self.search_filter_cost(a, b, filter),
+            |index| estimate(index, to),
+            || false,
+        )
+    }
+
+    /// Find path on nav mesh between two points with anytime search: quick weighted A* path is
+    /// found first and then improved by searches with smaller weights while budget remains.
+    ///
+    /// First search always completes, so path is returned if it exists even when budget is
+    /// exhausted.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `weight` - heuristic weight of first search.
+    /// * `budget` - amount of work allowed for improving path.
+    ///
+    /// # Returns
+    /// `Some` with best found path points on nav mesh or `None` if there is no path.
+    pub fn find_path_anytime(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+        weight: Scalar,
+        budget: NavQueryBudget,
+    ) -> Option<Vec<NavVec3>> {
+        self.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles_anytime(start, end, weight, budget)
+        })
+    }
+
+    /// Find path on nav mesh between two triangles with anytime search.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `weight` - heuristic weight of first search.
+    /// * `budget` - amount of work allowed for improving path.
+    ///
+    /// # Returns
+    /// `Some` with best found path triangles on nav mesh and path cost or `None` if there is no
+    /// path.
+    pub fn find_path_triangles_anytime(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        budget: NavQueryBudget,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        self.anytime_search(
+            from,
+            to,
+            weight,
+            budget,
+            |a, b| self.connection_cost(a, b),
+            |index| self.estimate_path_cost(index, to),
+        )
+    }
+
+    /// Find path on nav mesh between two triangles with anytime search, using custom cost
+    /// callback like `NavMesh::find_path_triangles_weighted_with_filter` does.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `weight` - heuristic weight of first search.
+    /// * `budget` - amount of work allowed for improving path.
+    /// * `filter` - cost callback.
+    ///
+    /// # Returns
+    /// `Some` with best found path triangles on nav mesh and path cost or `None` if there is no
+    /// path.
+    pub fn find_path_triangles_anytime_with_filter<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        budget: NavQueryBudget,
+        filter: &Q,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        let estimate = self.centers_distance_estimate();
+        self.anytime_search(
+            from,
+            to,
+            weight,
+            budget,
+            |a, b| self.search_filter_cost(a, b, filter),
+            |index| estimate(index, to),
+        )
+    }
+
+    fn anytime_search<C, H>(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        budget: NavQueryBudget,
+        cost: C,
+        heuristic: H,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        C: Fn(usize, usize) -> Option<Scalar>,
+        H: Fn(usize) -> Scalar,
+    {
+        let mut weight = weight.max(1.0);
+        let mut best = self.weighted_search(from, to, weight, &cost, &heuristic, || false)?;
+        let timer = budget.timer();
+        let mut expansions = 0;
+        while weight > 1.0 {
+            weight = 1.0 + (weight - 1.0) * 0.5;
+            if weight - 1.0 < ANYTIME_WEIGHT_TRESHOLD {
+                weight = 1.0;
+            }
+            let found = self.weighted_search(from, to, weight, &cost, &heuristic, || {
+                expansions += 1;
+                match budget {
+                    NavQueryBudget::Expansions(limit) => expansions > limit,
+                    NavQueryBudget::Time(limit) => {
+                        timer.is_some_and(|timer| timer.elapsed() >= limit)
+                    }
+                }
+            });
+            match found {
+                Some(path) => {
+                    if path.1 < best.1 {
+                        best = path;
+                    }
+                }
+                None => break,
+            }
+        }
+        Some(best)
+    }
+
+    /// Find path on nav mesh between two points with bidirectional A* search, that expands
+    /// triangles from both points at the same time.
+    ///
+    /// Search uses the same connections costs and landmarks estimates as `NavMesh::find_path`,
+    /// and finds the same optimal path cost while expanding less triangles on long corridor-like
+    /// nav meshes.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    pub fn find_path_bidirectional(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<Vec<NavVec3>> {
+        self.find_path_with(from, to, query, mode, |start, end| {
+            self.find_path_triangles_bidirectional(start, end)
+        })
+    }
+
+    /// Find path on nav mesh between two triangles with bidirectional A* search.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path cost if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let path = mesh.find_path_triangles_bidirectional(1, 2).unwrap().0;
+    /// assert_eq!(path, vec![1, 0, 3, 2]);
+    /// ```
+    pub fn find_path_triangles_bidirectional(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        self.bidirectional_search(
+            from,
+            to,
+            |a, b| self.connection_cost(a, b),
+            |a, b| self.estimate_path_cost(a, b),
+        )
+    }
+
+    /// Find path on nav mesh between two triangles with bidirectional A* search, using custom
+    /// cost callback like `NavMesh::find_path_triangles_with_filter` does.
+    ///
+    /// Remaining cost is estimated with distance between triangles centers scaled by the smallest
+    /// area cost, so found path is optimal as long as filter costs are not smaller than moves
+    /// lengths.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `filter` - cost callback.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path cost if found or `None` otherwise.
+    pub fn find_path_triangles_bidirectional_with_filter<Q>(
+        &self,
+        from: usize,
+        to: usize,
+        filter: &Q,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.bidirectional_search(
+            from,
+            to,
+            |a, b| self.search_filter_cost(a, b, filter),
+            self.centers_distance_estimate(),
+        )
+    }
+
+    /// Bidirectional A* search over triangles connections.
+    ///
+    /// # Arguments
+    /// * `cost` - cost of moving between connected triangles, `None` if move is not allowed.
+    /// * `estimate` - lower bound of path cost between two triangles.
+    fn bidirectional_search<C, E>(
+        &self,
+        from: usize,
+        to: usize,
+        cost: C,
+        estimate: E,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        C: Fn(usize, usize) -> Option<Scalar>,
+        E: Fn(usize, usize) -> Scalar,
+    {
+        let areas = self.areas();
+        if from >= areas.len()
+            || to >= areas.len()
+            || self.is_triangle_blocked(from)
+            || self.is_triangle_blocked(to)
+            || !self.are_triangles_connected(from, to)
+        {
+            return None;
+        }
+        if from == to {
+            return Some((vec![from], 0.0));
+        }
+        // average of forward and backward heuristics, keeps both searches consistent.
+        let potential = |index: usize| (estimate(index, to) - estimate(from, index)) * 0.5;
+        // [forward, backward]
+        let mut open = [BinaryHeap::new(), BinaryHeap::new()];
+        let mut costs = [HashMap::new(), HashMap::new()];
+        let mut parents = [HashMap::new(), HashMap::new()];
+        let mut closed = [HashSet::new(), HashSet::new()];
+        open[0].push(OpenNode(potential(from), from));
+        open[1].push(OpenNode(-potential(to), to));
+        costs[0].insert(from, 0.0);
+        costs[1].insert(to, 0.0);
+        let mut best = Scalar::INFINITY;
+        let mut meeting = None;
+        while let (Some(forward), Some(backward)) = (open[0].peek(), open[1].peek()) {
+            let keys = (forward.0, backward.0);
+            if keys.0 + keys.1 >= best {
+                break;
+            }
+            let side = if keys.0 <= keys.1 { 0 } else { 1 };
+            let other = 1 - side;
+            let OpenNode(_, current) = open[side].pop().unwrap();
+            if !closed[side].insert(current) {
+                continue;
+            }
+            let total = costs[side][&current];
+            for next in self.triangle_neighbors(current) {
+                if closed[side].contains(&next) {
+                    continue;
+                }
+                // backward search walks connections in reverse.
+                let step = if side == 0 {
+                    cost(current, next)
+                } else {
+                    cost(next, current)
+                };
+                let next_cost = match step {
+                    Some(step) => total + step,
+                    None => continue,
+                };
+                if next_cost < costs[side].get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                    costs[side].insert(next, next_cost);
+                    parents[side].insert(next, current);
+                    let key = if side == 0 {
+                        potential(next)
+                    } else {
+                        -potential(next)
+                    };
+                    open[side].push(OpenNode(next_cost + key, next));
+                    if let Some(rest) = costs[other].get(&next) {
+                        if next_cost + rest < best {
+                            best = next_cost + rest;
+                            meeting = Some(next);
+                        }
+                    }
+                }
+            }
+        }
+        let meeting = meeting?;
+        let mut path = vec![meeting];
+        let mut node = meeting;
+        while let Some(parent) = parents[0].get(&node) {
+            path.push(*parent);
+            node = *parent;
+        }
+        path.reverse();
+        let mut node = meeting;
+        while let Some(parent) = parents[1].get(&node) {
+            path.push(*parent);
+            node = *parent;
+        }
+        Some((path, best))
+    }
+
+    /// Connection cost asked from query filter, or `None` if either triangle is blocked.
+    fn search_filter_cost<Q>(&self, from: usize, to: usize, filter: &Q) -> Option<Scalar>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.connection_cost(from, to)?;
+        self.filtered_connection_cost(from, to, filter)
+    }
+
+    /// Distance between triangles centers scaled by the smallest area cost, lower bound of
+    /// filtered moves cost as long as filter costs are not smaller than moves lengths.
+    fn centers_distance_estimate(&self) -> impl Fn(usize, usize) -> Scalar + '_ {
+        let min_cost = self.min_area_cost();
+        move |a, b| (self.areas()[b].center - self.areas()[a].center).magnitude() * min_cost
+    }
+
+    fn min_area_cost(&self) -> Scalar {
+        self.areas()
+            .iter()
+            .map(|area| area.cost)
+            .fold(Scalar::INFINITY, Scalar::min)
+            .min(1.0)
+    }
+
+    /// Weighted A* search over triangles connections.
+    ///
+    /// # Arguments
+    /// * `cost` - cost of moving between connected triangles, `None` if move is not allowed.
+    /// * `heuristic` - estimated cost to target, scaled by `weight`.
+    /// * `exhausted` - called before every expansion, search is aborted when it returns true.
+    fn weighted_search<C, H, F>(
+        &self,
+        from: usize,
+        to: usize,
+        weight: Scalar,
+        cost: C,
+        heuristic: H,
+        mut exhausted: F,
+    ) -> Option<(Vec<usize>, Scalar)>
+    where
+        C: Fn(usize, usize) -> Option<Scalar>,
+        H: Fn(usize) -> Scalar,
+        F: FnMut() -> bool,
+    {
+        let areas = self.areas();
+        if from >= areas.len()
+            || to >= areas.len()
+            || self.is_triangle_blocked(from)
+            || self.is_triangle_blocked(to)
+            || !self.are_triangles_connected(from, to)
+        {
+            return None;
+        }
+        let weight = weight.max(1.0);
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut parents = HashMap::new();
+        let mut closed = HashSet::new();
+        open.push(OpenNode(heuristic(from) * weight, from));
+        costs.insert(from, 0.0);
+        while let Some(OpenNode(_, current)) = open.pop() {
+            if !closed.insert(current) {
+                continue;
+            }
+            let total = costs[&current];
+            if current == to {
+                let mut path = vec![current];
+                let mut node = current;
+                while let Some(parent) = parents.get(&node) {
+                    path.push(*parent);
+                    node = *parent;
+                }
+                path.reverse();
+                return Some((path, total));
+            }
+            if exhausted() {
+                return None;
+            }
+            for next in self.triangle_neighbors(current) {
+                if closed.contains(&next) {
+                    continue;
+                }
+                let next_cost = match cost(current, next) {
+                    Some(step) => total + step,
+                    None => continue,
+                };
+                if next_cost < costs.get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, current);
+                    open.push(OpenNode(next_cost + heuristic(next) * weight, next));
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{Error, NavMesh, NavResult, NavTriangle, NavVec3, Scalar};
+use std::collections::HashMap;
+
+impl NavMesh {
+    /// Reduce triangles count by merging neighbor triangles lying on the same plane into convex
+    /// polygons and triangulating them again without their inner vertices.
+    ///
+    /// Nav mesh boundary and borders between areas with different properties (cost, type,
+    /// flags, clearance) are kept as they are, so are all vertices lying on them. Obstacles are
+    /// not kept.
+    ///
+    /// # Arguments
+    /// * `max_error` - maximal distance of merged triangles vertices from plane of polygon they
+    ///   get merged into.
+    ///
+    /// # Returns
+    /// Simplified nav mesh or error if it could not be built.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let size = 4;
+    /// let mut vertices = vec![];
+    /// let mut triangles = vec![];
+    /// for row in 0..=size {
+    ///     for col in 0..=size {
+    ///         vertices.push((col as Scalar, row as Scalar, 0.0).into());
+    ///         if row < size && col < size {
+    ///             let i = row * (size + 1) + col;
+    ///             triangles.push((i, i + 1, i + size + 2).into());
+    ///             triangles.push((i + size + 2, i + size + 1, i).into());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let simplified = mesh.simplify(0.01).unwrap();
+    /// assert!(simplified.triangles().len() < mesh.triangles().len());
+    /// assert!(simplified.vertices().len() < mesh.vertices().len());
+    /// ```
+    pub fn simplify(&self, max_error: Scalar) -> NavResult<Self> {
+        let epsilon = self.tolerance().distance_sqr;
+        let areas = self.areas();
+        let point = |index: u32| self.vertices()[index as usize];
+        let polygons = self.convex_polygons(max_error);
+        let mut result_vertices = vec![];
+        // {source vertice: result vertice}
+        let mut used = HashMap::new();
+        let mut result_triangles = vec![];
+        for (outline, source) in polygons {
+            let points = outline
+                .iter()
+                .map(|index| point(*index))
+                .collect::<Vec<_>>();
+            for (a, b, c) in triangulate_convex(&points, epsilon) {
+                let mut vertice = |local: usize| {
+                    *used.entry(outline[local]).or_insert_with(|| {
+                        result_vertices.push(points[local]);
+                        result_vertices.len() as u32 - 1
+                    })
+                };
+                let triangle = NavTriangle::from((vertice(a), vertice(b), vertice(c)));
+                result_triangles.push((triangle, source));
+            }
+        }
+        if result_triangles.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        let mut result = Self::new(
+            result_vertices,
+            result_triangles.iter().map(|(t, _)| *t).collect(),
+        )?;
+        for (index, (_, source)) in result_triangles.into_iter().enumerate() {
+            let area = &areas[source];
+            result.set_area_cost(index, area.cost);
+            result.set_area_type(index, area.area_type);
+            result.set_area_flags(index, area.flags);
+            result.set_area_clearance(index, area.clearance);
+        }
+        result.set_up_axis(self.up_axis());
+        result.set_tolerance(self.tolerance());
+        Ok(result)
+    }
+
+    /// Group neighbor triangles lying on the same plane and having the same area properties
+    /// into convex polygons.
+    ///
+    /// # Returns
+    /// List of (polygon outline vertices, index of one of triangles polygon is made of).
+    pub(crate) fn convex_polygons(&self, max_error: Scalar) -> Vec<(Vec<u32>, usize)> {
+        let epsilon = self.tolerance().distance_sqr;
+        let max_error = max_error.max(0.0);
+        let vertices = self.vertices();
+        let triangles = self.triangles();
+        let areas = self.areas();
+        let area_key = |index: usize| {
+            let area = &areas[index];
+            (area.cost, area.area_type, area.flags, area.clearance)
+        };
+        // {directed edge: triangle index}
+        let edges = triangles
+            .iter()
+            .enumerate()
+            .flat_map(|(index, t)| {
+                [
+                    ((t.first, t.second), (index, t.third)),
+                    ((t.second, t.third), (index, t.first)),
+                    ((t.third, t.first), (index, t.second)),
+                ]
+            })
+            .collect::<HashMap<_, _>>();
+        let point = |index: u32| vertices[index as usize];
+
+        let mut assigned = vec![false; triangles.len()];
+        // [(polygon outline, source triangle)]
+        let mut polygons = vec![];
+        for seed in 0..triangles.len() {
+            if assigned[seed] {
+                continue;
+            }
+            assigned[seed] = true;
+            let t = triangles[seed];
+            let origin = point(t.first);
+            let normal = (point(t.second) - origin)
+                .cross(point(t.third) - origin)
+                .normalize();
+            let is_convex = |a: u32, b: u32, c: u32| {
+                (point(b) - point(a)).cross(point(c) - point(b)).dot(normal) >= -epsilon
+            };
+            let mut outline = vec![t.first, t.second, t.third];
+            let mut changed = true;
+            while changed {
+                changed = false;
+                let mut i = 0;
+                while i < outline.len() {
+                    let count = outline.len();
+                    let p = outline[i];
+                    let q = outline[(i + 1) % count];
+                    let (other, v) = match edges.get(&(q, p)) {
+                        Some(found) => *found,
+                        None => {
+                            i += 1;
+                            continue;
+                        }
+                    };
+                    let accepted = !assigned[other]
+                        && area_key(other) == area_key(seed)
+                        && point(v).distance_to_plane(origin, normal).abs() <= max_error
+                        && {
+                            let t = triangles[other];
+                            (point(t.second) - point(t.first))
+                                .cross(point(t.third) - point(t.first))
+                                .dot(normal)
+                                > 0.0
+                        };
+                    if accepted {
+                        let prev = outline[(i + count - 1) % count];
+                        let next = outline[(i + 2) % count];
+                        if v == prev {
+                            // triangle fills notch at `p`, which becomes inner vertice.
+                            if count > 3
+                                && is_convex(outline[(i + count - 2) % count], v, q)
+                                && is_convex(v, q, next)
+                            {
+                                outline.remove(i);
+                                assigned[other] = true;
+                                changed = true;
+                                continue;
+                            }
+                        } else if v == next {
+                            // triangle fills notch at `q`, which becomes inner vertice.
+                            if count > 3
+                                && is_convex(prev, p, v)
+                                && is_convex(p, v, outline[(i + 3) % count])
+                            {
+                                outline.remove((i + 1) % count);
+                                assigned[other] = true;
+                                changed = true;
+                                continue;
+                            }
+                        } else if !outline.contains(&v)
+                            && is_convex(prev, p, v)
+                            && is_convex(p, v, q)
+                            && is_convex(v, q, next)
+                        {
+                            outline.insert(i + 1, v);
+                            assigned[other] = true;
+                            changed = true;
+                            continue;
+                        }
+                    }
+                    i += 1;
+                }
+            }
+            polygons.push((outline, seed));
+        }
+        polygons
+    }
+}
+
+/// Triangulate convex polygon keeping all of its points, including ones lying on its edges, so
+/// edges shared with neighbor polygons stay connected.
+pub(crate) fn triangulate_convex(
+    polygon: &[NavVec3],
+    epsilon: Scalar,
+) -> Vec<(usize, usize, usize)> {
+    let mut indices = (0..polygon.len()).collect::<Vec<_>>();
+    let mut result = Vec::with_capacity(polygon.len().saturating_sub(2));
+    while indices.len() > 2 {
+        let count = indices.len();
+        let ear = (0..count).find(|i| {
+            let a = polygon[indices[(i + count - 1) % count]];
+            let b = polygon[indices[*i]];
+            let c = polygon[indices[(i + 1) % count]];
+            (b - a).cross(c - a).magnitude() > epsilon
+        });
+        match ear {
+            Some(i) => {
+                result.push((
+                    indices[(i + count - 1) % count],
+                    indices[i],
+                    indices[(i + 1) % count],
+                ));
+                indices.remove(i);
+            }
+            None => break,
+        }
+    }
+    result
+}
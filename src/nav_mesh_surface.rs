@@ -0,0 +1,275 @@
+use crate::{NavMesh, NavQuery, NavVec3, SCALAR_MAX};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Result of moving along nav mesh surface.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavSurfaceMove {
+    /// Reached point on nav mesh surface.
+    pub position: NavVec3,
+    /// Indices of triangles leading from start triangle to one containing reached point.
+    pub triangles: Vec<usize>,
+}
+
+impl NavSurfaceMove {
+    /// Index of triangle containing reached point.
+    #[inline]
+    pub fn triangle(&self) -> usize {
+        *self.triangles.last().unwrap()
+    }
+}
+
+impl NavMesh {
+    /// Find point on walkable nav mesh surface closest to given point, skipping triangles that
+    /// are blocked by obstacles or closed gates.
+    ///
+    /// Unlike snapping to nearest vertex, result lies anywhere on triangles or their edges.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with point on nav mesh surface or `None` if there is no walkable triangle.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// assert_eq!(
+    ///     mesh.clamp_point((0.5, 2.0, 1.0).into()),
+    ///     Some((0.5, 1.0, 0.0).into()),
+    /// );
+    /// ```
+    pub fn clamp_point(&self, point: NavVec3) -> Option<NavVec3> {
+        self.clamp_point_triangle(point).map(|(_, point)| point)
+    }
+
+    /// Project polyline onto walkable nav mesh surface. Every point gets clamped to the surface
+    /// and extra points are inserted where segments cross edges between triangles of different
+    /// slope, so projected polyline follows the surface instead of cutting through it.
+    ///
+    /// # Arguments
+    /// * `polyline` - list of points.
+    ///
+    /// # Returns
+    /// List of points lying on nav mesh surface, empty if there is no walkable triangle.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let points = mesh.project_path_onto_mesh(&[
+    ///     (0.5, 0.5, 1.0).into(),
+    ///     (1.5, 0.5, 0.5).into(),
+    /// ]);
+    /// assert_eq!(
+    ///     points,
+    ///     vec![
+    ///         (0.5, 0.5, 0.0).into(),
+    ///         (1.0, 0.5, 0.0).into(),
+    ///         (1.5, 0.5, 0.5).into(),
+    ///     ]
+    /// );
+    /// ```
+    pub fn project_path_onto_mesh(&self, polyline: &[NavVec3]) -> Vec<NavVec3> {
+        let tolerance = self.tolerance();
+        let mut result = Vec::with_capacity(polyline.len());
+        let mut previous = None;
+        for point in polyline {
+            let (triangle, point) = match self.clamp_point_triangle(*point) {
+                Some(item) => item,
+                None => return vec![],
+            };
+            if let Some((start, from)) = previous {
+                let (visited, _) = self.walk_line(start, from, point, tolerance);
+                for pair in visited.windows(2) {
+                    if let Some(crossing) = self.slope_crossing(pair[0], pair[1], from, point) {
+                        result.push(crossing);
+                    }
+                }
+            }
+            result.push(point);
+            previous = Some((triangle, point));
+        }
+        result.dedup_by(|a, b| a.same_as_within(*b, tolerance.distance_sqr));
+        result
+    }
+
+    /// Move point along walkable nav mesh surface towards target point, walking across
+    /// triangles and sliding along boundary edges and blocked triangles instead of stopping at
+    /// them, like Detour `moveAlongSurface` does.
+    ///
+    /// Only triangles near the movement segment are explored, so it is meant for small per frame
+    /// movements of character controllers rather than for long distances.
+    ///
+    /// # Arguments
+    /// * `start` - start point.
+    /// * `end` - target point.
+    ///
+    /// # Returns
+    /// `Some` with point on nav mesh surface closest to target point that can be reached from
+    /// start point and triangles visited on the way there, or `None` if there is no walkable
+    /// triangle.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let result = mesh
+    ///     .move_along_surface((0.5, 0.5, 0.0).into(), (1.5, 1.5, 0.0).into())
+    ///     .unwrap();
+    /// assert_eq!(result.position, (1.5, 1.0, 0.0).into());
+    /// assert_eq!(result.triangle(), 3);
+    /// ```
+    pub fn move_along_surface(&self, start: NavVec3, end: NavVec3) -> Option<NavSurfaceMove> {
+        let (triangle, start) = self.clamp_point_triangle(start)?;
+        let (position, triangles) = self.slide(triangle, start, end);
+        Some(NavSurfaceMove {
+            position,
+            triangles,
+        })
+    }
+
+    fn clamp_point_triangle(&self, point: NavVec3) -> Option<(usize, NavVec3)> {
+        if let Some(index) = self.find_closest_triangle(point, NavQuery::Accuracy) {
+            if !self.is_triangle_blocked(index) {
+                return Some((index, self.spatial(index).closest_point(point)));
+            }
+        }
+        (0..self.triangles().len())
+            .filter(|index| !self.is_triangle_blocked(*index))
+            .map(|index| {
+                let closest = self.spatial(index).closest_point(point);
+                (index, closest, closest.distance_squared(point))
+            })
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
+            .map(|(index, closest, _)| (index, closest))
+    }
+
+    /// Point where segment crosses edge between two connected triangles, if they have different
+    /// slopes.
+    fn slope_crossing(
+        &self,
+        from_triangle: usize,
+        to_triangle: usize,
+        from: NavVec3,
+        to: NavVec3,
+    ) -> Option<NavVec3> {
+        let tolerance = self.tolerance();
+        let n = self.spatial(from_triangle).normal();
+        let m = self.spatial(to_triangle).normal();
+        if n.dot(m) >= 1.0 - tolerance.angular {
+            return None;
+        }
+        let (a, b) = self.triangles_portal(from_triangle, to_triangle)?;
+        let up = (n + m).normalize();
+        let df = (b - a).cross(from - a).dot(up);
+        let dt = (b - a).cross(to - a).dot(up);
+        if (df - dt).abs() < tolerance.distance_sqr {
+            return None;
+        }
+        let point = NavVec3::unproject(from, to, (df / (df - dt)).clamp(0.0, 1.0));
+        Some(NavVec3::unproject(
+            a,
+            b,
+            point.project(a, b).clamp(0.0, 1.0),
+        ))
+    }
+
+    /// Explore walkable triangles around segment from start point to target point and find
+    /// point closest to target on them, so movement blocked by boundary slides along it.
+    ///
+    /// # Returns
+    /// Pair of reached point and triangles leading from start triangle to one containing it.
+    pub(crate) fn slide(&self, start: usize, from: NavVec3, to: NavVec3) -> (NavVec3, Vec<usize>) {
+        let epsilon = self.tolerance().distance;
+        let center = (from + to) * 0.5;
+        let radius = from.distance(to) * 0.5 + epsilon;
+        let radius2 = radius * radius;
+        // {triangle: parent triangle}
+        let mut parents = HashMap::<usize, Option<usize>>::new();
+        parents.insert(start, None);
+        let mut open = vec![start];
+        let mut best = (self.spatial(start).closest_point(to), SCALAR_MAX, start);
+        while let Some(current) = open.pop() {
+            let closest = self.spatial(current).closest_point(to);
+            let distance = closest.distance_squared(to);
+            if distance < best.1 {
+                best = (closest, distance, current);
+                if distance <= epsilon * epsilon {
+                    break;
+                }
+            }
+            for next in self.triangle_neighbors(current) {
+                if parents.contains_key(&next) || self.is_triangle_blocked(next) {
+                    continue;
+                }
+                let (a, b) = match self.triangles_portal(current, next) {
+                    Some(portal) => portal,
+                    None => continue,
+                };
+                if center.distance_squared_to_segment(a, b) > radius2 {
+                    continue;
+                }
+                parents.insert(next, Some(current));
+                open.push(next);
+            }
+        }
+        let mut visited = vec![best.2];
+        while let Some(Some(parent)) = parents.get(visited.last().unwrap()) {
+            visited.push(*parent);
+        }
+        visited.reverse();
+        (best.0, visited)
+    }
+}
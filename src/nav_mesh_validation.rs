@@ -0,0 +1,326 @@
+use crate::{
+    nav_mesh_csg::NavVerticesWelder, Error, NavConnection, NavMesh, NavResult, NavTolerance,
+    NavTriangle, NavVec3, Scalar,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Islands with area smaller than this part of whole nav mesh area are reported as slivers.
+const SLIVER_AREA_FACTOR: Scalar = 0.01;
+
+/// Problems found in nav mesh by `NavMesh::validate`.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMeshReport {
+    /// Triangles with repeated vertices or zero area.
+    pub degenerate_triangles: Vec<usize>,
+    /// Pairs of (first, duplicate) vertices lying at the same point.
+    pub duplicate_vertices: Vec<(usize, usize)>,
+    /// Edges shared by more than two triangles.
+    pub non_manifold_edges: Vec<NavConnection>,
+    /// Triangles facing against nav mesh up axis.
+    pub inverted_triangles: Vec<usize>,
+    /// Islands of triangles with tiny area compared to whole nav mesh area.
+    pub slivers: Vec<Vec<usize>>,
+}
+
+impl NavMeshReport {
+    /// Tells if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.degenerate_triangles.is_empty()
+            && self.duplicate_vertices.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.inverted_triangles.is_empty()
+            && self.slivers.is_empty()
+    }
+}
+
+/// Changes made to input data by `NavMesh::new_welded`.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavWeldReport {
+    /// Index of nav mesh vertice for every input vertice.
+    pub vertices_map: Vec<u32>,
+    /// Index of nav mesh triangle for every input triangle, `None` for dropped ones.
+    pub triangles_map: Vec<Option<usize>>,
+    /// Pairs of (first, welded) input vertices lying within weld distance.
+    pub welded_vertices: Vec<(usize, usize)>,
+    /// Input triangles dropped because of repeated vertices or zero area.
+    pub degenerate_triangles: Vec<usize>,
+    /// Pairs of (first, duplicate) input triangles using the same vertices, duplicates are
+    /// dropped.
+    pub duplicate_triangles: Vec<(usize, usize)>,
+}
+
+impl NavWeldReport {
+    /// Tells if input data was used without changes.
+    pub fn is_unchanged(&self) -> bool {
+        self.welded_vertices.is_empty()
+            && self.degenerate_triangles.is_empty()
+            && self.duplicate_triangles.is_empty()
+    }
+}
+
+impl NavMesh {
+    /// Create new nav mesh object from vertices and triangles like `NavMesh::new` does, first
+    /// welding vertices lying within weld distance, dropping degenerate and duplicate triangles
+    /// and remapping indices, so meshes exported from modeling tools get proper adjacency.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of vertices points.
+    /// * `triangles` - list of vertices indices that produces triangles.
+    /// * `tolerance` - weld distance and zero area treshold, set as nav mesh tolerance.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh object and report of changes made to input data, or `Err` with
+    /// `Error::TriangleVerticeIndexOutOfBounds` if input data is invalid or
+    /// `Error::NoWalkableSurface` if no triangle is left.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (1.0, 1.0, 0.0).into(), // 3
+    ///     (0.0, 1.0, 0.0).into(), // 4
+    ///     (0.0, 0.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 2).into(), // 0
+    ///     (3, 4, 5).into(), // 1
+    ///     (1, 2, 0).into(), // 2
+    ///     (0, 1, 1).into(), // 3
+    /// ];
+    ///
+    /// let (mesh, report) =
+    ///     NavMesh::new_welded(vertices, triangles, NavTolerance::default()).unwrap();
+    /// assert_eq!(mesh.vertices().len(), 4);
+    /// assert_eq!(mesh.triangles().len(), 2);
+    /// assert_eq!(report.welded_vertices, vec![(2, 3), (0, 5)]);
+    /// assert_eq!(report.duplicate_triangles, vec![(0, 2)]);
+    /// assert_eq!(report.degenerate_triangles, vec![3]);
+    /// assert_eq!(report.triangles_map, vec![Some(0), Some(1), None, None]);
+    /// // welded triangles share edge.
+    /// assert_eq!(mesh.triangle_neighbors(0).collect::<Vec<_>>(), vec![1]);
+    /// ```
+    pub fn new_welded(
+        vertices: Vec<NavVec3>,
+        triangles: Vec<NavTriangle>,
+        tolerance: NavTolerance,
+    ) -> NavResult<(Self, NavWeldReport)> {
+        for (index, t) in triangles.iter().enumerate() {
+            for (slot, vertice) in [t.first, t.second, t.third].into_iter().enumerate() {
+                if vertice as usize >= vertices.len() {
+                    return Err(Error::TriangleVerticeIndexOutOfBounds(
+                        index as u32,
+                        slot as u8,
+                        vertice,
+                    ));
+                }
+            }
+        }
+        let mut report = NavWeldReport::default();
+        let mut welder = NavVerticesWelder::new(tolerance.weld_distance);
+        // {welded vertice: first vertice}
+        let mut firsts = HashMap::new();
+        for (index, vertice) in vertices.iter().enumerate() {
+            let welded = welder.weld(*vertice);
+            match firsts.get(&welded) {
+                Some(first) => report.welded_vertices.push((*first, index)),
+                None => {
+                    firsts.insert(welded, index);
+                }
+            }
+            report.vertices_map.push(welded);
+        }
+        let welded = welder.into_vertices();
+        // {sorted triangle vertices: first triangle}
+        let mut unique = HashMap::new();
+        let mut result = vec![];
+        for (index, t) in triangles.iter().enumerate() {
+            let a = report.vertices_map[t.first as usize];
+            let b = report.vertices_map[t.second as usize];
+            let c = report.vertices_map[t.third as usize];
+            let cross = (welded[b as usize] - welded[a as usize])
+                .cross(welded[c as usize] - welded[a as usize]);
+            if a == b || b == c || c == a || cross.magnitude() <= tolerance.distance_sqr {
+                report.degenerate_triangles.push(index);
+                report.triangles_map.push(None);
+                continue;
+            }
+            let mut key = [a, b, c];
+            key.sort_unstable();
+            if let Some(first) = unique.get(&key) {
+                report.duplicate_triangles.push((*first, index));
+                report.triangles_map.push(None);
+                continue;
+            }
+            unique.insert(key, index);
+            report.triangles_map.push(Some(result.len()));
+            result.push(NavTriangle::from((a, b, c)));
+        }
+        if result.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        let mut mesh = Self::new(welded, result)?;
+        mesh.set_tolerance(tolerance);
+        Ok((mesh, report))
+    }
+
+    /// Find problems that make path finding silently fail or produce bad paths, common in hand
+    /// authored nav meshes.
+    ///
+    /// # Returns
+    /// Report of found problems.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (1.0, 1.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (0.0, 0.0, 0.0).into(), // 4
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 4).into()];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let report = mesh.validate();
+    /// assert_eq!(report.duplicate_vertices, vec![(0, 4)]);
+    /// assert!(mesh.repair().unwrap().validate().is_valid());
+    /// ```
+    pub fn validate(&self) -> NavMeshReport {
+        let mut report = NavMeshReport::default();
+        let mut welder = NavVerticesWelder::new(self.tolerance().weld_distance);
+        // {welded vertice: first vertice}
+        let mut firsts = HashMap::new();
+        for (index, vertice) in self.vertices().iter().enumerate() {
+            let welded = welder.weld(*vertice);
+            match firsts.get(&welded) {
+                Some(first) => report.duplicate_vertices.push((*first, index)),
+                None => {
+                    firsts.insert(welded, index);
+                }
+            }
+        }
+        let up = self.up_axis().up();
+        // {edge: triangles count}
+        let mut edges = HashMap::<NavConnection, usize>::new();
+        for (index, t) in self.triangles().iter().enumerate() {
+            let normal = self.triangle_cross(t);
+            if t.first == t.second
+                || t.second == t.third
+                || t.third == t.first
+                || normal.magnitude() <= self.tolerance().distance_sqr
+            {
+                report.degenerate_triangles.push(index);
+                continue;
+            }
+            if normal.dot(up) < -self.tolerance().distance_sqr {
+                report.inverted_triangles.push(index);
+            }
+            for (a, b) in [(t.first, t.second), (t.second, t.third), (t.third, t.first)] {
+                *edges.entry(NavConnection(a, b)).or_default() += 1;
+            }
+        }
+        report.non_manifold_edges = edges
+            .into_iter()
+            .filter(|(_, count)| *count > 2)
+            .map(|(edge, _)| edge)
+            .collect();
+        report
+            .non_manifold_edges
+            .sort_by_key(|edge| (edge.0.min(edge.1), edge.0.max(edge.1)));
+        let islands = self.find_triangle_islands();
+        if islands.len() > 1 {
+            let area = |list: &[usize]| {
+                list.iter()
+                    .map(|index| self.areas()[*index].size)
+                    .sum::<Scalar>()
+            };
+            let total = area(&(0..self.triangles().len()).collect::<Vec<_>>());
+            report.slivers = islands
+                .into_iter()
+                .filter(|island| area(island) < total * SLIVER_AREA_FACTOR)
+                .collect();
+        }
+        report
+    }
+
+    /// Fix problems found by `validate`: weld duplicate vertices, remove degenerate triangles
+    /// and flip triangles facing against up axis.
+    ///
+    /// Non manifold edges and slivers are left as they are, since they need decision about which
+    /// part is correct. Areas properties are kept, obstacles are not.
+    ///
+    /// # Returns
+    /// Repaired nav mesh or error if no triangle is left.
+    pub fn repair(&self) -> NavResult<Self> {
+        let mut welder = NavVerticesWelder::new(self.tolerance().weld_distance);
+        let indices = self
+            .vertices()
+            .iter()
+            .map(|vertice| welder.weld(*vertice))
+            .collect::<Vec<_>>();
+        let welded = welder.into_vertices();
+        let up = self.up_axis().up();
+        let mut vertices = vec![];
+        // {welded vertice: used vertice}
+        let mut used = HashMap::new();
+        let mut triangles = vec![];
+        for (index, t) in self.triangles().iter().enumerate() {
+            let a = indices[t.first as usize];
+            let b = indices[t.second as usize];
+            let c = indices[t.third as usize];
+            if a == b || b == c || c == a {
+                continue;
+            }
+            let normal = (welded[b as usize] - welded[a as usize])
+                .cross(welded[c as usize] - welded[a as usize]);
+            if normal.magnitude() <= self.tolerance().distance_sqr {
+                continue;
+            }
+            let (b, c) = if normal.dot(up) < -self.tolerance().distance_sqr {
+                (c, b)
+            } else {
+                (b, c)
+            };
+            let mut vertice = |welded_index: u32| {
+                *used.entry(welded_index).or_insert_with(|| {
+                    vertices.push(welded[welded_index as usize]);
+                    vertices.len() as u32 - 1
+                })
+            };
+            let triangle = NavTriangle::from((vertice(a), vertice(b), vertice(c)));
+            triangles.push((triangle, index));
+        }
+        if triangles.is_empty() {
+            return Err(Error::NoWalkableSurface);
+        }
+        let mut result = Self::new(vertices, triangles.iter().map(|(t, _)| *t).collect())?;
+        for (index, (_, triangle)) in triangles.into_iter().enumerate() {
+            let area = &self.areas()[triangle];
+            result.set_area_cost(index, area.cost);
+            result.set_area_type(index, area.area_type);
+            result.set_area_flags(index, area.flags);
+            result.set_area_clearance(index, area.clearance);
+        }
+        result.set_up_axis(self.up_axis());
+        result.set_tolerance(self.tolerance());
+        Ok(result)
+    }
+
+    fn triangle_cross(&self, triangle: &NavTriangle) -> NavVec3 {
+        let vertices = self.vertices();
+        let a = vertices[triangle.first as usize];
+        let b = vertices[triangle.second as usize];
+        let c = vertices[triangle.third as usize];
+        (b - a).cross(c - a)
+    }
+}
@@ -1,19 +1,16 @@
-use crate::{Error, NavConnection, NavResult, NavVec3, Scalar};
+use crate::{Error, NavConnection, NavResult, NavVec3, Scalar, SCALAR_MAX};
 use petgraph::{
     algo::{astar, tarjan_scc},
-    graph::NodeIndex,
+    stable_graph::{EdgeIndex, NodeIndex, StableGraph},
     visit::EdgeRef,
-    Graph, Undirected,
+    Undirected,
 };
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use spade::{rtree::RTree, BoundingRect, SpatialObject};
 use std::collections::HashMap;
-#[cfg(not(feature = "scalar64"))]
-use std::f32::MAX as SCALAR_MAX;
-#[cfg(feature = "scalar64")]
-use std::f64::MAX as SCALAR_MAX;
 use typid::ID;
 
 #[cfg(feature = "parallel")]
@@ -29,7 +26,8 @@ macro_rules! iter {
     };
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavSpatialConnection {
     pub connection: NavConnection,
     pub index: usize,
@@ -78,15 +76,21 @@ impl SpatialObject for NavSpatialConnection {
 /// Nav net identifier.
 pub type NavNetID = ID<NavNet>;
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavNet {
     id: NavNetID,
     vertices: Vec<NavVec3>,
     connections: Vec<NavConnection>,
     distances: Vec<Scalar>,
     costs: Vec<Scalar>,
-    graph: Graph<(), Scalar, Undirected>,
+    // connections costs overriding squared distances, missing ones are not overridden.
+    #[cfg_attr(feature = "serde", serde(default))]
+    connections_costs: Vec<Option<Scalar>>,
+    // edges weights are connections indices.
+    graph: StableGraph<(), usize, Undirected>,
     nodes: Vec<NodeIndex>,
+    edges: Vec<EdgeIndex>,
     nodes_map: HashMap<NodeIndex, usize>,
     rtree: RTree<NavSpatialConnection>,
     spatials: Vec<NavSpatialConnection>,
@@ -117,17 +121,18 @@ impl NavNet {
             .collect::<NavResult<Vec<_>>>()?;
 
         let costs = vec![1.0; vertices.len()];
+        let connections_costs = vec![None; connections.len()];
 
-        let mut graph = Graph::<(), Scalar, Undirected>::new_undirected();
+        let mut graph =
+            StableGraph::<(), usize, Undirected>::with_capacity(vertices.len(), connections.len());
         let nodes = (0..vertices.len())
             .map(|_| graph.add_node(()))
             .collect::<Vec<_>>();
-        graph.extend_with_edges(
-            iter!(connections)
-                .enumerate()
-                .map(|(i, conn)| (nodes[conn.0 as usize], nodes[conn.1 as usize], distances[i]))
-                .collect::<Vec<_>>(),
-        );
+        let edges = connections
+            .iter()
+            .enumerate()
+            .map(|(i, conn)| graph.add_edge(nodes[conn.0 as usize], nodes[conn.1 as usize], i))
+            .collect::<Vec<_>>();
         let nodes_map = iter!(nodes).enumerate().map(|(i, n)| (*n, i)).collect();
 
         let spatials = iter!(connections)
@@ -153,8 +158,10 @@ impl NavNet {
             connections,
             distances,
             costs,
+            connections_costs,
             graph,
             nodes,
+            edges,
             nodes_map,
             rtree,
             spatials,
@@ -167,7 +174,9 @@ impl NavNet {
         let vertices = iter!(self.vertices)
             .map(|v| (*v - origin) * value + origin)
             .collect::<Vec<_>>();
-        Self::new(vertices, self.connections.clone())
+        let mut result = Self::new(vertices, self.connections.clone())?;
+        result.connections_costs = self.connections_costs.clone();
+        Ok(result)
     }
 
     #[inline]
@@ -200,6 +209,37 @@ impl NavNet {
         &self.costs
     }
 
+    /// Cost of traversing connection used by path finding, squared connection length unless set
+    /// with `set_connection_cost`.
+    #[inline]
+    pub fn connection_cost(&self, index: usize) -> Option<Scalar> {
+        let distance = *self.distances.get(index)?;
+        Some(
+            self.connections_costs
+                .get(index)
+                .copied()
+                .flatten()
+                .unwrap_or(distance),
+        )
+    }
+
+    /// Override cost of traversing connection, for example with cost authored in external tool.
+    ///
+    /// # Arguments
+    /// * `index` - connection index.
+    /// * `cost` - new cost, `None` restores squared connection length.
+    ///
+    /// # Returns
+    /// `Some` with old cost or `None` if connection does not exists.
+    pub fn set_connection_cost(&mut self, index: usize, cost: Option<Scalar>) -> Option<Scalar> {
+        let old = self.connection_cost(index)?;
+        if self.connections_costs.len() < self.connections.len() {
+            self.connections_costs.resize(self.connections.len(), None);
+        }
+        self.connections_costs[index] = cost.map(|cost| cost.max(0.0));
+        Some(old)
+    }
+
     #[inline]
     pub fn set_vertice_cost(&mut self, index: usize, cost: Scalar) -> Option<Scalar> {
         let c = self.costs.get_mut(index)?;
@@ -208,6 +248,165 @@ impl NavNet {
         Some(old)
     }
 
+    /// Add vertice without any connections.
+    ///
+    /// # Returns
+    /// Index of added vertice.
+    pub fn add_vertice(&mut self, point: NavVec3) -> usize {
+        let index = self.vertices.len();
+        let node = self.graph.add_node(());
+        self.origin = (self.origin * index as Scalar + point) / (index + 1) as Scalar;
+        self.vertices.push(point);
+        self.costs.push(1.0);
+        self.nodes.push(node);
+        self.nodes_map.insert(node, index);
+        index
+    }
+
+    /// Remove vertice together with all its connections. Last vertice takes index of removed
+    /// one, so only connections of removed and last vertices are updated.
+    ///
+    /// # Returns
+    /// `Some` with removed vertice point or `None` if vertice does not exists.
+    pub fn remove_vertice(&mut self, index: usize) -> Option<NavVec3> {
+        let node = *self.nodes.get(index)?;
+        let mut connections = self
+            .graph
+            .edges(node)
+            .map(|edge| *edge.weight())
+            .collect::<Vec<_>>();
+        // removing from the back keeps indices of remaining connections valid.
+        connections.sort_unstable_by(|a, b| b.cmp(a));
+        connections.dedup();
+        for connection in connections {
+            self.remove_connection(connection);
+        }
+        self.graph.remove_node(node);
+        self.nodes_map.remove(&node);
+        let last = self.vertices.len() - 1;
+        let point = self.vertices.swap_remove(index);
+        self.costs.swap_remove(index);
+        self.nodes.swap_remove(index);
+        self.origin = if self.vertices.is_empty() {
+            NavVec3::default()
+        } else {
+            (self.origin * (last + 1) as Scalar - point) / last as Scalar
+        };
+        if index != last {
+            let node = self.nodes[index];
+            self.nodes_map.insert(node, index);
+            let moved = self
+                .graph
+                .edges(node)
+                .map(|edge| *edge.weight())
+                .collect::<Vec<_>>();
+            for connection in moved {
+                let spatial = &mut self.spatials[connection];
+                self.rtree.remove(spatial);
+                let c = &mut self.connections[connection];
+                if c.0 as usize == last {
+                    c.0 = index as u32;
+                }
+                if c.1 as usize == last {
+                    c.1 = index as u32;
+                }
+                spatial.connection = *c;
+                self.rtree.insert(spatial.clone());
+            }
+        }
+        Some(point)
+    }
+
+    /// Add connection between existing vertices.
+    ///
+    /// # Returns
+    /// `Ok` with index of added connection or `Err` if connection vertices indices are invalid.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mut net = NavNet::default();
+    /// let a = net.add_vertice((0.0, 0.0, 0.0).into());
+    /// let b = net.add_vertice((1.0, 0.0, 0.0).into());
+    /// let c = net.add_vertice((1.0, 1.0, 0.0).into());
+    /// net.add_connection(NavConnection(a as u32, b as u32)).unwrap();
+    /// net.add_connection(NavConnection(b as u32, c as u32)).unwrap();
+    /// let path = net
+    ///     .find_path((0.0, 0.1, 0.0).into(), (1.1, 1.0, 0.0).into())
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     path,
+    ///     vec![
+    ///         (0.0, 0.0, 0.0).into(),
+    ///         (1.0, 0.0, 0.0).into(),
+    ///         (1.0, 1.0, 0.0).into(),
+    ///     ]
+    /// );
+    /// ```
+    pub fn add_connection(&mut self, connection: NavConnection) -> NavResult<usize> {
+        let index = self.connections.len();
+        if connection.0 as usize >= self.vertices.len() {
+            return Err(Error::ConnectionVerticeIndexOutOfBounds(
+                index as u32,
+                0,
+                connection.0,
+            ));
+        }
+        if connection.1 as usize >= self.vertices.len() {
+            return Err(Error::ConnectionVerticeIndexOutOfBounds(
+                index as u32,
+                1,
+                connection.1,
+            ));
+        }
+        let a = self.vertices[connection.0 as usize];
+        let b = self.vertices[connection.1 as usize];
+        let edge = self.graph.add_edge(
+            self.nodes[connection.0 as usize],
+            self.nodes[connection.1 as usize],
+            index,
+        );
+        let spatial = NavSpatialConnection::new(connection, index, a, b);
+        self.rtree.insert(spatial.clone());
+        self.spatials.push(spatial);
+        self.connections.push(connection);
+        self.distances.push((b - a).sqr_magnitude());
+        if self.connections_costs.len() == index {
+            self.connections_costs.push(None);
+        }
+        self.edges.push(edge);
+        Ok(index)
+    }
+
+    /// Remove connection. Last connection takes index of removed one.
+    ///
+    /// # Returns
+    /// `Some` with removed connection or `None` if connection does not exists.
+    pub fn remove_connection(&mut self, index: usize) -> Option<NavConnection> {
+        let spatial = self.spatials.get(index)?;
+        self.rtree.remove(spatial);
+        self.graph.remove_edge(self.edges[index]);
+        let last = self.connections.len() - 1;
+        let connection = self.connections.swap_remove(index);
+        self.distances.swap_remove(index);
+        if self.connections_costs.len() == last + 1 {
+            self.connections_costs.swap_remove(index);
+        }
+        self.edges.swap_remove(index);
+        self.spatials.swap_remove(index);
+        if index != last {
+            let spatial = &mut self.spatials[index];
+            self.rtree.remove(spatial);
+            spatial.index = index;
+            self.rtree.insert(spatial.clone());
+            if let Some(weight) = self.graph.edge_weight_mut(self.edges[index]) {
+                *weight = index;
+            }
+        }
+        Some(connection)
+    }
+
     pub fn closest_point(&self, point: NavVec3) -> Option<NavVec3> {
         let index = self.find_closest_connection(point)?;
         Some(self.spatials[index].closest_point(point))
@@ -269,8 +468,9 @@ impl NavNet {
             |e| {
                 let a = self.nodes_map[&e.source()];
                 let b = self.nodes_map[&e.target()];
-                let w = *e.weight();
+                let w = self.distances[*e.weight()];
                 if filter(w, a, b) {
+                    let w = self.connection_cost(*e.weight()).unwrap_or(w);
                     let a = self.costs[a];
                     let b = self.costs[b];
                     w * a * b
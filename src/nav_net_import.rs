@@ -0,0 +1,359 @@
+use crate::{Error, NavConnection, NavNet, NavResult, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, path::Path};
+
+/// Waypoint graph import settings.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavNetImportSettings {
+    /// Convert Y-up coordinates into Z-up coordinates used by nav net.
+    pub y_up_to_z_up: bool,
+    /// Uniform scale applied to imported nodes positions.
+    pub scale: Scalar,
+    /// Edges without authored cost get cost equal to distance between their nodes, otherwise
+    /// they get cost of 1 so every edge costs the same.
+    pub distance_costs: bool,
+}
+
+impl Default for NavNetImportSettings {
+    fn default() -> Self {
+        Self {
+            y_up_to_z_up: false,
+            scale: 1.0,
+            distance_costs: true,
+        }
+    }
+}
+
+/// Loads waypoint graphs (for example patrol networks authored in external graph editors) as
+/// nav nets, from GraphML or simple JSON edge list.
+///
+/// Nodes positions are read from `x`, `y` and `z` node attributes (missing `z` is zero) and edges
+/// costs from `cost` or `weight` edge attributes, see `NavNet::set_connection_cost`. Edges are
+/// imported as undirected connections.
+///
+/// Every import function returns nav net together with source identifiers of its vertices.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavNetImporter {
+    settings: NavNetImportSettings,
+}
+
+impl NavNetImporter {
+    pub fn new(settings: NavNetImportSettings) -> Self {
+        Self { settings }
+    }
+
+    #[inline]
+    pub fn settings(&self) -> &NavNetImportSettings {
+        &self.settings
+    }
+
+    /// Import waypoint graph from GraphML file.
+    ///
+    /// # Arguments
+    /// * `path` - path to GraphML file.
+    ///
+    /// # Returns
+    /// `Ok` with nav net and vertices identifiers or `Err` with `Error::CouldNotImportNavNet`.
+    pub fn import_graphml<P>(&self, path: P) -> NavResult<(NavNet, Vec<String>)>
+    where
+        P: AsRef<Path>,
+    {
+        self.import_graphml_str(&read(path.as_ref())?)
+    }
+
+    /// Import waypoint graph from GraphML data.
+    ///
+    /// Besides node attributes, positions are also read from yEd node geometry (node center is
+    /// used).
+    ///
+    /// # Arguments
+    /// * `data` - GraphML data.
+    ///
+    /// # Returns
+    /// `Ok` with nav net and vertices identifiers or `Err` with `Error::CouldNotImportNavNet`.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+    ///   <key id="x" for="node" attr.name="x" attr.type="double"/>
+    ///   <key id="y" for="node" attr.name="y" attr.type="double"/>
+    ///   <key id="c" for="edge" attr.name="cost" attr.type="double"/>
+    ///   <graph edgedefault="undirected">
+    ///     <node id="gate"><data key="x">0</data><data key="y">0</data></node>
+    ///     <node id="tower"><data key="x">3</data><data key="y">4</data></node>
+    ///     <node id="well"><data key="x">3</data><data key="y">0</data></node>
+    ///     <edge source="gate" target="tower"/>
+    ///     <edge source="tower" target="well"><data key="c">10</data></edge>
+    ///   </graph>
+    /// </graphml>"#;
+    /// let (net, names) = NavNetImporter::default().import_graphml_str(data).unwrap();
+    /// assert_eq!(names, vec!["gate", "tower", "well"]);
+    /// assert_eq!(net.vertices()[1], (3.0, 4.0, 0.0).into());
+    /// assert_eq!(net.connections().len(), 2);
+    /// assert_eq!(net.connection_cost(0), Some(5.0));
+    /// assert_eq!(net.connection_cost(1), Some(10.0));
+    /// ```
+    pub fn import_graphml_str(&self, data: &str) -> NavResult<(NavNet, Vec<String>)> {
+        let document = roxmltree::Document::parse(data).map_err(error)?;
+        let root = document.root_element();
+        // {key id: attribute name}
+        let keys = root
+            .children()
+            .filter(|node| node.has_tag_name("key"))
+            .filter_map(|node| {
+                let id = node.attribute("id")?;
+                Some((id, node.attribute("attr.name").unwrap_or(id)))
+            })
+            .collect::<HashMap<_, _>>();
+        let data = |node: roxmltree::Node, name: &str| {
+            node.children()
+                .filter(|child| child.has_tag_name("data"))
+                .find(|child| {
+                    child
+                        .attribute("key")
+                        .map(|key| keys.get(key).copied().unwrap_or(key) == name)
+                        .unwrap_or_default()
+                })
+                .and_then(|child| child.text())
+                .map(|text| {
+                    text.trim()
+                        .parse::<Scalar>()
+                        .map_err(|_| error(format!("Attribute {} is not a number: {}", name, text)))
+                })
+                .transpose()
+        };
+        let graph = root
+            .children()
+            .find(|node| node.has_tag_name("graph"))
+            .ok_or_else(|| error("Missing graph element"))?;
+        let mut nodes = vec![];
+        for node in graph.children().filter(|node| node.has_tag_name("node")) {
+            let id = node
+                .attribute("id")
+                .ok_or_else(|| error("Node without id"))?;
+            let position = match (data(node, "x")?, data(node, "y")?) {
+                (Some(x), Some(y)) => Some((x, y, data(node, "z")?.unwrap_or(0.0))),
+                _ => node
+                    .descendants()
+                    .find(|child| child.tag_name().name() == "Geometry")
+                    .and_then(|geometry| {
+                        let value = |name| geometry.attribute(name)?.parse::<Scalar>().ok();
+                        let x = value("x")? + value("width").unwrap_or(0.0) * 0.5;
+                        let y = value("y")? + value("height").unwrap_or(0.0) * 0.5;
+                        Some((x, y, 0.0))
+                    }),
+            };
+            let position = position.ok_or_else(|| error(format!("Node {} has no position", id)))?;
+            nodes.push((id.to_owned(), position));
+        }
+        let mut edges = vec![];
+        for edge in graph.children().filter(|node| node.has_tag_name("edge")) {
+            let source = edge
+                .attribute("source")
+                .ok_or_else(|| error("Edge without source"))?;
+            let target = edge
+                .attribute("target")
+                .ok_or_else(|| error("Edge without target"))?;
+            let cost = match data(edge, "cost")? {
+                Some(cost) => Some(cost),
+                None => data(edge, "weight")?,
+            };
+            edges.push((source.to_owned(), target.to_owned(), cost));
+        }
+        self.build(nodes, edges)
+    }
+
+    /// Import waypoint graph from JSON file.
+    ///
+    /// # Arguments
+    /// * `path` - path to JSON file.
+    ///
+    /// # Returns
+    /// `Ok` with nav net and vertices identifiers or `Err` with `Error::CouldNotImportNavNet`.
+    pub fn import_json<P>(&self, path: P) -> NavResult<(NavNet, Vec<String>)>
+    where
+        P: AsRef<Path>,
+    {
+        self.import_json_str(&read(path.as_ref())?)
+    }
+
+    /// Import waypoint graph from JSON edge list data.
+    ///
+    /// Data is object with `nodes` list of objects with `id` (string or number) and either `x`,
+    /// `y` and `z` numbers or `position` array, and `edges` list of either objects with `source`
+    /// and `target` (or `from` and `to`) node ids and optional `cost`, or `[source, target]`
+    /// arrays with optional cost as third item.
+    ///
+    /// # Arguments
+    /// * `data` - JSON data.
+    ///
+    /// # Returns
+    /// `Ok` with nav net and vertices identifiers or `Err` with `Error::CouldNotImportNavNet`.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let data = r#"{
+    ///     "nodes": [
+    ///         { "id": "a", "x": 0, "y": 0 },
+    ///         { "id": "b", "position": [2, 0, 0] },
+    ///         { "id": "c", "x": 2, "y": 2, "z": 1 }
+    ///     ],
+    ///     "edges": [
+    ///         { "source": "a", "target": "b", "cost": 3 },
+    ///         ["b", "c"]
+    ///     ]
+    /// }"#;
+    /// let settings = NavNetImportSettings {
+    ///     distance_costs: false,
+    ///     ..Default::default()
+    /// };
+    /// let (net, names) = NavNetImporter::new(settings).import_json_str(data).unwrap();
+    /// assert_eq!(names, vec!["a", "b", "c"]);
+    /// assert_eq!(net.connection_cost(0), Some(3.0));
+    /// assert_eq!(net.connection_cost(1), Some(1.0));
+    /// assert_eq!(
+    ///     net.find_path((0.0, 0.0, 0.0).into(), (2.0, 2.0, 1.0).into()).unwrap().len(),
+    ///     3,
+    /// );
+    /// ```
+    pub fn import_json_str(&self, data: &str) -> NavResult<(NavNet, Vec<String>)> {
+        let root = serde_json::from_str::<Value>(data).map_err(error)?;
+        let id = |value: &Value| match value {
+            Value::String(id) => Some(id.to_owned()),
+            Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        };
+        let number = |value: Option<&Value>, name: &str| {
+            value
+                .map(|value| {
+                    value
+                        .as_f64()
+                        .map(|value| value as Scalar)
+                        .ok_or_else(|| error(format!("Attribute {} is not a number", name)))
+                })
+                .transpose()
+        };
+        let list = |name: &str| {
+            root.get(name)
+                .and_then(|value| value.as_array())
+                .ok_or_else(|| error(format!("Missing {} list", name)))
+        };
+        let mut nodes = vec![];
+        for node in list("nodes")? {
+            let node_id = node
+                .get("id")
+                .and_then(id)
+                .ok_or_else(|| error("Node without id"))?;
+            let position = match node.get("position").and_then(|value| value.as_array()) {
+                Some(position) => match position.as_slice() {
+                    [x, y] => (number(Some(x), "x")?, number(Some(y), "y")?, Some(0.0)),
+                    [x, y, z] => (
+                        number(Some(x), "x")?,
+                        number(Some(y), "y")?,
+                        number(Some(z), "z")?,
+                    ),
+                    _ => (None, None, None),
+                },
+                None => (
+                    number(node.get("x"), "x")?,
+                    number(node.get("y"), "y")?,
+                    number(node.get("z"), "z")?,
+                ),
+            };
+            let position = match position {
+                (Some(x), Some(y), z) => (x, y, z.unwrap_or(0.0)),
+                _ => return Err(error(format!("Node {} has no position", node_id))),
+            };
+            nodes.push((node_id, position));
+        }
+        let mut edges = vec![];
+        for edge in list("edges")? {
+            let (source, target, cost) = match edge {
+                Value::Array(items) => (
+                    items.first().and_then(id),
+                    items.get(1).and_then(id),
+                    number(items.get(2), "cost")?,
+                ),
+                _ => (
+                    edge.get("source").or_else(|| edge.get("from")).and_then(id),
+                    edge.get("target").or_else(|| edge.get("to")).and_then(id),
+                    number(edge.get("cost").or_else(|| edge.get("weight")), "cost")?,
+                ),
+            };
+            match (source, target) {
+                (Some(source), Some(target)) => edges.push((source, target, cost)),
+                _ => return Err(error("Edge without source or target")),
+            }
+        }
+        self.build(nodes, edges)
+    }
+
+    fn build(
+        &self,
+        nodes: Vec<(String, (Scalar, Scalar, Scalar))>,
+        edges: Vec<(String, String, Option<Scalar>)>,
+    ) -> NavResult<(NavNet, Vec<String>)> {
+        let mut indices = HashMap::with_capacity(nodes.len());
+        let mut names = Vec::with_capacity(nodes.len());
+        let mut vertices = Vec::with_capacity(nodes.len());
+        for (id, position) in nodes {
+            if indices.insert(id.clone(), vertices.len() as u32).is_some() {
+                return Err(error(format!("Duplicate node {}", id)));
+            }
+            names.push(id);
+            vertices.push(self.convert(position));
+        }
+        let index = |id: &str| {
+            indices
+                .get(id)
+                .copied()
+                .ok_or_else(|| error(format!("Edge references unknown node {}", id)))
+        };
+        let mut connections = Vec::with_capacity(edges.len());
+        let mut costs = Vec::with_capacity(edges.len());
+        for (source, target, cost) in edges {
+            let connection = NavConnection(index(&source)?, index(&target)?);
+            let cost = cost.unwrap_or_else(|| {
+                if self.settings.distance_costs {
+                    vertices[connection.0 as usize].distance(vertices[connection.1 as usize])
+                } else {
+                    1.0
+                }
+            });
+            connections.push(connection);
+            costs.push(cost);
+        }
+        let mut net = NavNet::new(vertices, connections)?;
+        for (index, cost) in costs.into_iter().enumerate() {
+            net.set_connection_cost(index, Some(cost));
+        }
+        Ok((net, names))
+    }
+
+    fn convert(&self, (x, y, z): (Scalar, Scalar, Scalar)) -> NavVec3 {
+        let result = if self.settings.y_up_to_z_up {
+            NavVec3::new(x, -z, y)
+        } else {
+            NavVec3::new(x, y, z)
+        };
+        result * self.settings.scale
+    }
+}
+
+fn read(path: &Path) -> NavResult<String> {
+    std::fs::read_to_string(path)
+        .map_err(|error| Error::CouldNotImportNavNet(format!("{}: {}", path.display(), error)))
+}
+
+fn error(message: impl ToString) -> Error {
+    Error::CouldNotImportNavNet(message.to_string())
+}
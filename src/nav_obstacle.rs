@@ -0,0 +1,227 @@
+use crate::{
+    nav_geom::{clip_polygon, polygon_area},
+    NavTolerance, NavVec3, Scalar, ZERO_TRESHOLD,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use typid::ID;
+
+/// Nav obstacle identifier.
+pub type NavObstacleID = ID<NavObstacle>;
+
+/// Number of side planes used to approximate cylinder with prism.
+const CYLINDER_PLANES: usize = 16;
+
+/// Obstacle volume that temporarily blocks nav mesh triangles it overlaps.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavObstacle {
+    /// Cylinder standing on `base` point and extending along `axis` (axis length is the cylinder
+    /// height).
+    Cylinder {
+        base: NavVec3,
+        axis: NavVec3,
+        radius: Scalar,
+    },
+    /// Convex volume described by its bounding planes: list of (origin, outward normal).
+    Convex(Vec<(NavVec3, NavVec3)>),
+}
+
+impl NavObstacle {
+    /// Create cylinder obstacle.
+    ///
+    /// # Arguments
+    /// * `base` - center of cylinder bottom cap.
+    /// * `axis` - cylinder axis, its length is the cylinder height.
+    /// * `radius` - cylinder radius.
+    pub fn cylinder(base: NavVec3, axis: NavVec3, radius: Scalar) -> Self {
+        Self::Cylinder {
+            base,
+            axis,
+            radius: radius.max(0.0),
+        }
+    }
+
+    /// Create axis aligned box obstacle.
+    ///
+    /// # Arguments
+    /// * `min` - box minimum corner.
+    /// * `max` - box maximum corner.
+    pub fn aabb(min: NavVec3, max: NavVec3) -> Self {
+        let (min, max) = (min.min(max), min.max(max));
+        Self::Convex(vec![
+            (min, NavVec3::new(-1.0, 0.0, 0.0)),
+            (min, NavVec3::new(0.0, -1.0, 0.0)),
+            (min, NavVec3::new(0.0, 0.0, -1.0)),
+            (max, NavVec3::new(1.0, 0.0, 0.0)),
+            (max, NavVec3::new(0.0, 1.0, 0.0)),
+            (max, NavVec3::new(0.0, 0.0, 1.0)),
+        ])
+    }
+
+    /// Create convex obstacle from its bounding planes.
+    ///
+    /// # Arguments
+    /// * `planes` - list of (origin, outward normal) planes.
+    pub fn convex(planes: Vec<(NavVec3, NavVec3)>) -> Self {
+        Self::Convex(
+            planes
+                .into_iter()
+                .map(|(origin, normal)| (origin, normal.normalize()))
+                .collect(),
+        )
+    }
+
+    /// Obstacle bounding box if it can be computed.
+    ///
+    /// # Returns
+    /// `Some` with (min, max) corners or `None` for unbounded plane sets.
+    pub fn bounds(&self) -> Option<(NavVec3, NavVec3)> {
+        match self {
+            Self::Cylinder { base, axis, radius } => {
+                let top = *base + *axis;
+                Some((base.min(top) - *radius, base.max(top) + *radius))
+            }
+            Self::Convex(planes) => {
+                let mut min = NavVec3::new(-Scalar::INFINITY, -Scalar::INFINITY, -Scalar::INFINITY);
+                let mut max = NavVec3::new(Scalar::INFINITY, Scalar::INFINITY, Scalar::INFINITY);
+                for (origin, normal) in planes {
+                    let n = normal.normalize();
+                    if n.x > 1.0 - ZERO_TRESHOLD {
+                        max.x = max.x.min(origin.x);
+                    } else if n.x < -1.0 + ZERO_TRESHOLD {
+                        min.x = min.x.max(origin.x);
+                    } else if n.y > 1.0 - ZERO_TRESHOLD {
+                        max.y = max.y.min(origin.y);
+                    } else if n.y < -1.0 + ZERO_TRESHOLD {
+                        min.y = min.y.max(origin.y);
+                    } else if n.z > 1.0 - ZERO_TRESHOLD {
+                        max.z = max.z.min(origin.z);
+                    } else if n.z < -1.0 + ZERO_TRESHOLD {
+                        min.z = min.z.max(origin.z);
+                    }
+                }
+                if min.x.is_finite()
+                    && min.y.is_finite()
+                    && min.z.is_finite()
+                    && max.x.is_finite()
+                    && max.y.is_finite()
+                    && max.z.is_finite()
+                {
+                    Some((min, max))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Bounding planes of obstacle volume, with cylinder approximated by prism.
+    ///
+    /// # Returns
+    /// List of (origin, outward normal) planes.
+    pub(crate) fn planes(&self) -> Vec<(NavVec3, NavVec3)> {
+        match self {
+            Self::Cylinder { base, axis, radius } => {
+                let n = axis.normalize();
+                if n.sqr_magnitude() < ZERO_TRESHOLD {
+                    return vec![];
+                }
+                let side = if n.x.abs() < 0.9 {
+                    NavVec3::new(1.0, 0.0, 0.0)
+                } else {
+                    NavVec3::new(0.0, 1.0, 0.0)
+                };
+                let u = n.cross(side).normalize();
+                let v = n.cross(u);
+                let mut result = (0..CYLINDER_PLANES)
+                    .map(|i| {
+                        let angle = std::f64::consts::TAU as Scalar * i as Scalar
+                            / CYLINDER_PLANES as Scalar;
+                        let normal = u * angle.cos() + v * angle.sin();
+                        (*base + normal * *radius, normal)
+                    })
+                    .collect::<Vec<_>>();
+                result.push((*base, -n));
+                result.push((*base + *axis, n));
+                result
+            }
+            Self::Convex(planes) => planes.clone(),
+        }
+    }
+
+    /// Tells if obstacle overlaps triangle.
+    ///
+    /// # Arguments
+    /// * `a` - first triangle vertice point.
+    /// * `b` - second triangle vertice point.
+    /// * `c` - third triangle vertice point.
+    pub fn intersects_triangle(&self, a: NavVec3, b: NavVec3, c: NavVec3) -> bool {
+        self.intersects_triangle_within(a, b, c, Default::default())
+    }
+
+    /// Tells if obstacle overlaps triangle, using given tolerances instead of default ones.
+    ///
+    /// # Arguments
+    /// * `a` - first triangle vertice point.
+    /// * `b` - second triangle vertice point.
+    /// * `c` - third triangle vertice point.
+    /// * `tolerance` - tolerances of nav mesh triangle belongs to.
+    pub fn intersects_triangle_within(
+        &self,
+        a: NavVec3,
+        b: NavVec3,
+        c: NavVec3,
+        tolerance: NavTolerance,
+    ) -> bool {
+        match self {
+            Self::Cylinder { base, axis, radius } => {
+                if axis.sqr_magnitude() < tolerance.distance_sqr {
+                    return false;
+                }
+                let n = axis.normalize();
+                let top = *base + *axis;
+                let polygon = clip_polygon(vec![a, b, c], *base, -n);
+                let polygon = clip_polygon(polygon, top, n);
+                if polygon.is_empty() {
+                    return false;
+                }
+                let projected = polygon
+                    .into_iter()
+                    .map(|p| p - n * (p - *base).dot(n))
+                    .collect::<Vec<_>>();
+                let count = projected.len();
+                let mut sides = (false, false);
+                for i in 0..count {
+                    let p = projected[i];
+                    let q = projected[(i + 1) % count];
+                    let t = base.project(p, q);
+                    let closest = if t.is_finite() {
+                        NavVec3::unproject(p, q, t.clamp(0.0, 1.0))
+                    } else {
+                        p
+                    };
+                    if (closest - *base).sqr_magnitude() < radius * radius - tolerance.distance_sqr
+                    {
+                        return true;
+                    }
+                    let side = (q - p).cross(*base - p).dot(n);
+                    if side > tolerance.distance_sqr {
+                        sides.0 = true;
+                    } else if side < -tolerance.distance_sqr {
+                        sides.1 = true;
+                    }
+                }
+                count > 2 && sides.0 != sides.1
+            }
+            Self::Convex(planes) => {
+                let polygon = planes
+                    .iter()
+                    .fold(vec![a, b, c], |polygon, (origin, normal)| {
+                        clip_polygon(polygon, *origin, *normal)
+                    });
+                polygon_area(&polygon) > tolerance.distance_sqr
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::Scalar;
+use core::cmp::Ordering;
+
+/// Open set entry of path finding searches, ordered so that `BinaryHeap` pops lowest cost first.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct OpenNode<T>(pub Scalar, pub T);
+
+impl<T> PartialEq for OpenNode<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for OpenNode<T> {}
+
+impl<T> PartialOrd for OpenNode<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for OpenNode<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal)
+    }
+}
@@ -0,0 +1,130 @@
+use crate::Scalar;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+static VERSION: AtomicU64 = AtomicU64::new(1);
+
+/// Produce new version number, unique across all nav meshes, so versions taken from different
+/// meshes can be compared to detect any change.
+pub(crate) fn next_version() -> u64 {
+    VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+type NavPathCacheKey = (usize, usize);
+type NavPathCacheValue = Option<(Vec<usize>, Scalar)>;
+
+#[derive(Debug, Default)]
+struct NavPathCacheInner {
+    version: u64,
+    tick: u64,
+    // {(from triangle, to triangle): (last use tick, triangles path with its cost)}
+    entries: HashMap<NavPathCacheKey, (u64, NavPathCacheValue)>,
+}
+
+/// Least recently used cache of triangles paths, invalidated when nav mesh version changes.
+///
+/// Uses interior mutability so it can be filled by path finding queries on shared nav mesh.
+#[derive(Debug, Default)]
+pub(crate) struct NavPathCache {
+    capacity: usize,
+    inner: Mutex<NavPathCacheInner>,
+}
+
+impl Clone for NavPathCache {
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+impl NavPathCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    pub fn get(&self, version: u64, from: usize, to: usize) -> Option<NavPathCacheValue> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let mut inner = self.lock();
+        if inner.version != version {
+            inner.version = version;
+            inner.entries.clear();
+            return None;
+        }
+        inner.tick += 1;
+        let tick = inner.tick;
+        let (used, value) = inner.entries.get_mut(&(from, to))?;
+        *used = tick;
+        Some(value.clone())
+    }
+
+    pub fn insert(&self, version: u64, from: usize, to: usize, value: NavPathCacheValue) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.lock();
+        if inner.version != version {
+            inner.version = version;
+            inner.entries.clear();
+        }
+        if inner.entries.len() >= self.capacity && !inner.entries.contains_key(&(from, to)) {
+            let oldest = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(key, _)| *key);
+            if let Some(key) = oldest {
+                inner.entries.remove(&key);
+            }
+        }
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.entries.insert((from, to), (tick, value));
+    }
+
+    /// Move entries cached for old version to new one, keeping only ones that are still valid.
+    ///
+    /// # Arguments
+    /// * `old` - nav mesh version before change.
+    /// * `new` - nav mesh version after change.
+    /// * `keep` - tells if cached entry is not affected by change.
+    pub fn retain<F>(&self, old: u64, new: u64, keep: F)
+    where
+        F: Fn(&NavPathCacheValue) -> bool,
+    {
+        let mut inner = self.lock();
+        if inner.version == old {
+            inner.entries.retain(|_, (_, value)| keep(value));
+        } else {
+            inner.entries.clear();
+        }
+        inner.version = new;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, NavPathCacheInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
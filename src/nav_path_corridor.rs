@@ -0,0 +1,301 @@
+use crate::{NavMesh, NavQuery, NavVec3, Scalar, ZERO_TRESHOLD};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Corridor of nav mesh triangles leading from agent position to its target.
+///
+/// Instead of recomputing whole path every frame, agent moves its position and target along the
+/// corridor, asks it for path corners to steer towards and replans only sections of corridor that
+/// got invalidated.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPathCorridor {
+    position: NavVec3,
+    target: NavVec3,
+    triangles: Vec<usize>,
+}
+
+impl NavPathCorridor {
+    /// Create corridor that contains only triangle agent stands on.
+    ///
+    /// # Arguments
+    /// * `position` - agent position.
+    /// * `triangle` - index of triangle agent stands on.
+    pub fn new(position: NavVec3, triangle: usize) -> Self {
+        Self {
+            position,
+            target: position,
+            triangles: vec![triangle],
+        }
+    }
+
+    /// Find corridor between two points.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with corridor if path was found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let mut corridor = NavPathCorridor::plan(
+    ///     &mesh,
+    ///     (0.0, 1.0, 0.0).into(),
+    ///     (1.5, 0.25, 0.5).into(),
+    ///     NavQuery::Accuracy,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(corridor.triangles(), &[1, 0, 3, 2]);
+    /// corridor.move_position(&mesh, (0.75, 0.25, 0.0).into());
+    /// assert_eq!(corridor.triangles(), &[0, 3, 2]);
+    /// ```
+    pub fn plan(mesh: &NavMesh, from: NavVec3, to: NavVec3, query: NavQuery) -> Option<Self> {
+        let start = mesh.find_closest_triangle(from, query)?;
+        let end = mesh.find_closest_triangle(to, query)?;
+        let (triangles, _) = mesh.find_path_triangles(start, end)?;
+        Some(Self {
+            position: mesh.closest_point_on_triangle(start, from)?,
+            target: mesh.closest_point_on_triangle(end, to)?,
+            triangles,
+        })
+    }
+
+    /// Replace corridor with given triangles list.
+    ///
+    /// # Arguments
+    /// * `target` - target point lying on the last corridor triangle.
+    /// * `triangles` - list of connected triangles, first one should contain agent position.
+    pub fn set_corridor(&mut self, target: NavVec3, triangles: Vec<usize>) {
+        if !triangles.is_empty() {
+            self.target = target;
+            self.triangles = triangles;
+        }
+    }
+
+    #[inline]
+    pub fn position(&self) -> NavVec3 {
+        self.position
+    }
+
+    #[inline]
+    pub fn target(&self) -> NavVec3 {
+        self.target
+    }
+
+    #[inline]
+    pub fn triangles(&self) -> &[usize] {
+        &self.triangles
+    }
+
+    #[inline]
+    pub fn first_triangle(&self) -> Option<usize> {
+        self.triangles.first().copied()
+    }
+
+    #[inline]
+    pub fn last_triangle(&self) -> Option<usize> {
+        self.triangles.last().copied()
+    }
+
+    /// Find path corners from agent position to target through the corridor.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    ///
+    /// # Returns
+    /// List of path points, starting with agent position and ending with target.
+    pub fn corners(&self, mesh: &NavMesh) -> Vec<NavVec3> {
+        mesh.string_pull(self.position, self.target, &self.triangles, 0.0)
+            .unwrap_or_else(|| vec![self.position, self.target])
+    }
+
+    /// Move agent position, shrinking corridor when position advances along it or extending it
+    /// when position drifts to triangle neighboring corridor start.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `position` - new agent position.
+    ///
+    /// # Returns
+    /// New agent position constrained to the corridor.
+    pub fn move_position(&mut self, mesh: &NavMesh, position: NavVec3) -> NavVec3 {
+        if let Some((index, point)) = self.closest(mesh, position, false) {
+            match index {
+                Ok(index) => {
+                    self.triangles.drain(..index);
+                }
+                Err(triangle) => self.triangles.insert(0, triangle),
+            }
+            self.position = point;
+        }
+        self.position
+    }
+
+    /// Move target, shrinking corridor when target moves back along it or extending it when
+    /// target moves to triangle neighboring corridor end.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `target` - new target position.
+    ///
+    /// # Returns
+    /// New target position constrained to the corridor.
+    pub fn move_target(&mut self, mesh: &NavMesh, target: NavVec3) -> NavVec3 {
+        if let Some((index, point)) = self.closest(mesh, target, true) {
+            match index {
+                Ok(index) => self.triangles.truncate(index + 1),
+                Err(triangle) => self.triangles.push(triangle),
+            }
+            self.target = point;
+        }
+        self.target
+    }
+
+    /// Shortcut corridor start if there is straight visible line on nav mesh from agent position
+    /// towards next corner.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `next` - next corner agent is steering towards.
+    /// * `distance` - maximal distance of visibility check.
+    ///
+    /// # Returns
+    /// True if corridor has been changed.
+    pub fn optimize_visibility(&mut self, mesh: &NavMesh, next: NavVec3, distance: Scalar) -> bool {
+        let first = match self.first_triangle() {
+            Some(first) => first,
+            None => return false,
+        };
+        let diff = next - self.position;
+        let length = diff.magnitude();
+        if length < ZERO_TRESHOLD {
+            return false;
+        }
+        let goal = self.position + diff * (distance.max(0.0).min(length) / length);
+        let (visited, _) = mesh.walk_line(first, self.position, goal, mesh.tolerance());
+        let furthest = visited.iter().rev().find_map(|triangle| {
+            self.triangles
+                .iter()
+                .rposition(|t| t == triangle)
+                .map(|index| (*triangle, index))
+        });
+        if let Some((triangle, index)) = furthest {
+            let count = visited.iter().position(|t| *t == triangle).unwrap() + 1;
+            if count < index + 1 {
+                self.triangles
+                    .splice(..=index, visited.into_iter().take(count));
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Tells if corridor triangles are still traversable.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `look_ahead` - number of triangles from the corridor start to check.
+    pub fn is_valid(&self, mesh: &NavMesh, look_ahead: usize) -> bool {
+        !self.triangles.is_empty()
+            && self.triangles.iter().take(look_ahead).all(|triangle| {
+                *triangle < mesh.areas().len() && !mesh.is_triangle_blocked(*triangle)
+            })
+    }
+
+    /// Replan sections of corridor that go through blocked triangles, keeping the rest of it.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    ///
+    /// # Returns
+    /// True if corridor is valid after replanning.
+    pub fn replan(&mut self, mesh: &NavMesh) -> bool {
+        let invalid = |triangle: &usize| {
+            *triangle >= mesh.areas().len() || mesh.is_triangle_blocked(*triangle)
+        };
+        while let Some(start) = self.triangles.iter().position(invalid) {
+            if start == 0 {
+                return false;
+            }
+            let end = match self.triangles[start..].iter().position(|t| !invalid(t)) {
+                Some(offset) => start + offset,
+                None => return false,
+            };
+            let (path, _) =
+                match mesh.find_path_triangles(self.triangles[start - 1], self.triangles[end]) {
+                    Some(result) => result,
+                    None => return false,
+                };
+            self.triangles.splice(start - 1..=end, path);
+        }
+        !self.triangles.is_empty()
+    }
+
+    /// Find corridor triangle (or neighbor of corridor end triangle) closest to the point.
+    ///
+    /// # Returns
+    /// `Some` with pair of (`Ok` with corridor index or `Err` with neighbor triangle index,
+    /// closest point on that triangle).
+    fn closest(
+        &self,
+        mesh: &NavMesh,
+        point: NavVec3,
+        from_end: bool,
+    ) -> Option<(Result<usize, usize>, NavVec3)> {
+        let end = if from_end {
+            *self.triangles.last()?
+        } else {
+            *self.triangles.first()?
+        };
+        let mut best: Option<(Result<usize, usize>, NavVec3, Scalar)> = None;
+        let mut consider = |index: Result<usize, usize>, triangle: usize| {
+            if let Some(p) = mesh.closest_point_on_triangle(triangle, point) {
+                let d = (p - point).sqr_magnitude();
+                let better = best
+                    .as_ref()
+                    .map(|(_, _, bd)| d < bd - ZERO_TRESHOLD)
+                    .unwrap_or(true);
+                if better {
+                    best = Some((index, p, d));
+                }
+            }
+        };
+        if from_end {
+            for (index, triangle) in self.triangles.iter().enumerate() {
+                consider(Ok(index), *triangle);
+            }
+        } else {
+            for (index, triangle) in self.triangles.iter().enumerate().rev() {
+                consider(Ok(index), *triangle);
+            }
+        }
+        for triangle in mesh.triangle_neighbors(end) {
+            if !self.triangles.contains(&triangle) && !mesh.is_triangle_blocked(triangle) {
+                consider(Err(triangle), triangle);
+            }
+        }
+        best.map(|(index, point, _)| (index, point))
+    }
+}
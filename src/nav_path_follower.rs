@@ -0,0 +1,248 @@
+use crate::{NavVec3, Scalar, ZERO_TRESHOLD};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Result of single `NavPathFollower` update.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPathSteering {
+    /// Point agent should move towards.
+    pub target: NavVec3,
+    /// Velocity agent wants to move with.
+    pub desired_velocity: NavVec3,
+    /// Change of velocity needed to reach desired velocity (desired velocity minus current one).
+    pub steering: NavVec3,
+    /// Index of path point agent steers towards.
+    pub corner: usize,
+    /// Tells if any path corner has been reached during this update.
+    pub corner_reached: bool,
+    /// Tells if agent has arrived at path end.
+    pub arrived: bool,
+    /// Tells if agent was too far from path and has been anchored to closest path point.
+    pub reanchored: bool,
+    /// Distance along the path to point closest to agent.
+    pub traveled: Scalar,
+    /// Distance along the path left to its end.
+    pub remaining: Scalar,
+    /// Traveled part of the path in range from 0 to 1.
+    pub progress: Scalar,
+}
+
+/// Follows path by finding steering target for agent current position and velocity, keeping track
+/// of reached corners and progress along the path.
+///
+/// Agents pushed away from path (for example by physics) further than `reanchor_distance` are
+/// anchored to closest point of the whole path and steered back towards it.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPathFollower {
+    /// Maximal agent speed.
+    pub max_speed: Scalar,
+    /// Distance from path corner at which it is considered reached.
+    pub corner_tolerance: Scalar,
+    /// Distance from path end at which agent is considered arrived.
+    pub arrival_tolerance: Scalar,
+    /// Distance from path end at which agent starts to slow down.
+    pub slowing_distance: Scalar,
+    /// Distance from followed path segment at which agent gets re-anchored.
+    pub reanchor_distance: Scalar,
+    path: Vec<NavVec3>,
+    // path length up to every path point.
+    distances: Vec<Scalar>,
+    segment: usize,
+}
+
+impl NavPathFollower {
+    /// Create new path follower without path.
+    ///
+    /// # Arguments
+    /// * `max_speed` - maximal agent speed.
+    /// * `tolerance` - distance at which corners and path end are considered reached.
+    pub fn new(max_speed: Scalar, tolerance: Scalar) -> Self {
+        let tolerance = tolerance.max(ZERO_TRESHOLD);
+        Self {
+            max_speed: max_speed.max(0.0),
+            corner_tolerance: tolerance,
+            arrival_tolerance: tolerance,
+            slowing_distance: 0.0,
+            reanchor_distance: tolerance * 2.0,
+            path: vec![],
+            distances: vec![],
+            segment: 0,
+        }
+    }
+
+    /// Replace followed path and start following it from its beginning.
+    ///
+    /// # Arguments
+    /// * `path` - path points.
+    pub fn set_path(&mut self, path: Vec<NavVec3>) {
+        let mut length = 0.0;
+        self.distances = std::iter::once(0.0)
+            .chain(path.windows(2).map(|pair| {
+                length += (pair[1] - pair[0]).magnitude();
+                length
+            }))
+            .take(path.len())
+            .collect();
+        self.path = path;
+        self.segment = 0;
+    }
+
+    pub fn clear_path(&mut self) {
+        self.set_path(vec![]);
+    }
+
+    #[inline]
+    pub fn path(&self) -> &[NavVec3] {
+        &self.path
+    }
+
+    /// Index of currently followed path segment.
+    #[inline]
+    pub fn segment(&self) -> usize {
+        self.segment
+    }
+
+    /// Total length of followed path.
+    #[inline]
+    pub fn length(&self) -> Scalar {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Find steering for agent following the path.
+    ///
+    /// # Arguments
+    /// * `position` - agent position.
+    /// * `velocity` - agent current velocity.
+    ///
+    /// # Returns
+    /// Steering target and progress along the path.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mut follower = NavPathFollower::new(1.0, 0.1);
+    /// follower.set_path(vec![
+    ///     (0.0, 0.0, 0.0).into(),
+    ///     (1.0, 0.0, 0.0).into(),
+    ///     (1.0, 1.0, 0.0).into(),
+    /// ]);
+    /// let steering = follower.update((0.5, 0.0, 0.0).into(), (1.0, 0.0, 0.0).into());
+    /// assert_eq!(steering.target, (1.0, 0.0, 0.0).into());
+    /// assert_eq!(steering.progress, 0.25);
+    /// let steering = follower.update((0.95, 0.0, 0.0).into(), (1.0, 0.0, 0.0).into());
+    /// assert!(steering.corner_reached);
+    /// assert_eq!(steering.target, (1.0, 1.0, 0.0).into());
+    /// let steering = follower.update((1.0, 0.95, 0.0).into(), (0.0, 1.0, 0.0).into());
+    /// assert!(steering.arrived);
+    /// ```
+    pub fn update(&mut self, position: NavVec3, velocity: NavVec3) -> NavPathSteering {
+        let last = match self.path.len() {
+            0 => {
+                return NavPathSteering {
+                    target: position,
+                    steering: -velocity,
+                    arrived: true,
+                    progress: 1.0,
+                    ..Default::default()
+                }
+            }
+            len => len - 1,
+        };
+        let segments = last.max(1);
+        self.segment = self.segment.min(segments - 1);
+        let (mut segment, mut anchor, mut t, distance) =
+            self.closest_on_segments(position, self.segment..segments);
+        let reanchored = distance > self.reanchor_distance;
+        if reanchored {
+            (segment, anchor, t, _) = self.closest_on_segments(position, 0..segments);
+        }
+        self.segment = segment;
+        let mut corner_reached = false;
+        while self.segment + 1 < last
+            && (self.path[self.segment + 1] - position).magnitude() <= self.corner_tolerance
+        {
+            self.segment += 1;
+            corner_reached = true;
+            anchor = self.path[self.segment];
+            t = 0.0;
+        }
+        let length = self.length();
+        let traveled = if last == 0 {
+            0.0
+        } else {
+            let from = self.distances[self.segment];
+            from + (self.distances[self.segment + 1] - from) * t
+        };
+        let remaining = (length - traveled).max(0.0);
+        let progress = if length > ZERO_TRESHOLD {
+            traveled / length
+        } else {
+            1.0
+        };
+        let corner = last.min(self.segment + 1);
+        let end = self.path[last];
+        let arrived = corner == last && (end - position).magnitude() <= self.arrival_tolerance;
+        let target = if arrived {
+            end
+        } else if reanchored {
+            anchor
+        } else {
+            self.path[corner]
+        };
+        let desired_velocity = if arrived {
+            NavVec3::default()
+        } else {
+            let diff = target - position;
+            let distance = diff.magnitude();
+            let speed = if self.slowing_distance > ZERO_TRESHOLD {
+                self.max_speed * (remaining.max(distance) / self.slowing_distance).min(1.0)
+            } else {
+                self.max_speed
+            };
+            if distance > ZERO_TRESHOLD {
+                diff * (speed / distance)
+            } else {
+                NavVec3::default()
+            }
+        };
+        NavPathSteering {
+            target,
+            desired_velocity,
+            steering: desired_velocity - velocity,
+            corner,
+            corner_reached,
+            arrived,
+            reanchored,
+            traveled,
+            remaining,
+            progress,
+        }
+    }
+
+    // (segment, closest point, segment factor, distance)
+    fn closest_on_segments(
+        &self,
+        position: NavVec3,
+        segments: std::ops::Range<usize>,
+    ) -> (usize, NavVec3, Scalar, Scalar) {
+        let mut result = (segments.start, self.path[0], 0.0, Scalar::INFINITY);
+        for segment in segments {
+            let from = self.path[segment];
+            let to = self.path.get(segment + 1).copied().unwrap_or(from);
+            let t = if (to - from).sqr_magnitude() > ZERO_TRESHOLD * ZERO_TRESHOLD {
+                position.project(from, to).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let point = NavVec3::unproject(from, to, t);
+            let distance = (point - position).magnitude();
+            if distance < result.3 {
+                result = (segment, point, t, distance);
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{NavMesh, NavPath, NavTileTriangle, NavTiledMesh};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+use typid::ID;
+
+/// Identifier of path watched for nav mesh changes.
+pub type NavPathWatchID = ID<NavPath>;
+
+/// Set of watched paths, each described by keys (triangles or tiles) it goes through, that
+/// remembers which of them were touched by changes until they get taken.
+#[derive(Debug, Clone)]
+pub(crate) struct NavPathWatcher<K> {
+    // {watch id: [key]}
+    watches: HashMap<NavPathWatchID, Vec<K>>,
+    // {key: [watch id]}
+    keys: HashMap<K, Vec<NavPathWatchID>>,
+    invalidated: HashSet<NavPathWatchID>,
+}
+
+impl<K> Default for NavPathWatcher<K> {
+    fn default() -> Self {
+        Self {
+            watches: Default::default(),
+            keys: Default::default(),
+            invalidated: Default::default(),
+        }
+    }
+}
+
+impl<K> NavPathWatcher<K>
+where
+    K: Copy + Eq + Hash,
+{
+    pub fn watch(&mut self, keys: impl IntoIterator<Item = K>) -> NavPathWatchID {
+        let id = NavPathWatchID::new();
+        self.insert(id, keys);
+        id
+    }
+
+    pub fn update(&mut self, id: NavPathWatchID, keys: impl IntoIterator<Item = K>) -> bool {
+        if !self.remove(id) {
+            return false;
+        }
+        self.invalidated.remove(&id);
+        self.insert(id, keys);
+        true
+    }
+
+    pub fn unwatch(&mut self, id: NavPathWatchID) -> bool {
+        self.invalidated.remove(&id);
+        self.remove(id)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.watches.len()
+    }
+
+    #[inline]
+    pub fn is_invalidated(&self, id: NavPathWatchID) -> bool {
+        self.invalidated.contains(&id)
+    }
+
+    pub fn notify<'a>(&mut self, keys: impl IntoIterator<Item = &'a K>)
+    where
+        K: 'a,
+    {
+        for key in keys {
+            if let Some(ids) = self.keys.get(key) {
+                self.invalidated.extend(ids.iter().copied());
+            }
+        }
+    }
+
+    pub fn take_invalidated(&mut self) -> Vec<NavPathWatchID> {
+        self.invalidated.drain().collect()
+    }
+
+    fn insert(&mut self, id: NavPathWatchID, keys: impl IntoIterator<Item = K>) {
+        let mut list = Vec::<K>::new();
+        for key in keys {
+            if !list.contains(&key) {
+                list.push(key);
+                self.keys.entry(key).or_default().push(id);
+            }
+        }
+        self.watches.insert(id, list);
+    }
+
+    fn remove(&mut self, id: NavPathWatchID) -> bool {
+        let list = match self.watches.remove(&id) {
+            Some(list) => list,
+            None => return false,
+        };
+        for key in list {
+            if let Some(ids) = self.keys.get_mut(&key) {
+                ids.retain(|other| *other != id);
+                if ids.is_empty() {
+                    self.keys.remove(&key);
+                }
+            }
+        }
+        true
+    }
+}
+
+impl NavMesh {
+    /// Start watching path (or corridor) going through given triangles, so when any of them
+    /// changes its area or gets blocked or unblocked by obstacle or gate, path gets reported by
+    /// `take_invalidated_paths` and only agents following affected paths need to replan.
+    ///
+    /// Paths that do not go through changed triangles are not reported, even if change (for
+    /// example opened gate) could make them shorter.
+    ///
+    /// # Arguments
+    /// * `triangles` - indices of triangles path goes through, for example `NavPath::triangles`
+    ///   or `NavPathCorridor::triangles`.
+    ///
+    /// # Returns
+    /// Watch identifier.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let long = mesh.watch_path(&mesh.find_path_triangles(1, 2).unwrap().0);
+    /// let short = mesh.watch_path(&mesh.find_path_triangles(1, 0).unwrap().0);
+    /// mesh.add_gate("door", vec![3], false).unwrap();
+    /// assert_eq!(mesh.take_invalidated_paths(), vec![long]);
+    /// assert!(mesh.take_invalidated_paths().is_empty());
+    /// mesh.set_area_cost(0, 2.0);
+    /// let mut invalidated = mesh.take_invalidated_paths();
+    /// invalidated.sort();
+    /// let mut expected = vec![long, short];
+    /// expected.sort();
+    /// assert_eq!(invalidated, expected);
+    /// ```
+    pub fn watch_path(&mut self, triangles: &[usize]) -> NavPathWatchID {
+        self.path_watcher_mut().watch(triangles.iter().copied())
+    }
+
+    /// Replace triangles of watched path, for example after agent replanned or advanced along
+    /// its corridor. Clears its invalidated state.
+    ///
+    /// # Returns
+    /// `true` if path is watched.
+    pub fn update_watched_path(&mut self, id: NavPathWatchID, triangles: &[usize]) -> bool {
+        self.path_watcher_mut()
+            .update(id, triangles.iter().copied())
+    }
+
+    /// Stop watching path.
+    ///
+    /// # Returns
+    /// `true` if path was watched.
+    pub fn unwatch_path(&mut self, id: NavPathWatchID) -> bool {
+        self.path_watcher_mut().unwatch(id)
+    }
+
+    /// Number of watched paths.
+    #[inline]
+    pub fn watched_paths_count(&self) -> usize {
+        self.path_watcher().len()
+    }
+
+    /// Tells if watched path was affected by changes not taken yet.
+    #[inline]
+    pub fn is_watched_path_invalidated(&self, id: NavPathWatchID) -> bool {
+        self.path_watcher().is_invalidated(id)
+    }
+
+    /// Take list of watched paths affected by changes since last call, in no particular order.
+    /// Paths stay watched.
+    pub fn take_invalidated_paths(&mut self) -> Vec<NavPathWatchID> {
+        self.path_watcher_mut().take_invalidated()
+    }
+}
+
+impl NavTiledMesh {
+    /// Start watching path going through given tiles triangles, so when any of its tiles gets
+    /// rebuilt, removed or modified (see `tile_mut`), path gets reported by
+    /// `take_invalidated_paths`.
+    ///
+    /// # Arguments
+    /// * `triangles` - tiles triangles path goes through.
+    ///
+    /// # Returns
+    /// Watch identifier.
+    pub fn watch_path(&mut self, triangles: &[NavTileTriangle]) -> NavPathWatchID {
+        self.path_watcher_mut()
+            .watch(triangles.iter().map(|triangle| triangle.tile))
+    }
+
+    /// Replace tiles triangles of watched path. Clears its invalidated state.
+    ///
+    /// # Returns
+    /// `true` if path is watched.
+    pub fn update_watched_path(
+        &mut self,
+        id: NavPathWatchID,
+        triangles: &[NavTileTriangle],
+    ) -> bool {
+        self.path_watcher_mut()
+            .update(id, triangles.iter().map(|triangle| triangle.tile))
+    }
+
+    /// Stop watching path.
+    ///
+    /// # Returns
+    /// `true` if path was watched.
+    pub fn unwatch_path(&mut self, id: NavPathWatchID) -> bool {
+        self.path_watcher_mut().unwatch(id)
+    }
+
+    /// Number of watched paths.
+    #[inline]
+    pub fn watched_paths_count(&self) -> usize {
+        self.path_watcher().len()
+    }
+
+    /// Tells if watched path was affected by changes not taken yet.
+    #[inline]
+    pub fn is_watched_path_invalidated(&self, id: NavPathWatchID) -> bool {
+        self.path_watcher().is_invalidated(id)
+    }
+
+    /// Take list of watched paths affected by changes since last call, in no particular order.
+    /// Paths stay watched.
+    pub fn take_invalidated_paths(&mut self) -> Vec<NavPathWatchID> {
+        self.path_watcher_mut().take_invalidated()
+    }
+}
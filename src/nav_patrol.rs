@@ -0,0 +1,242 @@
+use crate::{NavMesh, NavPath, NavPathMode, NavQuery, NavVec3, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Part of patrol route going between two consecutive waypoints.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPatrolLeg {
+    /// Index of waypoint leg starts at, in waypoints list given to `NavMesh::find_patrol_route`.
+    pub from: usize,
+    /// Index of waypoint leg ends at.
+    pub to: usize,
+    /// Range of route path points belonging to leg. Last point of leg is first point of next
+    /// one.
+    pub points: Range<usize>,
+    /// Total cost of leg triangles path.
+    pub cost: Scalar,
+    /// Geometric length of leg.
+    pub length: Scalar,
+}
+
+/// Closed loop patrol route visiting set of waypoints.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPatrolRoute {
+    /// Looping path, starting and ending at first visited waypoint.
+    pub path: NavPath,
+    /// Indices of waypoints in visiting order.
+    pub order: Vec<usize>,
+    /// Legs between consecutive waypoints, last one goes back to first visited waypoint.
+    pub legs: Vec<NavPatrolLeg>,
+}
+
+impl NavPatrolRoute {
+    /// Find leg that contains given path segment (segment index is index of its first point).
+    pub fn leg_of_segment(&self, segment: usize) -> Option<&NavPatrolLeg> {
+        self.legs
+            .iter()
+            .find(|leg| segment >= leg.points.start && segment + 1 < leg.points.end)
+    }
+}
+
+impl NavMesh {
+    /// Find closed loop patrol route visiting all waypoints, with paths between consecutive
+    /// waypoints stitched into single looping path.
+    ///
+    /// When reordering, route starts at first waypoint and visits nearest (by path length) not
+    /// visited one next, then gets shortened by reversing parts of it that cross over. This does
+    /// not guarantee shortest possible route, but gives sensible ones for usual waypoints counts.
+    ///
+    /// # Arguments
+    /// * `waypoints` - list of waypoints.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    /// * `reorder` - find visiting order instead of visiting waypoints in given order.
+    ///
+    /// # Returns
+    /// `Some` with route or `None` if there are less than two waypoints or some of them cannot be
+    /// reached.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let waypoints = [
+    ///     (0.25, 0.5, 0.0).into(),
+    ///     (1.75, 0.5, 0.0).into(),
+    ///     (1.0, 0.2, 0.0).into(),
+    ///     (1.0, 0.8, 0.0).into(),
+    /// ];
+    /// let route = mesh
+    ///     .find_patrol_route(&waypoints, NavQuery::Accuracy, NavPathMode::Accuracy, true)
+    ///     .unwrap();
+    /// assert_eq!(route.order, vec![0, 2, 1, 3]);
+    /// assert_eq!(route.legs.len(), 4);
+    /// assert_eq!(route.path.points.first(), route.path.points.last());
+    /// // crossing route found by visiting nearest waypoints first gets untangled.
+    /// let expected = NavVec3::new(0.75, 0.3, 0.0).magnitude() * 4.0;
+    /// assert!((route.path.length - expected).abs() < 1.0e-4);
+    /// ```
+    pub fn find_patrol_route(
+        &self,
+        waypoints: &[NavVec3],
+        query: NavQuery,
+        mode: NavPathMode,
+        reorder: bool,
+    ) -> Option<NavPatrolRoute> {
+        let count = waypoints.len();
+        if count < 2 {
+            return None;
+        }
+        let epsilon = self.tolerance().distance_sqr;
+        let mut waypoints_triangles = Vec::with_capacity(count);
+        for waypoint in waypoints {
+            waypoints_triangles.push(self.find_closest_triangle(*waypoint, query)?);
+        }
+        // paths between waypoints of lower and higher index, reversed when going backwards.
+        let mut paths = vec![None; count * count];
+        let leg_path = |from: usize, to: usize| {
+            if waypoints[from].same_as_within(waypoints[to], epsilon) {
+                let triangle = waypoints_triangles[from];
+                let point = self.closest_point_on_triangle(triangle, waypoints[from])?;
+                return Some(self.nav_path(vec![point, point], vec![triangle], 0.0));
+            }
+            self.find_path_detailed(waypoints[from], waypoints[to], query, mode)
+        };
+        let mut path = |from: usize, to: usize| -> Option<NavPath> {
+            let (a, b) = (from.min(to), from.max(to));
+            let cached = &mut paths[a * count + b];
+            if cached.is_none() {
+                *cached = Some(leg_path(a, b));
+            }
+            let result = cached.clone().flatten()?;
+            Some(if from > to { reversed(result) } else { result })
+        };
+        let order = if reorder {
+            let mut lengths = vec![Scalar::INFINITY; count * count];
+            for a in 0..count {
+                for b in (a + 1)..count {
+                    let length = path(a, b)?.length;
+                    lengths[a * count + b] = length;
+                    lengths[b * count + a] = length;
+                }
+            }
+            patrol_order(count, &lengths)
+        } else {
+            (0..count).collect::<Vec<_>>()
+        };
+        let mut result = NavPatrolRoute {
+            path: NavPath::default(),
+            order,
+            legs: Vec::with_capacity(count),
+        };
+        for index in 0..count {
+            let from = result.order[index];
+            let to = result.order[(index + 1) % count];
+            let leg = path(from, to)?;
+            let start = result.path.points.len().saturating_sub(1);
+            if index == 0 {
+                result.path = NavPath {
+                    points: vec![],
+                    cost: 0.0,
+                    length: 0.0,
+                    triangles: vec![],
+                    segment_area_types: vec![],
+                    ..leg.clone()
+                };
+                result.path.points.push(leg.points[0]);
+            }
+            result
+                .path
+                .points
+                .extend(leg.points.iter().skip(1).copied());
+            result.path.cost += leg.cost;
+            result.path.length += leg.length;
+            for triangle in leg.triangles {
+                if result.path.triangles.last() != Some(&triangle) {
+                    result.path.triangles.push(triangle);
+                }
+            }
+            result
+                .path
+                .segment_area_types
+                .extend(leg.segment_area_types);
+            result.legs.push(NavPatrolLeg {
+                from,
+                to,
+                points: start..result.path.points.len(),
+                cost: leg.cost,
+                length: leg.length,
+            });
+        }
+        Some(result)
+    }
+}
+
+/// Nearest neighbor visiting order improved by reversing crossing parts (2-opt), given
+/// symmetric distances matrix.
+fn patrol_order(count: usize, lengths: &[Scalar]) -> Vec<usize> {
+    let length = |a: usize, b: usize| lengths[a * count + b];
+    let mut order = vec![0];
+    let mut visited = vec![false; count];
+    visited[0] = true;
+    while order.len() < count {
+        let current = *order.last().unwrap();
+        let next = (0..count)
+            .filter(|index| !visited[*index])
+            .min_by(|a, b| {
+                length(current, *a)
+                    .partial_cmp(&length(current, *b))
+                    .unwrap()
+            })
+            .unwrap();
+        visited[next] = true;
+        order.push(next);
+    }
+    // every improvement makes route shorter, limit only guards against float noise.
+    for _ in 0..count * count {
+        let mut improved = false;
+        for i in 1..(count - 1) {
+            for j in (i + 1)..count {
+                let a = order[i - 1];
+                let b = order[i];
+                let c = order[j];
+                let d = order[(j + 1) % count];
+                let delta = length(a, c) + length(b, d) - length(a, b) - length(c, d);
+                if delta < -1.0e-4 {
+                    order[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    order
+}
+
+fn reversed(mut path: NavPath) -> NavPath {
+    path.points.reverse();
+    path.triangles.reverse();
+    path.segment_area_types.reverse();
+    path
+}
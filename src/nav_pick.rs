@@ -0,0 +1,210 @@
+use crate::{
+    closest_points_segment_triangle, NavMesh, NavQuery, NavQueryFilter, NavVec3, Scalar,
+    ZERO_TRESHOLD,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use spade::BoundingRect;
+use std::cmp::Ordering;
+
+/// Settings of resolving point-and-click navigation target from world space ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPickSettings {
+    /// Maximal distance along ray to look for nav mesh at.
+    pub max_distance: Scalar,
+    /// Maximal distance of target from ray, used when ray misses walkable nav mesh surface.
+    pub search_radius: Scalar,
+}
+
+impl Default for NavPickSettings {
+    fn default() -> Self {
+        Self {
+            max_distance: 1000.0,
+            search_radius: 1.0,
+        }
+    }
+}
+
+/// Navigation target resolved from world space ray, see `NavMesh::pick_target`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPickTarget {
+    /// Target point on nav mesh surface.
+    pub point: NavVec3,
+    /// Index of triangle containing target point.
+    pub triangle: usize,
+    /// Tells if ray hit target triangle, otherwise target is nav mesh point closest to ray.
+    pub direct: bool,
+    /// Distance from ray origin to ray point closest to target.
+    pub distance: Scalar,
+}
+
+impl NavMesh {
+    /// Resolve navigation target from world space ray, for example mouse click ray going from
+    /// camera through cursor.
+    ///
+    /// Target is the first walkable (not blocked) triangle hit by ray. If ray misses walkable
+    /// surface, target is point of walkable nav mesh closest to ray within search radius. When
+    /// agent position is given, only targets reachable from it are accepted, so clicks on
+    /// isolated islands snap to closest reachable surface instead.
+    ///
+    /// # Arguments
+    /// * `origin` - ray origin.
+    /// * `direction` - ray direction.
+    /// * `from` - `Some` with agent position that target has to be reachable from.
+    /// * `settings` - picking settings.
+    ///
+    /// # Returns
+    /// `Some` with navigation target or `None` if there is no valid target around ray.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (2.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 2.0, 0.0).into(), // 2
+    ///     (0.0, 2.0, 0.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let settings = NavPickSettings::default();
+    /// let target = mesh
+    ///     .pick_target((1.5, 0.5, 5.0).into(), (0.0, 0.0, -1.0).into(), None, settings)
+    ///     .unwrap();
+    /// assert!(target.direct);
+    /// assert_eq!(target.triangle, 0);
+    /// assert!(target.point.same_as((1.5, 0.5, 0.0).into()));
+    ///
+    /// // ray misses nav mesh, so target snaps to its edge.
+    /// let target = mesh
+    ///     .pick_target((2.5, 1.0, 5.0).into(), (0.0, 0.0, -1.0).into(), None, settings)
+    ///     .unwrap();
+    /// assert!(!target.direct);
+    /// assert!(target.point.same_as((2.0, 1.0, 0.0).into()));
+    /// assert!(mesh
+    ///     .pick_target((5.0, 1.0, 5.0).into(), (0.0, 0.0, -1.0).into(), None, settings)
+    ///     .is_none());
+    /// ```
+    pub fn pick_target(
+        &self,
+        origin: NavVec3,
+        direction: NavVec3,
+        from: Option<NavVec3>,
+        settings: NavPickSettings,
+    ) -> Option<NavPickTarget> {
+        self.pick_target_custom(
+            origin,
+            direction,
+            from,
+            settings,
+            |_| true,
+            |from, to| self.find_path_triangles(from, to).is_some(),
+        )
+    }
+
+    /// Resolve navigation target from world space ray like `pick_target` does, accepting only
+    /// targets on areas allowed by query filter and reachable through them.
+    ///
+    /// # Arguments
+    /// * `origin` - ray origin.
+    /// * `direction` - ray direction.
+    /// * `from` - `Some` with agent position that target has to be reachable from.
+    /// * `settings` - picking settings.
+    /// * `filter` - query filter.
+    ///
+    /// # Returns
+    /// `Some` with navigation target or `None` if there is no valid target around ray.
+    pub fn pick_target_with_filter<Q>(
+        &self,
+        origin: NavVec3,
+        direction: NavVec3,
+        from: Option<NavVec3>,
+        settings: NavPickSettings,
+        filter: &Q,
+    ) -> Option<NavPickTarget>
+    where
+        Q: NavQueryFilter + ?Sized,
+    {
+        self.pick_target_custom(
+            origin,
+            direction,
+            from,
+            settings,
+            |index| {
+                let area = &self.areas()[index];
+                filter
+                    .cost(area.center, area.center, area.area_type)
+                    .is_some()
+            },
+            |from, to| {
+                self.find_path_triangles_with_filter(from, to, filter)
+                    .is_some()
+            },
+        )
+    }
+
+    fn pick_target_custom<A, R>(
+        &self,
+        origin: NavVec3,
+        direction: NavVec3,
+        from: Option<NavVec3>,
+        settings: NavPickSettings,
+        allowed: A,
+        reachable: R,
+    ) -> Option<NavPickTarget>
+    where
+        A: Fn(usize) -> bool,
+        R: Fn(usize, usize) -> bool,
+    {
+        if direction.sqr_magnitude() < Scalar::EPSILON {
+            return None;
+        }
+        let radius = settings.search_radius.max(0.0);
+        let to = origin + direction.normalize() * settings.max_distance.max(0.0);
+        let extent = NavVec3::new(radius, radius, radius);
+        let mut candidates = self
+            .spatial_index()
+            .lookup_in_rectangle(&BoundingRect::from_corners(
+                &(origin.min(to) - extent),
+                &(origin.max(to) + extent),
+            ))
+            .into_iter()
+            .filter(|s| !self.is_triangle_blocked(s.index) && allowed(s.index))
+            .filter_map(|s| {
+                let (on_ray, point) = closest_points_segment_triangle(origin, to, s.a, s.b, s.c);
+                let offset = on_ray.distance(point);
+                if offset <= radius {
+                    Some((s.index, point, offset, on_ray.distance(origin)))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        // direct hits come first, ordered along ray, then misses by their distance from ray.
+        candidates.sort_by(|a, b| {
+            a.2.partial_cmp(&b.2)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.3.partial_cmp(&b.3).unwrap_or(Ordering::Equal))
+        });
+        let start = match from {
+            Some(from) => Some(self.find_closest_triangle(from, NavQuery::Accuracy)?),
+            None => None,
+        };
+        candidates
+            .into_iter()
+            .find(|(index, _, _, _)| match start {
+                Some(start) => start == *index || reachable(start, *index),
+                None => true,
+            })
+            .map(|(triangle, point, offset, distance)| NavPickTarget {
+                point,
+                triangle,
+                direct: offset <= ZERO_TRESHOLD,
+                distance,
+            })
+    }
+}
@@ -0,0 +1,420 @@
+use crate::{
+    nav_funnel::{center_portal, funnel},
+    nav_geom::closest_point_on_triangle,
+    nav_mesh_simplify::triangulate_convex,
+    nav_open_node::OpenNode,
+    Error, NavConnection, NavMesh, NavPathMode, NavResult, NavTriangle, NavUpAxis, NavVec3, Scalar,
+    SCALAR_MAX, ZERO_TRESHOLD,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+
+/// Convex navigation polygon.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPolygon {
+    /// Polygon outline vertices indices, in counter clockwise order looking from above.
+    pub vertices: Vec<u32>,
+    pub center: NavVec3,
+    pub cost: Scalar,
+    pub area_type: u8,
+    pub flags: u16,
+    pub clearance: Scalar,
+}
+
+/// Nav mesh made of convex polygons with any number of vertices instead of triangles, which
+/// makes its graph smaller and paths going through less portals.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavPolyMesh {
+    vertices: Vec<NavVec3>,
+    polygons: Vec<NavPolygon>,
+    // [polygon index: [(neighbor polygon index, shared edge vertices in polygon winding order)]]
+    neighbors: Vec<Vec<(usize, NavConnection)>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    up_axis: NavUpAxis,
+}
+
+impl NavPolyMesh {
+    /// Create new polygon nav mesh from vertices and convex polygons.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of vertices points.
+    /// * `polygons` - list of vertices indices that produces convex polygons.
+    ///
+    /// # Returns
+    /// `Ok` with polygon nav mesh or `Err` with `Error::PolygonVerticeIndexOutOfBounds` if input
+    /// data is invalid or `Error::PolygonTriangulationFailed` if polygon has less than three
+    /// vertices.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (2.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (0.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let polygons = vec![vec![0, 1, 4, 5], vec![1, 2, 3, 4]];
+    ///
+    /// let mesh = NavPolyMesh::new(vertices, polygons).unwrap();
+    /// let path = mesh
+    ///     .find_path((0.5, 0.5, 0.0).into(), (1.5, 0.5, 0.0).into(), NavPathMode::Accuracy)
+    ///     .unwrap();
+    /// assert_eq!(path.len(), 2);
+    /// ```
+    pub fn new(vertices: Vec<NavVec3>, polygons: Vec<Vec<u32>>) -> NavResult<Self> {
+        for (index, polygon) in polygons.iter().enumerate() {
+            if polygon.len() < 3 {
+                return Err(Error::PolygonTriangulationFailed(
+                    index as u32,
+                    polygon.clone(),
+                ));
+            }
+            if let Some((local, vertice)) = polygon
+                .iter()
+                .enumerate()
+                .find(|(_, vertice)| **vertice as usize >= vertices.len())
+            {
+                return Err(Error::PolygonVerticeIndexOutOfBounds(
+                    index as u32,
+                    local as u32,
+                    *vertice,
+                ));
+            }
+        }
+        let polygons = polygons
+            .into_iter()
+            .map(|polygon| {
+                let center = polygon
+                    .iter()
+                    .fold(NavVec3::default(), |a, v| a + vertices[*v as usize])
+                    / polygon.len() as Scalar;
+                NavPolygon {
+                    vertices: polygon,
+                    center,
+                    cost: 1.0,
+                    area_type: 0,
+                    flags: 0,
+                    clearance: SCALAR_MAX,
+                }
+            })
+            .collect::<Vec<_>>();
+        // {edge: [polygon index]}
+        let mut edges = HashMap::<NavConnection, Vec<usize>>::new();
+        for (index, polygon) in polygons.iter().enumerate() {
+            let count = polygon.vertices.len();
+            for i in 0..count {
+                let edge = NavConnection(polygon.vertices[i], polygon.vertices[(i + 1) % count]);
+                edges.entry(edge).or_default().push(index);
+            }
+        }
+        let mut neighbors = Vec::with_capacity(polygons.len());
+        for (index, polygon) in polygons.iter().enumerate() {
+            let outline = &polygon.vertices;
+            let count = outline.len();
+            let shared = |i: usize, other: usize| {
+                edges[&NavConnection(outline[i % count], outline[(i + 1) % count])].contains(&other)
+            };
+            // neighbors can share chain of co-linear edges, which together make one portal.
+            let mut list = vec![];
+            for i in 0..count {
+                let edge = NavConnection(outline[i], outline[(i + 1) % count]);
+                for other in &edges[&edge] {
+                    if *other == index || shared(i + count - 1, *other) {
+                        continue;
+                    }
+                    let mut end = i;
+                    while end < i + count - 1 && shared(end + 1, *other) {
+                        end += 1;
+                    }
+                    list.push((
+                        *other,
+                        NavConnection(outline[i], outline[(end + 1) % count]),
+                    ));
+                }
+            }
+            list.sort_by_key(|(index, _)| *index);
+            list.dedup_by_key(|(index, _)| *index);
+            neighbors.push(list);
+        }
+        Ok(Self {
+            vertices,
+            polygons,
+            neighbors,
+            up_axis: Default::default(),
+        })
+    }
+
+    /// Convert triangle nav mesh into polygon one, by merging neighbor triangles lying on the
+    /// same plane and having the same area properties into convex polygons.
+    ///
+    /// # Arguments
+    /// * `mesh` - source nav mesh.
+    /// * `max_error` - maximal distance of merged triangles vertices from plane of polygon they
+    ///   get merged into.
+    ///
+    /// # Returns
+    /// `Ok` with polygon nav mesh or `Err` if it could not be built.
+    pub fn from_nav_mesh(mesh: &NavMesh, max_error: Scalar) -> NavResult<Self> {
+        let polygons = mesh.convex_polygons(max_error);
+        let mut vertices = vec![];
+        // {source vertice: result vertice}
+        let mut used = HashMap::new();
+        let outlines = polygons
+            .iter()
+            .map(|(outline, _)| {
+                outline
+                    .iter()
+                    .map(|index| {
+                        *used.entry(*index).or_insert_with(|| {
+                            vertices.push(mesh.vertices()[*index as usize]);
+                            vertices.len() as u32 - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut result = Self::new(vertices, outlines)?;
+        for (polygon, (_, source)) in result.polygons.iter_mut().zip(polygons) {
+            let area = &mesh.areas()[source];
+            polygon.cost = area.cost;
+            polygon.area_type = area.area_type;
+            polygon.flags = area.flags;
+            polygon.clearance = area.clearance;
+        }
+        result.up_axis = mesh.up_axis();
+        Ok(result)
+    }
+
+    /// Convert polygon nav mesh into triangle one, keeping polygons properties.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh or `Err` if it could not be built.
+    pub fn to_nav_mesh(&self) -> NavResult<NavMesh> {
+        let mut triangles = vec![];
+        for (index, polygon) in self.polygons.iter().enumerate() {
+            let points = polygon
+                .vertices
+                .iter()
+                .map(|v| self.vertices[*v as usize])
+                .collect::<Vec<_>>();
+            for (a, b, c) in triangulate_convex(&points, ZERO_TRESHOLD) {
+                let triangle = NavTriangle::from((
+                    polygon.vertices[a],
+                    polygon.vertices[b],
+                    polygon.vertices[c],
+                ));
+                triangles.push((triangle, index));
+            }
+        }
+        let mut result = NavMesh::new(
+            self.vertices.clone(),
+            triangles.iter().map(|(t, _)| *t).collect(),
+        )?;
+        for (index, (_, polygon)) in triangles.into_iter().enumerate() {
+            let polygon = &self.polygons[polygon];
+            result.set_area_cost(index, polygon.cost);
+            result.set_area_type(index, polygon.area_type);
+            result.set_area_flags(index, polygon.flags);
+            result.set_area_clearance(index, polygon.clearance);
+        }
+        result.set_up_axis(self.up_axis);
+        Ok(result)
+    }
+
+    #[inline]
+    pub fn vertices(&self) -> &[NavVec3] {
+        &self.vertices
+    }
+
+    #[inline]
+    pub fn polygons(&self) -> &[NavPolygon] {
+        &self.polygons
+    }
+
+    #[inline]
+    pub fn up_axis(&self) -> NavUpAxis {
+        self.up_axis
+    }
+
+    #[inline]
+    pub fn set_up_axis(&mut self, up_axis: NavUpAxis) {
+        self.up_axis = up_axis;
+    }
+
+    /// Indices of polygons sharing an edge with given polygon.
+    pub fn polygon_neighbors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbors
+            .get(index)
+            .into_iter()
+            .flatten()
+            .map(|(index, _)| *index)
+    }
+
+    /// Edge shared by two neighbor polygons, going along first polygon outline.
+    pub fn polygons_portal(&self, from: usize, to: usize) -> Option<(NavVec3, NavVec3)> {
+        self.neighbors
+            .get(from)?
+            .iter()
+            .find(|(index, _)| *index == to)
+            .map(|(_, NavConnection(a, b))| {
+                (self.vertices[*a as usize], self.vertices[*b as usize])
+            })
+    }
+
+    /// Find point of polygon closest to given point.
+    ///
+    /// # Arguments
+    /// * `index` - polygon index.
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with closest point or `None` if polygon does not exists.
+    pub fn closest_point_on_polygon(&self, index: usize, point: NavVec3) -> Option<NavVec3> {
+        let polygon = self.polygons.get(index)?;
+        let a = self.vertices[polygon.vertices[0] as usize];
+        polygon
+            .vertices
+            .windows(2)
+            .skip(1)
+            .map(|pair| {
+                let b = self.vertices[pair[0] as usize];
+                let c = self.vertices[pair[1] as usize];
+                closest_point_on_triangle(point, a, b, c)
+            })
+            .min_by(|x, y| {
+                point
+                    .distance_squared(*x)
+                    .partial_cmp(&point.distance_squared(*y))
+                    .unwrap()
+            })
+    }
+
+    /// Find polygon closest to given point, testing all polygons.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with polygon index or `None` if mesh has no polygons.
+    pub fn find_closest_polygon(&self, point: NavVec3) -> Option<usize> {
+        (0..self.polygons.len())
+            .filter_map(|index| {
+                let closest = self.closest_point_on_polygon(index, point)?;
+                Some((index, point.distance_squared(closest)))
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index)
+    }
+
+    /// Find shortest path between two polygons.
+    ///
+    /// Moves between polygons cost squared distance between their centers scaled by both
+    /// polygons costs, same as triangles connections in `NavMesh`.
+    ///
+    /// # Arguments
+    /// * `from` - start polygon index.
+    /// * `to` - end polygon index.
+    ///
+    /// # Returns
+    /// `Some` with path polygons and path cost if found or `None` otherwise.
+    pub fn find_path_polygons(&self, from: usize, to: usize) -> Option<(Vec<usize>, Scalar)> {
+        if from >= self.polygons.len() || to >= self.polygons.len() {
+            return None;
+        }
+        let mut open = BinaryHeap::new();
+        let mut costs = vec![Scalar::INFINITY; self.polygons.len()];
+        let mut parents = vec![None; self.polygons.len()];
+        costs[from] = 0.0;
+        open.push(OpenNode(0.0, from));
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if current == to {
+                let mut path = vec![current];
+                let mut node = current;
+                while let Some(parent) = parents[node] {
+                    path.push(parent);
+                    node = parent;
+                }
+                path.reverse();
+                return Some((path, cost));
+            }
+            if cost > costs[current] {
+                continue;
+            }
+            let polygon = &self.polygons[current];
+            for next in self.polygon_neighbors(current) {
+                let other = &self.polygons[next];
+                let weight =
+                    (other.center - polygon.center).sqr_magnitude() * polygon.cost * other.cost;
+                let next_cost = cost + weight;
+                if next_cost < costs[next] {
+                    costs[next] = next_cost;
+                    parents[next] = Some(current);
+                    open.push(OpenNode(next_cost, next));
+                }
+            }
+        }
+        None
+    }
+
+    /// Find shortest path between two points.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with path points on polygon nav mesh if found or `None` otherwise.
+    pub fn find_path(&self, from: NavVec3, to: NavVec3, mode: NavPathMode) -> Option<Vec<NavVec3>> {
+        if from.same_as(to) {
+            return None;
+        }
+        let start = self.find_closest_polygon(from)?;
+        let end = self.find_closest_polygon(to)?;
+        let from = self.closest_point_on_polygon(start, from)?;
+        let to = self.closest_point_on_polygon(end, to)?;
+        let (polygons, _) = self.find_path_polygons(start, end)?;
+        if polygons.len() == 1 {
+            return Some(vec![from, to]);
+        }
+        let portals = polygons
+            .windows(2)
+            .map(|pair| self.polygons_portal(pair[0], pair[1]))
+            .collect::<Option<Vec<_>>>()?;
+        match mode {
+            NavPathMode::Accuracy | NavPathMode::Centerline(_) => {
+                // portals are not told apart from walls here, so both portal ends get centered.
+                let weight = match mode {
+                    NavPathMode::Centerline(weight) => weight,
+                    _ => 0.0,
+                };
+                let mut oriented = Vec::with_capacity(portals.len() + 2);
+                oriented.push((from, from));
+                // polygon outline goes around it counter clockwise, so portal end is on the left
+                // side when leaving polygon.
+                oriented.extend(
+                    portals
+                        .into_iter()
+                        .map(|(a, b)| center_portal((b, a), (true, true), weight)),
+                );
+                oriented.push((to, to));
+                Some(funnel(&oriented, self.up_axis.up()))
+            }
+            NavPathMode::MidPoints => {
+                let mut points = Vec::with_capacity(portals.len() + 2);
+                points.push(from);
+                points.extend(portals.into_iter().map(|(a, b)| (a + b) * 0.5));
+                points.push(to);
+                points.dedup_by(|a, b| a.same_as(*b));
+                Some(points)
+            }
+        }
+    }
+}
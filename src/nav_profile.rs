@@ -0,0 +1,277 @@
+use crate::{NavArea, NavConnection, NavMesh, NavQuery, NavVec3, Scalar, SCALAR_MAX};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use typid::ID;
+
+/// Nav profile identifier.
+pub type NavProfileID = ID<NavProfile>;
+
+/// Navigation profile of agent type (for example human, dog or tank), telling which parts of
+/// nav mesh it can traverse and how much it costs.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavProfile {
+    /// Agent radius, connections narrower than agent are not traversable.
+    pub radius: Scalar,
+    /// Agent height, areas with smaller clearance are not traversable.
+    pub height: Scalar,
+    /// Maximal slope (in radians) of traversable triangles.
+    pub max_slope: Scalar,
+    /// Traverse cost factor per area type. Area types without entry have cost factor of 1.
+    pub costs: HashMap<u8, Scalar>,
+    /// Area has to have at least one of these flags to be traversable.
+    pub include_flags: u16,
+    /// Area has to have none of these flags to be traversable.
+    pub exclude_flags: u16,
+}
+
+impl Default for NavProfile {
+    fn default() -> Self {
+        Self {
+            radius: 0.0,
+            height: 0.0,
+            max_slope: std::f64::consts::FRAC_PI_2 as Scalar,
+            costs: Default::default(),
+            include_flags: u16::MAX,
+            exclude_flags: 0,
+        }
+    }
+}
+
+impl NavProfile {
+    /// Set traverse cost factor of area type.
+    ///
+    /// # Arguments
+    /// * `area_type` - area type.
+    /// * `cost` - cost factor.
+    ///
+    /// # Returns
+    /// Old area type cost value.
+    pub fn set_cost(&mut self, area_type: u8, cost: Scalar) -> Scalar {
+        self.costs.insert(area_type, cost.max(0.0)).unwrap_or(1.0)
+    }
+
+    /// Traverse cost factor of area type.
+    #[inline]
+    pub fn cost(&self, area_type: u8) -> Scalar {
+        self.costs.get(&area_type).copied().unwrap_or(1.0)
+    }
+
+    fn passes(&self, area: &NavArea, slope: Scalar) -> bool {
+        area.flags & self.include_flags != 0
+            && area.flags & self.exclude_flags == 0
+            && area.clearance >= self.height
+            && slope <= self.max_slope
+    }
+}
+
+/// Profile with data precomputed for nav mesh it is registered on.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct NavProfileData {
+    profile: NavProfile,
+    // tells if triangle is traversable.
+    passable: Vec<bool>,
+    // connections too narrow for agent radius.
+    narrow: HashSet<NavConnection>,
+}
+
+impl NavMesh {
+    /// Register navigation profile, precomputing its traversable triangles and connections.
+    ///
+    /// Precomputed data is kept up to date when areas flags, clearance or up axis change.
+    ///
+    /// # Arguments
+    /// * `profile` - navigation profile.
+    ///
+    /// # Returns
+    /// Profile identifier used by profile queries.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// const DOG_DOOR: u16 = 2;
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_area_flags(3, NavArea::DEFAULT_FLAGS | DOG_DOOR);
+    /// let dog = mesh.add_profile(NavProfile::default());
+    /// let human = mesh.add_profile(NavProfile {
+    ///     exclude_flags: DOG_DOOR,
+    ///     ..Default::default()
+    /// });
+    /// assert!(mesh.find_path_triangles_for_profile(1, 2, dog).is_some());
+    /// assert!(mesh.find_path_triangles_for_profile(1, 2, human).is_none());
+    /// ```
+    pub fn add_profile(&mut self, profile: NavProfile) -> NavProfileID {
+        let id = NavProfileID::new();
+        let data = self.profile_data(profile);
+        self.profiles_mut().insert(id, data);
+        id
+    }
+
+    /// Remove navigation profile.
+    ///
+    /// # Arguments
+    /// * `id` - profile identifier.
+    ///
+    /// # Returns
+    /// `Some` with removed profile or `None` if there was no profile with given identifier.
+    pub fn remove_profile(&mut self, id: NavProfileID) -> Option<NavProfile> {
+        self.profiles_mut().remove(&id).map(|data| data.profile)
+    }
+
+    /// Get navigation profile by its identifier.
+    #[inline]
+    pub fn profile(&self, id: NavProfileID) -> Option<&NavProfile> {
+        self.profiles().get(&id).map(|data| &data.profile)
+    }
+
+    /// Iterator over all navigation profiles identifiers.
+    #[inline]
+    pub fn profile_ids(&self) -> impl Iterator<Item = NavProfileID> + '_ {
+        self.profiles().keys().copied()
+    }
+
+    /// Tells if triangle can be traversed by agents using given profile. Obstacles and gates are
+    /// not taken into account.
+    ///
+    /// # Returns
+    /// `Some` with result or `None` if there is no such profile or triangle.
+    pub fn is_triangle_passable_for_profile(&self, index: usize, id: NavProfileID) -> Option<bool> {
+        self.profiles().get(&id)?.passable.get(index).copied()
+    }
+
+    /// Find shortest path on nav mesh between two triangles for agents using given profile.
+    ///
+    /// # Arguments
+    /// * `from` - query triangle index from.
+    /// * `to` - query triangle index to.
+    /// * `id` - profile identifier.
+    ///
+    /// # Returns
+    /// `Some` with path triangles on nav mesh and path cost if found or `None` otherwise.
+    pub fn find_path_triangles_for_profile(
+        &self,
+        from: usize,
+        to: usize,
+        id: NavProfileID,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        let data = self.profiles().get(&id)?;
+        if !*data.passable.get(from)? || !*data.passable.get(to)? {
+            return None;
+        }
+        let profile = &data.profile;
+        let (path, cost) = self.find_path_triangles_with(from, to, |w, a, b| {
+            if data.passable[a]
+                && data.passable[b]
+                && !data.narrow.contains(&NavConnection(a as u32, b as u32))
+            {
+                let a = &self.areas()[a];
+                let b = &self.areas()[b];
+                w * a.cost * profile.cost(a.area_type) * b.cost * profile.cost(b.area_type)
+            } else {
+                SCALAR_MAX
+            }
+        })?;
+        if path.iter().any(|index| !data.passable[*index])
+            || path.windows(2).any(|pair| {
+                data.narrow
+                    .contains(&NavConnection(pair[0] as u32, pair[1] as u32))
+            })
+        {
+            return None;
+        }
+        Some((path, cost))
+    }
+
+    /// Find shortest path on nav mesh between two points for agents using given profile, keeping
+    /// path corners away from nav mesh boundary by profile radius.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `id` - profile identifier.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav mesh if found or `None` otherwise.
+    pub fn find_path_for_profile(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        id: NavProfileID,
+    ) -> Option<Vec<NavVec3>> {
+        if from.same_as_within(to, self.tolerance().distance_sqr) {
+            return None;
+        }
+        let radius = self.profile(id)?.radius;
+        let start = self.find_closest_triangle(from, query)?;
+        let end = self.find_closest_triangle(to, query)?;
+        let from = self.spatial(start).closest_point(from);
+        let to = self.spatial(end).closest_point(to);
+        let (triangles, _) = self.find_path_triangles_for_profile(start, end, id)?;
+        self.string_pull(from, to, &triangles, radius)
+    }
+
+    /// Recompute precomputed data of every profile, for example after up axis change.
+    pub(crate) fn refresh_profiles(&mut self) {
+        let ids = self.profile_ids().collect::<Vec<_>>();
+        for id in ids {
+            if let Some(data) = self.profiles_mut().remove(&id) {
+                let data = self.profile_data(data.profile);
+                self.profiles_mut().insert(id, data);
+            }
+        }
+    }
+
+    /// Recompute traversable state of triangle in every profile, after its area changed.
+    pub(crate) fn refresh_profiles_triangle(&mut self, index: usize) {
+        let slope = self.triangle_slope(index).unwrap_or(SCALAR_MAX);
+        let area = self.areas()[index].clone();
+        for data in self.profiles_mut().values_mut() {
+            data.passable[index] = data.profile.passes(&area, slope);
+        }
+    }
+
+    fn profile_data(&self, profile: NavProfile) -> NavProfileData {
+        let passable = self
+            .areas()
+            .iter()
+            .enumerate()
+            .map(|(index, area)| {
+                profile.passes(area, self.triangle_slope(index).unwrap_or(SCALAR_MAX))
+            })
+            .collect();
+        let narrow = if profile.radius > 0.0 {
+            (0..self.triangles().len())
+                .flat_map(|a| self.triangle_neighbors(a).map(move |b| (a, b)))
+                .filter(|(a, b)| a < b && self.shrunk_portal(*a, *b, profile.radius).is_none())
+                .map(|(a, b)| NavConnection(a as u32, b as u32))
+                .collect()
+        } else {
+            Default::default()
+        };
+        NavProfileData {
+            profile,
+            passable,
+            narrow,
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{NavUpAxis, NavVec3, Scalar, ZERO_TRESHOLD};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::Mul;
+
+/// Rotation unit quaternion.
+///
+/// Local frame of oriented things (agents, formations, shapes) uses X axis as forward and nav
+/// mesh up axis as up, so with Z up local Y axis points left and with Y up it points backwards.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavQuat {
+    pub x: Scalar,
+    pub y: Scalar,
+    pub z: Scalar,
+    pub w: Scalar,
+}
+
+impl Default for NavQuat {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl NavQuat {
+    pub const IDENTITY: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 1.0,
+    };
+
+    #[inline]
+    pub fn new(x: Scalar, y: Scalar, z: Scalar, w: Scalar) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Create rotation around axis by angle (in radians), counter clockwise when looking against
+    /// axis.
+    pub fn from_axis_angle(axis: NavVec3, angle: Scalar) -> Self {
+        let axis = axis.normalize();
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// Create shortest rotation turning one direction into another.
+    pub fn from_rotation_arc(from: NavVec3, to: NavVec3) -> Self {
+        let from = from.normalize();
+        let to = to.normalize();
+        let dot = from.dot(to);
+        if dot < -1.0 + ZERO_TRESHOLD {
+            // opposite directions, turn half way around any perpendicular axis.
+            let axis = from.cross(NavVec3::new(1.0, 0.0, 0.0));
+            let axis = if axis.sqr_magnitude() < ZERO_TRESHOLD {
+                from.cross(NavVec3::new(0.0, 1.0, 0.0))
+            } else {
+                axis
+            };
+            return Self::from_axis_angle(axis, std::f64::consts::PI as Scalar);
+        }
+        let axis = from.cross(to);
+        Self::new(axis.x, axis.y, axis.z, 1.0 + dot).normalize()
+    }
+
+    /// Create rotation around up axis, turning local forward axis towards given direction on
+    /// ground plane.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let rotation = NavQuat::facing((0.0, 0.0, -2.0).into(), NavUpAxis::Y);
+    /// assert!(rotation.forward().same_as((0.0, 0.0, -1.0).into()));
+    /// assert!(rotation.rotate(NavUpAxis::Y.up()).same_as(NavUpAxis::Y.up()));
+    /// let yaw = rotation.to_yaw(NavUpAxis::Y);
+    /// assert!((yaw - std::f64::consts::FRAC_PI_2 as Scalar).abs() < 1.0e-5);
+    /// ```
+    pub fn facing(direction: NavVec3, up_axis: NavUpAxis) -> Self {
+        let direction = up_axis.rotate_to_z_up(direction);
+        Self::from_axis_angle(up_axis.up(), direction.y.atan2(direction.x))
+    }
+
+    /// Angle (in radians) of rotation around up axis, that local forward axis got turned by.
+    pub fn to_yaw(self, up_axis: NavUpAxis) -> Scalar {
+        let forward = up_axis.rotate_to_z_up(self.forward());
+        forward.y.atan2(forward.x)
+    }
+
+    /// Rotation axis and angle (in radians).
+    pub fn to_axis_angle(self) -> (NavVec3, Scalar) {
+        let q = self.normalize();
+        let sin = (1.0 - q.w * q.w).max(0.0).sqrt();
+        let angle = 2.0 * q.w.clamp(-1.0, 1.0).acos();
+        if sin < ZERO_TRESHOLD {
+            (NavVec3::new(1.0, 0.0, 0.0), angle)
+        } else {
+            (NavVec3::new(q.x, q.y, q.z) / sin, angle)
+        }
+    }
+
+    #[inline]
+    pub fn dot(self, other: Self) -> Scalar {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    #[inline]
+    pub fn magnitude(self) -> Scalar {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        let len = self.magnitude();
+        if len < ZERO_TRESHOLD {
+            Self::IDENTITY
+        } else {
+            Self::new(self.x / len, self.y / len, self.z / len, self.w / len)
+        }
+    }
+
+    /// Opposite rotation.
+    #[inline]
+    pub fn inverse(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Rotate vector.
+    pub fn rotate(self, v: NavVec3) -> NavVec3 {
+        let u = NavVec3::new(self.x, self.y, self.z);
+        let t = u.cross(v) * 2.0;
+        v + t * self.w + u.cross(t)
+    }
+
+    /// Rotated local forward (X) axis.
+    #[inline]
+    pub fn forward(self) -> NavVec3 {
+        self.rotate(NavVec3::new(1.0, 0.0, 0.0))
+    }
+
+    /// Rotated local X, Y and Z axes.
+    pub fn to_basis(self) -> (NavVec3, NavVec3, NavVec3) {
+        (
+            self.rotate(NavVec3::new(1.0, 0.0, 0.0)),
+            self.rotate(NavVec3::new(0.0, 1.0, 0.0)),
+            self.rotate(NavVec3::new(0.0, 0.0, 1.0)),
+        )
+    }
+
+    /// Angle (in radians) of rotation between this and other rotation.
+    pub fn angle_between(self, other: Self) -> Scalar {
+        2.0 * self
+            .normalize()
+            .dot(other.normalize())
+            .abs()
+            .min(1.0)
+            .acos()
+    }
+
+    /// Spherical interpolation going shortest way.
+    ///
+    /// # Arguments
+    /// * `other` - target rotation.
+    /// * `factor` - interpolation factor, 0 gives this rotation and 1 gives target one.
+    pub fn slerp(self, other: Self, factor: Scalar) -> Self {
+        let mut dot = self.dot(other);
+        let other = if dot < 0.0 {
+            dot = -dot;
+            Self::new(-other.x, -other.y, -other.z, -other.w)
+        } else {
+            other
+        };
+        let (a, b) = if dot > 1.0 - ZERO_TRESHOLD {
+            (1.0 - factor, factor)
+        } else {
+            let angle = dot.acos();
+            let sin = angle.sin();
+            (
+                ((1.0 - factor) * angle).sin() / sin,
+                (factor * angle).sin() / sin,
+            )
+        };
+        Self::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+        .normalize()
+    }
+}
+
+impl Mul for NavQuat {
+    type Output = Self;
+
+    /// Combine rotations, applying right hand side first.
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        )
+    }
+}
+
+impl Mul<NavVec3> for NavQuat {
+    type Output = NavVec3;
+
+    fn mul(self, other: NavVec3) -> NavVec3 {
+        self.rotate(other)
+    }
+}
+
+impl From<(Scalar, Scalar, Scalar, Scalar)> for NavQuat {
+    fn from(value: (Scalar, Scalar, Scalar, Scalar)) -> Self {
+        Self::new(value.0, value.1, value.2, value.3)
+    }
+}
+
+impl From<[Scalar; 4]> for NavQuat {
+    fn from(value: [Scalar; 4]) -> Self {
+        Self::new(value[0], value[1], value[2], value[3])
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<mint::Quaternion<Scalar>> for NavQuat {
+    fn from(q: mint::Quaternion<Scalar>) -> Self {
+        Self::new(q.v.x, q.v.y, q.v.z, q.s)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<NavQuat> for mint::Quaternion<Scalar> {
+    fn from(q: NavQuat) -> Self {
+        Self {
+            v: mint::Vector3 {
+                x: q.x,
+                y: q.y,
+                z: q.z,
+            },
+            s: q.w,
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::Quat> for NavQuat {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(q: glam::Quat) -> Self {
+        Self::new(q.x as Scalar, q.y as Scalar, q.z as Scalar, q.w as Scalar)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<NavQuat> for glam::Quat {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(q: NavQuat) -> Self {
+        Self::from_xyzw(q.x as f32, q.y as f32, q.z as f32, q.w as f32)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::DQuat> for NavQuat {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(q: glam::DQuat) -> Self {
+        Self::new(q.x as Scalar, q.y as Scalar, q.z as Scalar, q.w as Scalar)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<NavQuat> for glam::DQuat {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(q: NavQuat) -> Self {
+        Self::from_xyzw(q.x as f64, q.y as f64, q.z as f64, q.w as f64)
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{
+    nav_open_node::OpenNode, nav_stats::NavTimer, NavMesh, NavMeshID, NavPathMode, NavQuery,
+    NavVec3, Scalar,
+};
+use std::{
+    collections::{BinaryHeap, HashMap, VecDeque},
+    time::Duration,
+};
+use typid::ID;
+
+/// Nav query identifier, used as handle to query result.
+pub type NavQueryID = ID<NavQueryStatus>;
+
+/// Status of path query requested from `NavQueryPool`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NavQueryStatus {
+    /// Query is waiting in queue or is being processed.
+    Pending,
+    /// Path has been found.
+    Found(Vec<NavVec3>),
+    /// There is no path between query points.
+    NotFound,
+}
+
+impl NavQueryStatus {
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending)
+    }
+}
+
+/// Amount of work `NavQueryPool` can perform during single update.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NavQueryBudget {
+    /// Maximal number of triangles expanded by searches.
+    Expansions(usize),
+    /// Maximal time spent on searches. Needs system clock, which `wasm32-unknown-unknown` target
+    /// does not have, there budget is never exhausted, use `Expansions` instead.
+    Time(Duration),
+}
+
+impl NavQueryBudget {
+    /// Start measuring time budget. System clock is read only for `Time` budget.
+    #[inline]
+    pub(crate) fn timer(&self) -> Option<NavTimer> {
+        match self {
+            Self::Expansions(_) => None,
+            Self::Time(_) => Some(NavTimer::start()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NavQuerySearch {
+    mesh: NavMeshID,
+    end: usize,
+    open: BinaryHeap<OpenNode<usize>>,
+    costs: HashMap<usize, Scalar>,
+    parents: HashMap<usize, usize>,
+}
+
+/// Path search that can be performed in steps, so one expensive query can be spread across
+/// frames or aborted (by dropping it) when it is not needed anymore.
+///
+/// Search uses the same triangle costs as `NavMesh::find_path`. Stepping it with different nav
+/// mesh than it was started on restarts it.
+#[derive(Debug)]
+pub struct NavPathSearch {
+    from: NavVec3,
+    to: NavVec3,
+    query: NavQuery,
+    mode: NavPathMode,
+    search: Option<NavQuerySearch>,
+    status: NavQueryStatus,
+}
+
+impl NavPathSearch {
+    /// Create search that gets started on first step.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    pub fn new(from: NavVec3, to: NavVec3, query: NavQuery, mode: NavPathMode) -> Self {
+        Self {
+            from,
+            to,
+            query,
+            mode,
+            search: None,
+            status: NavQueryStatus::Pending,
+        }
+    }
+
+    /// Search status, `NavQueryStatus::Pending` until search completes.
+    #[inline]
+    pub fn status(&self) -> &NavQueryStatus {
+        &self.status
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        !self.status.is_pending()
+    }
+
+    /// Perform up to given number of search steps, each expanding single triangle.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `nodes` - maximal number of steps.
+    ///
+    /// # Returns
+    /// Number of performed steps.
+    pub fn step(&mut self, mesh: &NavMesh, nodes: usize) -> usize {
+        let mut steps = 0;
+        while steps < nodes && !self.is_finished() {
+            steps += 1;
+            if let Some(result) = self.expand(mesh) {
+                self.status = result
+                    .and_then(|(triangles, cost)| {
+                        mesh.find_path_with(self.from, self.to, self.query, self.mode, |_, _| {
+                            Some((triangles, cost))
+                        })
+                    })
+                    .map(NavQueryStatus::Found)
+                    .unwrap_or(NavQueryStatus::NotFound);
+                self.search = None;
+            }
+        }
+        steps
+    }
+
+    /// Take search result.
+    ///
+    /// # Returns
+    /// `NavQueryStatus::Found` or `NavQueryStatus::NotFound` if search has completed, or
+    /// `NavQueryStatus::Pending` otherwise.
+    pub fn take_result(self) -> NavQueryStatus {
+        self.status
+    }
+
+    /// Perform single step of search.
+    ///
+    /// # Returns
+    /// `Some` with search result if search has completed or `None` otherwise.
+    #[allow(clippy::type_complexity)]
+    fn expand(&mut self, mesh: &NavMesh) -> Option<Option<(Vec<usize>, Scalar)>> {
+        let restart = self
+            .search
+            .as_ref()
+            .map(|search| search.mesh != mesh.id())
+            .unwrap_or(true);
+        if restart {
+            if self.from.same_as(self.to) {
+                return Some(None);
+            }
+            let start = mesh.find_closest_triangle(self.from, self.query);
+            let end = mesh.find_closest_triangle(self.to, self.query);
+            let (start, end) = match (start, end) {
+                (Some(start), Some(end)) => (start, end),
+                _ => return Some(None),
+            };
+            if mesh.is_triangle_blocked(start) || mesh.is_triangle_blocked(end) {
+                return Some(None);
+            }
+            let mut open = BinaryHeap::new();
+            open.push(OpenNode(0.0, start));
+            let mut costs = HashMap::new();
+            costs.insert(start, 0.0);
+            self.search = Some(NavQuerySearch {
+                mesh: mesh.id(),
+                end,
+                open,
+                costs,
+                parents: Default::default(),
+            });
+            return None;
+        }
+        let search = self.search.as_mut().unwrap();
+        let OpenNode(cost, current) = match search.open.pop() {
+            Some(node) => node,
+            None => return Some(None),
+        };
+        if current == search.end {
+            let mut path = vec![current];
+            let mut node = current;
+            while let Some(parent) = search.parents.get(&node) {
+                path.push(*parent);
+                node = *parent;
+            }
+            path.reverse();
+            return Some(Some((path, cost)));
+        }
+        if cost
+            > search
+                .costs
+                .get(&current)
+                .copied()
+                .unwrap_or(Scalar::INFINITY)
+        {
+            return None;
+        }
+        for next in mesh.triangle_neighbors(current) {
+            if let Some(weight) = mesh.connection_cost(current, next) {
+                let next_cost = cost + weight;
+                if next_cost < search.costs.get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                    search.costs.insert(next, next_cost);
+                    search.parents.insert(next, current);
+                    search.open.push(OpenNode(next_cost, next));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl NavMesh {
+    /// Start path search that is performed in steps with `NavPathSearch::step`.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// Started search.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let mut search = mesh.start_search(
+    ///     (0.0, 1.0, 0.0).into(),
+    ///     (1.5, 0.25, 0.5).into(),
+    ///     NavQuery::Accuracy,
+    ///     NavPathMode::MidPoints,
+    /// );
+    /// while !search.is_finished() {
+    ///     search.step(&mesh, 1);
+    /// }
+    /// assert!(matches!(search.take_result(), NavQueryStatus::Found(_)));
+    /// ```
+    pub fn start_search(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> NavPathSearch {
+        let mut result = NavPathSearch::new(from, to, query, mode);
+        result.step(self, 1);
+        result
+    }
+}
+
+/// Queue of path queries processed over multiple updates within given budget, so that spikes of
+/// many path requests are spread across frames.
+///
+/// Searches use the same triangle costs as `NavMesh::find_path`.
+#[derive(Debug, Default)]
+pub struct NavQueryPool {
+    queue: VecDeque<NavQueryID>,
+    requests: HashMap<NavQueryID, NavPathSearch>,
+    results: HashMap<NavQueryID, NavQueryStatus>,
+}
+
+impl NavQueryPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request path between two points.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// Query handle used to get its status.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 1.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 1.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    ///
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let mut pool = NavQueryPool::new();
+    /// let id = pool.request(
+    ///     (0.0, 1.0, 0.0).into(),
+    ///     (1.5, 0.25, 0.5).into(),
+    ///     NavQuery::Accuracy,
+    ///     NavPathMode::MidPoints,
+    /// );
+    /// while pool.status(id).unwrap().is_pending() {
+    ///     pool.update(&mesh, NavQueryBudget::Expansions(1));
+    /// }
+    /// assert!(matches!(pool.take(id), Some(NavQueryStatus::Found(_))));
+    /// ```
+    pub fn request(
+        &mut self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> NavQueryID {
+        let id = NavQueryID::new();
+        self.queue.push_back(id);
+        self.requests
+            .insert(id, NavPathSearch::new(from, to, query, mode));
+        id
+    }
+
+    /// Cancel query and forget its result.
+    ///
+    /// # Returns
+    /// True if query existed.
+    pub fn cancel(&mut self, id: NavQueryID) -> bool {
+        if self.requests.remove(&id).is_some() {
+            self.queue.retain(|other| *other != id);
+            true
+        } else {
+            self.results.remove(&id).is_some()
+        }
+    }
+
+    /// Get query status.
+    ///
+    /// # Returns
+    /// `Some` with status or `None` if query does not exist.
+    pub fn status(&self, id: NavQueryID) -> Option<&NavQueryStatus> {
+        if self.requests.contains_key(&id) {
+            Some(&NavQueryStatus::Pending)
+        } else {
+            self.results.get(&id)
+        }
+    }
+
+    /// Take result of completed query, removing it from pool.
+    ///
+    /// # Returns
+    /// `Some` with `NavQueryStatus::Found` or `NavQueryStatus::NotFound` status, or `None` if
+    /// query is still pending or does not exist.
+    pub fn take(&mut self, id: NavQueryID) -> Option<NavQueryStatus> {
+        self.results.remove(&id)
+    }
+
+    /// Number of queries waiting to be completed.
+    #[inline]
+    pub fn pending_count(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Number of completed queries that were not taken yet.
+    #[inline]
+    pub fn completed_count(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Process pending queries in order of requests, until budget is exhausted.
+    ///
+    /// Searches started on different nav mesh are restarted.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `budget` - amount of work allowed in this update.
+    ///
+    /// # Returns
+    /// Number of triangles expanded during this update.
+    pub fn update(&mut self, mesh: &NavMesh, budget: NavQueryBudget) -> usize {
+        let timer = budget.timer();
+        let mut expansions = 0;
+        let exhausted = |expansions: usize| match budget {
+            NavQueryBudget::Expansions(limit) => expansions >= limit,
+            NavQueryBudget::Time(limit) => timer.is_some_and(|timer| timer.elapsed() >= limit),
+        };
+        while let Some(id) = self.queue.front().copied() {
+            if exhausted(expansions) {
+                break;
+            }
+            let search = self.requests.get_mut(&id).unwrap();
+            while !search.is_finished() && !exhausted(expansions) {
+                expansions += search.step(mesh, 1);
+            }
+            if !search.is_finished() {
+                break;
+            }
+            self.queue.pop_front();
+            let search = self.requests.remove(&id).unwrap();
+            self.results.insert(id, search.take_result());
+        }
+        expansions
+    }
+}
@@ -0,0 +1,494 @@
+use crate::{
+    nav_binary::{checksum, BinaryReader, BinaryWriter},
+    nav_stats::NavTimer,
+    Error, NavMesh, NavPathMode, NavQuery, NavResult, NavStats, NavVec3, Scalar,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, time::Duration};
+
+const RECORDING_MAGIC: &[u8; 4] = b"NAVR";
+const RECORDING_VERSION: u16 = 2;
+
+/// Nav mesh query recorded by `NavRecorder`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavRecordedQuery {
+    /// `NavMesh::find_path` query.
+    Path {
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    },
+    /// `NavMesh::find_path_triangles` query.
+    Triangles { from: usize, to: usize },
+}
+
+impl NavRecordedQuery {
+    /// Perform query on nav mesh.
+    pub fn execute(&self, mesh: &NavMesh) -> NavRecordedResult {
+        match *self {
+            Self::Path {
+                from,
+                to,
+                query,
+                mode,
+            } => NavRecordedResult::Path(mesh.find_path(from, to, query, mode)),
+            Self::Triangles { from, to } => {
+                NavRecordedResult::Triangles(mesh.find_path_triangles(from, to))
+            }
+        }
+    }
+}
+
+/// Result of recorded nav mesh query.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavRecordedResult {
+    /// Path points.
+    Path(Option<Vec<NavVec3>>),
+    /// Path triangles and path cost.
+    Triangles(Option<(Vec<usize>, Scalar)>),
+}
+
+impl NavRecordedResult {
+    /// Tells if results are the same, with points and costs compared approximately, so results
+    /// recorded with other `Scalar` size can be compared too.
+    pub fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Path(a), Self::Path(b)) => match (a, b) {
+                (Some(a), Some(b)) => {
+                    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.same_as(*b))
+                }
+                (None, None) => true,
+                _ => false,
+            },
+            (Self::Triangles(a), Self::Triangles(b)) => match (a, b) {
+                (Some((a, x)), Some((b, y))) => {
+                    a == b && (x - y).abs() <= 1.0e-4 * x.abs().max(y.abs()).max(1.0)
+                }
+                (None, None) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Tells if query found path.
+    pub fn is_found(&self) -> bool {
+        match self {
+            Self::Path(result) => result.is_some(),
+            Self::Triangles(result) => result.is_some(),
+        }
+    }
+}
+
+/// Single recorded query with its result and search statistics.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavRecord {
+    pub query: NavRecordedQuery,
+    pub result: NavRecordedResult,
+    /// Statistics of triangles path search, with time spent on whole query.
+    pub stats: NavStats,
+}
+
+/// Outcome of replaying single recorded query, see `NavRecorder::replay`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavReplayResult {
+    /// Index of record.
+    pub index: usize,
+    /// Result of query performed again.
+    pub result: NavRecordedResult,
+    /// Tells if result is the same as recorded one.
+    pub matches: bool,
+}
+
+/// Records nav mesh queries together with their results and statistics, to save them into
+/// compact trace file attached to bug reports and replay them offline against the same nav mesh.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (2.0, 0.0, 0.0).into(), // 2
+///     (0.0, 1.0, 0.0).into(), // 3
+///     (1.0, 1.0, 0.0).into(), // 4
+///     (2.0, 1.0, 0.0).into(), // 5
+/// ];
+/// let triangles = vec![
+///     (0, 1, 4).into(), // 0
+///     (4, 3, 0).into(), // 1
+///     (1, 2, 5).into(), // 2
+///     (5, 4, 1).into(), // 3
+/// ];
+///
+/// let mesh = NavMesh::new(vertices, triangles).unwrap();
+/// let mut recorder = NavRecorder::new(&mesh);
+/// recorder.find_path(
+///     &mesh,
+///     (0.5, 0.5, 0.0).into(),
+///     (1.5, 0.5, 0.0).into(),
+///     NavQuery::Accuracy,
+///     NavPathMode::Accuracy,
+/// );
+/// recorder.find_path_triangles(&mesh, 1, 2);
+///
+/// let bytes = recorder.to_bytes();
+/// let loaded = NavRecorder::from_bytes(&bytes).unwrap();
+/// assert_eq!(loaded.records().count(), 2);
+/// assert!(loaded.matches_mesh(&mesh));
+/// assert!(loaded.replay(&mesh).iter().all(|result| result.matches));
+/// ```
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavRecorder {
+    fingerprint: u64,
+    records: VecDeque<NavRecord>,
+    capacity: Option<usize>,
+}
+
+impl NavRecorder {
+    /// Create recorder for queries performed on given nav mesh.
+    pub fn new(mesh: &NavMesh) -> Self {
+        Self {
+            fingerprint: mesh.fingerprint(),
+            records: Default::default(),
+            capacity: None,
+        }
+    }
+
+    /// Create recorder keeping only given number of most recent records, so it can stay enabled
+    /// in shipped games.
+    pub fn with_capacity(mesh: &NavMesh, capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity.max(1)),
+            ..Self::new(mesh)
+        }
+    }
+
+    /// Fingerprint of nav mesh queries were recorded on, see `NavMesh::fingerprint`.
+    #[inline]
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Tells if nav mesh has the same geometry as one queries were recorded on.
+    #[inline]
+    pub fn matches_mesh(&self, mesh: &NavMesh) -> bool {
+        mesh.fingerprint() == self.fingerprint
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    #[inline]
+    pub fn records(&self) -> impl Iterator<Item = &NavRecord> {
+        self.records.iter()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Add record, dropping the oldest one if recorder is full.
+    pub fn record(&mut self, record: NavRecord) {
+        if let Some(capacity) = self.capacity {
+            while self.records.len() >= capacity {
+                self.records.pop_front();
+            }
+        }
+        self.records.push_back(record);
+    }
+
+    /// Find path like `NavMesh::find_path` does, recording query.
+    pub fn find_path(
+        &mut self,
+        mesh: &NavMesh,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<Vec<NavVec3>> {
+        let timer = NavTimer::start();
+        let mut stats = NavStats::default();
+        let result = mesh.find_path_with(from, to, query, mode, |start, end| {
+            let (result, search) = mesh.find_path_triangles_with_stats(start, end);
+            stats = search;
+            result
+        });
+        stats.found = result.is_some();
+        stats.time = timer.elapsed();
+        self.record(NavRecord {
+            query: NavRecordedQuery::Path {
+                from,
+                to,
+                query,
+                mode,
+            },
+            result: NavRecordedResult::Path(result.clone()),
+            stats,
+        });
+        result
+    }
+
+    /// Find path triangles like `NavMesh::find_path_triangles` does, recording query.
+    pub fn find_path_triangles(
+        &mut self,
+        mesh: &NavMesh,
+        from: usize,
+        to: usize,
+    ) -> Option<(Vec<usize>, Scalar)> {
+        let (result, stats) = mesh.find_path_triangles_with_stats(from, to);
+        self.record(NavRecord {
+            query: NavRecordedQuery::Triangles { from, to },
+            result: NavRecordedResult::Triangles(result.clone()),
+            stats,
+        });
+        result
+    }
+
+    /// Perform recorded queries again on nav mesh, comparing results with recorded ones.
+    ///
+    /// Results are expected to match only on nav mesh with the same geometry (see
+    /// `matches_mesh`) and the same obstacles and gates states as during recording.
+    pub fn replay(&self, mesh: &NavMesh) -> Vec<NavReplayResult> {
+        self.records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                let result = record.query.execute(mesh);
+                NavReplayResult {
+                    index,
+                    matches: result.same_as(&record.result),
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    /// Serialize records into compact versioned binary format.
+    ///
+    /// # Returns
+    /// Bytes of serialized records.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let scalar_size = std::mem::size_of::<Scalar>();
+        let mut payload = BinaryWriter::default();
+        payload.write_u32(self.capacity.unwrap_or_default() as u32);
+        payload.write_u32(self.records.len() as u32);
+        for record in &self.records {
+            match record.query {
+                NavRecordedQuery::Path {
+                    from,
+                    to,
+                    query,
+                    mode,
+                } => {
+                    payload.write_u8(0);
+                    payload.write_vec3(from);
+                    payload.write_vec3(to);
+                    match query {
+                        NavQuery::Accuracy => payload.write_u8(0),
+                        NavQuery::Closest => payload.write_u8(1),
+                        NavQuery::ClosestFirst => payload.write_u8(2),
+                        NavQuery::Layered(step) => {
+                            payload.write_u8(3);
+                            payload.write_scalar(step);
+                        }
+                    }
+                    match mode {
+                        NavPathMode::Accuracy => payload.write_u8(0),
+                        NavPathMode::MidPoints => payload.write_u8(1),
+                        NavPathMode::Centerline(weight) => {
+                            payload.write_u8(2);
+                            payload.write_scalar(weight);
+                        }
+                    }
+                }
+                NavRecordedQuery::Triangles { from, to } => {
+                    payload.write_u8(1);
+                    payload.write_u32(from as u32);
+                    payload.write_u32(to as u32);
+                }
+            }
+            match &record.result {
+                NavRecordedResult::Path(Some(points)) => {
+                    payload.write_u8(1);
+                    payload.write_u32(points.len() as u32);
+                    for point in points {
+                        payload.write_vec3(*point);
+                    }
+                }
+                NavRecordedResult::Triangles(Some((triangles, cost))) => {
+                    payload.write_u8(1);
+                    payload.write_u32(triangles.len() as u32);
+                    for triangle in triangles {
+                        payload.write_u32(*triangle as u32);
+                    }
+                    payload.write_scalar(*cost);
+                }
+                _ => payload.write_u8(0),
+            }
+            let stats = &record.stats;
+            payload.write_u32(stats.from as u32);
+            payload.write_u32(stats.to as u32);
+            payload.write_u32(stats.expansions as u32);
+            payload.write_u32(stats.edges_visited as u32);
+            payload.write_u32(stats.open_peak as u32);
+            payload.write_u64(stats.time.as_nanos() as u64);
+            payload.write_u8(stats.cache_hit as u8 | (stats.found as u8) << 1);
+        }
+        let payload = payload.into_inner();
+
+        let mut result = BinaryWriter::with_capacity(payload.len() + 32);
+        result.write_bytes(RECORDING_MAGIC);
+        result.write_u16(RECORDING_VERSION);
+        result.write_u8(scalar_size as u8);
+        result.write_u8(0);
+        result.write_u64(self.fingerprint);
+        result.write_u64(payload.len() as u64);
+        result.write_u64(checksum(&payload));
+        result.write_bytes(&payload);
+        result.into_inner()
+    }
+
+    /// Deserialize records from binary format produced by `NavRecorder::to_bytes`.
+    ///
+    /// # Arguments
+    /// * `bytes` - serialized records bytes.
+    ///
+    /// # Returns
+    /// `Ok` with recorder or `Err` with `Error::CouldNotDeserializeNavMesh` if data is invalid,
+    /// corrupted or has unsupported version.
+    pub fn from_bytes(bytes: &[u8]) -> NavResult<Self> {
+        let invalid = |message: &str| Error::CouldNotDeserializeNavMesh(message.to_owned());
+        let mut header = BinaryReader::new(bytes);
+        if header.read_bytes(4)? != RECORDING_MAGIC {
+            return Err(invalid("Data is not a nav queries recording"));
+        }
+        let version = header.read_u16()?;
+        if version == 0 || version > RECORDING_VERSION {
+            return Err(Error::CouldNotDeserializeNavMesh(format!(
+                "Unsupported nav queries recording version: {}",
+                version
+            )));
+        }
+        let scalar_size = header.read_u8()? as usize;
+        header.read_u8()?;
+        let fingerprint = header.read_u64()?;
+        let size = header.read_u64()? as usize;
+        let sum = header.read_u64()?;
+        let payload = header.read_bytes(size)?;
+        if !header.is_empty() {
+            return Err(invalid("Unexpected data after nav queries recording"));
+        }
+        if checksum(payload) != sum {
+            return Err(invalid("Checksum mismatch"));
+        }
+
+        let mut reader = BinaryReader::new(payload);
+        reader.set_scalar_size(scalar_size)?;
+        let scalar_size = reader.scalar_size();
+        let capacity = match reader.read_u32()? {
+            0 => None,
+            capacity => Some(capacity as usize),
+        };
+        let count = reader.read_count(27)?;
+        let mut records = VecDeque::with_capacity(count);
+        for _ in 0..count {
+            let query = match reader.read_u8()? {
+                0 => {
+                    let from = reader.read_vec3()?;
+                    let to = reader.read_vec3()?;
+                    let query = match reader.read_u8()? {
+                        0 => NavQuery::Accuracy,
+                        1 => NavQuery::Closest,
+                        2 => NavQuery::ClosestFirst,
+                        3 => NavQuery::Layered(reader.read_scalar()?),
+                        _ => return Err(invalid("Unsupported query quality")),
+                    };
+                    let mode = match reader.read_u8()? {
+                        0 => NavPathMode::Accuracy,
+                        1 => NavPathMode::MidPoints,
+                        2 => NavPathMode::Centerline(reader.read_scalar()?),
+                        _ => return Err(invalid("Unsupported path mode")),
+                    };
+                    NavRecordedQuery::Path {
+                        from,
+                        to,
+                        query,
+                        mode,
+                    }
+                }
+                1 => NavRecordedQuery::Triangles {
+                    from: reader.read_u32()? as usize,
+                    to: reader.read_u32()? as usize,
+                },
+                _ => return Err(invalid("Unsupported query kind")),
+            };
+            let found = reader.read_u8()? != 0;
+            let result = match query {
+                NavRecordedQuery::Path { .. } => NavRecordedResult::Path(if found {
+                    let count = reader.read_count(scalar_size * 3)?;
+                    Some(
+                        (0..count)
+                            .map(|_| reader.read_vec3())
+                            .collect::<NavResult<Vec<_>>>()?,
+                    )
+                } else {
+                    None
+                }),
+                NavRecordedQuery::Triangles { .. } => NavRecordedResult::Triangles(if found {
+                    let count = reader.read_count(4)?;
+                    let triangles = (0..count)
+                        .map(|_| Ok(reader.read_u32()? as usize))
+                        .collect::<NavResult<Vec<_>>>()?;
+                    Some((triangles, reader.read_scalar()?))
+                } else {
+                    None
+                }),
+            };
+            let from = reader.read_u32()? as usize;
+            let to = reader.read_u32()? as usize;
+            let expansions = reader.read_u32()? as usize;
+            let edges_visited = reader.read_u32()? as usize;
+            let open_peak = if version >= 2 {
+                reader.read_u32()? as usize
+            } else {
+                0
+            };
+            let time = Duration::from_nanos(reader.read_u64()?);
+            let flags = reader.read_u8()?;
+            records.push_back(NavRecord {
+                query,
+                result,
+                stats: NavStats {
+                    from,
+                    to,
+                    expansions,
+                    edges_visited,
+                    open_peak,
+                    time,
+                    cache_hit: flags & 1 != 0,
+                    found: flags & 2 != 0,
+                },
+            });
+        }
+        if !reader.is_empty() {
+            return Err(invalid("Unexpected data after nav queries recording"));
+        }
+        Ok(Self {
+            fingerprint,
+            records,
+            capacity,
+        })
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{Error, NavCrowd, NavMesh, NavObstacle, NavObstacleID, NavResult, Scalar};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Runtime properties of single nav mesh area that gameplay can change.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavAreaState {
+    pub cost: Scalar,
+    pub area_type: u8,
+    pub flags: u16,
+}
+
+/// Snapshot of everything that changes on nav mesh while game runs (obstacles, gates states,
+/// areas properties, vertex attributes) together with crowds moving on it (agents with their
+/// paths and velocities), for save games and deterministic replays.
+///
+/// Nav mesh geometry is not part of state, it gets restored onto nav mesh loaded from the same
+/// source state was captured from.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (2.0, 0.0, 0.0).into(), // 2
+///     (0.0, 1.0, 0.0).into(), // 3
+///     (1.0, 1.0, 0.0).into(), // 4
+///     (2.0, 1.0, 0.0).into(), // 5
+/// ];
+/// let triangles = vec![
+///     (0, 1, 4).into(), // 0
+///     (4, 3, 0).into(), // 1
+///     (1, 2, 5).into(), // 2
+///     (5, 4, 1).into(), // 3
+/// ];
+///
+/// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+/// let clean = mesh.clone();
+/// mesh.add_gate("door", vec![2, 3], true).unwrap();
+/// mesh.set_gate("door", false);
+/// let mut crowd = NavCrowd::new();
+/// let id = crowd.add_agent(NavCrowdAgent::new((0.5, 0.5, 0.0).into(), 0.1, 1.0, 10.0));
+/// crowd.set_target(id, (1.5, 0.5, 0.0).into());
+/// let state = NavRuntimeState::capture(&mesh, [&crowd]);
+///
+/// mesh.set_gate("door", true);
+/// let mut restored = state.restore(&mut mesh).unwrap();
+/// assert_eq!(mesh.is_gate_open("door"), Some(false));
+/// let crowd = restored.remove(0);
+/// assert_eq!(crowd.agent(id).unwrap().target(), Some((1.5, 0.5, 0.0).into()));
+///
+/// // state does not carry gates definitions, only their states.
+/// let mut other = clean;
+/// state.restore(&mut other).unwrap();
+/// assert_eq!(other.is_gate_open("door"), None);
+/// ```
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavRuntimeState {
+    /// Number of nav mesh triangles, used to tell if state matches nav mesh.
+    pub triangles_count: usize,
+    /// Obstacles with their identifiers.
+    pub obstacles: Vec<(NavObstacleID, NavObstacle)>,
+    /// Gates names and whether they are open.
+    pub gates: Vec<(String, bool)>,
+    /// Properties of every nav mesh area.
+    pub areas: Vec<NavAreaState>,
+    /// Vertex attributes values.
+    pub vertex_attributes: Vec<(String, Vec<Scalar>)>,
+    /// Crowds moving on nav mesh.
+    pub crowds: Vec<NavCrowd>,
+}
+
+impl NavRuntimeState {
+    /// Capture runtime state of nav mesh and crowds moving on it.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh.
+    /// * `crowds` - crowds moving on nav mesh.
+    pub fn capture<'a, I>(mesh: &NavMesh, crowds: I) -> Self
+    where
+        I: IntoIterator<Item = &'a NavCrowd>,
+    {
+        // lists are sorted, so the same state always gets serialized the same way.
+        let mut obstacles = mesh
+            .obstacles()
+            .filter_map(|id| Some((id, mesh.obstacle(id)?.clone())))
+            .collect::<Vec<_>>();
+        obstacles.sort_by_key(|(id, _)| *id);
+        let mut gates = mesh
+            .gates()
+            .filter_map(|name| Some((name.to_owned(), mesh.is_gate_open(name)?)))
+            .collect::<Vec<_>>();
+        gates.sort();
+        let mut vertex_attributes = mesh
+            .vertex_attributes_map()
+            .iter()
+            .map(|(name, values)| (name.clone(), values.clone()))
+            .collect::<Vec<_>>();
+        vertex_attributes.sort_by(|a, b| a.0.cmp(&b.0));
+        Self {
+            triangles_count: mesh.triangles().len(),
+            obstacles,
+            gates,
+            areas: mesh
+                .areas()
+                .iter()
+                .map(|area| NavAreaState {
+                    cost: area.cost,
+                    area_type: area.area_type,
+                    flags: area.flags,
+                })
+                .collect(),
+            vertex_attributes,
+            crowds: crowds.into_iter().cloned().collect(),
+        }
+    }
+
+    /// Restore runtime state onto nav mesh, replacing all its obstacles. Gates that nav mesh does
+    /// not have are skipped.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh with the same geometry as one state was captured from.
+    ///
+    /// # Returns
+    /// `Ok` with restored crowds or `Err` with `Error::RuntimeStateDoesNotMatchNavMesh` or
+    /// `Error::VertexAttributeCountDoesNotMatchVertices` if state does not match nav mesh, in
+    /// which case nav mesh is left unchanged.
+    pub fn restore(&self, mesh: &mut NavMesh) -> NavResult<Vec<NavCrowd>> {
+        if self.triangles_count != mesh.triangles().len()
+            || self.areas.len() != mesh.triangles().len()
+        {
+            return Err(Error::RuntimeStateDoesNotMatchNavMesh(
+                self.triangles_count,
+                mesh.triangles().len(),
+            ));
+        }
+        if let Some((_, values)) = self
+            .vertex_attributes
+            .iter()
+            .find(|(_, values)| values.len() != mesh.vertices().len())
+        {
+            return Err(Error::VertexAttributeCountDoesNotMatchVertices(
+                values.len(),
+                mesh.vertices().len(),
+            ));
+        }
+        let old = mesh.obstacles().collect::<Vec<_>>();
+        for id in old {
+            mesh.remove_obstacle(id);
+        }
+        for (id, obstacle) in &self.obstacles {
+            mesh.insert_obstacle(*id, obstacle.clone());
+        }
+        for (name, open) in &self.gates {
+            mesh.set_gate(name, *open);
+        }
+        for (index, state) in self.areas.iter().enumerate() {
+            let area = mesh.areas()[index].clone();
+            if area.cost != state.cost {
+                mesh.set_area_cost(index, state.cost);
+            }
+            if area.area_type != state.area_type {
+                mesh.set_area_type(index, state.area_type);
+            }
+            if area.flags != state.flags {
+                mesh.set_area_flags(index, state.flags);
+            }
+        }
+        *mesh.vertex_attributes_map_mut() = self
+            .vertex_attributes
+            .iter()
+            .cloned()
+            .collect::<HashMap<_, _>>();
+        Ok(self.crowds.clone())
+    }
+}
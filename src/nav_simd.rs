@@ -0,0 +1,170 @@
+//! SSE implementations of `NavVec3` batch operations, processing four vectors at once.
+//!
+//! Functions are `unsafe` because they enable `sse` target feature, but SSE is part of x86_64
+//! baseline, so callers can always rely on it being available.
+
+use crate::{NavVec3, ZERO_TRESHOLD};
+use core::arch::x86_64::*;
+
+/// Four vectors stored as separate lanes of their components.
+#[derive(Copy, Clone)]
+struct Lanes {
+    x: __m128,
+    y: __m128,
+    z: __m128,
+}
+
+impl Lanes {
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn load(v: [NavVec3; 4]) -> Self {
+        Self {
+            x: _mm_set_ps(v[3].x, v[2].x, v[1].x, v[0].x),
+            y: _mm_set_ps(v[3].y, v[2].y, v[1].y, v[0].y),
+            z: _mm_set_ps(v[3].z, v[2].z, v[1].z, v[0].z),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn splat(v: NavVec3) -> Self {
+        Self {
+            x: _mm_set1_ps(v.x),
+            y: _mm_set1_ps(v.y),
+            z: _mm_set1_ps(v.z),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn add(self, other: Self) -> Self {
+        Self {
+            x: _mm_add_ps(self.x, other.x),
+            y: _mm_add_ps(self.y, other.y),
+            z: _mm_add_ps(self.z, other.z),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn sub(self, other: Self) -> Self {
+        Self {
+            x: _mm_sub_ps(self.x, other.x),
+            y: _mm_sub_ps(self.y, other.y),
+            z: _mm_sub_ps(self.z, other.z),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn scale(self, value: __m128) -> Self {
+        Self {
+            x: _mm_mul_ps(self.x, value),
+            y: _mm_mul_ps(self.y, value),
+            z: _mm_mul_ps(self.z, value),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn dot(self, other: Self) -> __m128 {
+        _mm_add_ps(
+            _mm_add_ps(_mm_mul_ps(self.x, other.x), _mm_mul_ps(self.y, other.y)),
+            _mm_mul_ps(self.z, other.z),
+        )
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn cross(self, other: Self) -> Self {
+        Self {
+            x: _mm_sub_ps(_mm_mul_ps(self.y, other.z), _mm_mul_ps(self.z, other.y)),
+            y: _mm_sub_ps(_mm_mul_ps(self.z, other.x), _mm_mul_ps(self.x, other.z)),
+            z: _mm_sub_ps(_mm_mul_ps(self.x, other.y), _mm_mul_ps(self.y, other.x)),
+        }
+    }
+
+    /// Same as `NavVec3::normalize`: vectors shorter than zero treshold become zero.
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn normalize(self) -> Self {
+        let len = _mm_sqrt_ps(self.dot(self));
+        let mask = _mm_cmpge_ps(len, _mm_set1_ps(ZERO_TRESHOLD));
+        Self {
+            x: _mm_and_ps(_mm_div_ps(self.x, len), mask),
+            y: _mm_and_ps(_mm_div_ps(self.y, len), mask),
+            z: _mm_and_ps(_mm_div_ps(self.z, len), mask),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse")]
+    unsafe fn store(self) -> [NavVec3; 4] {
+        let mut x = [0.0; 4];
+        let mut y = [0.0; 4];
+        let mut z = [0.0; 4];
+        // SAFETY: arrays have exactly four elements, unaligned store is used.
+        unsafe {
+            _mm_storeu_ps(x.as_mut_ptr(), self.x);
+            _mm_storeu_ps(y.as_mut_ptr(), self.y);
+            _mm_storeu_ps(z.as_mut_ptr(), self.z);
+        }
+        [
+            NavVec3::new(x[0], y[0], z[0]),
+            NavVec3::new(x[1], y[1], z[1]),
+            NavVec3::new(x[2], y[2], z[2]),
+            NavVec3::new(x[3], y[3], z[3]),
+        ]
+    }
+}
+
+#[target_feature(enable = "sse")]
+pub(crate) unsafe fn dot4(a: [NavVec3; 4], b: [NavVec3; 4]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    // SAFETY: array has exactly four elements, unaligned store is used.
+    unsafe { _mm_storeu_ps(result.as_mut_ptr(), Lanes::load(a).dot(Lanes::load(b))) };
+    result
+}
+
+/// Same as `NavVec3::raycast_triangle` performed for four triangles.
+#[cfg(not(feature = "robust"))]
+#[target_feature(enable = "sse")]
+pub(crate) unsafe fn raycast_triangle4(
+    from: NavVec3,
+    to: NavVec3,
+    a: [NavVec3; 4],
+    b: [NavVec3; 4],
+    c: [NavVec3; 4],
+) -> [Option<NavVec3>; 4] {
+    let origin = Lanes::splat(from);
+    let dir = Lanes::splat((to - from).normalize());
+    let length = _mm_set1_ps((to - from).magnitude());
+    let treshold = _mm_set1_ps(ZERO_TRESHOLD);
+    let a = Lanes::load(a);
+    let b = Lanes::load(b);
+    let c = Lanes::load(c);
+    let tab = b.sub(a).normalize();
+    let tbc = c.sub(b).normalize();
+    let tca = a.sub(c).normalize();
+    let n = tab.cross(tbc).normalize();
+    let denom = n.dot(dir);
+    let mut mask = _mm_cmpgt_ps(_mm_andnot_ps(_mm_set1_ps(-0.0), denom), treshold);
+    let t = _mm_div_ps(a.sub(origin).dot(n), denom);
+    mask = _mm_and_ps(mask, _mm_cmpge_ps(t, _mm_setzero_ps()));
+    mask = _mm_and_ps(mask, _mm_cmple_ps(t, length));
+    let contact = origin.add(dir.scale(t));
+    // contact is above plane with negated edge normal.
+    for (edge, point) in [(tab, a), (tbc, b), (tca, c)] {
+        let distance = edge.cross(n).dot(contact.sub(point));
+        mask = _mm_and_ps(mask, _mm_cmplt_ps(distance, treshold));
+    }
+    let bits = _mm_movemask_ps(mask);
+    let contact = contact.store();
+    let mut result = [None; 4];
+    for (i, item) in result.iter_mut().enumerate() {
+        if bits & (1 << i) != 0 {
+            *item = Some(contact[i]);
+        }
+    }
+    result
+}
@@ -0,0 +1,342 @@
+use crate::{
+    nav_open_node::OpenNode, NavGridCornerCutting, NavGridNeighborhood, Scalar, SCALAR_MAX,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+use typid::ID;
+
+/// Nav sparse grid identifier.
+pub type NavSparseGridID = ID<NavSparseGrid>;
+
+/// Dense square block of sparse grid cells.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct NavSparseGridChunk {
+    // cells costs, `None` for not walkable ones.
+    cells: Vec<Option<Scalar>>,
+    walkable: usize,
+}
+
+/// Grid with unbounded (possibly negative) cells coordinates, for procedural or infinite worlds.
+///
+/// Cells are stored in dense square chunks allocated lazily when first cell inside them gets
+/// marked walkable and released when their last walkable cell gets cleared, so only explored
+/// parts of the world take memory. Unlike `NavFreeGrid` cells are not connected explicitly but
+/// by set of allowed moves, like in `NavGrid`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavSparseGrid {
+    id: NavSparseGridID,
+    chunk_size: usize,
+    neighborhood: NavGridNeighborhood,
+    chunks: HashMap<(isize, isize), NavSparseGridChunk>,
+    // lowest cost ever set, keeps search heuristic admissible.
+    min_cost: Scalar,
+}
+
+impl Default for NavSparseGrid {
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl NavSparseGrid {
+    /// Create empty sparse grid with orthogonal moves.
+    ///
+    /// # Arguments
+    /// * `chunk_size` - number of columns and rows of single chunk.
+    pub fn new(chunk_size: usize) -> Self {
+        Self::with_neighborhood(chunk_size, NavGridNeighborhood::Orthogonal)
+    }
+
+    /// Create empty sparse grid with given set of allowed moves.
+    ///
+    /// # Arguments
+    /// * `chunk_size` - number of columns and rows of single chunk.
+    /// * `neighborhood` - set of allowed moves.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let mut grid = NavSparseGrid::with_neighborhood(
+    ///     16,
+    ///     NavGridNeighborhood::Diagonal(NavGridCornerCutting::Forbid),
+    /// );
+    /// grid.paint_walkable((-1_000_000, -1), (-999_998, 1), true);
+    /// assert_eq!(grid.chunks_count(), 2);
+    /// let path = grid.find_path((-1_000_000, -1), (-999_998, 1)).unwrap();
+    /// assert_eq!(path, vec![(-1_000_000, -1), (-999_999, 0), (-999_998, 1)]);
+    /// ```
+    pub fn with_neighborhood(chunk_size: usize, neighborhood: NavGridNeighborhood) -> Self {
+        Self {
+            id: NavSparseGridID::new(),
+            chunk_size: chunk_size.max(1),
+            neighborhood,
+            chunks: Default::default(),
+            min_cost: 1.0,
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NavSparseGridID {
+        self.id
+    }
+
+    #[inline]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    #[inline]
+    pub fn neighborhood(&self) -> &NavGridNeighborhood {
+        &self.neighborhood
+    }
+
+    /// Number of allocated chunks.
+    #[inline]
+    pub fn chunks_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Iterator over coordinates of allocated chunks.
+    #[inline]
+    pub fn chunks(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Number of walkable cells.
+    pub fn cells_count(&self) -> usize {
+        self.chunks.values().map(|chunk| chunk.walkable).sum()
+    }
+
+    /// Iterator over coordinates of walkable cells, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        let size = self.chunk_size as isize;
+        self.chunks.iter().flat_map(move |(coord, chunk)| {
+            chunk
+                .cells
+                .iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.is_some())
+                .map(move |(index, _)| {
+                    (
+                        coord.0 * size + index as isize % size,
+                        coord.1 * size + index as isize / size,
+                    )
+                })
+        })
+    }
+
+    /// Coordinate of chunk containing given cell.
+    #[inline]
+    pub fn chunk_coord(&self, col: isize, row: isize) -> (isize, isize) {
+        let size = self.chunk_size as isize;
+        (col.div_euclid(size), row.div_euclid(size))
+    }
+
+    #[inline]
+    pub fn is_walkable(&self, col: isize, row: isize) -> bool {
+        self.cell_cost(col, row).is_some()
+    }
+
+    /// Mark cell as walkable (with cost of 1) or not walkable.
+    ///
+    /// # Returns
+    /// Old walkable state of cell.
+    pub fn set_walkable(&mut self, col: isize, row: isize, walkable: bool) -> bool {
+        let (chunk_coord, index) = self.locate(col, row);
+        if walkable {
+            let count = self.chunk_size * self.chunk_size;
+            let chunk = self
+                .chunks
+                .entry(chunk_coord)
+                .or_insert_with(|| NavSparseGridChunk {
+                    cells: vec![None; count],
+                    walkable: 0,
+                });
+            if chunk.cells[index].is_some() {
+                return true;
+            }
+            chunk.cells[index] = Some(1.0);
+            chunk.walkable += 1;
+            false
+        } else {
+            let chunk = match self.chunks.get_mut(&chunk_coord) {
+                Some(chunk) => chunk,
+                None => return false,
+            };
+            if chunk.cells[index].take().is_none() {
+                return false;
+            }
+            chunk.walkable -= 1;
+            if chunk.walkable == 0 {
+                self.chunks.remove(&chunk_coord);
+            }
+            true
+        }
+    }
+
+    /// Mark all cells in rectangle spanned between two corner cells (both inclusive) as walkable
+    /// or not walkable.
+    ///
+    /// # Returns
+    /// Number of cells that changed their state.
+    pub fn paint_walkable(
+        &mut self,
+        from: (isize, isize),
+        to: (isize, isize),
+        walkable: bool,
+    ) -> usize {
+        let mut count = 0;
+        for row in from.1.min(to.1)..=from.1.max(to.1) {
+            for col in from.0.min(to.0)..=from.0.max(to.0) {
+                if self.set_walkable(col, row, walkable) != walkable {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Remove chunk with all its cells.
+    ///
+    /// # Returns
+    /// True if chunk was allocated.
+    pub fn clear_chunk(&mut self, chunk: (isize, isize)) -> bool {
+        self.chunks.remove(&chunk).is_some()
+    }
+
+    #[inline]
+    pub fn cell_cost(&self, col: isize, row: isize) -> Option<Scalar> {
+        let (chunk_coord, index) = self.locate(col, row);
+        self.chunks.get(&chunk_coord)?.cells[index]
+    }
+
+    /// Set traversal cost of walkable cell.
+    ///
+    /// # Returns
+    /// `Some` with old cost or `None` if cell is not walkable.
+    pub fn set_cell_cost(&mut self, col: isize, row: isize, cost: Scalar) -> Option<Scalar> {
+        let (chunk_coord, index) = self.locate(col, row);
+        let cell = self.chunks.get_mut(&chunk_coord)?.cells[index].as_mut()?;
+        let cost = cost.max(0.0);
+        self.min_cost = self.min_cost.min(cost);
+        Some(std::mem::replace(cell, cost))
+    }
+
+    /// Iterator over walkable neighbor cells.
+    pub fn neighbors(&self, col: isize, row: isize) -> impl Iterator<Item = (isize, isize)> + '_ {
+        let walkable = self.is_walkable(col, row);
+        self.neighborhood
+            .offsets(row.rem_euclid(2) as usize)
+            .into_iter()
+            .filter(move |_| walkable)
+            .filter(move |offset| *offset != (0, 0) && self.can_move((col, row), *offset))
+            .map(move |offset| (col + offset.0, row + offset.1))
+    }
+
+    /// Find cheapest path between cells, where moving between neighbor cells costs product of
+    /// their costs and move distance.
+    pub fn find_path(
+        &self,
+        from: (isize, isize),
+        to: (isize, isize),
+    ) -> Option<Vec<(isize, isize)>> {
+        self.find_path_custom(from, to, |_, _| true)
+    }
+
+    // filter params: first col-row, second col-row.
+    pub fn find_path_custom<F>(
+        &self,
+        from: (isize, isize),
+        to: (isize, isize),
+        mut filter: F,
+    ) -> Option<Vec<(isize, isize)>>
+    where
+        F: FnMut((isize, isize), (isize, isize)) -> bool,
+    {
+        if !self.is_walkable(from.0, from.1) || !self.is_walkable(to.0, to.1) {
+            return None;
+        }
+        let factor = self.min_cost * self.min_cost;
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut parents = HashMap::new();
+        open.push(OpenNode(0.0, from));
+        costs.insert(from, 0.0);
+        while let Some(OpenNode(_, current)) = open.pop() {
+            if current == to {
+                let mut path = vec![current];
+                let mut node = current;
+                while let Some(parent) = parents.get(&node) {
+                    path.push(*parent);
+                    node = *parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let current_cost = costs[&current];
+            let cost = self.cell_cost(current.0, current.1).unwrap_or(SCALAR_MAX);
+            for next in self.neighbors(current.0, current.1) {
+                if !filter(current, next) {
+                    continue;
+                }
+                let offset = (next.0 - current.0, next.1 - current.1);
+                let next_cost = current_cost
+                    + cost
+                        * self.cell_cost(next.0, next.1).unwrap_or(SCALAR_MAX)
+                        * self.neighborhood.distance(offset);
+                if next_cost < costs.get(&next).copied().unwrap_or(SCALAR_MAX) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, current);
+                    open.push(OpenNode(
+                        next_cost + self.heuristic(next, to) * factor,
+                        next,
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// Lower bound of moves distance between two cells.
+    fn heuristic(&self, a: (isize, isize), b: (isize, isize)) -> Scalar {
+        let dx = (a.0 - b.0).abs() as Scalar;
+        let dy = (a.1 - b.1).abs() as Scalar;
+        match self.neighborhood {
+            NavGridNeighborhood::Orthogonal => dx + dy,
+            NavGridNeighborhood::Diagonal(_) => {
+                dx.max(dy) + (std::f64::consts::SQRT_2 as Scalar - 1.0) * dx.min(dy)
+            }
+            NavGridNeighborhood::Hex | NavGridNeighborhood::Custom(_) => 0.0,
+        }
+    }
+
+    fn can_move(&self, (col, row): (isize, isize), offset: (isize, isize)) -> bool {
+        let (c, r) = (col + offset.0, row + offset.1);
+        if !self.is_walkable(c, r) {
+            return false;
+        }
+        match self.neighborhood {
+            NavGridNeighborhood::Diagonal(cutting) if offset.0 != 0 && offset.1 != 0 => {
+                let a = self.is_walkable(c, row);
+                let b = self.is_walkable(col, r);
+                match cutting {
+                    NavGridCornerCutting::Allow => true,
+                    NavGridCornerCutting::IfOneFree => a || b,
+                    NavGridCornerCutting::Forbid => a && b,
+                }
+            }
+            _ => true,
+        }
+    }
+
+    fn locate(&self, col: isize, row: isize) -> ((isize, isize), usize) {
+        let size = self.chunk_size as isize;
+        let chunk = (col.div_euclid(size), row.div_euclid(size));
+        let index = row.rem_euclid(size) * size + col.rem_euclid(size);
+        (chunk, index as usize)
+    }
+}
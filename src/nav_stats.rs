@@ -0,0 +1,153 @@
+//! Query statistics and global trace hook, for finding pathological queries in shipped games.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+type NavTraceHook = Arc<dyn Fn(&NavStats) + Send + Sync>;
+
+static TRACE_HOOK_SET: AtomicBool = AtomicBool::new(false);
+static TRACE_HOOK: RwLock<Option<NavTraceHook>> = RwLock::new(None);
+
+/// Clock measuring query time. `wasm32-unknown-unknown` target has no system clock, there
+/// measured time is always zero.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct NavTimer {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start: Instant,
+}
+
+impl NavTimer {
+    #[inline]
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            start: Instant::now(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            self.start.elapsed()
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Statistics of single triangles path search.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavStats {
+    /// Query triangle index from.
+    pub from: usize,
+    /// Query triangle index to.
+    pub to: usize,
+    /// Number of triangles taken from open list, not counting stale entries skipped there.
+    pub expansions: usize,
+    /// Number of triangles connections asked for their cost.
+    pub edges_visited: usize,
+    /// Largest number of entries open list held during search.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub open_peak: usize,
+    /// Time spent on query.
+    pub time: Duration,
+    /// Tells if result was taken from path cache.
+    pub cache_hit: bool,
+    /// Tells if path was found.
+    pub found: bool,
+}
+
+/// Install global hook called with statistics of every triangles path search performed by
+/// `NavMesh::find_path_triangles` (and so by `NavMesh::find_path` and other queries using it),
+/// replacing previous one.
+///
+/// Searches are measured only while hook is installed, so it costs nothing otherwise. Time is
+/// reported as zero on `wasm32-unknown-unknown` target, which has no system clock.
+///
+/// # Arguments
+/// * `hook` - callback called with search statistics.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+/// use std::sync::{
+///     atomic::{AtomicUsize, Ordering},
+///     Arc,
+/// };
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (2.0, 0.0, 0.0).into(), // 2
+///     (0.0, 1.0, 0.0).into(), // 3
+///     (1.0, 1.0, 0.0).into(), // 4
+///     (2.0, 1.0, 0.0).into(), // 5
+/// ];
+/// let triangles = vec![
+///     (0, 1, 4).into(), // 0
+///     (4, 3, 0).into(), // 1
+///     (1, 2, 5).into(), // 2
+///     (5, 4, 1).into(), // 3
+/// ];
+///
+/// let mesh = NavMesh::new(vertices, triangles).unwrap();
+/// let expansions = Arc::new(AtomicUsize::new(0));
+/// let counter = expansions.clone();
+/// set_nav_trace_hook(move |stats: &NavStats| {
+///     counter.fetch_add(stats.expansions, Ordering::Relaxed);
+/// });
+/// mesh.find_path_triangles(1, 2);
+/// clear_nav_trace_hook();
+/// assert!(expansions.load(Ordering::Relaxed) > 0);
+/// ```
+pub fn set_nav_trace_hook<F>(hook: F)
+where
+    F: Fn(&NavStats) + Send + Sync + 'static,
+{
+    let mut guard = TRACE_HOOK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *guard = Some(Arc::new(hook));
+    TRACE_HOOK_SET.store(true, Ordering::Release);
+}
+
+/// Remove global trace hook.
+pub fn clear_nav_trace_hook() {
+    let mut guard = TRACE_HOOK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *guard = None;
+    TRACE_HOOK_SET.store(false, Ordering::Release);
+}
+
+#[inline]
+pub(crate) fn is_tracing() -> bool {
+    TRACE_HOOK_SET.load(Ordering::Acquire)
+}
+
+pub(crate) fn trace(stats: &NavStats) {
+    if !is_tracing() {
+        return;
+    }
+    // hook is cloned out of the lock, so it can install or clear hooks itself.
+    let hook = TRACE_HOOK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if let Some(hook) = hook {
+        hook(stats);
+    }
+}
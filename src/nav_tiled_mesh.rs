@@ -0,0 +1,510 @@
+use crate::{
+    nav_funnel::{center_portal, funnel, orient_portal},
+    nav_open_node::OpenNode,
+    nav_path_cache::next_version,
+    nav_path_watch::NavPathWatcher,
+    NavMesh, NavPathMode, NavQuery, NavResult, NavTolerance, NavTriangle, NavUpAxis, NavVec3,
+    Scalar, ZERO_TRESHOLD,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+use typid::ID;
+
+/// Nav tiled mesh identifier.
+pub type NavTiledMeshID = ID<NavTiledMesh>;
+
+/// Tile coordinate (column, row) on ground plane.
+pub type NavTileCoord = (i32, i32);
+
+/// Reference to triangle of specific tile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavTileTriangle {
+    pub tile: NavTileCoord,
+    pub triangle: usize,
+}
+
+impl NavTileTriangle {
+    #[inline]
+    pub fn new(tile: NavTileCoord, triangle: usize) -> Self {
+        Self { tile, triangle }
+    }
+}
+
+/// Connection between triangles of two neighbor tiles.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavTileLink {
+    /// Triangle on the other side of link.
+    pub to: NavTileTriangle,
+    /// Shared part of tiles border edges.
+    pub portal: (NavVec3, NavVec3),
+}
+
+/// Nav mesh partitioned into fixed size tiles, each with its own triangulation and graph.
+/// Tiles are stitched together along matching border edges, so rebuilding one tile does not
+/// touch any of its neighbors.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavTiledMesh {
+    id: NavTiledMeshID,
+    tile_size: Scalar,
+    tiles: HashMap<NavTileCoord, NavMesh>,
+    links: HashMap<NavTileTriangle, Vec<NavTileLink>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "next_version"))]
+    version: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    up_axis: NavUpAxis,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    path_watcher: NavPathWatcher<NavTileCoord>,
+}
+
+impl NavTiledMesh {
+    /// Create new empty tiled nav mesh.
+    ///
+    /// # Arguments
+    /// * `tile_size` - size of single tile edge on ground plane.
+    pub fn new(tile_size: Scalar) -> Self {
+        Self {
+            id: NavTiledMeshID::new(),
+            tile_size: tile_size.max(ZERO_TRESHOLD),
+            tiles: Default::default(),
+            links: Default::default(),
+            version: next_version(),
+            up_axis: Default::default(),
+            path_watcher: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NavTiledMeshID {
+        self.id
+    }
+
+    #[inline]
+    pub fn tile_size(&self) -> Scalar {
+        self.tile_size
+    }
+
+    /// Axis pointing up, tiles are laid out on plane perpendicular to it.
+    #[inline]
+    pub fn up_axis(&self) -> NavUpAxis {
+        self.up_axis
+    }
+
+    /// Set up axis of tiled nav mesh and all its tiles.
+    pub fn set_up_axis(&mut self, up_axis: NavUpAxis) {
+        self.up_axis = up_axis;
+        for mesh in self.tiles.values_mut() {
+            mesh.set_up_axis(up_axis);
+        }
+    }
+
+    /// Tiled nav mesh version, changed every time tile is built, removed or any tile nav mesh
+    /// changes.
+    pub fn version(&self) -> u64 {
+        self.tiles
+            .values()
+            .map(|mesh| mesh.version())
+            .fold(self.version, u64::max)
+    }
+
+    /// Get coordinate of tile that contains given point.
+    #[inline]
+    pub fn tile_coord(&self, point: NavVec3) -> NavTileCoord {
+        let point = self.up_axis.rotate_to_z_up(point);
+        (
+            (point.x / self.tile_size).floor() as i32,
+            (point.y / self.tile_size).floor() as i32,
+        )
+    }
+
+    /// Get tile (min, max) bounds on ground plane.
+    #[inline]
+    pub fn tile_bounds(&self, coord: NavTileCoord) -> (NavVec3, NavVec3) {
+        let min = NavVec3::new(
+            coord.0 as Scalar * self.tile_size,
+            coord.1 as Scalar * self.tile_size,
+            0.0,
+        );
+        let max = min + NavVec3::new(self.tile_size, self.tile_size, 0.0);
+        let a = self.up_axis.rotate_from_z_up(min);
+        let b = self.up_axis.rotate_from_z_up(max);
+        (a.min(b), a.max(b))
+    }
+
+    #[inline]
+    pub fn tile(&self, coord: NavTileCoord) -> Option<&NavMesh> {
+        self.tiles.get(&coord)
+    }
+
+    #[inline]
+    pub fn tile_mut(&mut self, coord: NavTileCoord) -> Option<&mut NavMesh> {
+        self.version = next_version();
+        self.path_watcher.notify(&[coord]);
+        self.tiles.get_mut(&coord)
+    }
+
+    #[inline]
+    pub fn tiles(&self) -> impl Iterator<Item = (NavTileCoord, &NavMesh)> {
+        self.tiles.iter().map(|(coord, mesh)| (*coord, mesh))
+    }
+
+    #[inline]
+    pub(crate) fn path_watcher(&self) -> &NavPathWatcher<NavTileCoord> {
+        &self.path_watcher
+    }
+
+    #[inline]
+    pub(crate) fn path_watcher_mut(&mut self) -> &mut NavPathWatcher<NavTileCoord> {
+        &mut self.path_watcher
+    }
+
+    /// List of links going out of given tile triangle into neighbor tiles.
+    #[inline]
+    pub fn links(&self, triangle: NavTileTriangle) -> &[NavTileLink] {
+        self.links
+            .get(&triangle)
+            .map(|links| links.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Build (or rebuild) tile from vertices and triangles.
+    ///
+    /// # Arguments
+    /// * `coord` - tile coordinate.
+    /// * `vertices` - list of tile vertices points.
+    /// * `triangles` - list of tile vertices indices that produces triangles.
+    ///
+    /// # Returns
+    /// `Ok` with replaced tile nav mesh if there was any or `Err` if input data is invalid.
+    pub fn build_tile(
+        &mut self,
+        coord: NavTileCoord,
+        vertices: Vec<NavVec3>,
+        triangles: Vec<NavTriangle>,
+    ) -> NavResult<Option<NavMesh>> {
+        let mesh = NavMesh::new(vertices, triangles)?;
+        Ok(self.set_tile(coord, mesh))
+    }
+
+    /// Put nav mesh as tile and stitch it with its neighbors. Tile nav mesh gets up axis of tiled
+    /// nav mesh.
+    ///
+    /// # Arguments
+    /// * `coord` - tile coordinate.
+    /// * `mesh` - tile nav mesh.
+    ///
+    /// # Returns
+    /// `Some` with replaced tile nav mesh or `None` if tile was empty.
+    pub fn set_tile(&mut self, coord: NavTileCoord, mut mesh: NavMesh) -> Option<NavMesh> {
+        mesh.set_up_axis(self.up_axis);
+        let old = self.remove_tile(coord);
+        self.tiles.insert(coord, mesh);
+        self.stitch_tile(coord);
+        self.version = next_version();
+        self.path_watcher.notify(&[coord]);
+        old
+    }
+
+    /// Remove tile and all links to it.
+    ///
+    /// # Arguments
+    /// * `coord` - tile coordinate.
+    ///
+    /// # Returns
+    /// `Some` with removed tile nav mesh or `None` if tile was empty.
+    pub fn remove_tile(&mut self, coord: NavTileCoord) -> Option<NavMesh> {
+        let mesh = self.tiles.remove(&coord)?;
+        self.links.retain(|from, links| {
+            if from.tile == coord {
+                return false;
+            }
+            links.retain(|link| link.to.tile != coord);
+            !links.is_empty()
+        });
+        self.version = next_version();
+        self.path_watcher.notify(&[coord]);
+        Some(mesh)
+    }
+
+    fn stitch_tile(&mut self, coord: NavTileCoord) {
+        let mesh = match self.tiles.get(&coord) {
+            Some(mesh) => mesh,
+            None => return,
+        };
+        let edges = mesh.boundary_edges().collect::<Vec<_>>();
+        let mut links = vec![];
+        for col in (coord.0 - 1)..=(coord.0 + 1) {
+            for row in (coord.1 - 1)..=(coord.1 + 1) {
+                let other_coord = (col, row);
+                if other_coord == coord {
+                    continue;
+                }
+                let other = match self.tiles.get(&other_coord) {
+                    Some(other) => other,
+                    None => continue,
+                };
+                for (other_triangle, c, d) in other.boundary_edges() {
+                    for (triangle, a, b) in &edges {
+                        if let Some(portal) = Self::edges_overlap(*a, *b, c, d, mesh.tolerance()) {
+                            links.push((
+                                NavTileTriangle::new(coord, *triangle),
+                                NavTileTriangle::new(other_coord, other_triangle),
+                                portal,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        for (from, to, portal) in links {
+            self.links
+                .entry(from)
+                .or_default()
+                .push(NavTileLink { to, portal });
+            self.links
+                .entry(to)
+                .or_default()
+                .push(NavTileLink { to: from, portal });
+        }
+    }
+
+    pub(crate) fn edges_overlap(
+        a: NavVec3,
+        b: NavVec3,
+        c: NavVec3,
+        d: NavVec3,
+        tolerance: NavTolerance,
+    ) -> Option<(NavVec3, NavVec3)> {
+        let length = (b - a).magnitude();
+        if length < tolerance.distance {
+            return None;
+        }
+        let dir = (b - a) / length;
+        let tc = (c - a).dot(dir);
+        let td = (d - a).dot(dir);
+        if (c - (a + dir * tc)).sqr_magnitude() > tolerance.distance_sqr
+            || (d - (a + dir * td)).sqr_magnitude() > tolerance.distance_sqr
+        {
+            return None;
+        }
+        let from = tc.min(td).max(0.0);
+        let to = tc.max(td).min(length);
+        if to - from > tolerance.distance {
+            Some((a + dir * from, a + dir * to))
+        } else {
+            None
+        }
+    }
+
+    /// Find closest triangle of any tile to the given point.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with tile triangle if found or `None` otherwise.
+    pub fn find_closest_triangle(
+        &self,
+        point: NavVec3,
+        query: NavQuery,
+    ) -> Option<NavTileTriangle> {
+        let coord = self.tile_coord(point);
+        let nearby = ((coord.0 - 1)..=(coord.0 + 1))
+            .flat_map(|col| ((coord.1 - 1)..=(coord.1 + 1)).map(move |row| (col, row)))
+            .filter_map(|coord| self.tiles.get(&coord).map(|mesh| (coord, mesh)))
+            .collect::<Vec<_>>();
+        let candidates = if nearby.is_empty() {
+            self.tiles.iter().map(|(c, m)| (*c, m)).collect::<Vec<_>>()
+        } else {
+            nearby
+        };
+        candidates
+            .into_iter()
+            .filter_map(|(coord, mesh)| {
+                let triangle = mesh.find_closest_triangle(point, query)?;
+                let p = mesh.closest_point_on_triangle(triangle, point)?;
+                Some((
+                    (p - point).sqr_magnitude(),
+                    NavTileTriangle::new(coord, triangle),
+                ))
+            })
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal))
+            .map(|(_, triangle)| triangle)
+    }
+
+    /// Find closest point on any tile.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with point on tiled nav mesh if found or `None` otherwise.
+    pub fn closest_point(&self, point: NavVec3, query: NavQuery) -> Option<NavVec3> {
+        let triangle = self.find_closest_triangle(point, query)?;
+        self.tiles
+            .get(&triangle.tile)?
+            .closest_point_on_triangle(triangle.triangle, point)
+    }
+
+    /// Find shortest path between two tile triangles.
+    ///
+    /// Moves inside tile cost the same as in `NavMesh::find_path_triangles`, links between tiles
+    /// cost squared distance between triangles centers scaled by both areas costs.
+    ///
+    /// # Arguments
+    /// * `from` - start tile triangle.
+    /// * `to` - end tile triangle.
+    ///
+    /// # Returns
+    /// `Some` with path tile triangles and path cost if found or `None` otherwise.
+    pub fn find_path_triangles(
+        &self,
+        from: NavTileTriangle,
+        to: NavTileTriangle,
+    ) -> Option<(Vec<NavTileTriangle>, Scalar)> {
+        let start_mesh = self.tiles.get(&from.tile)?;
+        let end_mesh = self.tiles.get(&to.tile)?;
+        if from.triangle >= start_mesh.areas().len()
+            || to.triangle >= end_mesh.areas().len()
+            || start_mesh.is_triangle_blocked(from.triangle)
+            || end_mesh.is_triangle_blocked(to.triangle)
+        {
+            return None;
+        }
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::<NavTileTriangle, Scalar>::new();
+        let mut parents = HashMap::<NavTileTriangle, NavTileTriangle>::new();
+        costs.insert(from, 0.0);
+        open.push(OpenNode(0.0, from));
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if current == to {
+                let mut path = vec![current];
+                let mut node = current;
+                while let Some(parent) = parents.get(&node) {
+                    path.push(*parent);
+                    node = *parent;
+                }
+                path.reverse();
+                return Some((path, cost));
+            }
+            if cost > costs.get(&current).copied().unwrap_or(Scalar::INFINITY) {
+                continue;
+            }
+            let mesh = &self.tiles[&current.tile];
+            let area = &mesh.areas()[current.triangle];
+            let neighbors = mesh
+                .triangle_neighbors(current.triangle)
+                .map(|t| NavTileTriangle::new(current.tile, t))
+                .chain(self.links(current).iter().map(|link| link.to));
+            for next in neighbors {
+                let next_mesh = match self.tiles.get(&next.tile) {
+                    Some(mesh) => mesh,
+                    None => continue,
+                };
+                // moves inside tile cost the same as on tile nav mesh alone.
+                let weight = if next.tile == current.tile {
+                    match mesh.connection_cost(current.triangle, next.triangle) {
+                        Some(weight) => weight,
+                        None => continue,
+                    }
+                } else {
+                    if next_mesh.is_triangle_blocked(next.triangle) {
+                        continue;
+                    }
+                    let next_area = &next_mesh.areas()[next.triangle];
+                    (next_area.center - area.center).sqr_magnitude() * area.cost * next_area.cost
+                };
+                let next_cost = cost + weight;
+                if next_cost < costs.get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, current);
+                    open.push(OpenNode(next_cost, next));
+                }
+            }
+        }
+        None
+    }
+
+    /// Find shortest path between two points, going across tiles borders if needed.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with path points on tiled nav mesh if found or `None` otherwise.
+    pub fn find_path(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<Vec<NavVec3>> {
+        if from.same_as(to) {
+            return None;
+        }
+        let start = self.find_closest_triangle(from, query)?;
+        let end = self.find_closest_triangle(to, query)?;
+        let from = self.tiles[&start.tile].closest_point_on_triangle(start.triangle, from)?;
+        let to = self.tiles[&end.tile].closest_point_on_triangle(end.triangle, to)?;
+        let (triangles, _) = self.find_path_triangles(start, end)?;
+        if triangles.len() == 1 {
+            return Some(vec![from, to]);
+        }
+        let portals = triangles
+            .windows(2)
+            .map(|pair| self.portal(pair[0], pair[1]))
+            .collect::<Option<Vec<_>>>()?;
+        match mode {
+            NavPathMode::Accuracy | NavPathMode::Centerline(_) => {
+                // portals are not told apart from walls here, so both portal ends get centered.
+                let weight = match mode {
+                    NavPathMode::Centerline(weight) => weight,
+                    _ => 0.0,
+                };
+                let up = self.up_axis.up();
+                let mut oriented = Vec::with_capacity(portals.len() + 2);
+                oriented.push((from, from));
+                for (pair, portal) in triangles.windows(2).zip(portals) {
+                    let a = self.tiles[&pair[0].tile].areas()[pair[0].triangle].center;
+                    let b = self.tiles[&pair[1].tile].areas()[pair[1].triangle].center;
+                    let portal = center_portal(portal, (true, true), weight);
+                    oriented.push(orient_portal(a, b, portal, up));
+                }
+                oriented.push((to, to));
+                Some(funnel(&oriented, up))
+            }
+            NavPathMode::MidPoints => {
+                let mut points = Vec::with_capacity(portals.len() + 2);
+                points.push(from);
+                points.extend(portals.into_iter().map(|(a, b)| (a + b) * 0.5));
+                points.push(to);
+                points.dedup_by(|a, b| a.same_as(*b));
+                Some(points)
+            }
+        }
+    }
+
+    fn portal(&self, from: NavTileTriangle, to: NavTileTriangle) -> Option<(NavVec3, NavVec3)> {
+        if from.tile == to.tile {
+            self.tiles
+                .get(&from.tile)?
+                .triangles_portal(from.triangle, to.triangle)
+        } else {
+            self.links(from)
+                .iter()
+                .find(|link| link.to == to)
+                .map(|link| link.portal)
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    NavAnnotationShape, NavMesh, NavQuat, NavResult, NavTriangle, NavVec3, Scalar, SCALAR_MAX,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::Mul;
+
+/// Affine transformation, stored as columns of 4x4 matrix without projective row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavTransform {
+    pub x_axis: NavVec3,
+    pub y_axis: NavVec3,
+    pub z_axis: NavVec3,
+    pub translation: NavVec3,
+}
+
+impl Default for NavTransform {
+    fn default() -> Self {
+        Self {
+            x_axis: NavVec3::new(1.0, 0.0, 0.0),
+            y_axis: NavVec3::new(0.0, 1.0, 0.0),
+            z_axis: NavVec3::new(0.0, 0.0, 1.0),
+            translation: NavVec3::default(),
+        }
+    }
+}
+
+impl NavTransform {
+    /// Create transformation that scales, then rotates and then translates points.
+    ///
+    /// # Arguments
+    /// * `translation` - translation.
+    /// * `rotation` - rotation.
+    /// * `scale` - scale along each axis.
+    pub fn new(translation: NavVec3, rotation: NavQuat, scale: NavVec3) -> Self {
+        let (x_axis, y_axis, z_axis) = rotation.normalize().to_basis();
+        Self {
+            x_axis: x_axis * scale.x,
+            y_axis: y_axis * scale.y,
+            z_axis: z_axis * scale.z,
+            translation,
+        }
+    }
+
+    #[inline]
+    pub fn from_translation(translation: NavVec3) -> Self {
+        Self {
+            translation,
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn from_scale(scale: NavVec3) -> Self {
+        Self::new(NavVec3::default(), NavQuat::IDENTITY, scale)
+    }
+
+    #[inline]
+    pub fn from_rotation(rotation: NavQuat) -> Self {
+        Self::new(NavVec3::default(), rotation, NavVec3::new(1.0, 1.0, 1.0))
+    }
+
+    /// Create rotation around axis by angle (in radians), see `NavQuat::from_axis_angle`.
+    #[inline]
+    pub fn from_axis_angle(axis: NavVec3, angle: Scalar) -> Self {
+        Self::from_rotation(NavQuat::from_axis_angle(axis, angle))
+    }
+
+    /// Create transformation from column major 4x4 matrix elements, ignoring projective row.
+    pub fn from_cols_array(cols: &[Scalar; 16]) -> Self {
+        Self {
+            x_axis: NavVec3::new(cols[0], cols[1], cols[2]),
+            y_axis: NavVec3::new(cols[4], cols[5], cols[6]),
+            z_axis: NavVec3::new(cols[8], cols[9], cols[10]),
+            translation: NavVec3::new(cols[12], cols[13], cols[14]),
+        }
+    }
+
+    /// Column major 4x4 matrix elements.
+    pub fn to_cols_array(&self) -> [Scalar; 16] {
+        let (x, y, z, t) = (self.x_axis, self.y_axis, self.z_axis, self.translation);
+        [
+            x.x, x.y, x.z, 0.0, y.x, y.y, y.z, 0.0, z.x, z.y, z.z, 0.0, t.x, t.y, t.z, 1.0,
+        ]
+    }
+
+    #[inline]
+    pub fn transform_point(&self, point: NavVec3) -> NavVec3 {
+        self.transform_vector(point) + self.translation
+    }
+
+    /// Transform direction, ignoring translation.
+    #[inline]
+    pub fn transform_vector(&self, vector: NavVec3) -> NavVec3 {
+        self.x_axis * vector.x + self.y_axis * vector.y + self.z_axis * vector.z
+    }
+
+    /// Determinant of linear part, negative one tells transformation mirrors geometry.
+    #[inline]
+    pub fn determinant(&self) -> Scalar {
+        self.x_axis.cross(self.y_axis).dot(self.z_axis)
+    }
+
+    /// Inverse transformation.
+    ///
+    /// # Returns
+    /// `Some` with inverse or `None` if transformation collapses space (zero scale).
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant.abs() < Scalar::EPSILON {
+            return None;
+        }
+        // rows of inverse linear part are cross products of columns.
+        let rx = self.y_axis.cross(self.z_axis) / determinant;
+        let ry = self.z_axis.cross(self.x_axis) / determinant;
+        let rz = self.x_axis.cross(self.y_axis) / determinant;
+        let mut result = Self {
+            x_axis: NavVec3::new(rx.x, ry.x, rz.x),
+            y_axis: NavVec3::new(rx.y, ry.y, rz.y),
+            z_axis: NavVec3::new(rx.z, ry.z, rz.z),
+            translation: NavVec3::default(),
+        };
+        result.translation = -result.transform_vector(self.translation);
+        Some(result)
+    }
+
+    /// Smallest length of transformed unit axis, distances scale at least by this factor.
+    pub fn min_scale(&self) -> Scalar {
+        self.x_axis
+            .magnitude()
+            .min(self.y_axis.magnitude())
+            .min(self.z_axis.magnitude())
+    }
+}
+
+impl Mul for NavTransform {
+    type Output = Self;
+
+    /// Combine transformations, applying right hand side first.
+    fn mul(self, other: Self) -> Self {
+        Self {
+            x_axis: self.transform_vector(other.x_axis),
+            y_axis: self.transform_vector(other.y_axis),
+            z_axis: self.transform_vector(other.z_axis),
+            translation: self.transform_point(other.translation),
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::Mat4> for NavTransform {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(m: glam::Mat4) -> Self {
+        Self::from_cols_array(&m.to_cols_array().map(|v| v as Scalar))
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::DMat4> for NavTransform {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(m: glam::DMat4) -> Self {
+        Self::from_cols_array(&m.to_cols_array().map(|v| v as Scalar))
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<mint::ColumnMatrix4<Scalar>> for NavTransform {
+    fn from(m: mint::ColumnMatrix4<Scalar>) -> Self {
+        let cols: [Scalar; 16] = m.into();
+        Self::from_cols_array(&cols)
+    }
+}
+
+impl NavMesh {
+    /// Create copy of nav mesh with transformation baked into its vertices, for example to place
+    /// prefab nav mesh pieces (modular level rooms) at different world transforms.
+    ///
+    /// Triangles keep their indices, so areas settings, gates, profiles and annotations are
+    /// carried over (annotations and areas clearances get transformed too), as well as vertex
+    /// attributes. Mirroring transformations flip triangles winding so normals keep their
+    /// direction. Obstacles and landmarks are not carried over, since they are given in or
+    /// computed for old placement.
+    ///
+    /// # Arguments
+    /// * `transform` - transformation.
+    ///
+    /// # Returns
+    /// `Ok` with transformed nav mesh or `Err` if it cannot be built.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (1.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 0.0, 0.0).into(), // 2
+    ///     (0.0, 1.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 4).into(), // 0
+    ///     (4, 3, 0).into(), // 1
+    ///     (1, 2, 5).into(), // 2
+    ///     (5, 4, 1).into(), // 3
+    /// ];
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_area_cost(2, 3.0);
+    ///
+    /// // room turned by quarter around Z axis, doubled in size and moved.
+    /// let transform = NavTransform::from_translation((10.0, 0.0, 0.0).into())
+    ///     * NavTransform::from_axis_angle((0.0, 0.0, 1.0).into(), std::f64::consts::FRAC_PI_2 as _)
+    ///     * NavTransform::from_scale((2.0, 2.0, 2.0).into());
+    /// let room = mesh.transformed(&transform).unwrap();
+    /// assert!(room.vertices()[5].same_as((8.0, 4.0, 0.0).into()));
+    /// assert_eq!(room.areas()[2].cost, 3.0);
+    /// let path = room
+    ///     .find_path(
+    ///         (9.0, 0.5, 0.0).into(),
+    ///         (9.0, 3.5, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::MidPoints,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(path.len(), 2);
+    /// ```
+    pub fn transformed(&self, transform: &NavTransform) -> NavResult<Self> {
+        let vertices = self
+            .vertices()
+            .iter()
+            .map(|vertice| transform.transform_point(*vertice))
+            .collect::<Vec<_>>();
+        let mirrored = transform.determinant() < 0.0;
+        let triangles = self
+            .triangles()
+            .iter()
+            .map(|triangle| {
+                if mirrored {
+                    NavTriangle {
+                        first: triangle.first,
+                        second: triangle.third,
+                        third: triangle.second,
+                    }
+                } else {
+                    *triangle
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut result = Self::new(vertices, triangles)?;
+        result.set_up_axis(self.up_axis());
+        result.set_tolerance(self.tolerance());
+        let scale = transform.min_scale();
+        for (index, area) in self.areas().iter().enumerate() {
+            result.set_area_cost(index, area.cost);
+            result.set_area_type(index, area.area_type);
+            result.set_area_flags(index, area.flags);
+            if area.clearance < SCALAR_MAX {
+                result.set_area_clearance(index, area.clearance * scale);
+            }
+        }
+        for name in self.gates() {
+            let triangles = self.gate_triangles(name).unwrap_or_default().to_vec();
+            let open = self.is_gate_open(name).unwrap_or(true);
+            result.add_gate(name, triangles, open)?;
+        }
+        for (id, annotation) in self.annotations_map() {
+            let mut annotation = annotation.clone();
+            annotation.shape = match annotation.shape {
+                NavAnnotationShape::Point(position) => {
+                    NavAnnotationShape::Point(transform.transform_point(position))
+                }
+                NavAnnotationShape::Region { min, max } => {
+                    let corners = (0..8).map(|corner| {
+                        transform.transform_point(NavVec3::new(
+                            if corner & 1 == 0 { min.x } else { max.x },
+                            if corner & 2 == 0 { min.y } else { max.y },
+                            if corner & 4 == 0 { min.z } else { max.z },
+                        ))
+                    });
+                    let first = transform.transform_point(min);
+                    let (min, max) = corners.fold((first, first), |(min, max), corner| {
+                        (min.min(corner), max.max(corner))
+                    });
+                    NavAnnotationShape::Region { min, max }
+                }
+            };
+            annotation.direction = annotation
+                .direction
+                .map(|direction| transform.transform_vector(direction).normalize());
+            result.annotations_map_mut().insert(*id, annotation);
+        }
+        for (name, values) in self.vertex_attributes_map() {
+            result
+                .vertex_attributes_map_mut()
+                .insert(name.clone(), values.clone());
+        }
+        let profiles = self.profiles().clone();
+        *result.profiles_mut() = profiles;
+        result.refresh_profiles();
+        Ok(result)
+    }
+}
@@ -0,0 +1,336 @@
+//! Splitting polygons into nav mesh triangles, with backend chosen by polygons kind.
+
+use crate::{orient_on_plane, Error, NavMesh, NavResult, NavTriangle, NavVec3, ZERO_TRESHOLD};
+
+/// Splits polygon into triangles.
+pub trait NavTriangulator {
+    /// Triangulate polygon.
+    ///
+    /// # Arguments
+    /// * `polygon` - polygon points in winding order.
+    ///
+    /// # Returns
+    /// `Some` with triangles as indices of polygon points or `None` if polygon could not be
+    /// triangulated. Zero area triangles are never produced.
+    fn triangulate(&self, polygon: &[NavVec3]) -> Option<Vec<(usize, usize, usize)>>;
+}
+
+/// Triangle fan around first polygon point, fast but correct only for convex polygons.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NavFanTriangulator;
+
+impl NavTriangulator for NavFanTriangulator {
+    fn triangulate(&self, polygon: &[NavVec3]) -> Option<Vec<(usize, usize, usize)>> {
+        let result = (1..polygon.len().saturating_sub(1))
+            .map(|i| (0, i, i + 1))
+            .filter(|(a, b, c)| !is_degenerate(polygon[*a], polygon[*b], polygon[*c]))
+            .collect::<Vec<_>>();
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+}
+
+/// Ear clipping of simple (not self intersecting, possibly concave) polygons. Co-linear and
+/// repeated points are skipped instead of producing degenerate triangles.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NavEarClipTriangulator;
+
+impl NavTriangulator for NavEarClipTriangulator {
+    fn triangulate(&self, polygon: &[NavVec3]) -> Option<Vec<(usize, usize, usize)>> {
+        let normal = polygon_normal(polygon)?;
+        let mut indices = (0..polygon.len()).collect::<Vec<_>>();
+        let mut result = Vec::with_capacity(polygon.len().saturating_sub(2));
+        let mut misses = 0;
+        let mut i = 0;
+        while indices.len() > 2 {
+            if misses > indices.len() {
+                return None;
+            }
+            let count = indices.len();
+            let prev = indices[(i + count - 1) % count];
+            let current = indices[i % count];
+            let next = indices[(i + 1) % count];
+            let (a, b, c) = (polygon[prev], polygon[current], polygon[next]);
+            if is_degenerate(a, b, c) {
+                indices.remove(i % count);
+                misses = 0;
+                continue;
+            }
+            let convex = orient_on_plane(a, b, c, normal) > 0.0;
+            let is_ear = convex
+                && !indices.iter().any(|index| {
+                    *index != prev
+                        && *index != current
+                        && *index != next
+                        && is_inside_triangle(polygon[*index], a, b, c, normal)
+                        && ![a, b, c].iter().any(|p| p.same_as(polygon[*index]))
+                });
+            if is_ear {
+                result.push((prev, current, next));
+                indices.remove(i % count);
+                misses = 0;
+            } else {
+                i = (i + 1) % count;
+                misses += 1;
+            }
+        }
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+}
+
+impl NavMesh {
+    /// Create new nav mesh object from vertices and polygons, triangulated by given backend.
+    ///
+    /// # Arguments
+    /// * `vertices` - list of vertices points.
+    /// * `polygons` - list of vertices indices that produces polygons.
+    /// * `triangulator` - polygons triangulation backend.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh object or `Err` with `Error::PolygonTriangulationFailed` if any
+    /// polygon could not be triangulated.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// // L shaped concave polygon with co-linear points along its edges.
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(),
+    ///     (1.0, 0.0, 0.0).into(),
+    ///     (2.0, 0.0, 0.0).into(),
+    ///     (2.0, 1.0, 0.0).into(),
+    ///     (1.0, 1.0, 0.0).into(),
+    ///     (1.0, 2.0, 0.0).into(),
+    ///     (0.0, 2.0, 0.0).into(),
+    ///     (0.0, 1.0, 0.0).into(),
+    /// ];
+    /// let polygons = vec![vec![0, 1, 2, 3, 4, 5, 6, 7]];
+    ///
+    /// let mesh = NavMesh::from_polygons_with(vertices, polygons, &NavEarClipTriangulator).unwrap();
+    /// let size = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+    /// assert!((size - 3.0).abs() < 1.0e-4);
+    /// ```
+    pub fn from_polygons_with<T>(
+        vertices: Vec<NavVec3>,
+        polygons: Vec<Vec<u32>>,
+        triangulator: &T,
+    ) -> NavResult<Self>
+    where
+        T: NavTriangulator + ?Sized,
+    {
+        let mut triangles = vec![];
+        for (index, polygon) in polygons.iter().enumerate() {
+            let points = polygon
+                .iter()
+                .enumerate()
+                .map(|(local, vertice)| {
+                    vertices.get(*vertice as usize).copied().ok_or(
+                        Error::PolygonVerticeIndexOutOfBounds(index as u32, local as u32, *vertice),
+                    )
+                })
+                .collect::<NavResult<Vec<_>>>()?;
+            let found = triangulator
+                .triangulate(&points)
+                .ok_or_else(|| Error::PolygonTriangulationFailed(index as u32, polygon.clone()))?;
+            for (a, b, c) in found {
+                triangles.push(NavTriangle::from((polygon[a], polygon[b], polygon[c])));
+            }
+        }
+        Self::new(vertices, triangles)
+    }
+
+    /// Create new nav mesh object from walkable area outline with obstacle holes, triangulated
+    /// so that no triangle crosses outline or holes boundaries.
+    ///
+    /// Holes get connected to outline with bridge edges and resulting polygon gets triangulated
+    /// by ear clipping. Holes winding does not matter, holes should not overlap each other or
+    /// outline.
+    ///
+    /// # Arguments
+    /// * `outer` - walkable area outline points.
+    /// * `holes` - list of holes outlines points.
+    ///
+    /// # Returns
+    /// `Ok` with nav mesh object or `Err` with `Error::PolygonTriangulationFailed` if outline
+    /// (index 0) or hole (index of hole + 1) could not be triangulated, with indices of its
+    /// vertices (outline vertices come first, then vertices of every hole).
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let outer = vec![
+    ///     (0.0, 0.0, 0.0).into(),
+    ///     (3.0, 0.0, 0.0).into(),
+    ///     (3.0, 3.0, 0.0).into(),
+    ///     (0.0, 3.0, 0.0).into(),
+    /// ];
+    /// let holes = vec![vec![
+    ///     (1.0, 1.0, 0.0).into(),
+    ///     (2.0, 1.0, 0.0).into(),
+    ///     (2.0, 2.0, 0.0).into(),
+    ///     (1.0, 2.0, 0.0).into(),
+    /// ]];
+    ///
+    /// let mesh = NavMesh::from_polygons(&outer, &holes).unwrap();
+    /// let size = mesh.areas().iter().map(|area| area.size).sum::<Scalar>();
+    /// assert!((size - 8.0).abs() < 1.0e-4);
+    /// assert!(mesh
+    ///     .find_closest_triangle((1.5, 1.5, 0.0).into(), NavQuery::Closest)
+    ///     .is_some());
+    /// assert!(mesh.find_triangle_containing((1.5, 1.5, 0.0).into(), 0.1).is_none());
+    /// ```
+    pub fn from_polygons(outer: &[NavVec3], holes: &[Vec<NavVec3>]) -> NavResult<Self> {
+        let indices = |ring: &[usize]| ring.iter().map(|index| *index as u32).collect();
+        let normal = polygon_normal(outer).ok_or_else(|| {
+            Error::PolygonTriangulationFailed(0, (0..outer.len() as u32).collect())
+        })?;
+        let mut vertices = outer.to_vec();
+        let mut polygon = (0..outer.len()).collect::<Vec<_>>();
+        // holes wound against outline, so area outside of them lies on the left of their edges.
+        let mut rings = Vec::with_capacity(holes.len());
+        for (index, hole) in holes.iter().enumerate() {
+            let start = vertices.len();
+            vertices.extend_from_slice(hole);
+            let mut ring = (start..vertices.len()).collect::<Vec<_>>();
+            let hole_normal = polygon_normal(hole).ok_or_else(|| {
+                Error::PolygonTriangulationFailed(index as u32 + 1, indices(&ring))
+            })?;
+            if hole_normal.dot(normal) > 0.0 {
+                ring.reverse();
+            }
+            rings.push(ring);
+        }
+        for (index, ring) in rings.iter().enumerate() {
+            polygon = bridge_hole(&vertices, &polygon, ring, &rings[(index + 1)..], normal)
+                .ok_or_else(|| {
+                    Error::PolygonTriangulationFailed(index as u32 + 1, indices(ring))
+                })?;
+        }
+        let points = polygon
+            .iter()
+            .map(|index| vertices[*index])
+            .collect::<Vec<_>>();
+        let triangles = NavEarClipTriangulator
+            .triangulate(&points)
+            .ok_or_else(|| Error::PolygonTriangulationFailed(0, indices(&polygon)))?
+            .into_iter()
+            .map(|(a, b, c)| {
+                NavTriangle::from((polygon[a] as u32, polygon[b] as u32, polygon[c] as u32))
+            })
+            .collect();
+        Self::new(vertices, triangles)
+    }
+}
+
+/// Connect hole to polygon with bridge edge going from the hole to closest polygon vertice
+/// visible from it.
+///
+/// # Returns
+/// `Some` with polygon vertices indices going around the hole through the bridge or `None` if
+/// there is no bridge that does not cross any of edges.
+fn bridge_hole(
+    vertices: &[NavVec3],
+    polygon: &[usize],
+    hole: &[usize],
+    others: &[Vec<usize>],
+    normal: NavVec3,
+) -> Option<Vec<usize>> {
+    let orient = |a: NavVec3, b: NavVec3, c: NavVec3| orient_on_plane(a, b, c, normal);
+    let corner = |ring: &[usize], index: usize| {
+        let count = ring.len();
+        (
+            vertices[ring[(index + count - 1) % count]],
+            vertices[ring[index]],
+            vertices[ring[(index + 1) % count]],
+        )
+    };
+    // tells if target point lies inside the area on the left side of ring corner.
+    let is_in_wedge = |(prev, current, next): (NavVec3, NavVec3, NavVec3), target: NavVec3| {
+        let incoming = orient(prev, current, target) > 0.0;
+        let outgoing = orient(current, next, target) > 0.0;
+        if orient(prev, current, next) >= 0.0 {
+            incoming && outgoing
+        } else {
+            incoming || outgoing
+        }
+    };
+    let edges = std::iter::once(polygon)
+        .chain(std::iter::once(hole))
+        .chain(others.iter().map(|ring| ring.as_slice()))
+        .flat_map(|ring| {
+            (0..ring.len()).map(move |i| (vertices[ring[i]], vertices[ring[(i + 1) % ring.len()]]))
+        })
+        .collect::<Vec<_>>();
+    let mut candidates = hole
+        .iter()
+        .enumerate()
+        .flat_map(|(h, from)| {
+            polygon.iter().enumerate().map(move |(p, to)| {
+                let distance = (vertices[*to] - vertices[*from]).sqr_magnitude();
+                (distance, h, p)
+            })
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let (_, h, p) = candidates.into_iter().find(|(_, h, p)| {
+        let a = vertices[polygon[*p]];
+        let b = vertices[hole[*h]];
+        if a.same_as(b) || !is_in_wedge(corner(polygon, *p), b) || !is_in_wedge(corner(hole, *h), a)
+        {
+            return false;
+        }
+        !edges.iter().any(|(c, d)| {
+            if c.same_as(a) || c.same_as(b) || d.same_as(a) || d.same_as(b) {
+                return false;
+            }
+            orient(a, b, *c) * orient(a, b, *d) < 0.0 && orient(*c, *d, a) * orient(*c, *d, b) < 0.0
+        })
+    })?;
+    let mut result = Vec::with_capacity(polygon.len() + hole.len() + 2);
+    result.extend_from_slice(&polygon[..=p]);
+    result.extend_from_slice(&hole[h..]);
+    result.extend_from_slice(&hole[..=h]);
+    result.extend_from_slice(&polygon[p..]);
+    Some(result)
+}
+
+fn is_degenerate(a: NavVec3, b: NavVec3, c: NavVec3) -> bool {
+    (b - a).cross(c - a).magnitude() <= ZERO_TRESHOLD
+}
+
+fn is_inside_triangle(point: NavVec3, a: NavVec3, b: NavVec3, c: NavVec3, normal: NavVec3) -> bool {
+    orient_on_plane(a, b, point, normal) >= 0.0
+        && orient_on_plane(b, c, point, normal) >= 0.0
+        && orient_on_plane(c, a, point, normal) >= 0.0
+}
+
+/// Polygon normal computed with Newell's method, that works for concave polygons.
+fn polygon_normal(polygon: &[NavVec3]) -> Option<NavVec3> {
+    if polygon.len() < 3 {
+        return None;
+    }
+    let normal = (0..polygon.len()).fold(NavVec3::default(), |normal, i| {
+        let current = polygon[i];
+        let next = polygon[(i + 1) % polygon.len()];
+        normal
+            + NavVec3::new(
+                (current.y - next.y) * (current.z + next.z),
+                (current.z - next.z) * (current.x + next.x),
+                (current.x - next.x) * (current.y + next.y),
+            )
+    });
+    if normal.magnitude() <= ZERO_TRESHOLD {
+        None
+    } else {
+        Some(normal.normalize())
+    }
+}
@@ -1,11 +1,19 @@
 use crate::{Scalar, ZERO_TRESHOLD};
+use alloc::vec::Vec;
 use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+// float math comes from `libm` without `std` (test builds still link `std` and need no import).
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use spade::PointN;
-use std::ops::{Add, Div, Mul, Neg, Sub};
 
 #[repr(C)]
-#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NavVec3 {
     pub x: Scalar,
     pub y: Scalar,
@@ -30,7 +38,13 @@ impl NavVec3 {
 
     #[inline]
     pub fn same_as(self, other: Self) -> bool {
-        (other - self).sqr_magnitude() < ZERO_TRESHOLD
+        self.same_as_within(other, ZERO_TRESHOLD)
+    }
+
+    /// Tells if points are the same, using given treshold instead of default one.
+    #[inline]
+    pub fn same_as_within(self, other: Self, epsilon: Scalar) -> bool {
+        (other - self).sqr_magnitude() < epsilon
     }
 
     #[inline]
@@ -67,6 +81,101 @@ impl NavVec3 {
         self + (other - self) * factor
     }
 
+    /// Spherical interpolation between directions, with length interpolated linearly. Falls
+    /// back to linear interpolation for (almost) parallel or opposite vectors.
+    pub fn slerp(self, other: Self, factor: Scalar) -> Self {
+        let from = self.magnitude();
+        let to = other.magnitude();
+        if from < ZERO_TRESHOLD || to < ZERO_TRESHOLD {
+            return self.lerp(other, factor);
+        }
+        let angle = self.angle_between(other);
+        let sin = angle.sin();
+        if sin < ZERO_TRESHOLD {
+            return self.lerp(other, factor);
+        }
+        let a = ((1.0 - factor) * angle).sin() / sin;
+        let b = (factor * angle).sin() / sin;
+        let direction = self * (a / from) + other * (b / to);
+        direction * (from + (to - from) * factor)
+    }
+
+    /// Angle (in radians) between vectors, or 0 if any of them has zero length.
+    pub fn angle_between(self, other: Self) -> Scalar {
+        let len = self.magnitude() * other.magnitude();
+        if len < ZERO_TRESHOLD {
+            0.0
+        } else {
+            (self.dot(other) / len).clamp(-1.0, 1.0).acos()
+        }
+    }
+
+    /// Reflect vector off the surface with given normal.
+    #[inline]
+    pub fn reflect(self, normal: Self) -> Self {
+        let normal = normal.normalize();
+        self - normal * (2.0 * self.dot(normal))
+    }
+
+    /// Scale vector so that its length lies in range, keeping its direction.
+    pub fn clamp_length(self, min: Scalar, max: Scalar) -> Self {
+        let len = self.magnitude();
+        if len < ZERO_TRESHOLD {
+            self
+        } else if len < min {
+            self * (min / len)
+        } else if len > max {
+            self * (max / len)
+        } else {
+            self
+        }
+    }
+
+    /// Move point towards target by at most given distance, without overshooting it.
+    pub fn move_towards(self, target: Self, max_distance: Scalar) -> Self {
+        let diff = target - self;
+        let len = diff.magnitude();
+        if len <= max_distance || len < ZERO_TRESHOLD {
+            target
+        } else {
+            self + diff * (max_distance / len)
+        }
+    }
+
+    #[inline]
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    #[inline]
+    pub fn distance(self, other: Self) -> Scalar {
+        (other - self).magnitude()
+    }
+
+    #[inline]
+    pub fn distance_squared(self, other: Self) -> Scalar {
+        (other - self).sqr_magnitude()
+    }
+
+    /// Point of segment closest to this point.
+    pub fn closest_point_on_segment(self, from: Self, to: Self) -> Self {
+        let diff = to - from;
+        if diff.sqr_magnitude() < ZERO_TRESHOLD * ZERO_TRESHOLD {
+            return from;
+        }
+        Self::unproject(from, to, self.project(from, to).clamp(0.0, 1.0))
+    }
+
+    #[inline]
+    pub fn distance_to_segment(self, from: Self, to: Self) -> Scalar {
+        self.distance(self.closest_point_on_segment(from, to))
+    }
+
+    #[inline]
+    pub fn distance_squared_to_segment(self, from: Self, to: Self) -> Scalar {
+        self.distance_squared(self.closest_point_on_segment(from, to))
+    }
+
     #[inline]
     pub fn project(self, from: Self, to: Self) -> Scalar {
         let diff = to - from;
@@ -104,7 +213,13 @@ impl NavVec3 {
 
     #[inline]
     pub fn is_above_plane(self, origin: Self, normal: Self) -> bool {
-        self.distance_to_plane(origin, normal) > -ZERO_TRESHOLD
+        self.is_above_plane_within(origin, normal, ZERO_TRESHOLD)
+    }
+
+    /// Tells if point is above the plane, using given treshold instead of default one.
+    #[inline]
+    pub fn is_above_plane_within(self, origin: Self, normal: Self, epsilon: Scalar) -> bool {
+        self.distance_to_plane(origin, normal) > -epsilon
     }
 
     pub fn project_on_plane(self, origin: Self, normal: Self) -> Self {
@@ -129,7 +244,7 @@ impl NavVec3 {
 
     pub fn raycast_line(from: Self, to: Self, a: Self, b: Self, normal: Self) -> Option<Self> {
         let p = Self::raycast_plane(from, to, a, normal)?;
-        let t = p.project(a, b).max(0.0).min(1.0);
+        let t = p.project(a, b).clamp(0.0, 1.0);
         Some(Self::unproject(a, b, t))
     }
 
@@ -149,7 +264,23 @@ impl NavVec3 {
         }
     }
 
+    /// Find point where segment crosses triangle.
+    ///
+    /// With `robust` feature crossing is decided by exact orientation predicates (see
+    /// `segment_crosses_triangle`), otherwise by plane tests with `ZERO_TRESHOLD` tolerance.
     pub fn raycast_triangle(from: Self, to: Self, a: Self, b: Self, c: Self) -> Option<Self> {
+        #[cfg(feature = "robust")]
+        {
+            crate::segment_crosses_triangle(from, to, a, b, c).map(|t| Self::unproject(from, to, t))
+        }
+        #[cfg(not(feature = "robust"))]
+        {
+            Self::raycast_triangle_tolerant(from, to, a, b, c)
+        }
+    }
+
+    #[cfg(not(feature = "robust"))]
+    fn raycast_triangle_tolerant(from: Self, to: Self, a: Self, b: Self, c: Self) -> Option<Self> {
         let tab = (b - a).normalize();
         let tbc = (c - b).normalize();
         let tca = (a - c).normalize();
@@ -210,14 +341,121 @@ impl NavVec3 {
     }
 
     pub fn is_line_between_points(from: Self, to: Self, a: Self, b: Self, normal: Self) -> bool {
+        Self::is_line_between_points_within(from, to, a, b, normal, ZERO_TRESHOLD)
+    }
+
+    /// Tells if line goes between points, using given treshold instead of default one.
+    pub fn is_line_between_points_within(
+        from: Self,
+        to: Self,
+        a: Self,
+        b: Self,
+        normal: Self,
+        epsilon: Scalar,
+    ) -> bool {
         let n = (to - from).cross(normal);
-        let sa = Self::side(n.dot(a - from));
-        let sb = Self::side(n.dot(b - from));
+        let sa = Self::side(n.dot(a - from), epsilon);
+        let sb = Self::side(n.dot(b - from), epsilon);
         sa != sb
     }
 
-    fn side(v: Scalar) -> i8 {
-        if v.abs() < ZERO_TRESHOLD {
+    /// Dot products of pairs of vectors, computed four at once when `simd` feature is enabled.
+    ///
+    /// # Arguments
+    /// * `a` - first vectors.
+    /// * `b` - second vectors, paired with first ones by index.
+    ///
+    /// # Returns
+    /// Dot products of as many pairs as shorter of lists contains.
+    pub fn dot_batch(a: &[Self], b: &[Self]) -> Vec<Scalar> {
+        let count = a.len().min(b.len());
+        let mut result = Vec::with_capacity(count);
+        #[cfg(all(feature = "simd", target_arch = "x86_64", not(feature = "scalar64")))]
+        for (a, b) in a.chunks_exact(4).zip(b.chunks_exact(4)) {
+            // SAFETY: SSE is part of x86_64 baseline.
+            result.extend_from_slice(&unsafe {
+                crate::nav_simd::dot4([a[0], a[1], a[2], a[3]], [b[0], b[1], b[2], b[3]])
+            });
+        }
+        let done = result.len();
+        result.extend(
+            a[done..count]
+                .iter()
+                .zip(&b[done..count])
+                .map(|(a, b)| a.dot(*b)),
+        );
+        result
+    }
+
+    /// Same as `NavVec3::raycast_triangle` performed for many triangles, computed four at once
+    /// when `simd` feature is enabled.
+    ///
+    /// # Arguments
+    /// * `from` - ray start.
+    /// * `to` - ray end.
+    /// * `triangles` - list of triangles vertices.
+    ///
+    /// # Returns
+    /// Contact points of ray with every triangle.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let triangles = [
+    ///     (
+    ///         (0.0, -1.0, 0.0).into(),
+    ///         (1.0, 1.0, 0.0).into(),
+    ///         (-1.0, 1.0, 0.0).into(),
+    ///     ),
+    ///     (
+    ///         (5.0, -1.0, 0.0).into(),
+    ///         (6.0, 1.0, 0.0).into(),
+    ///         (4.0, 1.0, 0.0).into(),
+    ///     ),
+    /// ];
+    /// let result = NavVec3::raycast_triangle_batch(
+    ///     (0.0, 0.0, 1.0).into(),
+    ///     (0.0, 0.0, -1.0).into(),
+    ///     &triangles,
+    /// );
+    /// assert_eq!(result, vec![Some((0.0, 0.0, 0.0).into()), None]);
+    /// ```
+    pub fn raycast_triangle_batch(
+        from: Self,
+        to: Self,
+        triangles: &[(Self, Self, Self)],
+    ) -> Vec<Option<Self>> {
+        let mut result = Vec::with_capacity(triangles.len());
+        #[cfg(all(
+            feature = "simd",
+            target_arch = "x86_64",
+            not(feature = "scalar64"),
+            not(feature = "robust")
+        ))]
+        for t in triangles.chunks_exact(4) {
+            // SAFETY: SSE is part of x86_64 baseline.
+            result.extend_from_slice(&unsafe {
+                crate::nav_simd::raycast_triangle4(
+                    from,
+                    to,
+                    [t[0].0, t[1].0, t[2].0, t[3].0],
+                    [t[0].1, t[1].1, t[2].1, t[3].1],
+                    [t[0].2, t[1].2, t[2].2, t[3].2],
+                )
+            });
+        }
+        let done = result.len();
+        result.extend(
+            triangles[done..]
+                .iter()
+                .map(|(a, b, c)| Self::raycast_triangle(from, to, *a, *b, *c)),
+        );
+        result
+    }
+
+    fn side(v: Scalar, epsilon: Scalar) -> i8 {
+        if v.abs() < epsilon {
             0
         } else {
             v.signum() as i8
@@ -225,6 +463,64 @@ impl NavVec3 {
     }
 }
 
+/// Axis pointing up, perpendicular to ground plane.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NavUpAxis {
+    /// Y axis points up and ground lies on XZ plane.
+    Y,
+    /// Z axis points up and ground lies on XY plane.
+    #[default]
+    Z,
+}
+
+impl NavUpAxis {
+    /// Unit vector pointing up.
+    #[inline]
+    pub fn up(self) -> NavVec3 {
+        match self {
+            Self::Y => NavVec3::new(0.0, 1.0, 0.0),
+            Self::Z => NavVec3::new(0.0, 0.0, 1.0),
+        }
+    }
+
+    /// Height of point above ground plane.
+    #[inline]
+    pub fn height(self, v: NavVec3) -> Scalar {
+        match self {
+            Self::Y => v.y,
+            Self::Z => v.z,
+        }
+    }
+
+    /// Project point onto ground plane.
+    #[inline]
+    pub fn flatten(self, v: NavVec3) -> NavVec3 {
+        match self {
+            Self::Y => NavVec3::new(v.x, 0.0, v.z),
+            Self::Z => NavVec3::new(v.x, v.y, 0.0),
+        }
+    }
+
+    /// Rotate point from this axis space into Z-up space, so that ground lies on XY plane.
+    #[inline]
+    pub fn rotate_to_z_up(self, v: NavVec3) -> NavVec3 {
+        match self {
+            Self::Y => NavVec3::new(v.x, -v.z, v.y),
+            Self::Z => v,
+        }
+    }
+
+    /// Rotate point from Z-up space into this axis space.
+    #[inline]
+    pub fn rotate_from_z_up(self, v: NavVec3) -> NavVec3 {
+        match self {
+            Self::Y => NavVec3::new(v.x, v.z, -v.y),
+            Self::Z => v,
+        }
+    }
+}
+
 impl From<(Scalar, Scalar, Scalar)> for NavVec3 {
     fn from(value: (Scalar, Scalar, Scalar)) -> Self {
         Self {
@@ -382,6 +678,7 @@ impl Neg for NavVec3 {
     }
 }
 
+#[cfg(feature = "std")]
 impl PointN for NavVec3 {
     type Scalar = Scalar;
 
@@ -454,6 +751,19 @@ impl UlpsEq for NavVec3 {
     }
 }
 
+/// Reinterpret slice of vectors made of three scalars laid out the same way as `NavVec3`.
+#[cfg(any(feature = "mint", feature = "glam"))]
+#[inline]
+fn cast_slice<T: Copy, U: Copy>(v: &[T]) -> &[U] {
+    const {
+        assert!(core::mem::size_of::<T>() == core::mem::size_of::<U>());
+        assert!(core::mem::align_of::<T>() == core::mem::align_of::<U>());
+    }
+    // SAFETY: both types are `#[repr(C)]` structs of three scalars with the same size and
+    // alignment, so every bit pattern of one is valid value of the other.
+    unsafe { core::slice::from_raw_parts(v.as_ptr() as *const U, v.len()) }
+}
+
 #[cfg(feature = "mint")]
 impl From<mint::Vector3<Scalar>> for NavVec3 {
     fn from(v: mint::Vector3<Scalar>) -> Self {
@@ -473,22 +783,88 @@ impl From<NavVec3> for mint::Vector3<Scalar> {
 }
 
 #[cfg(feature = "mint")]
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_mint() {
-        let v = NavVec3::new(0.0, 1.0, -1.0);
-        let _f = mint::Vector3::<Scalar>::from(v);
-        let _t: mint::Vector3<Scalar> = v.into();
-
-        let v = mint::Vector3::<Scalar> {
-            x: 0.0,
-            y: 1.0,
-            z: -1.0,
-        };
-        let _f = NavVec3::from(v);
-        let _t: NavVec3 = v.into();
+impl From<mint::Point3<Scalar>> for NavVec3 {
+    fn from(v: mint::Point3<Scalar>) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<NavVec3> for mint::Point3<Scalar> {
+    fn from(v: NavVec3) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+impl NavVec3 {
+    /// View slice of mint points as nav vectors without copying.
+    #[inline]
+    pub fn from_mint_slice(v: &[mint::Point3<Scalar>]) -> &[Self] {
+        cast_slice(v)
+    }
+
+    /// View slice of nav vectors as mint points without copying.
+    #[inline]
+    pub fn as_mint_slice(v: &[Self]) -> &[mint::Point3<Scalar>] {
+        cast_slice(v)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::Vec3> for NavVec3 {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(v: glam::Vec3) -> Self {
+        Self::new(v.x as Scalar, v.y as Scalar, v.z as Scalar)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<NavVec3> for glam::Vec3 {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(v: NavVec3) -> Self {
+        Self::new(v.x as f32, v.y as f32, v.z as f32)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::DVec3> for NavVec3 {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(v: glam::DVec3) -> Self {
+        Self::new(v.x as Scalar, v.y as Scalar, v.z as Scalar)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<NavVec3> for glam::DVec3 {
+    #[allow(clippy::unnecessary_cast)]
+    fn from(v: NavVec3) -> Self {
+        Self::new(v.x as f64, v.y as f64, v.z as f64)
+    }
+}
+
+/// Glam vector type with the same scalar type as `NavVec3`.
+#[cfg(all(feature = "glam", not(feature = "scalar64")))]
+pub type NavGlamVec3 = glam::Vec3;
+/// Glam vector type with the same scalar type as `NavVec3`.
+#[cfg(all(feature = "glam", feature = "scalar64"))]
+pub type NavGlamVec3 = glam::DVec3;
+
+#[cfg(feature = "glam")]
+impl NavVec3 {
+    /// View slice of glam vectors as nav vectors without copying.
+    #[inline]
+    pub fn from_glam_slice(v: &[NavGlamVec3]) -> &[Self] {
+        cast_slice(v)
+    }
+
+    /// View slice of nav vectors as glam vectors without copying.
+    #[inline]
+    pub fn as_glam_slice(v: &[Self]) -> &[NavGlamVec3] {
+        cast_slice(v)
     }
 }
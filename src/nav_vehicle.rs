@@ -0,0 +1,532 @@
+use crate::{NavMesh, NavQuery, NavUpAxis, NavVec3, Scalar, ZERO_TRESHOLD};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const TAU: Scalar = std::f64::consts::TAU as Scalar;
+const PI: Scalar = std::f64::consts::PI as Scalar;
+
+/// Settings of converting path polyline into curve that vehicle with limited turning radius can
+/// follow.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavVehicleSettings {
+    /// Minimal turning radius of vehicle.
+    pub turning_radius: Scalar,
+    /// Allow driving backwards, with vehicle switching driving direction at path points.
+    pub allow_reverse: bool,
+    /// Multiplier of distance driven backwards, so reversing is chosen only when it pays off.
+    pub reverse_cost: Scalar,
+    /// Distance between sampled curve points.
+    pub step: Scalar,
+    /// Maximal vertical distance between curve and nav mesh surface it gets projected on.
+    pub height: Scalar,
+}
+
+impl Default for NavVehicleSettings {
+    fn default() -> Self {
+        Self {
+            turning_radius: 1.0,
+            allow_reverse: false,
+            reverse_cost: 2.0,
+            step: 0.25,
+            height: 1.0,
+        }
+    }
+}
+
+/// Curvature bounded path made of sampled turning arcs and straight lines.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavVehiclePath {
+    /// Curve points on nav mesh surface.
+    pub points: Vec<NavVec3>,
+    /// Unit direction vehicle faces at every point, on ground plane.
+    pub headings: Vec<NavVec3>,
+    /// Tells for every segment between consecutive points if vehicle drives backwards along it.
+    pub reverse: Vec<bool>,
+    /// Length of curve points polyline.
+    pub length: Scalar,
+    /// `false` when some part of curve could not fit on nav mesh and was replaced by original
+    /// path segment, which cannot be followed without turning in place.
+    pub feasible: bool,
+}
+
+impl NavVehiclePath {
+    /// Iterator over indices of points where vehicle switches driving direction.
+    pub fn cusps(&self) -> impl Iterator<Item = usize> + '_ {
+        self.reverse
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0] != pair[1])
+            .map(|(index, _)| index + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Turn {
+    Left,
+    Straight,
+    Right,
+}
+
+/// Vehicle pose on ground plane (rotated to Z up), with heading angle of driving direction.
+#[derive(Debug, Clone, Copy)]
+struct Pose {
+    x: Scalar,
+    y: Scalar,
+    heading: Scalar,
+}
+
+impl Pose {
+    fn advance(self, turn: Turn, length: Scalar, radius: Scalar) -> Self {
+        let (sin, cos) = self.heading.sin_cos();
+        match turn {
+            Turn::Straight => Self {
+                x: self.x + cos * length,
+                y: self.y + sin * length,
+                heading: self.heading,
+            },
+            Turn::Left => {
+                let heading = self.heading + length / radius;
+                Self {
+                    x: self.x + (heading.sin() - sin) * radius,
+                    y: self.y + (cos - heading.cos()) * radius,
+                    heading,
+                }
+            }
+            Turn::Right => {
+                let heading = self.heading - length / radius;
+                Self {
+                    x: self.x + (sin - heading.sin()) * radius,
+                    y: self.y + (heading.cos() - cos) * radius,
+                    heading,
+                }
+            }
+        }
+    }
+}
+
+/// Dubins curve made of three turns with their lengths.
+type Word = [(Turn, Scalar); 3];
+
+/// Leg of vehicle path between two consecutive path points.
+#[derive(Debug, Clone)]
+struct Leg {
+    reverse: bool,
+    fallback: bool,
+    cost: Scalar,
+    // (point, heading angle of driving direction) samples, without leg start point.
+    samples: Vec<(NavVec3, Scalar)>,
+}
+
+impl NavMesh {
+    /// Convert path polyline into curvature bounded path for vehicles (cars, large creatures)
+    /// that cannot turn in place, using shortest curves made of turning arcs and straight lines
+    /// (Dubins curves, or Reeds-Shepp like curves with direction switches when reversing is
+    /// allowed) going through all path points.
+    ///
+    /// Vehicle at inner path points faces half way between incoming and outgoing segments, or
+    /// opposite way when reversing is allowed. Curves that do not fit on nav mesh are replaced by
+    /// longer ones, and if none fits, original path segment is used and path is marked as not
+    /// feasible.
+    ///
+    /// # Arguments
+    /// * `path` - path points.
+    /// * `heading` - optional direction vehicle faces at path start, otherwise first segment
+    ///   direction (or opposite one when reversing is allowed) is used.
+    /// * `settings` - vehicle settings.
+    ///
+    /// # Returns
+    /// Vehicle path.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(),
+    ///     (10.0, 0.0, 0.0).into(),
+    ///     (10.0, 10.0, 0.0).into(),
+    ///     (0.0, 10.0, 0.0).into(),
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    ///
+    /// // target is behind vehicle facing right.
+    /// let path = [(5.0, 5.0, 0.0).into(), (3.0, 5.0, 0.0).into()];
+    /// let heading = Some((1.0, 0.0, 0.0).into());
+    /// let settings = NavVehicleSettings::default();
+    /// let forward = mesh.vehicle_path(&path, heading, &settings);
+    /// assert!(forward.feasible);
+    /// assert!(forward.length > 2.0 + settings.turning_radius * 3.0);
+    /// assert!(forward.reverse.iter().all(|reverse| !reverse));
+    ///
+    /// let settings = NavVehicleSettings {
+    ///     allow_reverse: true,
+    ///     ..Default::default()
+    /// };
+    /// let backward = mesh.vehicle_path(&path, heading, &settings);
+    /// assert!((backward.length - 2.0).abs() < 1.0e-4);
+    /// assert!(backward.reverse.iter().all(|reverse| *reverse));
+    /// assert!(backward.points.last().unwrap().same_as(path[1]));
+    /// ```
+    pub fn vehicle_path(
+        &self,
+        path: &[NavVec3],
+        heading: Option<NavVec3>,
+        settings: &NavVehicleSettings,
+    ) -> NavVehiclePath {
+        let up_axis = self.up_axis();
+        let epsilon = self.tolerance().distance;
+        // path points rotated to Z up, together with original ones.
+        let mut points = Vec::<NavVec3>::with_capacity(path.len());
+        let mut originals = Vec::<NavVec3>::with_capacity(path.len());
+        for point in path {
+            let flat = up_axis.rotate_to_z_up(*point);
+            let duplicate = points.last().map(|last| {
+                NavVec3::new(last.x - flat.x, last.y - flat.y, 0.0).magnitude() < epsilon
+            });
+            if !duplicate.unwrap_or_default() {
+                points.push(flat);
+                originals.push(*point);
+            }
+        }
+        let mut result = NavVehiclePath {
+            feasible: true,
+            ..Default::default()
+        };
+        if points.is_empty() {
+            return result;
+        }
+        let angle = |v: NavVec3| v.y.atan2(v.x);
+        let directions = points
+            .windows(2)
+            .map(|pair| NavVec3::new(pair[1].x - pair[0].x, pair[1].y - pair[0].y, 0.0).normalize())
+            .collect::<Vec<_>>();
+        // candidate vehicle heading angles per path point.
+        let mut candidates = Vec::with_capacity(points.len());
+        for index in 0..points.len() {
+            let given = if index == 0 {
+                heading
+                    .map(|heading| up_axis.rotate_to_z_up(heading))
+                    .filter(|heading| heading.x.abs() + heading.y.abs() > ZERO_TRESHOLD)
+            } else {
+                None
+            };
+            let base = match (given, index) {
+                (Some(heading), _) => angle(heading),
+                (None, 0) if directions.is_empty() => 0.0,
+                (None, 0) => angle(directions[0]),
+                (None, index) if index == directions.len() => angle(directions[index - 1]),
+                (None, index) => {
+                    let sum = directions[index - 1] + directions[index];
+                    // path turning back, vehicle arrives straight and reverses or turns around.
+                    if sum.sqr_magnitude() < ZERO_TRESHOLD {
+                        angle(directions[index - 1])
+                    } else {
+                        angle(sum)
+                    }
+                }
+            };
+            if settings.allow_reverse && given.is_none() {
+                candidates.push(vec![base, base + PI]);
+            } else {
+                candidates.push(vec![base]);
+            }
+        }
+        let heading_vector =
+            |angle: Scalar| up_axis.rotate_from_z_up(NavVec3::new(angle.cos(), angle.sin(), 0.0));
+        result.points.push(path[0]);
+        if points.len() == 1 {
+            result.headings.push(heading_vector(candidates[0][0]));
+            return result;
+        }
+        // best (fallbacks, cost, parent candidate, leg) per candidate of every path point.
+        let mut best = vec![candidates[0]
+            .iter()
+            .map(|_| Some((0, 0.0, 0, None)))
+            .collect::<Vec<_>>()];
+        for index in 1..points.len() {
+            let mut current =
+                vec![None::<(usize, Scalar, usize, Option<Leg>)>; candidates[index].len()];
+            for (to, to_heading) in candidates[index].iter().enumerate() {
+                for (from, from_heading) in candidates[index - 1].iter().enumerate() {
+                    let (fallbacks, cost) = match &best[index - 1][from] {
+                        Some((fallbacks, cost, _, _)) => (*fallbacks, *cost),
+                        None => continue,
+                    };
+                    let leg = self.vehicle_leg(
+                        (points[index - 1], *from_heading),
+                        (points[index], *to_heading),
+                        up_axis,
+                        settings,
+                    );
+                    let fallbacks = fallbacks + leg.fallback as usize;
+                    let cost = cost + leg.cost;
+                    let better = match &current[to] {
+                        Some((best_fallbacks, best_cost, _, _)) => {
+                            (fallbacks, cost) < (*best_fallbacks, *best_cost)
+                        }
+                        None => true,
+                    };
+                    if better {
+                        current[to] = Some((fallbacks, cost, from, Some(leg)));
+                    }
+                }
+            }
+            best.push(current);
+        }
+        let mut choice = (0..candidates[points.len() - 1].len())
+            .min_by(|a, b| {
+                let a = best[points.len() - 1][*a].as_ref().unwrap();
+                let b = best[points.len() - 1][*b].as_ref().unwrap();
+                (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap()
+            })
+            .unwrap();
+        let mut legs = Vec::with_capacity(points.len() - 1);
+        for index in (1..points.len()).rev() {
+            let (_, _, parent, leg) = best[index][choice].take().unwrap();
+            legs.push(leg.unwrap());
+            choice = parent;
+        }
+        legs.reverse();
+        let start_heading = candidates[0][choice];
+        let facing = |heading: Scalar, reverse: bool| {
+            heading_vector(if reverse { heading + PI } else { heading })
+        };
+        result
+            .headings
+            .push(facing(start_heading, legs[0].reverse && !legs[0].fallback));
+        for (index, leg) in legs.into_iter().enumerate() {
+            let count = leg.samples.len();
+            for (sample, (point, heading)) in leg.samples.into_iter().enumerate() {
+                // last sample lands exactly at original path point.
+                let point = if sample + 1 == count {
+                    originals[index + 1]
+                } else {
+                    point
+                };
+                result.length += result.points.last().unwrap().distance(point);
+                result.points.push(point);
+                result.headings.push(facing(heading, leg.reverse));
+                result.reverse.push(leg.reverse);
+            }
+            result.feasible &= !leg.fallback;
+        }
+        result
+    }
+
+    /// Find shortest curve between two vehicle poses (given as points rotated to Z up with
+    /// heading angles) that fits on nav mesh, or fallback straight segment.
+    fn vehicle_leg(
+        &self,
+        from: (NavVec3, Scalar),
+        to: (NavVec3, Scalar),
+        up_axis: NavUpAxis,
+        settings: &NavVehicleSettings,
+    ) -> Leg {
+        let radius = settings.turning_radius.max(ZERO_TRESHOLD);
+        let step = settings.step.max(ZERO_TRESHOLD);
+        let gears = if settings.allow_reverse {
+            &[false, true][..]
+        } else {
+            &[false][..]
+        };
+        let mut best = None::<Leg>;
+        for reverse in gears.iter().copied() {
+            let flip = if reverse { PI } else { 0.0 };
+            let start = Pose {
+                x: from.0.x,
+                y: from.0.y,
+                heading: from.1 + flip,
+            };
+            let end = Pose {
+                x: to.0.x,
+                y: to.0.y,
+                heading: to.1 + flip,
+            };
+            let multiplier = if reverse {
+                settings.reverse_cost.max(1.0)
+            } else {
+                1.0
+            };
+            for word in dubins_words(start, end, radius) {
+                let length = word.iter().map(|(_, length)| *length).sum::<Scalar>();
+                let cost = length * multiplier;
+                if best
+                    .as_ref()
+                    .map(|best| cost >= best.cost)
+                    .unwrap_or_default()
+                {
+                    break;
+                }
+                let count = ((length / step).ceil() as usize).max(1);
+                let mut samples = Vec::with_capacity(count);
+                let mut last = up_axis.rotate_from_z_up(from.0);
+                let mut valid = true;
+                for sample in 1..=count {
+                    let distance = length * sample as Scalar / count as Scalar;
+                    let pose = word_pose(start, &word, distance, radius);
+                    let height = from.0.z + (to.0.z - from.0.z) * distance / length;
+                    let point = up_axis.rotate_from_z_up(NavVec3::new(pose.x, pose.y, height));
+                    let point = match self.find_surface_point(point, settings.height) {
+                        Some((_, point)) => point,
+                        None => {
+                            valid = false;
+                            break;
+                        }
+                    };
+                    let visible = last.same_as(point)
+                        || self
+                            .raycast(last, point, NavQuery::Accuracy)
+                            .map(|result| result.hit.is_none())
+                            .unwrap_or_default();
+                    if !visible {
+                        valid = false;
+                        break;
+                    }
+                    samples.push((point, pose.heading - flip));
+                    last = point;
+                }
+                if valid {
+                    best = Some(Leg {
+                        reverse,
+                        fallback: false,
+                        cost,
+                        samples,
+                    });
+                    break;
+                }
+            }
+        }
+        best.unwrap_or_else(|| {
+            let direction = to.0 - from.0;
+            Leg {
+                reverse: false,
+                fallback: true,
+                cost: NavVec3::new(direction.x, direction.y, 0.0).magnitude(),
+                samples: vec![(
+                    up_axis.rotate_from_z_up(to.0),
+                    direction.y.atan2(direction.x),
+                )],
+            }
+        })
+    }
+}
+
+/// Pose reached after driving given distance along word.
+fn word_pose(start: Pose, word: &Word, distance: Scalar, radius: Scalar) -> Pose {
+    let mut pose = start;
+    let mut left = distance;
+    for (turn, length) in word {
+        let length = length.min(left);
+        pose = pose.advance(*turn, length, radius);
+        left -= length;
+        if left <= 0.0 {
+            break;
+        }
+    }
+    pose
+}
+
+/// Angle wrapped into `[0; TAU)` range, with angles just below full turn treated as zero so
+/// straight curves do not get extra loops.
+fn mod_tau(angle: Scalar) -> Scalar {
+    let angle = angle - TAU * (angle / TAU).floor();
+    if TAU - angle < 1.0e-5 {
+        0.0
+    } else {
+        angle
+    }
+}
+
+/// All Dubins curves between two poses, shortest first. Only curves that actually end at target
+/// pose are returned, which filters out numerical degeneracies.
+fn dubins_words(from: Pose, to: Pose, radius: Scalar) -> Vec<Word> {
+    let dx = (to.x - from.x) / radius;
+    let dy = (to.y - from.y) / radius;
+    let d = (dx * dx + dy * dy).sqrt();
+    let theta = if d > ZERO_TRESHOLD { dy.atan2(dx) } else { 0.0 };
+    let a = mod_tau(from.heading - theta);
+    let b = mod_tau(to.heading - theta);
+    let (sa, ca) = a.sin_cos();
+    let (sb, cb) = b.sin_cos();
+    let cab = (a - b).cos();
+    let mut words = Vec::<Word>::with_capacity(6);
+    let mut push = |turns: [Turn; 3], lengths: Option<[Scalar; 3]>| {
+        if let Some([t, p, q]) = lengths {
+            words.push([
+                (turns[0], t * radius),
+                (turns[1], p * radius),
+                (turns[2], q * radius),
+            ]);
+        }
+    };
+    let lsl = {
+        let squared = 2.0 + d * d - 2.0 * cab + 2.0 * d * (sa - sb);
+        (squared >= 0.0).then(|| {
+            let angle = (cb - ca).atan2(d + sa - sb);
+            [mod_tau(angle - a), squared.sqrt(), mod_tau(b - angle)]
+        })
+    };
+    push([Turn::Left, Turn::Straight, Turn::Left], lsl);
+    let rsr = {
+        let squared = 2.0 + d * d - 2.0 * cab + 2.0 * d * (sb - sa);
+        (squared >= 0.0).then(|| {
+            let angle = (ca - cb).atan2(d - sa + sb);
+            [mod_tau(a - angle), squared.sqrt(), mod_tau(angle - b)]
+        })
+    };
+    push([Turn::Right, Turn::Straight, Turn::Right], rsr);
+    let lsr = {
+        let squared = -2.0 + d * d + 2.0 * cab + 2.0 * d * (sa + sb);
+        (squared >= 0.0).then(|| {
+            let p = squared.sqrt();
+            let angle = (-ca - cb).atan2(d + sa + sb) - (-2.0 as Scalar).atan2(p);
+            [mod_tau(angle - a), p, mod_tau(angle - b)]
+        })
+    };
+    push([Turn::Left, Turn::Straight, Turn::Right], lsr);
+    let rsl = {
+        let squared = -2.0 + d * d + 2.0 * cab - 2.0 * d * (sa + sb);
+        (squared >= 0.0).then(|| {
+            let p = squared.sqrt();
+            let angle = (ca + cb).atan2(d - sa - sb) - (2.0 as Scalar).atan2(p);
+            [mod_tau(a - angle), p, mod_tau(b - angle)]
+        })
+    };
+    push([Turn::Right, Turn::Straight, Turn::Left], rsl);
+    let rlr = {
+        let cos = (6.0 - d * d + 2.0 * cab + 2.0 * d * (sa - sb)) / 8.0;
+        (cos.abs() <= 1.0).then(|| {
+            let p = mod_tau(TAU - cos.acos());
+            let t = mod_tau(a - (ca - cb).atan2(d - sa + sb) + p * 0.5);
+            [t, p, mod_tau(a - b - t + p)]
+        })
+    };
+    push([Turn::Right, Turn::Left, Turn::Right], rlr);
+    let lrl = {
+        let cos = (6.0 - d * d + 2.0 * cab + 2.0 * d * (sb - sa)) / 8.0;
+        (cos.abs() <= 1.0).then(|| {
+            let p = mod_tau(TAU - cos.acos());
+            let t = mod_tau(-a - (ca - cb).atan2(d + sa - sb) + p * 0.5);
+            [t, p, mod_tau(b - a - t + p)]
+        })
+    };
+    push([Turn::Left, Turn::Right, Turn::Left], lrl);
+    let tolerance = radius * 1.0e-3;
+    words.retain(|word| {
+        let length = word.iter().map(|(_, length)| *length).sum::<Scalar>();
+        let pose = word_pose(from, word, length, radius);
+        let heading = mod_tau(pose.heading - to.heading);
+        (pose.x - to.x).abs() < tolerance
+            && (pose.y - to.y).abs() < tolerance
+            && heading.min(TAU - heading) < 1.0e-3
+    });
+    words.sort_by(|a, b| {
+        let a = a.iter().map(|(_, length)| *length).sum::<Scalar>();
+        let b = b.iter().map(|(_, length)| *length).sum::<Scalar>();
+        a.partial_cmp(&b).unwrap()
+    });
+    words
+}
@@ -0,0 +1,244 @@
+use crate::{Error, NavMesh, NavQuery, NavQueryFilter, NavResult, NavVec3, Scalar, SCALAR_MAX};
+
+impl NavMesh {
+    /// Attach named per-vertex attribute (danger, light level, elevation noise), that can be
+    /// sampled at any point of nav mesh surface with values interpolated between triangles
+    /// vertices, see `sample_vertex_attribute`.
+    ///
+    /// # Arguments
+    /// * `name` - attribute name.
+    /// * `values` - attribute value for every nav mesh vertex.
+    ///
+    /// # Returns
+    /// `Ok` with `Some` previous values of attribute with that name or `Err` with
+    /// `Error::VertexAttributeCountDoesNotMatchVertices` if values count does not match vertices
+    /// count.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (2.0, 0.0, 0.0).into(), // 1
+    ///     (2.0, 2.0, 0.0).into(), // 2
+    ///     (0.0, 2.0, 0.0).into(), // 3
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    ///
+    /// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// mesh.set_vertex_attribute("light", vec![0.0, 1.0, 1.0, 0.0]).unwrap();
+    /// let light = mesh
+    ///     .sample_vertex_attribute("light", (0.5, 1.0, 0.0).into())
+    ///     .unwrap();
+    /// assert!((light - 0.25).abs() < 1.0e-5);
+    /// assert!(mesh.set_vertex_attribute("light", vec![1.0]).is_err());
+    /// ```
+    pub fn set_vertex_attribute(
+        &mut self,
+        name: impl Into<String>,
+        values: Vec<Scalar>,
+    ) -> NavResult<Option<Vec<Scalar>>> {
+        if values.len() != self.vertices().len() {
+            return Err(Error::VertexAttributeCountDoesNotMatchVertices(
+                values.len(),
+                self.vertices().len(),
+            ));
+        }
+        Ok(self.vertex_attributes_map_mut().insert(name.into(), values))
+    }
+
+    /// Detach named per-vertex attribute.
+    ///
+    /// # Returns
+    /// `Some` with attribute values if it was attached or `None` otherwise.
+    pub fn remove_vertex_attribute(&mut self, name: &str) -> Option<Vec<Scalar>> {
+        self.vertex_attributes_map_mut().remove(name)
+    }
+
+    /// Change value of named attribute at single vertex, for example to update danger level
+    /// as gameplay goes.
+    ///
+    /// # Arguments
+    /// * `name` - attribute name.
+    /// * `index` - vertex index.
+    /// * `value` - new attribute value.
+    ///
+    /// # Returns
+    /// `Some` with previous value or `None` if attribute or vertex does not exist.
+    pub fn set_vertex_attribute_value(
+        &mut self,
+        name: &str,
+        index: usize,
+        value: Scalar,
+    ) -> Option<Scalar> {
+        let item = self
+            .vertex_attributes_map_mut()
+            .get_mut(name)?
+            .get_mut(index)?;
+        Some(std::mem::replace(item, value))
+    }
+
+    /// Values of named attribute, one per vertex.
+    #[inline]
+    pub fn vertex_attribute(&self, name: &str) -> Option<&[Scalar]> {
+        self.vertex_attributes_map()
+            .get(name)
+            .map(|values| values.as_slice())
+    }
+
+    /// Names of attached per-vertex attributes.
+    #[inline]
+    pub fn vertex_attributes(&self) -> impl Iterator<Item = &str> + '_ {
+        self.vertex_attributes_map()
+            .keys()
+            .map(|name| name.as_str())
+    }
+
+    /// Interpolate named attribute at point of given triangle, point is projected on triangle
+    /// first.
+    ///
+    /// # Arguments
+    /// * `name` - attribute name.
+    /// * `triangle` - triangle index.
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with interpolated value or `None` if attribute or triangle does not exist.
+    pub fn triangle_vertex_attribute(
+        &self,
+        name: &str,
+        triangle: usize,
+        point: NavVec3,
+    ) -> Option<Scalar> {
+        self.interpolate_vertex_values(self.vertex_attribute(name)?, triangle, point)
+    }
+
+    /// Interpolate named attribute at nav mesh point closest to query point.
+    ///
+    /// # Arguments
+    /// * `name` - attribute name.
+    /// * `point` - query point.
+    ///
+    /// # Returns
+    /// `Some` with interpolated value or `None` if attribute does not exist or nav mesh is empty.
+    pub fn sample_vertex_attribute(&self, name: &str, point: NavVec3) -> Option<Scalar> {
+        let triangle = self.find_closest_triangle(point, NavQuery::Accuracy)?;
+        self.triangle_vertex_attribute(name, triangle, point)
+    }
+
+    fn interpolate_vertex_values(
+        &self,
+        values: &[Scalar],
+        triangle: usize,
+        point: NavVec3,
+    ) -> Option<Scalar> {
+        let vertices = self.triangles().get(triangle)?;
+        let spatial = self.spatial(triangle);
+        let weights = spatial.barycentric(spatial.closest_point(point));
+        Some(
+            values.get(vertices.first as usize)? * weights.x
+                + values.get(vertices.second as usize)? * weights.y
+                + values.get(vertices.third as usize)? * weights.z,
+        )
+    }
+}
+
+/// Query filter turning per-vertex attribute into dynamic path costs, so paths avoid parts of nav
+/// mesh with high attribute values (dangerous or lit places) and skip ones above limit.
+///
+/// Cost of move is its length multiplied by `1 + weight * value`, where value is average of
+/// attribute sampled at move ends.
+///
+/// # Example
+/// ```
+/// use navmesh::*;
+///
+/// let vertices = vec![
+///     (0.0, 0.0, 0.0).into(), // 0
+///     (1.0, 0.0, 0.0).into(), // 1
+///     (2.0, 0.0, 0.0).into(), // 2
+///     (0.0, 1.0, 0.0).into(), // 3
+///     (1.0, 1.0, 0.0).into(), // 4
+///     (2.0, 1.0, 0.0).into(), // 5
+/// ];
+/// let triangles = vec![
+///     (0, 1, 4).into(), // 0
+///     (4, 3, 0).into(), // 1
+///     (1, 2, 5).into(), // 2
+///     (5, 4, 1).into(), // 3
+/// ];
+///
+/// let mut mesh = NavMesh::new(vertices, triangles).unwrap();
+/// mesh.set_vertex_attribute("danger", vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0])
+///     .unwrap();
+/// let filter = NavVertexAttributeFilter::new(&mesh, "danger").unwrap();
+/// assert!(mesh.find_path_triangles_with_filter(1, 2, &filter).is_some());
+/// let filter = filter.with_max_value(0.5);
+/// assert!(mesh.find_path_triangles_with_filter(1, 2, &filter).is_none());
+/// ```
+#[derive(Clone, Copy)]
+pub struct NavVertexAttributeFilter<'a> {
+    mesh: &'a NavMesh,
+    values: &'a [Scalar],
+    weight: Scalar,
+    max_value: Scalar,
+}
+
+impl<'a> NavVertexAttributeFilter<'a> {
+    /// Create filter with weight of 1 and no values limit.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh that attribute is attached to.
+    /// * `name` - attribute name.
+    ///
+    /// # Returns
+    /// `Some` with filter or `None` if attribute does not exist.
+    pub fn new(mesh: &'a NavMesh, name: &str) -> Option<Self> {
+        Some(Self {
+            mesh,
+            values: mesh.vertex_attribute(name)?,
+            weight: 1.0,
+            max_value: SCALAR_MAX,
+        })
+    }
+
+    /// Set how much attribute value adds to moves cost.
+    pub fn with_weight(mut self, weight: Scalar) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Set attribute value above which moves are not allowed.
+    pub fn with_max_value(mut self, max_value: Scalar) -> Self {
+        self.max_value = max_value;
+        self
+    }
+
+    #[inline]
+    pub fn weight(&self) -> Scalar {
+        self.weight
+    }
+
+    #[inline]
+    pub fn max_value(&self) -> Scalar {
+        self.max_value
+    }
+
+    fn sample(&self, point: NavVec3) -> Option<Scalar> {
+        let triangle = self.mesh.find_closest_triangle(point, NavQuery::Accuracy)?;
+        self.mesh
+            .interpolate_vertex_values(self.values, triangle, point)
+    }
+}
+
+impl NavQueryFilter for NavVertexAttributeFilter<'_> {
+    fn cost(&self, from: NavVec3, to: NavVec3, _: u8) -> Option<Scalar> {
+        let a = self.sample(from)?;
+        let b = self.sample(to)?;
+        if a.max(b) > self.max_value {
+            return None;
+        }
+        Some(from.distance(to) * (1.0 + self.weight * (a + b) * 0.5).max(0.0))
+    }
+}
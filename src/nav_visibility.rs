@@ -0,0 +1,235 @@
+use crate::{nav_open_node::OpenNode, NavMesh, NavQuery, NavVec3, Scalar, SCALAR_MAX};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+
+/// Visibility graph of nav mesh boundary corners, where corners are connected when straight
+/// line between them goes only through walkable triangles.
+///
+/// Only corners where walkable area turns around obstacle (reflex ones) are used, since these
+/// are the only points shortest paths bend at, so paths found on this graph are taut.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavVisibilityGraph {
+    corners: Vec<NavVec3>,
+    // triangles sharing corner vertex.
+    corners_triangles: Vec<Vec<usize>>,
+    // [(corner, distance)] per corner.
+    neighbors: Vec<Vec<(usize, Scalar)>>,
+}
+
+impl NavVisibilityGraph {
+    /// List of corner points.
+    #[inline]
+    pub fn corners(&self) -> &[NavVec3] {
+        &self.corners
+    }
+
+    /// List of corners visible from given corner together with distances to them.
+    #[inline]
+    pub fn neighbors(&self, corner: usize) -> &[(usize, Scalar)] {
+        self.neighbors
+            .get(corner)
+            .map(|neighbors| neighbors.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Iterator over visibility edges (pairs of mutually visible corners, lower index first).
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors.iter().enumerate().flat_map(|(from, list)| {
+            list.iter()
+                .filter(move |(to, _)| from < *to)
+                .map(move |(to, _)| (from, *to))
+        })
+    }
+
+    #[inline]
+    pub fn edges_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    /// Find corners visible from given point.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh this graph was built from.
+    /// * `point` - query point.
+    pub fn visible_corners(&self, mesh: &NavMesh, point: NavVec3) -> Vec<usize> {
+        (0..self.corners.len())
+            .filter(|corner| self.is_corner_visible(mesh, *corner, point))
+            .collect()
+    }
+
+    /// Find taut shortest path between two points, going straight between mutually visible
+    /// corners.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh this graph was built from.
+    /// * `from` - start point.
+    /// * `to` - end point.
+    ///
+    /// # Returns
+    /// `Some` with path points or `None` if there is no path.
+    pub fn find_path(&self, mesh: &NavMesh, from: NavVec3, to: NavVec3) -> Option<Vec<NavVec3>> {
+        let from = mesh.closest_point(from, NavQuery::Accuracy)?;
+        let to = mesh.closest_point(to, NavQuery::Accuracy)?;
+        if mesh.raycast(from, to, NavQuery::Accuracy)?.hit.is_none() {
+            return Some(vec![from, to]);
+        }
+        // node of corners count is path end.
+        let end = self.corners.len();
+        let targets = self
+            .visible_corners(mesh, to)
+            .into_iter()
+            .map(|corner| (corner, self.corners[corner].distance(to)))
+            .collect::<HashMap<_, _>>();
+        if targets.is_empty() {
+            return None;
+        }
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut parents = HashMap::<usize, usize>::new();
+        for corner in self.visible_corners(mesh, from) {
+            let cost = self.corners[corner].distance(from);
+            costs.insert(corner, cost);
+            open.push(OpenNode(cost + self.corners[corner].distance(to), corner));
+        }
+        while let Some(OpenNode(_, current)) = open.pop() {
+            if current == end {
+                let mut path = vec![to];
+                let mut node = current;
+                while let Some(parent) = parents.get(&node) {
+                    path.push(self.corners[*parent]);
+                    node = *parent;
+                }
+                path.push(from);
+                path.reverse();
+                return Some(path);
+            }
+            let cost = costs[&current];
+            let next = self.neighbors[current]
+                .iter()
+                .copied()
+                .chain(targets.get(&current).map(|distance| (end, *distance)));
+            for (next, distance) in next {
+                let next_cost = cost + distance;
+                if next_cost < costs.get(&next).copied().unwrap_or(SCALAR_MAX) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, current);
+                    let heuristic = if next == end {
+                        0.0
+                    } else {
+                        self.corners[next].distance(to)
+                    };
+                    open.push(OpenNode(next_cost + heuristic, next));
+                }
+            }
+        }
+        None
+    }
+
+    fn is_corner_visible(&self, mesh: &NavMesh, corner: usize, point: NavVec3) -> bool {
+        let position = self.corners[corner];
+        self.corners_triangles[corner].iter().any(|triangle| {
+            mesh.walk_line(*triangle, position, point, mesh.tolerance())
+                .1
+        })
+    }
+}
+
+impl NavMesh {
+    /// Build visibility graph of nav mesh boundary corners, for taut shortest paths on mostly
+    /// flat maps or line of sight based tactical reasoning.
+    ///
+    /// Visibility respects triangles blocked at the time of building, so graph should be built
+    /// again after obstacles or gates change.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// // square with square hole in the middle.
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(), // 0
+    ///     (3.0, 0.0, 0.0).into(), // 1
+    ///     (3.0, 3.0, 0.0).into(), // 2
+    ///     (0.0, 3.0, 0.0).into(), // 3
+    ///     (1.0, 1.0, 0.0).into(), // 4
+    ///     (2.0, 1.0, 0.0).into(), // 5
+    ///     (2.0, 2.0, 0.0).into(), // 6
+    ///     (1.0, 2.0, 0.0).into(), // 7
+    /// ];
+    /// let triangles = vec![
+    ///     (0, 1, 5).into(),
+    ///     (5, 4, 0).into(),
+    ///     (1, 2, 6).into(),
+    ///     (6, 5, 1).into(),
+    ///     (2, 3, 7).into(),
+    ///     (7, 6, 2).into(),
+    ///     (3, 0, 4).into(),
+    ///     (4, 7, 3).into(),
+    /// ];
+    /// let mesh = NavMesh::new(vertices, triangles).unwrap();
+    /// let graph = mesh.build_visibility_graph();
+    /// // only hole corners are reflex.
+    /// assert_eq!(graph.corners().len(), 4);
+    /// assert_eq!(graph.edges_count(), 4);
+    /// let path = graph
+    ///     .find_path(&mesh, (0.5, 0.5, 0.0).into(), (2.5, 2.5, 0.0).into())
+    ///     .unwrap();
+    /// assert_eq!(path.len(), 3);
+    /// ```
+    pub fn build_visibility_graph(&self) -> NavVisibilityGraph {
+        let epsilon = self.tolerance().angular;
+        let vertices = self.vertices();
+        // {(vertex, vertex): edge uses count}
+        let mut edges = HashMap::<(u32, u32), usize>::new();
+        // [(triangle, angle)] per vertex.
+        let mut fans = vec![vec![]; vertices.len()];
+        for (index, triangle) in self.triangles().iter().enumerate() {
+            let t = [triangle.first, triangle.second, triangle.third];
+            for i in 0..3 {
+                let (a, b, c) = (t[i], t[(i + 1) % 3], t[(i + 2) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+                let p = vertices[a as usize];
+                let u = (vertices[b as usize] - p).normalize();
+                let v = (vertices[c as usize] - p).normalize();
+                fans[a as usize].push((index, u.dot(v).clamp(-1.0, 1.0).acos()));
+            }
+        }
+        let mut boundary = vec![false; vertices.len()];
+        for ((a, b), count) in edges {
+            if count == 1 {
+                boundary[a as usize] = true;
+                boundary[b as usize] = true;
+            }
+        }
+        let mut result = NavVisibilityGraph::default();
+        for (index, fan) in fans.into_iter().enumerate() {
+            let angle = fan.iter().map(|(_, angle)| *angle).sum::<Scalar>();
+            if !boundary[index] || angle <= std::f64::consts::PI as Scalar + epsilon {
+                continue;
+            }
+            let triangles = fan
+                .into_iter()
+                .map(|(triangle, _)| triangle)
+                .filter(|triangle| !self.is_triangle_blocked(*triangle))
+                .collect::<Vec<_>>();
+            if triangles.is_empty() {
+                continue;
+            }
+            result.corners.push(vertices[index]);
+            result.corners_triangles.push(triangles);
+        }
+        result.neighbors = vec![vec![]; result.corners.len()];
+        for a in 0..result.corners.len() {
+            for b in (a + 1)..result.corners.len() {
+                if result.is_corner_visible(self, a, result.corners[b]) {
+                    let distance = result.corners[a].distance(result.corners[b]);
+                    result.neighbors[a].push((b, distance));
+                    result.neighbors[b].push((a, distance));
+                }
+            }
+        }
+        result
+    }
+}
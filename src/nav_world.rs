@@ -0,0 +1,736 @@
+use crate::{
+    nav_funnel::{center_portal, funnel, orient_portal},
+    nav_open_node::OpenNode,
+    nav_path_cache::next_version,
+    NavMesh, NavPathMode, NavQuery, NavResult, NavTiledMesh, NavTransform, NavUpAxis, NavVec3,
+    Scalar,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+use typid::ID;
+
+/// Nav world identifier.
+pub type NavWorldID = ID<NavWorld>;
+
+/// Nav world instance identifier.
+pub type NavWorldInstanceID = ID<NavWorldInstance>;
+
+/// Nav world link identifier.
+pub type NavWorldLinkID = ID<NavWorldLink>;
+
+/// Reference to triangle of specific nav world instance.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavWorldTriangle {
+    pub instance: NavWorldInstanceID,
+    pub triangle: usize,
+}
+
+impl NavWorldTriangle {
+    #[inline]
+    pub fn new(instance: NavWorldInstanceID, triangle: usize) -> Self {
+        Self { instance, triangle }
+    }
+}
+
+/// Connection between triangles of two instances, made where their boundary edges meet.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavWorldPortal {
+    /// Triangle on the other side of portal.
+    pub to: NavWorldTriangle,
+    /// Shared part of boundary edges.
+    pub portal: (NavVec3, NavVec3),
+}
+
+/// Nav mesh placed in nav world, with transformation baked into its geometry.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavWorldInstance {
+    mesh: NavMesh,
+    transform: NavTransform,
+    // (min, max) of instance vertices.
+    bounds: (NavVec3, NavVec3),
+}
+
+impl NavWorldInstance {
+    /// Instance nav mesh in world space.
+    #[inline]
+    pub fn mesh(&self) -> &NavMesh {
+        &self.mesh
+    }
+
+    /// Transformation instance was placed with.
+    #[inline]
+    pub fn transform(&self) -> &NavTransform {
+        &self.transform
+    }
+
+    /// Instance (min, max) bounds in world space.
+    #[inline]
+    pub fn bounds(&self) -> (NavVec3, NavVec3) {
+        self.bounds
+    }
+}
+
+/// Explicit connection between two world points (ladders, jumps, teleports), that can connect
+/// instances that do not touch each other or points of the same instance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavWorldLink {
+    pub from: NavVec3,
+    pub to: NavVec3,
+    /// Tells if link can be traversed from `to` to `from` too.
+    pub bidirectional: bool,
+    /// Cost of traversing link, squared distance between link ends (like nav mesh connections
+    /// cost) is used if not set.
+    pub cost: Option<Scalar>,
+}
+
+impl NavWorldLink {
+    pub fn new(from: NavVec3, to: NavVec3, bidirectional: bool) -> Self {
+        Self {
+            from,
+            to,
+            bidirectional,
+            cost: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_cost(mut self, cost: Scalar) -> Self {
+        self.cost = Some(cost.max(0.0));
+        self
+    }
+}
+
+/// Step of link traversal: link, and whether it goes backwards (from `to` to `from`).
+type NavWorldLinkStep = (NavWorldLinkID, bool);
+
+/// Triangle of found path, together with link step it was entered through, if any.
+type NavWorldStep = (NavWorldTriangle, Option<NavWorldLinkStep>);
+
+/// Container of nav mesh instances placed at different world transforms (for example modular
+/// level kit pieces), allowing finding paths across all of them without merging them into one
+/// nav mesh.
+///
+/// Instances whose boundary edges meet get stitched together automatically, other ones can be
+/// connected with links.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavWorld {
+    id: NavWorldID,
+    instances: HashMap<NavWorldInstanceID, NavWorldInstance>,
+    portals: HashMap<NavWorldTriangle, Vec<NavWorldPortal>>,
+    // {link id: (link, triangles of link ends, if nav world covers them)}
+    links: HashMap<NavWorldLinkID, (NavWorldLink, Option<(NavWorldTriangle, NavWorldTriangle)>)>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "next_version"))]
+    version: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    up_axis: NavUpAxis,
+}
+
+impl NavWorld {
+    /// Create new empty nav world.
+    pub fn new() -> Self {
+        Self {
+            id: NavWorldID::new(),
+            version: next_version(),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NavWorldID {
+        self.id
+    }
+
+    #[inline]
+    pub fn up_axis(&self) -> NavUpAxis {
+        self.up_axis
+    }
+
+    /// Set up axis of nav world and all its instances.
+    pub fn set_up_axis(&mut self, up_axis: NavUpAxis) {
+        self.up_axis = up_axis;
+        for instance in self.instances.values_mut() {
+            instance.mesh.set_up_axis(up_axis);
+        }
+    }
+
+    /// Nav world version, changed every time instances or links change or any instance nav mesh
+    /// changes.
+    pub fn version(&self) -> u64 {
+        self.instances
+            .values()
+            .map(|instance| instance.mesh.version())
+            .fold(self.version, u64::max)
+    }
+
+    #[inline]
+    pub fn instance(&self, id: NavWorldInstanceID) -> Option<&NavWorldInstance> {
+        self.instances.get(&id)
+    }
+
+    /// Get instance nav mesh for changing its areas, gates or obstacles.
+    #[inline]
+    pub fn instance_mesh_mut(&mut self, id: NavWorldInstanceID) -> Option<&mut NavMesh> {
+        self.version = next_version();
+        self.instances
+            .get_mut(&id)
+            .map(|instance| &mut instance.mesh)
+    }
+
+    #[inline]
+    pub fn instances(&self) -> impl Iterator<Item = (NavWorldInstanceID, &NavWorldInstance)> {
+        self.instances.iter().map(|(id, instance)| (*id, instance))
+    }
+
+    /// List of portals going out of given instance triangle into other instances.
+    #[inline]
+    pub fn portals(&self, triangle: NavWorldTriangle) -> &[NavWorldPortal] {
+        self.portals
+            .get(&triangle)
+            .map(|portals| portals.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Place copy of nav mesh in world with given transformation, and stitch it with instances
+    /// it touches. Instance nav mesh gets up axis of nav world.
+    ///
+    /// # Arguments
+    /// * `mesh` - nav mesh, usually shared prefab one.
+    /// * `transform` - instance transformation.
+    ///
+    /// # Returns
+    /// `Ok` with instance identifier or `Err` if transformed nav mesh cannot be built.
+    pub fn add_instance(
+        &mut self,
+        mesh: &NavMesh,
+        transform: NavTransform,
+    ) -> NavResult<NavWorldInstanceID> {
+        let mut mesh = mesh.transformed(&transform)?;
+        mesh.set_up_axis(self.up_axis);
+        let bounds = mesh
+            .vertices()
+            .iter()
+            .fold(None::<(NavVec3, NavVec3)>, |bounds, v| {
+                Some(match bounds {
+                    Some((min, max)) => (min.min(*v), max.max(*v)),
+                    None => (*v, *v),
+                })
+            })
+            .unwrap_or_default();
+        let id = NavWorldInstanceID::new();
+        self.instances.insert(
+            id,
+            NavWorldInstance {
+                mesh,
+                transform,
+                bounds,
+            },
+        );
+        self.stitch_instance(id);
+        self.refresh_links();
+        self.version = next_version();
+        Ok(id)
+    }
+
+    /// Remove instance and all portals to it.
+    ///
+    /// # Returns
+    /// `Some` with removed instance or `None` if there was no such instance.
+    pub fn remove_instance(&mut self, id: NavWorldInstanceID) -> Option<NavWorldInstance> {
+        let instance = self.instances.remove(&id)?;
+        self.portals.retain(|from, portals| {
+            if from.instance == id {
+                return false;
+            }
+            portals.retain(|portal| portal.to.instance != id);
+            !portals.is_empty()
+        });
+        self.refresh_links();
+        self.version = next_version();
+        Some(instance)
+    }
+
+    /// Add link between two world points. Link ends get attached to closest instances
+    /// triangles, also when instances get added or removed later.
+    pub fn add_link(&mut self, link: NavWorldLink) -> NavWorldLinkID {
+        let id = NavWorldLinkID::new();
+        let ends = self.link_ends(&link);
+        self.links.insert(id, (link, ends));
+        self.version = next_version();
+        id
+    }
+
+    pub fn remove_link(&mut self, id: NavWorldLinkID) -> Option<NavWorldLink> {
+        self.version = next_version();
+        self.links.remove(&id).map(|(link, _)| link)
+    }
+
+    #[inline]
+    pub fn link(&self, id: NavWorldLinkID) -> Option<&NavWorldLink> {
+        self.links.get(&id).map(|(link, _)| link)
+    }
+
+    #[inline]
+    pub fn links(&self) -> impl Iterator<Item = (NavWorldLinkID, &NavWorldLink)> {
+        self.links.iter().map(|(id, (link, _))| (*id, link))
+    }
+
+    /// Find closest triangle of any instance to the given point.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with instance triangle if found or `None` otherwise.
+    pub fn find_closest_triangle(
+        &self,
+        point: NavVec3,
+        query: NavQuery,
+    ) -> Option<NavWorldTriangle> {
+        // instances within unit distance of point get searched first.
+        let contains = |(min, max): (NavVec3, NavVec3)| {
+            point.x >= min.x - 1.0
+                && point.y >= min.y - 1.0
+                && point.z >= min.z - 1.0
+                && point.x <= max.x + 1.0
+                && point.y <= max.y + 1.0
+                && point.z <= max.z + 1.0
+        };
+        let nearby = self
+            .instances
+            .iter()
+            .filter(|(_, instance)| contains(instance.bounds))
+            .collect::<Vec<_>>();
+        let candidates = if nearby.is_empty() {
+            self.instances.iter().collect::<Vec<_>>()
+        } else {
+            nearby
+        };
+        candidates
+            .into_iter()
+            .filter_map(|(id, instance)| {
+                let triangle = instance.mesh.find_closest_triangle(point, query)?;
+                let p = instance.mesh.closest_point_on_triangle(triangle, point)?;
+                Some((
+                    (p - point).sqr_magnitude(),
+                    NavWorldTriangle::new(*id, triangle),
+                ))
+            })
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal))
+            .map(|(_, triangle)| triangle)
+    }
+
+    /// Find closest point on any instance.
+    ///
+    /// # Arguments
+    /// * `point` - query point.
+    /// * `query` - query quality.
+    ///
+    /// # Returns
+    /// `Some` with point on nav world if found or `None` otherwise.
+    pub fn closest_point(&self, point: NavVec3, query: NavQuery) -> Option<NavVec3> {
+        let triangle = self.find_closest_triangle(point, query)?;
+        self.closest_point_on_triangle(triangle, point)
+    }
+
+    /// Find shortest path between two instances triangles, going through portals and links.
+    ///
+    /// # Arguments
+    /// * `from` - start instance triangle.
+    /// * `to` - end instance triangle.
+    ///
+    /// # Returns
+    /// `Some` with path instances triangles and path cost if found or `None` otherwise.
+    pub fn find_path_triangles(
+        &self,
+        from: NavWorldTriangle,
+        to: NavWorldTriangle,
+    ) -> Option<(Vec<NavWorldTriangle>, Scalar)> {
+        self.find_path_steps(from, to).map(|(steps, cost)| {
+            (
+                steps.into_iter().map(|(triangle, _)| triangle).collect(),
+                cost,
+            )
+        })
+    }
+
+    /// Find shortest path between two points, going across instances and links if needed.
+    ///
+    /// # Arguments
+    /// * `from` - query point from.
+    /// * `to` - query point to.
+    /// * `query` - query quality.
+    /// * `mode` - path finding quality.
+    ///
+    /// # Returns
+    /// `Some` with path points on nav world if found or `None` otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use navmesh::*;
+    ///
+    /// // single square room prefab.
+    /// let vertices = vec![
+    ///     (0.0, 0.0, 0.0).into(),
+    ///     (1.0, 0.0, 0.0).into(),
+    ///     (1.0, 1.0, 0.0).into(),
+    ///     (0.0, 1.0, 0.0).into(),
+    /// ];
+    /// let triangles = vec![(0, 1, 2).into(), (2, 3, 0).into()];
+    /// let room = NavMesh::new(vertices, triangles).unwrap();
+    ///
+    /// let mut world = NavWorld::new();
+    /// // two rooms next to each other get stitched.
+    /// world.add_instance(&room, NavTransform::default()).unwrap();
+    /// world
+    ///     .add_instance(&room, NavTransform::from_translation((1.0, 0.0, 0.0).into()))
+    ///     .unwrap();
+    /// // and third one is reachable only by jumping down.
+    /// world
+    ///     .add_instance(&room, NavTransform::from_translation((0.0, 3.0, -1.0).into()))
+    ///     .unwrap();
+    ///
+    /// let path = world
+    ///     .find_path(
+    ///         (0.5, 0.5, 0.0).into(),
+    ///         (1.5, 0.5, 0.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(path, vec![(0.5, 0.5, 0.0).into(), (1.5, 0.5, 0.0).into()]);
+    /// assert!(world
+    ///     .find_path(
+    ///         (0.5, 0.5, 0.0).into(),
+    ///         (0.5, 3.5, -1.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .is_none());
+    ///
+    /// world.add_link(NavWorldLink::new(
+    ///     (1.5, 0.9, 0.0).into(),
+    ///     (0.5, 3.1, -1.0).into(),
+    ///     false,
+    /// ));
+    /// let path = world
+    ///     .find_path(
+    ///         (0.5, 0.5, 0.0).into(),
+    ///         (0.5, 3.5, -1.0).into(),
+    ///         NavQuery::Accuracy,
+    ///         NavPathMode::Accuracy,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     path,
+    ///     vec![
+    ///         (0.5, 0.5, 0.0).into(),
+    ///         (1.5, 0.9, 0.0).into(),
+    ///         (0.5, 3.1, -1.0).into(),
+    ///         (0.5, 3.5, -1.0).into(),
+    ///     ]
+    /// );
+    /// ```
+    pub fn find_path(
+        &self,
+        from: NavVec3,
+        to: NavVec3,
+        query: NavQuery,
+        mode: NavPathMode,
+    ) -> Option<Vec<NavVec3>> {
+        if from.same_as(to) {
+            return None;
+        }
+        let start = self.find_closest_triangle(from, query)?;
+        let end = self.find_closest_triangle(to, query)?;
+        let from = self.closest_point_on_triangle(start, from)?;
+        let to = self.closest_point_on_triangle(end, to)?;
+        let (steps, _) = self.find_path_steps(start, end)?;
+        // split path into parts walked on surface, joined by links.
+        let mut result = vec![];
+        let mut part = vec![steps[0].0];
+        let mut part_start = from;
+        for (triangle, link) in steps.into_iter().skip(1) {
+            match link {
+                Some((id, backwards)) => {
+                    let link = &self.links[&id].0;
+                    let (a, b) = if backwards {
+                        (link.to, link.from)
+                    } else {
+                        (link.from, link.to)
+                    };
+                    let a = self.closest_point_on_triangle(*part.last()?, a)?;
+                    let b = self.closest_point_on_triangle(triangle, b)?;
+                    result.extend(self.find_part_path(&part, part_start, a, mode)?);
+                    part = vec![triangle];
+                    part_start = b;
+                }
+                None => part.push(triangle),
+            }
+        }
+        result.extend(self.find_part_path(&part, part_start, to, mode)?);
+        result.dedup_by(|a, b| a.same_as(*b));
+        Some(result)
+    }
+
+    fn find_part_path(
+        &self,
+        triangles: &[NavWorldTriangle],
+        from: NavVec3,
+        to: NavVec3,
+        mode: NavPathMode,
+    ) -> Option<Vec<NavVec3>> {
+        if triangles.len() == 1 {
+            return Some(vec![from, to]);
+        }
+        let portals = triangles
+            .windows(2)
+            .map(|pair| self.portal(pair[0], pair[1]))
+            .collect::<Option<Vec<_>>>()?;
+        match mode {
+            NavPathMode::Accuracy | NavPathMode::Centerline(_) => {
+                // portals are not told apart from walls here, so both portal ends get centered.
+                let weight = match mode {
+                    NavPathMode::Centerline(weight) => weight,
+                    _ => 0.0,
+                };
+                let up = self.up_axis.up();
+                let mut oriented = Vec::with_capacity(portals.len() + 2);
+                oriented.push((from, from));
+                for (pair, portal) in triangles.windows(2).zip(portals) {
+                    let a = self.area_center(pair[0])?;
+                    let b = self.area_center(pair[1])?;
+                    let portal = center_portal(portal, (true, true), weight);
+                    oriented.push(orient_portal(a, b, portal, up));
+                }
+                oriented.push((to, to));
+                Some(funnel(&oriented, up))
+            }
+            NavPathMode::MidPoints => {
+                let mut points = Vec::with_capacity(portals.len() + 2);
+                points.push(from);
+                points.extend(portals.into_iter().map(|(a, b)| (a + b) * 0.5));
+                points.push(to);
+                points.dedup_by(|a, b| a.same_as(*b));
+                Some(points)
+            }
+        }
+    }
+
+    /// Path finding over instances triangles, telling for every triangle link it was entered
+    /// through, if any.
+    fn find_path_steps(
+        &self,
+        from: NavWorldTriangle,
+        to: NavWorldTriangle,
+    ) -> Option<(Vec<NavWorldStep>, Scalar)> {
+        if !self.is_passable(from) || !self.is_passable(to) {
+            return None;
+        }
+        // {triangle: [(link end triangle, link step, link cost, link start, link end)]}
+        let mut outgoing = HashMap::<NavWorldTriangle, Vec<_>>::new();
+        for (id, (link, ends)) in &self.links {
+            let (a, b) = match ends {
+                Some(ends) => *ends,
+                None => continue,
+            };
+            let cost = link
+                .cost
+                .unwrap_or_else(|| (link.to - link.from).sqr_magnitude());
+            outgoing
+                .entry(a)
+                .or_default()
+                .push((b, (*id, false), cost, link.from, link.to));
+            if link.bidirectional {
+                outgoing
+                    .entry(b)
+                    .or_default()
+                    .push((a, (*id, true), cost, link.to, link.from));
+            }
+        }
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::<NavWorldTriangle, Scalar>::new();
+        let mut parents = HashMap::<NavWorldTriangle, NavWorldStep>::new();
+        costs.insert(from, 0.0);
+        open.push(OpenNode(0.0, from));
+        while let Some(OpenNode(cost, current)) = open.pop() {
+            if current == to {
+                let mut path = vec![(current, None)];
+                let mut node = current;
+                while let Some((parent, link)) = parents.get(&node) {
+                    path.last_mut().unwrap().1 = *link;
+                    path.push((*parent, None));
+                    node = *parent;
+                }
+                path.reverse();
+                return Some((path, cost));
+            }
+            if cost > costs.get(&current).copied().unwrap_or(Scalar::INFINITY) {
+                continue;
+            }
+            let mesh = &self.instances[&current.instance].mesh;
+            let area = &mesh.areas()[current.triangle];
+            let walked = mesh
+                .triangle_neighbors(current.triangle)
+                .map(|t| NavWorldTriangle::new(current.instance, t))
+                .chain(self.portals(current).iter().map(|portal| portal.to))
+                .filter_map(|next| {
+                    // moves inside instance cost the same as on its nav mesh alone.
+                    if next.instance == current.instance {
+                        let weight = mesh.connection_cost(current.triangle, next.triangle)?;
+                        return Some((next, None, weight));
+                    }
+                    let next_area =
+                        &self.instances.get(&next.instance)?.mesh.areas()[next.triangle];
+                    let weight = (next_area.center - area.center).sqr_magnitude()
+                        * area.cost
+                        * next_area.cost;
+                    Some((next, None, weight))
+                });
+            let linked = outgoing
+                .get(&current)
+                .into_iter()
+                .flatten()
+                .filter_map(|(next, step, link_cost, start, end)| {
+                    let next_area =
+                        &self.instances.get(&next.instance)?.mesh.areas()[next.triangle];
+                    let weight = (*start - area.center).sqr_magnitude() * area.cost
+                        + link_cost
+                        + (next_area.center - *end).sqr_magnitude() * next_area.cost;
+                    Some((*next, Some(*step), weight))
+                })
+                .collect::<Vec<_>>();
+            for (next, link, weight) in walked.chain(linked) {
+                if !self.is_passable(next) {
+                    continue;
+                }
+                let next_cost = cost + weight;
+                if next_cost < costs.get(&next).copied().unwrap_or(Scalar::INFINITY) {
+                    costs.insert(next, next_cost);
+                    parents.insert(next, (current, link));
+                    open.push(OpenNode(next_cost, next));
+                }
+            }
+        }
+        None
+    }
+
+    fn is_passable(&self, triangle: NavWorldTriangle) -> bool {
+        self.instances
+            .get(&triangle.instance)
+            .map(|instance| {
+                triangle.triangle < instance.mesh.areas().len()
+                    && !instance.mesh.is_triangle_blocked(triangle.triangle)
+            })
+            .unwrap_or_default()
+    }
+
+    fn area_center(&self, triangle: NavWorldTriangle) -> Option<NavVec3> {
+        self.instances
+            .get(&triangle.instance)?
+            .mesh
+            .areas()
+            .get(triangle.triangle)
+            .map(|area| area.center)
+    }
+
+    fn closest_point_on_triangle(
+        &self,
+        triangle: NavWorldTriangle,
+        point: NavVec3,
+    ) -> Option<NavVec3> {
+        self.instances
+            .get(&triangle.instance)?
+            .mesh
+            .closest_point_on_triangle(triangle.triangle, point)
+    }
+
+    fn portal(&self, from: NavWorldTriangle, to: NavWorldTriangle) -> Option<(NavVec3, NavVec3)> {
+        if from.instance == to.instance {
+            self.instances
+                .get(&from.instance)?
+                .mesh
+                .triangles_portal(from.triangle, to.triangle)
+        } else {
+            self.portals(from)
+                .iter()
+                .find(|portal| portal.to == to)
+                .map(|portal| portal.portal)
+        }
+    }
+
+    fn link_ends(&self, link: &NavWorldLink) -> Option<(NavWorldTriangle, NavWorldTriangle)> {
+        Some((
+            self.find_closest_triangle(link.from, NavQuery::Accuracy)?,
+            self.find_closest_triangle(link.to, NavQuery::Accuracy)?,
+        ))
+    }
+
+    fn refresh_links(&mut self) {
+        let ends = self
+            .links
+            .iter()
+            .map(|(id, (link, _))| (*id, self.link_ends(link)))
+            .collect::<Vec<_>>();
+        for (id, ends) in ends {
+            if let Some(item) = self.links.get_mut(&id) {
+                item.1 = ends;
+            }
+        }
+    }
+
+    fn stitch_instance(&mut self, id: NavWorldInstanceID) {
+        let instance = match self.instances.get(&id) {
+            Some(instance) => instance,
+            None => return,
+        };
+        let touches = |(amin, amax): (NavVec3, NavVec3), (bmin, bmax): (NavVec3, NavVec3)| {
+            let margin = 1.0e-3;
+            amin.x <= bmax.x + margin
+                && amin.y <= bmax.y + margin
+                && amin.z <= bmax.z + margin
+                && bmin.x <= amax.x + margin
+                && bmin.y <= amax.y + margin
+                && bmin.z <= amax.z + margin
+        };
+        let edges = instance.mesh.boundary_edges().collect::<Vec<_>>();
+        let mut portals = vec![];
+        for (other_id, other) in &self.instances {
+            if *other_id == id || !touches(instance.bounds, other.bounds) {
+                continue;
+            }
+            for (other_triangle, c, d) in other.mesh.boundary_edges() {
+                for (triangle, a, b) in &edges {
+                    if let Some(portal) =
+                        NavTiledMesh::edges_overlap(*a, *b, c, d, instance.mesh.tolerance())
+                    {
+                        portals.push((
+                            NavWorldTriangle::new(id, *triangle),
+                            NavWorldTriangle::new(*other_id, other_triangle),
+                            portal,
+                        ));
+                    }
+                }
+            }
+        }
+        for (from, to, portal) in portals {
+            self.portals
+                .entry(from)
+                .or_default()
+                .push(NavWorldPortal { to, portal });
+            self.portals
+                .entry(to)
+                .or_default()
+                .push(NavWorldPortal { to: from, portal });
+        }
+    }
+}